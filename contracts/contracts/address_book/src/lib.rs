@@ -0,0 +1,136 @@
+#![no_std]
+//! Canonical addresses of the suite's components.
+//!
+//! Each component (see [`syncro_common::address_book`] for the standard
+//! names) maps to its current address and a version counting how often it
+//! was re-pointed. Contracts set up with a book resolve their peers here on
+//! each use, so moving a deployment to upgraded components takes one
+//! `set_address` per component rather than a setter call on every contract
+//! that talks to it. Earlier addresses stay readable by version.
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Symbol, Val,
+    Vec,
+};
+use syncro_access::AccessError;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 3301,
+    NotInitialized = 3302,
+    NoPendingAdmin = 3303,
+    Unauthorized = 3304,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Components,           // Vec<Symbol> in order of first registration
+    Entry(Symbol),        // component -> AddressEntry
+    Version(Symbol, u32), // (component, version) -> Address
+}
+
+/// Where a component currently lives.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AddressEntry {
+    pub address: Address,
+    /// 1 for the first address, then one more per change.
+    pub version: u32,
+    pub updated_at: u64,
+}
+
+#[contractevent]
+pub struct AddressSet {
+    pub component: Symbol,
+    pub address: Address,
+    pub version: u32,
+}
+
+#[contract]
+pub struct AddressBookContract;
+
+#[contractimpl]
+impl AddressBookContract {
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    /// Point `component` at `address`. Admin only. Returns the new version.
+    pub fn set_address(env: Env, component: Symbol, address: Address) -> Result<u32, Error> {
+        syncro_access::require_admin(&env)?;
+        let storage = env.storage().persistent();
+        let version = match Self::get_entry(env.clone(), component.clone()) {
+            Some(entry) => entry.version + 1,
+            None => {
+                let mut components = Self::components(env.clone());
+                components.push_back(component.clone());
+                storage.set(&DataKey::Components, &components);
+                1
+            }
+        };
+        let entry = AddressEntry {
+            address: address.clone(),
+            version,
+            updated_at: env.ledger().timestamp(),
+        };
+        storage.set(&DataKey::Entry(component.clone()), &entry);
+        storage.set(&DataKey::Version(component.clone(), version), &address);
+        AddressSet {
+            component,
+            address,
+            version,
+        }
+        .publish(&env);
+        Ok(version)
+    }
+
+    /// Current address of `component`; what peers call when resolving.
+    pub fn resolve(env: Env, component: Symbol) -> Option<Address> {
+        Self::get_entry(env, component).map(|entry| entry.address)
+    }
+
+    pub fn get_entry(env: Env, component: Symbol) -> Option<AddressEntry> {
+        env.storage().persistent().get(&DataKey::Entry(component))
+    }
+
+    /// Address `component` had at `version`.
+    pub fn get_address_at(env: Env, component: Symbol, version: u32) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Version(component, version))
+    }
+
+    /// Every component ever set, in order of first registration.
+    pub fn components(env: Env) -> Vec<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Components)
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+#[cfg(test)]
+mod test;
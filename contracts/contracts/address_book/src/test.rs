@@ -0,0 +1,48 @@
+use super::*;
+use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, vec, Address, Env};
+use syncro_common::address_book::{LOGGING, ORACLE, RENEWAL};
+
+fn setup() -> (Env, AddressBookContractClient<'static>) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = AddressBookContractClient::new(&env, &env.register(AddressBookContract, ()));
+    client.init(&Address::generate(&env));
+    (env, client)
+}
+
+#[test]
+fn test_set_address_versions_each_component() {
+    let (env, client) = setup();
+    let renewal_v1 = Address::generate(&env);
+    let renewal_v2 = Address::generate(&env);
+    let logging = Address::generate(&env);
+
+    assert_eq!(client.set_address(&RENEWAL, &renewal_v1), 1);
+    assert_eq!(client.set_address(&LOGGING, &logging), 1);
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    assert_eq!(client.set_address(&RENEWAL, &renewal_v2), 2);
+
+    assert_eq!(client.resolve(&RENEWAL), Some(renewal_v2.clone()));
+    assert_eq!(
+        client.get_entry(&RENEWAL),
+        Some(AddressEntry {
+            address: renewal_v2,
+            version: 2,
+            updated_at: 500,
+        })
+    );
+    assert_eq!(client.get_address_at(&RENEWAL, &1), Some(renewal_v1));
+    assert_eq!(client.get_address_at(&RENEWAL, &3), None);
+    assert_eq!(client.resolve(&ORACLE), None);
+    assert_eq!(client.components(), vec![&env, RENEWAL, LOGGING]);
+}
+
+#[test]
+fn test_set_address_requires_admin() {
+    let (env, client) = setup();
+    env.set_auths(&[]);
+    assert!(client
+        .try_set_address(&RENEWAL, &Address::generate(&env))
+        .is_err());
+    assert!(client.components().is_empty());
+}
@@ -1,35 +1,82 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Symbol,
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, Address,
+    BytesN, Env, Val, Vec,
 };
+use syncro_access::AccessError;
+use syncro_ratelimit::LimitConfig;
+use syncro_upgrade::{Migration, UpgradeError};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum Error {
-    AlreadyInitialized = 1,
-    NotInitialized = 2,
-    Unauthorized = 3,
-    InvalidScope = 4,
+    AlreadyInitialized = 301,
+    NotInitialized = 302,
+    Unauthorized = 303,
+    InvalidScope = 304,
+    InvalidLimit = 305,
+    NoPendingAdmin = 306,
+    VersionMismatch = 307,
 }
 
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
 
-#[contracttype]
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-#[repr(u32)]
-pub enum Scope {
-    Renewals = 1 << 0,
-    GiftCards = 1 << 1,
-    Approvals = 1 << 2,
+impl From<UpgradeError> for Error {
+    fn from(err: UpgradeError) -> Self {
+        match err {
+            UpgradeError::NotInitialized => Error::NotInitialized,
+            UpgradeError::VersionMismatch => Error::VersionMismatch,
+        }
+    }
 }
 
+/// Storage layout version of this code; see `migrate`.
+pub const CODE_VERSION: u32 = 1;
+
+/// Steps from each earlier code version to the next, oldest first.
+const MIGRATIONS: &[Migration] = &[];
 
+pub use syncro_common::{ReasonCode, Scope};
 
 #[contracttype]
 #[derive(Clone)]
 enum DataKey {
-    Admin,
     Agent(Address),
+    Revoked(Address), // why the agent was last revoked
+    RateLimit,
+    Renewal,
+}
+
+/// The renewal contract's cache of the scopes this registry grants.
+#[contractclient(name = "AgentCacheClient")]
+pub trait AgentCacheInterface {
+    fn invalidate_agent_cache(env: Env, agent: Address);
+}
+
+#[contractevent(topics = ["agent", "reg"], data_format = "single-value")]
+pub struct AgentRegistered {
+    pub agent: Address,
+}
+
+#[contractevent(topics = ["agent", "scopes"], data_format = "vec")]
+pub struct AgentScopesUpdated {
+    pub agent: Address,
+    pub scopes: u32,
+}
+
+#[contractevent(topics = ["agent", "revoke"], data_format = "single-value")]
+pub struct AgentRevoked {
+    pub agent: Address,
 }
 
 #[contract]
@@ -39,47 +86,78 @@ pub struct AgentRegistry;
 impl AgentRegistry {
     /// Initialize the contract with an admin address.
     pub fn init(env: Env, admin: Address) -> Result<(), Error> {
-        if env.storage().instance().has(&DataKey::Admin) {
-            return Err(Error::AlreadyInitialized);
-        }
-        env.storage().instance().set(&DataKey::Admin, &admin);
+        syncro_access::init_admin(&env, &admin)?;
+        syncro_upgrade::init_version(&env, CODE_VERSION);
+        syncro_common::events::publish_schema(&env);
         Ok(())
     }
 
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
     fn require_admin(env: &Env) -> Result<Address, Error> {
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(Error::NotInitialized)?;
+        Ok(syncro_access::require_admin(env)?)
+    }
+
+    /// Nominate a new admin, who takes over once they call `accept_admin`.
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        syncro_access::propose_admin(&env, &new_admin)?;
+        Ok(())
+    }
+
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        syncro_access::accept_admin(&env)?;
+        Ok(())
+    }
+
+    /// Swap in the uploaded `wasm_hash`, keeping registered agents. Admin
+    /// only. Follow with `migrate` on the new code when its `CODE_VERSION`
+    /// moved.
+    pub fn upgrade(env: Env, wasm_hash: BytesN<32>) -> Result<(), Error> {
+        syncro_upgrade::upgrade(&env, &wasm_hash)?;
+        Ok(())
+    }
+
+    /// Bring storage from code version `from`, the one recorded, up to
+    /// `to`. Admin only.
+    pub fn migrate(env: Env, from: u32, to: u32) -> Result<(), Error> {
+        syncro_upgrade::migrate(&env, from, to, CODE_VERSION, MIGRATIONS)?;
+        Ok(())
+    }
 
-        admin.require_auth();
-        Ok(admin)
+    pub fn get_code_version(env: Env) -> u32 {
+        syncro_upgrade::version(&env)
     }
 
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
 
-    /// Register a new agent. Admin only.
+    /// Register a new agent with no scopes. Admin only.
     pub fn register(env: Env, agent: Address) -> Result<(), Error> {
         Self::require_admin(&env)?;
 
         env.storage()
             .persistent()
-            .set(&DataKey::Agent(agent.clone()), &true);
+            .set(&DataKey::Agent(agent.clone()), &0u32);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Revoked(agent.clone()));
 
-        env.events()
-            .publish((symbol_short!("agent"), symbol_short!("reg")), agent);
+        AgentRegistered { agent }.publish(&env);
 
         Ok(())
     }
 
-    pub fn update_scopes(
-        env: Env,
-        agent: Address,
-        scopes: u32,
-    ) -> Result<(), Error> {
+    pub fn update_scopes(env: Env, agent: Address, scopes: u32) -> Result<(), Error> {
         Self::require_admin(&env)?;
 
-        if !env.storage().persistent().has(&DataKey::Agent(agent.clone())) {
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Agent(agent.clone()))
+        {
             return Err(Error::Unauthorized);
         }
 
@@ -87,31 +165,68 @@ impl AgentRegistry {
             .persistent()
             .set(&DataKey::Agent(agent.clone()), &scopes);
 
-        env.events().publish(
-            (symbol_short!("agent"), symbol_short!("scopes")),
-            (agent, scopes),
-        );
+        Self::invalidate_cache(&env, &agent);
+        AgentScopesUpdated { agent, scopes }.publish(&env);
 
         Ok(())
     }
 
-
-    /// Revoke an agent's authorization. Admin only.
-    pub fn revoke_agent(env: Env, agent: Address) -> Result<(), Error> {
+    /// Revoke an agent's authorization, recording why. Admin only.
+    pub fn revoke_agent(env: Env, agent: Address, reason: ReasonCode) -> Result<(), Error> {
         Self::require_admin(&env)?;
 
         env.storage()
             .persistent()
             .remove(&DataKey::Agent(agent.clone()));
+        env.storage()
+            .persistent()
+            .set(&DataKey::Revoked(agent.clone()), &reason);
+
+        Self::invalidate_cache(&env, &agent);
+        AgentRevoked { agent }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Have `update_scopes` and `revoke_agent` drop the grants the renewal
+    /// contract cached for the agent, or stop with `None`. Admin only.
+    pub fn set_renewal_contract(env: Env, renewal: Option<Address>) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        match renewal {
+            Some(renewal) => env.storage().instance().set(&DataKey::Renewal, &renewal),
+            None => env.storage().instance().remove(&DataKey::Renewal),
+        }
+        Ok(())
+    }
 
-        env.events().publish(
-            (symbol_short!("agent"), symbol_short!("revoke")),
-            agent,
-        );
+    pub fn get_renewal_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Renewal)
+    }
+
+    fn invalidate_cache(env: &Env, agent: &Address) {
+        if let Some(renewal) = Self::get_renewal_contract(env.clone()) {
+            // Best effort: the cached grants lapse on their own, so a
+            // misconfigured renewal contract must not block a revocation.
+            let _ = AgentCacheClient::new(env, &renewal).try_invalidate_agent_cache(agent);
+        }
+    }
 
+    /// Limit how often one agent can pass `require_scope`. Admin only;
+    /// unlimited until set.
+    pub fn set_rate_limit(env: Env, config: LimitConfig) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if !config.is_valid() {
+            return Err(Error::InvalidLimit);
+        }
+        env.storage().instance().set(&DataKey::RateLimit, &config);
         Ok(())
     }
 
+    /// Why `agent` was revoked, until it is registered again.
+    pub fn get_revocation(env: Env, agent: Address) -> Option<ReasonCode> {
+        env.storage().persistent().get(&DataKey::Revoked(agent))
+    }
+
     /// Check if an agent is authorized.
     pub fn is_authorized(env: Env, agent: Address) -> bool {
         env.storage().persistent().has(&DataKey::Agent(agent))
@@ -124,7 +239,7 @@ impl AgentRegistry {
         }
     }
 
-       pub fn has_scope(env: Env, agent: Address, scope: Scope) -> bool {
+    pub fn has_scope(env: Env, agent: Address, scope: Scope) -> bool {
         match env
             .storage()
             .persistent()
@@ -135,15 +250,23 @@ impl AgentRegistry {
         }
     }
 
-      /// Enforce agent authorization + scope
+    /// Enforce agent authorization + scope
     pub fn require_scope(env: Env, agent: Address, scope: Scope) {
         agent.require_auth();
 
-        if !Self::has_scope(env, agent, scope) {
+        if !Self::has_scope(env.clone(), agent.clone(), scope) {
             panic!("agent missing required scope");
         }
+        if let Some(limit) = env
+            .storage()
+            .instance()
+            .get::<_, LimitConfig>(&DataKey::RateLimit)
+        {
+            if syncro_ratelimit::check_and_consume(&env, &agent, &limit).is_err() {
+                panic!("agent rate limited");
+            }
+        }
     }
-    
 }
 
 mod test;
@@ -9,7 +9,7 @@ fn test_registration_and_revocation() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register_contract(None, AgentRegistry);
+    let contract_id = env.register(AgentRegistry, ());
     let client = AgentRegistryClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -26,15 +26,23 @@ fn test_registration_and_revocation() {
     assert!(client.is_authorized(&agent));
 
     // Revoke
-    client.revoke(&agent);
+    client.revoke_agent(&agent, &ReasonCode::AgentRetired);
     assert!(!client.is_authorized(&agent));
+    assert_eq!(
+        client.get_revocation(&agent),
+        Some(ReasonCode::AgentRetired)
+    );
+
+    // Registering again clears the revocation
+    client.register(&agent);
+    assert_eq!(client.get_revocation(&agent), None);
 }
 
 #[test]
 #[should_panic(expected = "agent not authorized")]
 fn test_require_authorized_panics() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, AgentRegistry);
+    let contract_id = env.register(AgentRegistry, ());
     let client = AgentRegistryClient::new(&env, &contract_id);
 
     let agent = Address::generate(&env);
@@ -46,12 +54,12 @@ fn test_admin_auth() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register_contract(None, AgentRegistry);
+    let contract_id = env.register(AgentRegistry, ());
     let client = AgentRegistryClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    let not_admin = Address::generate(&env);
-    let agent = Address::generate(&env);
+    let _not_admin = Address::generate(&env);
+    let _agent = Address::generate(&env);
 
     client.init(&admin);
 
@@ -66,7 +74,7 @@ fn test_already_initialized() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register_contract(None, AgentRegistry);
+    let contract_id = env.register(AgentRegistry, ());
     let client = AgentRegistryClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -75,3 +83,109 @@ fn test_already_initialized() {
     let result = client.try_init(&admin);
     assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
 }
+
+#[test]
+#[should_panic(expected = "agent rate limited")]
+fn test_require_scope_rate_limited() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(AgentRegistry, ());
+    let client = AgentRegistryClient::new(&env, &contract_id);
+
+    let agent = Address::generate(&env);
+    client.init(&Address::generate(&env));
+    client.register(&agent);
+    client.update_scopes(&agent, &Scope::ALL);
+    client.set_rate_limit(&LimitConfig::token_bucket(2, 60));
+
+    client.require_scope(&agent, &Scope::Renewals);
+    client.require_scope(&agent, &Scope::Renewals);
+    client.require_scope(&agent, &Scope::Renewals);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(AgentRegistry, ());
+    let client = AgentRegistryClient::new(&env, &contract_id);
+
+    client.init(&Address::generate(&env));
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+
+    let new_admin = Address::generate(&env);
+    client.propose_admin(&new_admin);
+    client.accept_admin();
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+}
+
+#[test]
+fn test_migrate_requires_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(AgentRegistry, ());
+    let client = AgentRegistryClient::new(&env, &contract_id);
+
+    client.init(&Address::generate(&env));
+    assert_eq!(client.get_code_version(), CODE_VERSION);
+    assert_eq!(
+        client.try_migrate(&CODE_VERSION, &(CODE_VERSION + 1)),
+        Err(Ok(Error::VersionMismatch))
+    );
+
+    // Without the admin's signature both fail on auth before any checks
+    env.set_auths(&[]);
+    assert!(client
+        .try_migrate(&CODE_VERSION, &(CODE_VERSION + 1))
+        .unwrap_err()
+        .is_err());
+    assert!(client
+        .try_upgrade(&BytesN::from_array(&env, &[0; 32]))
+        .unwrap_err()
+        .is_err());
+}
+
+/// Stand-in for the renewal contract that counts cache invalidations.
+#[soroban_sdk::contract]
+struct MockCache;
+
+#[soroban_sdk::contractimpl]
+impl MockCache {
+    pub fn invalidate_agent_cache(env: Env, agent: Address) {
+        let count: u32 = env.storage().instance().get(&agent).unwrap_or(0);
+        env.storage().instance().set(&agent, &(count + 1));
+    }
+
+    pub fn invalidations(env: Env, agent: Address) -> u32 {
+        env.storage().instance().get(&agent).unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_scope_changes_invalidate_the_renewal_cache() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = AgentRegistryClient::new(&env, &env.register(AgentRegistry, ()));
+    let cache = MockCacheClient::new(&env, &env.register(MockCache, ()));
+    client.init(&Address::generate(&env));
+    let agent = Address::generate(&env);
+    client.register(&agent);
+
+    // Nothing to notify until the renewal contract is set
+    client.update_scopes(&agent, &Scope::Renewals.bit());
+    client.set_renewal_contract(&Some(cache.address.clone()));
+    assert_eq!(client.get_renewal_contract(), Some(cache.address.clone()));
+
+    client.update_scopes(&agent, &Scope::Audit.bit());
+    client.revoke_agent(&agent, &ReasonCode::AgentCompromised);
+    assert_eq!(cache.invalidations(&agent), 2);
+
+    // A renewal contract refusing the call doesn't block the revocation
+    client.set_renewal_contract(&Some(Address::generate(&env)));
+    client.register(&agent);
+    client.revoke_agent(&agent, &ReasonCode::AgentRetired);
+    assert!(!client.is_authorized(&agent));
+}
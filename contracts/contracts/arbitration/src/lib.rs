@@ -0,0 +1,425 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, Address,
+    BytesN, Env, Val, Vec,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1101,
+    NotInitialized = 1102,
+    InvalidQuorum = 1103,
+    NotArbiter = 1104,
+    SourceNotRegistered = 1105,
+    InvalidAmount = 1106,
+    DisputeNotFound = 1107,
+    DisputeClosed = 1108,
+    AlreadyDisputed = 1109,
+    AlreadyVoted = 1110,
+    NotParty = 1111,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Arbiters,
+    Quorum,
+    DisputeCounter,
+    Source(Address),                 // registered payment contract -> true
+    Dispute(u64),                    // dispute id -> Dispute
+    CycleDispute(Address, u64, u64), // (source, sub_id, cycle_id) -> dispute id
+    Vote(u64, Address),              // (dispute id, arbiter) -> Outcome
+}
+
+/// How a dispute is settled by the payment contract that holds the funds.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Outcome {
+    /// Return the disputed amount to the payer.
+    Refund = 1,
+    /// Pay the disputed amount out to the merchant.
+    Release = 2,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum DisputeStatus {
+    Open = 1,
+    Refunded = 2,
+    Released = 3,
+    Withdrawn = 4,
+}
+
+/// A disputed renewal charge. `source` is the payment contract holding the
+/// funds; it executes the final outcome through its [`DisputeHook`].
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    pub id: u64,
+    pub source: Address,
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub payer: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub evidence: Vec<BytesN<32>>,
+    pub refund_votes: u32,
+    pub release_votes: u32,
+    pub status: DisputeStatus,
+    pub opened_at: u32,
+}
+
+/// Implemented by payment contracts (renewal escrow and the like) that
+/// accept rulings. Called by this contract once a dispute is settled; the
+/// implementation should `require_auth` on the arbitration address.
+#[contractclient(name = "DisputeHookClient")]
+pub trait DisputeHook {
+    fn execute_ruling(env: Env, dispute_id: u64, sub_id: u64, cycle_id: u64, outcome: Outcome);
+}
+
+#[contractevent]
+pub struct ArbitersChanged {
+    pub arbiter_count: u32,
+    pub quorum: u32,
+}
+
+#[contractevent]
+pub struct DisputeOpened {
+    pub id: u64,
+    pub source: Address,
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub payer: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct EvidenceSubmitted {
+    pub id: u64,
+    pub party: Address,
+    pub evidence_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct RulingVoted {
+    pub id: u64,
+    pub arbiter: Address,
+    pub outcome: Outcome,
+}
+
+#[contractevent]
+pub struct DisputeResolved {
+    pub id: u64,
+    pub outcome: Outcome,
+}
+
+#[contractevent]
+pub struct DisputeWithdrawn {
+    pub id: u64,
+}
+
+#[contract]
+pub struct ArbitrationContract;
+
+#[contractimpl]
+impl ArbitrationContract {
+    /// Initialize with an admin and the initial arbiter set. A ruling takes
+    /// effect once `quorum` arbiters vote for the same outcome.
+    pub fn init(
+        env: Env,
+        admin: Address,
+        arbiters: Vec<Address>,
+        quorum: u32,
+    ) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        Self::store_arbiters(&env, arbiters, quorum)?;
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Configuration (admin only) ────────────────────────────────
+
+    /// Replace the arbiter set. Votes already cast on open disputes stay
+    /// counted.
+    pub fn set_arbiters(env: Env, arbiters: Vec<Address>, quorum: u32) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        Self::store_arbiters(&env, arbiters, quorum)
+    }
+
+    /// Allow disputes over charges made by `source`.
+    pub fn register_source(env: Env, source: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Source(source), &true);
+        Ok(())
+    }
+
+    pub fn remove_source(env: Env, source: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().persistent().remove(&DataKey::Source(source));
+        Ok(())
+    }
+
+    pub fn get_arbiters(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Arbiters)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_quorum(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::Quorum).unwrap_or(0)
+    }
+
+    // ── Disputes ──────────────────────────────────────────────────
+
+    /// Open a dispute over one cycle's charge. At most one dispute per
+    /// charge.
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_dispute(
+        env: Env,
+        payer: Address,
+        source: Address,
+        sub_id: u64,
+        cycle_id: u64,
+        merchant: Address,
+        amount: i128,
+        evidence_hash: BytesN<32>,
+    ) -> Result<u64, Error> {
+        payer.require_auth();
+        if !env
+            .storage()
+            .persistent()
+            .has(&DataKey::Source(source.clone()))
+        {
+            return Err(Error::SourceNotRegistered);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let cycle_key = DataKey::CycleDispute(source.clone(), sub_id, cycle_id);
+        if env.storage().persistent().has(&cycle_key) {
+            return Err(Error::AlreadyDisputed);
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DisputeCounter)
+            .unwrap_or(0u64)
+            + 1;
+        env.storage().instance().set(&DataKey::DisputeCounter, &id);
+
+        let mut evidence = Vec::new(&env);
+        evidence.push_back(evidence_hash);
+        let dispute = Dispute {
+            id,
+            source: source.clone(),
+            sub_id,
+            cycle_id,
+            payer: payer.clone(),
+            merchant,
+            amount,
+            evidence,
+            refund_votes: 0,
+            release_votes: 0,
+            status: DisputeStatus::Open,
+            opened_at: env.ledger().sequence(),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Dispute(id), &dispute);
+        env.storage().persistent().set(&cycle_key, &id);
+
+        DisputeOpened {
+            id,
+            source,
+            sub_id,
+            cycle_id,
+            payer,
+            amount,
+        }
+        .publish(&env);
+        Ok(id)
+    }
+
+    /// Attach a hash of off-chain evidence. Only the payer and the merchant
+    /// of an open dispute can submit.
+    pub fn submit_evidence(
+        env: Env,
+        party: Address,
+        id: u64,
+        evidence_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        party.require_auth();
+        let mut dispute = Self::load_open(&env, id)?;
+        if party != dispute.payer && party != dispute.merchant {
+            return Err(Error::NotParty);
+        }
+        dispute.evidence.push_back(evidence_hash.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Dispute(id), &dispute);
+
+        EvidenceSubmitted {
+            id,
+            party,
+            evidence_hash,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Cast an arbiter's vote. When an outcome reaches quorum the dispute is
+    /// closed and the ruling is executed on the source contract.
+    pub fn rule(
+        env: Env,
+        arbiter: Address,
+        id: u64,
+        outcome: Outcome,
+    ) -> Result<DisputeStatus, Error> {
+        arbiter.require_auth();
+        if !Self::get_arbiters(env.clone()).contains(&arbiter) {
+            return Err(Error::NotArbiter);
+        }
+        let mut dispute = Self::load_open(&env, id)?;
+
+        let vote_key = DataKey::Vote(id, arbiter.clone());
+        if env.storage().persistent().has(&vote_key) {
+            return Err(Error::AlreadyVoted);
+        }
+        env.storage().persistent().set(&vote_key, &outcome);
+
+        let votes = match outcome {
+            Outcome::Refund => {
+                dispute.refund_votes += 1;
+                dispute.refund_votes
+            }
+            Outcome::Release => {
+                dispute.release_votes += 1;
+                dispute.release_votes
+            }
+        };
+        RulingVoted {
+            id,
+            arbiter,
+            outcome,
+        }
+        .publish(&env);
+
+        if votes >= Self::get_quorum(env.clone()) {
+            dispute.status = match outcome {
+                Outcome::Refund => DisputeStatus::Refunded,
+                Outcome::Release => DisputeStatus::Released,
+            };
+            Self::execute(&env, &dispute, outcome);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Dispute(id), &dispute);
+        Ok(dispute.status)
+    }
+
+    /// Drop an open dispute. The payer gives up the claim, so the funds are
+    /// released to the merchant.
+    pub fn withdraw_dispute(env: Env, id: u64) -> Result<(), Error> {
+        let mut dispute = Self::load_open(&env, id)?;
+        dispute.payer.require_auth();
+
+        dispute.status = DisputeStatus::Withdrawn;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Dispute(id), &dispute);
+        Self::execute(&env, &dispute, Outcome::Release);
+
+        DisputeWithdrawn { id }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_dispute(env: Env, id: u64) -> Option<Dispute> {
+        env.storage().persistent().get(&DataKey::Dispute(id))
+    }
+
+    /// The dispute opened over a charge, if any.
+    pub fn get_cycle_dispute(env: Env, source: Address, sub_id: u64, cycle_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CycleDispute(source, sub_id, cycle_id))
+    }
+
+    pub fn get_vote(env: Env, id: u64, arbiter: Address) -> Option<Outcome> {
+        env.storage().persistent().get(&DataKey::Vote(id, arbiter))
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn store_arbiters(env: &Env, arbiters: Vec<Address>, quorum: u32) -> Result<(), Error> {
+        if quorum == 0 || quorum > arbiters.len() {
+            return Err(Error::InvalidQuorum);
+        }
+        env.storage().instance().set(&DataKey::Arbiters, &arbiters);
+        env.storage().instance().set(&DataKey::Quorum, &quorum);
+        ArbitersChanged {
+            arbiter_count: arbiters.len(),
+            quorum,
+        }
+        .publish(env);
+        Ok(())
+    }
+
+    fn load_open(env: &Env, id: u64) -> Result<Dispute, Error> {
+        let dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(id))
+            .ok_or(Error::DisputeNotFound)?;
+        if dispute.status != DisputeStatus::Open {
+            return Err(Error::DisputeClosed);
+        }
+        Ok(dispute)
+    }
+
+    fn execute(env: &Env, dispute: &Dispute, outcome: Outcome) {
+        DisputeHookClient::new(env, &dispute.source).execute_ruling(
+            &dispute.id,
+            &dispute.sub_id,
+            &dispute.cycle_id,
+            &outcome,
+        );
+        DisputeResolved {
+            id: dispute.id,
+            outcome,
+        }
+        .publish(env);
+    }
+}
+
+#[cfg(test)]
+mod test;
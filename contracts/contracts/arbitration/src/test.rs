@@ -0,0 +1,277 @@
+use super::*;
+use soroban_sdk::{contract, contractimpl, testutils::Address as _, vec, Address, BytesN, Env};
+
+/// Stand-in for a payment contract: records the rulings it is asked to
+/// execute.
+#[contract]
+struct MockEscrow;
+
+#[contractimpl]
+impl MockEscrow {
+    pub fn execute_ruling(
+        env: Env,
+        dispute_id: u64,
+        _sub_id: u64,
+        _cycle_id: u64,
+        outcome: Outcome,
+    ) {
+        env.storage().instance().set(&dispute_id, &outcome);
+    }
+
+    pub fn ruling(env: Env, dispute_id: u64) -> Option<Outcome> {
+        env.storage().instance().get(&dispute_id)
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: ArbitrationContractClient<'static>,
+    escrow: MockEscrowClient<'static>,
+    arbiters: [Address; 3],
+    payer: Address,
+    merchant: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(ArbitrationContract, ());
+    let client = ArbitrationContractClient::new(&env, &contract_id);
+    let arbiters = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let admin = Address::generate(&env);
+    client.init(
+        &admin,
+        &vec![
+            &env,
+            arbiters[0].clone(),
+            arbiters[1].clone(),
+            arbiters[2].clone(),
+        ],
+        &2,
+    );
+
+    let escrow_id = env.register(MockEscrow, ());
+    client.register_source(&escrow_id);
+    let escrow = MockEscrowClient::new(&env, &escrow_id);
+
+    let payer = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    Setup {
+        env,
+        client,
+        escrow,
+        arbiters,
+        payer,
+        merchant,
+    }
+}
+
+fn evidence(env: &Env, byte: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[byte; 32])
+}
+
+impl Setup {
+    fn open(&self, cycle_id: u64) -> u64 {
+        self.client.open_dispute(
+            &self.payer,
+            &self.escrow.address,
+            &1,
+            &cycle_id,
+            &self.merchant,
+            &1599,
+            &evidence(&self.env, 1),
+        )
+    }
+}
+
+#[test]
+fn test_quorum_refund_executes_on_source() {
+    let s = setup();
+    let id = s.open(1);
+
+    assert_eq!(
+        s.client.rule(&s.arbiters[0], &id, &Outcome::Refund),
+        DisputeStatus::Open
+    );
+    assert_eq!(s.escrow.ruling(&id), None);
+
+    assert_eq!(
+        s.client.rule(&s.arbiters[1], &id, &Outcome::Refund),
+        DisputeStatus::Refunded
+    );
+    assert_eq!(s.escrow.ruling(&id), Some(Outcome::Refund));
+
+    let dispute = s.client.get_dispute(&id).unwrap();
+    assert_eq!(dispute.status, DisputeStatus::Refunded);
+    assert_eq!(dispute.refund_votes, 2);
+}
+
+#[test]
+fn test_split_vote_needs_quorum() {
+    let s = setup();
+    let id = s.open(1);
+
+    s.client.rule(&s.arbiters[0], &id, &Outcome::Refund);
+    s.client.rule(&s.arbiters[1], &id, &Outcome::Release);
+    assert_eq!(
+        s.client.get_dispute(&id).unwrap().status,
+        DisputeStatus::Open
+    );
+
+    assert_eq!(
+        s.client.rule(&s.arbiters[2], &id, &Outcome::Release),
+        DisputeStatus::Released
+    );
+    assert_eq!(s.escrow.ruling(&id), Some(Outcome::Release));
+    assert_eq!(
+        s.client.get_vote(&id, &s.arbiters[0]),
+        Some(Outcome::Refund)
+    );
+}
+
+#[test]
+fn test_only_arbiters_vote_once() {
+    let s = setup();
+    let id = s.open(1);
+
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_rule(&stranger, &id, &Outcome::Refund),
+        Err(Ok(Error::NotArbiter))
+    );
+
+    s.client.rule(&s.arbiters[0], &id, &Outcome::Refund);
+    assert_eq!(
+        s.client.try_rule(&s.arbiters[0], &id, &Outcome::Refund),
+        Err(Ok(Error::AlreadyVoted))
+    );
+}
+
+#[test]
+fn test_closed_dispute_rejects_votes_and_evidence() {
+    let s = setup();
+    let id = s.open(1);
+    s.client.rule(&s.arbiters[0], &id, &Outcome::Release);
+    s.client.rule(&s.arbiters[1], &id, &Outcome::Release);
+
+    assert_eq!(
+        s.client.try_rule(&s.arbiters[2], &id, &Outcome::Refund),
+        Err(Ok(Error::DisputeClosed))
+    );
+    assert_eq!(
+        s.client
+            .try_submit_evidence(&s.payer, &id, &evidence(&s.env, 2)),
+        Err(Ok(Error::DisputeClosed))
+    );
+}
+
+#[test]
+fn test_open_dispute_validation() {
+    let s = setup();
+
+    let unknown = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_open_dispute(
+            &s.payer,
+            &unknown,
+            &1,
+            &1,
+            &s.merchant,
+            &1599,
+            &evidence(&s.env, 1)
+        ),
+        Err(Ok(Error::SourceNotRegistered))
+    );
+    assert_eq!(
+        s.client.try_open_dispute(
+            &s.payer,
+            &s.escrow.address,
+            &1,
+            &1,
+            &s.merchant,
+            &0,
+            &evidence(&s.env, 1)
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    let id = s.open(1);
+    assert_eq!(
+        s.client.get_cycle_dispute(&s.escrow.address, &1, &1),
+        Some(id)
+    );
+    assert_eq!(
+        s.client.try_open_dispute(
+            &s.payer,
+            &s.escrow.address,
+            &1,
+            &1,
+            &s.merchant,
+            &1599,
+            &evidence(&s.env, 1)
+        ),
+        Err(Ok(Error::AlreadyDisputed))
+    );
+
+    // Another cycle of the same subscription can be disputed
+    assert_eq!(s.open(2), id + 1);
+}
+
+#[test]
+fn test_evidence_from_parties_only() {
+    let s = setup();
+    let id = s.open(1);
+
+    s.client
+        .submit_evidence(&s.payer, &id, &evidence(&s.env, 2));
+    s.client
+        .submit_evidence(&s.merchant, &id, &evidence(&s.env, 3));
+    assert_eq!(s.client.get_dispute(&id).unwrap().evidence.len(), 3);
+
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client
+            .try_submit_evidence(&stranger, &id, &evidence(&s.env, 4)),
+        Err(Ok(Error::NotParty))
+    );
+}
+
+#[test]
+fn test_withdraw_releases_funds() {
+    let s = setup();
+    let id = s.open(1);
+
+    s.client.withdraw_dispute(&id);
+    assert_eq!(
+        s.client.get_dispute(&id).unwrap().status,
+        DisputeStatus::Withdrawn
+    );
+    assert_eq!(s.escrow.ruling(&id), Some(Outcome::Release));
+    assert_eq!(
+        s.client.try_withdraw_dispute(&id),
+        Err(Ok(Error::DisputeClosed))
+    );
+}
+
+#[test]
+fn test_arbiter_set_validation() {
+    let s = setup();
+    let one = vec![&s.env, Address::generate(&s.env)];
+    assert_eq!(
+        s.client.try_set_arbiters(&one, &2),
+        Err(Ok(Error::InvalidQuorum))
+    );
+    assert_eq!(
+        s.client.try_set_arbiters(&one, &0),
+        Err(Ok(Error::InvalidQuorum))
+    );
+
+    s.client.set_arbiters(&one, &1);
+    assert_eq!(s.client.get_arbiters(), one);
+    assert_eq!(s.client.get_quorum(), 1);
+}
@@ -0,0 +1,148 @@
+#![no_std]
+//! Keeper-facing batch execution of SYNCRO operations.
+//!
+//! A keeper submits one transaction carrying a list of items, each a
+//! subscription and the operations to apply to it (acquire the lock, renew,
+//! record a log, mark the registry entry renewed). Items are handed one at a
+//! time to the batch runner contract, so each applies all or nothing: an
+//! item that fails is rolled back and reported as `Reverted` while the rest
+//! of the batch goes ahead.
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, Address,
+    Env, Val, Vec,
+};
+use syncro_access::AccessError;
+use syncro_common::{BatchItem, ItemOutcome};
+
+/// Most items in one batch.
+pub const MAX_ITEMS: u32 = 20;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2801,
+    NotInitialized = 2802,
+    NoPendingAdmin = 2803,
+    Unauthorized = 2804,
+    EmptyBatch = 2805,
+    TooManyItems = 2806,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Runner,
+}
+
+/// The batch runner, initialized with this contract as its executor.
+#[contractclient(name = "RunnerClient")]
+pub trait RunnerInterface {
+    fn run_item(env: Env, keeper: Address, item: BatchItem) -> ItemOutcome;
+}
+
+#[contractevent]
+pub struct ItemReverted {
+    pub keeper: Address,
+    pub sub_id: u64,
+}
+
+#[contractevent]
+pub struct BatchExecuted {
+    pub keeper: Address,
+    pub items: u32,
+    pub reverted: u32,
+}
+
+#[contract]
+pub struct BatchExecutorContract;
+
+#[contractimpl]
+impl BatchExecutorContract {
+    pub fn init(env: Env, admin: Address, runner: Address) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::Runner, &runner);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    /// Point at a new runner deployment. Admin only.
+    pub fn set_runner(env: Env, runner: Address) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Runner, &runner);
+        Ok(())
+    }
+
+    pub fn get_runner(env: Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Runner)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Run `items` in order as `keeper`, returning each item's outcome.
+    /// Locks are taken in the keeper's name, so the keeper must authorize
+    /// the renewal contract calls beneath this one.
+    pub fn execute(
+        env: Env,
+        keeper: Address,
+        items: Vec<BatchItem>,
+    ) -> Result<Vec<ItemOutcome>, Error> {
+        keeper.require_auth();
+        if items.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
+        if items.len() > MAX_ITEMS {
+            return Err(Error::TooManyItems);
+        }
+
+        let runner = RunnerClient::new(&env, &Self::get_runner(env.clone())?);
+        let mut outcomes = Vec::new(&env);
+        let mut reverted = 0;
+        for item in items.iter() {
+            let outcome = match runner.try_run_item(&keeper, &item) {
+                Ok(Ok(outcome)) => outcome,
+                _ => {
+                    reverted += 1;
+                    ItemReverted {
+                        keeper: keeper.clone(),
+                        sub_id: item.sub_id,
+                    }
+                    .publish(&env);
+                    ItemOutcome::Reverted
+                }
+            };
+            outcomes.push_back(outcome);
+        }
+
+        BatchExecuted {
+            keeper,
+            items: items.len(),
+            reverted,
+        }
+        .publish(&env);
+        Ok(outcomes)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,111 @@
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, vec, Address, Env};
+use syncro_common::BatchOp;
+
+/// Stand-in for the batch runner: counts the items it completes and fails
+/// odd subscriptions after recording them.
+#[contract]
+struct MockRunner;
+
+#[contractimpl]
+impl MockRunner {
+    pub fn run_item(env: Env, _keeper: Address, item: BatchItem) -> ItemOutcome {
+        env.storage().instance().set(&item.sub_id, &true);
+        if item.sub_id % 2 == 1 {
+            panic!("item failed");
+        }
+        ItemOutcome::Renewed
+    }
+
+    pub fn was_run(env: Env, sub_id: u64) -> bool {
+        env.storage().instance().has(&sub_id)
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: BatchExecutorContractClient<'static>,
+    runner: MockRunnerClient<'static>,
+    keeper: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    let runner = MockRunnerClient::new(&env, &env.register(MockRunner, ()));
+    let client = BatchExecutorContractClient::new(&env, &env.register(BatchExecutorContract, ()));
+    client.init(&Address::generate(&env), &runner.address);
+    Setup {
+        keeper: Address::generate(&env),
+        env,
+        client,
+        runner,
+    }
+}
+
+impl Setup {
+    fn item(&self, sub_id: u64) -> BatchItem {
+        BatchItem {
+            sub_id,
+            ops: vec![&self.env, BatchOp::AcquireLock(100)],
+        }
+    }
+}
+
+#[test]
+fn test_failed_items_revert_alone() {
+    let s = setup();
+    let items = vec![&s.env, s.item(2), s.item(3), s.item(4)];
+    let outcomes = s.client.execute(&s.keeper, &items);
+    assert_eq!(
+        outcomes,
+        vec![
+            &s.env,
+            ItemOutcome::Renewed,
+            ItemOutcome::Reverted,
+            ItemOutcome::Renewed
+        ]
+    );
+    assert!(s.runner.was_run(&2));
+    assert!(!s.runner.was_run(&3));
+    assert!(s.runner.was_run(&4));
+}
+
+#[test]
+fn test_batch_size_is_bounded() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_execute(&s.keeper, &vec![&s.env])
+            .unwrap_err()
+            .unwrap(),
+        Error::EmptyBatch
+    );
+
+    let mut items = vec![&s.env];
+    for sub_id in 0..=MAX_ITEMS as u64 {
+        items.push_back(s.item(sub_id * 2));
+    }
+    assert_eq!(
+        s.client
+            .try_execute(&s.keeper, &items)
+            .unwrap_err()
+            .unwrap(),
+        Error::TooManyItems
+    );
+}
+
+#[test]
+fn test_admin_replaces_runner() {
+    let s = setup();
+    let runner = Address::generate(&s.env);
+    s.client.set_runner(&runner);
+    assert_eq!(s.client.get_runner(), runner);
+    assert_eq!(
+        s.client
+            .try_init(&Address::generate(&s.env), &runner)
+            .unwrap_err()
+            .unwrap(),
+        Error::AlreadyInitialized
+    );
+}
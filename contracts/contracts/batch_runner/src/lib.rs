@@ -0,0 +1,139 @@
+#![no_std]
+// The generated client and args builders for `renew` cannot be annotated one
+// by one.
+#![allow(clippy::too_many_arguments)]
+//! Runs one batch item on behalf of the batch executor.
+//!
+//! The executor calls `run_item` once per item with `try_`, so anything an
+//! item changed in the renewal, logging and registry contracts is rolled back
+//! with it if any of its operations fails. This has to be a contract of its
+//! own: a contract cannot call itself to open a fresh frame.
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractimpl, contracttype, Address, BytesN, Env,
+    String, Val, Vec,
+};
+use syncro_common::{BatchItem, BatchOp, ItemOutcome, LogEvent};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2901,
+    NotInitialized = 2902,
+    RenewalRequired = 2903,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Executor,
+    Renewal,
+    Logging,
+    Registry,
+}
+
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn acquire_renewal_lock(env: Env, agent: Address, sub_id: u64, lock_timeout: u32);
+    fn renew(env: Env, sub_id: u64, approval_id: u64, amount: i128, cycle_id: u64) -> bool;
+}
+
+#[contractclient(name = "LoggingClient")]
+pub trait LoggingInterface {
+    fn record_log(env: Env, sub_id: u64, event: LogEvent, data: String);
+}
+
+#[contractclient(name = "RegistryClient")]
+pub trait RegistryInterface {
+    fn mark_renewed(env: Env, subscription_id: BytesN<32>, renewer: Address) -> u64;
+}
+
+#[contract]
+pub struct BatchRunnerContract;
+
+#[contractimpl]
+impl BatchRunnerContract {
+    /// Initialize with the executor allowed to run items and the contracts
+    /// items operate on. The registry must grant this contract its renewer
+    /// role for `MarkRegistryRenewed`.
+    pub fn init(
+        env: Env,
+        executor: Address,
+        renewal: Address,
+        logging: Address,
+        registry: Address,
+    ) -> Result<(), Error> {
+        let storage = env.storage().instance();
+        if storage.has(&DataKey::Executor) {
+            return Err(Error::AlreadyInitialized);
+        }
+        storage.set(&DataKey::Executor, &executor);
+        storage.set(&DataKey::Renewal, &renewal);
+        storage.set(&DataKey::Logging, &logging);
+        storage.set(&DataKey::Registry, &registry);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    /// Apply `item`'s operations in order, as `keeper`. Executor only.
+    pub fn run_item(env: Env, keeper: Address, item: BatchItem) -> Result<ItemOutcome, Error> {
+        Self::address(&env, DataKey::Executor)?.require_auth();
+
+        let renewal = RenewalClient::new(&env, &Self::address(&env, DataKey::Renewal)?);
+        let mut renewed: Option<bool> = None;
+        for op in item.ops.iter() {
+            match op {
+                BatchOp::AcquireLock(lock_timeout) => {
+                    renewal.acquire_renewal_lock(&keeper, &item.sub_id, &lock_timeout);
+                }
+                BatchOp::Renew(args) => {
+                    renewed = Some(renewal.renew(
+                        &item.sub_id,
+                        &args.approval_id,
+                        &args.amount,
+                        &args.cycle_id,
+                    ));
+                }
+                BatchOp::RecordLog(event, data) => {
+                    LoggingClient::new(&env, &Self::address(&env, DataKey::Logging)?).record_log(
+                        &item.sub_id,
+                        &event,
+                        &data,
+                    );
+                }
+                BatchOp::MarkRegistryRenewed(subscription_id) => match renewed {
+                    Some(true) => {
+                        RegistryClient::new(&env, &Self::address(&env, DataKey::Registry)?)
+                            .mark_renewed(&subscription_id, &env.current_contract_address());
+                    }
+                    Some(false) => {}
+                    None => return Err(Error::RenewalRequired),
+                },
+            }
+        }
+
+        Ok(match renewed {
+            None => ItemOutcome::Executed,
+            Some(true) => ItemOutcome::Renewed,
+            Some(false) => ItemOutcome::RenewalFailed,
+        })
+    }
+
+    fn address(env: &Env, key: DataKey) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&key)
+            .ok_or(Error::NotInitialized)
+    }
+}
+
+#[cfg(test)]
+mod test;
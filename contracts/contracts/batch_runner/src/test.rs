@@ -0,0 +1,157 @@
+use super::*;
+use soroban_sdk::{
+    contract, symbol_short, testutils::Address as _, vec, Address, BytesN, Env, String,
+};
+use syncro_common::RenewOp;
+use syncro_testutils::{MockLogging, MockLoggingClient};
+
+/// Stand-in for the renewal contract: records lock holders and renews
+/// unless set failing, as when the payer can't pay.
+#[contract]
+struct MockRenewal;
+
+#[contractimpl]
+impl MockRenewal {
+    pub fn acquire_renewal_lock(env: Env, agent: Address, sub_id: u64, _lock_timeout: u32) {
+        env.storage().instance().set(&sub_id, &agent);
+    }
+
+    pub fn set_failing(env: Env, failing: bool) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("failing"), &failing);
+    }
+
+    pub fn renew(env: Env, _sub_id: u64, _approval_id: u64, _amount: i128, _cycle_id: u64) -> bool {
+        !env.storage()
+            .instance()
+            .get(&symbol_short!("failing"))
+            .unwrap_or(false)
+    }
+
+    pub fn lock_holder(env: Env, sub_id: u64) -> Option<Address> {
+        env.storage().instance().get(&sub_id)
+    }
+}
+
+/// Stand-in for the subscription registry: remembers who marked what.
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn mark_renewed(env: Env, subscription_id: BytesN<32>, renewer: Address) -> u64 {
+        env.storage().instance().set(&subscription_id, &renewer);
+        0
+    }
+
+    pub fn marked_by(env: Env, subscription_id: BytesN<32>) -> Option<Address> {
+        env.storage().instance().get(&subscription_id)
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: BatchRunnerContractClient<'static>,
+    renewal: MockRenewalClient<'static>,
+    logging: MockLoggingClient<'static>,
+    registry: MockRegistryClient<'static>,
+    keeper: Address,
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let renewal = MockRenewalClient::new(&env, &env.register(MockRenewal, ()));
+    let logging = MockLoggingClient::new(&env, &env.register(MockLogging, ()));
+    let registry = MockRegistryClient::new(&env, &env.register(MockRegistry, ()));
+    let client = BatchRunnerContractClient::new(&env, &env.register(BatchRunnerContract, ()));
+    client.init(
+        &Address::generate(&env),
+        &renewal.address,
+        &logging.address,
+        &registry.address,
+    );
+    Setup {
+        keeper: Address::generate(&env),
+        env,
+        client,
+        renewal,
+        logging,
+        registry,
+    }
+}
+
+impl Setup {
+    fn registry_id(&self) -> BytesN<32> {
+        BytesN::from_array(&self.env, &[7; 32])
+    }
+
+    fn full_item(&self) -> BatchItem {
+        BatchItem {
+            sub_id: 1,
+            ops: vec![
+                &self.env,
+                BatchOp::AcquireLock(100),
+                BatchOp::Renew(RenewOp {
+                    approval_id: 1,
+                    amount: 100,
+                    cycle_id: 1,
+                }),
+                BatchOp::RecordLog(LogEvent::Renewal, String::from_str(&self.env, "renewed")),
+                BatchOp::MarkRegistryRenewed(self.registry_id()),
+            ],
+        }
+    }
+}
+
+#[test]
+fn test_item_applies_every_operation() {
+    let s = setup();
+    assert_eq!(
+        s.client.run_item(&s.keeper, &s.full_item()),
+        ItemOutcome::Renewed
+    );
+    assert_eq!(s.renewal.lock_holder(&1), Some(s.keeper.clone()));
+    assert_eq!(s.logging.logs(&1), vec![&s.env, LogEvent::Renewal]);
+    assert_eq!(
+        s.registry.marked_by(&s.registry_id()),
+        Some(s.client.address.clone())
+    );
+}
+
+#[test]
+fn test_failed_renewal_leaves_registry_alone() {
+    let s = setup();
+    s.renewal.set_failing(&true);
+    assert_eq!(
+        s.client.run_item(&s.keeper, &s.full_item()),
+        ItemOutcome::RenewalFailed
+    );
+    assert_eq!(s.logging.logs(&1), vec![&s.env, LogEvent::Renewal]);
+    assert_eq!(s.registry.marked_by(&s.registry_id()), None);
+}
+
+#[test]
+fn test_registry_mark_needs_a_renewal() {
+    let s = setup();
+    let lock_only = BatchItem {
+        sub_id: 1,
+        ops: vec![&s.env, BatchOp::AcquireLock(100)],
+    };
+    assert_eq!(
+        s.client.run_item(&s.keeper, &lock_only),
+        ItemOutcome::Executed
+    );
+
+    let mark_only = BatchItem {
+        sub_id: 1,
+        ops: vec![&s.env, BatchOp::MarkRegistryRenewed(s.registry_id())],
+    };
+    assert_eq!(
+        s.client
+            .try_run_item(&s.keeper, &mark_only)
+            .unwrap_err()
+            .unwrap(),
+        Error::RenewalRequired
+    );
+}
@@ -0,0 +1,299 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, token,
+    xdr::ToXdr, Address, Bytes, BytesN, Env, Val, Vec,
+};
+use syncro_access::AccessError;
+use syncro_common::SubscriptionState;
+
+/// Version of the `BridgeMessage` layout this adapter accepts.
+pub const MESSAGE_VERSION: u32 = 1;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2101,
+    NotInitialized = 2102,
+    NoPendingAdmin = 2103,
+    Unauthorized = 2104,
+    UnsupportedVersion = 2105,
+    UnknownBridge = 2106,
+    Replayed = 2107,
+    CycleAlreadyCredited = 2108,
+    WrongToken = 2109,
+    InsufficientAmount = 2110,
+    SubscriptionInactive = 2111,
+    InvalidProof = 2112,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Token,
+    Renewal,
+    Bridge(Address, u32), // (bridge, source chain) -> verifier
+    Nonce(u32, u64),      // (source chain, nonce) -> true once delivered
+    Credit(u64, u64),     // (sub_id, cycle_id) -> Credit
+}
+
+/// Mirror of the renewal contract's `SubscriptionData`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionData {
+    pub owner: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub integrity_hash: BytesN<32>,
+    pub state: SubscriptionState,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+/// The renewal contract: source of the merchant and price of a subscription.
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn get_sub(env: Env, sub_id: u64) -> SubscriptionData;
+}
+
+/// A renewal payment sent from another chain. The bridge delivers the
+/// message together with the funds it released on this chain.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BridgeMessage {
+    /// Layout version; must equal `MESSAGE_VERSION`.
+    pub version: u32,
+    /// Bridge-specific identifier of the chain the payment came from.
+    pub source_chain: u32,
+    /// Unique per source chain; a nonce is only ever accepted once.
+    pub nonce: u64,
+    /// Payer on the source chain, in that chain's address encoding.
+    pub sender: Bytes,
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Checks that a message really was sent on its source chain, e.g. against
+/// the bridge's guardian signatures or a light client. `message_hash` is
+/// the SHA-256 of the message's XDR encoding.
+#[contractclient(name = "VerifierClient")]
+pub trait BridgeVerifier {
+    fn verify(env: Env, source_chain: u32, message_hash: BytesN<32>, proof: Bytes) -> bool;
+}
+
+/// A cycle paid through a bridge.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Credit {
+    pub bridge: Address,
+    pub source_chain: u32,
+    pub nonce: u64,
+    pub amount: i128,
+    pub credited_at: u64,
+}
+
+#[contractevent]
+pub struct BridgeAllowed {
+    pub bridge: Address,
+    pub source_chain: u32,
+    pub verifier: Address,
+}
+
+#[contractevent]
+pub struct BridgeRemoved {
+    pub bridge: Address,
+    pub source_chain: u32,
+}
+
+#[contractevent]
+pub struct PaymentBridged {
+    pub bridge: Address,
+    pub source_chain: u32,
+    pub nonce: u64,
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub amount: i128,
+}
+
+#[contract]
+pub struct BridgeIntakeContract;
+
+#[contractimpl]
+impl BridgeIntakeContract {
+    /// Initialize with an admin, the token renewals are paid in and the
+    /// renewal contract subscriptions are read from.
+    pub fn init(env: Env, admin: Address, token: Address, renewal: Address) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::Renewal, &renewal);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Bridges (admin only) ──────────────────────────────────────
+
+    /// Accept messages from `source_chain` delivered by `bridge`, checked
+    /// by `verifier`.
+    pub fn allow_bridge(
+        env: Env,
+        bridge: Address,
+        source_chain: u32,
+        verifier: Address,
+    ) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Bridge(bridge.clone(), source_chain), &verifier);
+        BridgeAllowed {
+            bridge,
+            source_chain,
+            verifier,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn remove_bridge(env: Env, bridge: Address, source_chain: u32) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Bridge(bridge.clone(), source_chain));
+        BridgeRemoved {
+            bridge,
+            source_chain,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Verifier for messages from `source_chain` delivered by `bridge`, if
+    /// allowed.
+    pub fn get_verifier(env: Env, bridge: Address, source_chain: u32) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Bridge(bridge, source_chain))
+    }
+
+    // ── Intake ────────────────────────────────────────────────────
+
+    /// Credit a bridged payment to its subscription cycle and forward the
+    /// funds from `bridge` to the merchant. Each nonce and each cycle can
+    /// only be credited once.
+    pub fn deliver(
+        env: Env,
+        bridge: Address,
+        message: BridgeMessage,
+        proof: Bytes,
+    ) -> Result<Credit, Error> {
+        bridge.require_auth();
+        if message.version != MESSAGE_VERSION {
+            return Err(Error::UnsupportedVersion);
+        }
+        let verifier = Self::get_verifier(env.clone(), bridge.clone(), message.source_chain)
+            .ok_or(Error::UnknownBridge)?;
+        let nonce_key = DataKey::Nonce(message.source_chain, message.nonce);
+        if env.storage().persistent().has(&nonce_key) {
+            return Err(Error::Replayed);
+        }
+        let credit_key = DataKey::Credit(message.sub_id, message.cycle_id);
+        if env.storage().persistent().has(&credit_key) {
+            return Err(Error::CycleAlreadyCredited);
+        }
+
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        if message.token != token {
+            return Err(Error::WrongToken);
+        }
+        let renewal: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Renewal)
+            .ok_or(Error::NotInitialized)?;
+        let sub = RenewalClient::new(&env, &renewal).get_sub(&message.sub_id);
+        if matches!(
+            sub.state,
+            SubscriptionState::Cancelled
+                | SubscriptionState::Failed
+                | SubscriptionState::Expired
+                | SubscriptionState::Paused
+        ) {
+            return Err(Error::SubscriptionInactive);
+        }
+        if message.amount < sub.amount {
+            return Err(Error::InsufficientAmount);
+        }
+
+        let message_hash: BytesN<32> = env.crypto().sha256(&message.clone().to_xdr(&env)).into();
+        if !VerifierClient::new(&env, &verifier).verify(
+            &message.source_chain,
+            &message_hash,
+            &proof,
+        ) {
+            return Err(Error::InvalidProof);
+        }
+
+        env.storage().persistent().set(&nonce_key, &true);
+        token::TokenClient::new(&env, &token).transfer(&bridge, &sub.merchant, &message.amount);
+        let credit = Credit {
+            bridge: bridge.clone(),
+            source_chain: message.source_chain,
+            nonce: message.nonce,
+            amount: message.amount,
+            credited_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&credit_key, &credit);
+
+        PaymentBridged {
+            bridge,
+            source_chain: message.source_chain,
+            nonce: message.nonce,
+            sub_id: message.sub_id,
+            cycle_id: message.cycle_id,
+            amount: message.amount,
+        }
+        .publish(&env);
+        Ok(credit)
+    }
+
+    pub fn get_credit(env: Env, sub_id: u64, cycle_id: u64) -> Option<Credit> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Credit(sub_id, cycle_id))
+    }
+
+    pub fn is_nonce_used(env: Env, source_chain: u32, nonce: u64) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Nonce(source_chain, nonce))
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,189 @@
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, Address, Bytes, BytesN, Env};
+use syncro_testutils::TestToken;
+
+/// Stand-in for the renewal contract: serves whatever `set_sub` stored.
+#[contract]
+struct MockRenewal;
+
+#[contractimpl]
+impl MockRenewal {
+    pub fn set_sub(env: Env, sub_id: u64, data: SubscriptionData) {
+        env.storage().instance().set(&sub_id, &data);
+    }
+
+    pub fn get_sub(env: Env, sub_id: u64) -> SubscriptionData {
+        env.storage().instance().get(&sub_id).unwrap()
+    }
+}
+
+/// Stand-in verifier: a valid proof is the message hash itself.
+#[contract]
+struct MockVerifier;
+
+#[contractimpl]
+impl MockVerifier {
+    pub fn verify(_env: Env, _source_chain: u32, message_hash: BytesN<32>, proof: Bytes) -> bool {
+        proof == Bytes::from(message_hash)
+    }
+}
+
+const CHAIN: u32 = 2;
+
+struct Setup {
+    env: Env,
+    client: BridgeIntakeContractClient<'static>,
+    token: TestToken,
+    renewal: MockRenewalClient<'static>,
+    bridge: Address,
+    merchant: Address,
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let token = TestToken::new(&env);
+    let renewal = MockRenewalClient::new(&env, &env.register(MockRenewal, ()));
+    let contract_id = env.register(BridgeIntakeContract, ());
+    let client = BridgeIntakeContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env), &token.address, &renewal.address);
+
+    let bridge = Address::generate(&env);
+    client.allow_bridge(&bridge, &CHAIN, &env.register(MockVerifier, ()));
+    token.mint(&bridge, 10_000);
+
+    let merchant = Address::generate(&env);
+    renewal.set_sub(
+        &1,
+        &SubscriptionData {
+            owner: Address::generate(&env),
+            merchant: merchant.clone(),
+            amount: 100,
+            frequency: 2_592_000,
+            spending_cap: 1_000,
+            integrity_hash: BytesN::from_array(&env, &[0; 32]),
+            state: SubscriptionState::Active,
+            failure_count: 0,
+            last_attempt_ledger: 0,
+        },
+    );
+    Setup {
+        env,
+        client,
+        token,
+        renewal,
+        bridge,
+        merchant,
+    }
+}
+
+impl Setup {
+    fn message(&self, nonce: u64, cycle_id: u64, amount: i128) -> BridgeMessage {
+        BridgeMessage {
+            version: MESSAGE_VERSION,
+            source_chain: CHAIN,
+            nonce,
+            sender: Bytes::from_array(&self.env, &[7; 20]),
+            sub_id: 1,
+            cycle_id,
+            token: self.token.address.clone(),
+            amount,
+        }
+    }
+
+    fn proof(&self, message: &BridgeMessage) -> Bytes {
+        self.env
+            .crypto()
+            .sha256(&message.clone().to_xdr(&self.env))
+            .to_bytes()
+            .into()
+    }
+
+    fn deliver(&self, message: &BridgeMessage) -> Result<Credit, Error> {
+        match self
+            .client
+            .try_deliver(&self.bridge, message, &self.proof(message))
+        {
+            Ok(credit) => Ok(credit.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+}
+
+#[test]
+fn test_delivery_credits_cycle_and_pays_merchant() {
+    let s = setup();
+    let credit = s.deliver(&s.message(1, 1, 100)).unwrap();
+    assert_eq!(credit.nonce, 1);
+    assert_eq!(s.token.balance(&s.merchant), 100);
+    assert_eq!(s.token.balance(&s.bridge), 9_900);
+    assert_eq!(s.client.get_credit(&1, &1), Some(credit));
+    assert!(s.client.is_nonce_used(&CHAIN, &1));
+}
+
+#[test]
+fn test_replays_rejected() {
+    let s = setup();
+    let message = s.message(1, 1, 100);
+    s.deliver(&message).unwrap();
+    assert_eq!(s.deliver(&message), Err(Error::Replayed));
+
+    // A fresh nonce cannot pay the same cycle twice either
+    assert_eq!(
+        s.deliver(&s.message(2, 1, 100)),
+        Err(Error::CycleAlreadyCredited)
+    );
+    assert_eq!(s.token.balance(&s.merchant), 100);
+}
+
+#[test]
+fn test_only_allowlisted_bridges() {
+    let s = setup();
+    let message = s.message(1, 1, 100);
+    assert_eq!(
+        s.client
+            .try_deliver(&Address::generate(&s.env), &message, &s.proof(&message)),
+        Err(Ok(Error::UnknownBridge))
+    );
+
+    let mut other_chain = message.clone();
+    other_chain.source_chain = CHAIN + 1;
+    assert_eq!(s.deliver(&other_chain), Err(Error::UnknownBridge));
+
+    s.client.remove_bridge(&s.bridge, &CHAIN);
+    assert_eq!(s.deliver(&message), Err(Error::UnknownBridge));
+}
+
+#[test]
+fn test_invalid_messages_rejected() {
+    let s = setup();
+    let message = s.message(1, 1, 100);
+    assert_eq!(
+        s.client
+            .try_deliver(&s.bridge, &message, &Bytes::from_array(&s.env, &[0; 32])),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert_eq!(
+        s.deliver(&s.message(1, 1, 99)),
+        Err(Error::InsufficientAmount)
+    );
+
+    let mut wrong_version = message.clone();
+    wrong_version.version = MESSAGE_VERSION + 1;
+    assert_eq!(s.deliver(&wrong_version), Err(Error::UnsupportedVersion));
+
+    let mut wrong_token = message;
+    wrong_token.token = Address::generate(&s.env);
+    assert_eq!(s.deliver(&wrong_token), Err(Error::WrongToken));
+}
+
+#[test]
+fn test_cancelled_subscription_not_credited() {
+    let s = setup();
+    let mut sub = s.renewal.get_sub(&1);
+    sub.state = SubscriptionState::Cancelled;
+    s.renewal.set_sub(&1, &sub);
+    assert_eq!(
+        s.deliver(&s.message(1, 1, 100)),
+        Err(Error::SubscriptionInactive)
+    );
+}
@@ -0,0 +1,375 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, token, Address, Env, Val,
+    Vec,
+};
+
+/// Most subscriptions a single bundle can hold.
+pub const MAX_COMPONENTS: u32 = 10;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1301,
+    NotInitialized = 1302,
+    InvalidBundle = 1303,
+    InvalidAmount = 1304,
+    BundleNotFound = 1305,
+    BundleInactive = 1306,
+    NotDue = 1307,
+    ComponentNotFound = 1308,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Token,
+    BundleCounter,
+    Bundle(u64), // bundle id -> Bundle
+}
+
+/// One subscription inside a bundle, paid `weight / total weight` of each
+/// charge.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Component {
+    pub sub_id: u64,
+    pub merchant: Address,
+    pub weight: u32,
+    /// Consecutive charges whose payout to this merchant failed.
+    pub failures: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bundle {
+    pub owner: Address,
+    pub components: Vec<Component>,
+    pub price: i128,
+    pub frequency: u64,
+    pub next_charge_at: u64,
+    /// Number of charges collected so far.
+    pub cycle: u64,
+    pub active: bool,
+}
+
+/// Result of one bundle charge. `paid` went to merchants; `refunded` is the
+/// share of components whose payout failed, returned to the owner.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BundleCharge {
+    pub cycle: u64,
+    pub collected: bool,
+    pub paid: i128,
+    pub refunded: i128,
+    pub failed: Vec<u64>,
+}
+
+#[contractevent]
+pub struct BundleCreated {
+    pub bundle_id: u64,
+    pub owner: Address,
+    pub price: i128,
+    pub component_count: u32,
+}
+
+#[contractevent]
+pub struct BundleCharged {
+    pub bundle_id: u64,
+    pub cycle: u64,
+    pub paid: i128,
+    pub refunded: i128,
+}
+
+#[contractevent]
+pub struct BundleChargeFailed {
+    pub bundle_id: u64,
+    pub cycle: u64,
+}
+
+#[contractevent]
+pub struct ComponentPaid {
+    pub bundle_id: u64,
+    pub sub_id: u64,
+    pub merchant: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct ComponentFailed {
+    pub bundle_id: u64,
+    pub sub_id: u64,
+    pub failures: u32,
+}
+
+#[contractevent]
+pub struct BundleCancelled {
+    pub bundle_id: u64,
+}
+
+#[contract]
+pub struct BundleContract;
+
+#[contractimpl]
+impl BundleContract {
+    /// Initialize with an admin and the token bundles are billed in.
+    pub fn init(env: Env, admin: Address, token: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Bundles ───────────────────────────────────────────────────
+
+    /// Combine subscriptions into one charge of `price` every `frequency`
+    /// seconds, starting now. The owner must give this contract a token
+    /// allowance covering the charges.
+    pub fn create_bundle(
+        env: Env,
+        owner: Address,
+        components: Vec<Component>,
+        price: i128,
+        frequency: u64,
+    ) -> Result<u64, Error> {
+        owner.require_auth();
+        if price <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if frequency == 0 || components.is_empty() || components.len() > MAX_COMPONENTS {
+            return Err(Error::InvalidBundle);
+        }
+        for component in components.iter() {
+            if component.weight == 0 {
+                return Err(Error::InvalidBundle);
+            }
+        }
+
+        let bundle_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::BundleCounter)
+            .unwrap_or(0u64)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::BundleCounter, &bundle_id);
+
+        let component_count = components.len();
+        let bundle = Bundle {
+            owner: owner.clone(),
+            components,
+            price,
+            frequency,
+            next_charge_at: env.ledger().timestamp(),
+            cycle: 0,
+            active: true,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Bundle(bundle_id), &bundle);
+
+        BundleCreated {
+            bundle_id,
+            owner,
+            price,
+            component_count,
+        }
+        .publish(&env);
+        Ok(bundle_id)
+    }
+
+    /// Drop one subscription from the bundle. The remaining components
+    /// share the full price. Removing the last component cancels the bundle.
+    pub fn remove_component(env: Env, bundle_id: u64, sub_id: u64) -> Result<(), Error> {
+        let mut bundle = Self::load_active(&env, bundle_id)?;
+        bundle.owner.require_auth();
+
+        let index = bundle
+            .components
+            .iter()
+            .position(|c| c.sub_id == sub_id)
+            .ok_or(Error::ComponentNotFound)?;
+        bundle.components.remove(index as u32);
+        if bundle.components.is_empty() {
+            bundle.active = false;
+            BundleCancelled { bundle_id }.publish(&env);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Bundle(bundle_id), &bundle);
+        Ok(())
+    }
+
+    pub fn cancel_bundle(env: Env, bundle_id: u64) -> Result<(), Error> {
+        let mut bundle = Self::load_active(&env, bundle_id)?;
+        bundle.owner.require_auth();
+
+        bundle.active = false;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Bundle(bundle_id), &bundle);
+        BundleCancelled { bundle_id }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_bundle(env: Env, bundle_id: u64) -> Option<Bundle> {
+        env.storage().persistent().get(&DataKey::Bundle(bundle_id))
+    }
+
+    // ── Charging ──────────────────────────────────────────────────
+
+    /// Collect the bundle price from the owner and split it across the
+    /// components by weight. Anyone may trigger a due charge.
+    ///
+    /// If the owner cannot be charged, every component records a failure
+    /// and the charge can be retried. If only some merchant payouts fail,
+    /// those components record a failure, their share goes back to the
+    /// owner and the cycle still advances.
+    pub fn charge(env: Env, bundle_id: u64) -> Result<BundleCharge, Error> {
+        let mut bundle = Self::load_active(&env, bundle_id)?;
+        if env.ledger().timestamp() < bundle.next_charge_at {
+            return Err(Error::NotDue);
+        }
+        let token = Self::token(&env)?;
+        let this = env.current_contract_address();
+        let cycle = bundle.cycle + 1;
+
+        let collected = token
+            .try_transfer_from(&this, &bundle.owner, &this, &bundle.price)
+            .is_ok_and(|r| r.is_ok());
+        if !collected {
+            let mut failed = Vec::new(&env);
+            let mut components = Vec::new(&env);
+            for mut component in bundle.components.iter() {
+                component.failures += 1;
+                Self::component_failed(&env, bundle_id, &component);
+                failed.push_back(component.sub_id);
+                components.push_back(component);
+            }
+            bundle.components = components;
+            env.storage()
+                .persistent()
+                .set(&DataKey::Bundle(bundle_id), &bundle);
+            BundleChargeFailed { bundle_id, cycle }.publish(&env);
+            return Ok(BundleCharge {
+                cycle,
+                collected: false,
+                paid: 0,
+                refunded: 0,
+                failed,
+            });
+        }
+
+        let total_weight: i128 = bundle.components.iter().map(|c| c.weight as i128).sum();
+        let last = bundle.components.len() - 1;
+        let mut allotted = 0i128;
+        let mut paid = 0i128;
+        let mut failed = Vec::new(&env);
+        let mut components = Vec::new(&env);
+        for (i, mut component) in bundle.components.iter().enumerate() {
+            // The last component absorbs rounding so the full price is split
+            let share = if i as u32 == last {
+                bundle.price - allotted
+            } else {
+                bundle.price * component.weight as i128 / total_weight
+            };
+            allotted += share;
+
+            let delivered = token
+                .try_transfer(&this, &component.merchant, &share)
+                .is_ok_and(|r| r.is_ok());
+            if delivered {
+                component.failures = 0;
+                paid += share;
+                ComponentPaid {
+                    bundle_id,
+                    sub_id: component.sub_id,
+                    merchant: component.merchant.clone(),
+                    amount: share,
+                }
+                .publish(&env);
+            } else {
+                component.failures += 1;
+                Self::component_failed(&env, bundle_id, &component);
+                failed.push_back(component.sub_id);
+            }
+            components.push_back(component);
+        }
+
+        let refunded = bundle.price - paid;
+        if refunded > 0 {
+            token.transfer(&this, &bundle.owner, &refunded);
+        }
+
+        bundle.components = components;
+        bundle.cycle = cycle;
+        bundle.next_charge_at += bundle.frequency;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Bundle(bundle_id), &bundle);
+
+        BundleCharged {
+            bundle_id,
+            cycle,
+            paid,
+            refunded,
+        }
+        .publish(&env);
+        Ok(BundleCharge {
+            cycle,
+            collected: true,
+            paid,
+            refunded,
+            failed,
+        })
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn load_active(env: &Env, bundle_id: u64) -> Result<Bundle, Error> {
+        let bundle: Bundle = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Bundle(bundle_id))
+            .ok_or(Error::BundleNotFound)?;
+        if !bundle.active {
+            return Err(Error::BundleInactive);
+        }
+        Ok(bundle)
+    }
+
+    fn component_failed(env: &Env, bundle_id: u64, component: &Component) {
+        ComponentFailed {
+            bundle_id,
+            sub_id: component.sub_id,
+            failures: component.failures,
+        }
+        .publish(env);
+    }
+
+    fn token(env: &Env) -> Result<token::TokenClient<'_>, Error> {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::TokenClient::new(env, &token))
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,206 @@
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, IssuerFlags},
+    vec, Address, Env,
+};
+use syncro_testutils::TestToken;
+
+struct Setup {
+    env: Env,
+    client: BundleContractClient<'static>,
+    token: TestToken,
+    owner: Address,
+    merchants: [Address; 3],
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let token = TestToken::new(&env);
+    token.sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+
+    let contract_id = env.register(BundleContract, ());
+    let client = BundleContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env), &token.address);
+
+    let owner = Address::generate(&env);
+    token.mint(&owner, 10_000);
+    token.approve(&owner, &contract_id, &5_000, &1_000);
+
+    let merchants = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    Setup {
+        env,
+        client,
+        token,
+        owner,
+        merchants,
+    }
+}
+
+fn component(merchant: &Address, sub_id: u64, weight: u32) -> Component {
+    Component {
+        sub_id,
+        merchant: merchant.clone(),
+        weight,
+        failures: 0,
+    }
+}
+
+impl Setup {
+    fn bundle(&self, weights: [u32; 3], price: i128) -> u64 {
+        let components = vec![
+            &self.env,
+            component(&self.merchants[0], 1, weights[0]),
+            component(&self.merchants[1], 2, weights[1]),
+            component(&self.merchants[2], 3, weights[2]),
+        ];
+        self.client
+            .create_bundle(&self.owner, &components, &price, &2_592_000)
+    }
+
+    fn balances(&self) -> [i128; 3] {
+        [
+            self.token.balance(&self.merchants[0]),
+            self.token.balance(&self.merchants[1]),
+            self.token.balance(&self.merchants[2]),
+        ]
+    }
+
+    fn advance(&self, seconds: u64) {
+        syncro_testutils::advance_time(&self.env, seconds);
+    }
+}
+
+#[test]
+fn test_charge_splits_by_weight() {
+    let s = setup();
+    let id = s.bundle([1, 1, 2], 1_000);
+
+    let charge = s.client.charge(&id);
+    assert!(charge.collected);
+    assert_eq!(charge.cycle, 1);
+    assert_eq!(charge.paid, 1_000);
+    assert!(charge.failed.is_empty());
+    assert_eq!(s.balances(), [250, 250, 500]);
+    assert_eq!(s.token.balance(&s.owner), 9_000);
+}
+
+#[test]
+fn test_rounding_goes_to_last_component() {
+    let s = setup();
+    let id = s.bundle([1, 1, 1], 100);
+    s.client.charge(&id);
+    assert_eq!(s.balances(), [33, 33, 34]);
+}
+
+#[test]
+fn test_charge_only_when_due() {
+    let s = setup();
+    let id = s.bundle([1, 1, 2], 1_000);
+    s.client.charge(&id);
+    assert_eq!(s.client.try_charge(&id), Err(Ok(Error::NotDue)));
+
+    s.advance(2_592_000);
+    assert_eq!(s.client.charge(&id).cycle, 2);
+    assert_eq!(s.balances(), [500, 500, 1_000]);
+}
+
+#[test]
+fn test_owner_failure_marks_every_component() {
+    let s = setup();
+    let id = s.bundle([1, 1, 2], 6_000);
+
+    // Allowance only covers 5000
+    let charge = s.client.charge(&id);
+    assert!(!charge.collected);
+    assert_eq!(charge.failed, vec![&s.env, 1u64, 2, 3]);
+    let bundle = s.client.get_bundle(&id).unwrap();
+    assert_eq!(bundle.cycle, 0);
+    assert!(bundle.components.iter().all(|c| c.failures == 1));
+
+    // Retry succeeds once the allowance is raised, and clears the failures
+    s.token.approve(&s.owner, &s.client.address, &6_000, &1_000);
+    let charge = s.client.charge(&id);
+    assert!(charge.collected);
+    assert_eq!(charge.cycle, 1);
+    let bundle = s.client.get_bundle(&id).unwrap();
+    assert!(bundle.components.iter().all(|c| c.failures == 0));
+}
+
+#[test]
+fn test_merchant_failure_refunds_its_share() {
+    let s = setup();
+    let id = s.bundle([1, 1, 2], 1_000);
+    s.token.admin.set_authorized(&s.merchants[1], &false);
+
+    let charge = s.client.charge(&id);
+    assert!(charge.collected);
+    assert_eq!(charge.paid, 750);
+    assert_eq!(charge.refunded, 250);
+    assert_eq!(charge.failed, vec![&s.env, 2u64]);
+    assert_eq!(s.balances(), [250, 0, 500]);
+    assert_eq!(s.token.balance(&s.owner), 9_250);
+
+    let bundle = s.client.get_bundle(&id).unwrap();
+    assert_eq!(bundle.cycle, 1);
+    assert_eq!(bundle.components.get(1).unwrap().failures, 1);
+}
+
+#[test]
+fn test_remove_and_cancel() {
+    let s = setup();
+    let id = s.bundle([1, 1, 2], 900);
+
+    s.client.remove_component(&id, &2);
+    assert_eq!(
+        s.client.try_remove_component(&id, &2),
+        Err(Ok(Error::ComponentNotFound))
+    );
+    s.client.charge(&id);
+    assert_eq!(s.balances(), [300, 0, 600]);
+
+    s.client.cancel_bundle(&id);
+    s.advance(2_592_000);
+    assert_eq!(s.client.try_charge(&id), Err(Ok(Error::BundleInactive)));
+}
+
+#[test]
+fn test_invalid_bundles_rejected() {
+    let s = setup();
+    assert_eq!(
+        s.client.try_create_bundle(
+            &s.owner,
+            &vec![&s.env, component(&s.merchants[0], 1, 0)],
+            &100,
+            &86_400
+        ),
+        Err(Ok(Error::InvalidBundle))
+    );
+    assert_eq!(
+        s.client
+            .try_create_bundle(&s.owner, &Vec::new(&s.env), &100, &86_400),
+        Err(Ok(Error::InvalidBundle))
+    );
+    assert_eq!(
+        s.client.try_create_bundle(
+            &s.owner,
+            &vec![&s.env, component(&s.merchants[0], 1, 1)],
+            &0,
+            &86_400
+        ),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    let mut too_many = Vec::new(&s.env);
+    for sub_id in 0..=MAX_COMPONENTS as u64 {
+        too_many.push_back(component(&s.merchants[0], sub_id, 1));
+    }
+    assert_eq!(
+        s.client
+            .try_create_bundle(&s.owner, &too_many, &100, &86_400),
+        Err(Ok(Error::InvalidBundle))
+    );
+}
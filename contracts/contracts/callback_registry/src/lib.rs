@@ -0,0 +1,345 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, Address,
+    Env, Symbol, Val, Vec,
+};
+pub use syncro_common::Notification;
+
+/// Most active callbacks per event kind. Every notification invokes at most
+/// this many contracts, which keeps an emitter's cost bounded.
+pub const MAX_CALLBACKS_PER_KIND: u32 = 16;
+/// Consecutive failed deliveries after which a callback is disabled.
+pub const MAX_FAILURES: u32 = 3;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1501,
+    NotInitialized = 1502,
+    NotEmitter = 1503,
+    CallbackNotFound = 1504,
+    TooManyCallbacks = 1505,
+    AlreadyActive = 1506,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    CallbackCounter,
+    Emitter(Address), // authorized emitter -> true
+    Callback(u64),    // callback id -> Registration
+    Kind(Symbol),     // event kind -> Vec<callback id> of active callbacks
+}
+
+/// A contract's interest in one kind of event. `merchant` and `sub_id`
+/// narrow the match when set.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Registration {
+    pub owner: Address,
+    pub callback: Address,
+    pub kind: Symbol,
+    pub merchant: Option<Address>,
+    pub sub_id: Option<u64>,
+    /// Consecutive deliveries that failed.
+    pub failures: u32,
+    pub active: bool,
+}
+
+/// Implemented by contracts that receive notifications. The registry is the
+/// direct caller, so a receiver can `require_auth` on the registry address to
+/// reject anything else.
+#[contractclient(name = "CallbackReceiverClient")]
+pub trait CallbackReceiver {
+    fn on_syncro_event(env: Env, callback_id: u64, emitter: Address, notification: Notification);
+}
+
+#[contractevent]
+pub struct CallbackRegistered {
+    pub id: u64,
+    pub owner: Address,
+    pub callback: Address,
+    pub kind: Symbol,
+}
+
+#[contractevent]
+pub struct CallbackRemoved {
+    pub id: u64,
+}
+
+#[contractevent]
+pub struct CallbackFailed {
+    pub id: u64,
+    pub failures: u32,
+}
+
+#[contractevent]
+pub struct CallbackDisabled {
+    pub id: u64,
+}
+
+#[contractevent]
+pub struct NotificationSent {
+    pub emitter: Address,
+    pub kind: Symbol,
+    pub sub_id: u64,
+    pub delivered: u32,
+}
+
+#[contract]
+pub struct CallbackRegistryContract;
+
+#[contractimpl]
+impl CallbackRegistryContract {
+    /// Initialize with an admin who manages the authorized emitters.
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Emitters (admin only) ─────────────────────────────────────
+
+    /// Allow `emitter` (a SYNCRO contract) to push notifications.
+    pub fn add_emitter(env: Env, emitter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Emitter(emitter), &true);
+        Ok(())
+    }
+
+    pub fn remove_emitter(env: Env, emitter: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Emitter(emitter));
+        Ok(())
+    }
+
+    pub fn is_emitter(env: Env, emitter: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Emitter(emitter))
+    }
+
+    // ── Registrations ─────────────────────────────────────────────
+
+    /// Register `callback` for events of `kind`, optionally only those for
+    /// one merchant or one subscription.
+    pub fn register(
+        env: Env,
+        owner: Address,
+        callback: Address,
+        kind: Symbol,
+        merchant: Option<Address>,
+        sub_id: Option<u64>,
+    ) -> Result<u64, Error> {
+        owner.require_auth();
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CallbackCounter)
+            .unwrap_or(0u64)
+            + 1;
+        Self::index(&env, &kind, id)?;
+        env.storage().instance().set(&DataKey::CallbackCounter, &id);
+
+        let registration = Registration {
+            owner: owner.clone(),
+            callback: callback.clone(),
+            kind: kind.clone(),
+            merchant,
+            sub_id,
+            failures: 0,
+            active: true,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Callback(id), &registration);
+
+        CallbackRegistered {
+            id,
+            owner,
+            callback,
+            kind,
+        }
+        .publish(&env);
+        Ok(id)
+    }
+
+    /// Remove a registration. Owner only.
+    pub fn unregister(env: Env, id: u64) -> Result<(), Error> {
+        let registration = Self::load(&env, id)?;
+        registration.owner.require_auth();
+
+        if registration.active {
+            Self::unindex(&env, &registration.kind, id);
+        }
+        env.storage().persistent().remove(&DataKey::Callback(id));
+        CallbackRemoved { id }.publish(&env);
+        Ok(())
+    }
+
+    /// Re-enable a callback disabled after repeated failures. Owner only.
+    pub fn reactivate(env: Env, id: u64) -> Result<(), Error> {
+        let mut registration = Self::load(&env, id)?;
+        registration.owner.require_auth();
+        if registration.active {
+            return Err(Error::AlreadyActive);
+        }
+
+        Self::index(&env, &registration.kind, id)?;
+        registration.failures = 0;
+        registration.active = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Callback(id), &registration);
+        Ok(())
+    }
+
+    pub fn get_registration(env: Env, id: u64) -> Option<Registration> {
+        env.storage().persistent().get(&DataKey::Callback(id))
+    }
+
+    /// Ids of the active callbacks for `kind`.
+    pub fn callbacks_for(env: Env, kind: Symbol) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Kind(kind))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // ── Delivery ──────────────────────────────────────────────────
+
+    /// Push `notification` to every matching callback and return how many
+    /// accepted it. Only authorized emitters may notify.
+    ///
+    /// A callback that fails does not fail the emitter; it records a failure
+    /// and is disabled after `MAX_FAILURES` in a row.
+    pub fn notify(env: Env, emitter: Address, notification: Notification) -> Result<u32, Error> {
+        emitter.require_auth();
+        if !Self::is_emitter(env.clone(), emitter.clone()) {
+            return Err(Error::NotEmitter);
+        }
+
+        let mut delivered = 0u32;
+        for id in Self::callbacks_for(env.clone(), notification.kind.clone()).iter() {
+            let mut registration = Self::load(&env, id)?;
+            if !Self::matches(&registration, &notification) {
+                continue;
+            }
+
+            let ok = CallbackReceiverClient::new(&env, &registration.callback)
+                .try_on_syncro_event(&id, &emitter, &notification)
+                .is_ok_and(|r| r.is_ok());
+            if ok {
+                delivered += 1;
+                if registration.failures == 0 {
+                    continue;
+                }
+                registration.failures = 0;
+            } else {
+                registration.failures += 1;
+                CallbackFailed {
+                    id,
+                    failures: registration.failures,
+                }
+                .publish(&env);
+                if registration.failures >= MAX_FAILURES {
+                    registration.active = false;
+                    Self::unindex(&env, &registration.kind, id);
+                    CallbackDisabled { id }.publish(&env);
+                }
+            }
+            env.storage()
+                .persistent()
+                .set(&DataKey::Callback(id), &registration);
+        }
+
+        NotificationSent {
+            emitter,
+            kind: notification.kind,
+            sub_id: notification.sub_id,
+            delivered,
+        }
+        .publish(&env);
+        Ok(delivered)
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn load(env: &Env, id: u64) -> Result<Registration, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Callback(id))
+            .ok_or(Error::CallbackNotFound)
+    }
+
+    fn matches(registration: &Registration, notification: &Notification) -> bool {
+        if let Some(merchant) = &registration.merchant {
+            if *merchant != notification.merchant {
+                return false;
+            }
+        }
+        if let Some(sub_id) = registration.sub_id {
+            if sub_id != notification.sub_id {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn index(env: &Env, kind: &Symbol, id: u64) -> Result<(), Error> {
+        let key = DataKey::Kind(kind.clone());
+        let mut ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if ids.len() >= MAX_CALLBACKS_PER_KIND {
+            return Err(Error::TooManyCallbacks);
+        }
+        ids.push_back(id);
+        env.storage().persistent().set(&key, &ids);
+        Ok(())
+    }
+
+    fn unindex(env: &Env, kind: &Symbol, id: u64) {
+        let key = DataKey::Kind(kind.clone());
+        let mut ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if let Some(index) = ids.first_index_of(id) {
+            ids.remove(index);
+        }
+        env.storage().persistent().set(&key, &ids);
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,224 @@
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, Address, Env, Symbol};
+
+#[contract]
+struct MockReceiver;
+
+#[contractimpl]
+impl MockReceiver {
+    pub fn on_syncro_event(env: Env, _callback_id: u64, _emitter: Address, n: Notification) {
+        let count: u32 = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "count"))
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "count"), &(count + 1));
+        env.storage().instance().set(&Symbol::new(&env, "last"), &n);
+    }
+
+    pub fn count(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, "count"))
+            .unwrap_or(0)
+    }
+
+    pub fn last(env: Env) -> Option<Notification> {
+        env.storage().instance().get(&Symbol::new(&env, "last"))
+    }
+}
+
+#[contract]
+struct MockFailingReceiver;
+
+#[contractimpl]
+impl MockFailingReceiver {
+    pub fn on_syncro_event(_env: Env, _callback_id: u64, _emitter: Address, _n: Notification) {
+        panic!("receiver unavailable");
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: CallbackRegistryContractClient<'static>,
+    emitter: Address,
+    owner: Address,
+    merchant: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(CallbackRegistryContract, ());
+    let client = CallbackRegistryContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+
+    let emitter = Address::generate(&env);
+    client.add_emitter(&emitter);
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    Setup {
+        env,
+        client,
+        emitter,
+        owner,
+        merchant,
+    }
+}
+
+impl Setup {
+    fn receiver(&self) -> MockReceiverClient<'static> {
+        MockReceiverClient::new(&self.env, &self.env.register(MockReceiver, ()))
+    }
+
+    fn notification(&self, kind: &str, sub_id: u64, merchant: &Address) -> Notification {
+        Notification {
+            kind: Symbol::new(&self.env, kind),
+            sub_id,
+            merchant: merchant.clone(),
+            cycle_id: 1,
+            amount: 500,
+        }
+    }
+
+    fn register(
+        &self,
+        callback: &Address,
+        kind: &str,
+        merchant: Option<Address>,
+        sub_id: Option<u64>,
+    ) -> u64 {
+        self.client.register(
+            &self.owner,
+            callback,
+            &Symbol::new(&self.env, kind),
+            &merchant,
+            &sub_id,
+        )
+    }
+}
+
+#[test]
+fn test_notify_delivers_to_matching_callbacks() {
+    let s = setup();
+    let any = s.receiver();
+    let by_merchant = s.receiver();
+    let by_sub = s.receiver();
+    let other_kind = s.receiver();
+    s.register(&any.address, "renewed", None, None);
+    s.register(
+        &by_merchant.address,
+        "renewed",
+        Some(s.merchant.clone()),
+        None,
+    );
+    s.register(&by_sub.address, "renewed", None, Some(7));
+    s.register(&other_kind.address, "cancelled", None, None);
+
+    let n = s.notification("renewed", 7, &s.merchant);
+    assert_eq!(s.client.notify(&s.emitter, &n), 3);
+    assert_eq!(any.last(), Some(n));
+
+    let other_merchant = Address::generate(&s.env);
+    let n = s.notification("renewed", 8, &other_merchant);
+    assert_eq!(s.client.notify(&s.emitter, &n), 1);
+
+    assert_eq!(any.count(), 2);
+    assert_eq!(by_merchant.count(), 1);
+    assert_eq!(by_sub.count(), 1);
+    assert_eq!(other_kind.count(), 0);
+}
+
+#[test]
+fn test_only_emitters_can_notify() {
+    let s = setup();
+    let n = s.notification("renewed", 1, &s.merchant);
+    assert_eq!(
+        s.client.try_notify(&Address::generate(&s.env), &n),
+        Err(Ok(Error::NotEmitter))
+    );
+
+    s.client.remove_emitter(&s.emitter);
+    assert_eq!(
+        s.client.try_notify(&s.emitter, &n),
+        Err(Ok(Error::NotEmitter))
+    );
+}
+
+#[test]
+fn test_failing_callback_is_disabled() {
+    let s = setup();
+    let good = s.receiver();
+    let failing = s.env.register(MockFailingReceiver, ());
+    let bad_id = s.register(&failing, "renewed", None, None);
+    s.register(&good.address, "renewed", None, None);
+
+    let n = s.notification("renewed", 1, &s.merchant);
+    for _ in 0..MAX_FAILURES {
+        // The failure does not stop delivery to other callbacks
+        assert_eq!(s.client.notify(&s.emitter, &n), 1);
+    }
+    assert_eq!(good.count(), MAX_FAILURES);
+
+    let registration = s.client.get_registration(&bad_id).unwrap();
+    assert!(!registration.active);
+    assert_eq!(registration.failures, MAX_FAILURES);
+    assert_eq!(
+        s.client
+            .callbacks_for(&Symbol::new(&s.env, "renewed"))
+            .len(),
+        1
+    );
+
+    s.client.reactivate(&bad_id);
+    assert_eq!(
+        s.client.try_reactivate(&bad_id),
+        Err(Ok(Error::AlreadyActive))
+    );
+    let registration = s.client.get_registration(&bad_id).unwrap();
+    assert!(registration.active);
+    assert_eq!(registration.failures, 0);
+}
+
+#[test]
+fn test_callbacks_per_kind_are_capped() {
+    let s = setup();
+    let receiver = s.receiver();
+    for _ in 0..MAX_CALLBACKS_PER_KIND {
+        s.register(&receiver.address, "renewed", None, None);
+    }
+    assert_eq!(
+        s.client.try_register(
+            &s.owner,
+            &receiver.address,
+            &Symbol::new(&s.env, "renewed"),
+            &None,
+            &None
+        ),
+        Err(Ok(Error::TooManyCallbacks))
+    );
+    // Other kinds have their own budget
+    s.register(&receiver.address, "cancelled", None, None);
+}
+
+#[test]
+fn test_unregister() {
+    let s = setup();
+    let receiver = s.receiver();
+    let id = s.register(&receiver.address, "renewed", None, None);
+    s.client.unregister(&id);
+
+    assert_eq!(s.client.get_registration(&id), None);
+    assert!(s
+        .client
+        .callbacks_for(&Symbol::new(&s.env, "renewed"))
+        .is_empty());
+    let n = s.notification("renewed", 1, &s.merchant);
+    assert_eq!(s.client.notify(&s.emitter, &n), 0);
+    assert_eq!(
+        s.client.try_unregister(&id),
+        Err(Ok(Error::CallbackNotFound))
+    );
+}
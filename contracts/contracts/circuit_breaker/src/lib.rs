@@ -0,0 +1,202 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Symbol, Val,
+    Vec,
+};
+use syncro_access::AccessError;
+
+/// Role bit held by accounts allowed to trip the breaker.
+pub const GUARDIAN: u32 = 1;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2001,
+    NotInitialized = 2002,
+    NotGuardian = 2003,
+    NotTripped = 2004,
+    NoPendingAdmin = 2005,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::NotGuardian,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Trip,              // TripInfo while the whole protocol is paused
+    Isolated(Address), // contract paused on its own -> TripInfo
+}
+
+/// Who paused, why and when.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TripInfo {
+    pub guardian: Address,
+    pub reason: Symbol,
+    pub tripped_at: u64,
+}
+
+#[contractevent]
+pub struct BreakerTripped {
+    pub guardian: Address,
+    pub reason: Symbol,
+}
+
+#[contractevent]
+pub struct BreakerReset {
+    pub admin: Address,
+}
+
+#[contractevent]
+pub struct ContractIsolated {
+    pub contract: Address,
+    pub guardian: Address,
+    pub reason: Symbol,
+}
+
+#[contractevent]
+pub struct ContractRestored {
+    pub contract: Address,
+}
+
+/// One switch for the whole protocol. Protocol contracts are pointed at the
+/// breaker and call `is_paused` with their own address before state-changing
+/// work; a guardian trips it once instead of pausing each contract.
+#[contract]
+pub struct CircuitBreakerContract;
+
+#[contractimpl]
+impl CircuitBreakerContract {
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Guardians (admin only) ────────────────────────────────────
+
+    pub fn add_guardian(env: Env, guardian: Address) -> Result<(), Error> {
+        syncro_access::grant_role(&env, &guardian, GUARDIAN)?;
+        Ok(())
+    }
+
+    pub fn remove_guardian(env: Env, guardian: Address) -> Result<(), Error> {
+        syncro_access::revoke_role(&env, &guardian, GUARDIAN)?;
+        Ok(())
+    }
+
+    pub fn is_guardian(env: Env, account: Address) -> bool {
+        syncro_access::has_role(&env, &account, GUARDIAN)
+    }
+
+    // ── Tripping ──────────────────────────────────────────────────
+
+    /// Pause every protocol contract. Tripping an already tripped breaker
+    /// keeps the original trip.
+    pub fn trip(env: Env, guardian: Address, reason: Symbol) -> Result<(), Error> {
+        syncro_access::require_role(&env, &guardian, GUARDIAN)?;
+        if env.storage().instance().has(&DataKey::Trip) {
+            return Ok(());
+        }
+        env.storage().instance().set(
+            &DataKey::Trip,
+            &TripInfo {
+                guardian: guardian.clone(),
+                reason: reason.clone(),
+                tripped_at: env.ledger().timestamp(),
+            },
+        );
+        BreakerTripped { guardian, reason }.publish(&env);
+        Ok(())
+    }
+
+    /// Resume the protocol. Admin only, so a single compromised guardian
+    /// can stop the protocol but not restart it.
+    pub fn reset(env: Env) -> Result<(), Error> {
+        let admin = syncro_access::require_admin(&env)?;
+        if !env.storage().instance().has(&DataKey::Trip) {
+            return Err(Error::NotTripped);
+        }
+        env.storage().instance().remove(&DataKey::Trip);
+        BreakerReset { admin }.publish(&env);
+        Ok(())
+    }
+
+    /// Pause a single contract, for incidents contained to one component.
+    pub fn isolate(
+        env: Env,
+        guardian: Address,
+        contract: Address,
+        reason: Symbol,
+    ) -> Result<(), Error> {
+        syncro_access::require_role(&env, &guardian, GUARDIAN)?;
+        env.storage().persistent().set(
+            &DataKey::Isolated(contract.clone()),
+            &TripInfo {
+                guardian: guardian.clone(),
+                reason: reason.clone(),
+                tripped_at: env.ledger().timestamp(),
+            },
+        );
+        ContractIsolated {
+            contract,
+            guardian,
+            reason,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Lift an `isolate`. Admin only.
+    pub fn restore(env: Env, contract: Address) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        let key = DataKey::Isolated(contract.clone());
+        if !env.storage().persistent().has(&key) {
+            return Err(Error::NotTripped);
+        }
+        env.storage().persistent().remove(&key);
+        ContractRestored { contract }.publish(&env);
+        Ok(())
+    }
+
+    // ── Queries ───────────────────────────────────────────────────
+
+    pub fn is_tripped(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::Trip)
+    }
+
+    /// Whether `contract` must refuse state-changing calls.
+    pub fn is_paused(env: Env, contract: Address) -> bool {
+        Self::is_tripped(env.clone())
+            || env.storage().persistent().has(&DataKey::Isolated(contract))
+    }
+
+    pub fn trip_info(env: Env) -> Option<TripInfo> {
+        env.storage().instance().get(&DataKey::Trip)
+    }
+
+    pub fn isolation_info(env: Env, contract: Address) -> Option<TripInfo> {
+        env.storage().persistent().get(&DataKey::Isolated(contract))
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,80 @@
+use super::*;
+use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+
+struct Setup {
+    env: Env,
+    client: CircuitBreakerContractClient<'static>,
+    guardian: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(CircuitBreakerContract, ());
+    let client = CircuitBreakerContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+
+    let guardian = Address::generate(&env);
+    client.add_guardian(&guardian);
+    Setup {
+        env,
+        client,
+        guardian,
+    }
+}
+
+#[test]
+fn test_trip_pauses_every_contract() {
+    let s = setup();
+    let renewal = Address::generate(&s.env);
+    let logging = Address::generate(&s.env);
+    assert!(!s.client.is_paused(&renewal));
+
+    let reason = Symbol::new(&s.env, "exploit");
+    s.client.trip(&s.guardian, &reason);
+    assert!(s.client.is_paused(&renewal));
+    assert!(s.client.is_paused(&logging));
+    assert_eq!(s.client.trip_info().unwrap().reason, reason);
+
+    // A second trip keeps the first one's details
+    s.client.trip(&s.guardian, &Symbol::new(&s.env, "again"));
+    assert_eq!(s.client.trip_info().unwrap().reason, reason);
+
+    s.client.reset();
+    assert!(!s.client.is_paused(&renewal));
+    assert_eq!(s.client.try_reset(), Err(Ok(Error::NotTripped)));
+}
+
+#[test]
+fn test_isolate_single_contract() {
+    let s = setup();
+    let renewal = Address::generate(&s.env);
+    let logging = Address::generate(&s.env);
+
+    s.client
+        .isolate(&s.guardian, &logging, &Symbol::new(&s.env, "spam"));
+    assert!(s.client.is_paused(&logging));
+    assert!(!s.client.is_paused(&renewal));
+    assert!(!s.client.is_tripped());
+
+    s.client.restore(&logging);
+    assert!(!s.client.is_paused(&logging));
+    assert_eq!(s.client.try_restore(&logging), Err(Ok(Error::NotTripped)));
+}
+
+#[test]
+fn test_only_guardians_trip() {
+    let s = setup();
+    let reason = Symbol::new(&s.env, "exploit");
+    assert_eq!(
+        s.client.try_trip(&Address::generate(&s.env), &reason),
+        Err(Ok(Error::NotGuardian))
+    );
+
+    s.client.remove_guardian(&s.guardian);
+    assert!(!s.client.is_guardian(&s.guardian));
+    assert_eq!(
+        s.client.try_trip(&s.guardian, &reason),
+        Err(Ok(Error::NotGuardian))
+    );
+}
@@ -0,0 +1,572 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, token, Address, Env, Val,
+    Vec,
+};
+
+/// Most members a shared plan can have.
+pub const MAX_MEMBERS: u32 = 20;
+/// Contributions kept per member; older ones are dropped first.
+const MAX_HISTORY: u32 = 100;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1401,
+    NotInitialized = 1402,
+    InvalidAmount = 1403,
+    InvalidShares = 1404,
+    PlanNotFound = 1405,
+    PlanInactive = 1406,
+    AlreadyMember = 1407,
+    NotMember = 1408,
+    TooManyMembers = 1409,
+    NotDue = 1410,
+    NoMembers = 1411,
+    InsufficientDeposit = 1412,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Token,
+    PlanCounter,
+    Plan(u64),             // plan id -> Plan
+    History(u64, Address), // (plan id, member) -> Vec<Contribution>
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Member {
+    pub address: Address,
+    /// Relative share of each charge the member pays.
+    pub shares: u32,
+    /// Funds the member pre-paid into the contract, used before pulling
+    /// from their allowance.
+    pub deposit: i128,
+    /// Consecutive charges the member could not cover.
+    pub missed: u32,
+}
+
+/// A subscription whose cost is shared between members. `pool` is a
+/// common fund that covers members who fail to pay.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Plan {
+    pub organizer: Address,
+    pub merchant: Address,
+    pub price: i128,
+    pub frequency: u64,
+    pub next_charge_at: u64,
+    pub cycle: u64,
+    pub members: Vec<Member>,
+    pub pool: i128,
+    pub active: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Contribution {
+    pub cycle: u64,
+    pub amount: i128,
+    pub ledger: u32,
+}
+
+/// Result of one charge attempt.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitCharge {
+    pub cycle: u64,
+    pub paid: bool,
+    /// Amount owed by members who could not pay.
+    pub shortfall: i128,
+    /// Part of the shortfall covered by the pool.
+    pub from_pool: i128,
+    pub missed: Vec<Address>,
+}
+
+#[contractevent]
+pub struct PlanCreated {
+    pub plan_id: u64,
+    pub organizer: Address,
+    pub merchant: Address,
+    pub price: i128,
+}
+
+#[contractevent]
+pub struct MemberJoined {
+    pub plan_id: u64,
+    pub member: Address,
+    pub shares: u32,
+}
+
+#[contractevent]
+pub struct MemberLeft {
+    pub plan_id: u64,
+    pub member: Address,
+    pub refunded: i128,
+}
+
+#[contractevent]
+pub struct DepositChanged {
+    pub plan_id: u64,
+    pub member: Address,
+    pub deposit: i128,
+}
+
+#[contractevent]
+pub struct PoolFunded {
+    pub plan_id: u64,
+    pub funder: Address,
+    pub amount: i128,
+    pub pool: i128,
+}
+
+#[contractevent]
+pub struct MemberMissed {
+    pub plan_id: u64,
+    pub member: Address,
+    pub cycle: u64,
+    pub owed: i128,
+}
+
+#[contractevent]
+pub struct PlanCharged {
+    pub plan_id: u64,
+    pub cycle: u64,
+    pub amount: i128,
+    pub from_pool: i128,
+}
+
+#[contractevent]
+pub struct PlanChargeFailed {
+    pub plan_id: u64,
+    pub cycle: u64,
+    pub shortfall: i128,
+}
+
+#[contractevent]
+pub struct PlanCancelled {
+    pub plan_id: u64,
+}
+
+#[contract]
+pub struct CostSplitContract;
+
+#[contractimpl]
+impl CostSplitContract {
+    /// Initialize with an admin and the token plans are paid in.
+    pub fn init(env: Env, admin: Address, token: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Plans ─────────────────────────────────────────────────────
+
+    /// Create a shared plan charging `price` every `frequency` seconds,
+    /// starting now. Members pay their share through a deposit or a token
+    /// allowance given to this contract.
+    pub fn create_plan(
+        env: Env,
+        organizer: Address,
+        merchant: Address,
+        price: i128,
+        frequency: u64,
+    ) -> Result<u64, Error> {
+        organizer.require_auth();
+        if price <= 0 || frequency == 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let plan_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PlanCounter)
+            .unwrap_or(0u64)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::PlanCounter, &plan_id);
+
+        let plan = Plan {
+            organizer: organizer.clone(),
+            merchant: merchant.clone(),
+            price,
+            frequency,
+            next_charge_at: env.ledger().timestamp(),
+            cycle: 0,
+            members: Vec::new(&env),
+            pool: 0,
+            active: true,
+        };
+        Self::save(&env, plan_id, &plan);
+
+        PlanCreated {
+            plan_id,
+            organizer,
+            merchant,
+            price,
+        }
+        .publish(&env);
+        Ok(plan_id)
+    }
+
+    /// Join a plan with `shares` of its cost.
+    pub fn join(env: Env, member: Address, plan_id: u64, shares: u32) -> Result<(), Error> {
+        member.require_auth();
+        let mut plan = Self::load_active(&env, plan_id)?;
+        if shares == 0 {
+            return Err(Error::InvalidShares);
+        }
+        if Self::index_of(&plan, &member).is_some() {
+            return Err(Error::AlreadyMember);
+        }
+        if plan.members.len() >= MAX_MEMBERS {
+            return Err(Error::TooManyMembers);
+        }
+
+        plan.members.push_back(Member {
+            address: member.clone(),
+            shares,
+            deposit: 0,
+            missed: 0,
+        });
+        Self::save(&env, plan_id, &plan);
+
+        MemberJoined {
+            plan_id,
+            member,
+            shares,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Leave a plan and get the unused deposit back.
+    pub fn leave(env: Env, member: Address, plan_id: u64) -> Result<i128, Error> {
+        member.require_auth();
+        let mut plan = Self::load(&env, plan_id)?;
+        let index = Self::index_of(&plan, &member).ok_or(Error::NotMember)?;
+
+        let refunded = plan.members.get_unchecked(index).deposit;
+        plan.members.remove(index);
+        Self::save(&env, plan_id, &plan);
+        if refunded > 0 {
+            Self::token(&env)?.transfer(&env.current_contract_address(), &member, &refunded);
+        }
+
+        MemberLeft {
+            plan_id,
+            member,
+            refunded,
+        }
+        .publish(&env);
+        Ok(refunded)
+    }
+
+    /// Organizer only. Refunds every member's deposit and returns the pool
+    /// to the organizer.
+    pub fn cancel_plan(env: Env, plan_id: u64) -> Result<(), Error> {
+        let mut plan = Self::load_active(&env, plan_id)?;
+        plan.organizer.require_auth();
+
+        let token = Self::token(&env)?;
+        let this = env.current_contract_address();
+        let mut members = Vec::new(&env);
+        for mut member in plan.members.iter() {
+            if member.deposit > 0 {
+                token.transfer(&this, &member.address, &member.deposit);
+                member.deposit = 0;
+            }
+            members.push_back(member);
+        }
+        if plan.pool > 0 {
+            token.transfer(&this, &plan.organizer, &plan.pool);
+            plan.pool = 0;
+        }
+        plan.members = members;
+        plan.active = false;
+        Self::save(&env, plan_id, &plan);
+
+        PlanCancelled { plan_id }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_plan(env: Env, plan_id: u64) -> Option<Plan> {
+        env.storage().persistent().get(&DataKey::Plan(plan_id))
+    }
+
+    // ── Funding ───────────────────────────────────────────────────
+
+    /// Pre-pay into the member's deposit.
+    pub fn deposit(env: Env, member: Address, plan_id: u64, amount: i128) -> Result<i128, Error> {
+        member.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut plan = Self::load_active(&env, plan_id)?;
+        let index = Self::index_of(&plan, &member).ok_or(Error::NotMember)?;
+
+        Self::token(&env)?.transfer(&member, env.current_contract_address(), &amount);
+        let mut entry = plan.members.get_unchecked(index);
+        entry.deposit += amount;
+        plan.members.set(index, entry.clone());
+        Self::save(&env, plan_id, &plan);
+
+        DepositChanged {
+            plan_id,
+            member,
+            deposit: entry.deposit,
+        }
+        .publish(&env);
+        Ok(entry.deposit)
+    }
+
+    pub fn withdraw(env: Env, member: Address, plan_id: u64, amount: i128) -> Result<i128, Error> {
+        member.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut plan = Self::load(&env, plan_id)?;
+        let index = Self::index_of(&plan, &member).ok_or(Error::NotMember)?;
+        let mut entry = plan.members.get_unchecked(index);
+        if amount > entry.deposit {
+            return Err(Error::InsufficientDeposit);
+        }
+
+        entry.deposit -= amount;
+        plan.members.set(index, entry.clone());
+        Self::save(&env, plan_id, &plan);
+        Self::token(&env)?.transfer(&env.current_contract_address(), &member, &amount);
+
+        DepositChanged {
+            plan_id,
+            member,
+            deposit: entry.deposit,
+        }
+        .publish(&env);
+        Ok(entry.deposit)
+    }
+
+    /// Add to the plan's shared pool. Pool funds cover members who miss a
+    /// payment and are returned to the organizer if the plan is cancelled.
+    pub fn fund_pool(env: Env, funder: Address, plan_id: u64, amount: i128) -> Result<i128, Error> {
+        funder.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut plan = Self::load_active(&env, plan_id)?;
+        Self::token(&env)?.transfer(&funder, env.current_contract_address(), &amount);
+        plan.pool += amount;
+        Self::save(&env, plan_id, &plan);
+
+        PoolFunded {
+            plan_id,
+            funder,
+            amount,
+            pool: plan.pool,
+        }
+        .publish(&env);
+        Ok(plan.pool)
+    }
+
+    // ── Charging ──────────────────────────────────────────────────
+
+    /// Collect each member's share (deposit first, then allowance) and pay
+    /// the merchant. Anyone may trigger a due charge.
+    ///
+    /// Members who cannot pay are recorded as missed and the pool covers
+    /// their share. If the pool cannot cover the shortfall, nothing is paid,
+    /// whatever was collected is credited to the members' deposits and the
+    /// charge can be retried.
+    pub fn charge(env: Env, plan_id: u64) -> Result<SplitCharge, Error> {
+        let mut plan = Self::load_active(&env, plan_id)?;
+        if plan.members.is_empty() {
+            return Err(Error::NoMembers);
+        }
+        if env.ledger().timestamp() < plan.next_charge_at {
+            return Err(Error::NotDue);
+        }
+        let token = Self::token(&env)?;
+        let this = env.current_contract_address();
+        let cycle = plan.cycle + 1;
+
+        let total_shares: i128 = plan.members.iter().map(|m| m.shares as i128).sum();
+        let last = plan.members.len() - 1;
+        let mut allotted = 0i128;
+        let mut shortfall = 0i128;
+        let mut missed = Vec::new(&env);
+        let mut members = Vec::new(&env);
+        let mut paid_by = Vec::new(&env);
+        for (i, mut member) in plan.members.iter().enumerate() {
+            // The last member absorbs rounding so the full price is split
+            let owed = if i as u32 == last {
+                plan.price - allotted
+            } else {
+                plan.price * member.shares as i128 / total_shares
+            };
+            allotted += owed;
+
+            let from_deposit = member.deposit.min(owed);
+            let rest = owed - from_deposit;
+            let pulled = rest == 0
+                || token
+                    .try_transfer_from(&this, &member.address, &this, &rest)
+                    .is_ok_and(|r| r.is_ok());
+            member.deposit -= from_deposit;
+            if pulled {
+                member.missed = 0;
+                paid_by.push_back(owed);
+            } else {
+                // Keep the deposit part; the member owes nothing extra
+                member.deposit += from_deposit;
+                member.missed += 1;
+                shortfall += owed;
+                missed.push_back(member.address.clone());
+                paid_by.push_back(0);
+                MemberMissed {
+                    plan_id,
+                    member: member.address.clone(),
+                    cycle,
+                    owed,
+                }
+                .publish(&env);
+            }
+            members.push_back(member);
+        }
+
+        if shortfall > plan.pool {
+            // Credit what was collected back to the members for the retry
+            let mut credited = Vec::new(&env);
+            for (mut member, paid) in members.iter().zip(paid_by.iter()) {
+                member.deposit += paid;
+                credited.push_back(member);
+            }
+            plan.members = credited;
+            Self::save(&env, plan_id, &plan);
+            PlanChargeFailed {
+                plan_id,
+                cycle,
+                shortfall,
+            }
+            .publish(&env);
+            return Ok(SplitCharge {
+                cycle,
+                paid: false,
+                shortfall,
+                from_pool: 0,
+                missed,
+            });
+        }
+
+        plan.pool -= shortfall;
+        token.transfer(&this, &plan.merchant, &plan.price);
+        for (member, paid) in members.iter().zip(paid_by.iter()) {
+            if paid > 0 {
+                Self::record(&env, plan_id, &member.address, cycle, paid);
+            }
+        }
+        plan.members = members;
+        plan.cycle = cycle;
+        plan.next_charge_at += plan.frequency;
+        Self::save(&env, plan_id, &plan);
+
+        PlanCharged {
+            plan_id,
+            cycle,
+            amount: plan.price,
+            from_pool: shortfall,
+        }
+        .publish(&env);
+        Ok(SplitCharge {
+            cycle,
+            paid: true,
+            shortfall,
+            from_pool: shortfall,
+            missed,
+        })
+    }
+
+    /// The member's most recent contributions, oldest first.
+    pub fn contributions(env: Env, plan_id: u64, member: Address) -> Vec<Contribution> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::History(plan_id, member))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn load(env: &Env, plan_id: u64) -> Result<Plan, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Plan(plan_id))
+            .ok_or(Error::PlanNotFound)
+    }
+
+    fn load_active(env: &Env, plan_id: u64) -> Result<Plan, Error> {
+        let plan = Self::load(env, plan_id)?;
+        if !plan.active {
+            return Err(Error::PlanInactive);
+        }
+        Ok(plan)
+    }
+
+    fn save(env: &Env, plan_id: u64, plan: &Plan) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Plan(plan_id), plan);
+    }
+
+    fn index_of(plan: &Plan, member: &Address) -> Option<u32> {
+        plan.members
+            .iter()
+            .position(|m| m.address == *member)
+            .map(|i| i as u32)
+    }
+
+    fn record(env: &Env, plan_id: u64, member: &Address, cycle: u64, amount: i128) {
+        let key = DataKey::History(plan_id, member.clone());
+        let mut history: Vec<Contribution> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if history.len() >= MAX_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(Contribution {
+            cycle,
+            amount,
+            ledger: env.ledger().sequence(),
+        });
+        env.storage().persistent().set(&key, &history);
+    }
+
+    fn token(env: &Env) -> Result<token::TokenClient<'_>, Error> {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::TokenClient::new(env, &token))
+    }
+}
+
+#[cfg(test)]
+mod test;
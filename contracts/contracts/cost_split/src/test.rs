@@ -0,0 +1,225 @@
+use super::*;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use syncro_testutils::TestToken;
+
+struct Setup {
+    env: Env,
+    client: CostSplitContractClient<'static>,
+    token: TestToken,
+    organizer: Address,
+    merchant: Address,
+    members: [Address; 3],
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let token = TestToken::new(&env);
+
+    let contract_id = env.register(CostSplitContract, ());
+    let client = CostSplitContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env), &token.address);
+
+    let organizer = Address::generate(&env);
+    let members = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    token.fund(&[&organizer, &members[0], &members[1], &members[2]], 10_000);
+    for member in members.iter() {
+        token.approve(member, &contract_id, &5_000, &1_000);
+    }
+    let merchant = Address::generate(&env);
+    Setup {
+        env,
+        client,
+        token,
+        organizer,
+        merchant,
+        members,
+    }
+}
+
+impl Setup {
+    /// A plan with every member joined at the given shares.
+    fn plan(&self, shares: [u32; 3], price: i128) -> u64 {
+        let id = self
+            .client
+            .create_plan(&self.organizer, &self.merchant, &price, &2_592_000);
+        for (member, shares) in self.members.iter().zip(shares) {
+            self.client.join(member, &id, &shares);
+        }
+        id
+    }
+
+    fn spent(&self) -> [i128; 3] {
+        [
+            10_000 - self.token.balance(&self.members[0]),
+            10_000 - self.token.balance(&self.members[1]),
+            10_000 - self.token.balance(&self.members[2]),
+        ]
+    }
+
+    fn advance(&self, seconds: u64) {
+        syncro_testutils::advance_time(&self.env, seconds);
+    }
+}
+
+#[test]
+fn test_charge_pulls_by_shares() {
+    let s = setup();
+    let id = s.plan([1, 1, 2], 1_000);
+
+    let charge = s.client.charge(&id);
+    assert!(charge.paid);
+    assert_eq!(charge.cycle, 1);
+    assert_eq!(charge.shortfall, 0);
+    assert_eq!(s.spent(), [250, 250, 500]);
+    assert_eq!(s.token.balance(&s.merchant), 1_000);
+
+    assert_eq!(s.client.try_charge(&id), Err(Ok(Error::NotDue)));
+    s.advance(2_592_000);
+    assert_eq!(s.client.charge(&id).cycle, 2);
+    assert_eq!(s.token.balance(&s.merchant), 2_000);
+}
+
+#[test]
+fn test_rounding_goes_to_last_member() {
+    let s = setup();
+    let id = s.plan([1, 1, 1], 100);
+    s.client.charge(&id);
+    assert_eq!(s.spent(), [33, 33, 34]);
+}
+
+#[test]
+fn test_deposit_used_before_allowance() {
+    let s = setup();
+    let id = s.plan([1, 1, 2], 1_000);
+    s.client.deposit(&s.members[0], &id, &100);
+
+    s.client.charge(&id);
+    // 100 from the deposit, the remaining 150 from the allowance
+    assert_eq!(s.spent(), [250, 250, 500]);
+    let plan = s.client.get_plan(&id).unwrap();
+    assert_eq!(plan.members.get(0).unwrap().deposit, 0);
+    assert_eq!(s.token.allowance(&s.members[0], &s.client.address), 4_850);
+}
+
+#[test]
+fn test_pool_covers_missed_member() {
+    let s = setup();
+    let id = s.plan([1, 1, 2], 1_000);
+    s.client.fund_pool(&s.organizer, &id, &300);
+    s.token
+        .approve(&s.members[1], &s.client.address, &0, &1_000);
+
+    let charge = s.client.charge(&id);
+    assert!(charge.paid);
+    assert_eq!(charge.shortfall, 250);
+    assert_eq!(charge.from_pool, 250);
+    assert_eq!(charge.missed, vec![&s.env, s.members[1].clone()]);
+    assert_eq!(s.spent(), [250, 0, 500]);
+    assert_eq!(s.token.balance(&s.merchant), 1_000);
+
+    let plan = s.client.get_plan(&id).unwrap();
+    assert_eq!(plan.pool, 50);
+    assert_eq!(plan.members.get(1).unwrap().missed, 1);
+    assert!(s.client.contributions(&id, &s.members[1]).is_empty());
+}
+
+#[test]
+fn test_uncovered_shortfall_credits_members_and_retries() {
+    let s = setup();
+    let id = s.plan([1, 1, 2], 1_000);
+    s.token
+        .approve(&s.members[2], &s.client.address, &0, &1_000);
+
+    let charge = s.client.charge(&id);
+    assert!(!charge.paid);
+    assert_eq!(charge.shortfall, 500);
+    assert_eq!(s.token.balance(&s.merchant), 0);
+
+    // What the paying members sent is held as their deposit
+    let plan = s.client.get_plan(&id).unwrap();
+    assert_eq!(plan.cycle, 0);
+    assert_eq!(plan.members.get(0).unwrap().deposit, 250);
+    assert_eq!(plan.members.get(1).unwrap().deposit, 250);
+    assert_eq!(plan.members.get(2).unwrap().missed, 1);
+
+    // The retry draws the deposits rather than charging them twice
+    s.token
+        .approve(&s.members[2], &s.client.address, &5_000, &1_000);
+    let charge = s.client.charge(&id);
+    assert!(charge.paid);
+    assert_eq!(charge.cycle, 1);
+    assert_eq!(s.spent(), [250, 250, 500]);
+    let plan = s.client.get_plan(&id).unwrap();
+    assert!(plan.members.iter().all(|m| m.deposit == 0 && m.missed == 0));
+}
+
+#[test]
+fn test_contribution_history() {
+    let s = setup();
+    let id = s.plan([1, 1, 2], 1_000);
+    s.client.charge(&id);
+    s.advance(2_592_000);
+    s.client.charge(&id);
+
+    let history = s.client.contributions(&id, &s.members[2]);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().cycle, 1);
+    assert_eq!(history.get(1).unwrap().cycle, 2);
+    assert!(history.iter().all(|c| c.amount == 500));
+}
+
+#[test]
+fn test_membership_rules() {
+    let s = setup();
+    let id = s.plan([1, 1, 2], 1_000);
+    assert_eq!(
+        s.client.try_join(&s.members[0], &id, &1),
+        Err(Ok(Error::AlreadyMember))
+    );
+    assert_eq!(
+        s.client.try_join(&Address::generate(&s.env), &id, &0),
+        Err(Ok(Error::InvalidShares))
+    );
+    assert_eq!(
+        s.client.try_withdraw(&s.members[0], &id, &1),
+        Err(Ok(Error::InsufficientDeposit))
+    );
+
+    s.client.deposit(&s.members[0], &id, &400);
+    assert_eq!(s.client.leave(&s.members[0], &id), 400);
+    assert_eq!(s.spent(), [0, 0, 0]);
+    assert_eq!(
+        s.client.try_deposit(&s.members[0], &id, &1),
+        Err(Ok(Error::NotMember))
+    );
+
+    // Remaining members split the full price
+    s.client.charge(&id);
+    assert_eq!(s.spent(), [0, 333, 667]);
+
+    let empty = s
+        .client
+        .create_plan(&s.organizer, &s.merchant, &100, &86_400);
+    assert_eq!(s.client.try_charge(&empty), Err(Ok(Error::NoMembers)));
+}
+
+#[test]
+fn test_cancel_refunds_deposits_and_pool() {
+    let s = setup();
+    let id = s.plan([1, 1, 2], 1_000);
+    s.client.deposit(&s.members[1], &id, &600);
+    s.client.fund_pool(&s.organizer, &id, &300);
+
+    s.client.cancel_plan(&id);
+    assert_eq!(s.spent(), [0, 0, 0]);
+    assert_eq!(s.token.balance(&s.organizer), 10_000);
+    assert_eq!(s.client.try_charge(&id), Err(Ok(Error::PlanInactive)));
+    assert_eq!(
+        s.client.try_join(&Address::generate(&s.env), &id, &1),
+        Err(Ok(Error::PlanInactive))
+    );
+}
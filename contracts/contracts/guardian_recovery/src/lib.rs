@@ -0,0 +1,426 @@
+#![no_std]
+//! M-of-N guardian recovery of subscription ownership.
+//!
+//! An owner names a set of guardians and how many of them must agree. If
+//! the owner loses their key, a guardian opens a recovery request naming the
+//! new owner and the renewal subscriptions to move, and other guardians
+//! approve it. Once the threshold is met a timelock runs, during which the
+//! owner can still cancel if the key wasn't lost after all. After that
+//! anyone can execute the request, which moves the subscriptions in the
+//! renewal contract and the owner's whole list in the registry.
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, Address,
+    BytesN, Env, Val, Vec,
+};
+use syncro_access::AccessError;
+use syncro_common::SubscriptionState;
+
+/// Most guardians one owner can name.
+pub const MAX_GUARDIANS: u32 = 10;
+/// Most renewal subscriptions one request can move.
+pub const MAX_SUBSCRIPTIONS: u32 = 25;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2601,
+    NotInitialized = 2602,
+    NoPendingAdmin = 2603,
+    Unauthorized = 2604,
+    InvalidGuardians = 2605,
+    InvalidThreshold = 2606,
+    NoGuardians = 2607,
+    NotGuardian = 2608,
+    RecoveryPending = 2609,
+    NoRecovery = 2610,
+    AlreadyApproved = 2611,
+    ThresholdNotMet = 2612,
+    TimelockActive = 2613,
+    InvalidNewOwner = 2614,
+    TooManySubscriptions = 2615,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Renewal,
+    Registry,
+    Timelock,
+    Guardians(Address), // owner -> GuardianSet
+    Recovery(Address),  // owner -> RecoveryRequest
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuardianSet {
+    pub guardians: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// A pending recovery of one owner's subscriptions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryRequest {
+    pub new_owner: Address,
+    /// Renewal subscriptions to move; the registry list moves as a whole.
+    pub sub_ids: Vec<u64>,
+    pub approvals: Vec<Address>,
+    /// Set once the threshold is met: when the request becomes executable.
+    pub ready_at: Option<u64>,
+}
+
+/// Mirror of the renewal contract's `SubscriptionData`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionData {
+    pub owner: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub integrity_hash: BytesN<32>,
+    pub state: SubscriptionState,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+/// The renewal contract, with this contract set as its recovery contract.
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn get_sub(env: Env, sub_id: u64) -> SubscriptionData;
+    fn recover_sub(env: Env, sub_id: u64, from: Address, new_owner: Address);
+}
+
+/// The subscription registry, with this contract set as its recovery
+/// contract.
+#[contractclient(name = "RegistryClient")]
+pub trait RegistryInterface {
+    fn recover_subscriptions(env: Env, from: Address, to: Address) -> u32;
+}
+
+#[contractevent]
+pub struct GuardiansSet {
+    pub owner: Address,
+    pub guardian_count: u32,
+    pub threshold: u32,
+}
+
+#[contractevent]
+pub struct RecoveryInitiated {
+    pub owner: Address,
+    pub new_owner: Address,
+    pub guardian: Address,
+}
+
+#[contractevent]
+pub struct RecoveryApproved {
+    pub owner: Address,
+    pub guardian: Address,
+    pub approvals: u32,
+}
+
+#[contractevent]
+pub struct RecoveryCancelled {
+    pub owner: Address,
+}
+
+#[contractevent]
+pub struct RecoveryExecuted {
+    pub owner: Address,
+    pub new_owner: Address,
+    pub renewal_subs: u32,
+    pub registry_subs: u32,
+}
+
+#[contract]
+pub struct GuardianRecoveryContract;
+
+#[contractimpl]
+impl GuardianRecoveryContract {
+    /// Initialize with an admin, the renewal contract and registry to
+    /// recover subscriptions in, and the seconds between a request reaching
+    /// its threshold and becoming executable.
+    pub fn init(
+        env: Env,
+        admin: Address,
+        renewal: Address,
+        registry: Address,
+        timelock: u64,
+    ) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Renewal, &renewal);
+        storage.set(&DataKey::Registry, &registry);
+        storage.set(&DataKey::Timelock, &timelock);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Configuration (admin only) ────────────────────────────────
+
+    /// Change the timelock. Requests already past their threshold keep
+    /// the time they were given.
+    pub fn set_timelock(env: Env, timelock: u64) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Timelock, &timelock);
+        Ok(())
+    }
+
+    pub fn get_timelock(env: Env) -> Result<u64, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Timelock)
+            .ok_or(Error::NotInitialized)
+    }
+
+    // ── Guardians ─────────────────────────────────────────────────
+
+    /// Name the guardians for `owner`'s subscriptions and how many must
+    /// approve a recovery. Replacing the set cancels any pending request.
+    pub fn set_guardians(
+        env: Env,
+        owner: Address,
+        guardians: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        if guardians.is_empty() || guardians.len() > MAX_GUARDIANS {
+            return Err(Error::InvalidGuardians);
+        }
+        for (i, guardian) in guardians.iter().enumerate() {
+            if guardian == owner || guardians.first_index_of(&guardian) != Some(i as u32) {
+                return Err(Error::InvalidGuardians);
+            }
+        }
+        if threshold == 0 || threshold > guardians.len() {
+            return Err(Error::InvalidThreshold);
+        }
+
+        Self::clear_request(&env, &owner);
+        let guardian_count = guardians.len();
+        env.storage().persistent().set(
+            &DataKey::Guardians(owner.clone()),
+            &GuardianSet {
+                guardians,
+                threshold,
+            },
+        );
+
+        GuardiansSet {
+            owner,
+            guardian_count,
+            threshold,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_guardians(env: Env, owner: Address) -> Option<GuardianSet> {
+        env.storage().persistent().get(&DataKey::Guardians(owner))
+    }
+
+    // ── Recovery ──────────────────────────────────────────────────
+
+    /// Open a request to move `sub_ids` (and `owner`'s registry list) to
+    /// `new_owner`. The initiating guardian's approval is counted.
+    pub fn initiate_recovery(
+        env: Env,
+        guardian: Address,
+        owner: Address,
+        new_owner: Address,
+        sub_ids: Vec<u64>,
+    ) -> Result<RecoveryRequest, Error> {
+        guardian.require_auth();
+        let set = Self::guardian_set(&env, &owner, &guardian)?;
+        if new_owner == owner {
+            return Err(Error::InvalidNewOwner);
+        }
+        if sub_ids.len() > MAX_SUBSCRIPTIONS {
+            return Err(Error::TooManySubscriptions);
+        }
+        let key = DataKey::Recovery(owner.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::RecoveryPending);
+        }
+
+        let mut request = RecoveryRequest {
+            new_owner: new_owner.clone(),
+            sub_ids,
+            approvals: Vec::from_array(&env, [guardian.clone()]),
+            ready_at: None,
+        };
+        Self::start_timelock_if_met(&env, &set, &mut request)?;
+        env.storage().persistent().set(&key, &request);
+
+        RecoveryInitiated {
+            owner,
+            new_owner,
+            guardian,
+        }
+        .publish(&env);
+        Ok(request)
+    }
+
+    /// Add `guardian`'s approval to the pending request. Reaching the
+    /// threshold starts the timelock.
+    pub fn approve_recovery(
+        env: Env,
+        guardian: Address,
+        owner: Address,
+    ) -> Result<RecoveryRequest, Error> {
+        guardian.require_auth();
+        let set = Self::guardian_set(&env, &owner, &guardian)?;
+        let key = DataKey::Recovery(owner.clone());
+        let mut request: RecoveryRequest = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NoRecovery)?;
+        if request.approvals.contains(&guardian) {
+            return Err(Error::AlreadyApproved);
+        }
+
+        request.approvals.push_back(guardian.clone());
+        Self::start_timelock_if_met(&env, &set, &mut request)?;
+        env.storage().persistent().set(&key, &request);
+
+        RecoveryApproved {
+            owner,
+            guardian,
+            approvals: request.approvals.len(),
+        }
+        .publish(&env);
+        Ok(request)
+    }
+
+    /// Drop the pending request. Owner only, so an owner who still holds
+    /// their key can stop a recovery they didn't ask for.
+    pub fn cancel_recovery(env: Env, owner: Address) -> Result<(), Error> {
+        owner.require_auth();
+        if !Self::clear_request(&env, &owner) {
+            return Err(Error::NoRecovery);
+        }
+        Ok(())
+    }
+
+    /// Carry out a request whose timelock has elapsed. Subscriptions that
+    /// no longer belong to `owner` are skipped. The guardian set moves to the
+    /// new owner unless they already have one. Returns how many renewal and
+    /// registry subscriptions moved.
+    pub fn execute_recovery(env: Env, owner: Address) -> Result<(u32, u32), Error> {
+        let key = DataKey::Recovery(owner.clone());
+        let request: RecoveryRequest = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NoRecovery)?;
+        let ready_at = request.ready_at.ok_or(Error::ThresholdNotMet)?;
+        if env.ledger().timestamp() < ready_at {
+            return Err(Error::TimelockActive);
+        }
+        env.storage().persistent().remove(&key);
+
+        let renewal = RenewalClient::new(&env, &Self::address(&env, DataKey::Renewal)?);
+        let mut renewal_subs = 0;
+        for sub_id in request.sub_ids.iter() {
+            if renewal.get_sub(&sub_id).owner == owner {
+                renewal.recover_sub(&sub_id, &owner, &request.new_owner);
+                renewal_subs += 1;
+            }
+        }
+        let registry_subs = RegistryClient::new(&env, &Self::address(&env, DataKey::Registry)?)
+            .recover_subscriptions(&owner, &request.new_owner);
+
+        let from_key = DataKey::Guardians(owner.clone());
+        let to_key = DataKey::Guardians(request.new_owner.clone());
+        if let Some(set) = env.storage().persistent().get::<_, GuardianSet>(&from_key) {
+            if !env.storage().persistent().has(&to_key)
+                && !set.guardians.contains(&request.new_owner)
+            {
+                env.storage().persistent().set(&to_key, &set);
+            }
+            env.storage().persistent().remove(&from_key);
+        }
+
+        RecoveryExecuted {
+            owner,
+            new_owner: request.new_owner,
+            renewal_subs,
+            registry_subs,
+        }
+        .publish(&env);
+        Ok((renewal_subs, registry_subs))
+    }
+
+    pub fn get_recovery(env: Env, owner: Address) -> Option<RecoveryRequest> {
+        env.storage().persistent().get(&DataKey::Recovery(owner))
+    }
+
+    fn guardian_set(env: &Env, owner: &Address, guardian: &Address) -> Result<GuardianSet, Error> {
+        let set: GuardianSet = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Guardians(owner.clone()))
+            .ok_or(Error::NoGuardians)?;
+        if !set.guardians.contains(guardian) {
+            return Err(Error::NotGuardian);
+        }
+        Ok(set)
+    }
+
+    fn start_timelock_if_met(
+        env: &Env,
+        set: &GuardianSet,
+        request: &mut RecoveryRequest,
+    ) -> Result<(), Error> {
+        if request.ready_at.is_none() && request.approvals.len() >= set.threshold {
+            let timelock = Self::get_timelock(env.clone())?;
+            request.ready_at = Some(env.ledger().timestamp().saturating_add(timelock));
+        }
+        Ok(())
+    }
+
+    /// Remove `owner`'s pending request, if any. Returns whether one existed.
+    fn clear_request(env: &Env, owner: &Address) -> bool {
+        let key = DataKey::Recovery(owner.clone());
+        if !env.storage().persistent().has(&key) {
+            return false;
+        }
+        env.storage().persistent().remove(&key);
+        RecoveryCancelled {
+            owner: owner.clone(),
+        }
+        .publish(env);
+        true
+    }
+
+    fn address(env: &Env, key: DataKey) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&key)
+            .ok_or(Error::NotInitialized)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,288 @@
+use super::*;
+use soroban_sdk::{
+    contract,
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env,
+};
+
+/// Stand-in for the renewal contract: moves owners on recovery.
+#[contract]
+struct MockRenewal;
+
+#[contractimpl]
+impl MockRenewal {
+    pub fn set_owner(env: Env, sub_id: u64, owner: Address) {
+        env.storage().instance().set(&sub_id, &owner);
+    }
+
+    pub fn get_sub(env: Env, sub_id: u64) -> SubscriptionData {
+        SubscriptionData {
+            owner: env.storage().instance().get(&sub_id).unwrap(),
+            merchant: env.current_contract_address(),
+            amount: 100,
+            frequency: 2_592_000,
+            spending_cap: 1_200,
+            integrity_hash: BytesN::from_array(&env, &[0; 32]),
+            state: SubscriptionState::Active,
+            failure_count: 0,
+            last_attempt_ledger: 0,
+        }
+    }
+
+    pub fn recover_sub(env: Env, sub_id: u64, from: Address, new_owner: Address) {
+        assert_eq!(Self::get_sub(env.clone(), sub_id).owner, from);
+        env.storage().instance().set(&sub_id, &new_owner);
+    }
+}
+
+/// Stand-in for the subscription registry: records the last recovery.
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn recover_subscriptions(env: Env, from: Address, to: Address) -> u32 {
+        env.storage().instance().set(&0u32, &(from, to));
+        3
+    }
+
+    pub fn last_recovery(env: Env) -> Option<(Address, Address)> {
+        env.storage().instance().get(&0u32)
+    }
+}
+
+const TIMELOCK: u64 = 86_400;
+
+struct Setup {
+    env: Env,
+    client: GuardianRecoveryContractClient<'static>,
+    renewal: MockRenewalClient<'static>,
+    registry: MockRegistryClient<'static>,
+    owner: Address,
+    new_owner: Address,
+    guardians: [Address; 3],
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_700_000_000);
+
+    let renewal = MockRenewalClient::new(&env, &env.register(MockRenewal, ()));
+    let registry = MockRegistryClient::new(&env, &env.register(MockRegistry, ()));
+    let client =
+        GuardianRecoveryContractClient::new(&env, &env.register(GuardianRecoveryContract, ()));
+    client.init(
+        &Address::generate(&env),
+        &renewal.address,
+        &registry.address,
+        &TIMELOCK,
+    );
+
+    let owner = Address::generate(&env);
+    let guardians = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    client.set_guardians(
+        &owner,
+        &vec![
+            &env,
+            guardians[0].clone(),
+            guardians[1].clone(),
+            guardians[2].clone(),
+        ],
+        &2,
+    );
+    renewal.set_owner(&1, &owner);
+    renewal.set_owner(&2, &owner);
+    Setup {
+        new_owner: Address::generate(&env),
+        env,
+        client,
+        renewal,
+        registry,
+        owner,
+        guardians,
+    }
+}
+
+impl Setup {
+    fn initiate(&self, guardian: &Address) -> Result<RecoveryRequest, Error> {
+        match self.client.try_initiate_recovery(
+            guardian,
+            &self.owner,
+            &self.new_owner,
+            &vec![&self.env, 1, 2],
+        ) {
+            Ok(request) => Ok(request.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+
+    fn approve(&self, guardian: &Address) -> Result<RecoveryRequest, Error> {
+        match self.client.try_approve_recovery(guardian, &self.owner) {
+            Ok(request) => Ok(request.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+
+    fn execute(&self) -> Result<(u32, u32), Error> {
+        match self.client.try_execute_recovery(&self.owner) {
+            Ok(moved) => Ok(moved.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+
+    fn pass_timelock(&self) {
+        self.env.ledger().with_mut(|li| li.timestamp += TIMELOCK);
+    }
+}
+
+#[test]
+fn test_guardians_recover_after_timelock() {
+    let s = setup();
+    let request = s.initiate(&s.guardians[0]).unwrap();
+    assert_eq!(request.ready_at, None);
+    assert_eq!(s.execute(), Err(Error::ThresholdNotMet));
+
+    let request = s.approve(&s.guardians[2]).unwrap();
+    assert_eq!(request.ready_at, Some(1_700_000_000 + TIMELOCK));
+    assert_eq!(s.execute(), Err(Error::TimelockActive));
+
+    s.pass_timelock();
+    assert_eq!(s.execute(), Ok((2, 3)));
+    assert_eq!(s.renewal.get_sub(&1).owner, s.new_owner);
+    assert_eq!(s.renewal.get_sub(&2).owner, s.new_owner);
+    assert_eq!(
+        s.registry.last_recovery(),
+        Some((s.owner.clone(), s.new_owner.clone()))
+    );
+    assert_eq!(s.client.get_recovery(&s.owner), None);
+
+    // Guardians now protect the new owner
+    assert_eq!(s.client.get_guardians(&s.owner), None);
+    assert_eq!(s.client.get_guardians(&s.new_owner).unwrap().threshold, 2);
+}
+
+#[test]
+fn test_subscriptions_no_longer_owned_are_skipped() {
+    let s = setup();
+    s.initiate(&s.guardians[0]).unwrap();
+    s.approve(&s.guardians[1]).unwrap();
+    let buyer = Address::generate(&s.env);
+    s.renewal.set_owner(&2, &buyer);
+
+    s.pass_timelock();
+    assert_eq!(s.execute(), Ok((1, 3)));
+    assert_eq!(s.renewal.get_sub(&2).owner, buyer);
+}
+
+#[test]
+fn test_only_guardians_approve_once() {
+    let s = setup();
+    let stranger = Address::generate(&s.env);
+    assert_eq!(s.initiate(&stranger), Err(Error::NotGuardian));
+    assert_eq!(s.approve(&s.guardians[0]), Err(Error::NoRecovery));
+
+    s.initiate(&s.guardians[0]).unwrap();
+    assert_eq!(s.initiate(&s.guardians[1]), Err(Error::RecoveryPending));
+    assert_eq!(s.approve(&s.guardians[0]), Err(Error::AlreadyApproved));
+    assert_eq!(s.approve(&stranger), Err(Error::NotGuardian));
+}
+
+#[test]
+fn test_owner_cancels_during_timelock() {
+    let s = setup();
+    s.initiate(&s.guardians[0]).unwrap();
+    s.approve(&s.guardians[1]).unwrap();
+    s.client.cancel_recovery(&s.owner);
+
+    s.pass_timelock();
+    assert_eq!(s.execute(), Err(Error::NoRecovery));
+    assert_eq!(s.renewal.get_sub(&1).owner, s.owner);
+    assert_eq!(
+        s.client.try_cancel_recovery(&s.owner).unwrap_err().unwrap(),
+        Error::NoRecovery
+    );
+}
+
+#[test]
+fn test_replacing_guardians_cancels_pending_recovery() {
+    let s = setup();
+    s.initiate(&s.guardians[0]).unwrap();
+    s.client
+        .set_guardians(&s.owner, &vec![&s.env, s.guardians[0].clone()], &1);
+    assert_eq!(s.client.get_recovery(&s.owner), None);
+
+    // A single guardian meets a threshold of one on initiation
+    let request = s.initiate(&s.guardians[0]).unwrap();
+    assert!(request.ready_at.is_some());
+    assert_eq!(s.initiate(&s.guardians[1]), Err(Error::NotGuardian));
+}
+
+#[test]
+fn test_guardian_set_validation() {
+    let s = setup();
+    let g = &s.guardians;
+    let try_set = |guardians: Vec<Address>, threshold: u32| -> Error {
+        s.client
+            .try_set_guardians(&s.owner, &guardians, &threshold)
+            .unwrap_err()
+            .unwrap()
+    };
+    assert_eq!(try_set(vec![&s.env], 1), Error::InvalidGuardians);
+    assert_eq!(
+        try_set(vec![&s.env, g[0].clone(), g[0].clone()], 1),
+        Error::InvalidGuardians
+    );
+    assert_eq!(
+        try_set(vec![&s.env, g[0].clone(), s.owner.clone()], 1),
+        Error::InvalidGuardians
+    );
+    assert_eq!(
+        try_set(vec![&s.env, g[0].clone(), g[1].clone()], 3),
+        Error::InvalidThreshold
+    );
+    assert_eq!(
+        try_set(vec![&s.env, g[0].clone()], 0),
+        Error::InvalidThreshold
+    );
+}
+
+#[test]
+fn test_recovery_requests_are_bounded() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_initiate_recovery(&s.guardians[0], &s.owner, &s.owner, &vec![&s.env, 1])
+            .unwrap_err()
+            .unwrap(),
+        Error::InvalidNewOwner
+    );
+
+    let mut sub_ids = vec![&s.env];
+    for id in 0..=MAX_SUBSCRIPTIONS as u64 {
+        sub_ids.push_back(id);
+    }
+    assert_eq!(
+        s.client
+            .try_initiate_recovery(&s.guardians[0], &s.owner, &s.new_owner, &sub_ids)
+            .unwrap_err()
+            .unwrap(),
+        Error::TooManySubscriptions
+    );
+    assert_eq!(
+        s.client
+            .try_initiate_recovery(
+                &s.guardians[0],
+                &Address::generate(&s.env),
+                &s.new_owner,
+                &vec![&s.env]
+            )
+            .unwrap_err()
+            .unwrap(),
+        Error::NoGuardians
+    );
+}
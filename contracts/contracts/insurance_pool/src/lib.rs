@@ -0,0 +1,509 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, token,
+    Address, BytesN, Env, Val, Vec,
+};
+use syncro_common::SubscriptionState;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1601,
+    NotInitialized = 1602,
+    InvalidAmount = 1603,
+    NotSubscriber = 1604,
+    MerchantNotSuspended = 1605,
+    NothingToClaim = 1606,
+    AlreadyClaimed = 1607,
+    ClaimNotFound = 1608,
+    InvalidStatus = 1609,
+    InsufficientFunds = 1610,
+    StakeLocked = 1611,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Token,
+    Renewal,
+    ClaimCounter,
+    Reserve,
+    Stake(Address),        // merchant -> MerchantStake
+    Claim(u64),            // claim id -> Claim
+    PeriodClaim(u64, u64), // (sub_id, last_renewed_at) -> claim id
+}
+
+/// Mirror of the renewal contract's `SubscriptionData`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionData {
+    pub owner: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub integrity_hash: BytesN<32>,
+    pub state: SubscriptionState,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+/// Mirror of the renewal contract's `LifecycleTimestamps`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LifecycleTimestamps {
+    pub created_at: u64,
+    pub activated_at: u64,
+    pub last_renewed_at: u64,
+    pub canceled_at: u64,
+    pub paused_at: u64,
+    pub resumed_at: u64,
+}
+
+/// Subscription state the pool verifies claims against.
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn get_sub(env: Env, sub_id: u64) -> SubscriptionData;
+    fn get_lifecycle(env: Env, sub_id: u64) -> LifecycleTimestamps;
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MerchantStake {
+    pub balance: i128,
+    /// Claims against the merchant not yet paid or rejected.
+    pub open_claims: u32,
+    /// When the merchant was suspended; 0 while in good standing.
+    pub suspended_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ClaimReason {
+    /// The merchant was suspended during a paid period. Verified on
+    /// submission and refunded pro rata.
+    Suspended = 1,
+    /// The merchant did not deliver. Needs the admin to verify it.
+    NonDelivery = 2,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ClaimStatus {
+    Pending = 1,
+    Approved = 2,
+    Rejected = 3,
+    Paid = 4,
+}
+
+/// A subscriber's claim over the last period they paid for.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Claim {
+    pub id: u64,
+    pub sub_id: u64,
+    pub subscriber: Address,
+    pub merchant: Address,
+    pub reason: ClaimReason,
+    pub amount: i128,
+    pub status: ClaimStatus,
+}
+
+#[contractevent]
+pub struct StakeDeposited {
+    pub merchant: Address,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+#[contractevent]
+pub struct StakeWithdrawn {
+    pub merchant: Address,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+#[contractevent]
+pub struct ReserveFunded {
+    pub funder: Address,
+    pub amount: i128,
+    pub reserve: i128,
+}
+
+#[contractevent]
+pub struct MerchantSuspended {
+    pub merchant: Address,
+    pub suspended_at: u64,
+}
+
+#[contractevent]
+pub struct MerchantReinstated {
+    pub merchant: Address,
+}
+
+#[contractevent]
+pub struct ClaimSubmitted {
+    pub id: u64,
+    pub sub_id: u64,
+    pub subscriber: Address,
+    pub merchant: Address,
+    pub reason: ClaimReason,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct ClaimVerified {
+    pub id: u64,
+    pub approved: bool,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct ClaimPaid {
+    pub id: u64,
+    pub from_stake: i128,
+    pub from_reserve: i128,
+}
+
+#[contract]
+pub struct InsurancePoolContract;
+
+#[contractimpl]
+impl InsurancePoolContract {
+    /// Initialize with an admin, the payout token and the renewal contract
+    /// claims are verified against.
+    pub fn init(env: Env, admin: Address, token: Address, renewal: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::Renewal, &renewal);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Funding ───────────────────────────────────────────────────
+
+    /// Add to the merchant's guarantee stake. Claims against the merchant
+    /// are paid from it before the shared reserve.
+    pub fn deposit_stake(env: Env, merchant: Address, amount: i128) -> Result<i128, Error> {
+        merchant.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::token(&env)?.transfer(&merchant, env.current_contract_address(), &amount);
+        let mut stake = Self::get_stake(env.clone(), merchant.clone());
+        stake.balance += amount;
+        Self::save_stake(&env, &merchant, &stake);
+
+        StakeDeposited {
+            merchant,
+            amount,
+            balance: stake.balance,
+        }
+        .publish(&env);
+        Ok(stake.balance)
+    }
+
+    /// Withdraw stake. Locked while the merchant is suspended or has open
+    /// claims.
+    pub fn withdraw_stake(env: Env, merchant: Address, amount: i128) -> Result<i128, Error> {
+        merchant.require_auth();
+        let mut stake = Self::get_stake(env.clone(), merchant.clone());
+        if stake.suspended_at != 0 || stake.open_claims > 0 {
+            return Err(Error::StakeLocked);
+        }
+        if amount <= 0 || amount > stake.balance {
+            return Err(Error::InvalidAmount);
+        }
+        stake.balance -= amount;
+        Self::save_stake(&env, &merchant, &stake);
+        Self::token(&env)?.transfer(&env.current_contract_address(), &merchant, &amount);
+
+        StakeWithdrawn {
+            merchant,
+            amount,
+            balance: stake.balance,
+        }
+        .publish(&env);
+        Ok(stake.balance)
+    }
+
+    /// Add to the shared reserve, e.g. from protocol fees.
+    pub fn fund_reserve(env: Env, funder: Address, amount: i128) -> Result<i128, Error> {
+        funder.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::token(&env)?.transfer(&funder, env.current_contract_address(), &amount);
+        let reserve = Self::reserve(env.clone()) + amount;
+        env.storage().instance().set(&DataKey::Reserve, &reserve);
+
+        ReserveFunded {
+            funder,
+            amount,
+            reserve,
+        }
+        .publish(&env);
+        Ok(reserve)
+    }
+
+    pub fn get_stake(env: Env, merchant: Address) -> MerchantStake {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Stake(merchant))
+            .unwrap_or_default()
+    }
+
+    pub fn reserve(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::Reserve).unwrap_or(0)
+    }
+
+    // ── Merchant standing (admin only) ────────────────────────────
+
+    /// Suspend a merchant. Subscribers with a paid period running past this
+    /// point can claim the unused part.
+    pub fn suspend_merchant(env: Env, merchant: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut stake = Self::get_stake(env.clone(), merchant.clone());
+        if stake.suspended_at == 0 {
+            stake.suspended_at = env.ledger().timestamp();
+            Self::save_stake(&env, &merchant, &stake);
+            MerchantSuspended {
+                merchant,
+                suspended_at: stake.suspended_at,
+            }
+            .publish(&env);
+        }
+        Ok(())
+    }
+
+    pub fn reinstate_merchant(env: Env, merchant: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut stake = Self::get_stake(env.clone(), merchant.clone());
+        if stake.suspended_at == 0 {
+            return Err(Error::MerchantNotSuspended);
+        }
+        stake.suspended_at = 0;
+        Self::save_stake(&env, &merchant, &stake);
+        MerchantReinstated { merchant }.publish(&env);
+        Ok(())
+    }
+
+    // ── Claims ────────────────────────────────────────────────────
+
+    /// Claim compensation for the last period paid on `sub_id`. The
+    /// subscription's owner, merchant and last renewal are read from the
+    /// renewal contract; one claim is allowed per paid period.
+    ///
+    /// `Suspended` claims are approved immediately for the share of the
+    /// period left when the merchant was suspended. `NonDelivery` claims ask
+    /// for the full period and wait for `verify_claim`.
+    pub fn submit_claim(
+        env: Env,
+        subscriber: Address,
+        sub_id: u64,
+        reason: ClaimReason,
+    ) -> Result<u64, Error> {
+        subscriber.require_auth();
+        let renewal = RenewalClient::new(&env, &Self::renewal(&env)?);
+        let sub = renewal.get_sub(&sub_id);
+        if sub.owner != subscriber {
+            return Err(Error::NotSubscriber);
+        }
+        let paid_at = renewal.get_lifecycle(&sub_id).last_renewed_at;
+        if paid_at == 0 || sub.amount <= 0 {
+            return Err(Error::NothingToClaim);
+        }
+        let period_key = DataKey::PeriodClaim(sub_id, paid_at);
+        if env.storage().persistent().has(&period_key) {
+            return Err(Error::AlreadyClaimed);
+        }
+
+        let mut stake = Self::get_stake(env.clone(), sub.merchant.clone());
+        let (amount, status) = match reason {
+            ClaimReason::Suspended => {
+                if stake.suspended_at == 0 {
+                    return Err(Error::MerchantNotSuspended);
+                }
+                let period_end = paid_at.saturating_add(sub.frequency);
+                let unused = period_end
+                    .saturating_sub(stake.suspended_at.max(paid_at))
+                    .min(sub.frequency);
+                let amount = sub.amount * unused as i128 / sub.frequency.max(1) as i128;
+                if amount == 0 {
+                    return Err(Error::NothingToClaim);
+                }
+                (amount, ClaimStatus::Approved)
+            }
+            ClaimReason::NonDelivery => (sub.amount, ClaimStatus::Pending),
+        };
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ClaimCounter)
+            .unwrap_or(0u64)
+            + 1;
+        env.storage().instance().set(&DataKey::ClaimCounter, &id);
+        env.storage().persistent().set(&period_key, &id);
+        let claim = Claim {
+            id,
+            sub_id,
+            subscriber: subscriber.clone(),
+            merchant: sub.merchant.clone(),
+            reason,
+            amount,
+            status,
+        };
+        env.storage().persistent().set(&DataKey::Claim(id), &claim);
+        stake.open_claims += 1;
+        Self::save_stake(&env, &sub.merchant, &stake);
+
+        ClaimSubmitted {
+            id,
+            sub_id,
+            subscriber,
+            merchant: sub.merchant,
+            reason,
+            amount,
+        }
+        .publish(&env);
+        Ok(id)
+    }
+
+    /// Approve or reject a pending claim. Admin only. An approval may lower
+    /// the amount paid but never raise it.
+    pub fn verify_claim(env: Env, id: u64, approved: bool, amount: i128) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        let mut claim = Self::load_claim(&env, id)?;
+        if claim.status != ClaimStatus::Pending {
+            return Err(Error::InvalidStatus);
+        }
+
+        if approved {
+            if amount <= 0 || amount > claim.amount {
+                return Err(Error::InvalidAmount);
+            }
+            claim.amount = amount;
+            claim.status = ClaimStatus::Approved;
+        } else {
+            claim.status = ClaimStatus::Rejected;
+            Self::close_claim(&env, &claim.merchant);
+        }
+        env.storage().persistent().set(&DataKey::Claim(id), &claim);
+
+        ClaimVerified {
+            id,
+            approved,
+            amount: claim.amount,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Pay an approved claim to the subscriber, from the merchant's stake
+    /// first and the shared reserve after. Anyone may trigger it.
+    pub fn payout(env: Env, id: u64) -> Result<i128, Error> {
+        let mut claim = Self::load_claim(&env, id)?;
+        if claim.status != ClaimStatus::Approved {
+            return Err(Error::InvalidStatus);
+        }
+        let mut stake = Self::get_stake(env.clone(), claim.merchant.clone());
+        let reserve = Self::reserve(env.clone());
+        let from_stake = stake.balance.min(claim.amount);
+        let from_reserve = claim.amount - from_stake;
+        if from_reserve > reserve {
+            return Err(Error::InsufficientFunds);
+        }
+
+        stake.balance -= from_stake;
+        stake.open_claims = stake.open_claims.saturating_sub(1);
+        Self::save_stake(&env, &claim.merchant, &stake);
+        env.storage()
+            .instance()
+            .set(&DataKey::Reserve, &(reserve - from_reserve));
+        claim.status = ClaimStatus::Paid;
+        env.storage().persistent().set(&DataKey::Claim(id), &claim);
+        Self::token(&env)?.transfer(
+            &env.current_contract_address(),
+            &claim.subscriber,
+            &claim.amount,
+        );
+
+        ClaimPaid {
+            id,
+            from_stake,
+            from_reserve,
+        }
+        .publish(&env);
+        Ok(claim.amount)
+    }
+
+    pub fn get_claim(env: Env, id: u64) -> Option<Claim> {
+        env.storage().persistent().get(&DataKey::Claim(id))
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn renewal(env: &Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Renewal)
+            .ok_or(Error::NotInitialized)
+    }
+
+    fn load_claim(env: &Env, id: u64) -> Result<Claim, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Claim(id))
+            .ok_or(Error::ClaimNotFound)
+    }
+
+    fn save_stake(env: &Env, merchant: &Address, stake: &MerchantStake) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Stake(merchant.clone()), stake);
+    }
+
+    fn close_claim(env: &Env, merchant: &Address) {
+        let mut stake = Self::get_stake(env.clone(), merchant.clone());
+        stake.open_claims = stake.open_claims.saturating_sub(1);
+        Self::save_stake(env, merchant, &stake);
+    }
+
+    fn token(env: &Env) -> Result<token::TokenClient<'_>, Error> {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::TokenClient::new(env, &token))
+    }
+}
+
+#[cfg(test)]
+mod test;
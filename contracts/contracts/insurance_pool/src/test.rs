@@ -0,0 +1,249 @@
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, Address, BytesN, Env};
+use syncro_testutils::{TestToken, START};
+
+const MONTH: u64 = 2_592_000;
+
+/// Stand-in for the renewal contract: serves whatever `set_sub` stored.
+#[contract]
+struct MockRenewal;
+
+#[contractimpl]
+impl MockRenewal {
+    pub fn set_sub(env: Env, sub_id: u64, data: SubscriptionData, last_renewed_at: u64) {
+        env.storage().instance().set(&sub_id, &data);
+        env.storage()
+            .instance()
+            .set(&(sub_id, 0u32), &last_renewed_at);
+    }
+
+    pub fn get_sub(env: Env, sub_id: u64) -> SubscriptionData {
+        env.storage().instance().get(&sub_id).unwrap()
+    }
+
+    pub fn get_lifecycle(env: Env, sub_id: u64) -> LifecycleTimestamps {
+        LifecycleTimestamps {
+            created_at: 0,
+            activated_at: 0,
+            last_renewed_at: env.storage().instance().get(&(sub_id, 0u32)).unwrap(),
+            canceled_at: 0,
+            paused_at: 0,
+            resumed_at: 0,
+        }
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: InsurancePoolContractClient<'static>,
+    renewal: MockRenewalClient<'static>,
+    token: TestToken,
+    merchant: Address,
+    subscriber: Address,
+    funder: Address,
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let token = TestToken::new(&env);
+
+    let renewal = MockRenewalClient::new(&env, &env.register(MockRenewal, ()));
+    let contract_id = env.register(InsurancePoolContract, ());
+    let client = InsurancePoolContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env), &token.address, &renewal.address);
+
+    let merchant = Address::generate(&env);
+    let subscriber = Address::generate(&env);
+    let funder = Address::generate(&env);
+    token.fund(&[&merchant, &funder], 10_000);
+    Setup {
+        env,
+        client,
+        renewal,
+        token,
+        merchant,
+        subscriber,
+        funder,
+    }
+}
+
+impl Setup {
+    /// A monthly subscription of 900 last renewed at `paid_at`.
+    fn sub(&self, sub_id: u64, paid_at: u64) {
+        let data = SubscriptionData {
+            owner: self.subscriber.clone(),
+            merchant: self.merchant.clone(),
+            amount: 900,
+            frequency: MONTH,
+            spending_cap: 10_000,
+            integrity_hash: BytesN::from_array(&self.env, &[0; 32]),
+            state: SubscriptionState::Active,
+            failure_count: 0,
+            last_attempt_ledger: 0,
+        };
+        self.renewal.set_sub(&sub_id, &data, &paid_at);
+    }
+
+    fn advance(&self, seconds: u64) {
+        syncro_testutils::advance_time(&self.env, seconds);
+    }
+}
+
+#[test]
+fn test_suspension_claim_refunds_unused_period() {
+    let s = setup();
+    s.sub(1, START);
+    s.client.deposit_stake(&s.merchant, &1_000);
+
+    // Suspended two thirds into the period
+    s.advance(MONTH * 2 / 3);
+    s.client.suspend_merchant(&s.merchant);
+    let id = s
+        .client
+        .submit_claim(&s.subscriber, &1, &ClaimReason::Suspended);
+
+    let claim = s.client.get_claim(&id).unwrap();
+    assert_eq!(claim.status, ClaimStatus::Approved);
+    assert_eq!(claim.amount, 300);
+
+    assert_eq!(s.client.payout(&id), 300);
+    assert_eq!(s.token.balance(&s.subscriber), 300);
+    assert_eq!(s.client.get_stake(&s.merchant).balance, 700);
+    assert_eq!(s.client.get_claim(&id).unwrap().status, ClaimStatus::Paid);
+    assert_eq!(s.client.try_payout(&id), Err(Ok(Error::InvalidStatus)));
+}
+
+#[test]
+fn test_claim_verified_against_renewal_state() {
+    let s = setup();
+    s.client.suspend_merchant(&s.merchant);
+
+    // Never renewed, so nothing was paid
+    s.sub(1, 0);
+    assert_eq!(
+        s.client
+            .try_submit_claim(&s.subscriber, &1, &ClaimReason::Suspended),
+        Err(Ok(Error::NothingToClaim))
+    );
+
+    // Paid period ended before the suspension
+    s.advance(MONTH * 2);
+    s.sub(2, START - MONTH);
+    assert_eq!(
+        s.client
+            .try_submit_claim(&s.subscriber, &2, &ClaimReason::Suspended),
+        Err(Ok(Error::NothingToClaim))
+    );
+
+    s.sub(3, START + MONTH * 2);
+    assert_eq!(
+        s.client
+            .try_submit_claim(&Address::generate(&s.env), &3, &ClaimReason::Suspended),
+        Err(Ok(Error::NotSubscriber))
+    );
+}
+
+#[test]
+fn test_suspension_claim_requires_suspended_merchant() {
+    let s = setup();
+    s.sub(1, START);
+    assert_eq!(
+        s.client
+            .try_submit_claim(&s.subscriber, &1, &ClaimReason::Suspended),
+        Err(Ok(Error::MerchantNotSuspended))
+    );
+
+    s.client.suspend_merchant(&s.merchant);
+    s.client.reinstate_merchant(&s.merchant);
+    assert_eq!(
+        s.client.try_reinstate_merchant(&s.merchant),
+        Err(Ok(Error::MerchantNotSuspended))
+    );
+}
+
+#[test]
+fn test_one_claim_per_paid_period() {
+    let s = setup();
+    s.sub(1, START);
+    s.client
+        .submit_claim(&s.subscriber, &1, &ClaimReason::NonDelivery);
+    assert_eq!(
+        s.client
+            .try_submit_claim(&s.subscriber, &1, &ClaimReason::NonDelivery),
+        Err(Ok(Error::AlreadyClaimed))
+    );
+
+    // The next renewal opens a new period
+    s.sub(1, START + MONTH);
+    s.client
+        .submit_claim(&s.subscriber, &1, &ClaimReason::NonDelivery);
+}
+
+#[test]
+fn test_non_delivery_claim_needs_verification() {
+    let s = setup();
+    s.sub(1, START);
+    s.client.fund_reserve(&s.funder, &1_000);
+    let id = s
+        .client
+        .submit_claim(&s.subscriber, &1, &ClaimReason::NonDelivery);
+    assert_eq!(s.client.get_claim(&id).unwrap().amount, 900);
+    assert_eq!(s.client.try_payout(&id), Err(Ok(Error::InvalidStatus)));
+
+    assert_eq!(
+        s.client.try_verify_claim(&id, &true, &901),
+        Err(Ok(Error::InvalidAmount))
+    );
+    s.client.verify_claim(&id, &true, &600);
+    assert_eq!(
+        s.client.try_verify_claim(&id, &false, &0),
+        Err(Ok(Error::InvalidStatus))
+    );
+
+    // No stake, so the reserve pays
+    assert_eq!(s.client.payout(&id), 600);
+    assert_eq!(s.client.reserve(), 400);
+    assert_eq!(s.token.balance(&s.subscriber), 600);
+}
+
+#[test]
+fn test_payout_splits_stake_and_reserve() {
+    let s = setup();
+    s.sub(1, START);
+    s.client.deposit_stake(&s.merchant, &200);
+    s.client.fund_reserve(&s.funder, &500);
+    let id = s
+        .client
+        .submit_claim(&s.subscriber, &1, &ClaimReason::NonDelivery);
+    s.client.verify_claim(&id, &true, &900);
+
+    assert_eq!(s.client.try_payout(&id), Err(Ok(Error::InsufficientFunds)));
+    s.client.fund_reserve(&s.funder, &200);
+    s.client.payout(&id);
+    assert_eq!(s.client.get_stake(&s.merchant).balance, 0);
+    assert_eq!(s.client.reserve(), 0);
+    assert_eq!(s.token.balance(&s.subscriber), 900);
+}
+
+#[test]
+fn test_stake_locked_while_exposed() {
+    let s = setup();
+    s.sub(1, START);
+    s.client.deposit_stake(&s.merchant, &1_000);
+    let id = s
+        .client
+        .submit_claim(&s.subscriber, &1, &ClaimReason::NonDelivery);
+    assert_eq!(
+        s.client.try_withdraw_stake(&s.merchant, &100),
+        Err(Ok(Error::StakeLocked))
+    );
+
+    s.client.verify_claim(&id, &false, &0);
+    assert_eq!(s.client.withdraw_stake(&s.merchant, &100), 900);
+
+    s.client.suspend_merchant(&s.merchant);
+    assert_eq!(
+        s.client.try_withdraw_stake(&s.merchant, &100),
+        Err(Ok(Error::StakeLocked))
+    );
+}
@@ -0,0 +1,352 @@
+#![no_std]
+// `execute_renewal` forwards every argument of the renewal contract's `renew`,
+// and the generated client and args builders cannot be annotated one by one.
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, token,
+    Address, Env, Val, Vec,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 701,
+    NotInitialized = 702,
+    Unauthorized = 703,
+    InvalidAmount = 704,
+    BountyNotFound = 705,
+    BountyOwnedByOther = 706,
+    AlreadyClaimed = 707,
+    ReservedByOther = 708,
+    InsufficientBalance = 709,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    RenewalContract,
+    Token,
+    Bounty(u64),       // sub_id -> Bounty
+    Reservation(u64),  // sub_id -> Reservation
+    Claimed(u64, u64), // (sub_id, cycle_id) -> keeper
+}
+
+/// Reward pool funded for one subscription's renewals.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bounty {
+    pub funder: Address,
+    pub reward_per_cycle: i128,
+    pub balance: i128,
+}
+
+/// Exclusive execution window held by one keeper for one cycle.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reservation {
+    pub keeper: Address,
+    pub cycle_id: u64,
+    pub expires_at: u32,
+}
+
+/// Subset of the renewal contract used by keepers executing through the
+/// bounty contract.
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn acquire_renewal_lock(env: Env, agent: Address, sub_id: u64, lock_timeout: u32);
+    fn renew(env: Env, sub_id: u64, approval_id: u64, amount: i128, cycle_id: u64) -> bool;
+}
+
+#[contractevent]
+pub struct BountyFunded {
+    pub sub_id: u64,
+    pub funder: Address,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+#[contractevent]
+pub struct BountyWithdrawn {
+    pub sub_id: u64,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+#[contractevent]
+pub struct CycleReserved {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub keeper: Address,
+    pub expires_at: u32,
+}
+
+#[contractevent]
+pub struct BountyClaimed {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub keeper: Address,
+    pub reward: i128,
+}
+
+#[contract]
+pub struct KeeperBountyContract;
+
+#[contractimpl]
+impl KeeperBountyContract {
+    /// Initialize with an admin, the renewal contract keepers execute
+    /// against, and the token bounties are paid in.
+    pub fn init(
+        env: Env,
+        admin: Address,
+        renewal_contract: Address,
+        token: Address,
+    ) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::RenewalContract, &renewal_contract);
+        env.storage().instance().set(&DataKey::Token, &token);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Funding ───────────────────────────────────────────────────
+
+    /// Fund (or top up) the bounty for a subscription. The first funder owns
+    /// the pool and sets the per-cycle reward; later top-ups must come from
+    /// the same funder.
+    pub fn fund(
+        env: Env,
+        funder: Address,
+        sub_id: u64,
+        reward_per_cycle: i128,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        funder.require_auth();
+        if amount <= 0 || reward_per_cycle <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let key = DataKey::Bounty(sub_id);
+        let mut bounty = match env.storage().persistent().get::<_, Bounty>(&key) {
+            Some(existing) => {
+                if existing.funder != funder {
+                    return Err(Error::BountyOwnedByOther);
+                }
+                existing
+            }
+            None => Bounty {
+                funder: funder.clone(),
+                reward_per_cycle,
+                balance: 0,
+            },
+        };
+        bounty.reward_per_cycle = reward_per_cycle;
+
+        Self::token(&env)?.transfer(&funder, env.current_contract_address(), &amount);
+        bounty.balance = bounty
+            .balance
+            .checked_add(amount)
+            .ok_or(Error::InvalidAmount)?;
+        env.storage().persistent().set(&key, &bounty);
+
+        BountyFunded {
+            sub_id,
+            funder,
+            amount,
+            balance: bounty.balance,
+        }
+        .publish(&env);
+        Ok(bounty.balance)
+    }
+
+    /// Return unclaimed funds to the funder.
+    pub fn withdraw(env: Env, sub_id: u64, amount: i128) -> Result<i128, Error> {
+        let key = DataKey::Bounty(sub_id);
+        let mut bounty: Bounty = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::BountyNotFound)?;
+        bounty.funder.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > bounty.balance {
+            return Err(Error::InsufficientBalance);
+        }
+
+        bounty.balance -= amount;
+        Self::token(&env)?.transfer(&env.current_contract_address(), &bounty.funder, &amount);
+        env.storage().persistent().set(&key, &bounty);
+
+        BountyWithdrawn {
+            sub_id,
+            amount,
+            balance: bounty.balance,
+        }
+        .publish(&env);
+        Ok(bounty.balance)
+    }
+
+    pub fn get_bounty(env: Env, sub_id: u64) -> Option<Bounty> {
+        env.storage().persistent().get(&DataKey::Bounty(sub_id))
+    }
+
+    // ── Keeper coordination ───────────────────────────────────────
+
+    /// Reserve the exclusive right to execute `cycle_id` for `ttl_ledgers`.
+    /// Competing keepers are rejected until the reservation lapses, so they
+    /// don't waste fees racing for the same bounty.
+    pub fn reserve(
+        env: Env,
+        keeper: Address,
+        sub_id: u64,
+        cycle_id: u64,
+        ttl_ledgers: u32,
+    ) -> Result<Reservation, Error> {
+        keeper.require_auth();
+        Self::ensure_unclaimed(&env, sub_id, cycle_id)?;
+        Self::ensure_not_reserved_by_other(&env, sub_id, &keeper)?;
+
+        let reservation = Reservation {
+            keeper: keeper.clone(),
+            cycle_id,
+            expires_at: env.ledger().sequence().saturating_add(ttl_ledgers),
+        };
+        let key = DataKey::Reservation(sub_id);
+        env.storage().temporary().set(&key, &reservation);
+        if ttl_ledgers > 0 {
+            env.storage()
+                .temporary()
+                .extend_ttl(&key, ttl_ledgers, ttl_ledgers);
+        }
+
+        CycleReserved {
+            sub_id,
+            cycle_id,
+            keeper,
+            expires_at: reservation.expires_at,
+        }
+        .publish(&env);
+        Ok(reservation)
+    }
+
+    pub fn get_reservation(env: Env, sub_id: u64) -> Option<Reservation> {
+        env.storage()
+            .temporary()
+            .get::<_, Reservation>(&DataKey::Reservation(sub_id))
+            .filter(|r| env.ledger().sequence() < r.expires_at)
+    }
+
+    /// Acquire the renewal lock and renew in one invocation, paying the
+    /// bounty to `keeper` if the renewal succeeds. The lock is taken in the
+    /// keeper's name and released inside this call, so the paid keeper is
+    /// always the lock holder. At most one bounty is paid per cycle.
+    pub fn execute_renewal(
+        env: Env,
+        keeper: Address,
+        sub_id: u64,
+        approval_id: u64,
+        amount: i128,
+        cycle_id: u64,
+        lock_timeout: u32,
+    ) -> Result<bool, Error> {
+        keeper.require_auth();
+        Self::ensure_unclaimed(&env, sub_id, cycle_id)?;
+        Self::ensure_not_reserved_by_other(&env, sub_id, &keeper)?;
+
+        let renewal_addr: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::RenewalContract)
+            .ok_or(Error::NotInitialized)?;
+        let renewal = RenewalClient::new(&env, &renewal_addr);
+        renewal.acquire_renewal_lock(&keeper, &sub_id, &lock_timeout);
+        let renewed = renewal.renew(&sub_id, &approval_id, &amount, &cycle_id);
+        env.storage()
+            .temporary()
+            .remove(&DataKey::Reservation(sub_id));
+
+        if !renewed {
+            return Ok(false);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Claimed(sub_id, cycle_id), &keeper);
+
+        // Pay whatever is left if the pool can't cover a full reward
+        let key = DataKey::Bounty(sub_id);
+        if let Some(mut bounty) = env.storage().persistent().get::<_, Bounty>(&key) {
+            let reward = bounty.reward_per_cycle.min(bounty.balance);
+            if reward > 0 {
+                bounty.balance -= reward;
+                env.storage().persistent().set(&key, &bounty);
+                Self::token(&env)?.transfer(&env.current_contract_address(), &keeper, &reward);
+
+                BountyClaimed {
+                    sub_id,
+                    cycle_id,
+                    keeper,
+                    reward,
+                }
+                .publish(&env);
+            }
+        }
+
+        Ok(true)
+    }
+
+    pub fn get_claimant(env: Env, sub_id: u64, cycle_id: u64) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Claimed(sub_id, cycle_id))
+    }
+
+    fn ensure_unclaimed(env: &Env, sub_id: u64, cycle_id: u64) -> Result<(), Error> {
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Claimed(sub_id, cycle_id))
+        {
+            return Err(Error::AlreadyClaimed);
+        }
+        Ok(())
+    }
+
+    fn ensure_not_reserved_by_other(env: &Env, sub_id: u64, keeper: &Address) -> Result<(), Error> {
+        if let Some(reservation) = Self::get_reservation(env.clone(), sub_id) {
+            if reservation.keeper != *keeper {
+                return Err(Error::ReservedByOther);
+            }
+        }
+        Ok(())
+    }
+
+    fn token(env: &Env) -> Result<token::TokenClient<'_>, Error> {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::TokenClient::new(env, &token))
+    }
+}
+
+#[cfg(test)]
+mod test;
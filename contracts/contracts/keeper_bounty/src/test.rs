@@ -0,0 +1,173 @@
+use super::*;
+use soroban_sdk::{contract, contractimpl, symbol_short, testutils::Address as _, Address, Env};
+use syncro_testutils::TestToken;
+
+/// Stand-in for the renewal contract: `renew` succeeds unless set failing.
+#[contract]
+struct MockRenewal;
+
+#[contractimpl]
+impl MockRenewal {
+    pub fn acquire_renewal_lock(_env: Env, _agent: Address, _sub_id: u64, _lock_timeout: u32) {}
+
+    pub fn set_failing(env: Env, failing: bool) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("failing"), &failing);
+    }
+
+    pub fn renew(env: Env, _sub_id: u64, _approval_id: u64, _amount: i128, _cycle_id: u64) -> bool {
+        !env.storage()
+            .instance()
+            .get(&symbol_short!("failing"))
+            .unwrap_or(false)
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: KeeperBountyContractClient<'static>,
+    renewal: MockRenewalClient<'static>,
+    token: TestToken,
+    funder: Address,
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let token = TestToken::new(&env);
+    let funder = Address::generate(&env);
+    token.mint(&funder, 10_000);
+
+    let renewal = MockRenewalClient::new(&env, &env.register(MockRenewal, ()));
+    let contract_id = env.register(KeeperBountyContract, ());
+    let client = KeeperBountyContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.init(&admin, &renewal.address, &token.address);
+    Setup {
+        env,
+        client,
+        renewal,
+        token,
+        funder,
+    }
+}
+
+#[test]
+fn test_fund_and_withdraw() {
+    let s = setup();
+
+    assert_eq!(s.client.fund(&s.funder, &1, &50, &1000), 1000);
+    assert_eq!(s.token.balance(&s.funder), 9000);
+
+    assert_eq!(s.client.withdraw(&1, &400), 600);
+    assert_eq!(s.token.balance(&s.funder), 9400);
+
+    let bounty = s.client.get_bounty(&1).unwrap();
+    assert_eq!(bounty.balance, 600);
+    assert_eq!(bounty.reward_per_cycle, 50);
+}
+
+#[test]
+fn test_only_pool_funder_can_top_up() {
+    let s = setup();
+
+    s.client.fund(&s.funder, &1, &50, &1000);
+
+    let other = Address::generate(&s.env);
+    let result = s.client.try_fund(&other, &1, &50, &1000);
+    assert_eq!(result, Err(Ok(Error::BountyOwnedByOther)));
+}
+
+#[test]
+fn test_keeper_paid_on_successful_renewal() {
+    let s = setup();
+    s.client.fund(&s.funder, &1, &50, &1000);
+
+    let keeper = Address::generate(&s.env);
+    let renewed = s
+        .client
+        .execute_renewal(&keeper, &1, &1, &500, &20260101, &200);
+    assert!(renewed);
+    assert_eq!(s.token.balance(&keeper), 50);
+    assert_eq!(s.client.get_claimant(&1, &20260101), Some(keeper));
+    assert_eq!(s.client.get_bounty(&1).unwrap().balance, 950);
+}
+
+#[test]
+fn test_no_bounty_on_failed_renewal() {
+    let s = setup();
+    s.client.fund(&s.funder, &1, &50, &1000);
+
+    let keeper = Address::generate(&s.env);
+    s.renewal.set_failing(&true);
+    let renewed = s
+        .client
+        .execute_renewal(&keeper, &1, &1, &500, &20260101, &200);
+    assert!(!renewed);
+    assert_eq!(s.token.balance(&keeper), 0);
+    assert_eq!(s.client.get_claimant(&1, &20260101), None);
+}
+
+#[test]
+fn test_one_bounty_per_cycle() {
+    let s = setup();
+    s.client.fund(&s.funder, &1, &50, &1000);
+
+    let keeper = Address::generate(&s.env);
+    s.client
+        .execute_renewal(&keeper, &1, &1, &500, &20260101, &200);
+
+    let result = s
+        .client
+        .try_execute_renewal(&keeper, &1, &2, &500, &20260101, &200);
+    assert_eq!(result, Err(Ok(Error::AlreadyClaimed)));
+}
+
+#[test]
+fn test_reservation_blocks_other_keepers() {
+    let s = setup();
+    s.client.fund(&s.funder, &1, &50, &1000);
+
+    let keeper = Address::generate(&s.env);
+    let rival = Address::generate(&s.env);
+    s.client.reserve(&keeper, &1, &20260101, &10);
+
+    let result = s
+        .client
+        .try_execute_renewal(&rival, &1, &1, &500, &20260101, &200);
+    assert_eq!(result, Err(Ok(Error::ReservedByOther)));
+
+    // Reservation holder can execute
+    assert!(s
+        .client
+        .execute_renewal(&keeper, &1, &1, &500, &20260101, &200));
+}
+
+#[test]
+fn test_reservation_lapses() {
+    let s = setup();
+    s.client.fund(&s.funder, &1, &50, &1000);
+
+    let keeper = Address::generate(&s.env);
+    let rival = Address::generate(&s.env);
+    s.client.reserve(&keeper, &1, &20260101, &10);
+
+    syncro_testutils::advance_ledgers(&s.env, 10);
+    assert!(s.client.get_reservation(&1).is_none());
+    assert!(s
+        .client
+        .execute_renewal(&rival, &1, &1, &500, &20260101, &200));
+    assert_eq!(s.token.balance(&rival), 50);
+}
+
+#[test]
+fn test_partial_reward_when_pool_runs_low() {
+    let s = setup();
+    s.client.fund(&s.funder, &1, &50, &30);
+
+    let keeper = Address::generate(&s.env);
+    s.client
+        .execute_renewal(&keeper, &1, &1, &500, &20260101, &200);
+    assert_eq!(s.token.balance(&keeper), 30);
+    assert_eq!(s.client.get_bounty(&1).unwrap().balance, 0);
+}
@@ -0,0 +1,381 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Val, Vec,
+};
+
+/// Entries kept in a user's history; older ones are dropped first.
+const MAX_HISTORY: u32 = 100;
+const BPS: i128 = 10_000;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1201,
+    NotInitialized = 1202,
+    InvalidRate = 1203,
+    InvalidAmount = 1204,
+    InsufficientPoints = 1205,
+    AlreadyCredited = 1206,
+    RedemptionMismatch = 1207,
+    NoPendingDiscount = 1208,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Reporter,
+    Program(Address),          // merchant -> Program
+    Balance(Address, Address), // (user, merchant) -> points
+    Pending(u64),              // sub_id -> PendingDiscount
+    History(Address),          // user -> Vec<PointsEntry>
+    Credited(u64, u64),        // (sub_id, cycle_id) -> true
+}
+
+/// A merchant's loyalty terms. One point is worth one unit of the
+/// subscription's payment token when redeemed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Program {
+    /// Points earned per renewal, in basis points of the amount paid.
+    pub earn_bps: u32,
+    /// Largest share of a renewal that redeemed points may cover.
+    pub max_discount_bps: u32,
+}
+
+/// Points set aside by the owner to discount a subscription's next
+/// renewals.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingDiscount {
+    pub owner: Address,
+    pub merchant: Address,
+    pub points: i128,
+}
+
+/// One change to a user's points: positive when earned or returned,
+/// negative when redeemed. Redemptions carry `cycle_id` 0.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PointsEntry {
+    pub merchant: Address,
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub points: i128,
+    pub ledger: u32,
+}
+
+#[contractevent]
+pub struct ProgramUpdated {
+    pub merchant: Address,
+    pub earn_bps: u32,
+    pub max_discount_bps: u32,
+}
+
+#[contractevent]
+pub struct PointsEarned {
+    pub user: Address,
+    pub merchant: Address,
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub points: i128,
+}
+
+#[contractevent]
+pub struct PointsRedeemed {
+    pub user: Address,
+    pub merchant: Address,
+    pub sub_id: u64,
+    pub points: i128,
+}
+
+#[contractevent]
+pub struct DiscountApplied {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub discount: i128,
+}
+
+#[contract]
+pub struct LoyaltyContract;
+
+#[contractimpl]
+impl LoyaltyContract {
+    /// Initialize with an admin and the renewal contract allowed to report
+    /// renewals.
+    pub fn init(env: Env, admin: Address, reporter: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Reporter, &reporter);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    /// Change the reporting contract. Admin only.
+    pub fn set_reporter(env: Env, reporter: Address) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Reporter, &reporter);
+        Ok(())
+    }
+
+    // ── Merchant programs ─────────────────────────────────────────
+
+    /// Create or update the merchant's program. Merchants without a
+    /// program earn their users no points.
+    pub fn set_program(
+        env: Env,
+        merchant: Address,
+        earn_bps: u32,
+        max_discount_bps: u32,
+    ) -> Result<(), Error> {
+        merchant.require_auth();
+        if earn_bps as i128 > BPS || max_discount_bps as i128 > BPS {
+            return Err(Error::InvalidRate);
+        }
+        env.storage().persistent().set(
+            &DataKey::Program(merchant.clone()),
+            &Program {
+                earn_bps,
+                max_discount_bps,
+            },
+        );
+        ProgramUpdated {
+            merchant,
+            earn_bps,
+            max_discount_bps,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_program(env: Env, merchant: Address) -> Option<Program> {
+        env.storage().persistent().get(&DataKey::Program(merchant))
+    }
+
+    // ── Renewal hook ──────────────────────────────────────────────
+
+    /// Called by the renewal contract after a successful renewal of
+    /// `amount`. Applies any pending discount (up to the program's cap),
+    /// credits points on the amount actually paid and returns the discount.
+    pub fn on_renewal(
+        env: Env,
+        sub_id: u64,
+        cycle_id: u64,
+        owner: Address,
+        merchant: Address,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        let reporter: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Reporter)
+            .ok_or(Error::NotInitialized)?;
+        reporter.require_auth();
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let credited_key = DataKey::Credited(sub_id, cycle_id);
+        if env.storage().persistent().has(&credited_key) {
+            return Err(Error::AlreadyCredited);
+        }
+        env.storage().persistent().set(&credited_key, &true);
+
+        let Some(program) = Self::get_program(env.clone(), merchant.clone()) else {
+            return Ok(0);
+        };
+
+        let discount = Self::take_discount(&env, sub_id, cycle_id, amount, &program);
+        let points = (amount - discount) * program.earn_bps as i128 / BPS;
+        if points > 0 {
+            Self::add_points(&env, &owner, &merchant, points);
+            Self::record(&env, &owner, &merchant, sub_id, cycle_id, points);
+            PointsEarned {
+                user: owner,
+                merchant,
+                sub_id,
+                cycle_id,
+                points,
+            }
+            .publish(&env);
+        }
+        Ok(discount)
+    }
+
+    // ── Redemption ────────────────────────────────────────────────
+
+    /// Set `points` aside as a discount on the subscription's next
+    /// renewals. Returns the total pending for the subscription.
+    pub fn redeem(
+        env: Env,
+        user: Address,
+        merchant: Address,
+        sub_id: u64,
+        points: i128,
+    ) -> Result<i128, Error> {
+        user.require_auth();
+        if points <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let balance = Self::balance(env.clone(), user.clone(), merchant.clone());
+        if points > balance {
+            return Err(Error::InsufficientPoints);
+        }
+
+        let key = DataKey::Pending(sub_id);
+        let mut pending = match env.storage().persistent().get::<_, PendingDiscount>(&key) {
+            Some(p) if p.owner != user || p.merchant != merchant => {
+                return Err(Error::RedemptionMismatch)
+            }
+            Some(p) => p,
+            None => PendingDiscount {
+                owner: user.clone(),
+                merchant: merchant.clone(),
+                points: 0,
+            },
+        };
+        pending.points += points;
+        env.storage().persistent().set(&key, &pending);
+        Self::add_points(&env, &user, &merchant, -points);
+        Self::record(&env, &user, &merchant, sub_id, 0, -points);
+
+        PointsRedeemed {
+            user,
+            merchant,
+            sub_id,
+            points,
+        }
+        .publish(&env);
+        Ok(pending.points)
+    }
+
+    /// Return unused pending points to the owner's balance.
+    pub fn cancel_redemption(env: Env, sub_id: u64) -> Result<i128, Error> {
+        let key = DataKey::Pending(sub_id);
+        let pending: PendingDiscount = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NoPendingDiscount)?;
+        pending.owner.require_auth();
+
+        env.storage().persistent().remove(&key);
+        Self::add_points(&env, &pending.owner, &pending.merchant, pending.points);
+        Self::record(
+            &env,
+            &pending.owner,
+            &pending.merchant,
+            sub_id,
+            0,
+            pending.points,
+        );
+        Ok(pending.points)
+    }
+
+    // ── Queries ───────────────────────────────────────────────────
+
+    pub fn balance(env: Env, user: Address, merchant: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Balance(user, merchant))
+            .unwrap_or(0)
+    }
+
+    pub fn pending_discount(env: Env, sub_id: u64) -> Option<PendingDiscount> {
+        env.storage().persistent().get(&DataKey::Pending(sub_id))
+    }
+
+    /// The user's most recent point changes, oldest first.
+    pub fn history(env: Env, user: Address) -> Vec<PointsEntry> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::History(user))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn take_discount(
+        env: &Env,
+        sub_id: u64,
+        cycle_id: u64,
+        amount: i128,
+        program: &Program,
+    ) -> i128 {
+        let key = DataKey::Pending(sub_id);
+        let Some(mut pending) = env.storage().persistent().get::<_, PendingDiscount>(&key) else {
+            return 0;
+        };
+        let cap = amount * program.max_discount_bps as i128 / BPS;
+        let discount = pending.points.min(cap);
+        if discount <= 0 {
+            return 0;
+        }
+
+        pending.points -= discount;
+        if pending.points == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &pending);
+        }
+        DiscountApplied {
+            sub_id,
+            cycle_id,
+            discount,
+        }
+        .publish(env);
+        discount
+    }
+
+    fn add_points(env: &Env, user: &Address, merchant: &Address, points: i128) {
+        let key = DataKey::Balance(user.clone(), merchant.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(balance + points));
+    }
+
+    fn record(
+        env: &Env,
+        user: &Address,
+        merchant: &Address,
+        sub_id: u64,
+        cycle_id: u64,
+        points: i128,
+    ) {
+        let key = DataKey::History(user.clone());
+        let mut history: Vec<PointsEntry> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if history.len() >= MAX_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(PointsEntry {
+            merchant: merchant.clone(),
+            sub_id,
+            cycle_id,
+            points,
+            ledger: env.ledger().sequence(),
+        });
+        env.storage().persistent().set(&key, &history);
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,157 @@
+use super::*;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+struct Setup {
+    env: Env,
+    client: LoyaltyContractClient<'static>,
+    user: Address,
+    merchant: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(LoyaltyContract, ());
+    let client = LoyaltyContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let reporter = Address::generate(&env);
+    client.init(&admin, &reporter);
+
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    Setup {
+        env,
+        client,
+        user,
+        merchant,
+    }
+}
+
+impl Setup {
+    fn renew(&self, sub_id: u64, cycle_id: u64, amount: i128) -> i128 {
+        self.client
+            .on_renewal(&sub_id, &cycle_id, &self.user, &self.merchant, &amount)
+    }
+}
+
+#[test]
+fn test_points_earned_on_renewal() {
+    let s = setup();
+    s.client.set_program(&s.merchant, &100, &5_000);
+
+    assert_eq!(s.renew(1, 1, 10_000), 0);
+    assert_eq!(s.client.balance(&s.user, &s.merchant), 100);
+
+    let history = s.client.history(&s.user);
+    assert_eq!(history.len(), 1);
+    let entry = history.get(0).unwrap();
+    assert_eq!(entry.points, 100);
+    assert_eq!(entry.cycle_id, 1);
+}
+
+#[test]
+fn test_no_program_no_points() {
+    let s = setup();
+    assert_eq!(s.renew(1, 1, 10_000), 0);
+    assert_eq!(s.client.balance(&s.user, &s.merchant), 0);
+    assert!(s.client.history(&s.user).is_empty());
+}
+
+#[test]
+fn test_cycle_credited_once() {
+    let s = setup();
+    s.client.set_program(&s.merchant, &100, &5_000);
+    s.renew(1, 1, 10_000);
+    assert_eq!(
+        s.client
+            .try_on_renewal(&1, &1, &s.user, &s.merchant, &10_000),
+        Err(Ok(Error::AlreadyCredited))
+    );
+}
+
+#[test]
+fn test_redeemed_points_discount_next_renewals() {
+    let s = setup();
+    s.client.set_program(&s.merchant, &1_000, &5_000);
+    s.renew(1, 1, 10_000);
+    assert_eq!(s.client.balance(&s.user, &s.merchant), 1_000);
+
+    assert_eq!(s.client.redeem(&s.user, &s.merchant, &1, &800), 800);
+    assert_eq!(s.client.balance(&s.user, &s.merchant), 200);
+
+    // Discount capped at half of the 1000 charge; points earned on the rest
+    assert_eq!(s.renew(1, 2, 1_000), 500);
+    assert_eq!(s.client.pending_discount(&1).unwrap().points, 300);
+    assert_eq!(s.client.balance(&s.user, &s.merchant), 250);
+
+    // The remainder is used up on the following cycle
+    assert_eq!(s.renew(1, 3, 1_000), 300);
+    assert!(s.client.pending_discount(&1).is_none());
+}
+
+#[test]
+fn test_redeem_validation() {
+    let s = setup();
+    s.client.set_program(&s.merchant, &1_000, &5_000);
+    s.renew(1, 1, 10_000);
+
+    assert_eq!(
+        s.client.try_redeem(&s.user, &s.merchant, &1, &1_001),
+        Err(Ok(Error::InsufficientPoints))
+    );
+    assert_eq!(
+        s.client.try_redeem(&s.user, &s.merchant, &1, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    s.client.redeem(&s.user, &s.merchant, &1, &100);
+    let other_merchant = Address::generate(&s.env);
+    s.client.set_program(&other_merchant, &1_000, &5_000);
+    s.client
+        .on_renewal(&2, &1, &s.user, &other_merchant, &10_000);
+    assert_eq!(
+        s.client.try_redeem(&s.user, &other_merchant, &1, &100),
+        Err(Ok(Error::RedemptionMismatch))
+    );
+}
+
+#[test]
+fn test_cancel_redemption_returns_points() {
+    let s = setup();
+    s.client.set_program(&s.merchant, &1_000, &5_000);
+    s.renew(1, 1, 10_000);
+    s.client.redeem(&s.user, &s.merchant, &1, &400);
+
+    assert_eq!(s.client.cancel_redemption(&1), 400);
+    assert_eq!(s.client.balance(&s.user, &s.merchant), 1_000);
+    assert_eq!(
+        s.client.try_cancel_redemption(&1),
+        Err(Ok(Error::NoPendingDiscount))
+    );
+}
+
+#[test]
+fn test_invalid_rates_rejected() {
+    let s = setup();
+    assert_eq!(
+        s.client.try_set_program(&s.merchant, &10_001, &0),
+        Err(Ok(Error::InvalidRate))
+    );
+    assert_eq!(
+        s.client.try_set_program(&s.merchant, &0, &10_001),
+        Err(Ok(Error::InvalidRate))
+    );
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let s = setup();
+    s.client.set_program(&s.merchant, &100, &0);
+    for cycle_id in 1..=(MAX_HISTORY as u64 + 5) {
+        s.renew(1, cycle_id, 10_000);
+    }
+    let history = s.client.history(&s.user);
+    assert_eq!(history.len(), MAX_HISTORY);
+    assert_eq!(history.get(0).unwrap().cycle_id, 6);
+}
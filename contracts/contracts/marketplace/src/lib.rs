@@ -0,0 +1,406 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, token,
+    Address, BytesN, Env, Val, Vec,
+};
+use syncro_access::AccessError;
+use syncro_common::SubscriptionState;
+
+const BPS: i128 = 10_000;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2301,
+    NotInitialized = 2302,
+    NoPendingAdmin = 2303,
+    Unauthorized = 2304,
+    InvalidPrice = 2305,
+    InvalidFee = 2306,
+    NotOwner = 2307,
+    NotTransferable = 2308,
+    TransferNotApproved = 2309,
+    AlreadyListed = 2310,
+    ListingNotFound = 2311,
+    StaleListing = 2312,
+    SelfPurchase = 2313,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Token,
+    Renewal,
+    Registry,
+    Fee,
+    ListingCounter,
+    Listing(u64),    // listing id -> Listing
+    SubListing(u64), // renewal sub_id -> open listing id
+}
+
+/// Mirror of the renewal contract's `SubscriptionData`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionData {
+    pub owner: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub integrity_hash: BytesN<32>,
+    pub state: SubscriptionState,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+/// Mirror of the subscription registry's `TransferApproval`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferApproval {
+    pub owner: Address,
+    pub operator: Address,
+}
+
+/// The renewal contract, which holds the subscription being sold.
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn get_sub(env: Env, sub_id: u64) -> SubscriptionData;
+    fn get_sub_transfer_operator(env: Env, sub_id: u64) -> Option<Address>;
+    fn transfer_sub(env: Env, sub_id: u64, caller: Address, new_owner: Address);
+}
+
+/// The subscription registry, whose metadata entry moves with the sale.
+#[contractclient(name = "RegistryClient")]
+pub trait RegistryInterface {
+    fn get_transfer_approval(env: Env, subscription_id: BytesN<32>) -> Option<TransferApproval>;
+    fn transfer_subscription(env: Env, subscription_id: BytesN<32>, operator: Address, to: Address);
+}
+
+/// Protocol fee taken from every sale.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub fee_bps: u32,
+    pub recipient: Address,
+}
+
+/// A subscription offered for sale. `registry_id` is the matching entry in
+/// the subscription registry, transferred along with the subscription.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Listing {
+    pub seller: Address,
+    pub sub_id: u64,
+    pub registry_id: Option<BytesN<32>>,
+    pub price: i128,
+    pub listed_at: u64,
+}
+
+#[contractevent]
+pub struct FeeSet {
+    pub fee_bps: u32,
+    pub recipient: Address,
+}
+
+#[contractevent]
+pub struct SubscriptionListed {
+    pub listing_id: u64,
+    pub sub_id: u64,
+    pub seller: Address,
+    pub price: i128,
+}
+
+#[contractevent]
+pub struct ListingCancelled {
+    pub listing_id: u64,
+    pub sub_id: u64,
+}
+
+#[contractevent]
+pub struct SubscriptionSold {
+    pub listing_id: u64,
+    pub sub_id: u64,
+    pub seller: Address,
+    pub buyer: Address,
+    pub price: i128,
+    pub fee: i128,
+}
+
+#[contract]
+pub struct MarketplaceContract;
+
+#[contractimpl]
+impl MarketplaceContract {
+    /// Initialize with an admin, the token sales are paid in, the renewal
+    /// contract and subscription registry ownership is moved in, and the
+    /// protocol fee.
+    pub fn init(
+        env: Env,
+        admin: Address,
+        token: Address,
+        renewal: Address,
+        registry: Address,
+        fee_bps: u32,
+        fee_recipient: Address,
+    ) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::Renewal, &renewal);
+        env.storage().instance().set(&DataKey::Registry, &registry);
+        Self::store_fee(&env, fee_bps, fee_recipient)?;
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Configuration (admin only) ────────────────────────────────
+
+    pub fn set_fee(env: Env, fee_bps: u32, recipient: Address) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        Self::store_fee(&env, fee_bps, recipient)
+    }
+
+    pub fn get_fee(env: Env) -> Result<FeeConfig, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Fee)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// The protocol fee taken from a sale at `price`.
+    pub fn quote_fee(env: Env, price: i128) -> Result<i128, Error> {
+        Ok(price * Self::get_fee(env)?.fee_bps as i128 / BPS)
+    }
+
+    // ── Listings ──────────────────────────────────────────────────
+
+    /// Offer an active subscription for `price`. The seller must first
+    /// approve this contract as transfer operator in the renewal contract
+    /// and, when `registry_id` is given, in the registry. A listing left
+    /// behind by a previous owner is replaced.
+    pub fn list(
+        env: Env,
+        seller: Address,
+        sub_id: u64,
+        price: i128,
+        registry_id: Option<BytesN<32>>,
+    ) -> Result<u64, Error> {
+        seller.require_auth();
+        if price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+        let sub = Self::renewal(&env)?.get_sub(&sub_id);
+        if sub.owner != seller {
+            return Err(Error::NotOwner);
+        }
+        if sub.state != SubscriptionState::Active {
+            return Err(Error::NotTransferable);
+        }
+        Self::check_approvals(&env, sub_id, &seller, &registry_id)?;
+
+        if let Some(open) = Self::listing_for_sub(env.clone(), sub_id) {
+            if Self::get_listing(env.clone(), open).map(|l| l.seller) == Some(seller.clone()) {
+                return Err(Error::AlreadyListed);
+            }
+            env.storage().persistent().remove(&DataKey::Listing(open));
+        }
+
+        let listing_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ListingCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::ListingCounter, &listing_id);
+        env.storage().persistent().set(
+            &DataKey::Listing(listing_id),
+            &Listing {
+                seller: seller.clone(),
+                sub_id,
+                registry_id,
+                price,
+                listed_at: env.ledger().timestamp(),
+            },
+        );
+        env.storage()
+            .persistent()
+            .set(&DataKey::SubListing(sub_id), &listing_id);
+
+        SubscriptionListed {
+            listing_id,
+            sub_id,
+            seller,
+            price,
+        }
+        .publish(&env);
+        Ok(listing_id)
+    }
+
+    /// Withdraw a listing. Seller only.
+    pub fn cancel_listing(env: Env, listing_id: u64) -> Result<(), Error> {
+        let listing = Self::get_listing(env.clone(), listing_id).ok_or(Error::ListingNotFound)?;
+        listing.seller.require_auth();
+        Self::remove_listing(&env, listing_id, listing.sub_id);
+        ListingCancelled {
+            listing_id,
+            sub_id: listing.sub_id,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Buy a listed subscription. In one transaction the buyer pays the
+    /// seller the price less the protocol fee, pays the fee to the fee
+    /// recipient, and becomes the owner in the renewal contract and the
+    /// registry. Returns the fee charged.
+    pub fn buy(env: Env, buyer: Address, listing_id: u64) -> Result<i128, Error> {
+        buyer.require_auth();
+        let listing = Self::get_listing(env.clone(), listing_id).ok_or(Error::ListingNotFound)?;
+        if buyer == listing.seller {
+            return Err(Error::SelfPurchase);
+        }
+        let renewal = Self::renewal(&env)?;
+        let sub = renewal.get_sub(&listing.sub_id);
+        if sub.owner != listing.seller {
+            return Err(Error::StaleListing);
+        }
+        if sub.state != SubscriptionState::Active {
+            return Err(Error::NotTransferable);
+        }
+        Self::check_approvals(&env, listing.sub_id, &listing.seller, &listing.registry_id)?;
+
+        let fee_config = Self::get_fee(env.clone())?;
+        let fee = listing.price * fee_config.fee_bps as i128 / BPS;
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        let token = token::TokenClient::new(&env, &token);
+        token.transfer(&buyer, &listing.seller, &(listing.price - fee));
+        if fee > 0 {
+            token.transfer(&buyer, &fee_config.recipient, &fee);
+        }
+
+        let this = env.current_contract_address();
+        renewal.transfer_sub(&listing.sub_id, &this, &buyer);
+        if let Some(registry_id) = &listing.registry_id {
+            Self::registry(&env)?.transfer_subscription(registry_id, &this, &buyer);
+        }
+        Self::remove_listing(&env, listing_id, listing.sub_id);
+
+        SubscriptionSold {
+            listing_id,
+            sub_id: listing.sub_id,
+            seller: listing.seller,
+            buyer,
+            price: listing.price,
+            fee,
+        }
+        .publish(&env);
+        Ok(fee)
+    }
+
+    pub fn get_listing(env: Env, listing_id: u64) -> Option<Listing> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Listing(listing_id))
+    }
+
+    /// The open listing for a renewal subscription, if any.
+    pub fn listing_for_sub(env: Env, sub_id: u64) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::SubListing(sub_id))
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn store_fee(env: &Env, fee_bps: u32, recipient: Address) -> Result<(), Error> {
+        if fee_bps as i128 > BPS {
+            return Err(Error::InvalidFee);
+        }
+        env.storage().instance().set(
+            &DataKey::Fee,
+            &FeeConfig {
+                fee_bps,
+                recipient: recipient.clone(),
+            },
+        );
+        FeeSet { fee_bps, recipient }.publish(env);
+        Ok(())
+    }
+
+    /// Both transfers a sale performs must already be approved for this
+    /// contract, so a buyer never pays for a subscription that cannot move.
+    fn check_approvals(
+        env: &Env,
+        sub_id: u64,
+        seller: &Address,
+        registry_id: &Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        let this = env.current_contract_address();
+        if Self::renewal(env)?.get_sub_transfer_operator(&sub_id) != Some(this.clone()) {
+            return Err(Error::TransferNotApproved);
+        }
+        if let Some(registry_id) = registry_id {
+            let expected = TransferApproval {
+                owner: seller.clone(),
+                operator: this,
+            };
+            if Self::registry(env)?.get_transfer_approval(registry_id) != Some(expected) {
+                return Err(Error::TransferNotApproved);
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_listing(env: &Env, listing_id: u64, sub_id: u64) {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Listing(listing_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SubListing(sub_id));
+    }
+
+    fn renewal(env: &Env) -> Result<RenewalClient<'_>, Error> {
+        let renewal: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Renewal)
+            .ok_or(Error::NotInitialized)?;
+        Ok(RenewalClient::new(env, &renewal))
+    }
+
+    fn registry(env: &Env) -> Result<RegistryClient<'_>, Error> {
+        let registry: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Registry)
+            .ok_or(Error::NotInitialized)?;
+        Ok(RegistryClient::new(env, &registry))
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,299 @@
+use super::*;
+use soroban_sdk::{
+    contract,
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env,
+};
+
+/// Stand-in for the renewal contract with operator-approved transfers.
+#[contract]
+struct MockRenewal;
+
+#[contractimpl]
+impl MockRenewal {
+    pub fn set_sub(env: Env, sub_id: u64, data: SubscriptionData) {
+        env.storage().instance().set(&sub_id, &data);
+    }
+
+    pub fn get_sub(env: Env, sub_id: u64) -> SubscriptionData {
+        env.storage().instance().get(&sub_id).unwrap()
+    }
+
+    pub fn approve_sub_transfer(env: Env, sub_id: u64, operator: Option<Address>) {
+        env.storage().instance().set(&(sub_id, 0u32), &operator);
+    }
+
+    pub fn get_sub_transfer_operator(env: Env, sub_id: u64) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&(sub_id, 0u32))
+            .unwrap_or(None)
+    }
+
+    pub fn transfer_sub(env: Env, sub_id: u64, caller: Address, new_owner: Address) {
+        caller.require_auth();
+        assert_eq!(
+            Self::get_sub_transfer_operator(env.clone(), sub_id),
+            Some(caller)
+        );
+        let mut data = Self::get_sub(env.clone(), sub_id);
+        data.owner = new_owner;
+        env.storage().instance().set(&sub_id, &data);
+        env.storage().instance().remove(&(sub_id, 0u32));
+    }
+}
+
+/// Stand-in for the subscription registry: records the last transfer.
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn set_approval(env: Env, subscription_id: BytesN<32>, approval: TransferApproval) {
+        env.storage().instance().set(&subscription_id, &approval);
+    }
+
+    pub fn get_transfer_approval(
+        env: Env,
+        subscription_id: BytesN<32>,
+    ) -> Option<TransferApproval> {
+        env.storage().instance().get(&subscription_id)
+    }
+
+    pub fn transfer_subscription(
+        env: Env,
+        subscription_id: BytesN<32>,
+        operator: Address,
+        to: Address,
+    ) {
+        operator.require_auth();
+        env.storage().instance().remove(&subscription_id);
+        env.storage().instance().set(&0u32, &to);
+    }
+
+    pub fn last_recipient(env: Env) -> Option<Address> {
+        env.storage().instance().get(&0u32)
+    }
+}
+
+const SUB_ID: u64 = 1;
+const PRICE: i128 = 1_000;
+
+struct Setup {
+    env: Env,
+    client: MarketplaceContractClient<'static>,
+    token: TokenClient<'static>,
+    renewal: MockRenewalClient<'static>,
+    registry: MockRegistryClient<'static>,
+    seller: Address,
+    buyer: Address,
+    fee_recipient: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token = TokenClient::new(&env, &sac.address());
+    let renewal = MockRenewalClient::new(&env, &env.register(MockRenewal, ()));
+    let registry = MockRegistryClient::new(&env, &env.register(MockRegistry, ()));
+    let contract_id = env.register(MarketplaceContract, ());
+    let client = MarketplaceContractClient::new(&env, &contract_id);
+    let fee_recipient = Address::generate(&env);
+    client.init(
+        &Address::generate(&env),
+        &sac.address(),
+        &renewal.address,
+        &registry.address,
+        &250,
+        &fee_recipient,
+    );
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    StellarAssetClient::new(&env, &sac.address()).mint(&buyer, &10_000);
+    renewal.set_sub(
+        &SUB_ID,
+        &SubscriptionData {
+            owner: seller.clone(),
+            merchant: Address::generate(&env),
+            amount: 100,
+            frequency: 2_592_000,
+            spending_cap: 1_200,
+            integrity_hash: BytesN::from_array(&env, &[0; 32]),
+            state: SubscriptionState::Active,
+            failure_count: 0,
+            last_attempt_ledger: 0,
+        },
+    );
+    renewal.approve_sub_transfer(&SUB_ID, &Some(contract_id));
+    Setup {
+        env,
+        client,
+        token,
+        renewal,
+        registry,
+        seller,
+        buyer,
+        fee_recipient,
+    }
+}
+
+impl Setup {
+    fn list(&self, registry_id: &Option<BytesN<32>>) -> Result<u64, Error> {
+        match self
+            .client
+            .try_list(&self.seller, &SUB_ID, &PRICE, registry_id)
+        {
+            Ok(id) => Ok(id.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+
+    fn buy(&self, listing_id: u64) -> Result<i128, Error> {
+        match self.client.try_buy(&self.buyer, &listing_id) {
+            Ok(fee) => Ok(fee.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+
+    fn set_state(&self, state: SubscriptionState) {
+        let mut sub = self.renewal.get_sub(&SUB_ID);
+        sub.state = state;
+        self.renewal.set_sub(&SUB_ID, &sub);
+    }
+}
+
+#[test]
+fn test_sale_pays_seller_and_fee_and_transfers_ownership() {
+    let s = setup();
+    let listing_id = s.list(&None).unwrap();
+    assert_eq!(s.client.listing_for_sub(&SUB_ID), Some(listing_id));
+
+    assert_eq!(s.buy(listing_id), Ok(25));
+    assert_eq!(s.token.balance(&s.seller), 975);
+    assert_eq!(s.token.balance(&s.fee_recipient), 25);
+    assert_eq!(s.token.balance(&s.buyer), 9_000);
+    assert_eq!(s.renewal.get_sub(&SUB_ID).owner, s.buyer);
+    assert_eq!(s.client.get_listing(&listing_id), None);
+    assert_eq!(s.client.listing_for_sub(&SUB_ID), None);
+}
+
+#[test]
+fn test_sale_transfers_registry_entry() {
+    let s = setup();
+    let registry_id = BytesN::from_array(&s.env, &[9; 32]);
+    s.registry.set_approval(
+        &registry_id,
+        &TransferApproval {
+            owner: s.seller.clone(),
+            operator: s.client.address.clone(),
+        },
+    );
+
+    let listing_id = s.list(&Some(registry_id)).unwrap();
+    s.buy(listing_id).unwrap();
+    assert_eq!(s.registry.last_recipient(), Some(s.buyer.clone()));
+}
+
+#[test]
+fn test_listing_requires_transfer_approvals() {
+    let s = setup();
+    let registry_id = BytesN::from_array(&s.env, &[9; 32]);
+    assert_eq!(s.list(&Some(registry_id)), Err(Error::TransferNotApproved));
+
+    s.renewal.approve_sub_transfer(&SUB_ID, &None);
+    assert_eq!(s.list(&None), Err(Error::TransferNotApproved));
+}
+
+#[test]
+fn test_only_owner_lists_active_subscriptions() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_list(&s.buyer, &SUB_ID, &PRICE, &None)
+            .unwrap_err()
+            .unwrap(),
+        Error::NotOwner
+    );
+    assert_eq!(
+        s.client
+            .try_list(&s.seller, &SUB_ID, &0, &None)
+            .unwrap_err()
+            .unwrap(),
+        Error::InvalidPrice
+    );
+
+    s.set_state(SubscriptionState::Retrying);
+    assert_eq!(s.list(&None), Err(Error::NotTransferable));
+}
+
+#[test]
+fn test_subscription_listed_once() {
+    let s = setup();
+    s.list(&None).unwrap();
+    assert_eq!(s.list(&None), Err(Error::AlreadyListed));
+}
+
+#[test]
+fn test_cancelled_listing_cannot_be_bought() {
+    let s = setup();
+    let listing_id = s.list(&None).unwrap();
+    s.client.cancel_listing(&listing_id);
+
+    assert_eq!(s.buy(listing_id), Err(Error::ListingNotFound));
+    assert_eq!(s.token.balance(&s.buyer), 10_000);
+}
+
+#[test]
+fn test_listing_goes_stale_when_owner_changes() {
+    let s = setup();
+    let listing_id = s.list(&None).unwrap();
+    let mut sub = s.renewal.get_sub(&SUB_ID);
+    sub.owner = Address::generate(&s.env);
+    s.renewal.set_sub(&SUB_ID, &sub);
+
+    assert_eq!(s.buy(listing_id), Err(Error::StaleListing));
+}
+
+#[test]
+fn test_sale_refused_once_subscription_lapses() {
+    let s = setup();
+    let listing_id = s.list(&None).unwrap();
+    s.set_state(SubscriptionState::Failed);
+
+    assert_eq!(s.buy(listing_id), Err(Error::NotTransferable));
+    assert_eq!(s.token.balance(&s.buyer), 10_000);
+}
+
+#[test]
+fn test_seller_cannot_buy_own_listing() {
+    let s = setup();
+    let listing_id = s.list(&None).unwrap();
+    assert_eq!(
+        s.client
+            .try_buy(&s.seller, &listing_id)
+            .unwrap_err()
+            .unwrap(),
+        Error::SelfPurchase
+    );
+}
+
+#[test]
+fn test_fee_is_bounded() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_set_fee(&10_001, &s.fee_recipient)
+            .unwrap_err()
+            .unwrap(),
+        Error::InvalidFee
+    );
+
+    s.client.set_fee(&0, &s.fee_recipient);
+    let listing_id = s.list(&None).unwrap();
+    assert_eq!(s.buy(listing_id), Ok(0));
+    assert_eq!(s.token.balance(&s.seller), PRICE);
+}
@@ -0,0 +1,249 @@
+#![no_std]
+//! Read-only aggregation of per-subscription views.
+//!
+//! A wallet showing a subscription needs its data, lifecycle timestamps and
+//! renewal lock from the renewal contract plus its log history from the
+//! logging contract: four or more simulated calls each. `get_view` gathers
+//! them in one, and `multi_get` does the same for a page of subscriptions.
+//! Nothing here writes state; the contract only remembers where the suite
+//! lives.
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractimpl, contracttype, Address, BytesN, Env,
+    String, Val, Vec,
+};
+use syncro_access::AccessError;
+use syncro_common::{LogEvent, SubscriptionState};
+
+/// Most subscriptions one `multi_get` reads.
+pub const MAX_SUBS: u32 = 20;
+/// Most log entries included per subscription.
+pub const MAX_RECENT_LOGS: u32 = 10;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 3201,
+    NotInitialized = 3202,
+    NoPendingAdmin = 3203,
+    Unauthorized = 3204,
+    TooManySubs = 3205,
+    TooManyLogs = 3206,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Renewal,
+    Logging,
+}
+
+/// Mirror of the renewal contract's `SubscriptionData`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionData {
+    pub owner: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub integrity_hash: BytesN<32>,
+    pub state: SubscriptionState,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+/// Mirror of the renewal contract's `LifecycleTimestamps`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LifecycleTimestamps {
+    pub created_at: u64,
+    pub activated_at: u64,
+    pub last_renewed_at: u64,
+    pub canceled_at: u64,
+    pub paused_at: u64,
+    pub resumed_at: u64,
+}
+
+/// Mirror of the renewal contract's `RenewalLockData`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RenewalLockData {
+    pub holder: Address,
+    pub locked_at: u32,
+    pub lock_timeout: u32,
+}
+
+/// Mirror of the logging contract's `LogEntry`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogEntry {
+    pub sub_id: u64,
+    pub event: LogEvent,
+    pub timestamp: u64,
+    pub data: String,
+}
+
+/// Mirror of the logging contract's `LogSummary`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogSummary {
+    pub count: u32,
+    pub recent: Vec<LogEntry>,
+}
+
+/// Everything a wallet shows for one subscription. The lock fields are
+/// unset while no renewal lock is held, and the log fields stay empty when
+/// no logging contract is configured.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionView {
+    pub sub_id: u64,
+    pub sub: SubscriptionData,
+    pub lifecycle: LifecycleTimestamps,
+    pub lock_holder: Option<Address>,
+    /// Ledger the held lock lapses at.
+    pub lock_expires_at: Option<u32>,
+    pub log_count: u32,
+    /// Newest log entries, oldest first.
+    pub recent_logs: Vec<LogEntry>,
+}
+
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn get_sub(env: Env, sub_id: u64) -> SubscriptionData;
+    fn get_lifecycle(env: Env, sub_id: u64) -> LifecycleTimestamps;
+    fn get_renewal_lock(env: Env, sub_id: u64) -> Option<RenewalLockData>;
+}
+
+#[contractclient(name = "LoggingClient")]
+pub trait LoggingInterface {
+    fn get_log_summary(env: Env, sub_id: u64, recent: u32) -> LogSummary;
+}
+
+#[contract]
+pub struct MulticallContract;
+
+#[contractimpl]
+impl MulticallContract {
+    /// Initialize with an admin and the contracts to read from.
+    pub fn init(
+        env: Env,
+        admin: Address,
+        renewal: Address,
+        logging: Option<Address>,
+    ) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        Self::store_contracts(&env, &renewal, &logging);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    /// Point at another deployment of the suite. Admin only.
+    pub fn set_contracts(
+        env: Env,
+        renewal: Address,
+        logging: Option<Address>,
+    ) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        Self::store_contracts(&env, &renewal, &logging);
+        Ok(())
+    }
+
+    pub fn get_renewal(env: Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Renewal)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn get_logging(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Logging)
+    }
+
+    // ── Views ─────────────────────────────────────────────────────
+
+    /// The view of `sub_id` with up to `recent_logs` log entries, or `None`
+    /// if the renewal contract has no such subscription.
+    pub fn get_view(
+        env: Env,
+        sub_id: u64,
+        recent_logs: u32,
+    ) -> Result<Option<SubscriptionView>, Error> {
+        let sub_ids = Vec::from_array(&env, [sub_id]);
+        Ok(Self::multi_get(env, sub_ids, recent_logs)?.first())
+    }
+
+    /// Views of `sub_ids` in order, skipping ids with no subscription, so
+    /// callers match results by `sub_id`. At most [`MAX_SUBS`] ids and
+    /// [`MAX_RECENT_LOGS`] entries each.
+    pub fn multi_get(
+        env: Env,
+        sub_ids: Vec<u64>,
+        recent_logs: u32,
+    ) -> Result<Vec<SubscriptionView>, Error> {
+        if sub_ids.len() > MAX_SUBS {
+            return Err(Error::TooManySubs);
+        }
+        if recent_logs > MAX_RECENT_LOGS {
+            return Err(Error::TooManyLogs);
+        }
+        let renewal = RenewalClient::new(&env, &Self::get_renewal(env.clone())?);
+        let logging = Self::get_logging(env.clone()).map(|id| LoggingClient::new(&env, &id));
+        let mut views = Vec::new(&env);
+        for sub_id in sub_ids.iter() {
+            let Ok(Ok(sub)) = renewal.try_get_sub(&sub_id) else {
+                continue;
+            };
+            let lock = renewal.get_renewal_lock(&sub_id);
+            let logs = match &logging {
+                Some(logging) => logging.get_log_summary(&sub_id, &recent_logs),
+                None => LogSummary {
+                    count: 0,
+                    recent: Vec::new(&env),
+                },
+            };
+            views.push_back(SubscriptionView {
+                sub_id,
+                sub,
+                lifecycle: renewal.get_lifecycle(&sub_id),
+                lock_holder: lock.as_ref().map(|lock| lock.holder.clone()),
+                lock_expires_at: lock.map(|lock| lock.locked_at.saturating_add(lock.lock_timeout)),
+                log_count: logs.count,
+                recent_logs: logs.recent,
+            });
+        }
+        Ok(views)
+    }
+
+    fn store_contracts(env: &Env, renewal: &Address, logging: &Option<Address>) {
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Renewal, renewal);
+        match logging {
+            Some(logging) => storage.set(&DataKey::Logging, logging),
+            None => storage.remove(&DataKey::Logging),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
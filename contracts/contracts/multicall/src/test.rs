@@ -0,0 +1,188 @@
+extern crate std;
+
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, vec, Address, BytesN, Env, String};
+
+/// Stand-in for the renewal contract serving whatever was `put`.
+#[contract]
+struct MockRenewal;
+
+#[contractimpl]
+impl MockRenewal {
+    pub fn put(env: Env, sub_id: u64, sub: SubscriptionData, lock: Option<RenewalLockData>) {
+        env.storage().instance().set(&sub_id, &(sub, lock));
+    }
+
+    pub fn get_sub(env: Env, sub_id: u64) -> SubscriptionData {
+        let (sub, _): (SubscriptionData, Option<RenewalLockData>) = env
+            .storage()
+            .instance()
+            .get(&sub_id)
+            .expect("Subscription not found");
+        sub
+    }
+
+    pub fn get_lifecycle(_env: Env, sub_id: u64) -> LifecycleTimestamps {
+        LifecycleTimestamps {
+            created_at: sub_id * 100,
+            activated_at: sub_id * 100,
+            last_renewed_at: 0,
+            canceled_at: 0,
+            paused_at: 0,
+            resumed_at: 0,
+        }
+    }
+
+    pub fn get_renewal_lock(env: Env, sub_id: u64) -> Option<RenewalLockData> {
+        let (_, lock): (SubscriptionData, Option<RenewalLockData>) =
+            env.storage().instance().get(&sub_id)?;
+        lock
+    }
+}
+
+/// Stand-in for the logging contract holding three entries per
+/// subscription.
+#[contract]
+struct MockLogs;
+
+#[contractimpl]
+impl MockLogs {
+    pub fn get_log_summary(env: Env, sub_id: u64, recent: u32) -> LogSummary {
+        let mut entries = Vec::new(&env);
+        for timestamp in 3_u64.saturating_sub(recent as u64)..3 {
+            entries.push_back(LogEntry {
+                sub_id,
+                event: LogEvent::Renewal,
+                timestamp,
+                data: String::from_str(&env, "Renewal successful"),
+            });
+        }
+        LogSummary {
+            count: 3,
+            recent: entries,
+        }
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: MulticallContractClient<'static>,
+    renewal: MockRenewalClient<'static>,
+    logging: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    let renewal = MockRenewalClient::new(&env, &env.register(MockRenewal, ()));
+    let logging = env.register(MockLogs, ());
+    let client = MulticallContractClient::new(&env, &env.register(MulticallContract, ()));
+    client.init(
+        &Address::generate(&env),
+        &renewal.address,
+        &Some(logging.clone()),
+    );
+    Setup {
+        env,
+        client,
+        renewal,
+        logging,
+    }
+}
+
+impl Setup {
+    fn add_sub(&self, sub_id: u64, locked: bool) -> SubscriptionData {
+        let sub = SubscriptionData {
+            owner: Address::generate(&self.env),
+            merchant: Address::generate(&self.env),
+            amount: 1_000,
+            frequency: 2_592_000,
+            spending_cap: 12_000,
+            integrity_hash: BytesN::from_array(&self.env, &[7; 32]),
+            state: SubscriptionState::Active,
+            failure_count: 0,
+            last_attempt_ledger: 0,
+        };
+        let lock = locked.then(|| RenewalLockData {
+            holder: Address::generate(&self.env),
+            locked_at: 10,
+            lock_timeout: 50,
+        });
+        self.renewal.put(&sub_id, &sub, &lock);
+        sub
+    }
+}
+
+#[test]
+fn test_view_gathers_every_read() {
+    let s = setup();
+    let sub = s.add_sub(1, true);
+
+    let view = s.client.get_view(&1, &2).unwrap();
+    assert_eq!(view.sub_id, 1);
+    assert_eq!(view.sub, sub);
+    assert_eq!(view.lifecycle.created_at, 100);
+    assert!(view.lock_holder.is_some());
+    assert_eq!(view.lock_expires_at, Some(60));
+    assert_eq!(view.log_count, 3);
+    let timestamps: std::vec::Vec<u64> = view.recent_logs.iter().map(|l| l.timestamp).collect();
+    assert_eq!(timestamps, [1, 2]);
+
+    assert_eq!(s.client.get_view(&2, &2), None);
+}
+
+#[test]
+fn test_multi_get_skips_unknown_subs() {
+    let s = setup();
+    s.add_sub(1, false);
+    s.add_sub(3, true);
+
+    let views = s.client.multi_get(&vec![&s.env, 3, 2, 1], &0);
+    let ids: std::vec::Vec<u64> = views.iter().map(|v| v.sub_id).collect();
+    assert_eq!(ids, [3, 1]);
+    assert!(views.get(0).unwrap().lock_holder.is_some());
+    assert_eq!(views.get(1).unwrap().lock_expires_at, None);
+    assert!(views.get(1).unwrap().recent_logs.is_empty());
+    assert!(s.client.multi_get(&vec![&s.env], &0).is_empty());
+}
+
+#[test]
+fn test_multi_get_is_bounded() {
+    let s = setup();
+    let mut sub_ids = Vec::new(&s.env);
+    for sub_id in 0..=MAX_SUBS as u64 {
+        sub_ids.push_back(sub_id);
+    }
+    assert_eq!(
+        s.client.try_multi_get(&sub_ids, &1),
+        Err(Ok(Error::TooManySubs))
+    );
+    assert_eq!(
+        s.client
+            .try_multi_get(&vec![&s.env, 1], &(MAX_RECENT_LOGS + 1)),
+        Err(Ok(Error::TooManyLogs))
+    );
+}
+
+#[test]
+fn test_logs_omitted_without_logging_contract() {
+    let s = setup();
+    s.add_sub(1, false);
+    s.client.set_contracts(&s.renewal.address, &None);
+    assert_eq!(s.client.get_logging(), None);
+    let view = s.client.get_view(&1, &5).unwrap();
+    assert_eq!(view.log_count, 0);
+    assert!(view.recent_logs.is_empty());
+
+    s.client
+        .set_contracts(&s.renewal.address, &Some(s.logging.clone()));
+    assert_eq!(s.client.get_view(&1, &5).unwrap().log_count, 3);
+}
+
+#[test]
+fn test_set_contracts_requires_admin() {
+    let s = setup();
+    s.env.set_auths(&[]);
+    assert!(s.client.try_set_contracts(&s.logging, &None).is_err());
+    assert_eq!(s.client.get_renewal(), s.renewal.address);
+}
@@ -0,0 +1,344 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, xdr::ToXdr, Address,
+    BytesN, Env, IntoVal, Symbol, TryFromVal, Val, Vec,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 801,
+    NotInitialized = 802,
+    NotSigner = 803,
+    InvalidThreshold = 804,
+    ProposalNotFound = 805,
+    AlreadyApproved = 806,
+    NotApproved = 807,
+    ThresholdNotMet = 808,
+    AlreadyExecuted = 809,
+    ProposalExpired = 810,
+    InvalidArgs = 811,
+    UnknownSelfCall = 812,
+    AlreadySigner = 813,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Signers,
+    Threshold,
+    ProposalCounter,
+    Proposal(u64),
+}
+
+/// A pending call to `function` on `target` with `args`, executed once
+/// `threshold` signers have approved it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub target: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+    pub approvals: Vec<Address>,
+    pub expires_at: u32,
+    pub executed: bool,
+}
+
+#[contractevent]
+pub struct ProposalCreated {
+    pub id: u64,
+    pub proposer: Address,
+    pub target: Address,
+    pub function: Symbol,
+}
+
+#[contractevent]
+pub struct ProposalApproved {
+    pub id: u64,
+    pub signer: Address,
+    pub approvals: u32,
+}
+
+#[contractevent]
+pub struct ApprovalRevoked {
+    pub id: u64,
+    pub signer: Address,
+    pub approvals: u32,
+}
+
+#[contractevent]
+pub struct ProposalExecuted {
+    pub id: u64,
+    pub target: Address,
+    pub function: Symbol,
+}
+
+#[contractevent]
+pub struct SignersChanged {
+    pub signer_count: u32,
+    pub threshold: u32,
+}
+
+#[contract]
+pub struct MultisigAdminContract;
+
+#[contractimpl]
+impl MultisigAdminContract {
+    /// Initialize the signer set and approval threshold.
+    pub fn init(env: Env, signers: Vec<Address>, threshold: u32) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Signers) {
+            return Err(Error::AlreadyInitialized);
+        }
+        Self::validate(&signers, threshold)?;
+        env.storage().instance().set(&DataKey::Signers, &signers);
+        env.storage().instance().set(&DataKey::Threshold, &threshold);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    pub fn get_signers(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Signers)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_threshold(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::Threshold)
+            .unwrap_or(0)
+    }
+
+    // ── Proposals ─────────────────────────────────────────────────
+
+    /// Propose a call. The proposer's approval is counted immediately.
+    pub fn propose(
+        env: Env,
+        proposer: Address,
+        target: Address,
+        function: Symbol,
+        args: Vec<Val>,
+        expires_at: u32,
+    ) -> Result<u64, Error> {
+        proposer.require_auth();
+        Self::require_signer(&env, &proposer)?;
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposalCounter)
+            .unwrap_or(0u64)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposalCounter, &id);
+
+        let mut approvals = Vec::new(&env);
+        approvals.push_back(proposer.clone());
+        let proposal = Proposal {
+            id,
+            proposer: proposer.clone(),
+            target: target.clone(),
+            function: function.clone(),
+            args,
+            approvals,
+            expires_at,
+            executed: false,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(id), &proposal);
+
+        ProposalCreated {
+            id,
+            proposer,
+            target,
+            function,
+        }
+        .publish(&env);
+        Ok(id)
+    }
+
+    /// Add a signer's approval to a pending proposal.
+    pub fn approve(env: Env, signer: Address, id: u64) -> Result<u32, Error> {
+        signer.require_auth();
+        Self::require_signer(&env, &signer)?;
+        let mut proposal = Self::load_pending(&env, id)?;
+
+        if proposal.approvals.contains(&signer) {
+            return Err(Error::AlreadyApproved);
+        }
+        proposal.approvals.push_back(signer.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(id), &proposal);
+
+        let approvals = proposal.approvals.len();
+        ProposalApproved {
+            id,
+            signer,
+            approvals,
+        }
+        .publish(&env);
+        Ok(approvals)
+    }
+
+    /// Withdraw a previously given approval.
+    pub fn revoke_approval(env: Env, signer: Address, id: u64) -> Result<u32, Error> {
+        signer.require_auth();
+        let mut proposal = Self::load_pending(&env, id)?;
+
+        let index = proposal
+            .approvals
+            .first_index_of(&signer)
+            .ok_or(Error::NotApproved)?;
+        proposal.approvals.remove(index);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(id), &proposal);
+
+        let approvals = proposal.approvals.len();
+        ApprovalRevoked {
+            id,
+            signer,
+            approvals,
+        }
+        .publish(&env);
+        Ok(approvals)
+    }
+
+    /// Execute a proposal once it has reached the threshold. Anyone may
+    /// trigger execution. Approvals from addresses that have since been
+    /// removed from the signer set are not counted.
+    pub fn execute(env: Env, id: u64) -> Result<Val, Error> {
+        let mut proposal = Self::load_pending(&env, id)?;
+
+        let signers = Self::get_signers(env.clone());
+        let valid = proposal
+            .approvals
+            .iter()
+            .filter(|a| signers.contains(a))
+            .count() as u32;
+        if valid < Self::get_threshold(env.clone()) {
+            return Err(Error::ThresholdNotMet);
+        }
+
+        // Mark executed before the external call so it cannot be replayed
+        proposal.executed = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(id), &proposal);
+
+        let result = if proposal.target == env.current_contract_address() {
+            // Contracts cannot re-enter themselves, so signer-set changes
+            // (add_signer, remove_signer, set_threshold) are dispatched
+            // internally.
+            Self::apply_self_call(&env, &proposal.function, &proposal.args)?;
+            ().into_val(&env)
+        } else {
+            env.invoke_contract::<Val>(&proposal.target, &proposal.function, proposal.args.clone())
+        };
+
+        ProposalExecuted {
+            id,
+            target: proposal.target,
+            function: proposal.function,
+        }
+        .publish(&env);
+        Ok(result)
+    }
+
+    pub fn get_proposal(env: Env, id: u64) -> Result<Proposal, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Proposal(id))
+            .ok_or(Error::ProposalNotFound)
+    }
+
+    /// SHA-256 over the XDR of (target, function, args), so signers can
+    /// check off-chain that a proposal encodes exactly the call they expect.
+    pub fn call_hash(env: Env, id: u64) -> Result<BytesN<32>, Error> {
+        let proposal = Self::get_proposal(env.clone(), id)?;
+        let encoded = (proposal.target, proposal.function, proposal.args).to_xdr(&env);
+        Ok(env.crypto().sha256(&encoded).into())
+    }
+
+    // ── Internal helpers ──────────────────────────────────────────
+
+    fn apply_self_call(env: &Env, function: &Symbol, args: &Vec<Val>) -> Result<(), Error> {
+        let mut signers = Self::get_signers(env.clone());
+        let mut threshold = Self::get_threshold(env.clone());
+
+        if *function == Symbol::new(env, "add_signer") {
+            let signer = Self::arg::<Address>(env, args, 0)?;
+            if signers.contains(&signer) {
+                return Err(Error::AlreadySigner);
+            }
+            signers.push_back(signer);
+        } else if *function == Symbol::new(env, "remove_signer") {
+            let signer = Self::arg::<Address>(env, args, 0)?;
+            let index = signers.first_index_of(&signer).ok_or(Error::NotSigner)?;
+            signers.remove(index);
+        } else if *function == Symbol::new(env, "set_threshold") {
+            threshold = Self::arg::<u32>(env, args, 0)?;
+        } else {
+            return Err(Error::UnknownSelfCall);
+        }
+
+        Self::validate(&signers, threshold)?;
+        env.storage().instance().set(&DataKey::Signers, &signers);
+        env.storage().instance().set(&DataKey::Threshold, &threshold);
+
+        SignersChanged {
+            signer_count: signers.len(),
+            threshold,
+        }
+        .publish(env);
+        Ok(())
+    }
+
+    fn arg<T: TryFromVal<Env, Val>>(env: &Env, args: &Vec<Val>, index: u32) -> Result<T, Error> {
+        let val = args.get(index).ok_or(Error::InvalidArgs)?;
+        T::try_from_val(env, &val).map_err(|_| Error::InvalidArgs)
+    }
+
+    fn validate(signers: &Vec<Address>, threshold: u32) -> Result<(), Error> {
+        if threshold == 0 || threshold > signers.len() {
+            return Err(Error::InvalidThreshold);
+        }
+        Ok(())
+    }
+
+    fn require_signer(env: &Env, who: &Address) -> Result<(), Error> {
+        if !Self::get_signers(env.clone()).contains(who) {
+            return Err(Error::NotSigner);
+        }
+        Ok(())
+    }
+
+    fn load_pending(env: &Env, id: u64) -> Result<Proposal, Error> {
+        let proposal = Self::get_proposal(env.clone(), id)?;
+        if proposal.executed {
+            return Err(Error::AlreadyExecuted);
+        }
+        if env.ledger().sequence() > proposal.expires_at {
+            return Err(Error::ProposalExpired);
+        }
+        Ok(proposal)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,229 @@
+use super::*;
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, IntoVal, Symbol,
+};
+
+/// Stand-in for a suite contract whose admin is the multisig.
+#[contract]
+struct MockTarget;
+
+#[contractimpl]
+impl MockTarget {
+    pub fn init(env: Env, admin: Address) {
+        env.storage().instance().set(&0u32, &admin);
+    }
+
+    pub fn set_paused(env: Env, paused: bool) {
+        let admin: Address = env.storage().instance().get(&0u32).unwrap();
+        admin.require_auth();
+        env.storage().instance().set(&1u32, &paused);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&1u32).unwrap_or(false)
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: MultisigAdminContractClient<'static>,
+    target: MockTargetClient<'static>,
+    signers: [Address; 3],
+}
+
+/// 2-of-3 multisig set as admin of a mock target.
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(MultisigAdminContract, ());
+    let client = MultisigAdminContractClient::new(&env, &contract_id);
+    let signers = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    client.init(
+        &vec![
+            &env,
+            signers[0].clone(),
+            signers[1].clone(),
+            signers[2].clone(),
+        ],
+        &2,
+    );
+
+    let target_id = env.register(MockTarget, ());
+    let target = MockTargetClient::new(&env, &target_id);
+    target.init(&contract_id);
+
+    Setup {
+        env,
+        client,
+        target,
+        signers,
+    }
+}
+
+fn propose_pause(s: &Setup) -> u64 {
+    s.client.propose(
+        &s.signers[0],
+        &s.target.address,
+        &Symbol::new(&s.env, "set_paused"),
+        &vec![&s.env, true.into_val(&s.env)],
+        &100,
+    )
+}
+
+#[test]
+fn test_execute_after_threshold() {
+    let s = setup();
+
+    let id = propose_pause(&s);
+    assert_eq!(s.client.approve(&s.signers[1], &id), 2);
+    s.client.execute(&id);
+
+    assert!(s.target.is_paused());
+    assert!(s.client.get_proposal(&id).executed);
+}
+
+#[test]
+fn test_execute_below_threshold_fails() {
+    let s = setup();
+
+    let id = propose_pause(&s);
+    assert_eq!(
+        s.client.try_execute(&id).err(),
+        Some(Ok(Error::ThresholdNotMet))
+    );
+    assert!(!s.target.is_paused());
+}
+
+#[test]
+fn test_cannot_execute_twice() {
+    let s = setup();
+
+    let id = propose_pause(&s);
+    s.client.approve(&s.signers[1], &id);
+    s.client.execute(&id);
+    assert_eq!(
+        s.client.try_execute(&id).err(),
+        Some(Ok(Error::AlreadyExecuted))
+    );
+}
+
+#[test]
+fn test_double_approval_rejected() {
+    let s = setup();
+
+    let id = propose_pause(&s);
+    assert_eq!(
+        s.client.try_approve(&s.signers[0], &id),
+        Err(Ok(Error::AlreadyApproved))
+    );
+}
+
+#[test]
+fn test_non_signer_cannot_propose_or_approve() {
+    let s = setup();
+
+    let outsider = Address::generate(&s.env);
+    let result = s.client.try_propose(
+        &outsider,
+        &s.target.address,
+        &Symbol::new(&s.env, "set_paused"),
+        &vec![&s.env, true.into_val(&s.env)],
+        &100,
+    );
+    assert_eq!(result, Err(Ok(Error::NotSigner)));
+
+    let id = propose_pause(&s);
+    assert_eq!(
+        s.client.try_approve(&outsider, &id),
+        Err(Ok(Error::NotSigner))
+    );
+}
+
+#[test]
+fn test_revoked_approval_not_counted() {
+    let s = setup();
+
+    let id = propose_pause(&s);
+    s.client.approve(&s.signers[1], &id);
+    assert_eq!(s.client.revoke_approval(&s.signers[1], &id), 1);
+    assert_eq!(
+        s.client.try_execute(&id).err(),
+        Some(Ok(Error::ThresholdNotMet))
+    );
+}
+
+#[test]
+fn test_expired_proposal_rejected() {
+    let s = setup();
+
+    let id = propose_pause(&s);
+    s.env.ledger().with_mut(|li| {
+        li.sequence_number = 101;
+    });
+    assert_eq!(
+        s.client.try_approve(&s.signers[1], &id),
+        Err(Ok(Error::ProposalExpired))
+    );
+}
+
+#[test]
+fn test_signer_set_changes_via_self_proposal() {
+    let s = setup();
+
+    let newcomer = Address::generate(&s.env);
+    let id = s.client.propose(
+        &s.signers[0],
+        &s.client.address,
+        &Symbol::new(&s.env, "add_signer"),
+        &vec![&s.env, newcomer.into_val(&s.env)],
+        &100,
+    );
+    s.client.approve(&s.signers[1], &id);
+    s.client.execute(&id);
+    assert_eq!(s.client.get_signers().len(), 4);
+
+    let id = s.client.propose(
+        &s.signers[0],
+        &s.client.address,
+        &Symbol::new(&s.env, "set_threshold"),
+        &vec![&s.env, 3u32.into_val(&s.env)],
+        &100,
+    );
+    s.client.approve(&newcomer, &id);
+    s.client.execute(&id);
+    assert_eq!(s.client.get_threshold(), 3);
+}
+
+#[test]
+fn test_threshold_above_signer_count_rejected() {
+    let s = setup();
+
+    let id = s.client.propose(
+        &s.signers[0],
+        &s.client.address,
+        &Symbol::new(&s.env, "set_threshold"),
+        &vec![&s.env, 4u32.into_val(&s.env)],
+        &100,
+    );
+    s.client.approve(&s.signers[1], &id);
+    assert_eq!(
+        s.client.try_execute(&id).err(),
+        Some(Ok(Error::InvalidThreshold))
+    );
+}
+
+#[test]
+fn test_call_hash_is_stable() {
+    let s = setup();
+
+    let first = propose_pause(&s);
+    let second = propose_pause(&s);
+    assert_eq!(s.client.call_hash(&first), s.client.call_hash(&second));
+}
@@ -0,0 +1,318 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, token,
+    Address, BytesN, Env, Val, Vec,
+};
+use syncro_common::SubscriptionState;
+
+const BPS: u32 = 10_000;
+/// Most tiers a merchant's discount schedule can have.
+pub const MAX_TIERS: u32 = 8;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1801,
+    NotInitialized = 1802,
+    InvalidSchedule = 1803,
+    InvalidAmount = 1804,
+    NotSubscriber = 1805,
+    AlreadyPrepaid = 1806,
+    NoDeposit = 1807,
+    CycleAlreadyDrawn = 1808,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Token,
+    Renewal,
+    Schedule(Address), // merchant -> Vec<DiscountTier>
+    Deposit(u64),      // sub_id -> TermDeposit
+}
+
+/// Mirror of the renewal contract's `SubscriptionData`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionData {
+    pub owner: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub integrity_hash: BytesN<32>,
+    pub state: SubscriptionState,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+/// The renewal contract: source of subscription terms and the only caller
+/// allowed to draw.
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn get_sub(env: Env, sub_id: u64) -> SubscriptionData;
+}
+
+/// Prepaying at least `min_cycles` cycles takes `discount_bps` off each.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiscountTier {
+    pub min_cycles: u32,
+    pub discount_bps: u32,
+}
+
+/// Funds set aside for a subscription's upcoming cycles.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TermDeposit {
+    pub owner: Address,
+    pub merchant: Address,
+    /// Discounted amount paid to the merchant each cycle.
+    pub per_cycle: i128,
+    pub discount_bps: u32,
+    pub cycles_bought: u32,
+    pub cycles_left: u32,
+    /// Last cycle drawn; 0 before the first draw.
+    pub last_cycle_id: u64,
+}
+
+#[contractevent]
+pub struct ScheduleSet {
+    pub merchant: Address,
+    pub tier_count: u32,
+}
+
+#[contractevent]
+pub struct TermPrepaid {
+    pub sub_id: u64,
+    pub owner: Address,
+    pub cycles: u32,
+    pub per_cycle: i128,
+    pub total: i128,
+}
+
+#[contractevent]
+pub struct CycleDrawn {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub amount: i128,
+    pub cycles_left: u32,
+}
+
+#[contractevent]
+pub struct PrepayCancelled {
+    pub sub_id: u64,
+    pub refunded: i128,
+}
+
+#[contract]
+pub struct PrepayContract;
+
+#[contractimpl]
+impl PrepayContract {
+    /// Initialize with an admin, the deposit token and the renewal contract
+    /// that draws each cycle.
+    pub fn init(env: Env, admin: Address, token: Address, renewal: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::Renewal, &renewal);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Merchant schedules ────────────────────────────────────────
+
+    /// Replace the merchant's discount schedule. Tiers must be in increasing
+    /// `min_cycles` order. Existing deposits keep the discount they were
+    /// bought at.
+    pub fn set_schedule(
+        env: Env,
+        merchant: Address,
+        tiers: Vec<DiscountTier>,
+    ) -> Result<(), Error> {
+        merchant.require_auth();
+        if tiers.len() > MAX_TIERS {
+            return Err(Error::InvalidSchedule);
+        }
+        let mut previous = 0u32;
+        for tier in tiers.iter() {
+            if tier.min_cycles <= previous || tier.discount_bps >= BPS {
+                return Err(Error::InvalidSchedule);
+            }
+            previous = tier.min_cycles;
+        }
+
+        let tier_count = tiers.len();
+        env.storage()
+            .persistent()
+            .set(&DataKey::Schedule(merchant.clone()), &tiers);
+        ScheduleSet {
+            merchant,
+            tier_count,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_schedule(env: Env, merchant: Address) -> Vec<DiscountTier> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Schedule(merchant))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Discount on prepaying `cycles` with `merchant`, in basis points.
+    pub fn discount_for(env: Env, merchant: Address, cycles: u32) -> u32 {
+        Self::get_schedule(env, merchant)
+            .iter()
+            .rev()
+            .find(|tier| tier.min_cycles <= cycles)
+            .map(|tier| tier.discount_bps)
+            .unwrap_or(0)
+    }
+
+    // ── Deposits ──────────────────────────────────────────────────
+
+    /// Prepay `cycles` renewals of `sub_id` at the merchant's discount for
+    /// that many cycles. The price is read from the renewal contract.
+    pub fn prepay(
+        env: Env,
+        owner: Address,
+        sub_id: u64,
+        cycles: u32,
+    ) -> Result<TermDeposit, Error> {
+        owner.require_auth();
+        if cycles == 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let sub = RenewalClient::new(&env, &Self::renewal(&env)?).get_sub(&sub_id);
+        if sub.owner != owner {
+            return Err(Error::NotSubscriber);
+        }
+        if let Some(existing) = Self::get_deposit(env.clone(), sub_id) {
+            if existing.cycles_left > 0 {
+                return Err(Error::AlreadyPrepaid);
+            }
+        }
+
+        let discount_bps = Self::discount_for(env.clone(), sub.merchant.clone(), cycles);
+        let per_cycle = sub.amount - sub.amount * discount_bps as i128 / BPS as i128;
+        if per_cycle <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let total = per_cycle * cycles as i128;
+        Self::token(&env)?.transfer(&owner, env.current_contract_address(), &total);
+
+        let deposit = TermDeposit {
+            owner: owner.clone(),
+            merchant: sub.merchant,
+            per_cycle,
+            discount_bps,
+            cycles_bought: cycles,
+            cycles_left: cycles,
+            last_cycle_id: 0,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Deposit(sub_id), &deposit);
+
+        TermPrepaid {
+            sub_id,
+            owner,
+            cycles,
+            per_cycle,
+            total,
+        }
+        .publish(&env);
+        Ok(deposit)
+    }
+
+    /// Called by the renewal contract on each successful renewal. Pays the
+    /// merchant one cycle from the deposit and returns the amount, or 0 when
+    /// the subscription has no prepaid cycles left.
+    pub fn draw(env: Env, sub_id: u64, cycle_id: u64) -> Result<i128, Error> {
+        Self::renewal(&env)?.require_auth();
+        let Some(mut deposit) = Self::get_deposit(env.clone(), sub_id) else {
+            return Ok(0);
+        };
+        if deposit.cycles_left == 0 {
+            return Ok(0);
+        }
+        if cycle_id <= deposit.last_cycle_id {
+            return Err(Error::CycleAlreadyDrawn);
+        }
+
+        deposit.cycles_left -= 1;
+        deposit.last_cycle_id = cycle_id;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Deposit(sub_id), &deposit);
+        Self::token(&env)?.transfer(
+            &env.current_contract_address(),
+            &deposit.merchant,
+            &deposit.per_cycle,
+        );
+
+        CycleDrawn {
+            sub_id,
+            cycle_id,
+            amount: deposit.per_cycle,
+            cycles_left: deposit.cycles_left,
+        }
+        .publish(&env);
+        Ok(deposit.per_cycle)
+    }
+
+    /// Close the deposit and refund the cycles not yet drawn. Owner only.
+    pub fn cancel(env: Env, sub_id: u64) -> Result<i128, Error> {
+        let deposit = Self::get_deposit(env.clone(), sub_id).ok_or(Error::NoDeposit)?;
+        deposit.owner.require_auth();
+
+        let refunded = deposit.per_cycle * deposit.cycles_left as i128;
+        env.storage().persistent().remove(&DataKey::Deposit(sub_id));
+        if refunded > 0 {
+            Self::token(&env)?.transfer(&env.current_contract_address(), &deposit.owner, &refunded);
+        }
+
+        PrepayCancelled { sub_id, refunded }.publish(&env);
+        Ok(refunded)
+    }
+
+    pub fn get_deposit(env: Env, sub_id: u64) -> Option<TermDeposit> {
+        env.storage().persistent().get(&DataKey::Deposit(sub_id))
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn renewal(env: &Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Renewal)
+            .ok_or(Error::NotInitialized)
+    }
+
+    fn token(env: &Env) -> Result<token::TokenClient<'_>, Error> {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::TokenClient::new(env, &token))
+    }
+}
+
+#[cfg(test)]
+mod test;
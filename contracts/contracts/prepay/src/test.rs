@@ -0,0 +1,161 @@
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, vec, Address, BytesN, Env};
+use syncro_testutils::TestToken;
+
+/// Stand-in for the renewal contract: serves whatever `set_sub` stored.
+#[contract]
+struct MockRenewal;
+
+#[contractimpl]
+impl MockRenewal {
+    pub fn set_sub(env: Env, sub_id: u64, data: SubscriptionData) {
+        env.storage().instance().set(&sub_id, &data);
+    }
+
+    pub fn get_sub(env: Env, sub_id: u64) -> SubscriptionData {
+        env.storage().instance().get(&sub_id).unwrap()
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: PrepayContractClient<'static>,
+    token: TestToken,
+    owner: Address,
+    merchant: Address,
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let token = TestToken::new(&env);
+    let renewal = MockRenewalClient::new(&env, &env.register(MockRenewal, ()));
+    let contract_id = env.register(PrepayContract, ());
+    let client = PrepayContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env), &token.address, &renewal.address);
+
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    token.mint(&owner, 10_000);
+    renewal.set_sub(
+        &1,
+        &SubscriptionData {
+            owner: owner.clone(),
+            merchant: merchant.clone(),
+            amount: 100,
+            frequency: 2_592_000,
+            spending_cap: 1_000,
+            integrity_hash: BytesN::from_array(&env, &[0; 32]),
+            state: SubscriptionState::Active,
+            failure_count: 0,
+            last_attempt_ledger: 0,
+        },
+    );
+    client.set_schedule(
+        &merchant,
+        &vec![
+            &env,
+            DiscountTier {
+                min_cycles: 3,
+                discount_bps: 500,
+            },
+            DiscountTier {
+                min_cycles: 12,
+                discount_bps: 1_500,
+            },
+        ],
+    );
+    Setup {
+        env,
+        client,
+        token,
+        owner,
+        merchant,
+    }
+}
+
+#[test]
+fn test_discount_by_term_length() {
+    let s = setup();
+    assert_eq!(s.client.discount_for(&s.merchant, &1), 0);
+    assert_eq!(s.client.discount_for(&s.merchant, &3), 500);
+    assert_eq!(s.client.discount_for(&s.merchant, &11), 500);
+    assert_eq!(s.client.discount_for(&s.merchant, &24), 1_500);
+
+    let deposit = s.client.prepay(&s.owner, &1, &12);
+    assert_eq!(deposit.per_cycle, 85);
+    assert_eq!(deposit.cycles_left, 12);
+    assert_eq!(s.token.balance(&s.owner), 10_000 - 1_020);
+}
+
+#[test]
+fn test_draw_pays_merchant_each_cycle() {
+    let s = setup();
+    s.client.prepay(&s.owner, &1, &3);
+
+    assert_eq!(s.client.draw(&1, &1), 95);
+    assert_eq!(s.client.try_draw(&1, &1), Err(Ok(Error::CycleAlreadyDrawn)));
+    assert_eq!(s.client.draw(&1, &2), 95);
+    assert_eq!(s.client.draw(&1, &3), 95);
+    assert_eq!(s.token.balance(&s.merchant), 285);
+
+    // Exhausted deposits and unknown subscriptions draw nothing
+    assert_eq!(s.client.draw(&1, &4), 0);
+    assert_eq!(s.client.draw(&2, &1), 0);
+
+    // A new term can be bought once the last one is used up
+    s.client.prepay(&s.owner, &1, &1);
+}
+
+#[test]
+fn test_cancel_refunds_remaining_cycles() {
+    let s = setup();
+    s.client.prepay(&s.owner, &1, &3);
+    assert_eq!(
+        s.client.try_prepay(&s.owner, &1, &3),
+        Err(Ok(Error::AlreadyPrepaid))
+    );
+    s.client.draw(&1, &1);
+
+    assert_eq!(s.client.cancel(&1), 190);
+    assert_eq!(s.token.balance(&s.owner), 10_000 - 95);
+    assert_eq!(s.client.get_deposit(&1), None);
+    assert_eq!(s.client.draw(&1, &2), 0);
+    assert_eq!(s.client.try_cancel(&1), Err(Ok(Error::NoDeposit)));
+}
+
+#[test]
+fn test_only_owner_prepays() {
+    let s = setup();
+    assert_eq!(
+        s.client.try_prepay(&Address::generate(&s.env), &1, &3),
+        Err(Ok(Error::NotSubscriber))
+    );
+    assert_eq!(
+        s.client.try_prepay(&s.owner, &1, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_invalid_schedules_rejected() {
+    let s = setup();
+    let tier = |min_cycles, discount_bps| DiscountTier {
+        min_cycles,
+        discount_bps,
+    };
+    assert_eq!(
+        s.client
+            .try_set_schedule(&s.merchant, &vec![&s.env, tier(6, 100), tier(3, 200)]),
+        Err(Ok(Error::InvalidSchedule))
+    );
+    assert_eq!(
+        s.client
+            .try_set_schedule(&s.merchant, &vec![&s.env, tier(3, 10_000)]),
+        Err(Ok(Error::InvalidSchedule))
+    );
+    assert_eq!(
+        s.client
+            .try_set_schedule(&s.merchant, &vec![&s.env, tier(0, 100)]),
+        Err(Ok(Error::InvalidSchedule))
+    );
+}
@@ -0,0 +1,244 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, String, Val,
+    Vec,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 601,
+    NotInitialized = 602,
+    ReceiptNotFound = 603,
+    DuplicateReceipt = 604,
+    NonTransferable = 605,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Minter,
+    Counter,
+    Receipt(u64),            // token_id -> Receipt
+    CycleReceipt(u64, u64),  // (sub_id, cycle_id) -> token_id
+    OwnerReceipts(Address),  // owner -> Vec<token_id>
+}
+
+/// Proof of payment for one successful renewal cycle.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Receipt {
+    pub token_id: u64,
+    pub owner: Address,
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub amount: i128,
+    pub merchant: Address,
+    pub timestamp: u64,
+}
+
+#[contractevent]
+pub struct ReceiptMinted {
+    pub token_id: u64,
+    pub owner: Address,
+    pub sub_id: u64,
+    pub cycle_id: u64,
+}
+
+#[contract]
+pub struct RenewalReceiptContract;
+
+#[contractimpl]
+impl RenewalReceiptContract {
+    /// Initialize with an admin and the renewal contract allowed to mint.
+    pub fn init(env: Env, admin: Address, minter: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Minter, &minter);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    /// Replace the minter (e.g. after a renewal contract redeploy). Admin only.
+    pub fn set_minter(env: Env, minter: Address) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Minter, &minter);
+        Ok(())
+    }
+
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, "SYNCRO Renewal Receipt")
+    }
+
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, "SRR")
+    }
+
+    /// Mint a receipt for a successful renewal. Minter only; one receipt per
+    /// (sub_id, cycle_id).
+    pub fn mint(
+        env: Env,
+        owner: Address,
+        sub_id: u64,
+        cycle_id: u64,
+        amount: i128,
+        merchant: Address,
+    ) -> Result<u64, Error> {
+        let minter: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Minter)
+            .ok_or(Error::NotInitialized)?;
+        minter.require_auth();
+
+        let cycle_key = DataKey::CycleReceipt(sub_id, cycle_id);
+        if env.storage().persistent().has(&cycle_key) {
+            return Err(Error::DuplicateReceipt);
+        }
+
+        let token_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::Counter)
+            .unwrap_or(0u64)
+            + 1;
+        env.storage().instance().set(&DataKey::Counter, &token_id);
+
+        let receipt = Receipt {
+            token_id,
+            owner: owner.clone(),
+            sub_id,
+            cycle_id,
+            amount,
+            merchant,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Receipt(token_id), &receipt);
+        env.storage().persistent().set(&cycle_key, &token_id);
+
+        let owner_key = DataKey::OwnerReceipts(owner.clone());
+        let mut owned: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&owner_key)
+            .unwrap_or(Vec::new(&env));
+        owned.push_back(token_id);
+        env.storage().persistent().set(&owner_key, &owned);
+
+        ReceiptMinted {
+            token_id,
+            owner,
+            sub_id,
+            cycle_id,
+        }
+        .publish(&env);
+
+        Ok(token_id)
+    }
+
+    /// Receipts are soulbound to the subscription owner.
+    pub fn transfer(
+        _env: Env,
+        _from: Address,
+        _to: Address,
+        _token_id: u64,
+    ) -> Result<(), Error> {
+        Err(Error::NonTransferable)
+    }
+
+    pub fn get_receipt(env: Env, token_id: u64) -> Result<Receipt, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Receipt(token_id))
+            .ok_or(Error::ReceiptNotFound)
+    }
+
+    pub fn owner_of(env: Env, token_id: u64) -> Result<Address, Error> {
+        Ok(Self::get_receipt(env, token_id)?.owner)
+    }
+
+    pub fn balance(env: Env, owner: Address) -> u32 {
+        Self::owned(&env, owner).len()
+    }
+
+    /// Page through an owner's receipt ids in mint order.
+    pub fn receipts_of(env: Env, owner: Address, offset: u32, limit: u32) -> Vec<u64> {
+        let owned = Self::owned(&env, owner);
+        let end = offset.saturating_add(limit).min(owned.len());
+        if offset >= end {
+            return Vec::new(&env);
+        }
+        owned.slice(offset..end)
+    }
+
+    /// Look up the receipt minted for a given cycle, if any.
+    pub fn receipt_for_cycle(env: Env, sub_id: u64, cycle_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CycleReceipt(sub_id, cycle_id))
+    }
+
+    /// Access-gating helper: true if `owner` holds a receipt for `cycle_id`
+    /// of `sub_id`.
+    pub fn verify(env: Env, owner: Address, sub_id: u64, cycle_id: u64) -> bool {
+        match Self::receipt_for_cycle(env.clone(), sub_id, cycle_id) {
+            Some(token_id) => match Self::get_receipt(env, token_id) {
+                Ok(receipt) => receipt.owner == owner,
+                Err(_) => false,
+            },
+            None => false,
+        }
+    }
+
+    /// Access-gating helper: true if `owner` has paid `merchant` at or after
+    /// `since` (Unix seconds).
+    pub fn has_paid_since(env: Env, owner: Address, merchant: Address, since: u64) -> bool {
+        let owned = Self::owned(&env, owner);
+        // Newest receipts are last, so scan backwards and stop early
+        for i in (0..owned.len()).rev() {
+            let token_id = owned.get_unchecked(i);
+            if let Some(receipt) = env
+                .storage()
+                .persistent()
+                .get::<_, Receipt>(&DataKey::Receipt(token_id))
+            {
+                if receipt.timestamp < since {
+                    return false;
+                }
+                if receipt.merchant == merchant {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn owned(env: &Env, owner: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OwnerReceipts(owner))
+            .unwrap_or(Vec::new(env))
+    }
+}
+
+#[cfg(test)]
+mod test;
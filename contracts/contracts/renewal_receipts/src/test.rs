@@ -0,0 +1,133 @@
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+fn setup() -> (Env, RenewalReceiptContractClient<'static>) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(RenewalReceiptContract, ());
+    let client = RenewalReceiptContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let minter = Address::generate(&env);
+    client.init(&admin, &minter);
+
+    (env, client)
+}
+
+#[test]
+fn test_mint_and_query() {
+    let (env, client) = setup();
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1700000000;
+    });
+
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let token_id = client.mint(&owner, &7, &20260101, &500, &merchant);
+    assert_eq!(token_id, 1);
+
+    let receipt = client.get_receipt(&token_id);
+    assert_eq!(receipt.owner, owner);
+    assert_eq!(receipt.sub_id, 7);
+    assert_eq!(receipt.cycle_id, 20260101);
+    assert_eq!(receipt.amount, 500);
+    assert_eq!(receipt.merchant, merchant);
+    assert_eq!(receipt.timestamp, 1700000000);
+
+    assert_eq!(client.owner_of(&token_id), owner);
+    assert_eq!(client.balance(&owner), 1);
+    assert_eq!(client.receipt_for_cycle(&7, &20260101), Some(token_id));
+}
+
+#[test]
+fn test_duplicate_cycle_rejected() {
+    let (env, client) = setup();
+
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.mint(&owner, &7, &20260101, &500, &merchant);
+
+    let result = client.try_mint(&owner, &7, &20260101, &500, &merchant);
+    assert_eq!(result, Err(Ok(Error::DuplicateReceipt)));
+}
+
+#[test]
+fn test_receipts_are_non_transferable() {
+    let (env, client) = setup();
+
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let token_id = client.mint(&owner, &7, &20260101, &500, &merchant);
+
+    let result = client.try_transfer(&owner, &other, &token_id);
+    assert_eq!(result, Err(Ok(Error::NonTransferable)));
+    assert_eq!(client.owner_of(&token_id), owner);
+}
+
+#[test]
+fn test_verify_checks_owner() {
+    let (env, client) = setup();
+
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.mint(&owner, &7, &20260101, &500, &merchant);
+
+    assert!(client.verify(&owner, &7, &20260101));
+    assert!(!client.verify(&other, &7, &20260101));
+    assert!(!client.verify(&owner, &7, &20260201));
+}
+
+#[test]
+fn test_has_paid_since() {
+    let (env, client) = setup();
+
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let other_merchant = Address::generate(&env);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+    client.mint(&owner, &7, &1, &500, &merchant);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+    client.mint(&owner, &8, &1, &500, &other_merchant);
+
+    assert!(client.has_paid_since(&owner, &merchant, &1000));
+    assert!(!client.has_paid_since(&owner, &merchant, &1500));
+    assert!(client.has_paid_since(&owner, &other_merchant, &1500));
+}
+
+#[test]
+fn test_receipts_of_paginates() {
+    let (env, client) = setup();
+
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    for cycle in 1..=5u64 {
+        client.mint(&owner, &7, &cycle, &500, &merchant);
+    }
+
+    let page = client.receipts_of(&owner, &1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap(), 2);
+    assert_eq!(page.get(1).unwrap(), 3);
+
+    assert_eq!(client.receipts_of(&owner, &4, &10).len(), 1);
+    assert_eq!(client.receipts_of(&owner, &10, &10).len(), 0);
+}
+
+#[test]
+fn test_missing_receipt() {
+    let (_env, client) = setup();
+    assert_eq!(client.try_get_receipt(&42), Err(Ok(Error::ReceiptNotFound)));
+}
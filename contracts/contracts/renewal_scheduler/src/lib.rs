@@ -0,0 +1,295 @@
+#![no_std]
+//! Shared queue of upcoming renewals.
+//!
+//! The renewal contract schedules each subscription's next due ledger here
+//! whenever it is created or renewed, so keepers can take the next batch of
+//! due renewals with `pop_due` instead of each indexing due dates on their
+//! own. Entries are kept in a binary min-heap ordered by
+//! `(due_ledger, sub_id)`, one storage slot per entry, with each
+//! subscription's slot indexed so rescheduling moves it in place.
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Val, Vec,
+};
+use syncro_access::AccessError;
+
+/// Role bit held by keepers allowed to pop due renewals.
+pub const KEEPER: u32 = 1;
+/// Most entries handed out by one `pop_due`.
+pub const MAX_BATCH: u32 = 50;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2701,
+    NotInitialized = 2702,
+    NoPendingAdmin = 2703,
+    NotKeeper = 2704,
+    InvalidLimit = 2705,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::NotKeeper,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Renewal,
+    Len,
+    Slot(u32),     // heap index -> QueueEntry
+    Position(u64), // sub_id -> heap index
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueueEntry {
+    pub due_ledger: u32,
+    pub sub_id: u64,
+}
+
+impl QueueEntry {
+    fn before(&self, other: &QueueEntry) -> bool {
+        (self.due_ledger, self.sub_id) < (other.due_ledger, other.sub_id)
+    }
+}
+
+#[contractevent]
+pub struct RenewalScheduled {
+    pub sub_id: u64,
+    pub due_ledger: u32,
+}
+
+#[contractevent]
+pub struct RenewalUnscheduled {
+    pub sub_id: u64,
+}
+
+#[contractevent]
+pub struct DueBatchPopped {
+    pub keeper: Address,
+    pub count: u32,
+}
+
+#[contract]
+pub struct RenewalSchedulerContract;
+
+#[contractimpl]
+impl RenewalSchedulerContract {
+    /// Initialize with an admin and the renewal contract allowed to
+    /// schedule renewals.
+    pub fn init(env: Env, admin: Address, renewal: Address) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::Renewal, &renewal);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Keepers (admin only) ──────────────────────────────────────
+
+    pub fn add_keeper(env: Env, keeper: Address) -> Result<(), Error> {
+        syncro_access::grant_role(&env, &keeper, KEEPER)?;
+        Ok(())
+    }
+
+    pub fn remove_keeper(env: Env, keeper: Address) -> Result<(), Error> {
+        syncro_access::revoke_role(&env, &keeper, KEEPER)?;
+        Ok(())
+    }
+
+    pub fn is_keeper(env: Env, account: Address) -> bool {
+        syncro_access::has_role(&env, &account, KEEPER)
+    }
+
+    // ── Scheduling (renewal contract only) ────────────────────────
+
+    /// Queue `sub_id` for `due_ledger`, moving it if already queued.
+    pub fn schedule(env: Env, sub_id: u64, due_ledger: u32) -> Result<(), Error> {
+        Self::require_renewal(&env)?;
+        let entry = QueueEntry { due_ledger, sub_id };
+        match Self::position(&env, sub_id) {
+            Some(index) => {
+                let previous = Self::slot(&env, index);
+                Self::put(&env, index, &entry);
+                if entry.before(&previous) {
+                    Self::sift_up(&env, index);
+                } else {
+                    Self::sift_down(&env, index);
+                }
+            }
+            None => {
+                let len = Self::queue_len(env.clone());
+                Self::put(&env, len, &entry);
+                env.storage().instance().set(&DataKey::Len, &(len + 1));
+                Self::sift_up(&env, len);
+            }
+        }
+        RenewalScheduled { sub_id, due_ledger }.publish(&env);
+        Ok(())
+    }
+
+    /// Drop `sub_id` from the queue. Returns whether it was queued.
+    pub fn unschedule(env: Env, sub_id: u64) -> Result<bool, Error> {
+        Self::require_renewal(&env)?;
+        let Some(index) = Self::position(&env, sub_id) else {
+            return Ok(false);
+        };
+        Self::remove_at(&env, index);
+        RenewalUnscheduled { sub_id }.publish(&env);
+        Ok(true)
+    }
+
+    // ── Keeper queries ────────────────────────────────────────────
+
+    /// Take up to `limit` entries due at or before the current ledger,
+    /// earliest first. Taken entries leave the queue, so concurrent keepers
+    /// get disjoint batches; the renewal contract queues each subscription
+    /// again once it renews or is due for a retry.
+    pub fn pop_due(env: Env, keeper: Address, limit: u32) -> Result<Vec<QueueEntry>, Error> {
+        syncro_access::require_role(&env, &keeper, KEEPER)?;
+        if limit == 0 || limit > MAX_BATCH {
+            return Err(Error::InvalidLimit);
+        }
+
+        let now = env.ledger().sequence();
+        let mut batch = Vec::new(&env);
+        while batch.len() < limit {
+            match Self::peek(env.clone()) {
+                Some(top) if top.due_ledger <= now => {
+                    Self::remove_at(&env, 0);
+                    batch.push_back(top);
+                }
+                _ => break,
+            }
+        }
+
+        if !batch.is_empty() {
+            DueBatchPopped {
+                keeper,
+                count: batch.len(),
+            }
+            .publish(&env);
+        }
+        Ok(batch)
+    }
+
+    /// The earliest queued entry, due or not.
+    pub fn peek(env: Env) -> Option<QueueEntry> {
+        env.storage().persistent().get(&DataKey::Slot(0))
+    }
+
+    pub fn queue_len(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::Len).unwrap_or(0)
+    }
+
+    pub fn get_due_ledger(env: Env, sub_id: u64) -> Option<u32> {
+        Self::position(&env, sub_id).map(|index| Self::slot(&env, index).due_ledger)
+    }
+
+    // ── Heap ──────────────────────────────────────────────────────
+
+    fn require_renewal(env: &Env) -> Result<(), Error> {
+        let renewal: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Renewal)
+            .ok_or(Error::NotInitialized)?;
+        renewal.require_auth();
+        Ok(())
+    }
+
+    fn position(env: &Env, sub_id: u64) -> Option<u32> {
+        env.storage().persistent().get(&DataKey::Position(sub_id))
+    }
+
+    fn slot(env: &Env, index: u32) -> QueueEntry {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Slot(index))
+            .expect("heap slot missing")
+    }
+
+    fn put(env: &Env, index: u32, entry: &QueueEntry) {
+        env.storage().persistent().set(&DataKey::Slot(index), entry);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Position(entry.sub_id), &index);
+    }
+
+    /// Replace the entry at `index` with the last one and restore order.
+    fn remove_at(env: &Env, index: u32) {
+        let last = Self::queue_len(env.clone()) - 1;
+        let removed = Self::slot(env, index);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Position(removed.sub_id));
+        if index != last {
+            let moved = Self::slot(env, last);
+            Self::put(env, index, &moved);
+        }
+        env.storage().persistent().remove(&DataKey::Slot(last));
+        env.storage().instance().set(&DataKey::Len, &last);
+        if index < last {
+            Self::sift_up(env, index);
+            Self::sift_down(env, index);
+        }
+    }
+
+    fn sift_up(env: &Env, mut index: u32) {
+        let entry = Self::slot(env, index);
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            let above = Self::slot(env, parent);
+            if !entry.before(&above) {
+                break;
+            }
+            Self::put(env, index, &above);
+            index = parent;
+        }
+        Self::put(env, index, &entry);
+    }
+
+    fn sift_down(env: &Env, mut index: u32) {
+        let len = Self::queue_len(env.clone());
+        let entry = Self::slot(env, index);
+        loop {
+            let left = 2 * index + 1;
+            if left >= len {
+                break;
+            }
+            let mut child = left;
+            let mut below = Self::slot(env, left);
+            if left + 1 < len {
+                let right = Self::slot(env, left + 1);
+                if right.before(&below) {
+                    child = left + 1;
+                    below = right;
+                }
+            }
+            if !below.before(&entry) {
+                break;
+            }
+            Self::put(env, index, &below);
+            index = child;
+        }
+        Self::put(env, index, &entry);
+    }
+}
+
+#[cfg(test)]
+mod test;
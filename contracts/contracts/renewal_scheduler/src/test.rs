@@ -0,0 +1,158 @@
+extern crate std;
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+struct Setup {
+    env: Env,
+    client: RenewalSchedulerContractClient<'static>,
+    keeper: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.sequence_number = 1_000);
+    let client =
+        RenewalSchedulerContractClient::new(&env, &env.register(RenewalSchedulerContract, ()));
+    client.init(&Address::generate(&env), &Address::generate(&env));
+
+    let keeper = Address::generate(&env);
+    client.add_keeper(&keeper);
+    Setup {
+        env,
+        client,
+        keeper,
+    }
+}
+
+impl Setup {
+    fn pop(&self, limit: u32) -> std::vec::Vec<(u32, u64)> {
+        self.client
+            .pop_due(&self.keeper, &limit)
+            .iter()
+            .map(|e| (e.due_ledger, e.sub_id))
+            .collect()
+    }
+}
+
+#[test]
+fn test_pop_due_returns_earliest_first() {
+    let s = setup();
+    s.client.schedule(&1, &990);
+    s.client.schedule(&2, &1_200);
+    s.client.schedule(&3, &950);
+    s.client.schedule(&4, &1_000);
+
+    assert_eq!(s.pop(10), [(950, 3), (990, 1), (1_000, 4)]);
+    assert_eq!(s.client.queue_len(), 1);
+    assert_eq!(
+        s.client.peek(),
+        Some(QueueEntry {
+            due_ledger: 1_200,
+            sub_id: 2
+        })
+    );
+}
+
+#[test]
+fn test_pop_due_respects_limit() {
+    let s = setup();
+    for sub_id in 1..=5 {
+        s.client.schedule(&sub_id, &(900 + sub_id as u32));
+    }
+    assert_eq!(s.pop(2), [(901, 1), (902, 2)]);
+    assert_eq!(s.pop(2), [(903, 3), (904, 4)]);
+    assert_eq!(s.client.queue_len(), 1);
+}
+
+#[test]
+fn test_nothing_due_yet() {
+    let s = setup();
+    s.client.schedule(&1, &1_001);
+    assert!(s.pop(10).is_empty());
+
+    s.env.ledger().with_mut(|li| li.sequence_number = 1_001);
+    assert_eq!(s.pop(10), [(1_001, 1)]);
+}
+
+#[test]
+fn test_rescheduling_moves_entry() {
+    let s = setup();
+    s.client.schedule(&1, &900);
+    s.client.schedule(&2, &950);
+    s.client.schedule(&1, &2_000);
+    assert_eq!(s.client.queue_len(), 2);
+    assert_eq!(s.client.get_due_ledger(&1), Some(2_000));
+    assert_eq!(s.pop(10), [(950, 2)]);
+
+    s.client.schedule(&1, &800);
+    assert_eq!(s.pop(10), [(800, 1)]);
+}
+
+#[test]
+fn test_unschedule_removes_entry() {
+    let s = setup();
+    s.client.schedule(&1, &900);
+    s.client.schedule(&2, &950);
+    s.client.schedule(&3, &980);
+
+    assert!(s.client.unschedule(&1));
+    assert!(!s.client.unschedule(&1));
+    assert_eq!(s.client.get_due_ledger(&1), None);
+    assert_eq!(s.pop(10), [(950, 2), (980, 3)]);
+}
+
+#[test]
+fn test_only_keepers_pop() {
+    let s = setup();
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_pop_due(&stranger, &1).unwrap_err().unwrap(),
+        Error::NotKeeper
+    );
+    assert_eq!(
+        s.client
+            .try_pop_due(&s.keeper, &(MAX_BATCH + 1))
+            .unwrap_err()
+            .unwrap(),
+        Error::InvalidLimit
+    );
+
+    s.client.remove_keeper(&s.keeper);
+    assert!(!s.client.is_keeper(&s.keeper));
+}
+
+#[test]
+fn test_heap_matches_sorted_model() {
+    let s = setup();
+    let mut model: std::collections::BTreeMap<u64, u32> = Default::default();
+
+    // Deterministic mix of inserts, reschedules and removals
+    let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+    for _ in 0..120 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        let sub_id = seed % 24;
+        let due = (seed >> 32) as u32 % 500;
+        if seed.is_multiple_of(5) {
+            assert_eq!(
+                s.client.unschedule(&sub_id),
+                model.remove(&sub_id).is_some()
+            );
+        } else {
+            s.client.schedule(&sub_id, &due);
+            model.insert(sub_id, due);
+        }
+    }
+
+    let mut expected: std::vec::Vec<(u32, u64)> =
+        model.iter().map(|(&sub_id, &due)| (due, sub_id)).collect();
+    expected.sort();
+    assert_eq!(s.client.queue_len(), expected.len() as u32);
+    assert_eq!(s.pop(MAX_BATCH), expected);
+}
@@ -0,0 +1,345 @@
+#![no_std]
+//! Merchant-funded renewal sponsorship.
+//!
+//! Merchants deposit tokens into a pool and set how much a keeper is paid
+//! back for each renewal of one of their subscriptions. Keepers submit the
+//! renewal through `sponsored_renew` and are reimbursed from the pool, so
+//! subscribers never need to hold fee funds themselves.
+
+// `sponsored_renew` forwards every argument of the renewal contract's `renew`,
+// and the generated client and args builders cannot be annotated one by one.
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, token,
+    Address, BytesN, Env, Val, Vec,
+};
+use syncro_access::AccessError;
+use syncro_common::SubscriptionState;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2501,
+    NotInitialized = 2502,
+    NoPendingAdmin = 2503,
+    Unauthorized = 2504,
+    InvalidAmount = 2505,
+    PoolNotFound = 2506,
+    InsufficientBalance = 2507,
+    NotSponsored = 2508,
+    AlreadyReimbursed = 2509,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Token,
+    Renewal,
+    MaxReimbursement,
+    Pool(Address),        // merchant -> Pool
+    Reimbursed(u64, u64), // (sub_id, cycle_id) -> keeper
+}
+
+/// Funds a merchant has set aside for renewals of their subscriptions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pool {
+    pub balance: i128,
+    /// Paid to the keeper for each successful renewal.
+    pub per_renewal: i128,
+}
+
+/// Mirror of the renewal contract's `SubscriptionData`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionData {
+    pub owner: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub integrity_hash: BytesN<32>,
+    pub state: SubscriptionState,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+/// Subset of the renewal contract used by keepers submitting sponsored
+/// renewals.
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn get_sub(env: Env, sub_id: u64) -> SubscriptionData;
+    fn acquire_renewal_lock(env: Env, agent: Address, sub_id: u64, lock_timeout: u32);
+    fn renew(env: Env, sub_id: u64, approval_id: u64, amount: i128, cycle_id: u64) -> bool;
+}
+
+#[contractevent]
+pub struct SponsorshipFunded {
+    pub merchant: Address,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+#[contractevent]
+pub struct SponsorshipWithdrawn {
+    pub merchant: Address,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+#[contractevent]
+pub struct ReimbursementSet {
+    pub merchant: Address,
+    pub per_renewal: i128,
+}
+
+#[contractevent]
+pub struct RenewalSponsored {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub merchant: Address,
+    pub keeper: Address,
+    pub reimbursed: i128,
+}
+
+#[contract]
+pub struct SponsorshipContract;
+
+#[contractimpl]
+impl SponsorshipContract {
+    /// Initialize with an admin, the token pools are held in, the renewal
+    /// contract keepers execute against, and the most a merchant may pay
+    /// per renewal.
+    pub fn init(
+        env: Env,
+        admin: Address,
+        token: Address,
+        renewal: Address,
+        max_reimbursement: i128,
+    ) -> Result<(), Error> {
+        if max_reimbursement <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        syncro_access::init_admin(&env, &admin)?;
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Token, &token);
+        storage.set(&DataKey::Renewal, &renewal);
+        storage.set(&DataKey::MaxReimbursement, &max_reimbursement);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Configuration (admin only) ────────────────────────────────
+
+    /// Lower or raise the per-renewal ceiling. Pools already above a lowered
+    /// ceiling are paid out at the ceiling.
+    pub fn set_max_reimbursement(env: Env, max_reimbursement: i128) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        if max_reimbursement <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxReimbursement, &max_reimbursement);
+        Ok(())
+    }
+
+    pub fn get_max_reimbursement(env: Env) -> Result<i128, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxReimbursement)
+            .ok_or(Error::NotInitialized)
+    }
+
+    // ── Merchant pools ────────────────────────────────────────────
+
+    /// Add funds to the merchant's pool, creating it if needed.
+    pub fn deposit(env: Env, merchant: Address, amount: i128) -> Result<i128, Error> {
+        merchant.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut pool = Self::get_pool(env.clone(), merchant.clone()).unwrap_or(Pool {
+            balance: 0,
+            per_renewal: 0,
+        });
+        Self::token(&env)?.transfer(&merchant, env.current_contract_address(), &amount);
+        pool.balance = pool
+            .balance
+            .checked_add(amount)
+            .ok_or(Error::InvalidAmount)?;
+        Self::save_pool(&env, &merchant, &pool);
+
+        SponsorshipFunded {
+            merchant,
+            amount,
+            balance: pool.balance,
+        }
+        .publish(&env);
+        Ok(pool.balance)
+    }
+
+    /// Set what a keeper is paid per renewal. Zero pauses sponsorship
+    /// without withdrawing the pool.
+    pub fn set_reimbursement(env: Env, merchant: Address, per_renewal: i128) -> Result<(), Error> {
+        merchant.require_auth();
+        if per_renewal < 0 || per_renewal > Self::get_max_reimbursement(env.clone())? {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut pool = Self::get_pool(env.clone(), merchant.clone()).ok_or(Error::PoolNotFound)?;
+        pool.per_renewal = per_renewal;
+        Self::save_pool(&env, &merchant, &pool);
+
+        ReimbursementSet {
+            merchant,
+            per_renewal,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Return unspent funds to the merchant.
+    pub fn withdraw(env: Env, merchant: Address, amount: i128) -> Result<i128, Error> {
+        merchant.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut pool = Self::get_pool(env.clone(), merchant.clone()).ok_or(Error::PoolNotFound)?;
+        if amount > pool.balance {
+            return Err(Error::InsufficientBalance);
+        }
+        pool.balance -= amount;
+        Self::save_pool(&env, &merchant, &pool);
+        Self::token(&env)?.transfer(&env.current_contract_address(), &merchant, &amount);
+
+        SponsorshipWithdrawn {
+            merchant,
+            amount,
+            balance: pool.balance,
+        }
+        .publish(&env);
+        Ok(pool.balance)
+    }
+
+    pub fn get_pool(env: Env, merchant: Address) -> Option<Pool> {
+        env.storage().persistent().get(&DataKey::Pool(merchant))
+    }
+
+    /// What a keeper would currently be paid for renewing a subscription of
+    /// `merchant`, or zero if the merchant isn't sponsoring renewals.
+    pub fn quote(env: Env, merchant: Address) -> i128 {
+        let Some(pool) = Self::get_pool(env.clone(), merchant) else {
+            return 0;
+        };
+        let max = Self::get_max_reimbursement(env).unwrap_or(0);
+        let reimbursement = pool.per_renewal.min(max);
+        if pool.balance < reimbursement {
+            return 0;
+        }
+        reimbursement
+    }
+
+    // ── Sponsored renewals ────────────────────────────────────────
+
+    /// Acquire the renewal lock in the keeper's name and renew, then pay the
+    /// keeper from the merchant's pool if the renewal succeeded. The pool
+    /// must cover a full reimbursement before the renewal is attempted, so a
+    /// keeper is never left paying fees for an unfunded merchant. Each cycle
+    /// is reimbursed at most once. Returns the amount paid.
+    pub fn sponsored_renew(
+        env: Env,
+        keeper: Address,
+        sub_id: u64,
+        approval_id: u64,
+        amount: i128,
+        cycle_id: u64,
+        lock_timeout: u32,
+    ) -> Result<i128, Error> {
+        keeper.require_auth();
+        let reimbursed_key = DataKey::Reimbursed(sub_id, cycle_id);
+        if env.storage().persistent().has(&reimbursed_key) {
+            return Err(Error::AlreadyReimbursed);
+        }
+
+        let renewal_addr: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Renewal)
+            .ok_or(Error::NotInitialized)?;
+        let renewal = RenewalClient::new(&env, &renewal_addr);
+        let merchant = renewal.get_sub(&sub_id).merchant;
+        let reimbursement = Self::quote(env.clone(), merchant.clone());
+        if reimbursement == 0 {
+            return Err(Error::NotSponsored);
+        }
+
+        renewal.acquire_renewal_lock(&keeper, &sub_id, &lock_timeout);
+        let renewed = renewal.renew(&sub_id, &approval_id, &amount, &cycle_id);
+        if !renewed {
+            return Ok(0);
+        }
+
+        env.storage().persistent().set(&reimbursed_key, &keeper);
+        let mut pool = Self::get_pool(env.clone(), merchant.clone()).ok_or(Error::PoolNotFound)?;
+        pool.balance -= reimbursement;
+        Self::save_pool(&env, &merchant, &pool);
+        Self::token(&env)?.transfer(&env.current_contract_address(), &keeper, &reimbursement);
+
+        RenewalSponsored {
+            sub_id,
+            cycle_id,
+            merchant,
+            keeper,
+            reimbursed: reimbursement,
+        }
+        .publish(&env);
+        Ok(reimbursement)
+    }
+
+    pub fn get_reimbursed_keeper(env: Env, sub_id: u64, cycle_id: u64) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Reimbursed(sub_id, cycle_id))
+    }
+
+    fn save_pool(env: &Env, merchant: &Address, pool: &Pool) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Pool(merchant.clone()), pool);
+    }
+
+    fn token(env: &Env) -> Result<token::TokenClient<'_>, Error> {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::TokenClient::new(env, &token))
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,198 @@
+use super::*;
+use soroban_sdk::{contract, symbol_short, testutils::Address as _, Address, BytesN, Env};
+use syncro_testutils::TestToken;
+
+/// Stand-in for the renewal contract: renewals need a lock and succeed
+/// unless set failing.
+#[contract]
+struct MockRenewal;
+
+#[contractimpl]
+impl MockRenewal {
+    pub fn set_sub(env: Env, sub_id: u64, data: SubscriptionData) {
+        env.storage().instance().set(&sub_id, &data);
+    }
+
+    pub fn get_sub(env: Env, sub_id: u64) -> SubscriptionData {
+        env.storage().instance().get(&sub_id).unwrap()
+    }
+
+    pub fn set_failing(env: Env, failing: bool) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("failing"), &failing);
+    }
+
+    pub fn acquire_renewal_lock(env: Env, agent: Address, sub_id: u64, _lock_timeout: u32) {
+        agent.require_auth();
+        env.storage().instance().set(&(sub_id, 0u32), &agent);
+    }
+
+    pub fn renew(env: Env, sub_id: u64, _approval_id: u64, _amount: i128, _cycle_id: u64) -> bool {
+        assert!(env.storage().instance().has(&(sub_id, 0u32)));
+        env.storage().instance().remove(&(sub_id, 0u32));
+        !env.storage()
+            .instance()
+            .get(&symbol_short!("failing"))
+            .unwrap_or(false)
+    }
+}
+
+const SUB_ID: u64 = 1;
+const MAX: i128 = 50;
+
+struct Setup {
+    client: SponsorshipContractClient<'static>,
+    renewal: MockRenewalClient<'static>,
+    token: TestToken,
+    merchant: Address,
+    keeper: Address,
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let token = TestToken::new(&env);
+    let renewal = MockRenewalClient::new(&env, &env.register(MockRenewal, ()));
+    let client = SponsorshipContractClient::new(&env, &env.register(SponsorshipContract, ()));
+    client.init(
+        &Address::generate(&env),
+        &token.address,
+        &renewal.address,
+        &MAX,
+    );
+
+    let merchant = Address::generate(&env);
+    token.mint(&merchant, 1_000);
+    renewal.set_sub(
+        &SUB_ID,
+        &SubscriptionData {
+            owner: Address::generate(&env),
+            merchant: merchant.clone(),
+            amount: 100,
+            frequency: 2_592_000,
+            spending_cap: 1_200,
+            integrity_hash: BytesN::from_array(&env, &[0; 32]),
+            state: SubscriptionState::Active,
+            failure_count: 0,
+            last_attempt_ledger: 0,
+        },
+    );
+    Setup {
+        client,
+        renewal,
+        token,
+        merchant,
+        keeper: Address::generate(&env),
+    }
+}
+
+impl Setup {
+    fn renew(&self, cycle_id: u64, succeed: bool) -> Result<i128, Error> {
+        self.renewal.set_failing(&!succeed);
+        match self
+            .client
+            .try_sponsored_renew(&self.keeper, &SUB_ID, &1, &100, &cycle_id, &100)
+        {
+            Ok(paid) => Ok(paid.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+}
+
+#[test]
+fn test_keeper_reimbursed_from_merchant_pool() {
+    let s = setup();
+    assert_eq!(s.client.deposit(&s.merchant, &200), 200);
+    s.client.set_reimbursement(&s.merchant, &30);
+    assert_eq!(s.client.quote(&s.merchant), 30);
+
+    assert_eq!(s.renew(1, true), Ok(30));
+    assert_eq!(s.token.balance(&s.keeper), 30);
+    assert_eq!(s.client.get_pool(&s.merchant).unwrap().balance, 170);
+    assert_eq!(
+        s.client.get_reimbursed_keeper(&SUB_ID, &1),
+        Some(s.keeper.clone())
+    );
+}
+
+#[test]
+fn test_failed_renewal_not_reimbursed() {
+    let s = setup();
+    s.client.deposit(&s.merchant, &200);
+    s.client.set_reimbursement(&s.merchant, &30);
+
+    assert_eq!(s.renew(1, false), Ok(0));
+    assert_eq!(s.token.balance(&s.keeper), 0);
+    assert_eq!(s.client.get_pool(&s.merchant).unwrap().balance, 200);
+    assert_eq!(s.client.get_reimbursed_keeper(&SUB_ID, &1), None);
+
+    // A later successful retry of the same cycle is still paid
+    assert_eq!(s.renew(1, true), Ok(30));
+}
+
+#[test]
+fn test_cycle_reimbursed_once() {
+    let s = setup();
+    s.client.deposit(&s.merchant, &200);
+    s.client.set_reimbursement(&s.merchant, &30);
+
+    s.renew(1, true).unwrap();
+    assert_eq!(s.renew(1, true), Err(Error::AlreadyReimbursed));
+    assert_eq!(s.renew(2, true), Ok(30));
+}
+
+#[test]
+fn test_unfunded_merchant_not_sponsored() {
+    let s = setup();
+    assert_eq!(s.renew(1, true), Err(Error::NotSponsored));
+
+    // Sponsorship paused
+    s.client.deposit(&s.merchant, &20);
+    assert_eq!(s.renew(1, true), Err(Error::NotSponsored));
+
+    // Pool can't cover a full reimbursement
+    s.client.set_reimbursement(&s.merchant, &30);
+    assert_eq!(s.client.quote(&s.merchant), 0);
+    assert_eq!(s.renew(1, true), Err(Error::NotSponsored));
+    assert_eq!(s.client.get_pool(&s.merchant).unwrap().balance, 20);
+}
+
+#[test]
+fn test_reimbursement_capped_by_admin() {
+    let s = setup();
+    s.client.deposit(&s.merchant, &200);
+    assert_eq!(
+        s.client
+            .try_set_reimbursement(&s.merchant, &(MAX + 1))
+            .unwrap_err()
+            .unwrap(),
+        Error::InvalidAmount
+    );
+
+    s.client.set_reimbursement(&s.merchant, &MAX);
+    s.client.set_max_reimbursement(&20);
+    assert_eq!(s.renew(1, true), Ok(20));
+}
+
+#[test]
+fn test_merchant_withdraws_unspent_funds() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_withdraw(&s.merchant, &10)
+            .unwrap_err()
+            .unwrap(),
+        Error::PoolNotFound
+    );
+
+    s.client.deposit(&s.merchant, &200);
+    assert_eq!(
+        s.client
+            .try_withdraw(&s.merchant, &201)
+            .unwrap_err()
+            .unwrap(),
+        Error::InsufficientBalance
+    );
+    assert_eq!(s.client.withdraw(&s.merchant, &150), 50);
+    assert_eq!(s.token.balance(&s.merchant), 950);
+}
@@ -1,7 +1,29 @@
 use soroban_sdk::{
-    contract, contractevent, contractimpl, contracttype, vec, xdr::ToXdr, Address, BytesN, Env,
-    String, Vec,
+    contract, contractclient, contractevent, contractimpl, contracttype, vec, xdr::ToXdr, Address,
+    BytesN, Env, String, Val, Vec,
 };
+use syncro_common::ReasonCode;
+use syncro_upgrade::{Migration, UpgradeError};
+
+/// Role bit held by accounts allowed to advance subscriptions to their next
+/// renewal.
+pub const RENEWER: u32 = 1;
+
+/// Storage layout version of this code; see `migrate`.
+pub const CODE_VERSION: u32 = 1;
+
+/// Length of the month `ServiceStats` normalizes amounts to, in seconds.
+pub const MONTH_SECONDS: u64 = 2_592_000;
+
+/// Seconds before its next renewal a subscription enters the reminder
+/// window `notify_due` reports it in.
+pub const REMINDER_WINDOW: u64 = 259_200;
+
+/// Most entries one `import_portfolio` takes.
+pub const MAX_PORTFOLIO_IMPORT: u32 = 50;
+
+/// Steps from each earlier code version to the next, oldest first.
+const MIGRATIONS: &[Migration] = &[];
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -19,6 +41,74 @@ pub enum DataKey {
     UserSubscriptions(Address),
     Subscription(BytesN<32>),
     SubscriptionCounter,
+    CircuitBreaker,
+    TransferApproval(BytesN<32>),
+    RecoveryContract,
+    AtRisk(BytesN<32>),
+    CancelReason(BytesN<32>),
+    SubscriptionMerchant(BytesN<32>),
+    MerchantSubscribers(Address),
+    MerchantStats(Address),
+    ServiceStats(String),
+    NotificationTarget(Address),
+    DueNotified(BytesN<32>),
+}
+
+/// One subscription in a user's exported portfolio, as `import_portfolio`
+/// takes it back.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PortfolioEntry {
+    pub subscription_id: BytesN<32>,
+    pub metadata: SubscriptionMetadata,
+}
+
+/// Aggregate view of the subscriptions to one service. `monthly_amount`
+/// sums the active ones' expected amounts over a [`MONTH_SECONDS`] month.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ServiceStats {
+    pub active: u32,
+    pub cancelled: u32,
+    pub monthly_amount: i128,
+}
+
+/// Aggregate view of a merchant's linked subscriptions. `expected_amount`
+/// sums the per-cycle amounts of the active ones.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MerchantStats {
+    pub subscribers: u32,
+    pub active: u32,
+    pub expected_amount: i128,
+}
+
+/// An owner's permission for `operator` to move one subscription out of
+/// the owner's list.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferApproval {
+    pub owner: Address,
+    pub operator: Address,
+}
+
+/// Interface a user's notification target implements to hear about their
+/// subscriptions coming due.
+#[contractclient(name = "DueNotificationClient")]
+pub trait DueNotificationTarget {
+    fn on_subscription_due(
+        env: Env,
+        user: Address,
+        subscription_id: BytesN<32>,
+        next_renewal: u64,
+        expected_amount: i128,
+    );
+}
+
+/// Interface of the protocol-wide circuit breaker.
+#[contractclient(name = "CircuitBreakerClient")]
+pub trait CircuitBreakerInterface {
+    fn is_paused(env: Env, contract: Address) -> bool;
 }
 
 #[contractevent]
@@ -32,6 +122,19 @@ pub struct SubscriptionCreatedEvent {
     pub next_renewal: u64,
 }
 
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionRenewedEvent {
+    pub subscription_id: BytesN<32>,
+    pub next_renewal: u64,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionAtRiskEvent {
+    pub subscription_id: BytesN<32>,
+}
+
 #[contractevent]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SubscriptionUpdatedEvent {
@@ -49,6 +152,38 @@ pub struct SubscriptionCancelledEvent {
     pub subscription_id: BytesN<32>,
     pub user: Address,
     pub service_id: String,
+    pub reason: Option<ReasonCode>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionTransferredEvent {
+    pub subscription_id: BytesN<32>,
+    pub from: Address,
+    pub to: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionMerchantSetEvent {
+    pub subscription_id: BytesN<32>,
+    pub merchant: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PortfolioImportedEvent {
+    pub user: Address,
+    pub subscription_ids: Vec<BytesN<32>>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionDueNotifiedEvent {
+    pub subscription_id: BytesN<32>,
+    pub user: Address,
+    pub target: Address,
+    pub next_renewal: u64,
 }
 
 #[contract]
@@ -56,6 +191,85 @@ pub struct SubscriptionRegistry;
 
 #[contractimpl]
 impl SubscriptionRegistry {
+    /// Set the admin allowed to configure the circuit breaker. The registry
+    /// works without an admin.
+    pub fn init(env: Env, admin: Address) {
+        if syncro_access::init_admin(&env, &admin).is_err() {
+            panic!("already initialized");
+        }
+        syncro_upgrade::init_version(&env, CODE_VERSION);
+        syncro_common::events::publish_schema(&env);
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    /// Refuse new subscriptions and updates while the circuit breaker
+    /// pauses this contract. Cancelling stays possible. Admin only.
+    pub fn set_circuit_breaker(env: Env, breaker: Address) {
+        syncro_access::require_admin(&env).expect("not initialized");
+        env.storage()
+            .instance()
+            .set(&DataKey::CircuitBreaker, &breaker);
+    }
+
+    /// Allow the guardian recovery contract to move a user's subscriptions
+    /// without their signature. Admin only.
+    pub fn set_recovery_contract(env: Env, recovery: Address) {
+        syncro_access::require_admin(&env).expect("not initialized");
+        env.storage()
+            .instance()
+            .set(&DataKey::RecoveryContract, &recovery);
+    }
+
+    /// Allow `renewer` (e.g. the batch runner or the renewal contract) to
+    /// call `mark_renewed` and `mark_at_risk`. Admin only.
+    pub fn add_renewer(env: Env, renewer: Address) {
+        syncro_access::grant_role(&env, &renewer, RENEWER).expect("not initialized");
+    }
+
+    pub fn remove_renewer(env: Env, renewer: Address) {
+        syncro_access::revoke_role(&env, &renewer, RENEWER).expect("not initialized");
+    }
+
+    /// Swap in the uploaded `wasm_hash`, keeping all subscriptions. Admin
+    /// only. Follow with `migrate` on the new code when its `CODE_VERSION`
+    /// moved.
+    pub fn upgrade(env: Env, wasm_hash: BytesN<32>) {
+        syncro_upgrade::upgrade(&env, &wasm_hash).expect("not initialized");
+    }
+
+    /// Bring storage from code version `from`, the one recorded, up to
+    /// `to`. Admin only.
+    pub fn migrate(env: Env, from: u32, to: u32) {
+        match syncro_upgrade::migrate(&env, from, to, CODE_VERSION, MIGRATIONS) {
+            Ok(()) => {}
+            Err(UpgradeError::NotInitialized) => panic!("not initialized"),
+            Err(UpgradeError::VersionMismatch) => panic!("version mismatch"),
+        }
+    }
+
+    pub fn get_code_version(env: Env) -> u32 {
+        syncro_upgrade::version(&env)
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    fn require_not_paused(env: &Env) {
+        if let Some(breaker) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::CircuitBreaker)
+        {
+            if CircuitBreakerClient::new(env, &breaker).is_paused(&env.current_contract_address()) {
+                panic!("protocol is paused");
+            }
+        }
+    }
+
     /// Create a new subscription for a user
     pub fn create_subscription(
         env: Env,
@@ -65,13 +279,46 @@ impl SubscriptionRegistry {
         expected_amount: i128,
         next_renewal: u64,
     ) -> BytesN<32> {
-        if billing_interval == 0 {
+        Self::require_not_paused(&env);
+        let subscription_id = Self::insert_subscription(
+            &env,
+            &user,
+            SubscriptionMetadata {
+                service_id: service_id.clone(),
+                billing_interval,
+                expected_amount,
+                next_renewal,
+                is_active: true,
+            },
+        );
+
+        SubscriptionCreatedEvent {
+            subscription_id: subscription_id.clone(),
+            user: user.clone(),
+            service_id: service_id.clone(),
+            billing_interval,
+            expected_amount,
+            next_renewal,
+        }
+        .publish(&env);
+
+        subscription_id
+    }
+
+    /// Store `metadata` under a new id in `user`'s list, after checking
+    /// its terms.
+    fn insert_subscription(
+        env: &Env,
+        user: &Address,
+        metadata: SubscriptionMetadata,
+    ) -> BytesN<32> {
+        if metadata.billing_interval == 0 {
             panic!("billing_interval must be greater than 0");
         }
-        if expected_amount <= 0 {
+        if metadata.expected_amount <= 0 {
             panic!("expected_amount must be non-negative");
         }
-        if next_renewal == 0 {
+        if metadata.next_renewal == 0 {
             panic!("next_renewal must be greater than 0");
         }
 
@@ -89,43 +336,29 @@ impl SubscriptionRegistry {
         // Create deterministic subscription ID from counter and user hash
         let mut id_bytes = [0u8; 32];
         let counter_bytes = counter.to_be_bytes();
-        let user_bytes = user.clone().to_xdr(&env);
+        let user_bytes = user.clone().to_xdr(env);
         id_bytes[..8].copy_from_slice(&counter_bytes);
         let user_hash = env.crypto().sha256(&user_bytes);
         id_bytes[8..32].copy_from_slice(&user_hash.to_array()[..24]);
-        let subscription_id = BytesN::from_array(&env, &id_bytes);
+        let subscription_id = BytesN::from_array(env, &id_bytes);
 
-        let metadata = SubscriptionMetadata {
-            service_id: service_id.clone(),
-            billing_interval,
-            expected_amount,
-            next_renewal,
-            is_active: true,
-        };
         env.storage()
             .instance()
             .set(&DataKey::Subscription(subscription_id.clone()), &metadata);
+        Self::update_service_stats(env, &metadata.service_id, |stats| {
+            if metadata.is_active {
+                stats.active += 1;
+                stats.monthly_amount += Self::monthly_amount(&metadata);
+            } else {
+                stats.cancelled += 1;
+            }
+        });
 
-        let mut user_subs: Vec<BytesN<32>> = env
-            .storage()
-            .instance()
-            .get(&DataKey::UserSubscriptions(user.clone()))
-            .unwrap_or_else(|| vec![&env]);
+        let mut user_subs = Self::get_user_subscriptions(env.clone(), user.clone());
         user_subs.push_back(subscription_id.clone());
         env.storage()
             .instance()
             .set(&DataKey::UserSubscriptions(user.clone()), &user_subs);
-
-        SubscriptionCreatedEvent {
-            subscription_id: subscription_id.clone(),
-            user: user.clone(),
-            service_id: service_id.clone(),
-            billing_interval,
-            expected_amount,
-            next_renewal,
-        }
-        .publish(&env);
-
         subscription_id
     }
 
@@ -139,6 +372,7 @@ impl SubscriptionRegistry {
         expected_amount: Option<i128>,
         next_renewal: Option<u64>,
     ) {
+        Self::require_not_paused(&env);
         let mut metadata: SubscriptionMetadata = env
             .storage()
             .instance()
@@ -148,6 +382,7 @@ impl SubscriptionRegistry {
         if !metadata.is_active {
             panic!("subscription is not active");
         }
+        let previous = metadata.clone();
 
         if let Some(sid) = service_id {
             metadata.service_id = sid;
@@ -162,6 +397,9 @@ impl SubscriptionRegistry {
             if ea <= 0 {
                 panic!("expected_amount must be non-negative");
             }
+            Self::update_merchant_stats(&env, &subscription_id, |stats| {
+                stats.expected_amount += ea - metadata.expected_amount;
+            });
             metadata.expected_amount = ea;
         }
         if let Some(nr) = next_renewal {
@@ -174,6 +412,14 @@ impl SubscriptionRegistry {
         env.storage()
             .instance()
             .set(&DataKey::Subscription(subscription_id.clone()), &metadata);
+        Self::update_service_stats(&env, &previous.service_id, |stats| {
+            stats.active -= 1;
+            stats.monthly_amount -= Self::monthly_amount(&previous);
+        });
+        Self::update_service_stats(&env, &metadata.service_id, |stats| {
+            stats.active += 1;
+            stats.monthly_amount += Self::monthly_amount(&metadata);
+        });
 
         SubscriptionUpdatedEvent {
             subscription_id: subscription_id.clone(),
@@ -186,8 +432,14 @@ impl SubscriptionRegistry {
         .publish(&env);
     }
 
-    /// Cancel a subscription by marking it as inactive
-    pub fn cancel_subscription(env: Env, subscription_id: BytesN<32>, user: Address) {
+    /// Cancel a subscription by marking it as inactive, optionally saying
+    /// why with one of the cancellation reason codes
+    pub fn cancel_subscription(
+        env: Env,
+        subscription_id: BytesN<32>,
+        user: Address,
+        reason: Option<ReasonCode>,
+    ) {
         let mut metadata: SubscriptionMetadata = env
             .storage()
             .instance()
@@ -202,13 +454,473 @@ impl SubscriptionRegistry {
         env.storage()
             .instance()
             .set(&DataKey::Subscription(subscription_id.clone()), &metadata);
+        Self::update_merchant_stats(&env, &subscription_id, |stats| {
+            stats.active -= 1;
+            stats.expected_amount -= metadata.expected_amount;
+        });
+        Self::update_service_stats(&env, &metadata.service_id, |stats| {
+            stats.active -= 1;
+            stats.cancelled += 1;
+            stats.monthly_amount -= Self::monthly_amount(&metadata);
+        });
+        if let Some(reason) = reason {
+            env.storage()
+                .instance()
+                .set(&DataKey::CancelReason(subscription_id.clone()), &reason);
+        }
 
         SubscriptionCancelledEvent {
             subscription_id: subscription_id.clone(),
             user: user.clone(),
             service_id: metadata.service_id.clone(),
+            reason,
+        }
+        .publish(&env);
+    }
+
+    /// Why a subscription was cancelled, when its owner said.
+    pub fn get_cancel_reason(env: Env, subscription_id: BytesN<32>) -> Option<ReasonCode> {
+        env.storage()
+            .instance()
+            .get(&DataKey::CancelReason(subscription_id))
+    }
+
+    /// Move an active subscription's next renewal one billing interval on,
+    /// after it has been charged, clearing any at-risk flag. Renewers only.
+    /// Returns the new `next_renewal`.
+    pub fn mark_renewed(env: Env, subscription_id: BytesN<32>, renewer: Address) -> u64 {
+        let key = DataKey::Subscription(subscription_id.clone());
+        let mut metadata = Self::require_renewable(&env, &subscription_id, &renewer);
+
+        metadata.next_renewal = metadata
+            .next_renewal
+            .checked_add(metadata.billing_interval)
+            .unwrap_or_else(|| panic!("next_renewal overflow"));
+        env.storage().instance().set(&key, &metadata);
+        env.storage()
+            .instance()
+            .remove(&DataKey::AtRisk(subscription_id.clone()));
+
+        SubscriptionRenewedEvent {
+            subscription_id,
+            next_renewal: metadata.next_renewal,
         }
         .publish(&env);
+        metadata.next_renewal
+    }
+
+    /// Flag an active subscription whose latest renewal failed, until the
+    /// next `mark_renewed`. Renewers only.
+    pub fn mark_at_risk(env: Env, subscription_id: BytesN<32>, renewer: Address) {
+        Self::require_renewable(&env, &subscription_id, &renewer);
+        env.storage()
+            .instance()
+            .set(&DataKey::AtRisk(subscription_id.clone()), &true);
+        SubscriptionAtRiskEvent { subscription_id }.publish(&env);
+    }
+
+    /// Whether the subscription's latest renewal failed.
+    pub fn is_at_risk(env: Env, subscription_id: BytesN<32>) -> bool {
+        env.storage()
+            .instance()
+            .has(&DataKey::AtRisk(subscription_id))
+    }
+
+    fn require_renewable(
+        env: &Env,
+        subscription_id: &BytesN<32>,
+        renewer: &Address,
+    ) -> SubscriptionMetadata {
+        if syncro_access::require_role(env, renewer, RENEWER).is_err() {
+            panic!("not a renewer");
+        }
+        let metadata: SubscriptionMetadata = env
+            .storage()
+            .instance()
+            .get(&DataKey::Subscription(subscription_id.clone()))
+            .unwrap_or_else(|| panic!("subscription not found"));
+        if !metadata.is_active {
+            panic!("subscription is not active");
+        }
+        metadata
+    }
+
+    /// Allow `operator` to transfer one of `user`'s subscriptions, or revoke
+    /// the approval with `None`. Owners transferring directly approve
+    /// themselves.
+    pub fn approve_transfer(
+        env: Env,
+        subscription_id: BytesN<32>,
+        user: Address,
+        operator: Option<Address>,
+    ) {
+        user.require_auth();
+        if !Self::get_user_subscriptions(env.clone(), user.clone()).contains(&subscription_id) {
+            panic!("not the subscription owner");
+        }
+
+        let key = DataKey::TransferApproval(subscription_id);
+        match operator {
+            Some(operator) => env.storage().instance().set(
+                &key,
+                &TransferApproval {
+                    owner: user,
+                    operator,
+                },
+            ),
+            None => env.storage().instance().remove(&key),
+        }
+    }
+
+    /// Move an active subscription to `to`'s list. `operator` must hold the
+    /// owner's approval, which the transfer consumes.
+    pub fn transfer_subscription(
+        env: Env,
+        subscription_id: BytesN<32>,
+        operator: Address,
+        to: Address,
+    ) {
+        operator.require_auth();
+        let key = DataKey::TransferApproval(subscription_id.clone());
+        let approval: TransferApproval = env
+            .storage()
+            .instance()
+            .get(&key)
+            .unwrap_or_else(|| panic!("transfer not approved"));
+        if approval.operator != operator {
+            panic!("transfer not approved");
+        }
+
+        let metadata: SubscriptionMetadata = env
+            .storage()
+            .instance()
+            .get(&DataKey::Subscription(subscription_id.clone()))
+            .unwrap_or_else(|| panic!("subscription not found"));
+        if !metadata.is_active {
+            panic!("subscription is not active");
+        }
+
+        let from = approval.owner;
+        let mut from_subs = Self::get_user_subscriptions(env.clone(), from.clone());
+        let index = from_subs
+            .first_index_of(&subscription_id)
+            .unwrap_or_else(|| panic!("not the subscription owner"));
+        from_subs.remove(index);
+        env.storage()
+            .instance()
+            .set(&DataKey::UserSubscriptions(from.clone()), &from_subs);
+
+        let mut to_subs = Self::get_user_subscriptions(env.clone(), to.clone());
+        to_subs.push_back(subscription_id.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::UserSubscriptions(to.clone()), &to_subs);
+        env.storage().instance().remove(&key);
+
+        SubscriptionTransferredEvent {
+            subscription_id,
+            from,
+            to,
+        }
+        .publish(&env);
+    }
+
+    /// Move every subscription in `from`'s list to `to`'s, dropping any
+    /// transfer approvals `from` granted. Only the configured recovery
+    /// contract may call this. Returns how many subscriptions moved.
+    pub fn recover_subscriptions(env: Env, from: Address, to: Address) -> u32 {
+        let recovery: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::RecoveryContract)
+            .unwrap_or_else(|| panic!("recovery contract not set"));
+        recovery.require_auth();
+        if from == to {
+            panic!("already the owner");
+        }
+
+        let from_subs = Self::get_user_subscriptions(env.clone(), from.clone());
+        let mut to_subs = Self::get_user_subscriptions(env.clone(), to.clone());
+        for subscription_id in from_subs.iter() {
+            env.storage()
+                .instance()
+                .remove(&DataKey::TransferApproval(subscription_id.clone()));
+            to_subs.push_back(subscription_id.clone());
+            SubscriptionTransferredEvent {
+                subscription_id,
+                from: from.clone(),
+                to: to.clone(),
+            }
+            .publish(&env);
+        }
+        env.storage()
+            .instance()
+            .remove(&DataKey::UserSubscriptions(from));
+        env.storage()
+            .instance()
+            .set(&DataKey::UserSubscriptions(to), &to_subs);
+        from_subs.len()
+    }
+
+    /// Every subscription in `user`'s list with its terms, in list order,
+    /// for `import_portfolio` to take into another deployment.
+    pub fn export_user_portfolio(env: Env, user: Address) -> Vec<PortfolioEntry> {
+        let mut entries = vec![&env];
+        for subscription_id in Self::get_user_subscriptions(env.clone(), user).iter() {
+            let Some(metadata) = Self::get_subscription(env.clone(), subscription_id.clone())
+            else {
+                continue;
+            };
+            entries.push_back(PortfolioEntry {
+                subscription_id,
+                metadata,
+            });
+        }
+        entries
+    }
+
+    /// Recreate up to [`MAX_PORTFOLIO_IMPORT`] exported subscriptions in
+    /// `user`'s list, cancelled ones included, under new ids. Each entry's
+    /// terms are checked as `create_subscription` checks them. Merchant
+    /// links aren't carried over, as they need the merchant's signature,
+    /// nor are cancellation reasons.
+    /// Returns the new ids, in entry order.
+    pub fn import_portfolio(
+        env: Env,
+        user: Address,
+        entries: Vec<PortfolioEntry>,
+    ) -> Vec<BytesN<32>> {
+        user.require_auth();
+        Self::require_not_paused(&env);
+        if entries.len() > MAX_PORTFOLIO_IMPORT {
+            panic!("too many portfolio entries");
+        }
+
+        let mut subscription_ids = vec![&env];
+        for entry in entries.iter() {
+            subscription_ids.push_back(Self::insert_subscription(&env, &user, entry.metadata));
+        }
+
+        PortfolioImportedEvent {
+            user,
+            subscription_ids: subscription_ids.clone(),
+        }
+        .publish(&env);
+        subscription_ids
+    }
+
+    /// Link an active subscription to the merchant billing it, with both
+    /// the owner's and the merchant's signature. A subscription links to
+    /// one merchant, once.
+    pub fn set_subscription_merchant(
+        env: Env,
+        subscription_id: BytesN<32>,
+        user: Address,
+        merchant: Address,
+    ) {
+        user.require_auth();
+        merchant.require_auth();
+        if !Self::get_user_subscriptions(env.clone(), user).contains(&subscription_id) {
+            panic!("not the subscription owner");
+        }
+        let metadata: SubscriptionMetadata = env
+            .storage()
+            .instance()
+            .get(&DataKey::Subscription(subscription_id.clone()))
+            .unwrap_or_else(|| panic!("subscription not found"));
+        if !metadata.is_active {
+            panic!("subscription is not active");
+        }
+        let key = DataKey::SubscriptionMerchant(subscription_id.clone());
+        if env.storage().instance().has(&key) {
+            panic!("merchant already set");
+        }
+        env.storage().instance().set(&key, &merchant);
+
+        let subscribers_key = DataKey::MerchantSubscribers(merchant.clone());
+        let mut subscribers: Vec<BytesN<32>> = env
+            .storage()
+            .instance()
+            .get(&subscribers_key)
+            .unwrap_or_else(|| vec![&env]);
+        subscribers.push_back(subscription_id.clone());
+        env.storage().instance().set(&subscribers_key, &subscribers);
+        Self::update_merchant_stats(&env, &subscription_id, |stats| {
+            stats.subscribers += 1;
+            stats.active += 1;
+            stats.expected_amount += metadata.expected_amount;
+        });
+
+        SubscriptionMerchantSetEvent {
+            subscription_id,
+            merchant,
+        }
+        .publish(&env);
+    }
+
+    /// The merchant a subscription is linked to, if any.
+    pub fn get_subscription_merchant(env: Env, subscription_id: BytesN<32>) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::SubscriptionMerchant(subscription_id))
+    }
+
+    /// Up to `limit` of the subscriptions linked to `merchant`, in link
+    /// order from `offset`, cancelled ones included. Merchant only.
+    pub fn get_merchant_subscribers(
+        env: Env,
+        merchant: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<BytesN<32>> {
+        merchant.require_auth();
+        let subscribers: Vec<BytesN<32>> = env
+            .storage()
+            .instance()
+            .get(&DataKey::MerchantSubscribers(merchant))
+            .unwrap_or_else(|| vec![&env]);
+        let start = offset.min(subscribers.len());
+        let end = start.saturating_add(limit).min(subscribers.len());
+        subscribers.slice(start..end)
+    }
+
+    /// Subscriber counts and the active per-cycle total for `merchant`.
+    /// Merchant only.
+    pub fn get_merchant_stats(env: Env, merchant: Address) -> MerchantStats {
+        merchant.require_auth();
+        env.storage()
+            .instance()
+            .get(&DataKey::MerchantStats(merchant))
+            .unwrap_or_default()
+    }
+
+    fn update_merchant_stats(
+        env: &Env,
+        subscription_id: &BytesN<32>,
+        update: impl FnOnce(&mut MerchantStats),
+    ) {
+        let Some(merchant) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::SubscriptionMerchant(subscription_id.clone()))
+        else {
+            return;
+        };
+        let key = DataKey::MerchantStats(merchant);
+        let mut stats: MerchantStats = env.storage().instance().get(&key).unwrap_or_default();
+        update(&mut stats);
+        env.storage().instance().set(&key, &stats);
+    }
+
+    /// Active and cancelled subscription counts and the active monthly
+    /// total for `service_id`.
+    pub fn get_service_stats(env: Env, service_id: String) -> ServiceStats {
+        env.storage()
+            .instance()
+            .get(&DataKey::ServiceStats(service_id))
+            .unwrap_or_default()
+    }
+
+    fn update_service_stats(
+        env: &Env,
+        service_id: &String,
+        update: impl FnOnce(&mut ServiceStats),
+    ) {
+        let key = DataKey::ServiceStats(service_id.clone());
+        let mut stats: ServiceStats = env.storage().instance().get(&key).unwrap_or_default();
+        update(&mut stats);
+        env.storage().instance().set(&key, &stats);
+    }
+
+    fn monthly_amount(metadata: &SubscriptionMetadata) -> i128 {
+        metadata
+            .expected_amount
+            .saturating_mul(MONTH_SECONDS as i128)
+            / metadata.billing_interval as i128
+    }
+
+    /// Have `notify_due` call `target` when the user's subscriptions come
+    /// due, or stop with `None`.
+    pub fn set_notification_target(env: Env, user: Address, target: Option<Address>) {
+        user.require_auth();
+        let key = DataKey::NotificationTarget(user);
+        match target {
+            Some(target) => env.storage().instance().set(&key, &target),
+            None => env.storage().instance().remove(&key),
+        }
+    }
+
+    pub fn get_notification_target(env: Env, user: Address) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::NotificationTarget(user))
+    }
+
+    /// Tell `user`'s notification target about up to `limit` of their
+    /// active subscriptions within [`REMINDER_WINDOW`] of their next
+    /// renewal, each once per renewal. Anyone may call this. A target that
+    /// fails is asked again on the next call. Returns how many
+    /// notifications were delivered.
+    pub fn notify_due(env: Env, user: Address, limit: u32) -> u32 {
+        let target: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::NotificationTarget(user.clone()))
+            .unwrap_or_else(|| panic!("notification target not set"));
+        let client = DueNotificationClient::new(&env, &target);
+        let now = env.ledger().timestamp();
+
+        let mut delivered = 0u32;
+        for subscription_id in Self::get_user_subscriptions(env.clone(), user.clone()).iter() {
+            if delivered >= limit {
+                break;
+            }
+            let Some(metadata) = Self::get_subscription(env.clone(), subscription_id.clone())
+            else {
+                continue;
+            };
+            let notified_key = DataKey::DueNotified(subscription_id.clone());
+            if !metadata.is_active
+                || now.saturating_add(REMINDER_WINDOW) < metadata.next_renewal
+                || env.storage().instance().get(&notified_key) == Some(metadata.next_renewal)
+            {
+                continue;
+            }
+
+            let ok = client
+                .try_on_subscription_due(
+                    &user,
+                    &subscription_id,
+                    &metadata.next_renewal,
+                    &metadata.expected_amount,
+                )
+                .is_ok_and(|r| r.is_ok());
+            if !ok {
+                continue;
+            }
+            env.storage()
+                .instance()
+                .set(&notified_key, &metadata.next_renewal);
+            delivered += 1;
+            SubscriptionDueNotifiedEvent {
+                subscription_id,
+                user: user.clone(),
+                target: target.clone(),
+                next_renewal: metadata.next_renewal,
+            }
+            .publish(&env);
+        }
+        delivered
+    }
+
+    /// The pending transfer approval for a subscription, if any.
+    pub fn get_transfer_approval(
+        env: Env,
+        subscription_id: BytesN<32>,
+    ) -> Option<TransferApproval> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TransferApproval(subscription_id))
     }
 
     /// Get subscription metadata by ID
@@ -0,0 +1,278 @@
+#![no_std]
+//! Merkle snapshots of subscription status.
+//!
+//! A publisher (an indexer following the renewal contract) periodically
+//! builds a Merkle tree over every subscription's `StatusLeaf` and commits
+//! the root here. Anyone holding a leaf and its proof can then show that a
+//! subscription was in a given state as of the snapshot, on this chain via
+//! `verify` or on another chain by replaying the hashing below.
+//!
+//! Hashing uses fixed big-endian layouts rather than XDR so other chains
+//! can reproduce it cheaply:
+//!
+//! - leaf: `sha256(0x00 || sub_id: u64 || state: u32 || next_due: u64)`,
+//!   with `state` as in [`state_code`]
+//! - node: `sha256(0x01 || min(a, b) || max(a, b))`
+//!
+//! Sorting each pair means a proof is just the list of sibling hashes from
+//! the leaf up, and the prefixes keep a leaf from passing as a node.
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Bytes, BytesN,
+    Env, Val, Vec,
+};
+use syncro_access::AccessError;
+use syncro_common::SubscriptionState;
+
+/// Role bit held by accounts allowed to commit snapshots.
+pub const PUBLISHER: u32 = 1;
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2401,
+    NotInitialized = 2402,
+    NoPendingAdmin = 2403,
+    NotPublisher = 2404,
+    EmptySnapshot = 2405,
+    TooSoon = 2406,
+    InvalidTimestamp = 2407,
+    UnknownEpoch = 2408,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::NotPublisher,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    MinInterval,
+    LatestEpoch,
+    Snapshot(u64), // epoch -> Snapshot
+}
+
+/// One subscription's entry in the tree.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatusLeaf {
+    pub sub_id: u64,
+    pub state: SubscriptionState,
+    /// Timestamp the next renewal is due.
+    pub next_due: u64,
+}
+
+/// A committed root. `taken_at` is the ledger time the publisher read the
+/// subscriptions at.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+    pub root: BytesN<32>,
+    pub leaf_count: u32,
+    pub taken_at: u64,
+    pub committed_at: u64,
+    pub publisher: Address,
+}
+
+#[contractevent]
+pub struct SnapshotCommitted {
+    pub epoch: u64,
+    pub root: BytesN<32>,
+    pub leaf_count: u32,
+    pub taken_at: u64,
+}
+
+/// Encoding of a state inside a leaf.
+pub fn state_code(state: &SubscriptionState) -> u32 {
+    match state {
+        SubscriptionState::Active => 0,
+        SubscriptionState::Retrying => 1,
+        SubscriptionState::Failed => 2,
+        SubscriptionState::Cancelled => 3,
+        SubscriptionState::Expired => 4,
+        SubscriptionState::Paused => 5,
+    }
+}
+
+#[contract]
+pub struct StatusSnapshotContract;
+
+#[contractimpl]
+impl StatusSnapshotContract {
+    /// Initialize with an admin and the minimum number of seconds between
+    /// two commits.
+    pub fn init(env: Env, admin: Address, min_interval: u64) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::MinInterval, &min_interval);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Configuration (admin only) ────────────────────────────────
+
+    pub fn add_publisher(env: Env, publisher: Address) -> Result<(), Error> {
+        syncro_access::grant_role(&env, &publisher, PUBLISHER)?;
+        Ok(())
+    }
+
+    pub fn remove_publisher(env: Env, publisher: Address) -> Result<(), Error> {
+        syncro_access::revoke_role(&env, &publisher, PUBLISHER)?;
+        Ok(())
+    }
+
+    pub fn is_publisher(env: Env, account: Address) -> bool {
+        syncro_access::has_role(&env, &account, PUBLISHER)
+    }
+
+    pub fn set_min_interval(env: Env, min_interval: u64) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::MinInterval, &min_interval);
+        Ok(())
+    }
+
+    // ── Snapshots ─────────────────────────────────────────────────
+
+    /// Commit the root of a tree over `leaf_count` leaves read at
+    /// `taken_at`. Snapshots must move forward in time and be at least the
+    /// minimum interval apart. Returns the new epoch, counting from 1.
+    pub fn commit(
+        env: Env,
+        publisher: Address,
+        root: BytesN<32>,
+        leaf_count: u32,
+        taken_at: u64,
+    ) -> Result<u64, Error> {
+        syncro_access::require_role(&env, &publisher, PUBLISHER)?;
+        if leaf_count == 0 {
+            return Err(Error::EmptySnapshot);
+        }
+        let now = env.ledger().timestamp();
+        if taken_at > now {
+            return Err(Error::InvalidTimestamp);
+        }
+
+        let latest = Self::latest_epoch(env.clone());
+        if let Some(previous) = latest.and_then(|epoch| Self::get_snapshot(env.clone(), epoch)) {
+            if taken_at <= previous.taken_at {
+                return Err(Error::InvalidTimestamp);
+            }
+            let min_interval: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::MinInterval)
+                .ok_or(Error::NotInitialized)?;
+            if now < previous.committed_at.saturating_add(min_interval) {
+                return Err(Error::TooSoon);
+            }
+        }
+
+        let epoch = latest.unwrap_or(0) + 1;
+        env.storage().persistent().set(
+            &DataKey::Snapshot(epoch),
+            &Snapshot {
+                root: root.clone(),
+                leaf_count,
+                taken_at,
+                committed_at: now,
+                publisher,
+            },
+        );
+        env.storage().instance().set(&DataKey::LatestEpoch, &epoch);
+
+        SnapshotCommitted {
+            epoch,
+            root,
+            leaf_count,
+            taken_at,
+        }
+        .publish(&env);
+        Ok(epoch)
+    }
+
+    pub fn latest_epoch(env: Env) -> Option<u64> {
+        env.storage().instance().get(&DataKey::LatestEpoch)
+    }
+
+    pub fn get_snapshot(env: Env, epoch: u64) -> Option<Snapshot> {
+        env.storage().persistent().get(&DataKey::Snapshot(epoch))
+    }
+
+    // ── Verification ──────────────────────────────────────────────
+
+    /// Whether `leaf` is in the tree committed at `epoch`.
+    pub fn verify(
+        env: Env,
+        epoch: u64,
+        leaf: StatusLeaf,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<bool, Error> {
+        let snapshot = Self::get_snapshot(env.clone(), epoch).ok_or(Error::UnknownEpoch)?;
+        Ok(Self::compute_root(env, leaf, proof) == snapshot.root)
+    }
+
+    /// Whether `leaf` is in the latest tree and its subscription was active.
+    pub fn is_active_subscriber(
+        env: Env,
+        leaf: StatusLeaf,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<bool, Error> {
+        let epoch = Self::latest_epoch(env.clone()).ok_or(Error::UnknownEpoch)?;
+        if leaf.state != SubscriptionState::Active {
+            return Ok(false);
+        }
+        Self::verify(env, epoch, leaf, proof)
+    }
+
+    pub fn leaf_hash(env: Env, leaf: StatusLeaf) -> BytesN<32> {
+        let mut data = Bytes::new(&env);
+        data.push_back(LEAF_PREFIX);
+        data.extend_from_array(&leaf.sub_id.to_be_bytes());
+        data.extend_from_array(&state_code(&leaf.state).to_be_bytes());
+        data.extend_from_array(&leaf.next_due.to_be_bytes());
+        env.crypto().sha256(&data).into()
+    }
+
+    /// Root reached by hashing `leaf` up through `proof`.
+    pub fn compute_root(env: Env, leaf: StatusLeaf, proof: Vec<BytesN<32>>) -> BytesN<32> {
+        let mut hash = Self::leaf_hash(env.clone(), leaf);
+        for sibling in proof.iter() {
+            hash = node_hash(&env, &hash, &sibling);
+        }
+        hash
+    }
+}
+
+fn node_hash(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (a, b) = (a.to_array(), b.to_array());
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = Bytes::new(env);
+    data.push_back(NODE_PREFIX);
+    data.extend_from_array(&lo);
+    data.extend_from_array(&hi);
+    env.crypto().sha256(&data).into()
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,205 @@
+extern crate std;
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env, Vec,
+};
+
+const INTERVAL: u64 = 3_600;
+
+struct Setup {
+    env: Env,
+    client: StatusSnapshotContractClient<'static>,
+    publisher: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_700_000_000);
+    let contract_id = env.register(StatusSnapshotContract, ());
+    let client = StatusSnapshotContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env), &INTERVAL);
+
+    let publisher = Address::generate(&env);
+    client.add_publisher(&publisher);
+    Setup {
+        env,
+        client,
+        publisher,
+    }
+}
+
+fn leaf(sub_id: u64, state: SubscriptionState) -> StatusLeaf {
+    StatusLeaf {
+        sub_id,
+        state,
+        next_due: 1_702_592_000,
+    }
+}
+
+/// Builds the tree the way a publisher would: pairs hashed level by level,
+/// a lone last node carried up unchanged. Returns the root and each leaf's
+/// proof.
+fn build(env: &Env, leaves: &[StatusLeaf]) -> (BytesN<32>, std::vec::Vec<Vec<BytesN<32>>>) {
+    let mut level: std::vec::Vec<BytesN<32>> = leaves
+        .iter()
+        .map(|l| StatusSnapshotContract::leaf_hash(env.clone(), l.clone()))
+        .collect();
+    let mut positions: std::vec::Vec<usize> = (0..leaves.len()).collect();
+    let mut proofs: std::vec::Vec<Vec<BytesN<32>>> = leaves.iter().map(|_| vec![env]).collect();
+    while level.len() > 1 {
+        for (proof, pos) in proofs.iter_mut().zip(positions.iter_mut()) {
+            let sibling = *pos ^ 1;
+            if sibling < level.len() {
+                proof.push_back(level[sibling].clone());
+            }
+            *pos /= 2;
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => node_hash(env, a, b),
+                [a] => a.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    (level[0].clone(), proofs)
+}
+
+#[test]
+fn test_every_leaf_verifies_against_committed_root() {
+    let s = setup();
+    let leaves = [
+        leaf(1, SubscriptionState::Active),
+        leaf(2, SubscriptionState::Retrying),
+        leaf(3, SubscriptionState::Active),
+    ];
+    let (root, proofs) = build(&s.env, &leaves);
+    let epoch = s.client.commit(&s.publisher, &root, &3, &1_699_999_000);
+    assert_eq!(epoch, 1);
+    assert_eq!(s.client.latest_epoch(), Some(1));
+
+    for (leaf, proof) in leaves.iter().zip(proofs.iter()) {
+        assert!(s.client.verify(&epoch, leaf, proof));
+    }
+}
+
+#[test]
+fn test_altered_leaf_or_proof_fails() {
+    let s = setup();
+    let leaves = [
+        leaf(1, SubscriptionState::Active),
+        leaf(2, SubscriptionState::Cancelled),
+    ];
+    let (root, proofs) = build(&s.env, &leaves);
+    let epoch = s.client.commit(&s.publisher, &root, &2, &1_699_999_000);
+
+    // A cancelled subscriber cannot claim to be active
+    let forged = leaf(2, SubscriptionState::Active);
+    assert!(!s.client.verify(&epoch, &forged, &proofs[1]));
+    assert!(!s.client.verify(&epoch, &leaves[0], &vec![&s.env]));
+}
+
+#[test]
+fn test_active_subscriber_checked_against_latest_snapshot() {
+    let s = setup();
+    let leaves = [
+        leaf(1, SubscriptionState::Active),
+        leaf(2, SubscriptionState::Failed),
+    ];
+    let (root, proofs) = build(&s.env, &leaves);
+    s.client.commit(&s.publisher, &root, &2, &1_699_999_000);
+
+    assert!(s.client.is_active_subscriber(&leaves[0], &proofs[0]));
+    assert!(!s.client.is_active_subscriber(&leaves[1], &proofs[1]));
+
+    // Once a newer snapshot drops the subscription, the old proof stops working
+    s.env.ledger().with_mut(|li| li.timestamp += INTERVAL);
+    let (root, _) = build(&s.env, &leaves[1..]);
+    s.client
+        .commit(&s.publisher, &root, &1, &(1_699_999_000 + INTERVAL));
+    assert!(!s.client.is_active_subscriber(&leaves[0], &proofs[0]));
+    assert!(s.client.verify(&1, &leaves[0], &proofs[0]));
+}
+
+#[test]
+fn test_single_leaf_tree_has_empty_proof() {
+    let s = setup();
+    let only = leaf(7, SubscriptionState::Active);
+    let root = StatusSnapshotContract::leaf_hash(s.env.clone(), only.clone());
+    s.client.commit(&s.publisher, &root, &1, &1_699_999_000);
+    assert!(s.client.is_active_subscriber(&only, &vec![&s.env]));
+}
+
+#[test]
+fn test_commits_are_spaced_and_move_forward() {
+    let s = setup();
+    let root = BytesN::from_array(&s.env, &[1; 32]);
+    s.client.commit(&s.publisher, &root, &1, &1_699_999_000);
+
+    let try_commit = |taken_at: u64| -> Result<u64, Error> {
+        match s.client.try_commit(&s.publisher, &root, &1, &taken_at) {
+            Ok(epoch) => Ok(epoch.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    };
+    assert_eq!(try_commit(1_699_999_500), Err(Error::TooSoon));
+
+    s.env.ledger().with_mut(|li| li.timestamp += INTERVAL);
+    assert_eq!(try_commit(1_699_999_000), Err(Error::InvalidTimestamp));
+    assert_eq!(
+        try_commit(s.env.ledger().timestamp() + 1),
+        Err(Error::InvalidTimestamp)
+    );
+    assert_eq!(try_commit(1_699_999_500), Ok(2));
+}
+
+#[test]
+fn test_only_publishers_commit() {
+    let s = setup();
+    let root = BytesN::from_array(&s.env, &[1; 32]);
+    assert_eq!(
+        s.client
+            .try_commit(&Address::generate(&s.env), &root, &1, &1_699_999_000)
+            .unwrap_err()
+            .unwrap(),
+        Error::NotPublisher
+    );
+    assert_eq!(
+        s.client
+            .try_commit(&s.publisher, &root, &0, &1_699_999_000)
+            .unwrap_err()
+            .unwrap(),
+        Error::EmptySnapshot
+    );
+
+    s.client.remove_publisher(&s.publisher);
+    assert!(!s.client.is_publisher(&s.publisher));
+    assert_eq!(
+        s.client
+            .try_verify(&1, &leaf(1, SubscriptionState::Active), &vec![&s.env])
+            .unwrap_err()
+            .unwrap(),
+        Error::UnknownEpoch
+    );
+}
+
+#[test]
+fn test_leaf_hash_layout() {
+    let env = Env::default();
+    let mut data = [0u8; 21];
+    data[1..9].copy_from_slice(&5u64.to_be_bytes());
+    data[9..13].copy_from_slice(&1u32.to_be_bytes());
+    data[13..21].copy_from_slice(&1_702_592_000u64.to_be_bytes());
+    let expected: BytesN<32> = env
+        .crypto()
+        .sha256(&soroban_sdk::Bytes::from_array(&env, &data))
+        .into();
+    assert_eq!(
+        StatusSnapshotContract::leaf_hash(env.clone(), leaf(5, SubscriptionState::Retrying)),
+        expected
+    );
+}
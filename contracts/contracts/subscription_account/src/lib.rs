@@ -0,0 +1,244 @@
+#![no_std]
+use soroban_sdk::{
+    auth::{Context, ContractContext, CustomAccountInterface},
+    contract, contracterror, contractevent, contractimpl, contracttype,
+    crypto::Hash,
+    Address, BytesN, Env, Symbol, TryFromVal, Val, Vec,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1001,
+    NotInitialized = 1002,
+    UnknownSigner = 1003,
+    DeviceExpired = 1004,
+    NotPermitted = 1005,
+    CapExceeded = 1006,
+    InvalidPolicy = 1007,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Owner,
+    Device(BytesN<32>), // device public key -> DevicePolicy
+}
+
+/// What a delegated device key may sign for. The key can approve renewals
+/// and cancel subscriptions on `renewal_contract`, and grant that contract a
+/// token allowance, each up to its cap. It cannot sign anything else.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DevicePolicy {
+    pub renewal_contract: Address,
+    /// Largest `max_spend` a single renewal approval may carry.
+    pub max_spend: i128,
+    /// Token the renewal contract charges in.
+    pub token: Address,
+    /// Largest allowance the device may grant the renewal contract.
+    pub allowance_cap: i128,
+    /// Last ledger at which the device key is accepted.
+    pub expires_at: u32,
+}
+
+/// Ed25519 signature over the authorization payload.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountSignature {
+    pub public_key: BytesN<32>,
+    pub signature: BytesN<64>,
+}
+
+#[contractevent]
+pub struct DeviceAdded {
+    pub public_key: BytesN<32>,
+    pub renewal_contract: Address,
+    pub max_spend: i128,
+    pub expires_at: u32,
+}
+
+#[contractevent]
+pub struct DeviceRemoved {
+    pub public_key: BytesN<32>,
+}
+
+#[contractevent]
+pub struct OwnerRotated {
+    pub public_key: BytesN<32>,
+}
+
+#[contract]
+pub struct SubscriptionAccountContract;
+
+#[contractimpl]
+impl SubscriptionAccountContract {
+    /// Initialize with the owner key, which can sign anything.
+    pub fn init(env: Env, owner_key: BytesN<32>) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Owner) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Owner, &owner_key);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Key management (owner only) ───────────────────────────────
+
+    /// Authorize a device key under `policy`, replacing any previous policy
+    /// for the same key.
+    pub fn add_device(env: Env, public_key: BytesN<32>, policy: DevicePolicy) -> Result<(), Error> {
+        env.current_contract_address().require_auth();
+        if policy.max_spend < 0 || policy.allowance_cap < 0 {
+            return Err(Error::InvalidPolicy);
+        }
+        if public_key == Self::owner(&env)? {
+            return Err(Error::InvalidPolicy);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Device(public_key.clone()), &policy);
+
+        DeviceAdded {
+            public_key,
+            renewal_contract: policy.renewal_contract,
+            max_spend: policy.max_spend,
+            expires_at: policy.expires_at,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn remove_device(env: Env, public_key: BytesN<32>) -> Result<(), Error> {
+        env.current_contract_address().require_auth();
+        let key = DataKey::Device(public_key.clone());
+        if !env.storage().persistent().has(&key) {
+            return Err(Error::UnknownSigner);
+        }
+        env.storage().persistent().remove(&key);
+        DeviceRemoved { public_key }.publish(&env);
+        Ok(())
+    }
+
+    /// Replace the owner key.
+    pub fn rotate_owner(env: Env, public_key: BytesN<32>) -> Result<(), Error> {
+        env.current_contract_address().require_auth();
+        env.storage().instance().set(&DataKey::Owner, &public_key);
+        OwnerRotated { public_key }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_owner(env: Env) -> Result<BytesN<32>, Error> {
+        Self::owner(&env)
+    }
+
+    pub fn get_device(env: Env, public_key: BytesN<32>) -> Option<DevicePolicy> {
+        env.storage().persistent().get(&DataKey::Device(public_key))
+    }
+
+    fn owner(env: &Env) -> Result<BytesN<32>, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Owner)
+            .ok_or(Error::NotInitialized)
+    }
+
+    // ── Policy checks ─────────────────────────────────────────────
+
+    fn check_device_context(
+        env: &Env,
+        policy: &DevicePolicy,
+        context: &Context,
+    ) -> Result<(), Error> {
+        let Context::Contract(ContractContext {
+            contract,
+            fn_name,
+            args,
+        }) = context
+        else {
+            return Err(Error::NotPermitted);
+        };
+
+        if *contract == policy.renewal_contract {
+            // approve_renewal(sub_id, approval_id, max_spend, expires_at)
+            if *fn_name == Symbol::new(env, "approve_renewal") {
+                let max_spend = arg::<i128>(env, args, 2)?;
+                if max_spend > policy.max_spend {
+                    return Err(Error::CapExceeded);
+                }
+                return Ok(());
+            }
+            if *fn_name == Symbol::new(env, "cancel_sub") {
+                return Ok(());
+            }
+        } else if *contract == policy.token && *fn_name == Symbol::new(env, "approve") {
+            // approve(from, spender, amount, expiration_ledger)
+            let spender = arg::<Address>(env, args, 1)?;
+            let amount = arg::<i128>(env, args, 2)?;
+            if spender != policy.renewal_contract {
+                return Err(Error::NotPermitted);
+            }
+            if amount > policy.allowance_cap {
+                return Err(Error::CapExceeded);
+            }
+            return Ok(());
+        }
+        Err(Error::NotPermitted)
+    }
+}
+
+/// Decode the `index`-th argument of an authorized call.
+fn arg<T: TryFromVal<Env, Val>>(env: &Env, args: &Vec<Val>, index: u32) -> Result<T, Error> {
+    let val = args.get(index).ok_or(Error::NotPermitted)?;
+    T::try_from_val(env, &val).map_err(|_| Error::NotPermitted)
+}
+
+#[contractimpl]
+impl CustomAccountInterface for SubscriptionAccountContract {
+    type Signature = AccountSignature;
+    type Error = Error;
+
+    /// The owner key authorizes anything. A device key authorizes only the
+    /// contexts its policy allows, and only until it expires.
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signature: AccountSignature,
+        auth_contexts: Vec<Context>,
+    ) -> Result<(), Error> {
+        env.crypto().ed25519_verify(
+            &signature.public_key,
+            &signature_payload.into(),
+            &signature.signature,
+        );
+
+        if signature.public_key == Self::owner(&env)? {
+            return Ok(());
+        }
+
+        let policy: DevicePolicy = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Device(signature.public_key))
+            .ok_or(Error::UnknownSigner)?;
+        if env.ledger().sequence() > policy.expires_at {
+            return Err(Error::DeviceExpired);
+        }
+        for context in auth_contexts.iter() {
+            Self::check_device_context(&env, &policy, &context)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,273 @@
+use super::*;
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    testutils::{Address as _, BytesN as _, Ledger},
+    Address, Env, IntoVal, InvokeError, Val,
+};
+
+struct Setup {
+    env: Env,
+    client: SubscriptionAccountContractClient<'static>,
+    account: Address,
+    owner: SigningKey,
+    device: SigningKey,
+    renewal: Address,
+    token: Address,
+}
+
+fn public_key(env: &Env, key: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &key.verifying_key().to_bytes())
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let account = env.register(SubscriptionAccountContract, ());
+    let client = SubscriptionAccountContractClient::new(&env, &account);
+    let owner = SigningKey::from_bytes(&[1; 32]);
+    let device = SigningKey::from_bytes(&[2; 32]);
+    client.init(&public_key(&env, &owner));
+
+    let renewal = Address::generate(&env);
+    let token = Address::generate(&env);
+    client.add_device(
+        &public_key(&env, &device),
+        &DevicePolicy {
+            renewal_contract: renewal.clone(),
+            max_spend: 1_000,
+            token: token.clone(),
+            allowance_cap: 12_000,
+            expires_at: 500,
+        },
+    );
+
+    Setup {
+        env,
+        client,
+        account,
+        owner,
+        device,
+        renewal,
+        token,
+    }
+}
+
+fn call(env: &Env, contract: &Address, fn_name: &str, args: Vec<Val>) -> Context {
+    Context::Contract(ContractContext {
+        contract: contract.clone(),
+        fn_name: Symbol::new(env, fn_name),
+        args,
+    })
+}
+
+impl Setup {
+    /// Sign a random payload with `key` and run the account's auth check.
+    fn check(
+        &self,
+        key: &SigningKey,
+        contexts: &[Context],
+    ) -> Result<(), Result<Error, InvokeError>> {
+        let payload = BytesN::<32>::random(&self.env);
+        let signature = AccountSignature {
+            public_key: public_key(&self.env, key),
+            signature: BytesN::from_array(&self.env, &key.sign(&payload.to_array()).to_bytes()),
+        };
+        self.env.try_invoke_contract_check_auth::<Error>(
+            &self.account,
+            &payload,
+            signature.into_val(&self.env),
+            &Vec::from_slice(&self.env, contexts),
+        )
+    }
+
+    fn approve_renewal(&self, max_spend: i128) -> Context {
+        call(
+            &self.env,
+            &self.renewal,
+            "approve_renewal",
+            (1u64, 1u64, max_spend, 100u32).into_val(&self.env),
+        )
+    }
+
+    fn token_call(&self, fn_name: &str, to: &Address, amount: i128) -> Context {
+        call(
+            &self.env,
+            &self.token,
+            fn_name,
+            (self.account.clone(), to.clone(), amount, 1_000u32).into_val(&self.env),
+        )
+    }
+}
+
+#[test]
+fn test_owner_signs_anything() {
+    let s = setup();
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.check(&s.owner, &[s.token_call("transfer", &stranger, 1_000_000)]),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_device_approves_renewals_up_to_cap() {
+    let s = setup();
+    assert_eq!(s.check(&s.device, &[s.approve_renewal(1_000)]), Ok(()));
+    assert_eq!(
+        s.check(&s.device, &[s.approve_renewal(1_001)]),
+        Err(Ok(Error::CapExceeded))
+    );
+}
+
+#[test]
+fn test_device_can_cancel_subscriptions() {
+    let s = setup();
+    let cancel = call(
+        &s.env,
+        &s.renewal,
+        "cancel_sub",
+        (7u64, None::<syncro_common::ReasonCode>).into_val(&s.env),
+    );
+    assert_eq!(s.check(&s.device, &[cancel]), Ok(()));
+}
+
+#[test]
+fn test_device_cannot_move_funds() {
+    let s = setup();
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.check(&s.device, &[s.token_call("transfer", &stranger, 1)]),
+        Err(Ok(Error::NotPermitted))
+    );
+
+    // Other renewal entrypoints are off limits too
+    let admin = call(
+        &s.env,
+        &s.renewal,
+        "set_admin",
+        (stranger.clone(),).into_val(&s.env),
+    );
+    assert_eq!(s.check(&s.device, &[admin]), Err(Ok(Error::NotPermitted)));
+}
+
+#[test]
+fn test_device_allowance_only_to_renewal_contract() {
+    let s = setup();
+    assert_eq!(
+        s.check(&s.device, &[s.token_call("approve", &s.renewal, 12_000)]),
+        Ok(())
+    );
+    assert_eq!(
+        s.check(&s.device, &[s.token_call("approve", &s.renewal, 12_001)]),
+        Err(Ok(Error::CapExceeded))
+    );
+
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.check(&s.device, &[s.token_call("approve", &stranger, 1)]),
+        Err(Ok(Error::NotPermitted))
+    );
+}
+
+#[test]
+fn test_one_forbidden_context_rejects_all() {
+    let s = setup();
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.check(
+            &s.device,
+            &[
+                s.approve_renewal(10),
+                s.token_call("transfer", &stranger, 1)
+            ]
+        ),
+        Err(Ok(Error::NotPermitted))
+    );
+}
+
+#[test]
+fn test_device_expires() {
+    let s = setup();
+    s.env.ledger().with_mut(|li| li.sequence_number = 501);
+    assert_eq!(
+        s.check(&s.device, &[s.approve_renewal(10)]),
+        Err(Ok(Error::DeviceExpired))
+    );
+}
+
+#[test]
+fn test_removed_and_unknown_keys_rejected() {
+    let s = setup();
+    s.client.remove_device(&public_key(&s.env, &s.device));
+    assert!(s
+        .client
+        .get_device(&public_key(&s.env, &s.device))
+        .is_none());
+    assert_eq!(
+        s.check(&s.device, &[s.approve_renewal(10)]),
+        Err(Ok(Error::UnknownSigner))
+    );
+
+    let stranger = SigningKey::from_bytes(&[3; 32]);
+    assert_eq!(
+        s.check(&stranger, &[s.approve_renewal(10)]),
+        Err(Ok(Error::UnknownSigner))
+    );
+}
+
+#[test]
+fn test_forged_signature_rejected() {
+    let s = setup();
+    let payload = BytesN::<32>::random(&s.env);
+    // Device signature presented under the owner's key
+    let signature = AccountSignature {
+        public_key: public_key(&s.env, &s.owner),
+        signature: BytesN::from_array(&s.env, &s.device.sign(&payload.to_array()).to_bytes()),
+    };
+    assert!(s
+        .env
+        .try_invoke_contract_check_auth::<Error>(
+            &s.account,
+            &payload,
+            signature.into_val(&s.env),
+            &Vec::new(&s.env),
+        )
+        .is_err());
+}
+
+#[test]
+fn test_owner_rotation() {
+    let s = setup();
+    let new_owner = SigningKey::from_bytes(&[4; 32]);
+    s.client.rotate_owner(&public_key(&s.env, &new_owner));
+    assert_eq!(s.client.get_owner(), public_key(&s.env, &new_owner));
+
+    let stranger = Address::generate(&s.env);
+    let transfer = s.token_call("transfer", &stranger, 5);
+    assert_eq!(
+        s.check(&new_owner, core::slice::from_ref(&transfer)),
+        Ok(())
+    );
+    assert_eq!(
+        s.check(&s.owner, &[transfer]),
+        Err(Ok(Error::UnknownSigner))
+    );
+}
+
+#[test]
+fn test_invalid_policy_rejected() {
+    let s = setup();
+    let policy = DevicePolicy {
+        renewal_contract: s.renewal.clone(),
+        max_spend: -1,
+        token: s.token.clone(),
+        allowance_cap: 0,
+        expires_at: 500,
+    };
+    assert_eq!(
+        s.client
+            .try_add_device(&public_key(&s.env, &s.device), &policy),
+        Err(Ok(Error::InvalidPolicy))
+    );
+}
@@ -1,17 +1,55 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec};
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractimpl, contracttype, Address, BytesN, Env,
+    String, Val, Vec,
+};
+use syncro_access::AccessError;
+use syncro_ratelimit::LimitConfig;
+use syncro_upgrade::{Migration, UpgradeError};
 
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub enum LogEvent {
-    Reminder,
-    Approval,
-    Renewal,
-    Failure,
-    Retry,
-    Cancellation,
+pub use syncro_common::LogEvent;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 401,
+    NotInitialized = 402,
+    InvalidLimit = 403,
+    RateLimited = 404,
+    NoPendingAdmin = 405,
+    Unauthorized = 406,
+    Paused = 407,
+    VersionMismatch = 408,
+    Private = 409,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+impl From<UpgradeError> for Error {
+    fn from(err: UpgradeError) -> Self {
+        match err {
+            UpgradeError::NotInitialized => Error::NotInitialized,
+            UpgradeError::VersionMismatch => Error::VersionMismatch,
+        }
+    }
 }
 
+/// Storage layout version of this code; see `migrate`.
+pub const CODE_VERSION: u32 = 1;
+
+/// Steps from each earlier code version to the next, oldest first.
+const MIGRATIONS: &[Migration] = &[];
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct LogEntry {
@@ -21,10 +59,35 @@ pub struct LogEntry {
     pub data: String,
 }
 
+/// How many entries a subscription has and the newest few of them, oldest
+/// first.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogSummary {
+    pub count: u32,
+    pub recent: Vec<LogEntry>,
+}
+
 #[contracttype]
 #[derive(Clone)]
 enum DataKey {
-    Logs(u64), // sub_id -> Vec<LogEntry>
+    RateLimit,      // LimitConfig applied per subscription
+    CircuitBreaker, // protocol circuit breaker address
+    Renewal,        // renewal contract whose privacy mode gates reads
+    Logs(u64),      // sub_id -> Vec<LogEntry>
+}
+
+/// Interface of the protocol-wide circuit breaker.
+#[contractclient(name = "CircuitBreakerClient")]
+pub trait CircuitBreakerInterface {
+    fn is_paused(env: Env, contract: Address) -> bool;
+}
+
+/// Interface of the renewal contract deciding who may read logs.
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn is_privacy_mode(env: Env) -> bool;
+    fn can_view(env: Env, viewer: Address, sub_id: u64) -> bool;
 }
 
 #[contract]
@@ -32,8 +95,106 @@ pub struct SubscriptionLoggingContract;
 
 #[contractimpl]
 impl SubscriptionLoggingContract {
-    pub fn record_log(env: Env, sub_id: u64, event: LogEvent, data: String) {
+    /// Set the admin allowed to configure the rate limit and circuit
+    /// breaker. Logging works without an admin.
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        syncro_upgrade::init_version(&env, CODE_VERSION);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    /// Nominate a new admin, who takes over once they call `accept_admin`.
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        syncro_access::propose_admin(&env, &new_admin)?;
+        Ok(())
+    }
+
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        syncro_access::accept_admin(&env)?;
+        Ok(())
+    }
+
+    /// Swap in the uploaded `wasm_hash`, keeping all logs. Admin only.
+    /// Follow with `migrate` on the new code when its `CODE_VERSION` moved.
+    pub fn upgrade(env: Env, wasm_hash: BytesN<32>) -> Result<(), Error> {
+        syncro_upgrade::upgrade(&env, &wasm_hash)?;
+        Ok(())
+    }
+
+    /// Bring storage from code version `from`, the one recorded, up to
+    /// `to`. Admin only.
+    pub fn migrate(env: Env, from: u32, to: u32) -> Result<(), Error> {
+        syncro_upgrade::migrate(&env, from, to, CODE_VERSION, MIGRATIONS)?;
+        Ok(())
+    }
+
+    pub fn get_code_version(env: Env) -> u32 {
+        syncro_upgrade::version(&env)
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    /// Limit how often entries can be logged for one subscription. Admin
+    /// only; unlimited until set.
+    pub fn set_rate_limit(env: Env, config: LimitConfig) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        if !config.is_valid() {
+            return Err(Error::InvalidLimit);
+        }
+        env.storage().instance().set(&DataKey::RateLimit, &config);
+        Ok(())
+    }
+
+    /// Refuse new entries while the circuit breaker pauses this contract.
+    /// Admin only.
+    pub fn set_circuit_breaker(env: Env, breaker: Address) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::CircuitBreaker, &breaker);
+        Ok(())
+    }
+
+    /// Follow `renewal`'s privacy mode: while it is on, logs are only
+    /// served through `get_logs_as` to viewers it allows. Admin only;
+    /// `None` makes logs public again.
+    pub fn set_renewal_contract(env: Env, renewal: Option<Address>) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        match renewal {
+            Some(renewal) => env.storage().instance().set(&DataKey::Renewal, &renewal),
+            None => env.storage().instance().remove(&DataKey::Renewal),
+        }
+        Ok(())
+    }
+
+    pub fn record_log(env: Env, sub_id: u64, event: LogEvent, data: String) -> Result<(), Error> {
+        if let Some(breaker) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::CircuitBreaker)
+        {
+            if CircuitBreakerClient::new(&env, &breaker).is_paused(&env.current_contract_address())
+            {
+                return Err(Error::Paused);
+            }
+        }
         let key = DataKey::Logs(sub_id);
+        if let Some(limit) = env
+            .storage()
+            .instance()
+            .get::<_, LimitConfig>(&DataKey::RateLimit)
+        {
+            syncro_ratelimit::check_and_consume(&env, &key, &limit)
+                .map_err(|_| Error::RateLimited)?;
+        }
+
         let mut logs: Vec<LogEntry> = env
             .storage()
             .persistent()
@@ -49,14 +210,53 @@ impl SubscriptionLoggingContract {
 
         logs.push_back(entry);
         env.storage().persistent().set(&key, &logs);
+        Ok(())
     }
 
-    pub fn get_logs(env: Env, sub_id: u64) -> Vec<LogEntry> {
-        let key = DataKey::Logs(sub_id);
+    pub fn get_logs(env: Env, sub_id: u64) -> Result<Vec<LogEntry>, Error> {
+        if Self::renewal_if_private(&env).is_some() {
+            return Err(Error::Private);
+        }
+        Ok(Self::load_logs(&env, sub_id))
+    }
+
+    /// `get_logs` for `viewer`, who must authorize the read and be allowed
+    /// by the renewal contract while it is in privacy mode.
+    pub fn get_logs_as(env: Env, viewer: Address, sub_id: u64) -> Result<Vec<LogEntry>, Error> {
+        if let Some(renewal) = Self::renewal_if_private(&env) {
+            viewer.require_auth();
+            if !renewal.can_view(&viewer, &sub_id) {
+                return Err(Error::Unauthorized);
+            }
+        }
+        Ok(Self::load_logs(&env, sub_id))
+    }
+
+    /// Entry count and up to `recent` newest entries for `sub_id`, for
+    /// callers that do not need the whole history.
+    pub fn get_log_summary(env: Env, sub_id: u64, recent: u32) -> Result<LogSummary, Error> {
+        let logs = Self::get_logs(env, sub_id)?;
+        let count = logs.len();
+        Ok(LogSummary {
+            count,
+            recent: logs.slice(count.saturating_sub(recent)..),
+        })
+    }
+
+    fn load_logs(env: &Env, sub_id: u64) -> Vec<LogEntry> {
         env.storage()
             .persistent()
-            .get(&key)
-            .unwrap_or(Vec::new(&env))
+            .get(&DataKey::Logs(sub_id))
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn renewal_if_private(env: &Env) -> Option<RenewalClient<'_>> {
+        let renewal = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::Renewal)?;
+        let client = RenewalClient::new(env, &renewal);
+        client.is_privacy_mode().then_some(client)
     }
 }
 
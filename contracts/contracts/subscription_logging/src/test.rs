@@ -1,5 +1,5 @@
 use super::*;
-use soroban_sdk::{testutils::Address as _, Env};
+use soroban_sdk::{contract, testutils::Address as _, Env};
 
 #[test]
 fn test_logging() {
@@ -23,4 +23,123 @@ fn test_logging() {
     assert_eq!(logs.len(), 2);
     assert_eq!(logs.get(0).unwrap().event, LogEvent::Renewal);
     assert_eq!(logs.get(1).unwrap().event, LogEvent::Failure);
+
+    let summary = client.get_log_summary(&sub_id, &1);
+    assert_eq!(summary.count, 2);
+    assert_eq!(summary.recent, logs.slice(1..));
+    assert_eq!(client.get_log_summary(&sub_id, &5).recent, logs);
+    assert_eq!(client.get_log_summary(&7, &5).count, 0);
+}
+
+#[test]
+fn test_rate_limit_per_subscription() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionLoggingContract, ());
+    let client = SubscriptionLoggingContractClient::new(&env, &contract_id);
+    let data = String::from_str(&env, "Success");
+
+    assert_eq!(
+        client.try_set_rate_limit(&LimitConfig::fixed_window(2, 3_600)),
+        Err(Ok(Error::NotInitialized))
+    );
+    client.init(&Address::generate(&env));
+    client.set_rate_limit(&LimitConfig::fixed_window(2, 3_600));
+
+    client.record_log(&1, &LogEvent::Renewal, &data);
+    client.record_log(&1, &LogEvent::Renewal, &data);
+    assert_eq!(
+        client.try_record_log(&1, &LogEvent::Renewal, &data),
+        Err(Ok(Error::RateLimited))
+    );
+    client.record_log(&2, &LogEvent::Renewal, &data);
+    assert_eq!(client.get_logs(&1).len(), 2);
+}
+
+/// Stand-in for the circuit breaker with every contract paused.
+#[contract]
+struct MockTrippedBreaker;
+
+#[contractimpl]
+impl MockTrippedBreaker {
+    pub fn is_paused(_env: Env, _contract: Address) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_circuit_breaker_blocks_logging() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionLoggingContract, ());
+    let client = SubscriptionLoggingContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+    client.set_circuit_breaker(&env.register(MockTrippedBreaker, ()));
+
+    assert_eq!(
+        client.try_record_log(&1, &LogEvent::Renewal, &String::from_str(&env, "Success")),
+        Err(Ok(Error::Paused))
+    );
+}
+
+/// Stand-in for a renewal contract in privacy mode that only lets the
+/// stored viewer read.
+#[contract]
+struct MockPrivateRenewal;
+
+#[contractimpl]
+impl MockPrivateRenewal {
+    pub fn allow(env: Env, viewer: Address) {
+        env.storage().instance().set(&0_u32, &viewer);
+    }
+
+    pub fn is_privacy_mode(_env: Env) -> bool {
+        true
+    }
+
+    pub fn can_view(env: Env, viewer: Address, _sub_id: u64) -> bool {
+        env.storage().instance().get(&0_u32) == Some(viewer)
+    }
+}
+
+#[test]
+fn test_private_logs_need_an_allowed_viewer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionLoggingContract, ());
+    let client = SubscriptionLoggingContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+    client.record_log(&1, &LogEvent::Renewal, &String::from_str(&env, "Success"));
+    let renewal = MockPrivateRenewalClient::new(&env, &env.register(MockPrivateRenewal, ()));
+    let owner = Address::generate(&env);
+    renewal.allow(&owner);
+
+    client.set_renewal_contract(&Some(renewal.address.clone()));
+    assert_eq!(client.try_get_logs(&1), Err(Ok(Error::Private)));
+    assert_eq!(client.try_get_log_summary(&1, &1), Err(Ok(Error::Private)));
+    assert_eq!(
+        client.try_get_logs_as(&Address::generate(&env), &1),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.get_logs_as(&owner, &1).len(), 1);
+
+    client.set_renewal_contract(&None);
+    assert_eq!(client.get_logs(&1).len(), 1);
+}
+
+#[test]
+fn test_code_version_recorded_on_init() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionLoggingContract, ());
+    let client = SubscriptionLoggingContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.try_migrate(&1, &2), Err(Ok(Error::NotInitialized)));
+    client.init(&Address::generate(&env));
+    assert_eq!(client.get_code_version(), CODE_VERSION);
+    // Already current: there is nothing to migrate to
+    assert_eq!(
+        client.try_migrate(&CODE_VERSION, &(CODE_VERSION + 1)),
+        Err(Ok(Error::VersionMismatch))
+    );
 }
@@ -1,15 +1,219 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractevent, contractimpl, contracttype, xdr::ToXdr, Address, Bytes, Env, IntoVal,
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, token,
+    xdr::ToXdr, Address, BytesN, Env, IntoVal, String, Symbol, Val, Vec,
 };
+use syncro_access::AccessError;
+pub use syncro_common::SubscriptionState;
+use syncro_common::{
+    address_book::{self, AGENTS, LOGGING, REGISTRY},
+    pause::PauseRecord,
+    ttl, LogEvent, Notification, ReasonCode, Scope,
+};
+use syncro_ratelimit::LimitConfig;
+use syncro_upgrade::{Migration, UpgradeError};
+
+/// Expected seconds between ledgers until the admin sets another with
+/// `set_ledger_seconds`.
+pub const LEDGER_SECONDS: u64 = 5;
+
+/// Seconds in one period of approval utilization.
+pub const UTILIZATION_PERIOD: u64 = 2_592_000;
+
+/// Periods of approval utilization kept per owner.
+pub const UTILIZATION_PERIODS: u32 = 12;
+
+/// Ledgers the earliest registered intent has the lock to itself once it
+/// comes free.
+pub const INTENT_PRIORITY_LEDGERS: u32 = 12;
+
+/// Ledgers past each renewal's due date during which only a
+/// subscription's preferred agent may lock it.
+pub const PREFERRED_AGENT_LEDGERS: u32 = 60;
+
+/// Largest step an indexation clause may raise an amount by, in basis
+/// points per interval.
+pub const MAX_INDEXATION_BPS: u32 = 1_000;
+
+/// Ledgers a scope the agent registry granted is trusted without asking
+/// it again.
+pub const AGENT_CACHE_LEDGERS: u32 = 120;
+
+/// Ledgers an owner has to confirm a renewal parked for going over the
+/// spending cap, about a day.
+pub const CONFIRMATION_LEDGERS: u32 = 17_280;
+
+/// Ledgers partial payments towards a cycle have to add up to its amount,
+/// counted from the first, about a day.
+pub const PARTIAL_PAYMENT_LEDGERS: u32 = 17_280;
+
+/// Operational keys a merchant can have acting for it at once.
+pub const MAX_MERCHANT_KEYS: u32 = 5;
+
+/// Payout recipients a subscription's charge can be split between.
+pub const MAX_PAYOUTS: u32 = 5;
+
+/// Renewal intents queued per subscription.
+pub const MAX_RENEWAL_INTENTS: u32 = 8;
+
+/// Most renewals one `renew_batch` takes.
+pub const MAX_RENEWAL_BATCH: u32 = 50;
+
+/// Seconds in the window a subscription's spending cap limits the sum of
+/// its charges over, counted from the first charge in the window.
+pub const SPEND_WINDOW: u64 = 2_592_000;
+
+/// Failed renewals a new subscription retries before failing, until the
+/// admin sets another default with `set_default_retry_policy`.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Ledgers a new subscription waits between retries, until the admin sets
+/// another default with `set_default_retry_policy`.
+pub const DEFAULT_COOLDOWN_LEDGERS: u32 = 10;
+
+/// Subscription ids per page of the index `sweep_expired` walks.
+const SWEEP_PAGE_SIZE: u32 = 100;
+
+/// Storage layout version of this code; see `migrate`.
+pub const CODE_VERSION: u32 = 1;
+
+/// Steps from each earlier code version to the next, oldest first.
+const MIGRATIONS: &[Migration] = &[];
+
+/// Moves one subscription's entries from one code version to the next.
+/// Entry `i` of a table upgrades subscriptions written by version `i + 1`.
+pub type SubMigration = fn(&Env, u64);
+
+/// Per-subscription steps from each earlier code version to the next,
+/// oldest first; run by `migrate_subs`.
+const SUB_MIGRATIONS: &[SubMigration] = &[];
+
+/// Role bit letting an account pause and unpause renewals.
+pub const PAUSER: u32 = 1;
+
+/// Role bit letting an account tune rate limits, timing windows, profile
+/// bounds and the default retry policy.
+pub const CONFIG_MANAGER: u32 = 1 << 1;
+
+/// Role bit letting an account set the protocol fee.
+pub const TREASURER: u32 = 1 << 2;
+
+const ALL_ROLES: u32 = PAUSER | CONFIG_MANAGER | TREASURER;
+
+/// Most accounts that can hold a role at once.
+pub const MAX_ROLE_HOLDERS: u32 = 20;
+
+/// Failures of the entrypoints, numbered in the renewal range of
+/// `syncro_common::errors`.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 101,
+    NotInitialized = 102,
+    NoPendingAdmin = 103,
+    VersionMismatch = 104,
+    Unauthorized = 105,
+    Paused = 106,
+    SubNotFound = 107,
+    LockRequired = 108,
+    LockActive = 109,
+    LockReserved = 110,
+    DuplicateCycle = 111,
+    CooldownActive = 112,
+    ApprovalInvalid = 113,
+    IntegrityViolation = 114,
+    SubFailed = 115,
+    SubCancelled = 116,
+    NotCancelled = 117,
+    CancellationFinal = 118,
+    CycleNotDerived = 119,
+    InvalidAmount = 120,
+    InvalidConfig = 121,
+    ProfileOutOfBounds = 122,
+    MerchantKeyExists = 123,
+    TooManyMerchantKeys = 124,
+    MerchantKeyNotFound = 125,
+    AgentNotAuthorized = 126,
+    AgentRateLimited = 127,
+    AgentNotAllowed = 128,
+    IntentExists = 129,
+    IntentQueueFull = 130,
+    InvalidIndexation = 131,
+    NoPendingRenewal = 132,
+    PendingRenewalRejected = 133,
+    AwaitingConfirmation = 134,
+    InvalidPayouts = 136,
+    ReceiptNotFound = 137,
+    PeerNotSet = 138,
+    NotOwner = 139,
+    AlreadyOwner = 140,
+    NotTransferable = 141,
+    PartialWindowClosed = 142,
+    PartialWindowOpen = 143,
+    ExceedsAmountDue = 144,
+    NoPartialPayments = 145,
+    Private = 146,
+    BatchTooLarge = 147,
+    TemplateNotFound = 148,
+    SubExpired = 149,
+    NotPaused = 150,
+    SubExists = 151,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+impl From<UpgradeError> for Error {
+    fn from(err: UpgradeError) -> Self {
+        match err {
+            UpgradeError::NotInitialized => Error::NotInitialized,
+            UpgradeError::VersionMismatch => Error::VersionMismatch,
+        }
+    }
+}
 
 /// Storage keys for contract-level state (admin, pause flag).
 #[contracttype]
 #[derive(Clone)]
 enum ContractKey {
-    Admin,
     Paused,
     LoggingContract,
+    MeteringContract,
+    ReceiptContract,
+    AgentRegistry,
+    LoyaltyContract,
+    CallbackRegistry,
+    PrepayContract,
+    AgentRateLimit,
+    CircuitBreaker,
+    ProtocolFee,
+    UtilityToken,
+    RecoveryContract,
+    Scheduler,
+    PrivacyMode,
+    AddressBook,
+    ProfileBounds,
+    SubscriptionRegistry,
+    LedgerSeconds,
+    RestoreWindow,
+    DerivedCycleIds,
+    Token,
+    NextTemplateId,
+    DelinquencyHorizon,
+    SweepLen,
+    SweepCursor,
+    GraceLedgers,
+    DefaultRetryPolicy,
+    RoleHolders,
 }
 
 /// Storage key for approvals: (sub_id, approval_id)
@@ -27,433 +231,4525 @@ struct CycleKey {
     sub_id: u64,
 }
 
-/// Storage key for renewal processing lock
+/// Storage key for renewal processing lock, in temporary storage
 #[contracttype]
 #[derive(Clone)]
 struct RenewalLockKey {
     lock_sub_id: u64,
 }
 
-/// Storage key for lifecycle timestamps per subscription
+/// Storage key for the agents queued for a subscription's next lock
 #[contracttype]
 #[derive(Clone)]
-struct LifecycleKey {
-    lifecycle_sub_id: u64,
+struct IntentKey {
+    intent_sub_id: u64,
 }
 
-/// Data stored for an active renewal lock
+/// Agents queued for a subscription's next lock, earliest first, and the
+/// ledger the lock was last released at, or the ledger the lock held will
+/// expire at.
 #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct RenewalLockData {
-    pub locked_at: u32,
-    pub lock_timeout: u32,
+#[derive(Clone)]
+struct IntentQueue {
+    intents: Vec<RenewalIntent>,
+    released_at: u32,
 }
 
-/// Renewal approval bound to subscription, amount, and expiration
+/// Storage key for when a subscription was cancelled and the state it
+/// was cancelled from
 #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct RenewalApproval {
-    pub sub_id: u64,
-    pub max_spend: i128,
-    pub expires_at: u32,
-    pub used: bool,
+#[derive(Clone)]
+struct CancellationKey {
+    cancelled_sub_id: u64,
 }
 
-/// Represents the current state of a subscription
+/// Storage key for the state a paused subscription resumes to
 #[contracttype]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum SubscriptionState {
-    Active,
-    Retrying,
-    Failed,
-    Cancelled,
+#[derive(Clone)]
+struct SubPauseKey {
+    pause_sub_id: u64,
 }
 
-/// Core subscription data stored on-chain
+/// A cancellation the owner may still undo.
 #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct SubscriptionData {
-    pub owner: Address,
-    pub merchant: Address,
-    pub amount: i128,
-    pub frequency: u64,
-    pub spending_cap: i128,
-    pub integrity_hash: soroban_sdk::BytesN<32>,
-    pub state: SubscriptionState,
-    pub failure_count: u32,
-    pub last_attempt_ledger: u32,
+#[derive(Clone)]
+struct Cancellation {
+    ledger: u32,
+    previous_state: SubscriptionState,
 }
 
-/// Immutable audit timestamps for subscription lifecycle events.
-/// All timestamps are Unix epoch seconds from env.ledger().timestamp().
+/// Storage key for the reason code an owner gave for cancelling
 #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct LifecycleTimestamps {
-    pub created_at: u64,
-    pub activated_at: u64,
-    pub last_renewed_at: u64,
-    pub canceled_at: u64,
+#[derive(Clone)]
+struct CancelReasonKey {
+    reason_sub_id: u64,
 }
 
-/// Events for subscription renewal tracking
-#[contractevent]
-pub struct RenewalSuccess {
-    pub sub_id: u64,
-    pub owner: Address,
+/// Storage key for the owner's notification preferences per subscription
+#[contracttype]
+#[derive(Clone)]
+struct NotificationKey {
+    notification_sub_id: u64,
 }
 
-#[contractevent]
-pub struct RenewalFailed {
-    pub sub_id: u64,
-    pub failure_count: u32,
-    pub ledger: u32,
+/// Storage key for the address paying for a subscription in its owner's
+/// place
+#[contracttype]
+#[derive(Clone)]
+struct PayerKey {
+    payer_sub_id: u64,
 }
 
-#[contractevent]
-pub struct StateTransition {
-    pub sub_id: u64,
-    pub new_state: SubscriptionState,
+/// Storage key for the payment strategy settling a subscription's charges
+#[contracttype]
+#[derive(Clone)]
+struct StrategyKey {
+    strategy_sub_id: u64,
 }
 
-#[contractevent]
-pub struct PauseToggled {
-    pub paused: bool,
+/// Storage key for the recipients a subscription's charge is split between
+#[contracttype]
+#[derive(Clone)]
+struct PayoutsKey {
+    payouts_sub_id: u64,
 }
 
-#[contractevent]
-pub struct ApprovalCreated {
-    pub sub_id: u64,
-    pub approval_id: u64,
-    pub max_spend: i128,
-    pub expires_at: u32,
+/// Storage key for the agent pinned to renew a subscription
+#[contracttype]
+#[derive(Clone)]
+struct PreferredAgentKey {
+    preferred_sub_id: u64,
 }
 
-#[contractevent]
-pub struct ApprovalRejected {
-    pub sub_id: u64,
-    pub approval_id: u64,
-    pub reason: u32, // 1=expired, 2=used, 3=amount_exceeded, 4=not_found
+/// Storage key for lifecycle timestamps per subscription
+#[contracttype]
+#[derive(Clone)]
+struct LifecycleKey {
+    lifecycle_sub_id: u64,
 }
 
-#[contractevent]
-pub struct DuplicateRenewalRejected {
-    pub sub_id: u64,
-    pub cycle_id: u64,
+/// Storage key for the states a subscription has been in
+#[contracttype]
+#[derive(Clone)]
+struct JournalKey {
+    journal_sub_id: u64,
 }
 
-#[contractevent]
-pub struct IntegrityViolation {
-    pub sub_id: u64,
+/// Storage key for why the latest renewal attempt failed
+#[contracttype]
+#[derive(Clone)]
+struct FailureKey {
+    failure_sub_id: u64,
 }
 
-#[contractevent]
-pub struct RenewalLockAcquired {
-    pub sub_id: u64,
-    pub locked_at: u32,
-    pub lock_timeout: u32,
+/// Storage key for the ledger a failing subscription's grace ends at
+#[contracttype]
+#[derive(Clone)]
+struct GraceKey {
+    grace_sub_id: u64,
 }
 
-#[contractevent]
-pub struct RenewalLockReleased {
-    pub sub_id: u64,
-    pub released_at: u32,
+/// Storage key for a merchant's configuration profile
+#[contracttype]
+#[derive(Clone)]
+struct MerchantKey {
+    profile_merchant: Address,
 }
 
-#[contractevent]
-pub struct RenewalLockExpired {
-    pub sub_id: u64,
-    pub original_locked_at: u32,
-    pub expired_at: u32,
+/// Storage key for the operational keys acting for a merchant
+#[contracttype]
+#[derive(Clone)]
+struct MerchantKeysKey {
+    keys_merchant: Address,
 }
 
-#[contractevent]
-pub struct LifecycleTimestampUpdated {
-    pub sub_id: u64,
-    pub event_kind: u32, // 1=created, 2=activated, 3=renewed, 4=canceled
-    pub timestamp: u64,
+/// Storage key for the subscription registry entry mirroring a subscription
+#[contracttype]
+#[derive(Clone)]
+struct RegistryEntryKey {
+    entry_sub_id: u64,
 }
 
-#[contract]
-pub struct SubscriptionRenewalContract;
-
-#[contractimpl]
-impl SubscriptionRenewalContract {
-    // ── Admin / Pause management ──────────────────────────────────
+/// Storage key for the subscriptions an owner has not cancelled
+#[contracttype]
+#[derive(Clone)]
+struct OwnerSubsKey {
+    subs_owner: Address,
+}
 
-    /// Initialize the contract admin. Can only be called once.
-    pub fn init(env: Env, admin: Address) {
-        if env.storage().instance().has(&ContractKey::Admin) {
-            panic!("Already initialized");
-        }
-        env.storage().instance().set(&ContractKey::Admin, &admin);
-        env.storage().instance().set(&ContractKey::Paused, &false);
-    }
+/// Storage key for an owner's approval utilization by period
+#[contracttype]
+#[derive(Clone)]
+struct UtilizationKey {
+    util_owner: Address,
+}
 
-    /// Internal helper – loads admin and calls `require_auth`.
-    fn require_admin(env: &Env) {
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&ContractKey::Admin)
-            .expect("Contract not initialized");
-        admin.require_auth();
-    }
+/// Storage key for the budget an owner gives an agent for approvals
+#[contracttype]
+#[derive(Clone)]
+struct DelegationKey {
+    delegation_owner: Address,
+    delegation_agent: Address,
+}
 
-    /// Pause or unpause all renewal execution. Admin only.
-    pub fn set_paused(env: Env, paused: bool) {
-        Self::require_admin(&env);
-        env.storage().instance().set(&ContractKey::Paused, &paused);
-        PauseToggled { paused }.publish(&env);
-    }
+/// Storage key for the delegation an agent created an approval under
+#[contracttype]
+#[derive(Clone)]
+struct DelegatedApprovalKey {
+    delegated_sub_id: u64,
+    delegated_approval_id: u64,
+}
 
-    /// Query the current pause state.
-    pub fn is_paused(env: Env) -> bool {
-        env.storage()
-            .instance()
-            .get(&ContractKey::Paused)
-            .unwrap_or(false)
-    }
+/// Storage key for a subscription's frequency change waiting for its
+/// cycle boundary
+#[contracttype]
+#[derive(Clone)]
+struct FrequencyChangeKey {
+    frequency_change_sub_id: u64,
+}
 
-    /// Set the logging contract address. Admin only.
-    pub fn set_logging_contract(env: Env, address: Address) {
-        Self::require_admin(&env);
-        env.storage()
-            .instance()
-            .set(&ContractKey::LoggingContract, &address);
-    }
+/// Storage key for the timestamp a subscription's billing periods are
+/// counted from since its last frequency change
+#[contracttype]
+#[derive(Clone)]
+struct BillingAnchorKey {
+    anchor_sub_id: u64,
+}
 
-    // ── Renewal lock management ────────────────────────────────────
+/// Storage key for the cheaper amount a subscription drops to instead of
+/// failing
+#[contracttype]
+#[derive(Clone)]
+struct FallbackKey {
+    fallback_sub_id: u64,
+}
 
-    /// Acquire a processing lock for a subscription renewal.
-    /// Prevents concurrent renewal execution by multiple workers.
-    pub fn acquire_renewal_lock(env: Env, sub_id: u64, lock_timeout: u32) {
-        if Self::is_paused(env.clone()) {
-            panic!("Protocol is paused");
-        }
+/// Storage key for what a subscription was charged in its current
+/// spending window
+#[contracttype]
+#[derive(Clone)]
+struct SpendKey {
+    spend_sub_id: u64,
+}
 
-        let lock_key = RenewalLockKey {
-            lock_sub_id: sub_id,
-        };
-        let current_ledger = env.ledger().sequence();
+/// Storage key for a subscription's retry policy
+#[contracttype]
+#[derive(Clone)]
+struct RetryPolicyKey {
+    retry_policy_sub_id: u64,
+}
 
-        if let Some(existing) = env
-            .storage()
-            .persistent()
-            .get::<RenewalLockKey, RenewalLockData>(&lock_key)
-        {
-            // Check if existing lock has expired
-            if current_ledger < existing.locked_at + existing.lock_timeout {
-                panic!("Renewal lock active");
-            }
-            // Lock expired — emit expiry event and allow re-acquisition
-            RenewalLockExpired {
-                sub_id,
-                original_locked_at: existing.locked_at,
-                expired_at: current_ledger,
-            }
-            .publish(&env);
-        }
+/// Storage key for a subscription's retry backoff policy
+#[contracttype]
+#[derive(Clone)]
+struct BackoffKey {
+    backoff_sub_id: u64,
+}
 
-        let lock_data = RenewalLockData {
-            locked_at: current_ledger,
-            lock_timeout,
-        };
-        env.storage().persistent().set(&lock_key, &lock_data);
+/// Storage key for the ledger a retrying subscription may next be retried
+/// at
+#[contracttype]
+#[derive(Clone)]
+struct RetryAtKey {
+    retry_sub_id: u64,
+}
 
-        RenewalLockAcquired {
-            sub_id,
-            locked_at: current_ledger,
-            lock_timeout,
-        }
-        .publish(&env);
-    }
+/// Storage key for a subscription's indexation clause
+#[contracttype]
+#[derive(Clone)]
+struct IndexationKey {
+    indexation_sub_id: u64,
+}
 
-    /// Release a processing lock for a subscription renewal.
-    pub fn release_renewal_lock(env: Env, sub_id: u64) {
+/// An indexation clause and when it next raises the amount.
+#[contracttype]
+#[derive(Clone)]
+struct IndexationSchedule {
+    terms: Indexation,
+    next_at: u64,
+}
+
+/// Storage key for a subscription's anchored terms; no version means the
+/// current anchor
+#[contracttype]
+#[derive(Clone)]
+struct MetadataKey {
+    metadata_sub_id: u64,
+    metadata_version: Option<u32>,
+}
+
+/// Storage key for a merchant's subscription template; no version means
+/// the latest one
+#[contracttype]
+#[derive(Clone)]
+struct TemplateKey {
+    template_id: u64,
+    template_version: Option<u32>,
+}
+
+/// Storage key for the template version a subscription was created from
+#[contracttype]
+#[derive(Clone)]
+struct SubTemplateKey {
+    template_sub_id: u64,
+}
+
+/// Storage key for the timestamp a subscription's first billing period
+/// starts at, when a trial puts it after creation
+#[contracttype]
+#[derive(Clone)]
+struct TrialKey {
+    trial_sub_id: u64,
+}
+
+/// Storage key for the code version a subscription's entries were
+/// written by
+#[contracttype]
+#[derive(Clone)]
+struct SubVersionKey {
+    version_sub_id: u64,
+}
+
+/// Storage key for a page of the subscriptions `sweep_expired` walks
+#[contracttype]
+#[derive(Clone)]
+struct SweepPageKey {
+    sweep_page: u32,
+}
+
+/// Storage key for the flag parking renewals over the spending cap for
+/// the owner to confirm instead of rejecting them
+#[contracttype]
+#[derive(Clone)]
+struct SoftCapKey {
+    soft_cap_sub_id: u64,
+}
+
+/// Storage key for a renewal over the spending cap awaiting the owner
+#[contracttype]
+#[derive(Clone)]
+struct PendingRenewalKey {
+    pending_sub_id: u64,
+}
+
+/// Storage key for what a successful renewal charged per cycle
+#[contracttype]
+#[derive(Clone)]
+struct ReceiptKey {
+    receipt_sub_id: u64,
+    receipt_cycle_id: u64,
+}
+
+/// Storage key for partial payments gathered towards one cycle
+#[contracttype]
+#[derive(Clone)]
+struct CycleFundingKey {
+    funding_sub_id: u64,
+    funding_cycle_id: u64,
+}
+
+/// Temporary storage key for the ledger until which the agent registry's
+/// grant of a scope to an agent is cached
+#[contracttype]
+#[derive(Clone)]
+struct AgentScopeKey {
+    cached_agent: Address,
+    cached_scope: Scope,
+}
+
+/// Storage key for the usage-metered flag per subscription
+#[contracttype]
+#[derive(Clone)]
+struct MeteredKey {
+    metered_sub_id: u64,
+}
+
+/// Storage key for the operator approved to transfer a subscription
+#[contracttype]
+#[derive(Clone)]
+struct TransferOperatorKey {
+    transfer_sub_id: u64,
+}
+
+/// Interface of the usage metering contract. Metered subscriptions are
+/// charged the amount settled for the cycle instead of a fixed amount.
+#[contractclient(name = "MeteringClient")]
+pub trait MeteringInterface {
+    fn quote(env: Env, sub_id: u64, period: u64) -> i128;
+    fn settle(env: Env, sub_id: u64, period: u64) -> i128;
+    fn get_settled(env: Env, sub_id: u64, period: u64) -> Option<i128>;
+}
+
+/// Interface of the receipt contract that mints proof-of-payment tokens.
+#[contractclient(name = "ReceiptClient")]
+pub trait ReceiptInterface {
+    fn mint(
+        env: Env,
+        owner: Address,
+        sub_id: u64,
+        cycle_id: u64,
+        amount: i128,
+        merchant: Address,
+    ) -> u64;
+}
+
+/// Interface of the logging contract that keeps the per-subscription audit log.
+#[contractclient(name = "LoggingClient")]
+pub trait LoggingInterface {
+    fn record_log(env: Env, sub_id: u64, event: LogEvent, data: soroban_sdk::String);
+}
+
+/// Interface of the agent registry consulted before a lock is handed out.
+#[contractclient(name = "AgentRegistryClient")]
+pub trait AgentRegistryInterface {
+    fn has_scope(env: Env, agent: Address, scope: Scope) -> bool;
+}
+
+/// Interface of the loyalty contract credited after each successful renewal.
+/// Returns the discount applied from the owner's redeemed points.
+#[contractclient(name = "LoyaltyClient")]
+pub trait LoyaltyInterface {
+    fn on_renewal(
+        env: Env,
+        sub_id: u64,
+        cycle_id: u64,
+        owner: Address,
+        merchant: Address,
+        amount: i128,
+    ) -> i128;
+}
+
+/// Interface of the prepay contract holding term deposits. Returns the
+/// amount paid to the merchant from the deposit, or 0 if nothing is prepaid.
+#[contractclient(name = "PrepayClient")]
+pub trait PrepayInterface {
+    fn draw(env: Env, sub_id: u64, cycle_id: u64) -> i128;
+}
+
+/// Interface of a contract settling a subscription's renewal charges in
+/// its own way, such as from escrow, through a swap or against a gift card
+/// balance. `renewal` is the calling contract, which authorizes the call.
+/// Returns the amount paid to the merchant, or 0 if the charge failed.
+/// Paying less than `amount` counts as a failed charge too.
+#[contractclient(name = "PaymentStrategyClient")]
+pub trait PaymentStrategy {
+    fn settle(
+        env: Env,
+        renewal: Address,
+        sub_id: u64,
+        cycle_id: u64,
+        payer: Address,
+        merchant: Address,
+        amount: i128,
+    ) -> i128;
+}
+
+/// Interface of the callback registry that pushes renewal events to
+/// registered contracts.
+#[contractclient(name = "CallbackRegistryClient")]
+pub trait CallbackRegistryInterface {
+    fn notify(env: Env, emitter: Address, notification: Notification) -> u32;
+}
+
+/// Interface of the SYNCRO utility token, whose staking tiers discount the
+/// protocol fee.
+#[contractclient(name = "UtilityTokenClient")]
+pub trait UtilityTokenInterface {
+    fn fee_discount_bps(env: Env, account: Address) -> u32;
+}
+
+/// Interface of the renewal scheduler that queues subscriptions by the
+/// ledger their next renewal is due.
+#[contractclient(name = "SchedulerClient")]
+pub trait SchedulerInterface {
+    fn schedule(env: Env, sub_id: u64, due_ledger: u32);
+    fn unschedule(env: Env, sub_id: u64) -> bool;
+}
+
+/// Interface of the user-facing subscription registry, kept in step with
+/// renewal outcomes for the subscriptions linked to an entry there.
+#[contractclient(name = "SubscriptionRegistryClient")]
+pub trait SubscriptionRegistryInterface {
+    fn get_user_subscriptions(env: Env, user: Address) -> Vec<BytesN<32>>;
+    fn mark_renewed(env: Env, subscription_id: BytesN<32>, renewer: Address) -> u64;
+    fn mark_at_risk(env: Env, subscription_id: BytesN<32>, renewer: Address);
+    fn get_subscription(env: Env, subscription_id: BytesN<32>) -> Option<RegistryMetadata>;
+}
+
+/// Mirror of the subscription registry's `SubscriptionMetadata`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegistryMetadata {
+    pub service_id: String,
+    pub billing_interval: u64,
+    pub expected_amount: i128,
+    pub next_renewal: u64,
+    pub is_active: bool,
+}
+
+/// Interface of the protocol-wide circuit breaker.
+#[contractclient(name = "CircuitBreakerClient")]
+pub trait CircuitBreakerInterface {
+    fn is_paused(env: Env, contract: Address) -> bool;
+    fn trip(env: Env, guardian: Address, reason: Symbol);
+}
+
+/// Data stored for an active renewal lock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RenewalLockData {
+    pub holder: Address,
+    pub locked_at: u32,
+    pub lock_timeout: u32,
+}
+
+/// An agent waiting for a subscription's renewal lock.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RenewalIntent {
+    pub agent: Address,
+    pub registered_at: u32,
+}
+
+/// Price adjustment agreed at init: the amount rises by `rate_bps` every
+/// `interval` seconds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Indexation {
+    pub rate_bps: u32,
+    pub interval: u64,
+}
+
+/// Terms a merchant offers every subscription created from a template.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplateTerms {
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    /// Seconds after creation before the first billing period starts.
+    pub trial: u64,
+}
+
+/// One version of a merchant's subscription template.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionTemplate {
+    pub merchant: Address,
+    pub version: u32,
+    pub terms: TemplateTerms,
+}
+
+/// The template version a subscription was created from.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplateRef {
+    pub template_id: u64,
+    pub version: u32,
+}
+
+/// Which notifications an owner agreed to receive.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NotificationMode {
+    All,
+    FailuresOnly,
+    Muted,
+}
+
+/// How an owner wants notification agents to contact them about a
+/// subscription; `remind_days` of 0 means no reminder before renewals.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NotificationPrefs {
+    pub remind_days: u32,
+    pub mode: NotificationMode,
+}
+
+/// A renewal of `amount` for `cycle_id` over the spending cap, which the
+/// owner can confirm until `expires_at`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRenewal {
+    pub cycle_id: u64,
+    pub amount: i128,
+    pub expires_at: u32,
+    pub confirmed: bool,
+}
+
+/// A new billing frequency agreed for a subscription, taking effect at
+/// the cycle boundary `effective_at`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FrequencyChange {
+    pub frequency: u64,
+    pub effective_at: u64,
+}
+
+/// One funder's part of a cycle's payment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartialPayment {
+    pub funder: Address,
+    pub amount: i128,
+}
+
+/// Partial payments towards a cycle that needs `required`, held by this
+/// contract and taken until `due_by`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CycleFunding {
+    pub required: i128,
+    pub paid: i128,
+    pub due_by: u32,
+    pub payments: Vec<PartialPayment>,
+}
+
+/// One renewal in a `renew_batch`, with the arguments `renew` takes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RenewalRequest {
+    pub sub_id: u64,
+    pub approval_id: u64,
+    pub amount: i128,
+    pub cycle_id: u64,
+}
+
+/// What became of one renewal in a `renew_batch`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RenewalOutcome {
+    Renewed,
+    /// `renew` returned false: the charge failed and retry logic ran, or
+    /// it was parked for the owner to confirm.
+    NotRenewed,
+    /// A precondition failed and nothing was renewed; holds the code of
+    /// the `Error` `renew` would have failed with.
+    Skipped(u32),
+}
+
+/// A recipient of part of each renewal charge, in proportion to `weight`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payout {
+    pub recipient: Address,
+    pub weight: u32,
+}
+
+/// What one recipient was paid out of a renewal charge.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutShare {
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+/// Record of a successful renewal: what `payer` was charged, the protocol
+/// fee out of it, the integrity hash of the terms charged under and the
+/// anchored terms' hash at the time, if any.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Receipt {
+    pub amount: i128,
+    pub fee: i128,
+    pub payer: Address,
+    pub merchant: Address,
+    pub ledger: u32,
+    pub terms_hash: BytesN<32>,
+    pub metadata_hash: Option<BytesN<32>>,
+}
+
+/// Limits an owner puts on the approvals one agent may create for them:
+/// the total `max_spend` of its unused approvals, and how many it holds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegationBudget {
+    pub max_outstanding: i128,
+    pub max_approvals: u32,
+}
+
+/// An agent's budget from an owner and how much of it the agent's unused
+/// approvals take up.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delegation {
+    pub budget: DelegationBudget,
+    pub outstanding: i128,
+    pub approvals: u32,
+}
+
+/// Renewal approval bound to subscription, amount, and expiration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RenewalApproval {
+    pub sub_id: u64,
+    pub max_spend: i128,
+    pub expires_at: u32,
+    pub used: bool,
+}
+
+/// How much of the spend an owner approved was charged within one
+/// [`UTILIZATION_PERIOD`]. A low `spent` against `max_spend` marks
+/// approvals broader than the charges they cover.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalUtilization {
+    /// Ledger timestamp divided by `UTILIZATION_PERIOD`.
+    pub period: u64,
+    /// Approvals consumed in the period.
+    pub approvals: u32,
+    /// Sum of their `max_spend`.
+    pub max_spend: i128,
+    /// Sum of the amounts they were consumed for.
+    pub spent: i128,
+}
+
+/// Hash of the off-chain terms (plan description, terms of service
+/// version) owner and merchant agreed on for a subscription.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetadataAnchor {
+    pub hash: BytesN<32>,
+    /// 1 for the first anchor, then one more per change.
+    pub version: u32,
+    pub anchored_at: u64,
+}
+
+/// Core subscription data stored on-chain
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionData {
+    pub owner: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub integrity_hash: soroban_sdk::BytesN<32>,
+    pub state: SubscriptionState,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+/// A subscription entering `state` at `ledger`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateChange {
+    pub state: SubscriptionState,
+    pub ledger: u32,
+}
+
+/// Immutable audit timestamps for subscription lifecycle events.
+/// All timestamps are Unix epoch seconds from env.ledger().timestamp().
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LifecycleTimestamps {
+    pub created_at: u64,
+    pub activated_at: u64,
+    pub last_renewed_at: u64,
+    pub canceled_at: u64,
+    pub paused_at: u64,
+    pub resumed_at: u64,
+}
+
+/// What a subscription was charged since `started_at`, counted against
+/// its spending cap until [`SPEND_WINDOW`] has passed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendWindow {
+    pub started_at: u64,
+    pub spent: i128,
+}
+
+/// How many failed renewals a subscription retries before failing, and
+/// how many ledgers apart.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub cooldown_ledgers: u32,
+}
+
+/// How long a failing subscription waits between retries: `base_ledgers`
+/// after the first failure, `multiplier` times longer after each further
+/// one, never more than `max_ledgers`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackoffPolicy {
+    pub base_ledgers: u32,
+    pub multiplier: u32,
+    pub max_ledgers: u32,
+}
+
+impl BackoffPolicy {
+    /// Cooldown after `failures` consecutive failed attempts.
+    pub fn cooldown(&self, failures: u32) -> u32 {
+        self.multiplier
+            .checked_pow(failures.saturating_sub(1))
+            .and_then(|factor| self.base_ledgers.checked_mul(factor))
+            .unwrap_or(u32::MAX)
+            .min(self.max_ledgers)
+    }
+}
+
+/// Settings a merchant applies to all of its subscriptions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerchantProfile {
+    /// Replaces each subscription's own `max_retries`.
+    pub max_retries: u32,
+    /// Replaces each subscription's own `cooldown_ledgers`.
+    pub cooldown_ledgers: u32,
+    /// Ledgers from the first failed attempt during which a subscription
+    /// keeps retrying even once its retries are used up.
+    pub grace_ledgers: u32,
+    /// Protocol fee in basis points charged instead of the global one.
+    pub fee_bps: Option<u32>,
+    /// Agents allowed to lock the merchant's subscriptions for renewal;
+    /// empty allows every agent the registry does.
+    pub allowed_agents: Vec<Address>,
+}
+
+/// Limits the admin puts on what merchants may set in their profiles.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileBounds {
+    pub max_retries: u32,
+    pub max_cooldown_ledgers: u32,
+    pub max_grace_ledgers: u32,
+    pub min_fee_bps: u32,
+    pub max_fee_bps: u32,
+    pub max_agents: u32,
+}
+
+impl ProfileBounds {
+    fn allow(&self, profile: &MerchantProfile) -> bool {
+        profile.max_retries <= self.max_retries
+            && profile.cooldown_ledgers <= self.max_cooldown_ledgers
+            && profile.grace_ledgers <= self.max_grace_ledgers
+            && profile
+                .fee_bps
+                .is_none_or(|fee| (self.min_fee_bps..=self.max_fee_bps).contains(&fee))
+            && profile.allowed_agents.len() <= self.max_agents
+    }
+}
+
+/// A protocol rule `check_invariants` found broken for one subscription.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InvariantViolation {
+    /// Cancelled, but `canceled_at` is unset.
+    CancelledWithoutTimestamp,
+    /// `canceled_at` is set on a subscription that is not cancelled.
+    TimestampWithoutCancel,
+    /// Failed or retrying with no failures counted. A grace period or a
+    /// changed retry policy can keep a subscription retrying past its
+    /// limit, so a failed subscription is only known to have failed at
+    /// least once.
+    FailuresNotCounted,
+    /// Active while still counting failures from earlier attempts.
+    ActiveWithFailures,
+    /// A renewal lock is held on a failed or cancelled subscription.
+    LockOnTerminalSub,
+    /// No lifecycle timestamps were stored for the subscription.
+    LifecycleMissing,
+    /// A lifecycle timestamp predates `created_at`.
+    LifecycleOutOfOrder,
+    /// The stored parameters no longer match their integrity hash.
+    IntegrityMismatch,
+}
+
+/// A way `check_link_consistency` found a subscription's linked registry
+/// entry to disagree with it.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LinkDiscrepancy {
+    /// The registry has no entry under the linked id.
+    EntryMissing,
+    /// The entry's `expected_amount` is not the subscription's amount.
+    AmountMismatch,
+    /// The entry's `billing_interval` is not the subscription's frequency.
+    IntervalMismatch,
+    /// The entry is active while the subscription has failed, been
+    /// cancelled or expired, or the other way round.
+    StatusMismatch,
+}
+
+/// Events for subscription renewal tracking
+#[contractevent]
+pub struct RenewalSuccess {
+    pub sub_id: u64,
+    pub owner: Address,
+    /// How the charge, net of the protocol fee, was paid out.
+    pub payouts: Vec<PayoutShare>,
+}
+
+/// A failed renewal attempt, with when the next attempt can clear the
+/// cooldown (`None` once the subscription has failed for good) and how
+/// many retries are left before any grace period.
+#[contractevent]
+pub struct RenewalFailed {
+    pub sub_id: u64,
+    pub failure_count: u32,
+    pub ledger: u32,
+    pub next_retry_ledger: Option<u32>,
+    pub retries_remaining: u32,
+}
+
+/// A failing subscription keeps retrying until `ends_at` even once its
+/// retries are used up.
+#[contractevent]
+pub struct GracePeriodStarted {
+    pub sub_id: u64,
+    pub ends_at: u32,
+}
+
+#[contractevent]
+pub struct StateTransition {
+    pub sub_id: u64,
+    pub new_state: SubscriptionState,
+}
+
+#[contractevent]
+pub struct FrequencyChangeScheduled {
+    pub sub_id: u64,
+    pub frequency: u64,
+    pub effective_at: u64,
+}
+
+#[contractevent]
+pub struct FrequencyChangeCancelled {
+    pub sub_id: u64,
+}
+
+#[contractevent]
+pub struct FrequencyChanged {
+    pub sub_id: u64,
+    pub previous_frequency: u64,
+    pub frequency: u64,
+}
+
+/// New terms the owner and merchant agreed for a subscription, in force at
+/// once.
+#[contractevent]
+pub struct AmendmentApplied {
+    pub sub_id: u64,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+}
+
+#[contractevent]
+pub struct DowngradedOnFailure {
+    pub sub_id: u64,
+    pub previous_amount: i128,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct RenewalPendingConfirmation {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub amount: i128,
+    pub expires_at: u32,
+}
+
+#[contractevent]
+pub struct PendingRenewalConfirmed {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct RenewalBatchProcessed {
+    pub requests: u32,
+    pub renewed: u32,
+    pub skipped: u32,
+}
+
+#[contractevent]
+pub struct PartialPaymentReceived {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub funder: Address,
+    pub amount: i128,
+    pub paid: i128,
+    pub required: i128,
+}
+
+#[contractevent]
+pub struct PartialPaymentsRefunded {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct AmountIndexed {
+    pub sub_id: u64,
+    pub previous_amount: i128,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct MetadataAnchored {
+    pub sub_id: u64,
+    pub hash: BytesN<32>,
+    pub version: u32,
+}
+
+#[contractevent]
+pub struct PauseToggled {
+    pub paused: bool,
+}
+
+#[contractevent]
+pub struct AdminTransferred {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+pub struct RoleGranted {
+    #[topic]
+    pub account: Address,
+    pub role: u32,
+    pub roles: u32,
+}
+
+#[contractevent]
+pub struct RoleRevoked {
+    #[topic]
+    pub account: Address,
+    pub role: u32,
+    pub roles: u32,
+}
+
+#[contractevent]
+pub struct ApprovalCreated {
+    pub sub_id: u64,
+    pub approval_id: u64,
+    pub max_spend: i128,
+    pub expires_at: u32,
+}
+
+#[contractevent]
+pub struct DelegationBudgetSet {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub agent: Address,
+    pub max_outstanding: i128,
+    pub max_approvals: u32,
+}
+
+#[contractevent]
+pub struct DelegationRevoked {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub agent: Address,
+}
+
+#[contractevent]
+pub struct ApprovalDelegated {
+    pub sub_id: u64,
+    pub approval_id: u64,
+    pub agent: Address,
+    pub max_spend: i128,
+}
+
+#[contractevent]
+pub struct ApprovalRejected {
+    pub sub_id: u64,
+    pub approval_id: u64,
+    pub reason: ReasonCode,
+}
+
+#[contractevent]
+pub struct ApprovalConsumed {
+    pub sub_id: u64,
+    pub approval_id: u64,
+    pub max_spend: i128,
+    pub spent: i128,
+}
+
+#[contractevent]
+pub struct DuplicateRenewalRejected {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+}
+
+/// A renewal rejected for taking the subscription's spend in its window
+/// past the spending cap.
+#[contractevent]
+pub struct SpendingCapExceeded {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub amount: i128,
+    pub spent: i128,
+    pub spending_cap: i128,
+}
+
+#[contractevent]
+pub struct IntegrityViolation {
+    pub sub_id: u64,
+}
+
+#[contractevent]
+pub struct RenewalLockAcquired {
+    pub sub_id: u64,
+    pub locked_at: u32,
+    pub lock_timeout: u32,
+}
+
+#[contractevent]
+pub struct RenewalLockReleased {
+    pub sub_id: u64,
+    pub released_at: u32,
+}
+
+#[contractevent]
+pub struct RenewalIntentRegistered {
+    pub sub_id: u64,
+    pub agent: Address,
+    pub position: u32,
+}
+
+#[contractevent]
+pub struct SubscriptionMigrated {
+    pub sub_id: u64,
+    pub from: u32,
+    pub to: u32,
+}
+
+#[contractevent]
+pub struct SubscriptionExpired {
+    pub sub_id: u64,
+    pub due_at: u64,
+}
+
+#[contractevent]
+pub struct SubscriptionCancelled {
+    pub sub_id: u64,
+    pub merchant: Address,
+    pub reason: Option<ReasonCode>,
+}
+
+#[contractevent]
+pub struct SubscriptionRestored {
+    pub sub_id: u64,
+    pub state: SubscriptionState,
+}
+
+#[contractevent]
+pub struct PayerSet {
+    pub sub_id: u64,
+    pub payer: Option<Address>,
+}
+
+#[contractevent]
+pub struct PaymentStrategySet {
+    pub sub_id: u64,
+    pub strategy: Option<Address>,
+}
+
+#[contractevent]
+pub struct NotificationPrefsSet {
+    pub sub_id: u64,
+    pub remind_days: Option<u32>,
+    pub mode: Option<NotificationMode>,
+}
+
+#[contractevent]
+pub struct PreferredAgentSet {
+    pub sub_id: u64,
+    pub agent: Option<Address>,
+}
+
+#[contractevent]
+pub struct RenewalLockExpired {
+    pub sub_id: u64,
+    pub original_locked_at: u32,
+    pub expired_at: u32,
+}
+
+#[contractevent]
+pub struct ProtocolFeeCharged {
+    pub sub_id: u64,
+    pub cycle_id: u64,
+    pub merchant: Address,
+    pub fee: i128,
+    pub discount_bps: u32,
+}
+
+#[contractevent]
+pub struct TransferOperatorSet {
+    pub sub_id: u64,
+    pub operator: Option<Address>,
+}
+
+#[contractevent]
+pub struct SubscriptionTransferred {
+    pub sub_id: u64,
+    pub from: Address,
+    pub to: Address,
+}
+
+#[contractevent]
+pub struct TemplateSet {
+    pub template_id: u64,
+    pub merchant: Address,
+    pub version: u32,
+}
+
+#[contractevent]
+pub struct MerchantProfileSet {
+    pub merchant: Address,
+    pub cleared: bool,
+}
+
+#[contractevent]
+pub struct AgentCacheInvalidated {
+    pub agent: Address,
+}
+
+#[contractevent]
+pub struct MerchantKeySet {
+    pub merchant: Address,
+    pub key: Address,
+    pub revoked: bool,
+}
+
+#[contractevent]
+pub struct LifecycleTimestampUpdated {
+    pub sub_id: u64,
+    pub event_kind: u32, // 1=created, 2=activated, 3=renewed, 4=canceled, 5=paused, 6=resumed
+    pub timestamp: u64,
+}
+
+/// Ledgers closed in `seconds` at `ledger_seconds` a ledger, saturating at
+/// `u32::MAX`.
+fn ledgers_at(seconds: u64, ledger_seconds: u64) -> u32 {
+    (seconds / ledger_seconds).min(u32::MAX as u64) as u32
+}
+
+/// Extend the persistent entry under `key` once it nears archival, as
+/// reads and writes on the hot paths do.
+fn keep_alive<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, ttl::BUMP_THRESHOLD, ttl::BUMP_TO);
+}
+
+/// `amount * part / whole` rounded down, for a non-negative `amount` and
+/// `part <= whole`, without overflowing on the way.
+fn portion(amount: i128, part: i128, whole: i128) -> i128 {
+    amount / whole * part + amount % whole * part / whole
+}
+
+#[contract]
+pub struct SubscriptionRenewalContract;
+
+#[contractimpl]
+impl SubscriptionRenewalContract {
+    // ── Admin / Pause management ──────────────────────────────────
+
+    /// Initialize the contract admin. Can only be called once.
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        env.storage().instance().set(&ContractKey::Paused, &false);
+        syncro_upgrade::init_version(&env, CODE_VERSION);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    /// Internal helper – loads admin and calls `require_auth`.
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        syncro_access::require_admin(env)?;
+        Ok(())
+    }
+
+    /// Internal helper – requires `caller`'s auth and that it is the admin
+    /// or holds `role`.
+    fn require_admin_or(env: &Env, caller: &Address, role: u32) -> Result<(), Error> {
+        caller.require_auth();
+        if *caller != syncro_access::admin(env)? && !syncro_access::has_role(env, caller, role) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    // ── Roles ─────────────────────────────────────────────────────
+
+    /// Give `account` the `role` bits, any of `PAUSER`, `CONFIG_MANAGER`
+    /// and `TREASURER`, on top of those it holds. Admin only. Returns its
+    /// roles now.
+    pub fn grant_role(env: Env, account: Address, role: u32) -> Result<u32, Error> {
+        if role == 0 || role & !ALL_ROLES != 0 {
+            return Err(Error::InvalidConfig);
+        }
+        let mut holders = Self::get_role_holders(env.clone());
+        if !holders.contains(&account) {
+            if holders.len() >= MAX_ROLE_HOLDERS {
+                return Err(Error::InvalidConfig);
+            }
+            holders.push_back(account.clone());
+        }
+        let roles = syncro_access::grant_role(&env, &account, role)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::RoleHolders, &holders);
+        RoleGranted {
+            account,
+            role,
+            roles,
+        }
+        .publish(&env);
+        Ok(roles)
+    }
+
+    /// Take the `role` bits from `account`. Admin only. Returns its roles
+    /// now; an account left with none drops off `get_role_holders`.
+    /// Revoking bits the account doesn't hold publishes nothing.
+    pub fn revoke_role(env: Env, account: Address, role: u32) -> Result<u32, Error> {
+        let held = syncro_access::roles(&env, &account);
+        let roles = syncro_access::revoke_role(&env, &account, role)?;
+        if roles == held {
+            return Ok(roles);
+        }
+        if roles == 0 {
+            let mut holders = Self::get_role_holders(env.clone());
+            if let Some(i) = holders.first_index_of(&account) {
+                holders.remove(i);
+                env.storage()
+                    .instance()
+                    .set(&ContractKey::RoleHolders, &holders);
+            }
+        }
+        RoleRevoked {
+            account,
+            role,
+            roles,
+        }
+        .publish(&env);
+        Ok(roles)
+    }
+
+    /// The role bits `account` holds. The admin holds every power without
+    /// any bits set.
+    pub fn get_roles(env: Env, account: Address) -> u32 {
+        syncro_access::roles(&env, &account)
+    }
+
+    /// Accounts holding at least one role, in the order first granted.
+    pub fn get_role_holders(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&ContractKey::RoleHolders)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Hand the admin role to a new address immediately. Admin only. Used
+    /// by the suite factory to pass on a freshly deployed contract; prefer
+    /// `propose_admin` otherwise.
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let previous_admin = syncro_access::set_admin(&env, &new_admin)?;
+        AdminTransferred {
+            previous_admin,
+            new_admin,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Nominate a new admin, who takes over once they call `accept_admin`.
+    /// Admin only.
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        syncro_access::propose_admin(&env, &new_admin)?;
+        Ok(())
+    }
+
+    /// Complete a handover started with `propose_admin`. Nominee only.
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        let (previous_admin, new_admin) = syncro_access::accept_admin(&env)?;
+        AdminTransferred {
+            previous_admin,
+            new_admin,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Query the current admin.
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        Ok(syncro_access::admin(&env)?)
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        syncro_access::pending_admin(&env)
+    }
+
+    /// Swap in the uploaded `wasm_hash`, keeping subscriptions and
+    /// settings. Admin only. Follow with `migrate` on the new code when its
+    /// `CODE_VERSION` moved.
+    pub fn upgrade(env: Env, wasm_hash: BytesN<32>) -> Result<(), Error> {
+        syncro_upgrade::upgrade(&env, &wasm_hash)?;
+        Ok(())
+    }
+
+    /// Bring storage from code version `from`, the one recorded, up to
+    /// `to`. Admin only. Subscriptions are too many to move in one call;
+    /// follow with `migrate_subs` for those written by older code.
+    pub fn migrate(env: Env, from: u32, to: u32) -> Result<(), Error> {
+        syncro_upgrade::migrate(&env, from, to, CODE_VERSION, MIGRATIONS)?;
+        Ok(())
+    }
+
+    pub fn get_code_version(env: Env) -> u32 {
+        syncro_upgrade::version(&env)
+    }
+
+    /// Bring each of `sub_ids`, at most `MAX_RENEWAL_BATCH`, from the code
+    /// version it was written by up to the one storage was migrated to.
+    /// Admin only. Unknown and current subscriptions are skipped. Returns
+    /// how many were migrated.
+    pub fn migrate_subs(env: Env, sub_ids: Vec<u64>) -> Result<u32, Error> {
+        Self::require_admin(&env)?;
+        if sub_ids.len() > MAX_RENEWAL_BATCH {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(Self::migrate_entries(&env, &sub_ids, SUB_MIGRATIONS))
+    }
+
+    /// The code version `sub_id`'s entries were written by. Subscriptions
+    /// created before versioning count as version 1.
+    pub fn get_sub_version(env: Env, sub_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&SubVersionKey {
+                version_sub_id: sub_id,
+            })
+            .unwrap_or(1)
+    }
+
+    fn migrate_entries(env: &Env, sub_ids: &Vec<u64>, steps: &[SubMigration]) -> u32 {
+        let to = syncro_upgrade::version(env);
+        let mut migrated = 0;
+        for sub_id in sub_ids.iter() {
+            if !env.storage().persistent().has(&sub_id) {
+                continue;
+            }
+            let from = Self::get_sub_version(env.clone(), sub_id);
+            if from >= to {
+                continue;
+            }
+            for v in from..to {
+                let step = steps.get((v - 1) as usize).expect("migration step missing");
+                step(env, sub_id);
+            }
+            env.storage().persistent().set(
+                &SubVersionKey {
+                    version_sub_id: sub_id,
+                },
+                &to,
+            );
+            SubscriptionMigrated { sub_id, from, to }.publish(env);
+            migrated += 1;
+        }
+        migrated
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    /// Extend `sub_id`'s data, lifecycle timestamps, retry policy, version
+    /// and cycle guard to live at least `ledgers` more ledgers, up to the
+    /// network's maximum. Anyone may call it, to keep a long-lived
+    /// subscription from being archived between renewals. Returns how
+    /// many entries were found.
+    pub fn bump_sub(env: Env, sub_id: u64, ledgers: u32) -> Result<u32, Error> {
+        if ledgers == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        if !env.storage().persistent().has(&sub_id) {
+            return Err(Error::SubNotFound);
+        }
+        let ledgers = ledgers.min(env.storage().max_ttl());
+        let keys: [Val; 5] = [
+            sub_id.into_val(&env),
+            LifecycleKey {
+                lifecycle_sub_id: sub_id,
+            }
+            .into_val(&env),
+            RetryPolicyKey {
+                retry_policy_sub_id: sub_id,
+            }
+            .into_val(&env),
+            SubVersionKey {
+                version_sub_id: sub_id,
+            }
+            .into_val(&env),
+            CycleKey { sub_id }.into_val(&env),
+        ];
+        let storage = env.storage().persistent();
+        let mut found = 0;
+        for key in keys.iter() {
+            if storage.has(key) {
+                storage.extend_ttl(key, ledgers, ledgers);
+                found += 1;
+            }
+        }
+        Ok(found)
+    }
+
+    /// Pause or unpause all renewal execution, noting `caller` and an
+    /// optional incident `reason` code in the pause history. Admin or
+    /// pauser.
+    pub fn set_paused(
+        env: Env,
+        caller: Address,
+        paused: bool,
+        reason: Option<u32>,
+    ) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, PAUSER)?;
+        env.storage().instance().set(&ContractKey::Paused, &paused);
+        syncro_common::pause::record(&env, paused, &caller, reason);
+        PauseToggled { paused }.publish(&env);
+        Ok(())
+    }
+
+    /// Pause renewals here and trip the circuit breaker, halting every
+    /// contract pointed at it, like the registry's subscription creation
+    /// and updates. Admin or pauser. This contract must be a guardian of
+    /// the breaker. Resuming is split the same way: the breaker's admin
+    /// resets it, and `set_paused` lifts the pause here.
+    pub fn pause_all(env: Env, caller: Address, reason: Symbol) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, PAUSER)?;
+        let breaker: Address = env
+            .storage()
+            .instance()
+            .get(&ContractKey::CircuitBreaker)
+            .ok_or(Error::PeerNotSet)?;
+        if CircuitBreakerClient::new(&env, &breaker)
+            .try_trip(&env.current_contract_address(), &reason)
+            .is_err()
+        {
+            return Err(Error::Unauthorized);
+        }
+        env.storage().instance().set(&ContractKey::Paused, &true);
+        syncro_common::pause::record_trip(&env, &caller, &reason);
+        PauseToggled { paused: true }.publish(&env);
+        Ok(())
+    }
+
+    /// Who paused renewals, when and why, while the admin pause holds.
+    /// Circuit breaker pauses are described by the breaker's `trip_info`.
+    pub fn get_pause_info(env: Env) -> Option<PauseRecord> {
+        syncro_common::pause::info(&env)
+    }
+
+    /// The latest pause and unpause changes, oldest first.
+    pub fn get_pause_history(env: Env) -> Vec<PauseRecord> {
+        syncro_common::pause::history(&env)
+    }
+
+    /// Query the current pause state: paused here, or by the circuit
+    /// breaker.
+    pub fn is_paused(env: Env) -> bool {
+        if env
+            .storage()
+            .instance()
+            .get(&ContractKey::Paused)
+            .unwrap_or(false)
+        {
+            return true;
+        }
+        match env
+            .storage()
+            .instance()
+            .get::<_, Address>(&ContractKey::CircuitBreaker)
+        {
+            Some(breaker) => {
+                CircuitBreakerClient::new(&env, &breaker).is_paused(&env.current_contract_address())
+            }
+            None => false,
+        }
+    }
+
+    /// Set the protocol fee taken from each renewal, in basis points of the
+    /// amount charged. Admin or treasurer.
+    pub fn set_protocol_fee(env: Env, caller: Address, fee_bps: u32) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, TREASURER)?;
+        if fee_bps > 10_000 {
+            return Err(Error::InvalidConfig);
+        }
+        env.storage()
+            .instance()
+            .set(&ContractKey::ProtocolFee, &fee_bps);
+        Ok(())
+    }
+
+    /// Set the token renewals are charged in. Admin only. Payers approve
+    /// this contract to spend it; each renewal pulls the charge from the
+    /// payer and pays it out to the merchant's recipients.
+    pub fn set_token(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&ContractKey::Token, &address);
+        Ok(())
+    }
+
+    /// The token renewals are charged in, if set.
+    pub fn get_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ContractKey::Token)
+    }
+
+    /// Set the utility token address. Admin only. Once set, merchants'
+    /// staking tiers discount the protocol fee.
+    pub fn set_utility_token(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::UtilityToken, &address);
+        Ok(())
+    }
+
+    /// Protocol fee on `amount` charged for `merchant`, and the staking
+    /// discount applied to it in basis points.
+    pub fn quote_protocol_fee(env: Env, merchant: Address, amount: i128) -> (i128, u32) {
+        let fee_bps = Self::fee_bps(&env, &merchant).unwrap_or(0);
+        if fee_bps == 0 {
+            return (0, 0);
+        }
+        let discount_bps = match env
+            .storage()
+            .instance()
+            .get::<_, Address>(&ContractKey::UtilityToken)
+        {
+            Some(token) => UtilityTokenClient::new(&env, &token)
+                .fee_discount_bps(&merchant)
+                .min(10_000),
+            None => 0,
+        };
+        let fee = portion(amount, fee_bps as i128, 10_000);
+        (
+            fee - portion(fee, discount_bps as i128, 10_000),
+            discount_bps,
+        )
+    }
+
+    /// Fee rate for `merchant`: its profile's override, else the global fee
+    /// if one was set.
+    fn fee_bps(env: &Env, merchant: &Address) -> Option<u32> {
+        Self::profile_of(env, merchant)
+            .and_then(|profile| profile.fee_bps)
+            .or_else(|| env.storage().instance().get(&ContractKey::ProtocolFee))
+    }
+
+    /// Set the circuit breaker address. Admin only. Once set, tripping the
+    /// breaker pauses renewals as `set_paused` does.
+    pub fn set_circuit_breaker(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::CircuitBreaker, &address);
+        Ok(())
+    }
+
+    /// Set the logging contract address. Admin only.
+    pub fn set_logging_contract(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::LoggingContract, &address);
+        Ok(())
+    }
+
+    /// Set the usage metering contract address. Admin only.
+    pub fn set_metering_contract(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::MeteringContract, &address);
+        Ok(())
+    }
+
+    /// Set the receipt contract address. Admin only.
+    pub fn set_receipt_contract(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::ReceiptContract, &address);
+        Ok(())
+    }
+
+    /// Set the agent registry address. Admin only. Once set, only agents
+    /// holding the Renewals scope can acquire renewal locks.
+    pub fn set_agent_registry(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::AgentRegistry, &address);
+        Ok(())
+    }
+
+    /// Set the loyalty contract address. Admin only.
+    pub fn set_loyalty_contract(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::LoyaltyContract, &address);
+        Ok(())
+    }
+
+    /// Set the prepay contract address. Admin only. Once set, renewals of
+    /// prepaid subscriptions are paid from the owner's term deposit.
+    pub fn set_prepay_contract(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::PrepayContract, &address);
+        Ok(())
+    }
+
+    /// Set the callback registry address. Admin only. Once set, renewals,
+    /// failed renewals and cancellations are pushed to registered callbacks.
+    pub fn set_callback_registry(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::CallbackRegistry, &address);
+        Ok(())
+    }
+
+    /// Set the guardian recovery contract address. Admin only. Once set, it
+    /// can move subscriptions away from owners who lost their keys.
+    pub fn set_recovery_contract(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::RecoveryContract, &address);
+        Ok(())
+    }
+
+    /// Set the renewal scheduler address. Admin only. Once set, each
+    /// subscription is queued there for the ledger its next renewal or
+    /// retry is due, and dropped when it fails or is cancelled.
+    pub fn set_scheduler(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::Scheduler, &address);
+        Ok(())
+    }
+
+    /// Set the subscription registry address. Admin only. Once set, each
+    /// renewal outcome advances or flags the registry entry linked to the
+    /// subscription; the registry must list this contract as a renewer.
+    pub fn set_subscription_registry(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::SubscriptionRegistry, &address);
+        Ok(())
+    }
+
+    /// Set the address book. Admin only. Once set, the logging contract
+    /// and agent registry are resolved there on each use, falling back to
+    /// the addresses set here for components the book does not list.
+    pub fn set_address_book(env: Env, address: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::AddressBook, &address);
+        Ok(())
+    }
+
+    pub fn get_address_book(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ContractKey::AddressBook)
+    }
+
+    /// Limit how often one agent can acquire renewal locks. Admin or config
+    /// manager; unlimited until set.
+    pub fn set_agent_rate_limit(
+        env: Env,
+        caller: Address,
+        config: LimitConfig,
+    ) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, CONFIG_MANAGER)?;
+        if !config.is_valid() {
+            return Err(Error::InvalidConfig);
+        }
+        env.storage()
+            .instance()
+            .set(&ContractKey::AgentRateLimit, &config);
+        Ok(())
+    }
+
+    // ── Merchant profiles ─────────────────────────────────────────
+
+    /// Set the limits merchant profiles must stay within. Admin or config
+    /// manager. Merchants cannot set profiles until this is called.
+    /// Profiles stored under looser bounds stay in effect until the merchant
+    /// edits them.
+    pub fn set_profile_bounds(
+        env: Env,
+        caller: Address,
+        bounds: ProfileBounds,
+    ) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, CONFIG_MANAGER)?;
+        if bounds.min_fee_bps > bounds.max_fee_bps || bounds.max_fee_bps > 10_000 {
+            return Err(Error::InvalidConfig);
+        }
+        env.storage()
+            .instance()
+            .set(&ContractKey::ProfileBounds, &bounds);
+        Ok(())
+    }
+
+    pub fn get_profile_bounds(env: Env) -> Option<ProfileBounds> {
+        env.storage().instance().get(&ContractKey::ProfileBounds)
+    }
+
+    /// Apply `profile` to all of `merchant`'s subscriptions, current and
+    /// future. Requires the merchant's authorization.
+    pub fn set_merchant_profile(
+        env: Env,
+        merchant: Address,
+        profile: MerchantProfile,
+    ) -> Result<(), Error> {
+        merchant.require_auth();
+        let bounds = Self::get_profile_bounds(env.clone()).ok_or(Error::ProfileOutOfBounds)?;
+        if !bounds.allow(&profile) {
+            return Err(Error::ProfileOutOfBounds);
+        }
+        env.storage().persistent().set(
+            &MerchantKey {
+                profile_merchant: merchant.clone(),
+            },
+            &profile,
+        );
+        MerchantProfileSet {
+            merchant,
+            cleared: false,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Drop `merchant`'s profile, returning its subscriptions to their own
+    /// retry policies (the admin default unless changed) and the global fee.
+    pub fn clear_merchant_profile(env: Env, merchant: Address) {
+        merchant.require_auth();
+        env.storage().persistent().remove(&MerchantKey {
+            profile_merchant: merchant.clone(),
+        });
+        MerchantProfileSet {
+            merchant,
+            cleared: true,
+        }
+        .publish(&env);
+    }
+
+    pub fn get_merchant_profile(env: Env, merchant: Address) -> Option<MerchantProfile> {
+        env.storage().persistent().get(&MerchantKey {
+            profile_merchant: merchant,
+        })
+    }
+
+    /// Let `key`, e.g. a billing service's, call the merchant-gated
+    /// functions of `merchant`'s subscriptions so the merchant's own key
+    /// can stay offline. Keys cannot manage the profile or other keys.
+    /// Requires the merchant's authorization.
+    pub fn add_merchant_key(env: Env, merchant: Address, key: Address) -> Result<(), Error> {
+        merchant.require_auth();
+        let mut keys = Self::get_merchant_keys(env.clone(), merchant.clone());
+        if keys.contains(&key) {
+            return Err(Error::MerchantKeyExists);
+        }
+        if keys.len() >= MAX_MERCHANT_KEYS {
+            return Err(Error::TooManyMerchantKeys);
+        }
+        keys.push_back(key.clone());
+        env.storage().persistent().set(
+            &MerchantKeysKey {
+                keys_merchant: merchant.clone(),
+            },
+            &keys,
+        );
+        MerchantKeySet {
+            merchant,
+            key,
+            revoked: false,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Stop `key` acting for `merchant`. Requires the merchant's
+    /// authorization.
+    pub fn revoke_merchant_key(env: Env, merchant: Address, key: Address) -> Result<(), Error> {
+        merchant.require_auth();
+        let mut keys = Self::get_merchant_keys(env.clone(), merchant.clone());
+        let index = keys
+            .first_index_of(&key)
+            .ok_or(Error::MerchantKeyNotFound)?;
+        keys.remove(index);
+        let storage_key = MerchantKeysKey {
+            keys_merchant: merchant.clone(),
+        };
+        if keys.is_empty() {
+            env.storage().persistent().remove(&storage_key);
+        } else {
+            env.storage().persistent().set(&storage_key, &keys);
+        }
+        MerchantKeySet {
+            merchant,
+            key,
+            revoked: true,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_merchant_keys(env: Env, merchant: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&MerchantKeysKey {
+                keys_merchant: merchant,
+            })
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Whether `caller` is `merchant` or one of its keys.
+    fn acts_for_merchant(env: &Env, caller: &Address, merchant: &Address) -> bool {
+        caller == merchant
+            || Self::get_merchant_keys(env.clone(), merchant.clone()).contains(caller)
+    }
+
+    fn require_merchant(env: &Env, caller: &Address, merchant: &Address) -> Result<(), Error> {
+        caller.require_auth();
+        if !Self::acts_for_merchant(env, caller, merchant) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// The profile in effect for `merchant`. Skips the storage read while
+    /// profiles are not enabled, keeping the renewal path's footprint.
+    fn profile_of(env: &Env, merchant: &Address) -> Option<MerchantProfile> {
+        if !env.storage().instance().has(&ContractKey::ProfileBounds) {
+            return None;
+        }
+        Self::get_merchant_profile(env.clone(), merchant.clone())
+    }
+
+    // ── Ledger time ───────────────────────────────────────────────
+    //
+    // Cooldowns, lock timeouts, approval expiry and renewal due dates are
+    // all ledger sequences. These helpers turn seconds and timestamps into
+    // sequences, and back, at the expected ledger time.
+
+    /// Set the expected seconds between ledgers. Admin or config manager.
+    pub fn set_ledger_seconds(env: Env, caller: Address, seconds: u64) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, CONFIG_MANAGER)?;
+        if seconds == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        env.storage()
+            .instance()
+            .set(&ContractKey::LedgerSeconds, &seconds);
+        Ok(())
+    }
+
+    /// Expected seconds between ledgers; [`LEDGER_SECONDS`] until set.
+    pub fn get_ledger_seconds(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&ContractKey::LedgerSeconds)
+            .unwrap_or(LEDGER_SECONDS)
+    }
+
+    /// Ledgers expected to close in `seconds`, saturating at `u32::MAX`.
+    pub fn ledgers_for(env: Env, seconds: u64) -> u32 {
+        ledgers_at(seconds, Self::get_ledger_seconds(env))
+    }
+
+    /// Sequence the ledger is expected to have at `timestamp`. Timestamps
+    /// already passed give the current sequence.
+    pub fn ledger_at(env: Env, timestamp: u64) -> u32 {
+        let now = env.ledger().timestamp();
+        env.ledger()
+            .sequence()
+            .saturating_add(Self::ledgers_for(env, timestamp.saturating_sub(now)))
+    }
+
+    /// Timestamp the ledger is expected to close `sequence` at. Sequences
+    /// already closed give the current timestamp.
+    pub fn timestamp_at(env: Env, sequence: u32) -> u64 {
+        let ledgers = sequence.saturating_sub(env.ledger().sequence()) as u64;
+        env.ledger()
+            .timestamp()
+            .saturating_add(ledgers.saturating_mul(Self::get_ledger_seconds(env)))
+    }
+
+    // ── Billing cycles ────────────────────────────────────────────
+
+    /// The `cycle_id` for period `period_index` of `sub_id`: the timestamp
+    /// the period starts at, counting whole frequencies from creation, the
+    /// end of its trial, or its last frequency change.
+    /// Keepers deriving ids this way agree on them, and they increase
+    /// from one period to the next as `renew` requires.
+    pub fn compute_cycle_id(env: Env, sub_id: u64, period_index: u64) -> Result<u64, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        let (start, frequency) = Self::billing_terms(&env, sub_id, &data)?;
+        Ok(start.saturating_add(period_index.saturating_mul(frequency)))
+    }
+
+    /// Whether every `cycle_id` passed to `renew` must be the id of the
+    /// period underway, as `get_current_cycle_id` derives it from the
+    /// ledger timestamp, so workers cannot invent ids to get past the
+    /// duplicate-cycle guard. Enforced by default; turning it off lets
+    /// callers name any id above the last one charged, which a lock holder
+    /// can set to `u64::MAX` to block every later renewal. Admin or config
+    /// manager.
+    pub fn set_derived_cycle_ids(env: Env, caller: Address, enabled: bool) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, CONFIG_MANAGER)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::DerivedCycleIds, &enabled);
+        Ok(())
+    }
+
+    pub fn is_derived_cycle_ids(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&ContractKey::DerivedCycleIds)
+            .unwrap_or(true)
+    }
+
+    /// The `cycle_id` of the period of `sub_id` underway at the current
+    /// ledger timestamp, or `None` during its trial.
+    pub fn get_current_cycle_id(env: Env, sub_id: u64) -> Result<Option<u64>, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        Self::current_cycle_id(&env, sub_id, &data)
+    }
+
+    fn current_cycle_id(
+        env: &Env,
+        sub_id: u64,
+        data: &SubscriptionData,
+    ) -> Result<Option<u64>, Error> {
+        let (start, frequency) = Self::billing_terms(env, sub_id, data)?;
+        let Some(elapsed) = env.ledger().timestamp().checked_sub(start) else {
+            return Ok(None);
+        };
+        Ok(Some(match frequency {
+            0 => start,
+            frequency => start.saturating_add(elapsed - elapsed % frequency),
+        }))
+    }
+
+    /// Whether `cycle_id` may be charged for `sub_id` now: with derived ids
+    /// enforced only the current period's id may.
+    fn cycle_allowed(
+        env: &Env,
+        sub_id: u64,
+        data: &SubscriptionData,
+        cycle_id: u64,
+    ) -> Result<bool, Error> {
+        if !Self::is_derived_cycle_ids(env.clone()) {
+            return Ok(true);
+        }
+        Ok(Self::current_cycle_id(env, sub_id, data)? == Some(cycle_id))
+    }
+
+    /// Timestamp `sub_id`'s billing periods are counted from, and the
+    /// frequency they last: a frequency change takes over from its cycle
+    /// boundary even before a renewal applies it.
+    fn billing_terms(env: &Env, sub_id: u64, data: &SubscriptionData) -> Result<(u64, u64), Error> {
+        if let Some(change) = Self::get_frequency_change(env.clone(), sub_id) {
+            if env.ledger().timestamp() >= change.effective_at {
+                return Ok((change.effective_at, change.frequency));
+            }
+        }
+        Ok((Self::billing_start(env, sub_id)?, data.frequency))
+    }
+
+    /// Timestamp `sub_id`'s billing periods start from: its last frequency
+    /// change, the end of its trial, or creation.
+    fn billing_start(env: &Env, sub_id: u64) -> Result<u64, Error> {
+        let storage = env.storage().persistent();
+        let start = storage
+            .get(&BillingAnchorKey {
+                anchor_sub_id: sub_id,
+            })
+            .or_else(|| {
+                storage.get(&TrialKey {
+                    trial_sub_id: sub_id,
+                })
+            });
+        match start {
+            Some(start) => Ok(start),
+            None => Ok(Self::load_lifecycle(env, sub_id)?.created_at),
+        }
+    }
+
+    // ── Renewal lock management ────────────────────────────────────
+
+    /// Acquire a processing lock for a subscription renewal, held for
+    /// `lock_timeout` ledgers.
+    /// Prevents concurrent renewal execution by multiple workers.
+    /// The holder must authorize the `renew` that consumes the lock.
+    pub fn acquire_renewal_lock(
+        env: Env,
+        agent: Address,
+        sub_id: u64,
+        lock_timeout: u32,
+    ) -> Result<(), Error> {
+        if Self::is_paused(env.clone()) {
+            return Err(Error::Paused);
+        }
+
+        agent.require_auth();
+        if let Some(registry) = Self::peer(&env, &ContractKey::AgentRegistry, AGENTS) {
+            if !Self::registry_grants(&env, &registry, &agent, Scope::Renewals) {
+                return Err(Error::AgentNotAuthorized);
+            }
+        }
+        if let Some(limit) = env
+            .storage()
+            .instance()
+            .get::<_, LimitConfig>(&ContractKey::AgentRateLimit)
+        {
+            if syncro_ratelimit::check_and_consume(&env, &agent, &limit).is_err() {
+                return Err(Error::AgentRateLimited);
+            }
+        }
+        if env.storage().instance().has(&ContractKey::ProfileBounds) {
+            if let Some(profile) = env
+                .storage()
+                .persistent()
+                .get::<_, SubscriptionData>(&sub_id)
+                .and_then(|data| Self::profile_of(&env, &data.merchant))
+            {
+                if !profile.allowed_agents.is_empty() && !profile.allowed_agents.contains(&agent) {
+                    return Err(Error::AgentNotAllowed);
+                }
+            }
+        }
+
+        // A pinned agent has each renewal to itself until its window closes
+        let preferred_window = match Self::get_preferred_agent(env.clone(), sub_id) {
+            Some(preferred) if Self::in_preferred_window(&env, sub_id)? => {
+                if preferred != agent {
+                    return Err(Error::LockReserved);
+                }
+                true
+            }
+            _ => false,
+        };
+
+        let lock_key = RenewalLockKey {
+            lock_sub_id: sub_id,
+        };
+        let current_ledger = env.ledger().sequence();
+        let intent_key = IntentKey {
+            intent_sub_id: sub_id,
+        };
+        let queue: Option<IntentQueue> = env.storage().persistent().get(&intent_key);
+        let mut freed_at = queue.as_ref().map_or(0, |queue| queue.released_at);
+
+        if let Some(existing) = env
+            .storage()
+            .temporary()
+            .get::<RenewalLockKey, RenewalLockData>(&lock_key)
+        {
+            // Check if existing lock has expired
+            freed_at = existing.locked_at.saturating_add(existing.lock_timeout);
+            if current_ledger < freed_at {
+                return Err(Error::LockActive);
+            }
+            // Lock expired — emit expiry event and allow re-acquisition
+            RenewalLockExpired {
+                sub_id,
+                original_locked_at: existing.locked_at,
+                expired_at: current_ledger,
+            }
+            .publish(&env);
+        }
+
+        // The earliest registered intent gets first claim on a freed lock;
+        // taking the lock fulfils the taker's own intent
+        if let Some(mut queue) = queue {
+            if let Some(first) = queue.intents.first() {
+                let claim_ends = freed_at
+                    .max(first.registered_at)
+                    .saturating_add(INTENT_PRIORITY_LEDGERS);
+                if first.agent != agent && !preferred_window {
+                    if current_ledger < claim_ends {
+                        return Err(Error::LockReserved);
+                    }
+                    // The earliest agent let its claim lapse
+                    queue.intents.pop_front();
+                }
+            }
+            queue.intents = Vec::from_iter(
+                &env,
+                queue.intents.iter().filter(|intent| intent.agent != agent),
+            );
+            if queue.intents.is_empty() {
+                env.storage().persistent().remove(&intent_key);
+            } else {
+                // The lock's entry may be evicted once it expires, so the
+                // queue keeps when the next claim starts
+                queue.released_at = Self::lock_expiry(&env, current_ledger, lock_timeout);
+                env.storage().persistent().set(&intent_key, &queue);
+            }
+        }
+
+        let lock_data = RenewalLockData {
+            holder: agent,
+            locked_at: current_ledger,
+            lock_timeout,
+        };
+        // Held in temporary storage, which costs no rent past the lock's
+        // life; the entry outliving a short timeout is still checked above
+        let ttl = lock_timeout.min(env.storage().max_ttl());
+        env.storage().temporary().set(&lock_key, &lock_data);
+        env.storage().temporary().extend_ttl(&lock_key, ttl, ttl);
+
+        RenewalLockAcquired {
+            sub_id,
+            locked_at: current_ledger,
+            lock_timeout,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Release a processing lock for a subscription renewal.
+    pub fn release_renewal_lock(env: Env, sub_id: u64) -> Result<(), Error> {
+        let lock_key = RenewalLockKey {
+            lock_sub_id: sub_id,
+        };
+        if !env.storage().temporary().has(&lock_key) {
+            return Err(Error::LockRequired);
+        }
+
+        Self::release_lock(&env, sub_id);
+        Ok(())
+    }
+
+    /// Ledger a lock taken at `locked_at` is gone by: when it times out,
+    /// or sooner if its entry's TTL can't last that long.
+    fn lock_expiry(env: &Env, locked_at: u32, lock_timeout: u32) -> u32 {
+        locked_at.saturating_add(lock_timeout.min(env.storage().max_ttl()))
+    }
+
+    /// Drop `sub_id`'s lock, starting the claim of the earliest intent.
+    fn release_lock(env: &Env, sub_id: u64) {
+        let current_ledger = env.ledger().sequence();
+        env.storage().temporary().remove(&RenewalLockKey {
+            lock_sub_id: sub_id,
+        });
+        let intent_key = IntentKey {
+            intent_sub_id: sub_id,
+        };
+        if let Some(mut queue) = env
+            .storage()
+            .persistent()
+            .get::<_, IntentQueue>(&intent_key)
+        {
+            queue.released_at = current_ledger;
+            env.storage().persistent().set(&intent_key, &queue);
+        }
+
+        RenewalLockReleased {
+            sub_id,
+            released_at: current_ledger,
+        }
+        .publish(env);
+    }
+
+    /// Queue `agent` for `sub_id`'s lock. When the lock is next free the
+    /// earliest registered agent has it to itself for
+    /// [`INTENT_PRIORITY_LEDGERS`] ledgers. Returns the agent's place in
+    /// the queue, 0 being next.
+    pub fn register_renewal_intent(env: Env, agent: Address, sub_id: u64) -> Result<u32, Error> {
+        agent.require_auth();
+        let data = Self::load_sub(&env, sub_id)?;
+        if matches!(
+            data.state,
+            SubscriptionState::Failed | SubscriptionState::Cancelled | SubscriptionState::Expired
+        ) {
+            return Err(Error::SubFailed);
+        }
+        if data.state == SubscriptionState::Paused {
+            return Err(Error::Paused);
+        }
+        let intent_key = IntentKey {
+            intent_sub_id: sub_id,
+        };
+        let mut queue = env
+            .storage()
+            .persistent()
+            .get::<_, IntentQueue>(&intent_key)
+            .unwrap_or_else(|| IntentQueue {
+                intents: Vec::new(&env),
+                released_at: Self::get_renewal_lock(env.clone(), sub_id).map_or(0, |lock| {
+                    Self::lock_expiry(&env, lock.locked_at, lock.lock_timeout)
+                }),
+            });
+        if queue.intents.iter().any(|intent| intent.agent == agent) {
+            return Err(Error::IntentExists);
+        }
+        if queue.intents.len() >= MAX_RENEWAL_INTENTS {
+            return Err(Error::IntentQueueFull);
+        }
+        queue.intents.push_back(RenewalIntent {
+            agent: agent.clone(),
+            registered_at: env.ledger().sequence(),
+        });
+        env.storage().persistent().set(&intent_key, &queue);
+
+        let position = queue.intents.len() - 1;
+        RenewalIntentRegistered {
+            sub_id,
+            agent,
+            position,
+        }
+        .publish(&env);
+        Ok(position)
+    }
+
+    /// Pin `agent` as the one to renew `sub_id`, or unpin with `None`.
+    /// Until [`PREFERRED_AGENT_LEDGERS`] ledgers after each renewal falls
+    /// due, only the pinned agent can lock the subscription. `caller` must
+    /// be the owner, the merchant or one of its keys.
+    pub fn set_preferred_agent(
+        env: Env,
+        caller: Address,
+        sub_id: u64,
+        agent: Option<Address>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        let data = Self::load_sub(&env, sub_id)?;
+        if caller != data.owner && !Self::acts_for_merchant(&env, &caller, &data.merchant) {
+            return Err(Error::Unauthorized);
+        }
+        let key = PreferredAgentKey {
+            preferred_sub_id: sub_id,
+        };
+        match &agent {
+            Some(agent) => env.storage().persistent().set(&key, agent),
+            None => env.storage().persistent().remove(&key),
+        }
+        PreferredAgentSet { sub_id, agent }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_preferred_agent(env: Env, sub_id: u64) -> Option<Address> {
+        env.storage().persistent().get(&PreferredAgentKey {
+            preferred_sub_id: sub_id,
+        })
+    }
+
+    /// Whether `sub_id`'s current renewal is still inside the preferred
+    /// agent's window: from the last renewal, or creation, until
+    /// [`PREFERRED_AGENT_LEDGERS`] ledgers past the next due date.
+    fn in_preferred_window(env: &Env, sub_id: u64) -> Result<bool, Error> {
+        let data = Self::load_sub(env, sub_id)?;
+        let window = PREFERRED_AGENT_LEDGERS as u64 * Self::get_ledger_seconds(env.clone());
+        Ok(env.ledger().timestamp() < Self::due_at(env, sub_id, &data)?.saturating_add(window))
+    }
+
+    /// Timestamp `sub_id`'s next renewal falls due at: one billing period
+    /// after the last renewal, or after the first period starts.
+    fn due_at(env: &Env, sub_id: u64, data: &SubscriptionData) -> Result<u64, Error> {
+        let cycle_start = match Self::load_lifecycle(env, sub_id)?.last_renewed_at {
+            0 => Self::billing_start(env, sub_id)?,
+            renewed_at => renewed_at,
+        };
+        Ok(cycle_start.saturating_add(data.frequency))
+    }
+
+    /// Agents queued for `sub_id`'s lock, earliest first.
+    pub fn get_renewal_intents(env: Env, sub_id: u64) -> Vec<RenewalIntent> {
+        env.storage()
+            .persistent()
+            .get::<_, IntentQueue>(&IntentKey {
+                intent_sub_id: sub_id,
+            })
+            .map_or(Vec::new(&env), |queue| queue.intents)
+    }
+
+    /// Query the current renewal lock for a subscription.
+    pub fn get_renewal_lock(env: Env, sub_id: u64) -> Option<RenewalLockData> {
+        let lock_key = RenewalLockKey {
+            lock_sub_id: sub_id,
+        };
+        env.storage().temporary().get(&lock_key)
+    }
+
+    // ── Subscription templates ────────────────────────────────────
+
+    /// Publish `terms` as version 1 of a new template of `merchant`'s.
+    /// Returns the template id.
+    pub fn create_template(
+        env: Env,
+        merchant: Address,
+        terms: TemplateTerms,
+    ) -> Result<u64, Error> {
+        merchant.require_auth();
+        let template_id: u64 = env
+            .storage()
+            .instance()
+            .get(&ContractKey::NextTemplateId)
+            .unwrap_or(0);
+        let next_id = template_id.checked_add(1).ok_or(Error::InvalidConfig)?;
+        Self::store_template(
+            &env,
+            template_id,
+            SubscriptionTemplate {
+                merchant,
+                version: 1,
+                terms,
+            },
+        )?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::NextTemplateId, &next_id);
+        Ok(template_id)
+    }
+
+    /// Publish `terms` as the next version of `template_id`. Subscriptions
+    /// already created keep the version they were created from. Requires
+    /// the merchant's authorization. Returns the new version.
+    pub fn update_template(env: Env, template_id: u64, terms: TemplateTerms) -> Result<u32, Error> {
+        let current =
+            Self::get_template(env.clone(), template_id).ok_or(Error::TemplateNotFound)?;
+        current.merchant.require_auth();
+        let version = current.version.checked_add(1).ok_or(Error::InvalidConfig)?;
+        Self::store_template(
+            &env,
+            template_id,
+            SubscriptionTemplate {
+                merchant: current.merchant,
+                version,
+                terms,
+            },
+        )?;
+        Ok(version)
+    }
+
+    fn store_template(
+        env: &Env,
+        template_id: u64,
+        template: SubscriptionTemplate,
+    ) -> Result<(), Error> {
+        let terms = &template.terms;
+        if terms.amount <= 0 || terms.spending_cap < terms.amount {
+            return Err(Error::InvalidAmount);
+        }
+        if terms.frequency == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        for template_version in [None, Some(template.version)] {
+            env.storage().persistent().set(
+                &TemplateKey {
+                    template_id,
+                    template_version,
+                },
+                &template,
+            );
+        }
+        TemplateSet {
+            template_id,
+            merchant: template.merchant,
+            version: template.version,
+        }
+        .publish(env);
+        Ok(())
+    }
+
+    /// The latest version of `template_id`.
+    pub fn get_template(env: Env, template_id: u64) -> Option<SubscriptionTemplate> {
+        env.storage().persistent().get(&TemplateKey {
+            template_id,
+            template_version: None,
+        })
+    }
+
+    /// `template_id` as it was at `version`.
+    pub fn get_template_at(
+        env: Env,
+        template_id: u64,
+        version: u32,
+    ) -> Option<SubscriptionTemplate> {
+        env.storage().persistent().get(&TemplateKey {
+            template_id,
+            template_version: Some(version),
+        })
+    }
+
+    /// Create `sub_id` for `owner` on the terms of the latest version of
+    /// `template_id`. The integrity hash is the one `init_sub` gives the
+    /// same terms, so anyone can rederive it from the version recorded
+    /// with `get_sub_template`. Requires the owner's authorization.
+    pub fn init_sub_from_template(
+        env: Env,
+        template_id: u64,
+        owner: Address,
+        sub_id: u64,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        let template =
+            Self::get_template(env.clone(), template_id).ok_or(Error::TemplateNotFound)?;
+        let terms = template.terms;
+        Self::create_sub(
+            env.clone(),
+            owner,
+            template.merchant,
+            terms.amount,
+            terms.frequency,
+            terms.spending_cap,
+            sub_id,
+            None,
+            terms.trial,
+        )?;
+        env.storage().persistent().set(
+            &SubTemplateKey {
+                template_sub_id: sub_id,
+            },
+            &TemplateRef {
+                template_id,
+                version: template.version,
+            },
+        );
+        Ok(())
+    }
+
+    /// The template version `sub_id` was created from, if any.
+    pub fn get_sub_template(env: Env, sub_id: u64) -> Option<TemplateRef> {
+        env.storage().persistent().get(&SubTemplateKey {
+            template_sub_id: sub_id,
+        })
+    }
+
+    /// Timestamp `sub_id`'s trial ends and its first billing period
+    /// starts at, if it was created with one.
+    pub fn get_trial_end(env: Env, sub_id: u64) -> Option<u64> {
+        env.storage().persistent().get(&TrialKey {
+            trial_sub_id: sub_id,
+        })
+    }
+
+    // ── Subscription logic ────────────────────────────────────────
+
+    /// Initialize a subscription. `sub_id` must not be taken, even by a
+    /// cancelled or expired subscription. Requires the owner's
+    /// authorization.
+    pub fn init_sub(
+        env: Env,
+        owner: Address,
+        merchant: Address,
+        amount: i128,
+        frequency: u64,
+        spending_cap: i128,
+        sub_id: u64,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        Self::create_sub(
+            env,
+            owner,
+            merchant,
+            amount,
+            frequency,
+            spending_cap,
+            sub_id,
+            None,
+            0,
+        )
+    }
+
+    /// Like `init_sub`, with the amount rising by `indexation.rate_bps`
+    /// (at most [`MAX_INDEXATION_BPS`]) every `indexation.interval` seconds
+    /// but never above `spending_cap`. Each renewal applies the steps due by
+    /// then. The clause is covered by the integrity hash. Requires the
+    /// owner's authorization.
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_indexed_sub(
+        env: Env,
+        owner: Address,
+        merchant: Address,
+        amount: i128,
+        frequency: u64,
+        spending_cap: i128,
+        sub_id: u64,
+        indexation: Indexation,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        if indexation.rate_bps == 0 || indexation.rate_bps > MAX_INDEXATION_BPS {
+            return Err(Error::InvalidIndexation);
+        }
+        if indexation.interval == 0 || indexation.interval < frequency {
+            return Err(Error::InvalidIndexation);
+        }
+        Self::create_sub(
+            env.clone(),
+            owner,
+            merchant,
+            amount,
+            frequency,
+            spending_cap,
+            sub_id,
+            Some(indexation.clone()),
+            0,
+        )?;
+        env.storage().persistent().set(
+            &IndexationKey {
+                indexation_sub_id: sub_id,
+            },
+            &IndexationSchedule {
+                terms: indexation.clone(),
+                next_at: env.ledger().timestamp().saturating_add(indexation.interval),
+            },
+        );
+        Ok(())
+    }
+
+    /// The amount `renew` has to be given for `sub_id` now: its amount
+    /// with the indexation steps due by now applied. Metered subscriptions
+    /// charge their settled usage instead.
+    pub fn get_amount_due(env: Env, sub_id: u64) -> Result<i128, Error> {
+        let mut data = Self::load_sub(&env, sub_id)?;
+        Self::apply_indexation(&env, sub_id, &mut data);
+        Ok(data.amount)
+    }
+
+    pub fn get_indexation(env: Env, sub_id: u64) -> Option<Indexation> {
+        env.storage()
+            .persistent()
+            .get::<_, IndexationSchedule>(&IndexationKey {
+                indexation_sub_id: sub_id,
+            })
+            .map(|schedule| schedule.terms)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_sub(
+        env: Env,
+        owner: Address,
+        merchant: Address,
+        amount: i128,
+        frequency: u64,
+        spending_cap: i128,
+        sub_id: u64,
+        indexation: Option<Indexation>,
+        trial: u64,
+    ) -> Result<(), Error> {
+        if env.storage().persistent().has(&sub_id) {
+            return Err(Error::SubExists);
+        }
+        let integrity_hash =
+            Self::integrity_hash(&env, &merchant, amount, frequency, spending_cap, indexation);
+
+        let key = sub_id;
+        Self::index_owner(&env, &owner, sub_id);
+        Self::index_sweep(&env, sub_id);
+        env.storage().persistent().set(
+            &SubVersionKey {
+                version_sub_id: sub_id,
+            },
+            &CODE_VERSION,
+        );
+        env.storage().persistent().set(
+            &RetryPolicyKey {
+                retry_policy_sub_id: sub_id,
+            },
+            &Self::get_default_retry_policy(env.clone()),
+        );
+        let data = SubscriptionData {
+            owner,
+            merchant,
+            amount,
+            frequency,
+            spending_cap,
+            integrity_hash,
+            state: SubscriptionState::Active,
+            failure_count: 0,
+            last_attempt_ledger: 0,
+        };
+        env.storage().persistent().set(&key, &data);
+        keep_alive(&env, &key);
+        Self::journal_state(&env, sub_id, SubscriptionState::Active);
+
+        // Initialize lifecycle timestamps
+        let now = env.ledger().timestamp();
+        let lifecycle = LifecycleTimestamps {
+            created_at: now,
+            activated_at: now,
+            last_renewed_at: 0,
+            canceled_at: 0,
+            paused_at: 0,
+            resumed_at: 0,
+        };
+        let lc_key = LifecycleKey {
+            lifecycle_sub_id: sub_id,
+        };
+        env.storage().persistent().set(&lc_key, &lifecycle);
+        keep_alive(&env, &lc_key);
+        if trial > 0 {
+            env.storage().persistent().set(
+                &TrialKey {
+                    trial_sub_id: sub_id,
+                },
+                &now.saturating_add(trial),
+            );
+        }
+
+        LifecycleTimestampUpdated {
+            sub_id,
+            event_kind: 1,
+            timestamp: now,
+        }
+        .publish(&env);
+        LifecycleTimestampUpdated {
+            sub_id,
+            event_kind: 2,
+            timestamp: now,
+        }
+        .publish(&env);
+
+        // Record initialization log
+        Self::record_log(
+            &env,
+            sub_id,
+            LogEvent::Renewal,
+            soroban_sdk::String::from_str(&env, "Subscription initialized"),
+        );
+        let due = env.ledger().sequence().saturating_add(Self::ledgers_for(
+            env.clone(),
+            trial.saturating_add(frequency),
+        ));
+        Self::schedule(&env, sub_id, Some(due));
+        Ok(())
+    }
+
+    /// `component`'s entry in the address book when one is set and lists
+    /// it, otherwise the peer address stored under `key`.
+    fn peer(env: &Env, key: &ContractKey, component: Symbol) -> Option<Address> {
+        let storage = env.storage().instance();
+        storage
+            .get::<_, Address>(&ContractKey::AddressBook)
+            .and_then(|book| address_book::resolve(env, &book, &component))
+            .or_else(|| storage.get(key))
+    }
+
+    fn record_log(env: &Env, sub_id: u64, event: LogEvent, data_str: soroban_sdk::String) {
+        if let Some(log_addr) = Self::peer(env, &ContractKey::LoggingContract, LOGGING) {
+            // Best effort: a rate-limited or failing log must not block the
+            // renewal it describes.
+            let _ = LoggingClient::new(env, &log_addr).try_record_log(&sub_id, &event, &data_str);
+        }
+    }
+
+    /// Advance the registry entry linked to `sub_id` after a successful
+    /// renewal, or flag it as at risk after a failed one.
+    fn sync_registry(env: &Env, sub_id: u64, renewed: bool) {
+        let Some(registry) = Self::peer(env, &ContractKey::SubscriptionRegistry, REGISTRY) else {
+            return;
+        };
+        let Some(entry) = Self::get_registry_entry(env.clone(), sub_id) else {
+            return;
+        };
+        // Best effort: an entry cancelled in the registry must not hold up
+        // the charge itself.
+        let registry = SubscriptionRegistryClient::new(env, &registry);
+        let renewer = env.current_contract_address();
+        if renewed {
+            let _ = registry.try_mark_renewed(&entry, &renewer);
+        } else {
+            let _ = registry.try_mark_at_risk(&entry, &renewer);
+        }
+    }
+
+    /// Queue `sub_id` for `due_ledger` in the scheduler, or drop it with
+    /// `None`, when a scheduler is linked.
+    fn schedule(env: &Env, sub_id: u64, due_ledger: Option<u32>) {
+        if let Some(scheduler) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&ContractKey::Scheduler)
+        {
+            // Best effort: keepers can still find the subscription without
+            // the queue, so a misconfigured scheduler must not block it.
+            let scheduler = SchedulerClient::new(env, &scheduler);
+            match due_ledger {
+                Some(due_ledger) => {
+                    let _ = scheduler.try_schedule(&sub_id, &due_ledger);
+                }
+                None => {
+                    let _ = scheduler.try_unschedule(&sub_id);
+                }
+            }
+        }
+    }
+
+    fn notify(env: &Env, kind: &str, sub_id: u64, merchant: &Address, cycle_id: u64, amount: i128) {
+        if let Some(registry) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&ContractKey::CallbackRegistry)
+        {
+            // Best effort: a registry that refuses this contract as an
+            // emitter must not undo the change it is told about.
+            let _ = CallbackRegistryClient::new(env, &registry).try_notify(
+                &env.current_contract_address(),
+                &Notification {
+                    kind: Symbol::new(env, kind),
+                    sub_id,
+                    merchant: merchant.clone(),
+                    cycle_id,
+                    amount,
+                },
+            );
+        }
+    }
+
+    /// Explicitly cancel a subscription, optionally saying why with one of
+    /// the cancellation reason codes. The owner can undo it with
+    /// `restore_sub` within the restore window.
+    pub fn cancel_sub(env: Env, sub_id: u64, reason: Option<ReasonCode>) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+
+        data.owner.require_auth();
+
+        if data.state == SubscriptionState::Cancelled {
+            return Err(Error::SubCancelled);
+        }
+        if data.state == SubscriptionState::Expired {
+            return Err(Error::SubExpired);
+        }
+
+        Self::unindex_owner(&env, &data.owner, sub_id);
+        Self::cancel(&env, sub_id, data, reason)?;
+        Ok(())
+    }
+
+    /// Cancel up to `limit` of `owner`'s subscriptions, for owners leaving
+    /// the platform. Requires the owner's authorization. Returns how many
+    /// are left; call again until it returns 0.
+    pub fn cancel_all_for_owner(env: Env, owner: Address, limit: u32) -> Result<u32, Error> {
+        owner.require_auth();
+        let subs = Self::get_owner_subs(env.clone(), owner.clone());
+        let batch = limit.min(subs.len());
+        for sub_id in subs.slice(..batch).iter() {
+            Self::cancel(&env, sub_id, Self::load_sub(&env, sub_id)?, None)?;
+        }
+        let remaining = subs.slice(batch..);
+        let key = OwnerSubsKey { subs_owner: owner };
+        if remaining.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &remaining);
+        }
+        Ok(remaining.len())
+    }
+
+    /// Ids of `owner`'s subscriptions that are neither cancelled nor
+    /// expired, oldest first.
+    pub fn get_owner_subs(env: Env, owner: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&OwnerSubsKey { subs_owner: owner })
+            .unwrap_or(Vec::new(&env))
+    }
+
+    fn index_owner(env: &Env, owner: &Address, sub_id: u64) {
+        let mut subs = Self::get_owner_subs(env.clone(), owner.clone());
+        if !subs.contains(sub_id) {
+            subs.push_back(sub_id);
+            env.storage().persistent().set(
+                &OwnerSubsKey {
+                    subs_owner: owner.clone(),
+                },
+                &subs,
+            );
+        }
+    }
+
+    fn unindex_owner(env: &Env, owner: &Address, sub_id: u64) {
+        let mut subs = Self::get_owner_subs(env.clone(), owner.clone());
+        if let Some(index) = subs.first_index_of(sub_id) {
+            subs.remove(index);
+            env.storage().persistent().set(
+                &OwnerSubsKey {
+                    subs_owner: owner.clone(),
+                },
+                &subs,
+            );
+        }
+    }
+
+    /// Mark a subscription cancelled and tell every linked peer.
+    fn cancel(
+        env: &Env,
+        sub_id: u64,
+        mut data: SubscriptionData,
+        reason: Option<ReasonCode>,
+    ) -> Result<(), Error> {
+        let key = sub_id;
+        env.storage().persistent().set(
+            &CancellationKey {
+                cancelled_sub_id: sub_id,
+            },
+            &Cancellation {
+                ledger: env.ledger().sequence(),
+                previous_state: data.state,
+            },
+        );
+        if let Some(reason) = reason {
+            env.storage().persistent().set(
+                &CancelReasonKey {
+                    reason_sub_id: sub_id,
+                },
+                &reason,
+            );
+        }
+        data.state = SubscriptionState::Cancelled;
+        env.storage().persistent().set(&key, &data);
+        Self::journal_state(env, sub_id, SubscriptionState::Cancelled);
+
+        // A cancelled subscription can no longer be renewed, so a lock held
+        // for it, or waited on, has nothing left to guard
         let lock_key = RenewalLockKey {
             lock_sub_id: sub_id,
         };
-        if !env.storage().persistent().has(&lock_key) {
-            panic!("No renewal lock to release");
+        if env.storage().temporary().has(&lock_key) {
+            Self::release_lock(env, sub_id);
+        }
+        env.storage().persistent().remove(&IntentKey {
+            intent_sub_id: sub_id,
+        });
+
+        // Update lifecycle timestamps
+        let lc_key = LifecycleKey {
+            lifecycle_sub_id: sub_id,
+        };
+        let mut lifecycle = Self::load_lifecycle(env, sub_id)?;
+        let now = env.ledger().timestamp();
+        lifecycle.canceled_at = now;
+        env.storage().persistent().set(&lc_key, &lifecycle);
+
+        LifecycleTimestampUpdated {
+            sub_id,
+            event_kind: 4,
+            timestamp: now,
+        }
+        .publish(env);
+
+        // Record cancellation log
+        Self::record_log(
+            env,
+            sub_id,
+            LogEvent::Cancellation,
+            soroban_sdk::String::from_str(env, "Subscription cancelled"),
+        );
+        Self::notify(env, "cancelled", sub_id, &data.merchant, 0, 0);
+        Self::schedule(env, sub_id, None);
+
+        // Emit state transition event
+        StateTransition {
+            sub_id,
+            new_state: SubscriptionState::Cancelled,
+        }
+        .publish(env);
+        SubscriptionCancelled {
+            sub_id,
+            merchant: data.merchant,
+            reason,
+        }
+        .publish(env);
+        Ok(())
+    }
+
+    /// Why `sub_id` was cancelled, when its owner said.
+    pub fn get_cancel_reason(env: Env, sub_id: u64) -> Option<ReasonCode> {
+        env.storage().persistent().get(&CancelReasonKey {
+            reason_sub_id: sub_id,
+        })
+    }
+
+    /// Set how many ledgers after a cancellation the owner can still undo
+    /// it. Admin or config manager. 0, the default, makes cancellations
+    /// final at once.
+    pub fn set_restore_window(env: Env, caller: Address, ledgers: u32) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, CONFIG_MANAGER)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::RestoreWindow, &ledgers);
+        Ok(())
+    }
+
+    pub fn get_restore_window(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&ContractKey::RestoreWindow)
+            .unwrap_or(0)
+    }
+
+    /// Set how many ledgers from its first failed renewal a subscription
+    /// keeps retrying once its retries are used up, before it fails, giving
+    /// the owner time to top up. Admin or config manager. A merchant
+    /// profile's `grace_ledgers` takes precedence; 0, the default, fails at
+    /// once.
+    pub fn set_grace_period(env: Env, caller: Address, ledgers: u32) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, CONFIG_MANAGER)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::GraceLedgers, &ledgers);
+        Ok(())
+    }
+
+    pub fn get_grace_period(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&ContractKey::GraceLedgers)
+            .unwrap_or(0)
+    }
+
+    /// Ledgers left in `sub_id`'s grace period; 0 when it is not failing
+    /// or the period is over.
+    pub fn get_grace_remaining(env: Env, sub_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get::<_, u32>(&GraceKey {
+                grace_sub_id: sub_id,
+            })
+            .map_or(0, |ends_at| ends_at.saturating_sub(env.ledger().sequence()))
+    }
+
+    /// Undo the cancellation of `sub_id` within the restore window,
+    /// returning it to the state it was cancelled from. Lifecycle history
+    /// is kept; only `canceled_at` is cleared. Requires the owner's
+    /// authorization.
+    pub fn restore_sub(env: Env, sub_id: u64) -> Result<(), Error> {
+        let mut data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        if data.state != SubscriptionState::Cancelled {
+            return Err(Error::NotCancelled);
+        }
+        let cancellation_key = CancellationKey {
+            cancelled_sub_id: sub_id,
+        };
+        let cancellation: Cancellation = env
+            .storage()
+            .persistent()
+            .get(&cancellation_key)
+            .ok_or(Error::NotCancelled)?;
+        let window = Self::get_restore_window(env.clone());
+        if window == 0 || env.ledger().sequence() > cancellation.ledger.saturating_add(window) {
+            return Err(Error::CancellationFinal);
+        }
+
+        data.state = cancellation.previous_state;
+        env.storage().persistent().set(&sub_id, &data);
+        Self::journal_state(&env, sub_id, data.state);
+        env.storage().persistent().remove(&cancellation_key);
+        env.storage().persistent().remove(&CancelReasonKey {
+            reason_sub_id: sub_id,
+        });
+        Self::index_owner(&env, &data.owner, sub_id);
+
+        let lc_key = LifecycleKey {
+            lifecycle_sub_id: sub_id,
+        };
+        let mut lifecycle = Self::load_lifecycle(&env, sub_id)?;
+        lifecycle.canceled_at = 0;
+        env.storage().persistent().set(&lc_key, &lifecycle);
+
+        SubscriptionRestored {
+            sub_id,
+            state: data.state,
+        }
+        .publish(&env);
+        StateTransition {
+            sub_id,
+            new_state: data.state,
+        }
+        .publish(&env);
+        Self::record_log(
+            &env,
+            sub_id,
+            LogEvent::Cancellation,
+            soroban_sdk::String::from_str(&env, "Cancellation undone"),
+        );
+        Self::notify(&env, "restored", sub_id, &data.merchant, 0, 0);
+        if data.state != SubscriptionState::Failed {
+            let due = Self::ledger_at(env.clone(), Self::due_at(&env, sub_id, &data)?);
+            Self::schedule(&env, sub_id, Some(due));
+        }
+        Ok(())
+    }
+
+    /// Pause an active or retrying subscription: `renew` rejects it until
+    /// `resume_sub`. Requires the owner's authorization.
+    pub fn pause_sub(env: Env, sub_id: u64) -> Result<(), Error> {
+        let mut data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        match data.state {
+            SubscriptionState::Active | SubscriptionState::Retrying => {}
+            SubscriptionState::Paused => return Err(Error::Paused),
+            SubscriptionState::Failed => return Err(Error::SubFailed),
+            SubscriptionState::Cancelled => return Err(Error::SubCancelled),
+            SubscriptionState::Expired => return Err(Error::SubExpired),
+        }
+        env.storage().persistent().set(
+            &SubPauseKey {
+                pause_sub_id: sub_id,
+            },
+            &data.state,
+        );
+        data.state = SubscriptionState::Paused;
+        env.storage().persistent().set(&sub_id, &data);
+        Self::journal_state(&env, sub_id, SubscriptionState::Paused);
+
+        // A paused subscription cannot be renewed, so a lock held for it
+        // has nothing left to guard
+        if env.storage().temporary().has(&RenewalLockKey {
+            lock_sub_id: sub_id,
+        }) {
+            Self::release_lock(&env, sub_id);
+        }
+        Self::schedule(&env, sub_id, None);
+
+        let now = Self::stamp_lifecycle(&env, sub_id, |lc, now| lc.paused_at = now)?;
+        LifecycleTimestampUpdated {
+            sub_id,
+            event_kind: 5,
+            timestamp: now,
+        }
+        .publish(&env);
+        StateTransition {
+            sub_id,
+            new_state: SubscriptionState::Paused,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Return a paused subscription to the state it was paused from.
+    /// Requires the owner's authorization.
+    pub fn resume_sub(env: Env, sub_id: u64) -> Result<(), Error> {
+        let mut data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        if data.state != SubscriptionState::Paused {
+            return Err(Error::NotPaused);
+        }
+        let pause_key = SubPauseKey {
+            pause_sub_id: sub_id,
+        };
+        data.state = env
+            .storage()
+            .persistent()
+            .get(&pause_key)
+            .unwrap_or(SubscriptionState::Active);
+        env.storage().persistent().remove(&pause_key);
+        env.storage().persistent().set(&sub_id, &data);
+        Self::journal_state(&env, sub_id, data.state);
+        let due = Self::ledger_at(env.clone(), Self::due_at(&env, sub_id, &data)?);
+        Self::schedule(&env, sub_id, Some(due));
+
+        let now = Self::stamp_lifecycle(&env, sub_id, |lc, now| lc.resumed_at = now)?;
+        LifecycleTimestampUpdated {
+            sub_id,
+            event_kind: 6,
+            timestamp: now,
+        }
+        .publish(&env);
+        StateTransition {
+            sub_id,
+            new_state: data.state,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Set one of `sub_id`'s lifecycle timestamps to now, returning it.
+    fn stamp_lifecycle(
+        env: &Env,
+        sub_id: u64,
+        stamp: impl FnOnce(&mut LifecycleTimestamps, u64),
+    ) -> Result<u64, Error> {
+        let mut lifecycle = Self::load_lifecycle(env, sub_id)?;
+        let now = env.ledger().timestamp();
+        stamp(&mut lifecycle, now);
+        env.storage().persistent().set(
+            &LifecycleKey {
+                lifecycle_sub_id: sub_id,
+            },
+            &lifecycle,
+        );
+        Ok(now)
+    }
+
+    // ── Expiry ────────────────────────────────────────────────────
+
+    /// Set how many seconds past its due date an unrenewed subscription
+    /// is left before `sweep_expired` expires it. Admin or config manager.
+    /// Unset, the default, expires nothing.
+    pub fn set_delinquency_horizon(env: Env, caller: Address, seconds: u64) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, CONFIG_MANAGER)?;
+        if seconds == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        env.storage()
+            .instance()
+            .set(&ContractKey::DelinquencyHorizon, &seconds);
+        Ok(())
+    }
+
+    pub fn get_delinquency_horizon(env: Env) -> Option<u64> {
+        env.storage()
+            .instance()
+            .get(&ContractKey::DelinquencyHorizon)
+    }
+
+    /// Walk up to `limit` entries of the subscription index, resuming
+    /// where the last sweep stopped, and expire each active or retrying
+    /// subscription left unrenewed past the delinquency horizon: it turns
+    /// `Expired` for good and its lock, intents and cycle bookkeeping are
+    /// dropped. Expired and failed subscriptions leave the index;
+    /// cancelled ones stay while they may still be restored. Anyone may
+    /// call it. Returns how many subscriptions expired.
+    pub fn sweep_expired(env: Env, limit: u32) -> Result<u32, Error> {
+        if Self::is_paused(env.clone()) {
+            return Err(Error::Paused);
+        }
+        let Some(horizon) = Self::get_delinquency_horizon(env.clone()) else {
+            return Ok(0);
+        };
+        let instance = env.storage().instance();
+        let mut len: u32 = instance.get(&ContractKey::SweepLen).unwrap_or(0);
+        let mut cursor: u32 = instance.get(&ContractKey::SweepCursor).unwrap_or(0);
+        let now = env.ledger().timestamp();
+        let mut expired = 0;
+        for _ in 0..limit {
+            if len == 0 {
+                break;
+            }
+            if cursor >= len {
+                cursor = 0;
+            }
+            let sub_id = Self::sweep_entry(&env, cursor);
+            let drop = match env
+                .storage()
+                .persistent()
+                .get::<_, SubscriptionData>(&sub_id)
+            {
+                None => true,
+                Some(data) => match data.state {
+                    SubscriptionState::Active | SubscriptionState::Retrying => {
+                        let due_at = Self::due_at(&env, sub_id, &data)?;
+                        if now >= due_at.saturating_add(horizon) {
+                            Self::expire(&env, sub_id, data, due_at);
+                            expired += 1;
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    SubscriptionState::Cancelled | SubscriptionState::Paused => false,
+                    SubscriptionState::Failed | SubscriptionState::Expired => true,
+                },
+            };
+            if drop {
+                // Fill the slot with the last entry, which is walked next
+                len -= 1;
+                let last = Self::sweep_entry(&env, len);
+                Self::pop_sweep_entry(&env, len);
+                if cursor < len {
+                    Self::set_sweep_entry(&env, cursor, last);
+                }
+            } else {
+                cursor += 1;
+            }
+        }
+        instance.set(&ContractKey::SweepLen, &len);
+        instance.set(&ContractKey::SweepCursor, &cursor);
+        Ok(expired)
+    }
+
+    /// Mark `sub_id` expired and drop what it kept for renewals.
+    fn expire(env: &Env, sub_id: u64, mut data: SubscriptionData, due_at: u64) {
+        data.state = SubscriptionState::Expired;
+        env.storage().persistent().set(&sub_id, &data);
+        Self::journal_state(env, sub_id, SubscriptionState::Expired);
+        Self::unindex_owner(env, &data.owner, sub_id);
+        if env.storage().temporary().has(&RenewalLockKey {
+            lock_sub_id: sub_id,
+        }) {
+            Self::release_lock(env, sub_id);
+        }
+        env.storage().persistent().remove(&IntentKey {
+            intent_sub_id: sub_id,
+        });
+        env.storage().persistent().remove(&CycleKey { sub_id });
+        env.storage().persistent().remove(&PendingRenewalKey {
+            pending_sub_id: sub_id,
+        });
+        Self::schedule(env, sub_id, None);
+        StateTransition {
+            sub_id,
+            new_state: SubscriptionState::Expired,
+        }
+        .publish(env);
+        SubscriptionExpired { sub_id, due_at }.publish(env);
+    }
+
+    fn index_sweep(env: &Env, sub_id: u64) {
+        let len: u32 = env
+            .storage()
+            .instance()
+            .get(&ContractKey::SweepLen)
+            .unwrap_or(0);
+        let key = SweepPageKey {
+            sweep_page: len / SWEEP_PAGE_SIZE,
+        };
+        let mut page: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        page.push_back(sub_id);
+        env.storage().persistent().set(&key, &page);
+        env.storage()
+            .instance()
+            .set(&ContractKey::SweepLen, &(len + 1));
+    }
+
+    fn sweep_page(env: &Env, position: u32) -> (SweepPageKey, Vec<u64>) {
+        let key = SweepPageKey {
+            sweep_page: position / SWEEP_PAGE_SIZE,
+        };
+        let page = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        (key, page)
+    }
+
+    fn sweep_entry(env: &Env, position: u32) -> u64 {
+        let (_, page) = Self::sweep_page(env, position);
+        page.get(position % SWEEP_PAGE_SIZE).unwrap_or(0)
+    }
+
+    fn set_sweep_entry(env: &Env, position: u32, sub_id: u64) {
+        let (key, mut page) = Self::sweep_page(env, position);
+        page.set(position % SWEEP_PAGE_SIZE, sub_id);
+        env.storage().persistent().set(&key, &page);
+    }
+
+    /// Remove the entry at `position`, the last one.
+    fn pop_sweep_entry(env: &Env, position: u32) {
+        let (key, mut page) = Self::sweep_page(env, position);
+        page.pop_back();
+        if page.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &page);
+        }
+    }
+
+    /// Opt a subscription in or out of usage-based billing. Owner only.
+    /// While metered, `renew` charges the amount settled by the metering
+    /// contract for the cycle and ignores the caller-supplied amount.
+    pub fn set_metered(env: Env, sub_id: u64, metered: bool) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+
+        data.owner.require_auth();
+
+        let key = MeteredKey {
+            metered_sub_id: sub_id,
+        };
+        if metered {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
+
+    /// Have renewals of `sub_id` that would take its spend in the window
+    /// past its spending cap wait for the owner's `confirm_pending_renewal`,
+    /// and be rejected if it doesn't come, instead of being rejected at
+    /// once. Owner only.
+    pub fn set_soft_cap(env: Env, sub_id: u64, enabled: bool) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        let key = SoftCapKey {
+            soft_cap_sub_id: sub_id,
+        };
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
+
+    /// What `sub_id` was charged in its current spending window.
+    pub fn get_spend_window(env: Env, sub_id: u64) -> SpendWindow {
+        Self::spend_window(&env, sub_id)
+    }
+
+    /// The subscription's window, or a fresh one starting now once it has
+    /// passed.
+    fn spend_window(env: &Env, sub_id: u64) -> SpendWindow {
+        let now = env.ledger().timestamp();
+        env.storage()
+            .persistent()
+            .get::<_, SpendWindow>(&SpendKey {
+                spend_sub_id: sub_id,
+            })
+            .filter(|window| now < window.started_at.saturating_add(SPEND_WINDOW))
+            .unwrap_or(SpendWindow {
+                started_at: now,
+                spent: 0,
+            })
+    }
+
+    pub fn is_soft_cap(env: Env, sub_id: u64) -> bool {
+        env.storage().persistent().has(&SoftCapKey {
+            soft_cap_sub_id: sub_id,
+        })
+    }
+
+    pub fn get_pending_renewal(env: Env, sub_id: u64) -> Option<PendingRenewal> {
+        env.storage().persistent().get(&PendingRenewalKey {
+            pending_sub_id: sub_id,
+        })
+    }
+
+    /// Agree to the renewal of `sub_id` parked for going over the spending
+    /// cap; the next `renew` for its cycle may then charge up to the parked
+    /// amount. Must come within [`CONFIRMATION_LEDGERS`] of parking.
+    /// Requires the owner's authorization.
+    pub fn confirm_pending_renewal(env: Env, sub_id: u64) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        let key = PendingRenewalKey {
+            pending_sub_id: sub_id,
+        };
+        let mut pending: PendingRenewal = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NoPendingRenewal)?;
+        if env.ledger().sequence() >= pending.expires_at {
+            return Err(Error::NoPendingRenewal);
+        }
+        pending.confirmed = true;
+        env.storage().persistent().set(&key, &pending);
+        PendingRenewalConfirmed {
+            sub_id,
+            cycle_id: pending.cycle_id,
+            amount: pending.amount,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Query whether a subscription is billed by usage.
+    pub fn is_metered(env: Env, sub_id: u64) -> bool {
+        env.storage().persistent().has(&MeteredKey {
+            metered_sub_id: sub_id,
+        })
+    }
+
+    /// Split each charge of `sub_id` between up to [`MAX_PAYOUTS`]
+    /// recipients in proportion to their weights, e.g. a platform and a
+    /// creator; an empty list pays the merchant everything. `caller` must
+    /// be the merchant or one of its keys.
+    pub fn set_payouts(
+        env: Env,
+        caller: Address,
+        sub_id: u64,
+        payouts: Vec<Payout>,
+    ) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        Self::require_merchant(&env, &caller, &data.merchant)?;
+        if payouts.len() > MAX_PAYOUTS {
+            return Err(Error::InvalidPayouts);
+        }
+        for (i, payout) in payouts.iter().enumerate() {
+            if payout.weight == 0 {
+                return Err(Error::InvalidPayouts);
+            }
+            if payouts
+                .iter()
+                .skip(i + 1)
+                .any(|other| other.recipient == payout.recipient)
+            {
+                return Err(Error::InvalidPayouts);
+            }
+        }
+        let key = PayoutsKey {
+            payouts_sub_id: sub_id,
+        };
+        if payouts.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &payouts);
+        }
+        Ok(())
+    }
+
+    pub fn get_payouts(env: Env, sub_id: u64) -> Vec<Payout> {
+        env.storage()
+            .persistent()
+            .get(&PayoutsKey {
+                payouts_sub_id: sub_id,
+            })
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// What the successful renewal of `sub_id` for `cycle_id` charged.
+    pub fn get_receipt(env: Env, sub_id: u64, cycle_id: u64) -> Result<Receipt, Error> {
+        Self::require_public(&env)?;
+        env.storage()
+            .persistent()
+            .get(&ReceiptKey {
+                receipt_sub_id: sub_id,
+                receipt_cycle_id: cycle_id,
+            })
+            .ok_or(Error::ReceiptNotFound)
+    }
+
+    /// `amount` split between `sub_id`'s payout recipients, rounding down;
+    /// the first recipient also gets what rounding leaves over.
+    fn split_payouts(env: &Env, sub_id: u64, merchant: &Address, amount: i128) -> Vec<PayoutShare> {
+        let payouts = Self::get_payouts(env.clone(), sub_id);
+        if payouts.is_empty() {
+            return soroban_sdk::vec![
+                env,
+                PayoutShare {
+                    recipient: merchant.clone(),
+                    amount,
+                }
+            ];
+        }
+        let total: i128 = payouts.iter().map(|payout| payout.weight as i128).sum();
+        let mut shares = Vec::new(env);
+        for payout in payouts.iter() {
+            shares.push_back(PayoutShare {
+                recipient: payout.recipient,
+                amount: portion(amount, payout.weight as i128, total),
+            });
         }
+        let paid: i128 = shares.iter().map(|share| share.amount).sum();
+        let mut first = shares.get_unchecked(0);
+        first.amount += amount - paid;
+        shares.set(0, first);
+        shares
+    }
+
+    /// Return `refund` of the `paid` held for a cycle to its funders, each
+    /// in proportion to what they paid. Rounding leftovers go to the first.
+    fn refund_funders(
+        env: &Env,
+        token: &token::TokenClient,
+        funders: &Vec<PartialPayment>,
+        paid: i128,
+        refund: i128,
+    ) {
+        let this = env.current_contract_address();
+        let shared: i128 = funders
+            .iter()
+            .map(|payment| portion(refund, payment.amount, paid))
+            .sum();
+        for (i, payment) in funders.iter().enumerate() {
+            let mut share = portion(refund, payment.amount, paid);
+            if i == 0 {
+                share += refund - shared;
+            }
+            if share > 0 {
+                token.transfer(&this, &payment.funder, &share);
+            }
+        }
+    }
 
+    /// Complete `cycle_id` once `amount` is paid for it: reactivate the
+    /// subscription, pay the charge out and record the renewal everywhere
+    /// it is tracked. A loyalty discount goes back to whoever paid: the
+    /// `funders` of a partially funded cycle, otherwise `payer`. Without a
+    /// `token` the charge was settled straight to the merchant, so no
+    /// discount, protocol fee or payout split applies.
+    #[allow(clippy::too_many_arguments)]
+    fn complete_renewal(
+        env: &Env,
+        sub_id: u64,
+        mut data: SubscriptionData,
+        cycle_id: u64,
+        amount: i128,
+        payer: &Address,
+        funders: Option<&Vec<PartialPayment>>,
+        token: Option<&token::TokenClient>,
+    ) -> Result<(), Error> {
         let current_ledger = env.ledger().sequence();
-        env.storage().persistent().remove(&lock_key);
 
-        RenewalLockReleased {
+        // Capture previous state before changing it
+        let previous_state = data.state;
+
+        // Renewal successful
+        data.state = SubscriptionState::Active;
+        data.failure_count = 0;
+        data.last_attempt_ledger = current_ledger;
+        env.storage().persistent().set(&sub_id, &data);
+        Self::journal_state(env, sub_id, SubscriptionState::Active);
+        env.storage().persistent().remove(&FailureKey {
+            failure_sub_id: sub_id,
+        });
+        if previous_state == SubscriptionState::Retrying {
+            env.storage().persistent().remove(&GraceKey {
+                grace_sub_id: sub_id,
+            });
+        }
+
+        // Store cycle_id on success only
+        env.storage()
+            .persistent()
+            .set(&CycleKey { sub_id }, &cycle_id);
+        let mut window = Self::spend_window(env, sub_id);
+        window.spent = window.spent.saturating_add(amount);
+        env.storage().persistent().set(
+            &SpendKey {
+                spend_sub_id: sub_id,
+            },
+            &window,
+        );
+
+        // Credit loyalty points; redeemed points lower the amount paid,
+        // and are refunded to whoever paid
+        let loyalty = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&ContractKey::LoyaltyContract);
+        let discount = match (token, loyalty) {
+            (Some(token), Some(loyalty_addr)) => {
+                let discount = LoyaltyClient::new(env, &loyalty_addr).on_renewal(
+                    &sub_id,
+                    &cycle_id,
+                    &data.owner,
+                    &data.merchant,
+                    &amount,
+                );
+                if discount > 0 {
+                    match funders {
+                        Some(funders) => {
+                            Self::refund_funders(env, token, funders, amount, discount)
+                        }
+                        None => token.transfer(&env.current_contract_address(), payer, &discount),
+                    }
+                }
+                discount
+            }
+            _ => 0,
+        };
+        let amount = amount - discount;
+
+        // Protocol fee on what the merchant receives, discounted by the
+        // merchant's staking tier
+        let fee = if token.is_some() && Self::fee_bps(env, &data.merchant).is_some() {
+            let (fee, discount_bps) =
+                Self::quote_protocol_fee(env.clone(), data.merchant.clone(), amount);
+            ProtocolFeeCharged {
+                sub_id,
+                cycle_id,
+                merchant: data.merchant.clone(),
+                fee,
+                discount_bps,
+            }
+            .publish(env);
+            fee
+        } else {
+            0
+        };
+
+        // Pay the charge out between the payout recipients, and the fee
+        // to the admin
+        let payouts = match token {
+            Some(token) => {
+                let payouts = Self::split_payouts(env, sub_id, &data.merchant, amount - fee);
+                Self::pay_out(env, token, &payouts, fee)?;
+                payouts
+            }
+            None => soroban_sdk::vec![
+                env,
+                PayoutShare {
+                    recipient: data.merchant.clone(),
+                    amount,
+                }
+            ],
+        };
+        RenewalSuccess {
             sub_id,
-            released_at: current_ledger,
+            owner: data.owner.clone(),
+            payouts,
         }
-        .publish(&env);
+        .publish(env);
+
+        env.storage().persistent().set(
+            &ReceiptKey {
+                receipt_sub_id: sub_id,
+                receipt_cycle_id: cycle_id,
+            },
+            &Receipt {
+                amount,
+                fee,
+                payer: payer.clone(),
+                merchant: data.merchant.clone(),
+                ledger: current_ledger,
+                terms_hash: data.integrity_hash.clone(),
+                metadata_hash: Self::get_metadata_anchor(env.clone(), sub_id)
+                    .map(|anchor| anchor.hash),
+            },
+        );
+
+        // Mint a proof-of-payment receipt when a receipt contract is linked
+        if let Some(receipt_addr) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&ContractKey::ReceiptContract)
+        {
+            ReceiptClient::new(env, &receipt_addr).mint(
+                &data.owner,
+                &sub_id,
+                &cycle_id,
+                &amount,
+                &data.merchant,
+            );
+        }
+
+        // Update lifecycle timestamps
+        let lc_key = LifecycleKey {
+            lifecycle_sub_id: sub_id,
+        };
+        let mut lifecycle = Self::load_lifecycle(env, sub_id)?;
+        let now = env.ledger().timestamp();
+        lifecycle.last_renewed_at = now;
+
+        LifecycleTimestampUpdated {
+            sub_id,
+            event_kind: 3,
+            timestamp: now,
+        }
+        .publish(env);
+
+        // If recovering from Retrying, also update activated_at
+        if previous_state == SubscriptionState::Retrying {
+            lifecycle.activated_at = now;
+            LifecycleTimestampUpdated {
+                sub_id,
+                event_kind: 2,
+                timestamp: now,
+            }
+            .publish(env);
+        }
+        env.storage().persistent().set(&lc_key, &lifecycle);
+
+        // Auto-release lock
+        Self::release_lock(env, sub_id);
+
+        // Record renewal success log
+        Self::record_log(
+            env,
+            sub_id,
+            LogEvent::Renewal,
+            soroban_sdk::String::from_str(env, "Renewal successful"),
+        );
+        Self::notify(env, "renewed", sub_id, &data.merchant, cycle_id, amount);
+        Self::sync_registry(env, sub_id, true);
+        Self::schedule(
+            env,
+            sub_id,
+            Some(current_ledger.saturating_add(Self::ledgers_for(env.clone(), data.frequency))),
+        );
+        Ok(())
     }
 
-    /// Query the current renewal lock for a subscription.
-    pub fn get_renewal_lock(env: Env, sub_id: u64) -> Option<RenewalLockData> {
-        let lock_key = RenewalLockKey {
-            lock_sub_id: sub_id,
+    fn token_client(env: &Env) -> Result<token::TokenClient<'_>, Error> {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&ContractKey::Token)
+            .ok_or(Error::PeerNotSet)?;
+        Ok(token::TokenClient::new(env, &token))
+    }
+
+    /// Pull `amount` from `payer` into this contract under the allowance
+    /// the payer gave it. False if the transfer fails, as it does when the
+    /// payer's balance or allowance falls short.
+    fn pull_payment(env: &Env, token: &token::TokenClient, payer: &Address, amount: i128) -> bool {
+        let contract = env.current_contract_address();
+        matches!(
+            token.try_transfer_from(&contract, payer, &contract, &amount),
+            Ok(Ok(()))
+        )
+    }
+
+    /// Pay a pulled renewal charge on to its recipients, and `fee` to the
+    /// admin.
+    fn pay_out(
+        env: &Env,
+        token: &token::TokenClient,
+        payouts: &Vec<PayoutShare>,
+        fee: i128,
+    ) -> Result<(), Error> {
+        let contract = env.current_contract_address();
+        for share in payouts.iter() {
+            if share.amount > 0 {
+                token.transfer(&contract, &share.recipient, &share.amount);
+            }
+        }
+        if fee > 0 {
+            let admin = syncro_access::admin(env)?;
+            token.transfer(&contract, &admin, &fee);
+        }
+        Ok(())
+    }
+
+    /// Have `payer` fund `sub_id` in its owner's place, or go back to the
+    /// owner paying with `None`. The payer approves renewals and is
+    /// charged for them; the owner keeps control of the subscription,
+    /// including cancelling it. Requires the owner's authorization, and the
+    /// new payer's.
+    pub fn set_payer(env: Env, sub_id: u64, payer: Option<Address>) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        let key = PayerKey {
+            payer_sub_id: sub_id,
         };
-        env.storage().persistent().get(&lock_key)
+        match &payer {
+            Some(payer) => {
+                payer.require_auth();
+                env.storage().persistent().set(&key, payer);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        PayerSet { sub_id, payer }.publish(&env);
+        Ok(())
     }
 
-    // ── Subscription logic ────────────────────────────────────────
+    /// Who pays for `sub_id`: its payer when one is set, else its owner.
+    pub fn get_payer(env: Env, sub_id: u64) -> Result<Address, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        Ok(Self::payer_of(&env, sub_id, &data))
+    }
 
-    /// Initialize a subscription
-    pub fn init_sub(
+    /// Record how the owner consents to be notified about `sub_id`, or
+    /// withdraw that consent with `None`. Notification agents read this
+    /// before messaging the owner. Requires the owner's authorization.
+    pub fn set_notification_prefs(
         env: Env,
-        owner: Address,
-        merchant: Address,
+        sub_id: u64,
+        prefs: Option<NotificationPrefs>,
+    ) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        let key = NotificationKey {
+            notification_sub_id: sub_id,
+        };
+        match &prefs {
+            Some(prefs) => env.storage().persistent().set(&key, prefs),
+            None => env.storage().persistent().remove(&key),
+        }
+        NotificationPrefsSet {
+            sub_id,
+            remind_days: prefs.as_ref().map(|prefs| prefs.remind_days),
+            mode: prefs.map(|prefs| prefs.mode),
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_notification_prefs(
+        env: Env,
+        sub_id: u64,
+    ) -> Result<Option<NotificationPrefs>, Error> {
+        Self::require_public(&env)?;
+        Ok(env.storage().persistent().get(&NotificationKey {
+            notification_sub_id: sub_id,
+        }))
+    }
+
+    fn payer_of(env: &Env, sub_id: u64, data: &SubscriptionData) -> Address {
+        env.storage()
+            .persistent()
+            .get(&PayerKey {
+                payer_sub_id: sub_id,
+            })
+            .unwrap_or(data.owner.clone())
+    }
+
+    /// Have the `strategy` contract settle `sub_id`'s charges, or go back
+    /// to prepaid deposits and pulling from the payer with `None`. The
+    /// strategy pays the merchant itself, so no protocol fee or payout
+    /// split applies. Requires the owner's authorization and `caller`'s,
+    /// who must be the merchant or one of its keys.
+    pub fn set_payment_strategy(
+        env: Env,
+        caller: Address,
+        sub_id: u64,
+        strategy: Option<Address>,
+    ) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        Self::require_merchant(&env, &caller, &data.merchant)?;
+        let key = StrategyKey {
+            strategy_sub_id: sub_id,
+        };
+        match &strategy {
+            Some(strategy) => env.storage().persistent().set(&key, strategy),
+            None => env.storage().persistent().remove(&key),
+        }
+        PaymentStrategySet { sub_id, strategy }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_payment_strategy(env: Env, sub_id: u64) -> Option<Address> {
+        env.storage().persistent().get(&StrategyKey {
+            strategy_sub_id: sub_id,
+        })
+    }
+
+    /// Have `strategy` settle a charge, counting a strategy that fails or
+    /// settles short of `amount` as paying nothing.
+    fn settle_with(
+        env: &Env,
+        strategy: &Address,
+        sub_id: u64,
+        cycle_id: u64,
+        payer: &Address,
+        merchant: &Address,
         amount: i128,
-        frequency: u64,
-        spending_cap: i128,
+    ) -> i128 {
+        PaymentStrategyClient::new(env, strategy)
+            .try_settle(
+                &env.current_contract_address(),
+                &sub_id,
+                &cycle_id,
+                payer,
+                merchant,
+                &amount,
+            )
+            .ok()
+            .and_then(|r| r.ok())
+            .filter(|settled| *settled >= amount)
+            .unwrap_or(0)
+    }
+
+    /// Set the cheaper amount `sub_id` switches to once its retries run out,
+    /// instead of failing, or remove it with `None`. The plan is used at
+    /// most once. Requires the owner's authorization and `caller`'s, who
+    /// must be the merchant or one of its keys.
+    pub fn set_fallback_plan(
+        env: Env,
+        caller: Address,
         sub_id: u64,
-    ) {
-        let mut integrity_data = soroban_sdk::Vec::<soroban_sdk::Val>::new(&env);
-        integrity_data.push_back(merchant.into_val(&env));
-        integrity_data.push_back(amount.into_val(&env));
-        integrity_data.push_back(frequency.into_val(&env));
-        integrity_data.push_back(spending_cap.into_val(&env));
+        amount: Option<i128>,
+    ) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        Self::require_merchant(&env, &caller, &data.merchant)?;
+        let key = FallbackKey {
+            fallback_sub_id: sub_id,
+        };
+        match amount {
+            Some(amount) => {
+                if amount <= 0 || amount >= data.amount {
+                    return Err(Error::InvalidAmount);
+                }
+                env.storage().persistent().set(&key, &amount);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        Ok(())
+    }
 
-        // Use a simple hash of the vector of values
-        let integrity_hash = env.crypto().sha256(&integrity_data.to_xdr(&env));
+    /// Set the retry policy subscriptions created from now on start with.
+    /// Admin or config manager.
+    pub fn set_default_retry_policy(
+        env: Env,
+        caller: Address,
+        policy: RetryPolicy,
+    ) -> Result<(), Error> {
+        Self::require_admin_or(&env, &caller, CONFIG_MANAGER)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::DefaultRetryPolicy, &policy);
+        Ok(())
+    }
 
-        let key = sub_id;
-        let data = SubscriptionData {
-            owner,
-            merchant,
-            amount,
-            frequency,
-            spending_cap,
-            integrity_hash: integrity_hash.into(),
-            state: SubscriptionState::Active,
-            failure_count: 0,
-            last_attempt_ledger: 0,
+    pub fn get_default_retry_policy(env: Env) -> RetryPolicy {
+        env.storage()
+            .instance()
+            .get(&ContractKey::DefaultRetryPolicy)
+            .unwrap_or(RetryPolicy {
+                max_retries: DEFAULT_MAX_RETRIES,
+                cooldown_ledgers: DEFAULT_COOLDOWN_LEDGERS,
+            })
+    }
+
+    /// Change how many failed renewals `sub_id` retries and how far apart.
+    /// Requires the owner's authorization and `caller`'s, who must be the
+    /// merchant or one of its keys.
+    pub fn set_retry_policy(
+        env: Env,
+        caller: Address,
+        sub_id: u64,
+        policy: RetryPolicy,
+    ) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        Self::require_merchant(&env, &caller, &data.merchant)?;
+        env.storage().persistent().set(
+            &RetryPolicyKey {
+                retry_policy_sub_id: sub_id,
+            },
+            &policy,
+        );
+        Ok(())
+    }
+
+    /// The retry policy `renew` applies to `sub_id`, unless its merchant's
+    /// profile overrides it.
+    pub fn get_retry_policy(env: Env, sub_id: u64) -> Result<RetryPolicy, Error> {
+        Self::load_sub(&env, sub_id)?;
+        Ok(Self::retry_policy_of(&env, sub_id))
+    }
+
+    fn retry_policy_of(env: &Env, sub_id: u64) -> RetryPolicy {
+        env.storage()
+            .persistent()
+            .get(&RetryPolicyKey {
+                retry_policy_sub_id: sub_id,
+            })
+            .unwrap_or_else(|| Self::get_default_retry_policy(env.clone()))
+    }
+
+    /// Set how `sub_id`'s cooldown between retries grows with each
+    /// consecutive failure, or remove the policy with `None`. While set it
+    /// replaces the cooldown of its retry policy and the merchant
+    /// profile's. Requires the owner's authorization and `caller`'s, who
+    /// must be the merchant or one of its keys.
+    pub fn set_backoff_policy(
+        env: Env,
+        caller: Address,
+        sub_id: u64,
+        policy: Option<BackoffPolicy>,
+    ) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        Self::require_merchant(&env, &caller, &data.merchant)?;
+        let key = BackoffKey {
+            backoff_sub_id: sub_id,
         };
-        env.storage().persistent().set(&key, &data);
+        match policy {
+            Some(policy) => {
+                if policy.multiplier == 0 || policy.base_ledgers > policy.max_ledgers {
+                    return Err(Error::InvalidConfig);
+                }
+                env.storage().persistent().set(&key, &policy);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        Ok(())
+    }
 
-        // Initialize lifecycle timestamps
+    pub fn get_backoff_policy(env: Env, sub_id: u64) -> Option<BackoffPolicy> {
+        env.storage().persistent().get(&BackoffKey {
+            backoff_sub_id: sub_id,
+        })
+    }
+
+    /// The ledger from which a retrying subscription may next be retried,
+    /// as set by its latest failure; `None` unless it is retrying.
+    pub fn get_next_retry_ledger(env: Env, sub_id: u64) -> Result<Option<u32>, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        if data.state != SubscriptionState::Retrying {
+            return Ok(None);
+        }
+        Ok(env.storage().persistent().get(&RetryAtKey {
+            retry_sub_id: sub_id,
+        }))
+    }
+
+    /// Cooldown after `failures` consecutive failures: the subscription's
+    /// backoff policy's, or `fixed` without one.
+    fn cooldown_after(env: &Env, sub_id: u64, failures: u32, fixed: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get::<_, BackoffPolicy>(&BackoffKey {
+                backoff_sub_id: sub_id,
+            })
+            .map_or(fixed, |policy| policy.cooldown(failures))
+    }
+
+    pub fn get_fallback_plan(env: Env, sub_id: u64) -> Option<i128> {
+        env.storage().persistent().get(&FallbackKey {
+            fallback_sub_id: sub_id,
+        })
+    }
+
+    /// Bill `sub_id` every `frequency` seconds from the next cycle
+    /// boundary on, replacing any change already pending. The renewal
+    /// charging the first period at the new frequency applies it. Requires
+    /// the owner's authorization and `caller`'s, who must be the merchant
+    /// or one of its keys. Returns the timestamp the change takes effect at.
+    pub fn schedule_frequency_change(
+        env: Env,
+        caller: Address,
+        sub_id: u64,
+        frequency: u64,
+    ) -> Result<u64, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        Self::require_merchant(&env, &caller, &data.merchant)?;
+        if data.state == SubscriptionState::Failed {
+            return Err(Error::SubFailed);
+        }
+        if data.state == SubscriptionState::Cancelled {
+            return Err(Error::SubCancelled);
+        }
+        if data.state == SubscriptionState::Expired {
+            return Err(Error::SubExpired);
+        }
+        if frequency == 0 || frequency == data.frequency {
+            return Err(Error::InvalidConfig);
+        }
+        let (start, current) = Self::billing_terms(&env, sub_id, &data)?;
         let now = env.ledger().timestamp();
-        let lifecycle = LifecycleTimestamps {
-            created_at: now,
-            activated_at: now,
-            last_renewed_at: 0,
-            canceled_at: 0,
+        let effective_at = match now.checked_sub(start) {
+            Some(elapsed) if current > 0 => {
+                start.saturating_add((elapsed / current + 1).saturating_mul(current))
+            }
+            Some(_) => now,
+            None => start,
         };
-        let lc_key = LifecycleKey {
-            lifecycle_sub_id: sub_id,
+        env.storage().persistent().set(
+            &FrequencyChangeKey {
+                frequency_change_sub_id: sub_id,
+            },
+            &FrequencyChange {
+                frequency,
+                effective_at,
+            },
+        );
+        FrequencyChangeScheduled {
+            sub_id,
+            frequency,
+            effective_at,
+        }
+        .publish(&env);
+        Ok(effective_at)
+    }
+
+    /// Drop `sub_id`'s pending frequency change, if any, before it
+    /// applies. Requires the owner's authorization.
+    pub fn cancel_frequency_change(env: Env, sub_id: u64) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        let key = FrequencyChangeKey {
+            frequency_change_sub_id: sub_id,
         };
-        env.storage().persistent().set(&lc_key, &lifecycle);
+        if env.storage().persistent().has(&key) {
+            env.storage().persistent().remove(&key);
+            FrequencyChangeCancelled { sub_id }.publish(&env);
+        }
+        Ok(())
+    }
 
-        LifecycleTimestampUpdated {
+    pub fn get_frequency_change(env: Env, sub_id: u64) -> Option<FrequencyChange> {
+        env.storage().persistent().get(&FrequencyChangeKey {
+            frequency_change_sub_id: sub_id,
+        })
+    }
+
+    /// Switch `data` to its pending frequency once the change's cycle
+    /// boundary has passed, and rehash it. Returns the change, to store
+    /// with `store_frequency_change` once the renewal goes ahead.
+    fn apply_frequency_change(
+        env: &Env,
+        sub_id: u64,
+        data: &mut SubscriptionData,
+    ) -> Option<FrequencyChange> {
+        let change = Self::get_frequency_change(env.clone(), sub_id)?;
+        if env.ledger().timestamp() < change.effective_at {
+            return None;
+        }
+        data.frequency = change.frequency;
+        data.integrity_hash = Self::integrity_hash(
+            env,
+            &data.merchant,
+            data.amount,
+            data.frequency,
+            data.spending_cap,
+            Self::get_indexation(env.clone(), sub_id),
+        );
+        Some(change)
+    }
+
+    /// Record that `change` took `sub_id` off `previous_frequency`.
+    fn store_frequency_change(
+        env: &Env,
+        sub_id: u64,
+        previous_frequency: u64,
+        change: FrequencyChange,
+    ) {
+        env.storage().persistent().remove(&FrequencyChangeKey {
+            frequency_change_sub_id: sub_id,
+        });
+        env.storage().persistent().set(
+            &BillingAnchorKey {
+                anchor_sub_id: sub_id,
+            },
+            &change.effective_at,
+        );
+        FrequencyChanged {
+            sub_id,
+            previous_frequency,
+            frequency: change.frequency,
+        }
+        .publish(env);
+    }
+
+    /// Replace `sub_id`'s amount, frequency and spending cap at once, and
+    /// rehash them. A new frequency starts a billing period now and drops
+    /// any pending frequency change. Requires both the owner's and the
+    /// merchant's authorization.
+    pub fn amend_sub(
+        env: Env,
+        sub_id: u64,
+        new_amount: i128,
+        new_frequency: u64,
+        new_cap: i128,
+    ) -> Result<(), Error> {
+        let mut data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        data.merchant.require_auth();
+        if data.state == SubscriptionState::Failed {
+            return Err(Error::SubFailed);
+        }
+        if data.state == SubscriptionState::Cancelled {
+            return Err(Error::SubCancelled);
+        }
+        if data.state == SubscriptionState::Expired {
+            return Err(Error::SubExpired);
+        }
+        if new_amount <= 0 || new_cap < new_amount {
+            return Err(Error::InvalidAmount);
+        }
+        if new_frequency == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        let indexation = Self::get_indexation(env.clone(), sub_id);
+        if matches!(&indexation, Some(terms) if terms.interval < new_frequency) {
+            return Err(Error::InvalidIndexation);
+        }
+
+        if new_frequency != data.frequency {
+            env.storage().persistent().set(
+                &BillingAnchorKey {
+                    anchor_sub_id: sub_id,
+                },
+                &env.ledger().timestamp(),
+            );
+            let key = FrequencyChangeKey {
+                frequency_change_sub_id: sub_id,
+            };
+            if env.storage().persistent().has(&key) {
+                env.storage().persistent().remove(&key);
+                FrequencyChangeCancelled { sub_id }.publish(&env);
+            }
+        }
+        data.amount = new_amount;
+        data.frequency = new_frequency;
+        data.spending_cap = new_cap;
+        data.integrity_hash = Self::integrity_hash(
+            &env,
+            &data.merchant,
+            data.amount,
+            data.frequency,
+            data.spending_cap,
+            indexation,
+        );
+        env.storage().persistent().set(&sub_id, &data);
+        AmendmentApplied {
             sub_id,
-            event_kind: 1,
-            timestamp: now,
+            amount: new_amount,
+            frequency: new_frequency,
+            spending_cap: new_cap,
         }
         .publish(&env);
-        LifecycleTimestampUpdated {
+        Ok(())
+    }
+
+    /// Anchor the hash of the terms agreed for `sub_id`, replacing the
+    /// current anchor. Requires both the owner's and the merchant's
+    /// authorization. Returns the anchor's version; earlier versions stay
+    /// readable so disputes can cite the terms in force at the time.
+    pub fn anchor_metadata(env: Env, sub_id: u64, hash: BytesN<32>) -> Result<u32, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        data.merchant.require_auth();
+        let version =
+            Self::get_metadata_anchor(env.clone(), sub_id).map_or(1, |anchor| anchor.version + 1);
+        let anchor = MetadataAnchor {
+            hash: hash.clone(),
+            version,
+            anchored_at: env.ledger().timestamp(),
+        };
+        for metadata_version in [None, Some(version)] {
+            env.storage().persistent().set(
+                &MetadataKey {
+                    metadata_sub_id: sub_id,
+                    metadata_version,
+                },
+                &anchor,
+            );
+        }
+        MetadataAnchored {
             sub_id,
-            event_kind: 2,
-            timestamp: now,
+            hash,
+            version,
         }
         .publish(&env);
+        Ok(version)
+    }
 
-        // Record initialization log
-        Self::record_log(
-            &env,
-            sub_id,
-            2,
-            soroban_sdk::String::from_str(&env, "Subscription initialized"),
-        );
+    pub fn get_metadata_anchor(env: Env, sub_id: u64) -> Option<MetadataAnchor> {
+        env.storage().persistent().get(&MetadataKey {
+            metadata_sub_id: sub_id,
+            metadata_version: None,
+        })
     }
 
-    fn record_log(env: &Env, sub_id: u64, event_type: u32, data_str: soroban_sdk::String) {
-        if let Some(_log_addr) = env
-            .storage()
-            .instance()
-            .get::<_, Address>(&ContractKey::LoggingContract)
-        {
-            // Here we would call the logging contract.
-            // Since we are in a multi-contract setup, we'd use a client.
-            // For now, we'll emit an event as a placeholder or assume the client is available.
-            // (In a real implementation, we'd use a cross-contract call).
-            env.events().publish(
-                (soroban_sdk::symbol_short!("log"), sub_id),
-                (event_type, data_str),
-            );
+    /// The anchor `sub_id` had at `version`.
+    pub fn get_metadata_anchor_at(env: Env, sub_id: u64, version: u32) -> Option<MetadataAnchor> {
+        env.storage().persistent().get(&MetadataKey {
+            metadata_sub_id: sub_id,
+            metadata_version: Some(version),
+        })
+    }
+
+    /// Link `sub_id` to the owner's `entry` in the subscription registry so
+    /// renewals keep it current, or unlink it with `None`. Requires the
+    /// owner's authorization.
+    pub fn link_registry_entry(
+        env: Env,
+        sub_id: u64,
+        entry: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        data.owner.require_auth();
+        let key = RegistryEntryKey {
+            entry_sub_id: sub_id,
+        };
+        match entry {
+            Some(entry) => {
+                let registry = Self::peer(&env, &ContractKey::SubscriptionRegistry, REGISTRY)
+                    .ok_or(Error::PeerNotSet)?;
+                if !SubscriptionRegistryClient::new(&env, &registry)
+                    .get_user_subscriptions(&data.owner)
+                    .contains(&entry)
+                {
+                    return Err(Error::NotOwner);
+                }
+                env.storage().persistent().set(&key, &entry);
+            }
+            None => env.storage().persistent().remove(&key),
         }
+        Ok(())
     }
 
-    /// Explicitly cancel a subscription
-    pub fn cancel_sub(env: Env, sub_id: u64) {
-        let key = sub_id;
-        let mut data: SubscriptionData = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .expect("Subscription not found");
+    pub fn get_registry_entry(env: Env, sub_id: u64) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&RegistryEntryKey {
+            entry_sub_id: sub_id,
+        })
+    }
+
+    // ── Ownership transfer ────────────────────────────────────────
+
+    /// Allow `operator` (e.g. a marketplace) to transfer a subscription on
+    /// the owner's behalf, or revoke the approval with `None`. Owner only.
+    pub fn approve_sub_transfer(
+        env: Env,
+        sub_id: u64,
+        operator: Option<Address>,
+    ) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
 
         data.owner.require_auth();
 
-        if data.state == SubscriptionState::Cancelled {
-            panic!("Subscription already cancelled");
+        let key = TransferOperatorKey {
+            transfer_sub_id: sub_id,
+        };
+        match &operator {
+            Some(operator) => env.storage().persistent().set(&key, operator),
+            None => env.storage().persistent().remove(&key),
         }
+        TransferOperatorSet { sub_id, operator }.publish(&env);
+        Ok(())
+    }
 
-        data.state = SubscriptionState::Cancelled;
-        env.storage().persistent().set(&key, &data);
+    /// The operator currently approved to transfer a subscription.
+    pub fn get_sub_transfer_operator(env: Env, sub_id: u64) -> Option<Address> {
+        env.storage().persistent().get(&TransferOperatorKey {
+            transfer_sub_id: sub_id,
+        })
+    }
 
-        // Update lifecycle timestamps
-        let lc_key = LifecycleKey {
-            lifecycle_sub_id: sub_id,
+    /// Hand a subscription to `new_owner`. `caller` must be the owner or
+    /// the approved operator; the approval is cleared by the transfer.
+    /// Cancelled and failed subscriptions cannot change hands.
+    pub fn transfer_sub(
+        env: Env,
+        sub_id: u64,
+        caller: Address,
+        new_owner: Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let mut data = Self::load_sub(&env, sub_id)?;
+
+        let key = TransferOperatorKey {
+            transfer_sub_id: sub_id,
         };
-        let mut lifecycle: LifecycleTimestamps = env
-            .storage()
-            .persistent()
-            .get(&lc_key)
-            .expect("Lifecycle data not found");
-        let now = env.ledger().timestamp();
-        lifecycle.canceled_at = now;
-        env.storage().persistent().set(&lc_key, &lifecycle);
+        let operator: Option<Address> = env.storage().persistent().get(&key);
+        if caller != data.owner && operator.as_ref() != Some(&caller) {
+            return Err(Error::Unauthorized);
+        }
+        if matches!(
+            data.state,
+            SubscriptionState::Cancelled | SubscriptionState::Failed | SubscriptionState::Expired
+        ) {
+            return Err(Error::NotTransferable);
+        }
+        if new_owner == data.owner {
+            return Err(Error::AlreadyOwner);
+        }
 
-        LifecycleTimestampUpdated {
+        let from = data.owner.clone();
+        data.owner = new_owner.clone();
+        env.storage().persistent().set(&sub_id, &data);
+        env.storage().persistent().remove(&key);
+        // The payer agreed to fund the previous owner, not whoever buys in
+        if env.storage().persistent().has(&PayerKey {
+            payer_sub_id: sub_id,
+        }) {
+            env.storage().persistent().remove(&PayerKey {
+                payer_sub_id: sub_id,
+            });
+            PayerSet {
+                sub_id,
+                payer: None,
+            }
+            .publish(&env);
+        }
+        // Nor did the new owner consent to the previous owner's messaging
+        if env.storage().persistent().has(&NotificationKey {
+            notification_sub_id: sub_id,
+        }) {
+            env.storage().persistent().remove(&NotificationKey {
+                notification_sub_id: sub_id,
+            });
+            NotificationPrefsSet {
+                sub_id,
+                remind_days: None,
+                mode: None,
+            }
+            .publish(&env);
+        }
+        Self::unindex_owner(&env, &from, sub_id);
+        Self::index_owner(&env, &new_owner, sub_id);
+
+        SubscriptionTransferred {
             sub_id,
-            event_kind: 4,
-            timestamp: now,
+            from,
+            to: new_owner,
         }
         .publish(&env);
+        Ok(())
+    }
 
-        // Record cancellation log
-        Self::record_log(
-            &env,
-            sub_id,
-            5,
-            soroban_sdk::String::from_str(&env, "Subscription cancelled"),
-        );
+    /// Move a subscription from `from` to `new_owner` without the owner's
+    /// signature. Only the configured recovery contract may call this, once
+    /// the owner's guardians have approved. Any pending transfer approval is
+    /// dropped, since it was granted by the lost key.
+    pub fn recover_sub(
+        env: Env,
+        sub_id: u64,
+        from: Address,
+        new_owner: Address,
+    ) -> Result<(), Error> {
+        let recovery: Address = env
+            .storage()
+            .instance()
+            .get(&ContractKey::RecoveryContract)
+            .ok_or(Error::PeerNotSet)?;
+        recovery.require_auth();
 
-        // Emit state transition event
-        StateTransition {
+        let mut data = Self::load_sub(&env, sub_id)?;
+        if data.owner != from {
+            return Err(Error::NotOwner);
+        }
+        if new_owner == from {
+            return Err(Error::AlreadyOwner);
+        }
+
+        data.owner = new_owner.clone();
+        env.storage().persistent().set(&sub_id, &data);
+        env.storage().persistent().remove(&TransferOperatorKey {
+            transfer_sub_id: sub_id,
+        });
+        Self::unindex_owner(&env, &from, sub_id);
+        if data.state != SubscriptionState::Cancelled {
+            Self::index_owner(&env, &new_owner, sub_id);
+        }
+
+        SubscriptionTransferred {
             sub_id,
-            new_state: SubscriptionState::Cancelled,
+            from,
+            to: new_owner,
         }
         .publish(&env);
+        Ok(())
     }
 
     // ── Approval management ───────────────────────────────────────
 
-    /// Create a renewal approval for a subscription
+    /// Create a renewal approval for a subscription, usable up to and
+    /// including ledger `expires_at`; see `ledger_at` to expire it at a
+    /// timestamp. Signed by whoever pays for the subscription.
     pub fn approve_renewal(
         env: Env,
         sub_id: u64,
         approval_id: u64,
         max_spend: i128,
         expires_at: u32,
-    ) {
-        let sub_key = sub_id;
-        let data: SubscriptionData = env
-            .storage()
-            .persistent()
-            .get(&sub_key)
-            .expect("Subscription not found");
+    ) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
 
-        data.owner.require_auth();
+        Self::payer_of(&env, sub_id, &data).require_auth();
 
         let approval = RenewalApproval {
             sub_id,
@@ -467,6 +4763,7 @@ impl SubscriptionRenewalContract {
             approval_id,
         };
         env.storage().persistent().set(&key, &approval);
+        keep_alive(&env, &key);
 
         ApprovalCreated {
             sub_id,
@@ -475,236 +4772,890 @@ impl SubscriptionRenewalContract {
             expires_at,
         }
         .publish(&env);
+        Ok(())
+    }
+
+    /// Let `agent` create approvals for the subscriptions `owner` pays for
+    /// with `approve_renewal_as`, within `budget`, or stop it with `None`.
+    /// Signed by `owner`. Approvals the agent already created stay
+    /// usable; lowering the budget only holds back new ones.
+    pub fn set_delegation_budget(
+        env: Env,
+        owner: Address,
+        agent: Address,
+        budget: Option<DelegationBudget>,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        let key = DelegationKey {
+            delegation_owner: owner.clone(),
+            delegation_agent: agent.clone(),
+        };
+        let Some(budget) = budget else {
+            env.storage().persistent().remove(&key);
+            DelegationRevoked { owner, agent }.publish(&env);
+            return Ok(());
+        };
+        if budget.max_outstanding <= 0 || budget.max_approvals == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        let delegation = match env.storage().persistent().get::<_, Delegation>(&key) {
+            Some(current) => Delegation { budget, ..current },
+            None => Delegation {
+                budget,
+                outstanding: 0,
+                approvals: 0,
+            },
+        };
+        env.storage().persistent().set(&key, &delegation);
+        DelegationBudgetSet {
+            owner,
+            agent,
+            max_outstanding: delegation.budget.max_outstanding,
+            max_approvals: delegation.budget.max_approvals,
+        }
+        .publish(&env);
+        Ok(())
     }
 
-    /// Validate and consume an approval
-    fn consume_approval(env: &Env, sub_id: u64, approval_id: u64, amount: i128) -> bool {
+    pub fn get_delegation(env: Env, owner: Address, agent: Address) -> Option<Delegation> {
+        env.storage().persistent().get(&DelegationKey {
+            delegation_owner: owner,
+            delegation_agent: agent,
+        })
+    }
+
+    /// Create a renewal approval as `approve_renewal` does, signed by
+    /// `agent` on the behalf of the subscription's payer: its owner unless
+    /// another payer is set. The agent needs the Approvals scope when an
+    /// agent registry is set, and a budget from the payer with room for
+    /// `max_spend` and one more approval. The budget
+    /// is freed once the approval is used, or revoked with
+    /// `revoke_delegated_approval`.
+    pub fn approve_renewal_as(
+        env: Env,
+        agent: Address,
+        sub_id: u64,
+        approval_id: u64,
+        max_spend: i128,
+        expires_at: u32,
+    ) -> Result<(), Error> {
+        agent.require_auth();
+        if let Some(registry) = Self::peer(&env, &ContractKey::AgentRegistry, AGENTS) {
+            if !Self::registry_grants(&env, &registry, &agent, Scope::Approvals) {
+                return Err(Error::AgentNotAuthorized);
+            }
+        }
+        let data = Self::load_sub(&env, sub_id)?;
+        let delegation_key = DelegationKey {
+            delegation_owner: Self::payer_of(&env, sub_id, &data),
+            delegation_agent: agent.clone(),
+        };
+        let mut delegation: Delegation = env
+            .storage()
+            .persistent()
+            .get(&delegation_key)
+            .ok_or(Error::AgentNotAllowed)?;
+        if max_spend <= 0 {
+            return Err(Error::InvalidAmount);
+        }
         let key = ApprovalKey {
             sub_id,
             approval_id,
         };
+        if env.storage().persistent().has(&key) {
+            return Err(Error::ApprovalInvalid);
+        }
+        if delegation.approvals >= delegation.budget.max_approvals {
+            return Err(Error::AgentRateLimited);
+        }
+        delegation.outstanding = delegation
+            .outstanding
+            .checked_add(max_spend)
+            .filter(|outstanding| *outstanding <= delegation.budget.max_outstanding)
+            .ok_or(Error::InvalidAmount)?;
+        delegation.approvals += 1;
 
-        let approval_opt: Option<RenewalApproval> = env.storage().persistent().get(&key);
-
-        if approval_opt.is_none() {
-            ApprovalRejected {
+        env.storage().persistent().set(
+            &key,
+            &RenewalApproval {
                 sub_id,
-                approval_id,
-                reason: 4,
-            }
-            .publish(env);
-            return false;
+                max_spend,
+                expires_at,
+                used: false,
+            },
+        );
+        keep_alive(&env, &key);
+        env.storage().persistent().set(
+            &DelegatedApprovalKey {
+                delegated_sub_id: sub_id,
+                delegated_approval_id: approval_id,
+            },
+            &delegation_key,
+        );
+        env.storage().persistent().set(&delegation_key, &delegation);
+
+        ApprovalCreated {
+            sub_id,
+            approval_id,
+            max_spend,
+            expires_at,
+        }
+        .publish(&env);
+        ApprovalDelegated {
+            sub_id,
+            approval_id,
+            agent,
+            max_spend,
         }
+        .publish(&env);
+        Ok(())
+    }
 
-        let mut approval = approval_opt.unwrap();
+    /// Withdraw an unused approval an agent created for `sub_id`, freeing
+    /// its share of the agent's budget. Signed by the payer; expired
+    /// approvals hold budget until revoked.
+    pub fn revoke_delegated_approval(env: Env, sub_id: u64, approval_id: u64) -> Result<(), Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        Self::payer_of(&env, sub_id, &data).require_auth();
+        let key = ApprovalKey {
+            sub_id,
+            approval_id,
+        };
+        let approval: RenewalApproval = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::ApprovalInvalid)?;
+        if approval.used || !Self::release_delegation(&env, sub_id, approval_id, approval.max_spend)
+        {
+            return Err(Error::ApprovalInvalid);
+        }
+        env.storage().persistent().remove(&key);
+        Ok(())
+    }
 
-        if approval.used {
-            ApprovalRejected {
-                sub_id,
-                approval_id,
-                reason: 2,
-            }
-            .publish(env);
+    /// Give back the budget approval `approval_id` took from the agent
+    /// that created it, if one did.
+    fn release_delegation(env: &Env, sub_id: u64, approval_id: u64, max_spend: i128) -> bool {
+        let marker = DelegatedApprovalKey {
+            delegated_sub_id: sub_id,
+            delegated_approval_id: approval_id,
+        };
+        let Some(delegation_key) = env.storage().persistent().get::<_, DelegationKey>(&marker)
+        else {
             return false;
+        };
+        env.storage().persistent().remove(&marker);
+        if let Some(mut delegation) = env
+            .storage()
+            .persistent()
+            .get::<_, Delegation>(&delegation_key)
+        {
+            delegation.outstanding = delegation.outstanding.saturating_sub(max_spend).max(0);
+            delegation.approvals = delegation.approvals.saturating_sub(1);
+            env.storage().persistent().set(&delegation_key, &delegation);
         }
+        true
+    }
 
-        let current_ledger = env.ledger().sequence();
-        if current_ledger > approval.expires_at {
-            ApprovalRejected {
-                sub_id,
-                approval_id,
-                reason: 1,
+    /// Validate and consume an approval, announcing why it was refused
+    fn consume_approval(
+        env: &Env,
+        payer: &Address,
+        sub_id: u64,
+        approval_id: u64,
+        amount: i128,
+    ) -> bool {
+        match Self::check_approval(env, sub_id, approval_id, amount) {
+            Ok(max_spend) => {
+                ApprovalConsumed {
+                    sub_id,
+                    approval_id,
+                    max_spend,
+                    spent: amount,
+                }
+                .publish(env);
+                Self::record_utilization(env, payer, max_spend, amount);
+                true
+            }
+            Err(reason) => {
+                ApprovalRejected {
+                    sub_id,
+                    approval_id,
+                    reason,
+                }
+                .publish(env);
+                false
             }
-            .publish(env);
-            return false;
         }
+    }
 
+    fn check_approval(
+        env: &Env,
+        sub_id: u64,
+        approval_id: u64,
+        amount: i128,
+    ) -> Result<i128, ReasonCode> {
+        let key = ApprovalKey {
+            sub_id,
+            approval_id,
+        };
+        let mut approval: RenewalApproval = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(ReasonCode::ApprovalNotFound)?;
+        if approval.used {
+            return Err(ReasonCode::ApprovalUsed);
+        }
+        if env.ledger().sequence() > approval.expires_at {
+            return Err(ReasonCode::ApprovalExpired);
+        }
         if amount > approval.max_spend {
-            ApprovalRejected {
-                sub_id,
-                approval_id,
-                reason: 3,
-            }
-            .publish(env);
-            return false;
+            return Err(ReasonCode::AmountExceeded);
+        }
+
+        approval.used = true;
+        env.storage().persistent().set(&key, &approval);
+        Self::release_delegation(env, sub_id, approval_id, approval.max_spend);
+        Ok(approval.max_spend)
+    }
+
+    fn record_utilization(env: &Env, owner: &Address, max_spend: i128, spent: i128) {
+        let period = env.ledger().timestamp() / UTILIZATION_PERIOD;
+        let mut history = Self::get_approval_utilization(env.clone(), owner.clone());
+        let mut current = match history.last() {
+            Some(last) if last.period == period => history.pop_back().unwrap(),
+            _ => ApprovalUtilization {
+                period,
+                approvals: 0,
+                max_spend: 0,
+                spent: 0,
+            },
+        };
+        current.approvals = current.approvals.saturating_add(1);
+        current.max_spend = current.max_spend.saturating_add(max_spend);
+        current.spent = current.spent.saturating_add(spent);
+        if history.len() == UTILIZATION_PERIODS {
+            history.pop_front();
+        }
+        history.push_back(current);
+        env.storage().persistent().set(
+            &UtilizationKey {
+                util_owner: owner.clone(),
+            },
+            &history,
+        );
+    }
+
+    /// `owner`'s approval utilization, over the subscriptions it pays for,
+    /// for the latest periods in which approvals were consumed, oldest
+    /// first.
+    pub fn get_approval_utilization(env: Env, owner: Address) -> Vec<ApprovalUtilization> {
+        env.storage()
+            .persistent()
+            .get(&UtilizationKey { util_owner: owner })
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // ── Partial payments ──────────────────────────────────────────
+
+    /// Pay `amount` towards `cycle_id` of `sub_id` from `funder`, who can
+    /// be anyone, e.g. one of two funding sources sharing a charge. The
+    /// payments are held until together they reach the subscription's
+    /// amount, which completes the cycle as a successful renewal does; they
+    /// must get there within [`PARTIAL_PAYMENT_LEDGERS`] of the first.
+    /// Returns whether the cycle is complete. Requires `funder`'s
+    /// authorization.
+    pub fn pay_partial(
+        env: Env,
+        sub_id: u64,
+        cycle_id: u64,
+        funder: Address,
+        amount: i128,
+    ) -> Result<bool, Error> {
+        funder.require_auth();
+        if Self::is_paused(env.clone()) {
+            return Err(Error::Paused);
+        }
+        let data = Self::load_sub(&env, sub_id)?;
+        if data.state == SubscriptionState::Failed {
+            return Err(Error::SubFailed);
+        }
+        if data.state == SubscriptionState::Cancelled {
+            return Err(Error::SubCancelled);
+        }
+        if data.state == SubscriptionState::Expired {
+            return Err(Error::SubExpired);
+        }
+        if data.state == SubscriptionState::Paused {
+            return Err(Error::Paused);
+        }
+        if !Self::cycle_allowed(&env, sub_id, &data, cycle_id)? {
+            return Err(Error::CycleNotDerived);
+        }
+        if Self::is_charged(&env, sub_id, cycle_id) {
+            return Err(Error::DuplicateCycle);
+        }
+        if !Self::integrity_holds(&env, sub_id, &data) {
+            return Err(Error::IntegrityViolation);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let key = CycleFundingKey {
+            funding_sub_id: sub_id,
+            funding_cycle_id: cycle_id,
+        };
+        let current_ledger = env.ledger().sequence();
+        let mut funding = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(CycleFunding {
+                required: data.amount,
+                paid: 0,
+                due_by: current_ledger.saturating_add(PARTIAL_PAYMENT_LEDGERS),
+                payments: Vec::new(&env),
+            });
+        if current_ledger >= funding.due_by {
+            return Err(Error::PartialWindowClosed);
+        }
+        if funding
+            .paid
+            .checked_add(amount)
+            .is_none_or(|paid| paid > funding.required)
+        {
+            return Err(Error::ExceedsAmountDue);
+        }
+
+        let token = Self::token_client(&env)?;
+        token.transfer(&funder, env.current_contract_address(), &amount);
+        funding.paid += amount;
+        funding.payments.push_back(PartialPayment {
+            funder: funder.clone(),
+            amount,
+        });
+        PartialPaymentReceived {
+            sub_id,
+            cycle_id,
+            funder,
+            amount,
+            paid: funding.paid,
+            required: funding.required,
+        }
+        .publish(&env);
+        if funding.paid < funding.required {
+            env.storage().persistent().set(&key, &funding);
+            return Ok(false);
+        }
+
+        env.storage().persistent().remove(&key);
+        let payer = Self::payer_of(&env, sub_id, &data);
+        Self::complete_renewal(
+            &env,
+            sub_id,
+            data,
+            cycle_id,
+            funding.required,
+            &payer,
+            Some(&funding.payments),
+            Some(&token),
+        )?;
+        Ok(true)
+    }
+
+    /// Partial payments held towards `cycle_id` of `sub_id`, if any.
+    pub fn get_cycle_funding(env: Env, sub_id: u64, cycle_id: u64) -> Option<CycleFunding> {
+        env.storage().persistent().get(&CycleFundingKey {
+            funding_sub_id: sub_id,
+            funding_cycle_id: cycle_id,
+        })
+    }
+
+    /// Return the partial payments towards `cycle_id` of `sub_id` to their
+    /// funders, once the cycle can no longer be completed by them: its
+    /// window closed, or it was charged another way. Anyone can call this.
+    /// Returns the amount refunded.
+    pub fn refund_partial_payments(env: Env, sub_id: u64, cycle_id: u64) -> Result<i128, Error> {
+        let key = CycleFundingKey {
+            funding_sub_id: sub_id,
+            funding_cycle_id: cycle_id,
+        };
+        let funding: CycleFunding = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NoPartialPayments)?;
+        if env.ledger().sequence() < funding.due_by && !Self::is_charged(&env, sub_id, cycle_id) {
+            return Err(Error::PartialWindowOpen);
+        }
+        let token = Self::token_client(&env)?;
+        let contract = env.current_contract_address();
+        for payment in funding.payments.iter() {
+            token.transfer(&contract, &payment.funder, &payment.amount);
+        }
+        env.storage().persistent().remove(&key);
+        PartialPaymentsRefunded {
+            sub_id,
+            cycle_id,
+            amount: funding.paid,
         }
+        .publish(&env);
+        Ok(funding.paid)
+    }
 
-        approval.used = true;
-        env.storage().persistent().set(&key, &approval);
-        true
+    /// Whether `cycle_id` or a later cycle of `sub_id` was charged.
+    fn is_charged(env: &Env, sub_id: u64, cycle_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .get::<_, u64>(&CycleKey { sub_id })
+            .is_some_and(|last| cycle_id <= last)
     }
 
     // ── Renewal logic ─────────────────────────────────────────────
 
-    /// Attempt to renew the subscription.
-    /// Returns true if renewal is successful (simulated), false if it failed and retry logic was triggered.
-    /// limits: max retries allowed.
-    /// cooldown: min ledgers between retries.
+    /// Attempt to renew the subscription by pulling `amount` of the
+    /// configured token from the payer. `amount` must be the
+    /// subscription's amount; metered subscriptions ignore it and charge
+    /// their settled usage.
+    /// Returns true if renewal is successful, false if the transfer failed and retry logic was triggered.
+    /// Also returns false when a charge over a soft spending cap is parked
+    /// for the owner to confirm.
+    /// Failures are retried under the subscription's retry policy, which
+    /// gives way to the merchant's profile when it has one; the cooldown
+    /// also gives way to the subscription's backoff policy.
+    /// cycle_id: billing cycle being charged; must increase from one successful renewal to the next.
     pub fn renew(
         env: Env,
         sub_id: u64,
         approval_id: u64,
         amount: i128,
-        max_retries: u32,
-        cooldown_ledgers: u32,
         cycle_id: u64,
-        succeed: bool,
-    ) -> bool {
+    ) -> Result<bool, Error> {
         // 1. Check global pause
         if Self::is_paused(env.clone()) {
-            panic!("Protocol is paused");
+            return Err(Error::Paused);
+        }
+        Self::renew_unpaused(env, sub_id, approval_id, amount, cycle_id)
+    }
+
+    /// Renew each of `requests` in order as `renew` would, for keepers
+    /// with many subscriptions due. A request whose preconditions fail is
+    /// skipped, with the code of the error `renew` would have returned,
+    /// and the batch goes on; events announcing why, like
+    /// `ApprovalRejected`, stay published. Every lock holder must authorize
+    /// the batch. Takes at most [`MAX_RENEWAL_BATCH`] requests.
+    pub fn renew_batch(
+        env: Env,
+        requests: Vec<RenewalRequest>,
+    ) -> Result<Vec<RenewalOutcome>, Error> {
+        if requests.len() > MAX_RENEWAL_BATCH {
+            return Err(Error::BatchTooLarge);
+        }
+        if Self::is_paused(env.clone()) {
+            return Err(Error::Paused);
+        }
+
+        let mut outcomes = Vec::new(&env);
+        let (mut renewed, mut skipped) = (0, 0);
+        for request in requests.iter() {
+            let outcome = match Self::renew_unpaused(
+                env.clone(),
+                request.sub_id,
+                request.approval_id,
+                request.amount,
+                request.cycle_id,
+            ) {
+                Ok(true) => {
+                    renewed += 1;
+                    RenewalOutcome::Renewed
+                }
+                Ok(false) => RenewalOutcome::NotRenewed,
+                Err(err) => {
+                    skipped += 1;
+                    RenewalOutcome::Skipped(err as u32)
+                }
+            };
+            outcomes.push_back(outcome);
+        }
+
+        RenewalBatchProcessed {
+            requests: requests.len(),
+            renewed,
+            skipped,
         }
+        .publish(&env);
+        Ok(outcomes)
+    }
 
+    /// `renew` past the pause check. Every precondition is checked before
+    /// any state is written, here or in the metering contract, so a batch
+    /// can skip a request that fails one.
+    fn renew_unpaused(
+        env: Env,
+        sub_id: u64,
+        approval_id: u64,
+        amount: i128,
+        cycle_id: u64,
+    ) -> Result<bool, Error> {
         // Get current ledger early (needed for lock verification)
         let current_ledger = env.ledger().sequence();
 
         // 2. Load subscription data
         let key = sub_id;
-        let mut data: SubscriptionData = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .expect("Subscription not found");
+        let mut data = Self::load_sub(&env, sub_id)?;
+
+        // The merchant's profile overrides the subscription's retry policy,
+        // which is only read once there are failures to handle
+        let profile = Self::profile_of(&env, &data.merchant);
+        let retry_policy = || match &profile {
+            Some(profile) => RetryPolicy {
+                max_retries: profile.max_retries,
+                cooldown_ledgers: profile.cooldown_ledgers,
+            },
+            None => Self::retry_policy_of(&env, sub_id),
+        };
 
-        // 3. Check failed state
+        // 3. Check terminal states
         if data.state == SubscriptionState::Failed {
-            panic!("Subscription is in FAILED state");
+            return Err(Error::SubFailed);
+        }
+        if data.state == SubscriptionState::Cancelled {
+            return Err(Error::SubCancelled);
+        }
+        if data.state == SubscriptionState::Expired {
+            return Err(Error::SubExpired);
+        }
+        if data.state == SubscriptionState::Paused {
+            return Err(Error::Paused);
         }
 
         // 4. Verify renewal lock exists and is not expired
         let lock_key = RenewalLockKey {
             lock_sub_id: sub_id,
         };
-        let lock_data: Option<RenewalLockData> = env.storage().persistent().get(&lock_key);
+        let lock_data: Option<RenewalLockData> = env.storage().temporary().get(&lock_key);
         match lock_data {
-            None => panic!("Renewal lock required"),
+            None => return Err(Error::LockRequired),
             Some(ref ld) => {
-                if current_ledger >= ld.locked_at + ld.lock_timeout {
-                    panic!("Renewal lock expired");
+                if current_ledger >= ld.locked_at.saturating_add(ld.lock_timeout) {
+                    return Err(Error::LockRequired);
                 }
+                ld.holder.require_auth();
             }
         }
 
-        // 5. Cycle guard: reject duplicate renewal for the same billing cycle
+        // 5. Cycle guard: reject duplicate renewal for the same or an earlier
+        // billing cycle, so no cycle can be charged twice
+        if !Self::cycle_allowed(&env, sub_id, &data, cycle_id)? {
+            return Err(Error::CycleNotDerived);
+        }
         let cycle_key = CycleKey { sub_id };
         let last_cycle: Option<u64> = env.storage().persistent().get(&cycle_key);
         if let Some(last) = last_cycle {
-            if cycle_id == last {
+            if cycle_id <= last {
                 DuplicateRenewalRejected { sub_id, cycle_id }.publish(&env);
-                panic!("Duplicate renewal for cycle");
+                return Err(Error::DuplicateCycle);
             }
         }
 
         // 6. Check cooldown
-        if data.failure_count > 0 && current_ledger < data.last_attempt_ledger + cooldown_ledgers {
-            panic!("Cooldown period active");
-        }
-
-        // 7. Validate and consume approval
-        if !Self::consume_approval(&env, sub_id, approval_id, amount) {
-            panic!("Invalid or expired approval");
+        if data.failure_count > 0 {
+            let cooldown = Self::cooldown_after(
+                &env,
+                sub_id,
+                data.failure_count,
+                retry_policy().cooldown_ledgers,
+            );
+            if current_ledger < data.last_attempt_ledger.saturating_add(cooldown) {
+                return Err(Error::CooldownActive);
+            }
         }
 
         // 7. Validate Integrity Hash
-        let mut integrity_data = soroban_sdk::Vec::<soroban_sdk::Val>::new(&env);
-        integrity_data.push_back(data.merchant.into_val(&env));
-        integrity_data.push_back(data.amount.into_val(&env));
-        integrity_data.push_back(data.frequency.into_val(&env));
-        integrity_data.push_back(data.spending_cap.into_val(&env));
-
-        let current_hash = env.crypto().sha256(&integrity_data.to_xdr(&env));
-        let current_hash_bytes: soroban_sdk::BytesN<32> = current_hash.into();
-
-        if current_hash_bytes.as_ref() != data.integrity_hash.as_ref() {
+        if !Self::integrity_holds(&env, sub_id, &data) {
             IntegrityViolation { sub_id }.publish(&env);
-            panic!("Subscription integrity violation: parameters tampered");
+            return Err(Error::IntegrityViolation);
         }
 
-        if succeed {
-            // Capture previous state before changing it
-            let previous_state = data.state;
-
-            // Simulated success - renewal successful
-            data.state = SubscriptionState::Active;
-            data.failure_count = 0;
-            data.last_attempt_ledger = current_ledger;
-            env.storage().persistent().set(&key, &data);
-
-            // Store cycle_id on success only
-            env.storage().persistent().set(&cycle_key, &cycle_id);
+        // Without a payment strategy or a prepay contract to draw from, the
+        // charge is pulled in the configured token
+        let strategy = Self::get_payment_strategy(env.clone(), sub_id);
+        let prepay = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&ContractKey::PrepayContract);
+        if strategy.is_none()
+            && prepay.is_none()
+            && !env.storage().instance().has(&ContractKey::Token)
+        {
+            return Err(Error::PeerNotSet);
+        }
 
-            // Emit renewal success event
-            RenewalSuccess {
-                sub_id,
-                owner: data.owner.clone(),
-            }
-            .publish(&env);
+        // Indexed subscriptions step their amount up for each interval
+        // passed, and a due frequency change takes over. Both apply to this
+        // charge, but are only stored once the approval is consumed
+        let previous_amount = data.amount;
+        let previous_frequency = data.frequency;
+        let indexation = Self::apply_indexation(&env, sub_id, &mut data);
+        let frequency_change = Self::apply_frequency_change(&env, sub_id, &mut data);
 
-            // Update lifecycle timestamps
-            let lc_key = LifecycleKey {
-                lifecycle_sub_id: sub_id,
-            };
-            let mut lifecycle: LifecycleTimestamps = env
+        // Metered subscriptions charge the usage for this cycle. It is only
+        // quoted here and settled once the approval is consumed, since
+        // settling closes the period
+        let metering = if Self::is_metered(env.clone(), sub_id) {
+            let address: Address = env
                 .storage()
-                .persistent()
-                .get(&lc_key)
-                .expect("Lifecycle data not found");
-            let now = env.ledger().timestamp();
-            lifecycle.last_renewed_at = now;
+                .instance()
+                .get(&ContractKey::MeteringContract)
+                .ok_or(Error::PeerNotSet)?;
+            Some(MeteringClient::new(&env, &address))
+        } else {
+            None
+        };
+        let amount = match &metering {
+            Some(metering) => metering
+                .get_settled(&sub_id, &cycle_id)
+                .unwrap_or_else(|| metering.quote(&sub_id, &cycle_id)),
+            None if amount <= 0 || amount != data.amount => return Err(Error::InvalidAmount),
+            None => amount,
+        };
 
-            LifecycleTimestampUpdated {
+        // Charges taking the spend in the window past the spending cap are
+        // rejected, or with a soft cap parked until the owner confirms them
+        let pending_key = PendingRenewalKey {
+            pending_sub_id: sub_id,
+        };
+        let spent = Self::spend_window(&env, sub_id).spent;
+        let over_cap = spent.saturating_add(amount) > data.spending_cap;
+        if over_cap && !Self::is_soft_cap(env.clone(), sub_id) {
+            SpendingCapExceeded {
                 sub_id,
-                event_kind: 3,
-                timestamp: now,
+                cycle_id,
+                amount,
+                spent,
+                spending_cap: data.spending_cap,
             }
             .publish(&env);
-
-            // If recovering from Retrying, also update activated_at
-            if previous_state == SubscriptionState::Retrying {
-                lifecycle.activated_at = now;
-                LifecycleTimestampUpdated {
-                    sub_id,
-                    event_kind: 2,
-                    timestamp: now,
+            return Err(Error::InvalidAmount);
+        }
+        if over_cap {
+            match Self::get_pending_renewal(env.clone(), sub_id) {
+                Some(pending) if pending.cycle_id == cycle_id => {
+                    if !pending.confirmed {
+                        if current_ledger >= pending.expires_at {
+                            return Err(Error::PendingRenewalRejected);
+                        }
+                        return Err(Error::AwaitingConfirmation);
+                    }
+                    if amount > pending.amount {
+                        return Err(Error::ExceedsAmountDue);
+                    }
+                }
+                _ => {
+                    let expires_at = current_ledger.saturating_add(CONFIRMATION_LEDGERS);
+                    env.storage().persistent().set(
+                        &pending_key,
+                        &PendingRenewal {
+                            cycle_id,
+                            amount,
+                            expires_at,
+                            confirmed: false,
+                        },
+                    );
+                    RenewalPendingConfirmation {
+                        sub_id,
+                        cycle_id,
+                        amount,
+                        expires_at,
+                    }
+                    .publish(&env);
+                    Self::release_lock(&env, sub_id);
+                    return Ok(false);
                 }
-                .publish(&env);
             }
-            env.storage().persistent().set(&lc_key, &lifecycle);
+        }
 
-            // Auto-release lock
-            env.storage().persistent().remove(&lock_key);
-            RenewalLockReleased {
+        // 8. Validate and consume approval
+        let payer = Self::payer_of(&env, sub_id, &data);
+        if !Self::consume_approval(&env, &payer, sub_id, approval_id, amount) {
+            return Err(Error::ApprovalInvalid);
+        }
+        if let Some(metering) = &metering {
+            metering.settle(&sub_id, &cycle_id);
+        }
+        if let Some(schedule) = &indexation {
+            Self::store_indexation(&env, sub_id, schedule, previous_amount, data.amount);
+        }
+        if let Some(change) = frequency_change {
+            Self::store_frequency_change(&env, sub_id, previous_frequency, change);
+        }
+
+        // A payment strategy settles the charge itself. Otherwise prepaid
+        // cycles are paid from the term deposit at its discount, and any
+        // other is pulled from the payer into this contract
+        let settled = match (&strategy, prepay) {
+            (Some(strategy), _) => Some(Self::settle_with(
+                &env,
+                strategy,
                 sub_id,
-                released_at: current_ledger,
+                cycle_id,
+                &payer,
+                &data.merchant,
+                amount,
+            )),
+            (None, Some(prepay_addr)) => {
+                let drawn = PrepayClient::new(&env, &prepay_addr).draw(&sub_id, &cycle_id);
+                (drawn > 0).then_some(drawn)
             }
-            .publish(&env);
+            (None, None) => None,
+        };
+        let token = match settled {
+            Some(_) => None,
+            None => Some(Self::token_client(&env)?),
+        };
+        let paid = match (&token, settled) {
+            (Some(token), _) => Self::pull_payment(&env, token, &payer, amount),
+            (None, settled) => settled.is_some_and(|paid| paid > 0),
+        };
 
-            // Record renewal success log
-            Self::record_log(
+        if paid {
+            if over_cap {
+                env.storage().persistent().remove(&pending_key);
+            }
+            let amount = settled.unwrap_or(amount);
+            Self::complete_renewal(
                 &env,
                 sub_id,
-                2,
-                soroban_sdk::String::from_str(&env, "Renewal successful"),
-            );
-
-            true
+                data,
+                cycle_id,
+                amount,
+                &payer,
+                None,
+                token.as_ref(),
+            )?;
+            Ok(true)
         } else {
-            // Simulated failure - renewal failed, apply retry logic
+            // Payment failed - apply retry logic
             // Do NOT store cycle_id on failure — retries with same cycle_id remain allowed
-            data.failure_count += 1;
+            data.failure_count = data.failure_count.saturating_add(1);
             data.last_attempt_ledger = current_ledger;
+            let RetryPolicy {
+                max_retries,
+                cooldown_ledgers,
+            } = retry_policy();
+            let cooldown_ledgers =
+                Self::cooldown_after(&env, sub_id, data.failure_count, cooldown_ledgers);
+
+            // Determine new state based on retry count. A grace period,
+            // the merchant's or the default, counted from the first failure,
+            // defers giving up.
+            let grace_key = GraceKey {
+                grace_sub_id: sub_id,
+            };
+            if data.failure_count == 1 {
+                let grace = match &profile {
+                    Some(profile) => profile.grace_ledgers,
+                    None => Self::get_grace_period(env.clone()),
+                };
+                let ends_at = current_ledger.saturating_add(grace);
+                env.storage().persistent().set(&grace_key, &ends_at);
+                if grace > 0 {
+                    GracePeriodStarted { sub_id, ends_at }.publish(&env);
+                }
+            }
+            let grace_ends: u32 = env
+                .storage()
+                .persistent()
+                .get(&grace_key)
+                .unwrap_or(current_ledger);
+            let exhausted = data.failure_count > max_retries && current_ledger >= grace_ends;
+            let reason = if exhausted {
+                ReasonCode::RetriesExhausted
+            } else {
+                ReasonCode::PaymentFailed
+            };
+            env.storage().persistent().set(
+                &FailureKey {
+                    failure_sub_id: sub_id,
+                },
+                &reason,
+            );
+            let fallback = if exhausted {
+                Self::get_fallback_plan(env.clone(), sub_id)
+            } else {
+                None
+            };
 
-            // Emit renewal failure event
+            // Emit renewal failure event with when keepers can try again;
+            // a downgrade starts retries afresh without a cooldown
+            let (next_retry_ledger, retries_remaining) = match (fallback, exhausted) {
+                (Some(_), _) => (Some(current_ledger), max_retries),
+                (None, true) => (None, 0),
+                (None, false) => (
+                    Some(current_ledger.saturating_add(cooldown_ledgers)),
+                    max_retries.saturating_sub(data.failure_count),
+                ),
+            };
             RenewalFailed {
                 sub_id,
                 failure_count: data.failure_count,
                 ledger: current_ledger,
+                next_retry_ledger,
+                retries_remaining,
             }
             .publish(&env);
+            if let Some(fallback) = fallback {
+                // Keep the customer on the cheaper plan; the unpaid cycle
+                // can be retried at the new amount
+                let previous_amount = data.amount;
+                data.amount = fallback;
+                data.integrity_hash = Self::integrity_hash(
+                    &env,
+                    &data.merchant,
+                    data.amount,
+                    data.frequency,
+                    data.spending_cap,
+                    Self::get_indexation(env.clone(), sub_id),
+                );
+                data.failure_count = 0;
+                data.state = SubscriptionState::Active;
+                Self::journal_state(&env, sub_id, SubscriptionState::Active);
+                env.storage().persistent().remove(&FallbackKey {
+                    fallback_sub_id: sub_id,
+                });
+                env.storage().persistent().remove(&grace_key);
+                DowngradedOnFailure {
+                    sub_id,
+                    previous_amount,
+                    amount: fallback,
+                }
+                .publish(&env);
+                StateTransition {
+                    sub_id,
+                    new_state: SubscriptionState::Active,
+                }
+                .publish(&env);
 
-            // Determine new state based on retry count
-            if data.failure_count > max_retries {
+                Self::record_log(
+                    &env,
+                    sub_id,
+                    LogEvent::Retry,
+                    soroban_sdk::String::from_str(
+                        &env,
+                        "Renewal failed - downgraded to fallback plan",
+                    ),
+                );
+                Self::schedule(
+                    &env,
+                    sub_id,
+                    Some(current_ledger.saturating_add(cooldown_ledgers)),
+                );
+            } else if exhausted {
                 data.state = SubscriptionState::Failed;
+                Self::journal_state(&env, sub_id, SubscriptionState::Failed);
                 StateTransition {
                     sub_id,
                     new_state: SubscriptionState::Failed,
@@ -715,11 +5666,14 @@ impl SubscriptionRenewalContract {
                 Self::record_log(
                     &env,
                     sub_id,
-                    3,
+                    LogEvent::Failure,
                     soroban_sdk::String::from_str(&env, "Renewal failed - max retries exceeded"),
                 );
+                Self::schedule(&env, sub_id, None);
+                env.storage().persistent().remove(&grace_key);
             } else {
                 data.state = SubscriptionState::Retrying;
+                Self::journal_state(&env, sub_id, SubscriptionState::Retrying);
                 StateTransition {
                     sub_id,
                     new_state: SubscriptionState::Retrying,
@@ -730,42 +5684,468 @@ impl SubscriptionRenewalContract {
                 Self::record_log(
                     &env,
                     sub_id,
-                    4,
+                    LogEvent::Retry,
                     soroban_sdk::String::from_str(&env, "Renewal failed - scheduled for retry"),
                 );
+                let retry_at = current_ledger.saturating_add(cooldown_ledgers);
+                env.storage().persistent().set(
+                    &RetryAtKey {
+                        retry_sub_id: sub_id,
+                    },
+                    &retry_at,
+                );
+                Self::schedule(&env, sub_id, Some(retry_at));
             }
 
             env.storage().persistent().set(&key, &data);
 
             // Auto-release lock
-            env.storage().persistent().remove(&lock_key);
-            RenewalLockReleased {
+            Self::release_lock(&env, sub_id);
+            Self::notify(
+                &env,
+                "renew_failed",
                 sub_id,
-                released_at: current_ledger,
-            }
-            .publish(&env);
+                &data.merchant,
+                cycle_id,
+                amount,
+            );
+            Self::sync_registry(&env, sub_id, false);
 
-            false
+            Ok(false)
         }
     }
 
-    pub fn get_sub(env: Env, sub_id: u64) -> SubscriptionData {
+    // ── Views and privacy ─────────────────────────────────────────
+
+    /// Turn privacy mode on or off. Admin only. While on, subscription
+    /// details are only served through the `_as` views to the owner, the
+    /// merchant or an agent holding the Audit scope; everyone else gets
+    /// `get_status`.
+    pub fn set_privacy_mode(env: Env, enabled: bool) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&ContractKey::PrivacyMode, &enabled);
+        Ok(())
+    }
+
+    pub fn is_privacy_mode(env: Env) -> bool {
         env.storage()
+            .instance()
+            .get(&ContractKey::PrivacyMode)
+            .unwrap_or(false)
+    }
+
+    /// Whether `viewer` may read `sub_id`'s details. Always true outside
+    /// privacy mode. Does not check `viewer`'s authorization.
+    pub fn can_view(env: Env, viewer: Address, sub_id: u64) -> Result<bool, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        Ok(Self::may_view(&env, &viewer, &data))
+    }
+
+    /// Why the latest renewal attempt on `sub_id` failed; `None` once a
+    /// renewal succeeds.
+    pub fn get_last_failure(env: Env, sub_id: u64) -> Option<ReasonCode> {
+        env.storage().persistent().get(&FailureKey {
+            failure_sub_id: sub_id,
+        })
+    }
+
+    /// State of `sub_id`, readable by anyone in every mode.
+    pub fn get_status(env: Env, sub_id: u64) -> Result<SubscriptionState, Error> {
+        Ok(Self::load_sub(&env, sub_id)?.state)
+    }
+
+    pub fn get_sub(env: Env, sub_id: u64) -> Result<SubscriptionData, Error> {
+        Self::require_public(&env)?;
+        Self::load_sub(&env, sub_id)
+    }
+
+    pub fn get_lifecycle(env: Env, sub_id: u64) -> Result<LifecycleTimestamps, Error> {
+        Self::require_public(&env)?;
+        Self::load_lifecycle(&env, sub_id)
+    }
+
+    /// The state `sub_id` was in at the end of `ledger`, from its journal
+    /// of state changes; `None` before it was created.
+    pub fn get_state_at(
+        env: Env,
+        sub_id: u64,
+        ledger: u32,
+    ) -> Result<Option<SubscriptionState>, Error> {
+        Self::require_public(&env)?;
+        Ok(Self::state_journal(&env, sub_id)
+            .iter()
+            .rev()
+            .find(|change| change.ledger <= ledger)
+            .map(|change| change.state))
+    }
+
+    /// Every state `sub_id` has entered and when, oldest first.
+    pub fn get_state_journal(env: Env, sub_id: u64) -> Result<Vec<StateChange>, Error> {
+        Self::require_public(&env)?;
+        Ok(Self::state_journal(&env, sub_id))
+    }
+
+    fn state_journal(env: &Env, sub_id: u64) -> Vec<StateChange> {
+        env.storage()
+            .persistent()
+            .get(&JournalKey {
+                journal_sub_id: sub_id,
+            })
+            .unwrap_or(Vec::new(env))
+    }
+
+    /// Append `state` to `sub_id`'s journal unless it is already in it.
+    fn journal_state(env: &Env, sub_id: u64, state: SubscriptionState) {
+        let mut journal = Self::state_journal(env, sub_id);
+        if journal.last().is_some_and(|change| change.state == state) {
+            return;
+        }
+        journal.push_back(StateChange {
+            state,
+            ledger: env.ledger().sequence(),
+        });
+        env.storage().persistent().set(
+            &JournalKey {
+                journal_sub_id: sub_id,
+            },
+            &journal,
+        );
+    }
+
+    /// `get_sub` for `viewer`, who must authorize the read while privacy
+    /// mode is on.
+    pub fn get_sub_as(env: Env, viewer: Address, sub_id: u64) -> Result<SubscriptionData, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        Self::require_viewer(&env, &viewer, &data)?;
+        Ok(data)
+    }
+
+    /// `get_lifecycle` for `viewer`, who must authorize the read while
+    /// privacy mode is on.
+    pub fn get_lifecycle_as(
+        env: Env,
+        viewer: Address,
+        sub_id: u64,
+    ) -> Result<LifecycleTimestamps, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        Self::require_viewer(&env, &viewer, &data)?;
+        Self::load_lifecycle(&env, sub_id)
+    }
+
+    /// Approval `approval_id` of `sub_id` for `viewer`, who must authorize
+    /// the read while privacy mode is on.
+    pub fn get_approval(
+        env: Env,
+        viewer: Address,
+        sub_id: u64,
+        approval_id: u64,
+    ) -> Result<Option<RenewalApproval>, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        Self::require_viewer(&env, &viewer, &data)?;
+        Ok(env.storage().persistent().get(&ApprovalKey {
+            sub_id,
+            approval_id,
+        }))
+    }
+
+    fn load_sub(env: &Env, sub_id: u64) -> Result<SubscriptionData, Error> {
+        let data = env
+            .storage()
             .persistent()
             .get(&sub_id)
-            .expect("Subscription not found")
+            .ok_or(Error::SubNotFound)?;
+        keep_alive(env, &sub_id);
+        Ok(data)
     }
 
-    pub fn get_lifecycle(env: Env, sub_id: u64) -> LifecycleTimestamps {
+    fn load_lifecycle(env: &Env, sub_id: u64) -> Result<LifecycleTimestamps, Error> {
         let lc_key = LifecycleKey {
             lifecycle_sub_id: sub_id,
         };
-        env.storage()
+        let lifecycle = env
+            .storage()
             .persistent()
             .get(&lc_key)
-            .expect("Lifecycle data not found")
+            .ok_or(Error::SubNotFound)?;
+        keep_alive(env, &lc_key);
+        Ok(lifecycle)
+    }
+
+    /// Whether `registry` grants `agent` `scope`. A grant is cached for
+    /// [`AGENT_CACHE_LEDGERS`] ledgers to spare busy agents the
+    /// cross-contract call on every lock; refusals are never cached.
+    fn registry_grants(env: &Env, registry: &Address, agent: &Address, scope: Scope) -> bool {
+        let key = AgentScopeKey {
+            cached_agent: agent.clone(),
+            cached_scope: scope,
+        };
+        let current_ledger = env.ledger().sequence();
+        if env
+            .storage()
+            .temporary()
+            .get::<_, u32>(&key)
+            .is_some_and(|valid_until| current_ledger < valid_until)
+        {
+            return true;
+        }
+        if !AgentRegistryClient::new(env, registry).has_scope(agent, &scope) {
+            return false;
+        }
+        env.storage()
+            .temporary()
+            .set(&key, &current_ledger.saturating_add(AGENT_CACHE_LEDGERS));
+        env.storage()
+            .temporary()
+            .extend_ttl(&key, AGENT_CACHE_LEDGERS, AGENT_CACHE_LEDGERS);
+        true
+    }
+
+    /// Drop the cached registry grants of `agent`, so its next lock, audit
+    /// read or delegated approval asks the registry again. The registry
+    /// calls this on revocations and scope changes once given this
+    /// contract with `set_renewal_contract`. Anyone else may too; at worst
+    /// it costs the agent one more registry call.
+    pub fn invalidate_agent_cache(env: Env, agent: Address) {
+        for scope in [Scope::Renewals, Scope::Audit, Scope::Approvals] {
+            env.storage().temporary().remove(&AgentScopeKey {
+                cached_agent: agent.clone(),
+                cached_scope: scope,
+            });
+        }
+        AgentCacheInvalidated { agent }.publish(&env);
+    }
+
+    fn require_public(env: &Env) -> Result<(), Error> {
+        if Self::is_privacy_mode(env.clone()) {
+            return Err(Error::Private);
+        }
+        Ok(())
+    }
+
+    fn may_view(env: &Env, viewer: &Address, data: &SubscriptionData) -> bool {
+        if !Self::is_privacy_mode(env.clone()) || *viewer == data.owner || *viewer == data.merchant
+        {
+            return true;
+        }
+        match Self::peer(env, &ContractKey::AgentRegistry, AGENTS) {
+            Some(registry) => Self::registry_grants(env, &registry, viewer, Scope::Audit),
+            None => false,
+        }
+    }
+
+    fn require_viewer(env: &Env, viewer: &Address, data: &SubscriptionData) -> Result<(), Error> {
+        if !Self::is_privacy_mode(env.clone()) {
+            return Ok(());
+        }
+        viewer.require_auth();
+        if !Self::may_view(env, viewer, data) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    // ── Monitoring ────────────────────────────────────────────────
+
+    /// Every protocol rule `sub_id` currently breaks; empty when healthy.
+    /// Lets monitors verify stored state without reimplementing the rules.
+    pub fn check_invariants(env: Env, sub_id: u64) -> Result<Vec<InvariantViolation>, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        let mut violations = Vec::new(&env);
+        let terminal = matches!(
+            data.state,
+            SubscriptionState::Failed | SubscriptionState::Cancelled | SubscriptionState::Expired
+        );
+
+        let lifecycle: Option<LifecycleTimestamps> =
+            env.storage().persistent().get(&LifecycleKey {
+                lifecycle_sub_id: sub_id,
+            });
+        match lifecycle {
+            None => violations.push_back(InvariantViolation::LifecycleMissing),
+            Some(lc) => {
+                let cancelled = data.state == SubscriptionState::Cancelled;
+                if cancelled && lc.canceled_at == 0 {
+                    violations.push_back(InvariantViolation::CancelledWithoutTimestamp);
+                }
+                if !cancelled && lc.canceled_at != 0 {
+                    violations.push_back(InvariantViolation::TimestampWithoutCancel);
+                }
+                let before_creation = |t: u64| t != 0 && t < lc.created_at;
+                if lc.activated_at < lc.created_at
+                    || before_creation(lc.last_renewed_at)
+                    || before_creation(lc.canceled_at)
+                    || before_creation(lc.paused_at)
+                    || before_creation(lc.resumed_at)
+                {
+                    violations.push_back(InvariantViolation::LifecycleOutOfOrder);
+                }
+            }
+        }
+
+        match data.state {
+            SubscriptionState::Failed | SubscriptionState::Retrying if data.failure_count == 0 => {
+                violations.push_back(InvariantViolation::FailuresNotCounted);
+            }
+            SubscriptionState::Active if data.failure_count != 0 => {
+                violations.push_back(InvariantViolation::ActiveWithFailures);
+            }
+            _ => {}
+        }
+
+        if terminal && Self::get_renewal_lock(env.clone(), sub_id).is_some() {
+            violations.push_back(InvariantViolation::LockOnTerminalSub);
+        }
+        if !Self::integrity_holds(&env, sub_id, &data) {
+            violations.push_back(InvariantViolation::IntegrityMismatch);
+        }
+        Ok(violations)
+    }
+
+    /// Every way the registry entry `sub_id` is linked to disagrees with
+    /// it on amount, interval or status; empty when they agree or when no
+    /// entry is linked.
+    pub fn check_link_consistency(env: Env, sub_id: u64) -> Result<Vec<LinkDiscrepancy>, Error> {
+        let data = Self::load_sub(&env, sub_id)?;
+        let mut discrepancies = Vec::new(&env);
+        let Some(entry) = Self::get_registry_entry(env.clone(), sub_id) else {
+            return Ok(discrepancies);
+        };
+        let registry = Self::peer(&env, &ContractKey::SubscriptionRegistry, REGISTRY)
+            .ok_or(Error::PeerNotSet)?;
+        let Some(metadata) =
+            SubscriptionRegistryClient::new(&env, &registry).get_subscription(&entry)
+        else {
+            discrepancies.push_back(LinkDiscrepancy::EntryMissing);
+            return Ok(discrepancies);
+        };
+
+        if metadata.expected_amount != data.amount {
+            discrepancies.push_back(LinkDiscrepancy::AmountMismatch);
+        }
+        if metadata.billing_interval != data.frequency {
+            discrepancies.push_back(LinkDiscrepancy::IntervalMismatch);
+        }
+        let terminal = matches!(
+            data.state,
+            SubscriptionState::Failed | SubscriptionState::Cancelled | SubscriptionState::Expired
+        );
+        if metadata.is_active == terminal {
+            discrepancies.push_back(LinkDiscrepancy::StatusMismatch);
+        }
+        Ok(discrepancies)
+    }
+
+    fn integrity_hash(
+        env: &Env,
+        merchant: &Address,
+        amount: i128,
+        frequency: u64,
+        spending_cap: i128,
+        indexation: Option<Indexation>,
+    ) -> BytesN<32> {
+        let mut integrity_data = Vec::<Val>::new(env);
+        integrity_data.push_back(merchant.into_val(env));
+        integrity_data.push_back(amount.into_val(env));
+        integrity_data.push_back(frequency.into_val(env));
+        integrity_data.push_back(spending_cap.into_val(env));
+        // Only indexed subscriptions hash a clause, so existing hashes hold
+        if let Some(indexation) = indexation {
+            integrity_data.push_back(indexation.into_val(env));
+        }
+        env.crypto().sha256(&integrity_data.to_xdr(env)).into()
+    }
+
+    fn integrity_holds(env: &Env, sub_id: u64, data: &SubscriptionData) -> bool {
+        Self::integrity_hash(
+            env,
+            &data.merchant,
+            data.amount,
+            data.frequency,
+            data.spending_cap,
+            Self::get_indexation(env.clone(), sub_id),
+        ) == data.integrity_hash
+    }
+
+    /// Raise `data.amount` by every indexation step due by now, rounding
+    /// down and stopping at the spending cap, and rehash it. Returns the
+    /// advanced schedule, to store with `store_indexation` once the
+    /// renewal goes ahead.
+    fn apply_indexation(
+        env: &Env,
+        sub_id: u64,
+        data: &mut SubscriptionData,
+    ) -> Option<IndexationSchedule> {
+        let mut schedule =
+            env.storage()
+                .persistent()
+                .get::<_, IndexationSchedule>(&IndexationKey {
+                    indexation_sub_id: sub_id,
+                })?;
+        let now = env.ledger().timestamp();
+        if now < schedule.next_at {
+            return None;
+        }
+        let interval = schedule.terms.interval;
+        let steps = ((now - schedule.next_at) / interval).saturating_add(1);
+        schedule.next_at = schedule
+            .next_at
+            .saturating_add(steps.saturating_mul(interval));
+
+        let previous_amount = data.amount;
+        let rate_bps = schedule.terms.rate_bps as i128;
+        for _ in 0..steps {
+            let next = data
+                .amount
+                .saturating_add(portion(data.amount, rate_bps, 10_000))
+                .min(data.spending_cap);
+            if next <= data.amount {
+                break;
+            }
+            data.amount = next;
+        }
+        if data.amount != previous_amount {
+            data.integrity_hash = Self::integrity_hash(
+                env,
+                &data.merchant,
+                data.amount,
+                data.frequency,
+                data.spending_cap,
+                Some(schedule.terms.clone()),
+            );
+        }
+        Some(schedule)
+    }
+
+    /// Store `schedule` and announce the step from `previous_amount` to
+    /// `amount`, if there was one.
+    fn store_indexation(
+        env: &Env,
+        sub_id: u64,
+        schedule: &IndexationSchedule,
+        previous_amount: i128,
+        amount: i128,
+    ) {
+        env.storage().persistent().set(
+            &IndexationKey {
+                indexation_sub_id: sub_id,
+            },
+            schedule,
+        );
+        if amount != previous_amount {
+            AmountIndexed {
+                sub_id,
+                previous_amount,
+                amount,
+            }
+            .publish(env);
+        }
     }
 }
 
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils;
+
 #[cfg(test)]
 mod test;
@@ -1,8 +1,10 @@
 use super::*;
 use soroban_sdk::{
-    testutils::{Address as _, Ledger},
-    Address, Env,
+    contract, contractimpl,
+    testutils::{Address as _, Events, Ledger},
+    Address, Env, IntoVal, Map, MuxedAddress, Symbol, Val,
 };
+use syncro_common::events::EVENT_SCHEMA_VERSION;
 
 /// Helper: creates env, registers contract, initializes admin, returns (client, admin).
 fn setup() -> (Env, SubscriptionRenewalContractClient<'static>, Address) {
@@ -14,10 +16,135 @@ fn setup() -> (Env, SubscriptionRenewalContractClient<'static>, Address) {
 
     let admin = Address::generate(&env);
     client.init(&admin);
+    client.set_token(&env.register(MockToken, ()));
+    // Most tests name their cycles freely; derived ids have tests of their own
+    client.set_derived_cycle_ids(&admin, &false);
 
     (env, client, admin)
 }
 
+/// Helper: acquires the renewal lock for `sub_id` on behalf of a fresh agent.
+fn acquire_lock(
+    env: &Env,
+    client: &SubscriptionRenewalContractClient,
+    sub_id: u64,
+    lock_timeout: u32,
+) -> Address {
+    let agent = Address::generate(env);
+    client.acquire_renewal_lock(&agent, &sub_id, &lock_timeout);
+    agent
+}
+
+/// Token whose transfers all go through, except pulls during the one
+/// ledger it is set failing at.
+#[contract]
+struct MockToken;
+
+#[contractimpl]
+impl MockToken {
+    pub fn set_failing_at(env: Env, ledger: u32) {
+        env.storage().instance().set(&0u32, &ledger);
+    }
+
+    pub fn transfer_from(env: Env, _spender: Address, _from: Address, _to: Address, _amount: i128) {
+        if env.storage().instance().get(&0u32) == Some(env.ledger().sequence()) {
+            panic!("insufficient allowance");
+        }
+    }
+
+    pub fn transfer(_env: Env, _from: Address, _to: MuxedAddress, _amount: i128) {}
+}
+
+/// Helper: renews with the payer's transfer failing, as it does when their
+/// balance or allowance falls short. Later pulls in the same ledger fail
+/// too.
+fn renew_unpaid(
+    env: &Env,
+    client: &SubscriptionRenewalContractClient,
+    sub_id: &u64,
+    approval_id: &u64,
+    amount: &i128,
+    cycle_id: &u64,
+) -> bool {
+    MockTokenClient::new(env, &client.get_token().unwrap())
+        .set_failing_at(&env.ledger().sequence());
+    client.renew(sub_id, approval_id, amount, cycle_id)
+}
+
+#[test]
+fn test_init_announces_event_schema() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = SubscriptionRenewalContractClient::new(
+        &env,
+        &env.register(SubscriptionRenewalContract, ()),
+    );
+    client.init(&Address::generate(&env));
+    let (contract, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(contract, client.address);
+    assert_eq!(
+        topics,
+        soroban_sdk::vec![&env, Symbol::new(&env, "event_schema").into_val(&env)]
+    );
+    let data: Map<Symbol, u32> = data.into_val(&env);
+    assert_eq!(
+        data.get(Symbol::new(&env, "version")),
+        Some(EVENT_SCHEMA_VERSION)
+    );
+    assert_eq!(client.get_event_version(), EVENT_SCHEMA_VERSION);
+}
+
+#[test]
+fn test_init_records_code_version() {
+    let (_env, client, _) = setup();
+    assert_eq!(client.get_code_version(), CODE_VERSION);
+}
+
+#[test]
+fn test_migrate_past_code_version_fails() {
+    let (_env, client, _) = setup();
+    assert_eq!(
+        client.try_migrate(&CODE_VERSION, &(CODE_VERSION + 1)),
+        Err(Ok(Error::VersionMismatch))
+    );
+}
+
+fn double_amount(env: &Env, sub_id: u64) {
+    let mut data: SubscriptionData = env.storage().persistent().get(&sub_id).unwrap();
+    data.amount *= 2;
+    env.storage().persistent().set(&sub_id, &data);
+}
+
+#[test]
+fn test_migrate_subs_steps_older_entries_forward() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &1);
+    assert_eq!(client.get_sub_version(&1), CODE_VERSION);
+
+    // Nothing to do while storage is at the code's own version.
+    assert_eq!(client.migrate_subs(&soroban_sdk::vec![&env, 1, 99]), 0);
+
+    env.as_contract(&client.address, || {
+        syncro_upgrade::init_version(&env, CODE_VERSION + 1);
+        let ids = soroban_sdk::vec![&env, 1, 99];
+        let steps: &[SubMigration] = &[double_amount];
+        assert_eq!(
+            SubscriptionRenewalContract::migrate_entries(&env, &ids, steps),
+            1
+        );
+        // A second pass finds the entry current.
+        assert_eq!(
+            SubscriptionRenewalContract::migrate_entries(&env, &ids, steps),
+            0
+        );
+    });
+    assert_eq!(client.get_sub(&1).amount, 1000);
+    assert_eq!(client.get_sub_version(&1), CODE_VERSION + 1);
+    assert_eq!(client.get_sub_version(&99), 1);
+}
+
 // ── Pause feature tests ──────────────────────────────────────────
 
 #[test]
@@ -28,27 +155,52 @@ fn test_default_not_paused() {
 
 #[test]
 fn test_admin_can_pause() {
-    let (_env, client, _admin) = setup();
+    let (_env, client, admin) = setup();
 
-    client.set_paused(&true);
+    client.set_paused(&admin, &true, &None);
     assert!(client.is_paused());
 }
 
 #[test]
 fn test_admin_can_unpause() {
-    let (_env, client, _admin) = setup();
+    let (_env, client, admin) = setup();
 
-    client.set_paused(&true);
+    client.set_paused(&admin, &true, &None);
     assert!(client.is_paused());
 
-    client.set_paused(&false);
+    client.set_paused(&admin, &false, &None);
     assert!(!client.is_paused());
 }
 
 #[test]
-#[should_panic(expected = "Protocol is paused")]
+fn test_pause_records_who_when_and_why() {
+    let (env, client, admin) = setup();
+    assert_eq!(client.get_pause_info(), None);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    client.set_paused(&admin, &true, &Some(42));
+    let info = client.get_pause_info().unwrap();
+    assert_eq!(info.by, admin);
+    assert_eq!(info.at, 1_000);
+    assert_eq!(info.reason, Some(42));
+
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    client.set_paused(&admin, &false, &None);
+    assert_eq!(client.get_pause_info(), None);
+
+    let history = client.get_pause_history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap(), info);
+    let resumed = history.get(1).unwrap();
+    assert_eq!(
+        (resumed.paused, resumed.at, resumed.reason),
+        (false, 2_000, None)
+    );
+}
+
+#[test]
 fn test_renew_blocked_when_paused() {
-    let (env, client, _admin) = setup();
+    let (env, client, admin) = setup();
 
     let user = Address::generate(&env);
     let sub_id = 100;
@@ -56,16 +208,19 @@ fn test_renew_blocked_when_paused() {
     let merchant = Address::generate(&env);
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
     client.approve_renewal(&sub_id, &1, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.set_paused(&true);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.set_paused(&admin, &true, &None);
 
-    // Should panic because the protocol is paused
-    client.renew(&sub_id, &1, &500, &3, &10, &20260101, &true);
+    // Should fail because the protocol is paused
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &500, &20260101),
+        Err(Ok(Error::Paused))
+    );
 }
 
 #[test]
 fn test_renew_works_after_unpause() {
-    let (env, client, _admin) = setup();
+    let (env, client, admin) = setup();
 
     let user = Address::generate(&env);
     let sub_id = 101;
@@ -75,21 +230,132 @@ fn test_renew_works_after_unpause() {
     client.approve_renewal(&sub_id, &1, &1000, &100);
 
     // Pause then unpause
-    client.set_paused(&true);
-    client.set_paused(&false);
+    client.set_paused(&admin, &true, &None);
+    client.set_paused(&admin, &false, &None);
 
     // Should succeed now
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &1, &500, &3, &10, &20260101, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &1, &500, &20260101);
     assert!(result);
 }
 
 #[test]
-#[should_panic(expected = "Already initialized")]
 fn test_cannot_init_twice() {
     let (env, client, _admin) = setup();
     let another = Address::generate(&env);
-    client.init(&another);
+    assert_eq!(
+        client.try_init(&another),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_admin_can_be_transferred() {
+    let (env, client, admin) = setup();
+    assert_eq!(client.get_admin(), admin);
+
+    let new_admin = Address::generate(&env);
+    client.set_admin(&new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+
+    // New admin retains control of admin-only entrypoints
+    client.set_paused(&new_admin, &true, &None);
+    assert!(client.is_paused());
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let (env, client, admin) = setup();
+    let new_admin = Address::generate(&env);
+
+    client.propose_admin(&new_admin);
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+
+    client.accept_admin();
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+}
+
+#[test]
+fn test_accept_admin_requires_nomination() {
+    let (_env, client, _admin) = setup();
+    assert_eq!(client.try_accept_admin(), Err(Ok(Error::NoPendingAdmin)));
+}
+
+// ── Role tests ───────────────────────────────────────────────────
+
+#[test]
+fn test_roles_delegate_admin_powers() {
+    let (env, client, _admin) = setup();
+    let pauser = Address::generate(&env);
+    let treasurer = Address::generate(&env);
+
+    // Without a role, nobody but the admin can pause.
+    assert_eq!(
+        client.try_set_paused(&pauser, &true, &None),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    assert_eq!(client.grant_role(&pauser, &PAUSER), PAUSER);
+    assert_eq!(
+        client.grant_role(&treasurer, &(TREASURER | CONFIG_MANAGER)),
+        TREASURER | CONFIG_MANAGER
+    );
+    assert_eq!(
+        client.get_role_holders(),
+        soroban_sdk::vec![&env, pauser.clone(), treasurer.clone()]
+    );
+
+    client.set_paused(&pauser, &true, &Some(7));
+    assert_eq!(client.get_pause_info().unwrap().by, pauser);
+    client.set_protocol_fee(&treasurer, &150);
+    client.set_grace_period(&treasurer, &30);
+
+    // Each role only opens its own entrypoints.
+    assert_eq!(
+        client.try_set_protocol_fee(&pauser, &0),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_paused(&treasurer, &false, &None),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    assert_eq!(client.revoke_role(&treasurer, &TREASURER), CONFIG_MANAGER);
+    assert_eq!(
+        client.try_set_protocol_fee(&treasurer, &0),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.revoke_role(&pauser, &PAUSER), 0);
+    assert_eq!(client.get_roles(&pauser), 0);
+    assert_eq!(
+        client.get_role_holders(),
+        soroban_sdk::vec![&env, treasurer.clone()]
+    );
+
+    // Revoking a role the account doesn't hold changes nothing
+    assert_eq!(client.revoke_role(&pauser, &PAUSER), 0);
+    let role_revoked = Symbol::new(&env, "role_revoked");
+    assert!(!env.events().all().iter().any(|(_, topics, _)| {
+        let topic: Symbol = topics.first().unwrap().into_val(&env);
+        topic == role_revoked
+    }));
+}
+
+#[test]
+fn test_grant_role_rejects_unknown_bits() {
+    let (env, client, _admin) = setup();
+    let account = Address::generate(&env);
+    assert_eq!(
+        client.try_grant_role(&account, &0),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_grant_role(&account, &(1 << 5)),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert!(client.get_role_holders().is_empty());
 }
 
 // ── Original tests (updated to use setup helper) ─────────────────
@@ -105,8 +371,8 @@ fn test_renewal_success() {
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
     client.approve_renewal(&sub_id, &1, &1000, &100);
 
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &1, &500, &3, &10, &20260115, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &1, &500, &20260115);
     assert!(result);
 
     let data = client.get_sub(&sub_id);
@@ -120,24 +386,22 @@ fn test_retry_logic() {
 
     let user = Address::generate(&env);
     let sub_id = 456;
-    let max_retries = 2;
-    let cooldown = 10;
 
     let merchant = Address::generate(&env);
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 2,
+            cooldown_ledgers: 10,
+        },
+    );
 
     // First failure (cycle_id same for retries — allowed because failure doesn't store cycle)
     client.approve_renewal(&sub_id, &1, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(
-        &sub_id,
-        &1,
-        &500,
-        &max_retries,
-        &cooldown,
-        &20260201,
-        &false,
-    );
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = renew_unpaid(&env, &client, &sub_id, &1, &500, &20260201);
     assert!(!result);
 
     let data = client.get_sub(&sub_id);
@@ -151,16 +415,8 @@ fn test_retry_logic() {
 
     // renewal attempt but fail again (ledger 100)
     client.approve_renewal(&sub_id, &2, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(
-        &sub_id,
-        &2,
-        &500,
-        &max_retries,
-        &cooldown,
-        &20260201,
-        &false,
-    );
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &2, &500, &20260201);
 
     // Advance past cooldown
     env.ledger().with_mut(|li| {
@@ -169,24 +425,89 @@ fn test_retry_logic() {
 
     // Third failure (count becomes 3 > max_retries 2) -> Should fail
     client.approve_renewal(&sub_id, &3, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(
-        &sub_id,
-        &3,
-        &500,
-        &max_retries,
-        &cooldown,
-        &20260201,
-        &false,
-    );
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &3, &500, &20260201);
 
     let data = client.get_sub(&sub_id);
     assert_eq!(data.state, SubscriptionState::Failed);
     assert_eq!(data.failure_count, 3);
 }
 
+/// Data of the latest event published under `name`.
+fn last_event(env: &Env, name: &str) -> Map<Symbol, Val> {
+    let name = Symbol::new(env, name);
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(_, topics, _)| {
+            let topic: Symbol = topics.first().unwrap().into_val(env);
+            topic == name
+        })
+        .last()
+        .unwrap();
+    data.into_val(env)
+}
+
+#[test]
+fn test_renewal_failed_hints_next_retry() {
+    let (env, client, _admin) = setup();
+    let sub_id = 1740;
+    let merchant = Address::generate(&env);
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 2,
+            cooldown_ledgers: 10,
+        },
+    );
+    testutils::advance_to(&env, 50);
+    client.approve_renewal(&sub_id, &1, &1000, &1000);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &1);
+
+    let data = last_event(&env, "renewal_failed");
+    let next: Option<u32> = data
+        .get(Symbol::new(&env, "next_retry_ledger"))
+        .unwrap()
+        .into_val(&env);
+    let remaining: u32 = data
+        .get(Symbol::new(&env, "retries_remaining"))
+        .unwrap()
+        .into_val(&env);
+    assert_eq!(next, Some(60));
+    assert_eq!(remaining, 1);
+
+    // Retrying at the hinted ledger clears the cooldown
+    testutils::advance_to(&env, 60);
+    client.approve_renewal(&sub_id, &2, &1000, &1000);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &2, &500, &1);
+    client.approve_renewal(&sub_id, &3, &1000, &1000);
+    testutils::advance_to(&env, 70);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &3, &500, &1);
+
+    // No retry is coming once the subscription has failed
+    let data = last_event(&env, "renewal_failed");
+    let next: Option<u32> = data
+        .get(Symbol::new(&env, "next_retry_ledger"))
+        .unwrap()
+        .into_val(&env);
+    assert_eq!(next, None);
+    assert_eq!(client.get_sub(&sub_id).state, SubscriptionState::Failed);
+}
+
 #[test]
-#[should_panic(expected = "Cooldown period active")]
 fn test_cooldown_enforcement() {
     let (env, client, _admin) = setup();
 
@@ -198,13 +519,107 @@ fn test_cooldown_enforcement() {
 
     // Fail once
     client.approve_renewal(&sub_id, &1, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &1, &500, &3, &10, &20260301, &false);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &20260301);
 
     // Try again immediately (cooldown not met)
     client.approve_renewal(&sub_id, &2, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &2, &500, &3, &10, &20260301, &false);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &2, &500, &20260301),
+        Err(Ok(Error::CooldownActive))
+    );
+}
+
+#[test]
+fn test_retry_policy_fixed_at_init() {
+    let (env, client, admin) = setup();
+    let merchant = Address::generate(&env);
+    client.init_sub(&Address::generate(&env), &merchant, &500, &86400, &1000, &1);
+    let default = RetryPolicy {
+        max_retries: DEFAULT_MAX_RETRIES,
+        cooldown_ledgers: DEFAULT_COOLDOWN_LEDGERS,
+    };
+    assert_eq!(client.get_retry_policy(&1), default);
+
+    // A new default applies to subscriptions created after it
+    let strict = RetryPolicy {
+        max_retries: 0,
+        cooldown_ledgers: 5,
+    };
+    client.set_default_retry_policy(&admin, &strict);
+    client.init_sub(&Address::generate(&env), &merchant, &500, &86400, &1000, &2);
+    assert_eq!(client.get_retry_policy(&1), default);
+    assert_eq!(client.get_retry_policy(&2), strict);
+
+    client.approve_renewal(&2, &1, &1000, &100);
+    acquire_lock(&env, &client, 2, 200);
+    renew_unpaid(&env, &client, &2, &1, &500, &1);
+    assert_eq!(client.get_status(&2), SubscriptionState::Failed);
+
+    assert!(client
+        .try_set_retry_policy(&Address::generate(&env), &1, &strict)
+        .is_err());
+    client.set_retry_policy(&merchant, &1, &strict);
+    assert_eq!(client.get_retry_policy(&1), strict);
+}
+
+#[test]
+fn test_backoff_policy_lengthens_cooldown_per_failure() {
+    let (env, client, _admin) = setup();
+
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let sub_id = 790;
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 5,
+            cooldown_ledgers: 5,
+        },
+    );
+    assert_eq!(
+        client.try_set_backoff_policy(
+            &merchant,
+            &sub_id,
+            &Some(BackoffPolicy {
+                base_ledgers: 10,
+                multiplier: 0,
+                max_ledgers: 50,
+            })
+        ),
+        Err(Ok(Error::InvalidConfig))
+    );
+    let policy = BackoffPolicy {
+        base_ledgers: 10,
+        multiplier: 3,
+        max_ledgers: 50,
+    };
+    client.set_backoff_policy(&merchant, &sub_id, &Some(policy.clone()));
+    assert_eq!(client.get_backoff_policy(&sub_id), Some(policy));
+    assert_eq!(client.get_next_retry_ledger(&sub_id), None);
+
+    // 10, then 30, then capped at 50 ledgers; the retry policy's cooldown
+    // of 5 gives way to the backoff policy
+    testutils::advance_to(&env, 100);
+    client.approve_renewal(&sub_id, &1, &1000, &1000);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &1);
+    for (approval_id, retry_at) in [(2, 110), (3, 140), (4, 190)] {
+        assert_eq!(client.get_next_retry_ledger(&sub_id), Some(retry_at));
+        client.approve_renewal(&sub_id, &approval_id, &1000, &1000);
+        testutils::advance_to(&env, retry_at - 1);
+        acquire_lock(&env, &client, sub_id, 200);
+        assert_eq!(
+            client.try_renew(&sub_id, &approval_id, &500, &1),
+            Err(Ok(Error::CooldownActive))
+        );
+        testutils::advance_to(&env, retry_at);
+        renew_unpaid(&env, &client, &sub_id, &approval_id, &500, &1);
+    }
+    assert_eq!(client.get_next_retry_ledger(&sub_id), Some(240));
 }
 
 #[test]
@@ -219,8 +634,8 @@ fn test_event_emission_on_success() {
     client.approve_renewal(&sub_id, &1, &1000, &100);
 
     // Successful renewal should emit RenewalSuccess event
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &1, &500, &3, &10, &20260315, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &1, &500, &20260315);
     assert!(result);
 
     // Verify event was emitted by checking subscription data
@@ -235,20 +650,101 @@ fn test_zero_max_retries() {
 
     let user = Address::generate(&env);
     let sub_id = 111;
-    let max_retries = 0;
 
     let merchant = Address::generate(&env);
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 0,
+            cooldown_ledgers: 10,
+        },
+    );
     client.approve_renewal(&sub_id, &1, &1000, &100);
 
     // First failure with max_retries = 0 should immediately fail
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &1, &500, &max_retries, &10, &20260401, &false);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = renew_unpaid(&env, &client, &sub_id, &1, &500, &20260401);
     assert!(!result);
 
     let data = client.get_sub(&sub_id);
     assert_eq!(data.state, SubscriptionState::Failed);
     assert_eq!(data.failure_count, 1);
+    assert_eq!(
+        client.get_last_failure(&sub_id),
+        Some(ReasonCode::RetriesExhausted)
+    );
+}
+
+#[test]
+fn test_exhausted_retries_downgrade_to_fallback_plan() {
+    let (env, client, _admin) = setup();
+    let token = syncro_testutils::TestToken::new(&env);
+    client.set_token(&token.address);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    token.mint(&user, 300);
+    token.approve(
+        &user,
+        &client.address,
+        &1000,
+        &(env.ledger().sequence() + 1000),
+    );
+    let sub_id = 112;
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 0,
+            cooldown_ledgers: 0,
+        },
+    );
+    assert!(client
+        .try_set_fallback_plan(&merchant, &sub_id, &Some(500))
+        .is_err());
+    client.set_fallback_plan(&merchant, &sub_id, &Some(200));
+    assert_eq!(client.get_fallback_plan(&sub_id), Some(200));
+
+    // The user can't afford the full plan
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!client.renew(&sub_id, &1, &500, &20260401));
+    let topic = soroban_sdk::vec![
+        &env,
+        Symbol::new(&env, "downgraded_on_failure").into_val(&env)
+    ];
+    assert!(env
+        .events()
+        .all()
+        .iter()
+        .any(|(_, topics, _)| topics == topic));
+
+    let data = client.get_sub(&sub_id);
+    assert_eq!(data.state, SubscriptionState::Active);
+    assert_eq!(data.amount, 200);
+    assert_eq!(data.failure_count, 0);
+    assert!(client.check_invariants(&sub_id).is_empty());
+    assert_eq!(client.get_fallback_plan(&sub_id), None);
+    assert_eq!(token.balance(&user), 300);
+
+    // The same cycle is charged at the lower amount, and only at it
+    client.approve_renewal(&sub_id, &2, &200, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &2, &500, &20260401),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert!(client.renew(&sub_id, &2, &200, &20260401));
+    assert_eq!(token.balance(&user), 100);
+    assert_eq!(token.balance(&merchant), 200);
+
+    // With the fallback used up, the next exhaustion fails the subscription
+    client.approve_renewal(&sub_id, &3, &200, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!client.renew(&sub_id, &3, &200, &20260402));
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Failed);
 }
 
 #[test]
@@ -257,24 +753,14 @@ fn test_multiple_failures_then_success() {
 
     let user = Address::generate(&env);
     let sub_id = 222;
-    let max_retries = 3;
-    let cooldown = 10;
 
     let merchant = Address::generate(&env);
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
 
     // First failure
     client.approve_renewal(&sub_id, &1, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(
-        &sub_id,
-        &1,
-        &500,
-        &max_retries,
-        &cooldown,
-        &20260501,
-        &false,
-    );
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &20260501);
     let data = client.get_sub(&sub_id);
     assert_eq!(data.state, SubscriptionState::Retrying);
     assert_eq!(data.failure_count, 1);
@@ -286,19 +772,15 @@ fn test_multiple_failures_then_success() {
 
     // Second failure
     client.approve_renewal(&sub_id, &2, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(
-        &sub_id,
-        &2,
-        &500,
-        &max_retries,
-        &cooldown,
-        &20260501,
-        &false,
-    );
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &2, &500, &20260501);
     let data = client.get_sub(&sub_id);
     assert_eq!(data.state, SubscriptionState::Retrying);
     assert_eq!(data.failure_count, 2);
+    assert_eq!(
+        client.get_last_failure(&sub_id),
+        Some(ReasonCode::PaymentFailed)
+    );
 
     // Advance ledger
     env.ledger().with_mut(|li| {
@@ -307,56 +789,46 @@ fn test_multiple_failures_then_success() {
 
     // Now succeed - should reset failure count and return to Active
     client.approve_renewal(&sub_id, &3, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &3, &500, &max_retries, &cooldown, &20260501, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &3, &500, &20260501);
     assert!(result);
 
     let data = client.get_sub(&sub_id);
     assert_eq!(data.state, SubscriptionState::Active);
     assert_eq!(data.failure_count, 0);
+    assert_eq!(client.get_last_failure(&sub_id), None);
 }
 
 #[test]
-#[should_panic(expected = "Subscription is in FAILED state")]
 fn test_cannot_renew_failed_subscription() {
     let (env, client, _admin) = setup();
 
     let user = Address::generate(&env);
     let sub_id = 333;
-    let max_retries = 1;
-    let cooldown = 10;
 
     let merchant = Address::generate(&env);
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 1,
+            cooldown_ledgers: 10,
+        },
+    );
 
     // Fail twice to reach Failed state
     client.approve_renewal(&sub_id, &1, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(
-        &sub_id,
-        &1,
-        &500,
-        &max_retries,
-        &cooldown,
-        &20260601,
-        &false,
-    );
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &20260601);
 
     env.ledger().with_mut(|li| {
         li.sequence_number = 20;
     });
 
     client.approve_renewal(&sub_id, &2, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(
-        &sub_id,
-        &2,
-        &500,
-        &max_retries,
-        &cooldown,
-        &20260601,
-        &false,
-    );
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &2, &500, &20260601);
 
     let data = client.get_sub(&sub_id);
     assert_eq!(data.state, SubscriptionState::Failed);
@@ -366,10 +838,170 @@ fn test_cannot_renew_failed_subscription() {
         li.sequence_number = 40;
     });
 
-    // Try to renew a FAILED subscription - should panic
+    // Try to renew a FAILED subscription - should fail
     client.approve_renewal(&sub_id, &3, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &3, &500, &max_retries, &cooldown, &20260701, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &3, &500, &20260701),
+        Err(Ok(Error::SubFailed))
+    );
+}
+
+// ── Token transfer tests ─────────────────────────────────────────
+
+#[test]
+fn test_renewal_pulls_charge_from_payer() {
+    let (env, client, admin) = setup();
+    let token = syncro_testutils::TestToken::new(&env);
+    client.set_token(&token.address);
+    assert_eq!(client.get_token(), Some(token.address.clone()));
+
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let partner = Address::generate(&env);
+    let sub_id = 17512;
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 3,
+            cooldown_ledgers: 0,
+        },
+    );
+    client.set_payouts(
+        &merchant,
+        &sub_id,
+        &soroban_sdk::vec![
+            &env,
+            Payout {
+                recipient: merchant.clone(),
+                weight: 3,
+            },
+            Payout {
+                recipient: partner.clone(),
+                weight: 1,
+            },
+        ],
+    );
+    client.set_protocol_fee(&admin, &200);
+    token.mint(&owner, 1000);
+    token.approve(&owner, &client.address, &600, &1000);
+
+    // 500 is pulled from the owner; the fee goes to the admin and the rest
+    // is split between the payout recipients
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &20260101));
+    assert_eq!(token.balance(&owner), 500);
+    assert_eq!(token.balance(&admin), 10);
+    assert_eq!(token.balance(&merchant), 368);
+    assert_eq!(token.balance(&partner), 122);
+    assert_eq!(token.balance(&client.address), 0);
+
+    // The 100 left of the allowance doesn't cover the next charge
+    client.approve_renewal(&sub_id, &2, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!client.renew(&sub_id, &2, &500, &20260201));
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Retrying);
+    assert_eq!(token.balance(&owner), 500);
+
+    token.approve(&owner, &client.address, &500, &1000);
+    client.approve_renewal(&sub_id, &3, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &3, &500, &20260201));
+    assert_eq!(token.balance(&owner), 0);
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Active);
+}
+
+#[test]
+fn test_renewal_requires_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = SubscriptionRenewalContractClient::new(
+        &env,
+        &env.register(SubscriptionRenewalContract, ()),
+    );
+    let admin = Address::generate(&env);
+    client.init(&admin);
+    client.set_derived_cycle_ids(&admin, &false);
+    let merchant = Address::generate(&env);
+    client.init_sub(&Address::generate(&env), &merchant, &500, &86400, &1000, &1);
+    client.approve_renewal(&1, &1, &1000, &100);
+    acquire_lock(&env, &client, 1, 200);
+    assert_eq!(
+        client.try_renew(&1, &1, &500, &1),
+        Err(Ok(Error::PeerNotSet))
+    );
+}
+
+// ── Partial payment tests ────────────────────────────────────────
+
+#[test]
+fn test_partial_payments_complete_cycle_once_funded() {
+    let (env, client, _admin) = setup();
+    let token = syncro_testutils::TestToken::new(&env);
+    client.set_token(&token.address);
+    let merchant = Address::generate(&env);
+    let wallet = Address::generate(&env);
+    let card = Address::generate(&env);
+    token.fund(&[&wallet, &card], 1000);
+    let sub_id = 1752;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+
+    assert!(!client.pay_partial(&sub_id, &1, &wallet, &300));
+    let funding = client.get_cycle_funding(&sub_id, &1).unwrap();
+    assert_eq!((funding.required, funding.paid), (500, 300));
+    assert!(client.try_pay_partial(&sub_id, &1, &card, &300).is_err());
+    assert_eq!(
+        client.try_pay_partial(&sub_id, &1, &card, &i128::MAX),
+        Err(Ok(Error::ExceedsAmountDue))
+    );
+    assert!(client.try_refund_partial_payments(&sub_id, &1).is_err());
+    assert_eq!(token.balance(&merchant), 0);
+
+    // The second source covers the rest, completing the cycle
+    assert!(client.pay_partial(&sub_id, &1, &card, &200));
+    assert_eq!(token.balance(&wallet), 700);
+    assert_eq!(token.balance(&card), 800);
+    assert_eq!(token.balance(&merchant), 500);
+    assert_eq!(client.get_cycle_funding(&sub_id, &1), None);
+    assert_eq!(client.get_receipt(&sub_id, &1).amount, 500);
+    assert!(client.try_pay_partial(&sub_id, &1, &card, &100).is_err());
+}
+
+#[test]
+fn test_partial_payments_refunded_after_window() {
+    let (env, client, _admin) = setup();
+    let token = syncro_testutils::TestToken::new(&env);
+    client.set_token(&token.address);
+    let wallet = Address::generate(&env);
+    token.mint(&wallet, 1000);
+    let sub_id = 17522;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+
+    client.pay_partial(&sub_id, &1, &wallet, &300);
+    env.ledger()
+        .with_mut(|li| li.sequence_number += PARTIAL_PAYMENT_LEDGERS);
+    assert!(client.try_pay_partial(&sub_id, &1, &wallet, &200).is_err());
+    assert_eq!(client.refund_partial_payments(&sub_id, &1), 300);
+    assert_eq!(token.balance(&wallet), 1000);
+    assert_eq!(client.get_cycle_funding(&sub_id, &1), None);
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Active);
 }
 
 // ── Approval system tests ────────────────────────────────────────
@@ -389,13 +1021,12 @@ fn test_approval_required_for_renewal() {
     client.approve_renewal(&sub_id, &approval_id, &1000, &100);
 
     // Renew with valid approval
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &approval_id, &500, &3, &10, &20260801, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &approval_id, &500, &20260801);
     assert!(result);
 }
 
 #[test]
-#[should_panic(expected = "Invalid or expired approval")]
 fn test_renewal_without_approval_fails() {
     let (env, client, _admin) = setup();
 
@@ -406,12 +1037,14 @@ fn test_renewal_without_approval_fails() {
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
 
     // Try to renew without creating approval
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &999, &500, &3, &10, &20260901, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &999, &500, &20260901),
+        Err(Ok(Error::ApprovalInvalid))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Invalid or expired approval")]
 fn test_approval_cannot_be_reused() {
     let (env, client, _admin) = setup();
 
@@ -424,20 +1057,22 @@ fn test_approval_cannot_be_reused() {
     client.approve_renewal(&sub_id, &approval_id, &1000, &100);
 
     // First use - should succeed
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &approval_id, &500, &3, &10, &20261001, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.renew(&sub_id, &approval_id, &500, &20261001);
 
     env.ledger().with_mut(|li| {
         li.sequence_number = 20;
     });
 
     // Second use - should fail (already used) — use different cycle_id to bypass cycle guard
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &approval_id, &500, &3, &10, &20261101, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &approval_id, &500, &20261101),
+        Err(Ok(Error::ApprovalInvalid))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Invalid or expired approval")]
 fn test_expired_approval_rejected() {
     let (env, client, _admin) = setup();
 
@@ -457,12 +1092,14 @@ fn test_expired_approval_rejected() {
     });
 
     // Try to use expired approval
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &approval_id, &500, &3, &10, &20261201, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &approval_id, &500, &20261201),
+        Err(Ok(Error::ApprovalInvalid))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Invalid or expired approval")]
 fn test_amount_exceeds_max_spend() {
     let (env, client, _admin) = setup();
 
@@ -471,14 +1108,43 @@ fn test_amount_exceeds_max_spend() {
     let approval_id = 4;
 
     let merchant = Address::generate(&env);
-    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.init_sub(&user, &merchant, &1500, &86400, &2000, &sub_id);
 
     // Create approval with max_spend = 1000
     client.approve_renewal(&sub_id, &approval_id, &1000, &100);
 
     // Try to renew with amount > max_spend
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &approval_id, &1500, &3, &10, &20270101, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &approval_id, &1500, &20270101),
+        Err(Ok(Error::ApprovalInvalid))
+    );
+}
+
+#[test]
+fn test_renew_charges_only_the_subscription_amount() {
+    let (env, client, _admin) = setup();
+    let sub_id = 506;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &2000,
+        &sub_id,
+    );
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+
+    for amount in [0, -1, 499, 501] {
+        assert_eq!(
+            client.try_renew(&sub_id, &1, &amount, &1),
+            Err(Ok(Error::InvalidAmount))
+        );
+    }
+    assert_eq!(client.get_sub(&sub_id).failure_count, 0);
+    assert!(client.renew(&sub_id, &1, &500, &1));
+    assert_eq!(client.get_receipt(&sub_id, &1).amount, 500);
 }
 
 #[test]
@@ -489,30 +1155,179 @@ fn test_multiple_approvals_for_same_subscription() {
     let sub_id = 505;
 
     let merchant = Address::generate(&env);
-    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.init_sub(&user, &merchant, &500, &86400, &2000, &sub_id);
 
     // Create multiple approvals
     client.approve_renewal(&sub_id, &1, &1000, &100);
     client.approve_renewal(&sub_id, &2, &2000, &200);
 
     // Use first approval
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &1, &500, &3, &10, &20270201, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.renew(&sub_id, &1, &500, &20270201);
 
     env.ledger().with_mut(|li| {
         li.sequence_number = 20;
     });
 
     // Use second approval — different cycle_id since first succeeded
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &2, &1500, &3, &10, &20270301, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &2, &500, &20270301);
     assert!(result);
 }
 
+#[test]
+fn test_soft_cap_parks_renewal_until_owner_confirms() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let sub_id = 1748;
+    client.init_sub(
+        &owner,
+        &Address::generate(&env),
+        &1500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_soft_cap(&sub_id, &true);
+    client.approve_renewal(&sub_id, &1, &2000, &100);
+
+    // Over the cap, the renewal is parked and the approval left unused
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!client.renew(&sub_id, &1, &1500, &1));
+    let pending = client.get_pending_renewal(&sub_id).unwrap();
+    assert_eq!(
+        (pending.cycle_id, pending.amount, pending.confirmed),
+        (1, 1500, false)
+    );
+    assert_eq!(client.get_sub(&sub_id).failure_count, 0);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.try_renew(&sub_id, &1, &1500, &1).is_err());
+
+    client.confirm_pending_renewal(&sub_id);
+    assert_eq!(env.auths()[0].0, owner);
+    assert!(client.renew(&sub_id, &1, &1500, &1));
+    assert_eq!(client.get_pending_renewal(&sub_id), None);
+}
+
+#[test]
+fn test_spending_cap_limits_spend_per_window() {
+    let (env, client, _admin) = setup();
+    let sub_id = 1759;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &600,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &600, &1));
+    assert_eq!(
+        client.get_spend_window(&sub_id),
+        SpendWindow {
+            started_at: 0,
+            spent: 600,
+        }
+    );
+
+    // A second charge in the same window would pass the cap
+    env.ledger().with_mut(|li| li.timestamp = SPEND_WINDOW - 1);
+    client.approve_renewal(&sub_id, &2, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &2, &600, &2),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(client.get_spend_window(&sub_id).spent, 600);
+
+    // Once the window has passed, the spend starts over
+    env.ledger().with_mut(|li| li.timestamp = SPEND_WINDOW);
+    assert!(client.renew(&sub_id, &2, &600, &2));
+    assert_eq!(
+        client.get_spend_window(&sub_id),
+        SpendWindow {
+            started_at: SPEND_WINDOW,
+            spent: 600,
+        }
+    );
+}
+
+#[test]
+fn test_soft_cap_confirmation_window_closes() {
+    let (env, client, _admin) = setup();
+    let sub_id = 1749;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &1500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_soft_cap(&sub_id, &true);
+    client.approve_renewal(&sub_id, &1, &2000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!client.renew(&sub_id, &1, &1500, &1));
+
+    env.ledger()
+        .with_mut(|li| li.sequence_number = CONFIRMATION_LEDGERS);
+    assert_eq!(
+        client.try_confirm_pending_renewal(&sub_id),
+        Err(Ok(Error::NoPendingRenewal))
+    );
+}
+
 // ── Cycle guard tests ────────────────────────────────────────────
 
 #[test]
-#[should_panic(expected = "Duplicate renewal for cycle")]
+fn test_approval_utilization_aggregated_per_period() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &510);
+    client.init_sub(&user, &merchant, &300, &86400, &1000, &511);
+    assert!(client.get_approval_utilization(&user).is_empty());
+
+    client.approve_renewal(&510, &1, &1000, &100);
+    acquire_lock(&env, &client, 510, 200);
+    client.renew(&510, &1, &500, &1);
+    client.approve_renewal(&511, &1, &400, &100);
+    acquire_lock(&env, &client, 511, 200);
+    renew_unpaid(&env, &client, &511, &1, &300, &1);
+    // A rejected approval is not consumed
+    client.approve_renewal(&511, &2, &100, &100);
+    acquire_lock(&env, &client, 511, 200);
+    assert!(client.try_renew(&511, &2, &300, &1).is_err());
+
+    env.ledger()
+        .with_mut(|li| li.timestamp += UTILIZATION_PERIOD);
+    client.approve_renewal(&510, &2, &2000, &100);
+    acquire_lock(&env, &client, 510, 200);
+    client.renew(&510, &2, &500, &2);
+
+    assert_eq!(
+        client.get_approval_utilization(&user),
+        soroban_sdk::vec![
+            &env,
+            ApprovalUtilization {
+                period: 0,
+                approvals: 2,
+                max_spend: 1400,
+                spent: 800,
+            },
+            ApprovalUtilization {
+                period: 1,
+                approvals: 1,
+                max_spend: 2000,
+                spent: 500,
+            },
+        ]
+    );
+}
+
+#[test]
 fn test_duplicate_cycle_rejected_after_success() {
     let (env, client, _admin) = setup();
 
@@ -525,14 +1340,17 @@ fn test_duplicate_cycle_rejected_after_success() {
 
     // First renewal succeeds — stores cycle_id
     client.approve_renewal(&sub_id, &1, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &1, &500, &3, &10, &cycle_id, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &1, &500, &cycle_id);
     assert!(result);
 
-    // Second renewal with same cycle_id — should panic
+    // Second renewal with same cycle_id — should fail
     client.approve_renewal(&sub_id, &2, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &2, &500, &3, &10, &cycle_id, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &2, &500, &cycle_id),
+        Err(Ok(Error::DuplicateCycle))
+    );
 }
 
 #[test]
@@ -548,8 +1366,8 @@ fn test_retry_same_cycle_allowed_after_failure() {
 
     // First attempt fails — does NOT store cycle_id
     client.approve_renewal(&sub_id, &1, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &1, &500, &3, &10, &cycle_id, &false);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = renew_unpaid(&env, &client, &sub_id, &1, &500, &cycle_id);
     assert!(!result);
 
     // Advance ledger past cooldown
@@ -559,8 +1377,8 @@ fn test_retry_same_cycle_allowed_after_failure() {
 
     // Retry with same cycle_id — should succeed because failure didn't record cycle
     client.approve_renewal(&sub_id, &2, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &2, &500, &3, &10, &cycle_id, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &2, &500, &cycle_id);
     assert!(result);
 }
 
@@ -576,32 +1394,55 @@ fn test_different_cycle_allowed_after_success() {
 
     // First cycle succeeds
     client.approve_renewal(&sub_id, &1, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &1, &500, &3, &10, &20260315, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &1, &500, &20260315);
     assert!(result);
 
     // Different cycle_id — should succeed
     client.approve_renewal(&sub_id, &2, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &2, &500, &3, &10, &20260415, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &2, &500, &20260415);
     assert!(result);
 }
 
 #[test]
-fn test_first_renewal_always_allowed() {
+fn test_earlier_cycle_rejected_after_success() {
     let (env, client, _admin) = setup();
 
     let user = Address::generate(&env);
-    let sub_id = 603;
+    let sub_id = 604;
 
     let merchant = Address::generate(&env);
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
 
-    // First renewal ever — no stored cycle, guard passes
     client.approve_renewal(&sub_id, &1, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    let result = client.renew(&sub_id, &1, &500, &3, &10, &20260101, &true);
-    assert!(result);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &20260415));
+
+    // Going back to an already-passed cycle must not charge again
+    client.approve_renewal(&sub_id, &2, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &2, &500, &20260315),
+        Err(Ok(Error::DuplicateCycle))
+    );
+}
+
+#[test]
+fn test_first_renewal_always_allowed() {
+    let (env, client, _admin) = setup();
+
+    let user = Address::generate(&env);
+    let sub_id = 603;
+
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+
+    // First renewal ever — no stored cycle, guard passes
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    let result = client.renew(&sub_id, &1, &500, &20260101);
+    assert!(result);
 
     let data = client.get_sub(&sub_id);
     assert_eq!(data.state, SubscriptionState::Active);
@@ -618,14 +1459,35 @@ fn test_cancel_sub() {
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
 
     // Cancel subscription
-    client.cancel_sub(&sub_id);
+    client.cancel_sub(&sub_id, &Some(ReasonCode::TooExpensive));
 
     let data = client.get_sub(&sub_id);
     assert_eq!(data.state, SubscriptionState::Cancelled);
+    assert_eq!(
+        client.get_cancel_reason(&sub_id),
+        Some(ReasonCode::TooExpensive)
+    );
+}
+
+#[test]
+fn test_cancelled_sub_cannot_renew() {
+    let (env, client, _admin) = setup();
+
+    let user = Address::generate(&env);
+    let sub_id = 601;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    client.cancel_sub(&sub_id, &None);
+
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &500, &20260101),
+        Err(Ok(Error::SubCancelled))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Subscription already cancelled")]
 fn test_cannot_cancel_twice() {
     let (env, client, _admin) = setup();
 
@@ -635,44 +1497,435 @@ fn test_cannot_cancel_twice() {
     let merchant = Address::generate(&env);
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
 
-    client.cancel_sub(&sub_id);
-    client.cancel_sub(&sub_id);
+    client.cancel_sub(&sub_id, &None);
+    assert_eq!(
+        client.try_cancel_sub(&sub_id, &None),
+        Err(Ok(Error::SubCancelled))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Subscription not found")]
 fn test_cancel_non_existent_sub() {
     let (_env, client, _admin) = setup();
-    client.cancel_sub(&999);
+    assert_eq!(
+        client.try_cancel_sub(&999, &None),
+        Err(Ok(Error::SubNotFound))
+    );
+}
+
+#[test]
+fn test_restore_sub_within_window() {
+    let (env, client, admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let user = Address::generate(&env);
+    let sub_id = 1743;
+    client.init_sub(
+        &user,
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.approve_renewal(&sub_id, &1, &1000, &1000);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &1);
+    client.set_restore_window(&admin, &100);
+
+    client.cancel_sub(&sub_id, &None);
+    assert!(client.get_owner_subs(&user).is_empty());
+    testutils::advance(&env, 100);
+    client.restore_sub(&sub_id);
+    assert_eq!(env.auths()[0].0, user);
+
+    // Back where it was, with its history intact
+    let data = client.get_sub(&sub_id);
+    assert_eq!(data.state, SubscriptionState::Retrying);
+    assert_eq!(data.failure_count, 1);
+    let lifecycle = client.get_lifecycle(&sub_id);
+    assert_eq!(lifecycle.created_at, 1_000);
+    assert_eq!(lifecycle.canceled_at, 0);
+    assert_eq!(
+        client.get_owner_subs(&user),
+        soroban_sdk::vec![&env, sub_id]
+    );
+    assert!(client.check_invariants(&sub_id).is_empty());
+}
+
+#[test]
+fn test_restore_sub_after_window_fails() {
+    let (env, client, admin) = setup();
+    let sub_id = 1744;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_restore_window(&admin, &100);
+    client.cancel_sub(&sub_id, &None);
+    testutils::advance(&env, 101);
+    assert_eq!(
+        client.try_restore_sub(&sub_id),
+        Err(Ok(Error::CancellationFinal))
+    );
+}
+
+#[test]
+fn test_cancel_all_for_owner_pages_through_subs() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    for sub_id in 1..=5 {
+        client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    }
+    client.init_sub(&other, &merchant, &500, &86400, &1000, &6);
+    client.cancel_sub(&2, &None);
+    assert_eq!(
+        client.get_owner_subs(&user),
+        soroban_sdk::vec![&env, 1, 3, 4, 5]
+    );
+
+    assert_eq!(client.cancel_all_for_owner(&user, &3), 1);
+    assert_eq!(client.get_owner_subs(&user), soroban_sdk::vec![&env, 5]);
+    assert_eq!(client.get_sub(&4).state, SubscriptionState::Cancelled);
+    assert_eq!(client.get_sub(&5).state, SubscriptionState::Active);
+    assert_eq!(client.cancel_all_for_owner(&user, &3), 0);
+    assert_eq!(client.cancel_all_for_owner(&user, &3), 0);
+    for sub_id in 1..=5 {
+        assert_eq!(client.get_sub(&sub_id).state, SubscriptionState::Cancelled);
+    }
+    assert_eq!(client.get_sub(&6).state, SubscriptionState::Active);
+}
+
+#[test]
+fn test_cancel_all_for_owner_leaves_expired_subs_alone() {
+    let (env, client, admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &8);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &9);
+    client.set_delinquency_horizon(&admin, &86400);
+    client.approve_renewal(&9, &1, &1000, &100);
+    acquire_lock(&env, &client, 9, 200);
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 86400);
+    assert!(client.renew(&9, &1, &500, &1));
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 2 * 86400);
+    assert_eq!(client.sweep_expired(&10), 1);
+    assert_eq!(client.get_owner_subs(&user), soroban_sdk::vec![&env, 9]);
+
+    assert_eq!(client.cancel_all_for_owner(&user, &10), 0);
+    assert_eq!(client.get_status(&8), SubscriptionState::Expired);
+    assert_eq!(client.get_status(&9), SubscriptionState::Cancelled);
+}
+
+#[test]
+fn test_transferred_subs_follow_their_owner() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    client.init_sub(&user, &Address::generate(&env), &500, &86400, &1000, &7);
+    client.transfer_sub(&7, &user, &buyer);
+    assert!(client.get_owner_subs(&user).is_empty());
+    assert_eq!(client.get_owner_subs(&buyer), soroban_sdk::vec![&env, 7]);
+
+    env.set_auths(&[]);
+    assert!(client.try_cancel_all_for_owner(&buyer, &10).is_err());
+}
+
+#[test]
+fn test_derived_cycle_ids_enforced_when_enabled() {
+    let (env, client, admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let sub_id = 1751;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    let first = client.compute_cycle_id(&sub_id, &1);
+    assert_eq!(first, 87_400);
+    assert_eq!(client.compute_cycle_id(&sub_id, &2), 173_800);
+
+    client.set_derived_cycle_ids(&admin, &true);
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &500, &20260101),
+        Err(Ok(Error::CycleNotDerived))
+    );
+    // Period 1 has not started yet
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &500, &first),
+        Err(Ok(Error::CycleNotDerived))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 90_000);
+    assert_eq!(client.get_current_cycle_id(&sub_id), Some(first));
+    assert!(client.renew(&sub_id, &1, &500, &first));
+}
+
+#[test]
+fn test_derived_cycle_ids_reject_other_periods() {
+    let (env, client, admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let sub_id = 1754;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_derived_cycle_ids(&admin, &true);
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+
+    env.ledger().with_mut(|li| li.timestamp = 200_000);
+    let current = client.compute_cycle_id(&sub_id, &2);
+    assert_eq!(client.get_current_cycle_id(&sub_id), Some(current));
+    // A missed period can no longer be charged, nor can the next one early
+    for cycle_id in [
+        client.compute_cycle_id(&sub_id, &1),
+        client.compute_cycle_id(&sub_id, &3),
+    ] {
+        assert_eq!(
+            client.try_renew(&sub_id, &1, &500, &cycle_id),
+            Err(Ok(Error::CycleNotDerived))
+        );
+    }
+    assert!(client.renew(&sub_id, &1, &500, &current));
+}
+
+#[test]
+fn test_cycle_ids_are_derived_by_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = SubscriptionRenewalContractClient::new(
+        &env,
+        &env.register(SubscriptionRenewalContract, ()),
+    );
+    client.init(&Address::generate(&env));
+    client.set_token(&env.register(MockToken, ()));
+    assert!(client.is_derived_cycle_ids());
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let sub_id = 1754;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    // A lock holder cannot push the last cycle past every later period
+    for cycle_id in [2, u64::MAX] {
+        assert_eq!(
+            client.try_renew(&sub_id, &1, &500, &cycle_id),
+            Err(Ok(Error::CycleNotDerived))
+        );
+    }
+    let current = client.get_current_cycle_id(&sub_id).unwrap();
+    assert!(client.renew(&sub_id, &1, &500, &current));
+}
+
+// ── Owner pause tests ────────────────────────────────────────────
+
+#[test]
+fn test_paused_sub_cannot_renew_until_resumed() {
+    let (env, client, _admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let sub_id = 1758;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    client.pause_sub(&sub_id);
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Paused);
+    assert_eq!(client.get_lifecycle(&sub_id).paused_at, 2_000);
+    // Pausing released the lock
+    assert!(client.get_renewal_lock(&sub_id).is_none());
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &500, &1),
+        Err(Ok(Error::Paused))
+    );
+    assert_eq!(client.try_pause_sub(&sub_id), Err(Ok(Error::Paused)));
+
+    env.ledger().with_mut(|li| li.timestamp = 3_000);
+    client.resume_sub(&sub_id);
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Active);
+    let lc = client.get_lifecycle(&sub_id);
+    assert_eq!((lc.paused_at, lc.resumed_at), (2_000, 3_000));
+    assert!(client.check_invariants(&sub_id).is_empty());
+    assert!(client.renew(&sub_id, &1, &500, &1));
+}
+
+#[test]
+fn test_resume_requires_paused_sub() {
+    let (env, client, _admin) = setup();
+    let sub_id = 1759;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert_eq!(client.try_resume_sub(&sub_id), Err(Ok(Error::NotPaused)));
+    client.cancel_sub(&sub_id, &None);
+    assert_eq!(client.try_pause_sub(&sub_id), Err(Ok(Error::SubCancelled)));
+}
+
+// ── Expiry tests ─────────────────────────────────────────────────
+
+#[test]
+fn test_sweep_expires_delinquent_subscriptions() {
+    let (env, client, admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    for sub_id in [1755, 1756, 1757] {
+        client.init_sub(
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &500,
+            &86400,
+            &1000,
+            &sub_id,
+        );
+    }
+    client.set_delinquency_horizon(&admin, &(2 * 86400));
+    client.cancel_sub(&1757, &None);
+
+    // 1755 renews on time; 1756 is never renewed
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + 86400);
+    client.approve_renewal(&1755, &1, &1000, &100);
+    acquire_lock(&env, &client, 1755, 200);
+    assert!(client.renew(&1755, &1, &500, &1));
+    acquire_lock(&env, &client, 1756, 200);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_000 + 3 * 86400 - 1);
+    assert_eq!(client.sweep_expired(&10), 0);
+    env.ledger().with_mut(|li| li.timestamp += 1);
+    assert_eq!(client.sweep_expired(&10), 1);
+    let data = last_event(&env, "subscription_expired");
+    let due_at: u64 = data
+        .get(Symbol::new(&env, "due_at"))
+        .unwrap()
+        .into_val(&env);
+    assert_eq!(due_at, 1_000 + 86400);
+
+    assert_eq!(client.get_status(&1756), SubscriptionState::Expired);
+    assert_eq!(client.get_status(&1755), SubscriptionState::Active);
+    assert_eq!(client.get_status(&1757), SubscriptionState::Cancelled);
+    assert!(client.get_renewal_lock(&1756).is_none());
+    assert!(client.check_invariants(&1756).is_empty());
+
+    client.approve_renewal(&1756, &1, &1000, &100);
+    assert_eq!(
+        client.try_renew(&1756, &1, &500, &1),
+        Err(Ok(Error::SubExpired))
+    );
+    assert_eq!(
+        client.try_cancel_sub(&1756, &None),
+        Err(Ok(Error::SubExpired))
+    );
+}
+
+#[test]
+fn test_sweep_bounded_by_limit() {
+    let (env, client, admin) = setup();
+    for sub_id in 0..3 {
+        client.init_sub(
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &500,
+            &86400,
+            &1000,
+            &sub_id,
+        );
+    }
+    env.ledger().with_mut(|li| li.timestamp += 10 * 86400);
+    // Nothing expires until a horizon is set
+    assert_eq!(client.sweep_expired(&10), 0);
+
+    client.set_delinquency_horizon(&admin, &86400);
+    assert_eq!(client.sweep_expired(&2), 2);
+    assert_eq!(client.sweep_expired(&2), 1);
+    assert_eq!(client.sweep_expired(&2), 0);
+    for sub_id in 0..3 {
+        assert_eq!(client.get_status(&sub_id), SubscriptionState::Expired);
+    }
 }
 
 // ── Renewal lock tests ──────────────────────────────────────────
 
 #[test]
 fn test_acquire_renewal_lock() {
-    let (_env, client, _admin) = setup();
+    let (env, client, _admin) = setup();
 
     let sub_id = 700;
 
-    client.acquire_renewal_lock(&sub_id, &200);
+    let agent = acquire_lock(&env, &client, sub_id, 200);
 
     let lock = client.get_renewal_lock(&sub_id);
     assert!(lock.is_some());
     let lock_data = lock.unwrap();
+    assert_eq!(lock_data.holder, agent);
     assert_eq!(lock_data.locked_at, 0); // default ledger
     assert_eq!(lock_data.lock_timeout, 200);
 }
 
 #[test]
-#[should_panic(expected = "Renewal lock active")]
+fn test_lock_lives_in_temporary_storage_for_its_timeout() {
+    use soroban_sdk::testutils::storage::Temporary as _;
+
+    let (env, client, _admin) = setup();
+    let sub_id = 7001;
+    acquire_lock(&env, &client, sub_id, 200);
+
+    let key = RenewalLockKey {
+        lock_sub_id: sub_id,
+    };
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().persistent().has(&key));
+        assert_eq!(env.storage().temporary().get_ttl(&key), 200);
+    });
+}
+
+#[test]
 fn test_lock_prevents_concurrent_acquisition() {
-    let (_env, client, _admin) = setup();
+    let (env, client, _admin) = setup();
 
     let sub_id = 701;
 
-    client.acquire_renewal_lock(&sub_id, &200);
-    // Second acquire should panic
-    client.acquire_renewal_lock(&sub_id, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    // Second acquire should fail
+    assert_eq!(
+        client.try_acquire_renewal_lock(&Address::generate(&env), &sub_id, &200),
+        Err(Ok(Error::LockActive))
+    );
 }
 
 #[test]
@@ -681,7 +1934,7 @@ fn test_lock_auto_expires_and_reacquirable() {
 
     let sub_id = 702;
 
-    client.acquire_renewal_lock(&sub_id, &50);
+    acquire_lock(&env, &client, sub_id, 50);
 
     // Advance ledger past lock timeout
     env.ledger().with_mut(|li| {
@@ -689,7 +1942,7 @@ fn test_lock_auto_expires_and_reacquirable() {
     });
 
     // Should succeed — old lock expired
-    client.acquire_renewal_lock(&sub_id, &200);
+    acquire_lock(&env, &client, sub_id, 200);
 
     let lock = client.get_renewal_lock(&sub_id);
     assert!(lock.is_some());
@@ -700,11 +1953,11 @@ fn test_lock_auto_expires_and_reacquirable() {
 
 #[test]
 fn test_release_renewal_lock() {
-    let (_env, client, _admin) = setup();
+    let (env, client, _admin) = setup();
 
     let sub_id = 703;
 
-    client.acquire_renewal_lock(&sub_id, &200);
+    acquire_lock(&env, &client, sub_id, 200);
     assert!(client.get_renewal_lock(&sub_id).is_some());
 
     client.release_renewal_lock(&sub_id);
@@ -712,17 +1965,18 @@ fn test_release_renewal_lock() {
 }
 
 #[test]
-#[should_panic(expected = "No renewal lock to release")]
-fn test_release_nonexistent_lock_panics() {
+fn test_release_nonexistent_lock_fails() {
     let (_env, client, _admin) = setup();
 
     let sub_id = 704;
-    client.release_renewal_lock(&sub_id);
+    assert_eq!(
+        client.try_release_renewal_lock(&sub_id),
+        Err(Ok(Error::LockRequired))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Renewal lock required")]
-fn test_renew_without_lock_panics() {
+fn test_renew_without_lock_fails() {
     let (env, client, _admin) = setup();
 
     let user = Address::generate(&env);
@@ -732,8 +1986,11 @@ fn test_renew_without_lock_panics() {
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
     client.approve_renewal(&sub_id, &1, &1000, &100);
 
-    // Renew without acquiring lock — should panic
-    client.renew(&sub_id, &1, &500, &3, &10, &20260101, &true);
+    // Renew without acquiring lock — should fail
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &500, &20260101),
+        Err(Ok(Error::LockRequired))
+    );
 }
 
 #[test]
@@ -747,10 +2004,10 @@ fn test_renew_with_lock_succeeds_and_auto_releases() {
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
     client.approve_renewal(&sub_id, &1, &1000, &100);
 
-    client.acquire_renewal_lock(&sub_id, &200);
+    acquire_lock(&env, &client, sub_id, 200);
     assert!(client.get_renewal_lock(&sub_id).is_some());
 
-    let result = client.renew(&sub_id, &1, &500, &3, &10, &20260101, &true);
+    let result = client.renew(&sub_id, &1, &500, &20260101);
     assert!(result);
 
     // Lock should be auto-released after renew
@@ -768,10 +2025,10 @@ fn test_renew_failure_also_releases_lock() {
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
     client.approve_renewal(&sub_id, &1, &1000, &200);
 
-    client.acquire_renewal_lock(&sub_id, &200);
+    acquire_lock(&env, &client, sub_id, 200);
     assert!(client.get_renewal_lock(&sub_id).is_some());
 
-    let result = client.renew(&sub_id, &1, &500, &3, &10, &20260101, &false);
+    let result = renew_unpaid(&env, &client, &sub_id, &1, &500, &20260101);
     assert!(!result);
 
     // Lock should be auto-released even after failure
@@ -779,8 +2036,7 @@ fn test_renew_failure_also_releases_lock() {
 }
 
 #[test]
-#[should_panic(expected = "Renewal lock expired")]
-fn test_renew_with_expired_lock_panics() {
+fn test_renew_with_expired_lock_fails() {
     let (env, client, _admin) = setup();
 
     let user = Address::generate(&env);
@@ -790,27 +2046,369 @@ fn test_renew_with_expired_lock_panics() {
     client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
     client.approve_renewal(&sub_id, &1, &1000, &200);
 
-    client.acquire_renewal_lock(&sub_id, &50);
+    acquire_lock(&env, &client, sub_id, 50);
 
     // Advance ledger past lock timeout
     env.ledger().with_mut(|li| {
         li.sequence_number = 60;
     });
 
-    // Renew with expired lock — should panic
-    client.renew(&sub_id, &1, &500, &3, &10, &20260101, &true);
+    // Renew with expired lock — should fail
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &500, &20260101),
+        Err(Ok(Error::LockRequired))
+    );
+}
+
+#[test]
+fn test_earliest_intent_gets_freed_lock_first() {
+    let (env, client, _admin) = setup();
+    let sub_id = 1737;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    let holder = acquire_lock(&env, &client, sub_id, 50);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    assert_eq!(client.register_renewal_intent(&first, &sub_id), 0);
+    assert_eq!(client.register_renewal_intent(&second, &sub_id), 1);
+    assert!(client.try_register_renewal_intent(&first, &sub_id).is_err());
+
+    // The freed lock goes to the earliest intent, not whoever asks first
+    client.release_renewal_lock(&sub_id);
+    assert!(client
+        .try_acquire_renewal_lock(&second, &sub_id, &50)
+        .is_err());
+    assert!(client
+        .try_acquire_renewal_lock(&holder, &sub_id, &50)
+        .is_err());
+    client.acquire_renewal_lock(&first, &sub_id, &50);
+    assert_eq!(client.get_renewal_intents(&sub_id).len(), 1);
+
+    // Once the lock expires the next intent has its claim
+    testutils::advance(&env, 50);
+    assert!(client
+        .try_acquire_renewal_lock(&holder, &sub_id, &50)
+        .is_err());
+    client.acquire_renewal_lock(&second, &sub_id, &50);
+    assert!(client.get_renewal_intents(&sub_id).is_empty());
+}
+
+#[test]
+fn test_intent_claim_outlives_evicted_lock() {
+    let (env, client, _admin) = setup();
+    let sub_id = 17652;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    let holder = acquire_lock(&env, &client, sub_id, 50);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.register_renewal_intent(&first, &sub_id);
+    client.register_renewal_intent(&second, &sub_id);
+
+    // The expired lock's entry is gone, but the claim still runs from its
+    // expiry rather than from an older release
+    testutils::advance(&env, 51);
+    assert!(client.get_renewal_lock(&sub_id).is_none());
+    assert_eq!(
+        client.try_acquire_renewal_lock(&holder, &sub_id, &50),
+        Err(Ok(Error::LockReserved))
+    );
+    client.acquire_renewal_lock(&first, &sub_id, &50);
+
+    // The same holds for a lock taken while others are queued
+    testutils::advance(&env, 51);
+    assert!(client.get_renewal_lock(&sub_id).is_none());
+    assert_eq!(
+        client.try_acquire_renewal_lock(&holder, &sub_id, &50),
+        Err(Ok(Error::LockReserved))
+    );
+    client.acquire_renewal_lock(&second, &sub_id, &50);
+}
+
+#[test]
+fn test_lapsed_intent_opens_lock_to_others() {
+    let (env, client, _admin) = setup();
+    let sub_id = 1738;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    let idle = Address::generate(&env);
+    client.register_renewal_intent(&idle, &sub_id);
+    testutils::advance(&env, INTENT_PRIORITY_LEDGERS);
+
+    let agent = acquire_lock(&env, &client, sub_id, 50);
+    assert_eq!(client.get_renewal_lock(&sub_id).unwrap().holder, agent);
+    assert!(client.get_renewal_intents(&sub_id).is_empty());
+}
+
+#[test]
+fn test_preferred_agent_has_renewal_window_to_itself() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let sub_id = 1739;
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &sub_id);
+    let keeper = Address::generate(&env);
+    let other = Address::generate(&env);
+    assert!(client
+        .try_set_preferred_agent(&other, &sub_id, &Some(other.clone()))
+        .is_err());
+    client.set_preferred_agent(&merchant, &sub_id, &Some(keeper.clone()));
+    assert_eq!(client.get_preferred_agent(&sub_id), Some(keeper.clone()));
+
+    // Other agents wait out the cycle and the window past its due date,
+    // queued intents included
+    client.register_renewal_intent(&other, &sub_id);
+    testutils::advance_time(&env, 86400);
+    assert!(client
+        .try_acquire_renewal_lock(&other, &sub_id, &20)
+        .is_err());
+    client.acquire_renewal_lock(&keeper, &sub_id, &20);
+    client.release_renewal_lock(&sub_id);
+
+    testutils::advance(&env, PREFERRED_AGENT_LEDGERS);
+    client.acquire_renewal_lock(&other, &sub_id, &20);
+
+    client.set_preferred_agent(&owner, &sub_id, &None);
+    assert_eq!(client.get_preferred_agent(&sub_id), None);
 }
 
 #[test]
-#[should_panic(expected = "Protocol is paused")]
 fn test_acquire_lock_blocked_when_paused() {
-    let (_env, client, _admin) = setup();
+    let (env, client, admin) = setup();
 
     let sub_id = 709;
 
-    client.set_paused(&true);
-    // Should panic because protocol is paused
-    client.acquire_renewal_lock(&sub_id, &200);
+    client.set_paused(&admin, &true, &None);
+    // Should fail because protocol is paused
+    assert_eq!(
+        client.try_acquire_renewal_lock(&Address::generate(&env), &sub_id, &200),
+        Err(Ok(Error::Paused))
+    );
+}
+
+// ── Batch renewal tests ──────────────────────────────────────────
+
+fn renewal_request(sub_id: u64, approval_id: u64, cycle_id: u64) -> RenewalRequest {
+    RenewalRequest {
+        sub_id,
+        approval_id,
+        amount: 500,
+        cycle_id,
+    }
+}
+
+#[test]
+fn test_renew_batch_skips_requests_failing_preconditions() {
+    let (env, client, _admin) = setup();
+    let merchant = Address::generate(&env);
+    for sub_id in [1501, 1502, 1503] {
+        client.init_sub(
+            &Address::generate(&env),
+            &merchant,
+            &500,
+            &86400,
+            &1000,
+            &sub_id,
+        );
+        client.approve_renewal(&sub_id, &1, &1000, &100);
+    }
+    acquire_lock(&env, &client, 1501, 200);
+    acquire_lock(&env, &client, 1502, 200);
+
+    let outcomes = client.renew_batch(&soroban_sdk::vec![
+        &env,
+        renewal_request(1501, 1, 1),
+        renewal_request(1502, 2, 1),
+        renewal_request(1503, 1, 1),
+    ]);
+    assert_eq!(
+        outcomes,
+        soroban_sdk::vec![
+            &env,
+            RenewalOutcome::Renewed,
+            RenewalOutcome::Skipped(Error::ApprovalInvalid as u32),
+            RenewalOutcome::Skipped(Error::LockRequired as u32),
+        ]
+    );
+    assert_eq!(client.get_receipt(&1501, &1).amount, 500);
+    // The skipped requests left their subscriptions as they were
+    assert!(client.get_renewal_lock(&1502).is_some());
+    assert!(!client.get_approval(&merchant, &1503, &1).unwrap().used);
+    assert_eq!(client.get_lifecycle(&1502).last_renewed_at, 0);
+}
+
+#[test]
+fn test_renew_batch_leaves_metered_usage_unsettled_when_skipped() {
+    let (env, client, _admin) = setup();
+    let metering = env.register(MockMetering, ());
+    client.set_metering_contract(&metering);
+    let sub_id = 1504;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_metered(&sub_id, &true);
+    // The quoted 750 is more than the approval allows
+    client.approve_renewal(&sub_id, &1, &500, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+
+    let outcomes = client.renew_batch(&soroban_sdk::vec![&env, renewal_request(sub_id, 1, 1)]);
+    assert_eq!(
+        outcomes,
+        soroban_sdk::vec![&env, RenewalOutcome::Skipped(Error::ApprovalInvalid as u32)]
+    );
+    assert_eq!(
+        MockMeteringClient::new(&env, &metering).get_settled(&sub_id, &1),
+        None
+    );
+}
+
+#[test]
+fn test_renew_batch_bounded() {
+    let (env, client, _admin) = setup();
+    let mut requests = Vec::new(&env);
+    for sub_id in 0..=MAX_RENEWAL_BATCH as u64 {
+        requests.push_back(renewal_request(sub_id, 1, 1));
+    }
+    assert_eq!(
+        client.try_renew_batch(&requests),
+        Err(Ok(Error::BatchTooLarge))
+    );
+}
+
+// ── Template tests ───────────────────────────────────────────────
+
+fn template_terms(amount: i128, trial: u64) -> TemplateTerms {
+    TemplateTerms {
+        amount,
+        frequency: 86400,
+        spending_cap: 1000,
+        trial,
+    }
+}
+
+#[test]
+fn test_init_sub_from_template_uses_its_terms() {
+    let (env, client, _admin) = setup();
+    let merchant = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let template_id = client.create_template(&merchant, &template_terms(500, 0));
+    client.init_sub_from_template(&template_id, &owner, &1531);
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &1532);
+
+    let sub = client.get_sub(&1531);
+    assert_eq!(sub.merchant, merchant);
+    assert_eq!(sub.amount, 500);
+    // Same terms, same integrity hash as a subscription set up by hand
+    assert_eq!(sub.integrity_hash, client.get_sub(&1532).integrity_hash);
+    assert_eq!(
+        client.get_sub_template(&1531),
+        Some(TemplateRef {
+            template_id,
+            version: 1,
+        })
+    );
+    assert_eq!(client.get_sub_template(&1532), None);
+    assert_eq!(client.get_trial_end(&1531), None);
+}
+
+#[test]
+fn test_template_update_keeps_existing_subscriptions() {
+    let (env, client, _admin) = setup();
+    let merchant = Address::generate(&env);
+    let template_id = client.create_template(&merchant, &template_terms(500, 0));
+    client.init_sub_from_template(&template_id, &Address::generate(&env), &1533);
+
+    assert_eq!(
+        client.update_template(&template_id, &template_terms(700, 0)),
+        2
+    );
+    client.init_sub_from_template(&template_id, &Address::generate(&env), &1534);
+
+    assert_eq!(client.get_sub(&1533).amount, 500);
+    assert_eq!(client.get_sub(&1534).amount, 700);
+    assert_eq!(client.get_sub_template(&1534).unwrap().version, 2);
+    assert_eq!(
+        client.get_template_at(&template_id, &1).unwrap().terms,
+        template_terms(500, 0)
+    );
+    assert_eq!(client.get_template(&template_id).unwrap().version, 2);
+}
+
+#[test]
+fn test_template_trial_delays_first_period() {
+    let (env, client, _admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let template_id =
+        client.create_template(&Address::generate(&env), &template_terms(500, 7 * 86400));
+    client.init_sub_from_template(&template_id, &Address::generate(&env), &1535);
+
+    assert_eq!(client.get_trial_end(&1535), Some(1_000 + 7 * 86400));
+    assert_eq!(client.compute_cycle_id(&1535, &0), 1_000 + 7 * 86400);
+    assert_eq!(client.compute_cycle_id(&1535, &1), 1_000 + 8 * 86400);
+    assert_eq!(client.get_current_cycle_id(&1535), None);
+}
+
+#[test]
+fn test_template_cannot_reinitialize_a_taken_sub_id() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &1537);
+    let template_id = client.create_template(&Address::generate(&env), &template_terms(700, 0));
+
+    assert_eq!(
+        client.try_init_sub_from_template(&template_id, &Address::generate(&env), &1537),
+        Err(Ok(Error::SubExists))
+    );
+    let sub = client.get_sub(&1537);
+    assert_eq!(
+        (sub.owner, sub.merchant, sub.amount),
+        (owner, merchant, 500)
+    );
+    assert_eq!(client.get_sub_template(&1537), None);
+}
+
+#[test]
+fn test_template_terms_validated() {
+    let (env, client, _admin) = setup();
+    let merchant = Address::generate(&env);
+    assert_eq!(
+        client.try_create_template(&merchant, &template_terms(1500, 0)),
+        Err(Ok(Error::InvalidAmount))
+    );
+    let mut terms = template_terms(500, 0);
+    terms.frequency = 0;
+    assert_eq!(
+        client.try_create_template(&merchant, &terms),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_init_sub_from_template(&7, &Address::generate(&env), &1536),
+        Err(Ok(Error::TemplateNotFound))
+    );
 }
 
 // ── Lifecycle timestamp tests ─────────────────────────────────────
@@ -855,8 +2453,8 @@ fn test_lifecycle_renewed_at_updated_on_success() {
     });
 
     client.approve_renewal(&sub_id, &1, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &1, &500, &3, &10, &20260101, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.renew(&sub_id, &1, &500, &20260101);
 
     let lc = client.get_lifecycle(&sub_id);
     assert_eq!(lc.created_at, 1700000000);
@@ -883,7 +2481,7 @@ fn test_lifecycle_canceled_at_set_on_cancel() {
         li.timestamp = 1700200000;
     });
 
-    client.cancel_sub(&sub_id);
+    client.cancel_sub(&sub_id, &None);
 
     let lc = client.get_lifecycle(&sub_id);
     assert_eq!(lc.created_at, 1700000000);
@@ -909,8 +2507,8 @@ fn test_lifecycle_activated_at_updated_on_recovery_from_retrying() {
         li.timestamp = 1700100000;
     });
     client.approve_renewal(&sub_id, &1, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &1, &500, &3, &10, &20260201, &false);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &20260201);
 
     let data = client.get_sub(&sub_id);
     assert_eq!(data.state, SubscriptionState::Retrying);
@@ -921,8 +2519,8 @@ fn test_lifecycle_activated_at_updated_on_recovery_from_retrying() {
         li.timestamp = 1700200000;
     });
     client.approve_renewal(&sub_id, &2, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &2, &500, &3, &10, &20260201, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.renew(&sub_id, &2, &500, &20260201);
 
     let lc = client.get_lifecycle(&sub_id);
     assert_eq!(lc.created_at, 1700000000);
@@ -949,8 +2547,8 @@ fn test_lifecycle_not_updated_on_renewal_failure() {
         li.timestamp = 1700100000;
     });
     client.approve_renewal(&sub_id, &1, &1000, &200);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &1, &500, &3, &10, &20260301, &false);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &20260301);
 
     let lc = client.get_lifecycle(&sub_id);
     assert_eq!(lc.last_renewed_at, 0); // unchanged on failure
@@ -976,8 +2574,8 @@ fn test_lifecycle_multiple_renewals_update_last_renewed() {
         li.timestamp = 1700100000;
     });
     client.approve_renewal(&sub_id, &1, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &1, &500, &3, &10, &20260401, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.renew(&sub_id, &1, &500, &20260401);
 
     let lc = client.get_lifecycle(&sub_id);
     assert_eq!(lc.last_renewed_at, 1700100000);
@@ -987,8 +2585,8 @@ fn test_lifecycle_multiple_renewals_update_last_renewed() {
         li.timestamp = 1700200000;
     });
     client.approve_renewal(&sub_id, &2, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &2, &500, &3, &10, &20260501, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.renew(&sub_id, &2, &500, &20260501);
 
     let lc = client.get_lifecycle(&sub_id);
     assert_eq!(lc.last_renewed_at, 1700200000);
@@ -996,13 +2594,11 @@ fn test_lifecycle_multiple_renewals_update_last_renewed() {
 }
 
 #[test]
-#[should_panic(expected = "Lifecycle data not found")]
 fn test_get_lifecycle_nonexistent_sub() {
     let (_env, client, _admin) = setup();
-    client.get_lifecycle(&999);
+    assert_eq!(client.try_get_lifecycle(&999), Err(Ok(Error::SubNotFound)));
 }
 #[test]
-#[should_panic(expected = "Subscription integrity violation: parameters tampered")]
 fn test_integrity_violation() {
     let (env, client, _admin) = setup();
 
@@ -1020,6 +2616,2560 @@ fn test_integrity_violation() {
     });
 
     client.approve_renewal(&sub_id, &1, &1000, &100);
-    client.acquire_renewal_lock(&sub_id, &200);
-    client.renew(&sub_id, &1, &200, &3, &10, &20260101, &true);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &200, &20260101),
+        Err(Ok(Error::IntegrityViolation))
+    );
+}
+
+#[test]
+fn test_state_at_ledger_from_journal() {
+    let (env, client, _admin) = setup();
+    let sub_id = 1745;
+    env.ledger().with_mut(|li| li.sequence_number = 10);
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.approve_renewal(&sub_id, &1, &1000, &1000);
+    client.approve_renewal(&sub_id, &2, &1000, &1000);
+
+    env.ledger().with_mut(|li| li.sequence_number = 20);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!renew_unpaid(&env, &client, &sub_id, &1, &500, &1));
+
+    env.ledger().with_mut(|li| li.sequence_number = 40);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &2, &500, &1));
+
+    env.ledger().with_mut(|li| li.sequence_number = 50);
+    client.cancel_sub(&sub_id, &None);
+
+    assert_eq!(client.get_state_at(&sub_id, &9), None);
+    assert_eq!(
+        client.get_state_at(&sub_id, &10),
+        Some(SubscriptionState::Active)
+    );
+    assert_eq!(
+        client.get_state_at(&sub_id, &39),
+        Some(SubscriptionState::Retrying)
+    );
+    assert_eq!(
+        client.get_state_at(&sub_id, &40),
+        Some(SubscriptionState::Active)
+    );
+    assert_eq!(
+        client.get_state_at(&sub_id, &1_000),
+        Some(SubscriptionState::Cancelled)
+    );
+    assert_eq!(client.get_state_journal(&sub_id).len(), 4);
+}
+
+// ── Frequency change tests ───────────────────────────────────────
+
+#[test]
+fn test_frequency_change_applies_at_cycle_boundary() {
+    let (env, client, _admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let merchant = Address::generate(&env);
+    let sub_id = 1754;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1500,
+        &sub_id,
+    );
+    env.ledger().with_mut(|li| li.timestamp = 50_000);
+    let effective_at = client.schedule_frequency_change(&merchant, &sub_id, &(7 * 86400));
+    assert_eq!(effective_at, 87_400);
+    assert_eq!(
+        client.get_frequency_change(&sub_id),
+        Some(FrequencyChange {
+            frequency: 7 * 86400,
+            effective_at,
+        })
+    );
+
+    // Renewing before the boundary keeps the old frequency
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &1));
+    assert_eq!(client.get_sub(&sub_id).frequency, 86400);
+
+    env.ledger().with_mut(|li| li.timestamp = 100_000);
+    assert_eq!(client.get_current_cycle_id(&sub_id), Some(effective_at));
+    client.approve_renewal(&sub_id, &2, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &2, &500, &2));
+
+    let data = last_event(&env, "frequency_changed");
+    let frequency: u64 = data
+        .get(Symbol::new(&env, "frequency"))
+        .unwrap()
+        .into_val(&env);
+    assert_eq!(frequency, 7 * 86400);
+    assert_eq!(client.get_sub(&sub_id).frequency, 7 * 86400);
+    assert_eq!(client.get_frequency_change(&sub_id), None);
+    assert_eq!(
+        client.compute_cycle_id(&sub_id, &1),
+        effective_at + 7 * 86400
+    );
+    // The rehashed terms still pass the integrity check
+    client.approve_renewal(&sub_id, &3, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &3, &500, &3));
+}
+
+#[test]
+fn test_frequency_change_cancelled_before_boundary() {
+    let (env, client, _admin) = setup();
+    let merchant = Address::generate(&env);
+    let sub_id = 1755;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert_eq!(
+        client.try_schedule_frequency_change(&merchant, &sub_id, &86400),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_schedule_frequency_change(&Address::generate(&env), &sub_id, &(7 * 86400)),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.schedule_frequency_change(&merchant, &sub_id, &(7 * 86400));
+    client.cancel_frequency_change(&sub_id);
+    assert_eq!(client.get_frequency_change(&sub_id), None);
+
+    env.ledger().with_mut(|li| li.timestamp += 2 * 86400);
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &1));
+    assert_eq!(client.get_sub(&sub_id).frequency, 86400);
+}
+
+#[test]
+fn test_amend_sub_rehashes_agreed_terms() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let sub_id = 1760;
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &sub_id);
+    client.schedule_frequency_change(&merchant, &sub_id, &(7 * 86400));
+
+    assert_eq!(
+        client.try_amend_sub(&sub_id, &800, &86400, &700),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_amend_sub(&sub_id, &800, &0, &1600),
+        Err(Ok(Error::InvalidConfig))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+    client.amend_sub(&sub_id, &800, &(30 * 86400), &1600);
+    assert_eq!(env.auths()[0].0, owner);
+    assert_eq!(env.auths()[1].0, merchant);
+    let data = last_event(&env, "amendment_applied");
+    let amount: i128 = data
+        .get(Symbol::new(&env, "amount"))
+        .unwrap()
+        .into_val(&env);
+    assert_eq!(amount, 800);
+
+    let sub = client.get_sub(&sub_id);
+    assert_eq!(
+        (sub.amount, sub.frequency, sub.spending_cap),
+        (800, 30 * 86400, 1600)
+    );
+    assert_eq!(client.get_frequency_change(&sub_id), None);
+    assert_eq!(client.get_current_cycle_id(&sub_id), Some(5_000));
+    assert!(client.check_invariants(&sub_id).is_empty());
+
+    // The new terms renew without tripping the integrity check
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &800, &1));
+}
+
+// ── Indexation tests ─────────────────────────────────────────────
+
+const YEAR: u64 = 31_536_000;
+
+fn init_indexed(env: &Env, client: &SubscriptionRenewalContractClient, sub_id: u64) {
+    client.init_indexed_sub(
+        &Address::generate(env),
+        &Address::generate(env),
+        &1000,
+        &86400,
+        &1100,
+        &sub_id,
+        &Indexation {
+            rate_bps: 300,
+            interval: YEAR,
+        },
+    );
+}
+
+#[test]
+fn test_indexed_amount_steps_up_to_spending_cap() {
+    let (env, client, _admin) = setup();
+    let token = syncro_testutils::TestToken::new(&env);
+    client.set_token(&token.address);
+    let sub_id = 1744;
+    init_indexed(&env, &client, sub_id);
+    assert_eq!(client.get_indexation(&sub_id).unwrap().rate_bps, 300);
+    let data = client.get_sub(&sub_id);
+    token.mint(&data.owner, 10_000);
+    token.approve(&data.owner, &client.address, &10_000, &10_000);
+
+    // Nothing is due before the first interval ends
+    env.ledger()
+        .with_mut(|li| li.timestamp = YEAR - SPEND_WINDOW);
+    client.approve_renewal(&sub_id, &1, &2000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &1000, &1));
+    assert_eq!(client.get_sub(&sub_id).amount, 1000);
+    assert_eq!(token.balance(&data.merchant), 1000);
+
+    // The stepped-up amount is what gets charged, and the old one refused
+    env.ledger().with_mut(|li| li.timestamp = YEAR);
+    assert_eq!(client.get_amount_due(&sub_id), 1030);
+    assert_eq!(client.get_sub(&sub_id).amount, 1000);
+    client.approve_renewal(&sub_id, &2, &2000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &2, &1000, &2),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert!(client.renew(&sub_id, &2, &1030, &2));
+    assert_eq!(client.get_sub(&sub_id).amount, 1030);
+    assert_eq!(token.balance(&data.merchant), 2030);
+    assert_eq!(client.get_receipt(&sub_id, &2).amount, 1030);
+
+    // Two intervals at once compound, rounding down at each step
+    env.ledger().with_mut(|li| li.timestamp = 3 * YEAR);
+    client.approve_renewal(&sub_id, &3, &2000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &3, &1091, &3));
+    assert_eq!(client.get_sub(&sub_id).amount, 1091);
+
+    // The next step would pass the spending cap, so it stops there
+    env.ledger().with_mut(|li| li.timestamp = 4 * YEAR);
+    client.approve_renewal(&sub_id, &4, &2000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &4, &1100, &4));
+    assert_eq!(client.get_sub(&sub_id).amount, 1100);
+    assert_eq!(token.balance(&data.merchant), 1000 + 1030 + 1091 + 1100);
+    assert!(client.check_invariants(&sub_id).is_empty());
+}
+
+#[test]
+fn test_indexation_clause_covered_by_integrity_hash() {
+    let (env, client, _admin) = setup();
+    let sub_id = 1745;
+    init_indexed(&env, &client, sub_id);
+
+    env.as_contract(&client.address, || {
+        env.storage().persistent().set(
+            &IndexationKey {
+                indexation_sub_id: sub_id,
+            },
+            &IndexationSchedule {
+                terms: Indexation {
+                    rate_bps: 1_000,
+                    interval: YEAR,
+                },
+                next_at: YEAR,
+            },
+        );
+    });
+
+    client.approve_renewal(&sub_id, &1, &2000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &1000, &1),
+        Err(Ok(Error::IntegrityViolation))
+    );
+}
+
+#[test]
+fn test_indexation_rate_bounded() {
+    let (env, client, _admin) = setup();
+    assert_eq!(
+        client.try_init_indexed_sub(
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &1000,
+            &86400,
+            &1100,
+            &1746,
+            &Indexation {
+                rate_bps: MAX_INDEXATION_BPS + 1,
+                interval: YEAR,
+            },
+        ),
+        Err(Ok(Error::InvalidIndexation))
+    );
+}
+
+#[test]
+fn test_taken_sub_ids_cannot_be_reinitialized() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let sub_id = 1747;
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &sub_id);
+
+    let intruder = Address::generate(&env);
+    assert_eq!(
+        client.try_init_sub(&intruder, &intruder, &1, &86400, &1000, &sub_id),
+        Err(Ok(Error::SubExists))
+    );
+    // Nor can an indexation schedule be attached to it
+    assert_eq!(
+        client.try_init_indexed_sub(
+            &intruder,
+            &intruder,
+            &500,
+            &86400,
+            &1000,
+            &sub_id,
+            &Indexation {
+                rate_bps: 300,
+                interval: YEAR,
+            },
+        ),
+        Err(Ok(Error::SubExists))
+    );
+    let data = client.get_sub(&sub_id);
+    assert_eq!((data.owner, data.merchant), (owner, merchant));
+    assert_eq!(client.get_indexation(&sub_id), None);
+    assert_eq!(client.get_owner_subs(&intruder).len(), 0);
+}
+
+#[test]
+fn test_init_sub_requires_the_owner() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    env.set_auths(&[]);
+    assert!(client
+        .try_init_sub(&owner, &Address::generate(&env), &500, &86400, &1000, &1748)
+        .is_err());
+    assert!(client.try_get_sub(&1748).is_err());
+}
+
+// ── Usage metering tests ─────────────────────────────────────────
+
+/// Stand-in for the metering contract: every period settles to 750.
+#[contract]
+struct MockMetering;
+
+#[contractimpl]
+impl MockMetering {
+    pub fn quote(_env: Env, _sub_id: u64, _period: u64) -> i128 {
+        750
+    }
+
+    pub fn settle(env: Env, sub_id: u64, period: u64) -> i128 {
+        env.storage().instance().set(&(sub_id, period), &750i128);
+        750
+    }
+
+    pub fn get_settled(env: Env, sub_id: u64, period: u64) -> Option<i128> {
+        env.storage().instance().get(&(sub_id, period))
+    }
+}
+
+#[test]
+fn test_metered_sub_charges_settled_amount() {
+    let (env, client, _admin) = setup();
+
+    let metering = env.register(MockMetering, ());
+    client.set_metering_contract(&metering);
+
+    let user = Address::generate(&env);
+    let sub_id = 1000;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_metered(&sub_id, &true);
+    assert!(client.is_metered(&sub_id));
+
+    // Caller-supplied amount is ignored; the settled 750 fits the approval
+    client.approve_renewal(&sub_id, &1, &800, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &0, &20260101));
+    assert_eq!(
+        MockMeteringClient::new(&env, &metering).get_settled(&sub_id, &20260101),
+        Some(750)
+    );
+}
+
+#[test]
+fn test_metered_amount_checked_against_approval() {
+    let (env, client, _admin) = setup();
+
+    let metering = env.register(MockMetering, ());
+    client.set_metering_contract(&metering);
+
+    let user = Address::generate(&env);
+    let sub_id = 1001;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_metered(&sub_id, &true);
+
+    // Settled 750 exceeds the approval's max_spend of 500
+    client.approve_renewal(&sub_id, &1, &500, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &500, &20260101),
+        Err(Ok(Error::ApprovalInvalid))
+    );
+}
+
+#[test]
+fn test_metered_sub_requires_metering_contract() {
+    let (env, client, _admin) = setup();
+
+    let user = Address::generate(&env);
+    let sub_id = 1002;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_metered(&sub_id, &true);
+
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert_eq!(
+        client.try_renew(&sub_id, &1, &500, &20260101),
+        Err(Ok(Error::PeerNotSet))
+    );
+}
+
+// ── Receipt tests ────────────────────────────────────────────────
+
+/// Stand-in for the receipt contract: counts mints per contract instance.
+#[contract]
+struct MockReceipts;
+
+#[contractimpl]
+impl MockReceipts {
+    pub fn mint(
+        env: Env,
+        _owner: Address,
+        _sub_id: u64,
+        _cycle_id: u64,
+        _amount: i128,
+        _merchant: Address,
+    ) -> u64 {
+        let count: u64 = env.storage().instance().get(&0u32).unwrap_or(0) + 1;
+        env.storage().instance().set(&0u32, &count);
+        count
+    }
+
+    pub fn minted(env: Env) -> u64 {
+        env.storage().instance().get(&0u32).unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_receipt_minted_on_success_only() {
+    let (env, client, _admin) = setup();
+
+    let receipts = env.register(MockReceipts, ());
+    let receipts_client = MockReceiptsClient::new(&env, &receipts);
+    client.set_receipt_contract(&receipts);
+
+    let user = Address::generate(&env);
+    let sub_id = 1100;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+
+    client.approve_renewal(&sub_id, &1, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &20260101);
+    assert_eq!(receipts_client.minted(), 0);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 20;
+    });
+    client.approve_renewal(&sub_id, &2, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.renew(&sub_id, &2, &500, &20260101);
+    assert_eq!(receipts_client.minted(), 1);
+}
+
+#[test]
+fn test_get_receipt_records_each_charge() {
+    let (env, client, admin) = setup();
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let sub_id = 1749;
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &sub_id);
+    let terms = BytesN::from_array(&env, &[9; 32]);
+    client.anchor_metadata(&sub_id, &terms);
+    client.set_protocol_fee(&admin, &200);
+
+    env.ledger().with_mut(|li| li.sequence_number = 30);
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &7));
+
+    assert_eq!(
+        client.get_receipt(&sub_id, &7),
+        Receipt {
+            amount: 500,
+            fee: 10,
+            payer: owner,
+            merchant,
+            ledger: 30,
+            terms_hash: client.get_sub(&sub_id).integrity_hash,
+            metadata_hash: Some(terms),
+        }
+    );
+    assert!(client.try_get_receipt(&sub_id, &8).is_err());
+}
+
+// ── Loyalty tests ────────────────────────────────────────────────
+
+/// Stand-in for the loyalty contract: grants a fixed discount of 100 and
+/// remembers the amount it was credited with.
+#[contract]
+struct MockLoyalty;
+
+#[contractimpl]
+impl MockLoyalty {
+    pub fn on_renewal(
+        env: Env,
+        _sub_id: u64,
+        _cycle_id: u64,
+        _owner: Address,
+        _merchant: Address,
+        amount: i128,
+    ) -> i128 {
+        env.storage().instance().set(&0u32, &amount);
+        100
+    }
+
+    pub fn credited(env: Env) -> Option<i128> {
+        env.storage().instance().get(&0u32)
+    }
+}
+
+/// Stand-in for the receipt contract that remembers the minted amount.
+#[contract]
+struct MockAmountReceipts;
+
+#[contractimpl]
+impl MockAmountReceipts {
+    pub fn mint(
+        env: Env,
+        _owner: Address,
+        _sub_id: u64,
+        _cycle_id: u64,
+        amount: i128,
+        _merchant: Address,
+    ) -> u64 {
+        env.storage().instance().set(&0u32, &amount);
+        1
+    }
+
+    pub fn amount(env: Env) -> i128 {
+        env.storage().instance().get(&0u32).unwrap()
+    }
+}
+
+#[test]
+fn test_loyalty_credited_and_discount_reaches_receipt() {
+    let (env, client, _admin) = setup();
+
+    let loyalty = env.register(MockLoyalty, ());
+    let receipts = env.register(MockAmountReceipts, ());
+    client.set_loyalty_contract(&loyalty);
+    client.set_receipt_contract(&receipts);
+
+    let user = Address::generate(&env);
+    let sub_id = 1150;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+
+    client.approve_renewal(&sub_id, &1, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &20260101);
+    assert_eq!(MockLoyaltyClient::new(&env, &loyalty).credited(), None);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 20;
+    });
+    client.approve_renewal(&sub_id, &2, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &2, &500, &20260101));
+    assert_eq!(MockLoyaltyClient::new(&env, &loyalty).credited(), Some(500));
+    assert_eq!(MockAmountReceiptsClient::new(&env, &receipts).amount(), 400);
+}
+
+#[test]
+fn test_loyalty_discount_on_partial_payments_goes_to_funders() {
+    let (env, client, _admin) = setup();
+    let token = syncro_testutils::TestToken::new(&env);
+    client.set_token(&token.address);
+    client.set_loyalty_contract(&env.register(MockLoyalty, ()));
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let wallet = Address::generate(&env);
+    let card = Address::generate(&env);
+    token.fund(&[&wallet, &card], 1000);
+    let sub_id = 17521;
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &sub_id);
+
+    client.pay_partial(&sub_id, &1, &wallet, &300);
+    assert!(client.pay_partial(&sub_id, &1, &card, &200));
+
+    // The discount of 100 is split 60/40 like the payments, not sent to the owner
+    assert_eq!(token.balance(&wallet), 760);
+    assert_eq!(token.balance(&card), 840);
+    assert_eq!(token.balance(&owner), 0);
+    assert_eq!(token.balance(&merchant), 400);
+}
+
+// ── Agent registry tests ─────────────────────────────────────────
+
+/// Stand-in for the agent registry: agents listed via `grant` hold every scope.
+#[contract]
+struct MockAgents;
+
+#[contractimpl]
+impl MockAgents {
+    pub fn grant(env: Env, agent: Address) {
+        env.storage().instance().set(&agent, &true);
+    }
+
+    pub fn revoke(env: Env, agent: Address) {
+        env.storage().instance().remove(&agent);
+    }
+
+    pub fn has_scope(env: Env, agent: Address, _scope: Scope) -> bool {
+        env.storage().instance().has(&agent)
+    }
+}
+
+#[test]
+fn test_scoped_agent_can_lock_and_renew() {
+    let (env, client, _admin) = setup();
+
+    let agents = env.register(MockAgents, ());
+    client.set_agent_registry(&agents);
+    let agent = Address::generate(&env);
+    MockAgentsClient::new(&env, &agents).grant(&agent);
+
+    let user = Address::generate(&env);
+    let sub_id = 1200;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+
+    client.acquire_renewal_lock(&agent, &sub_id, &200);
+    assert!(client.renew(&sub_id, &1, &500, &20260101));
+}
+
+#[test]
+fn test_unscoped_agent_cannot_lock() {
+    let (env, client, _admin) = setup();
+
+    let agents = env.register(MockAgents, ());
+    client.set_agent_registry(&agents);
+
+    assert_eq!(
+        client.try_acquire_renewal_lock(&Address::generate(&env), &1201, &200),
+        Err(Ok(Error::AgentNotAuthorized))
+    );
+}
+
+#[test]
+fn test_registry_grant_cached_until_invalidated_or_lapsed() {
+    let (env, client, _admin) = setup();
+    let agents = MockAgentsClient::new(&env, &env.register(MockAgents, ()));
+    client.set_agent_registry(&agents.address);
+    let agent = Address::generate(&env);
+    agents.grant(&agent);
+
+    client.acquire_renewal_lock(&agent, &1250, &200);
+    agents.revoke(&agent);
+    // The cached grant still stands...
+    client.acquire_renewal_lock(&agent, &1251, &200);
+    // ...until someone relays the revocation
+    client.invalidate_agent_cache(&agent);
+    assert!(client
+        .try_acquire_renewal_lock(&agent, &1252, &200)
+        .is_err());
+
+    // Delegated approvals are cached and relayed the same way
+    agents.grant(&agent);
+    let user = Address::generate(&env);
+    client.init_sub(&user, &Address::generate(&env), &500, &86400, &1000, &1255);
+    client.set_delegation_budget(
+        &user,
+        &agent,
+        &Some(DelegationBudget {
+            max_outstanding: 1000,
+            max_approvals: 2,
+        }),
+    );
+    client.approve_renewal_as(&agent, &1255, &1, &500, &100);
+    agents.revoke(&agent);
+    client.invalidate_agent_cache(&agent);
+    assert_eq!(
+        client.try_approve_renewal_as(&agent, &1255, &2, &500, &100),
+        Err(Ok(Error::AgentNotAuthorized))
+    );
+
+    // Without a relay, the grant lapses on its own
+    agents.grant(&agent);
+    client.acquire_renewal_lock(&agent, &1253, &200);
+    agents.revoke(&agent);
+    env.ledger()
+        .with_mut(|li| li.sequence_number = AGENT_CACHE_LEDGERS);
+    assert!(client
+        .try_acquire_renewal_lock(&agent, &1254, &200)
+        .is_err());
+}
+
+#[test]
+fn test_agent_lock_rate_limit() {
+    let (env, client, admin) = setup();
+    client.set_agent_rate_limit(&admin, &LimitConfig::fixed_window(2, 3_600));
+
+    let agent = Address::generate(&env);
+    client.acquire_renewal_lock(&agent, &1202, &200);
+    client.acquire_renewal_lock(&agent, &1203, &200);
+    // Other agents have their own budget
+    acquire_lock(&env, &client, 1204, 200);
+    assert_eq!(
+        client.try_acquire_renewal_lock(&agent, &1205, &200),
+        Err(Ok(Error::AgentRateLimited))
+    );
+}
+
+// ── Callback registry tests ──────────────────────────────────────
+
+/// Stand-in for the callback registry: keeps every notification it receives.
+#[contract]
+struct MockCallbacks;
+
+#[contractimpl]
+impl MockCallbacks {
+    pub fn notify(env: Env, _emitter: Address, notification: Notification) -> u32 {
+        let mut sent: soroban_sdk::Vec<Notification> = env
+            .storage()
+            .instance()
+            .get(&0u32)
+            .unwrap_or(soroban_sdk::Vec::new(&env));
+        sent.push_back(notification);
+        env.storage().instance().set(&0u32, &sent);
+        1
+    }
+
+    pub fn sent(env: Env) -> soroban_sdk::Vec<Notification> {
+        env.storage()
+            .instance()
+            .get(&0u32)
+            .unwrap_or(soroban_sdk::Vec::new(&env))
+    }
+}
+
+#[test]
+fn test_renewal_events_pushed_to_callback_registry() {
+    let (env, client, _admin) = setup();
+
+    let callbacks = env.register(MockCallbacks, ());
+    client.set_callback_registry(&callbacks);
+
+    let user = Address::generate(&env);
+    let sub_id = 1250;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+
+    client.approve_renewal(&sub_id, &1, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    renew_unpaid(&env, &client, &sub_id, &1, &500, &20260101);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 20;
+    });
+    client.approve_renewal(&sub_id, &2, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.renew(&sub_id, &2, &500, &20260101);
+    client.cancel_sub(&sub_id, &None);
+
+    let sent = MockCallbacksClient::new(&env, &callbacks).sent();
+    assert_eq!(sent.len(), 3);
+    assert_eq!(sent.get(0).unwrap().kind, Symbol::new(&env, "renew_failed"));
+    assert_eq!(sent.get(1).unwrap().kind, Symbol::new(&env, "renewed"));
+    assert_eq!(sent.get(2).unwrap().kind, Symbol::new(&env, "cancelled"));
+    let renewed = sent.get(1).unwrap();
+    assert_eq!(renewed.sub_id, sub_id);
+    assert_eq!(renewed.merchant, merchant);
+    assert_eq!(renewed.cycle_id, 20260101);
+    assert_eq!(renewed.amount, 500);
+}
+
+/// Stand-in for a callback registry that has not allowlisted the renewal
+/// contract as an emitter.
+#[contract]
+struct RejectingCallbacks;
+
+#[contractimpl]
+impl RejectingCallbacks {
+    pub fn notify(_env: Env, _emitter: Address, _notification: Notification) -> u32 {
+        panic!("not an emitter")
+    }
+}
+
+#[test]
+fn test_refused_notifications_do_not_block_renewals() {
+    let (env, client, _admin) = setup();
+    client.set_callback_registry(&env.register(RejectingCallbacks, ()));
+
+    let sub_id = 1251;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.approve_renewal(&sub_id, &1, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &20260101));
+    client.cancel_sub(&sub_id, &None);
+    assert_eq!(client.get_sub(&sub_id).state, SubscriptionState::Cancelled);
+}
+
+// ── Prepay tests ─────────────────────────────────────────────────
+
+/// Stand-in for the prepay contract: covers cycles after the first at 450.
+#[contract]
+struct MockPrepay;
+
+#[contractimpl]
+impl MockPrepay {
+    pub fn draw(_env: Env, _sub_id: u64, cycle_id: u64) -> i128 {
+        if cycle_id > 1 {
+            450
+        } else {
+            0
+        }
+    }
+}
+
+#[test]
+fn test_prepaid_cycle_drawn_from_deposit() {
+    let (env, client, _admin) = setup();
+
+    let prepay = env.register(MockPrepay, ());
+    let receipts = env.register(MockAmountReceipts, ());
+    client.set_prepay_contract(&prepay);
+    client.set_receipt_contract(&receipts);
+    let receipts = MockAmountReceiptsClient::new(&env, &receipts);
+
+    let user = Address::generate(&env);
+    let sub_id = 1300;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+
+    client.approve_renewal(&sub_id, &1, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &1));
+    assert_eq!(receipts.amount(), 500);
+
+    client.approve_renewal(&sub_id, &2, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &2, &500, &2));
+    assert_eq!(receipts.amount(), 450);
+}
+
+// ── Circuit breaker tests ────────────────────────────────────────
+
+/// Stand-in for the circuit breaker: `trip` pauses every contract, once
+/// `guardian` is allowed to.
+#[contract]
+struct MockBreaker;
+
+#[contractimpl]
+impl MockBreaker {
+    pub fn add_guardian(env: Env, guardian: Address) {
+        env.storage().instance().set(&guardian, &true);
+    }
+
+    pub fn trip(env: Env, guardian: Address, _reason: Symbol) {
+        guardian.require_auth();
+        assert!(env.storage().instance().has(&guardian), "not a guardian");
+        env.storage().instance().set(&0u32, &true);
+    }
+
+    pub fn is_paused(env: Env, _contract: Address) -> bool {
+        env.storage().instance().has(&0u32)
+    }
+}
+
+#[test]
+fn test_tripped_breaker_pauses_renewals() {
+    let (env, client, _admin) = setup();
+    let breaker = MockBreakerClient::new(&env, &env.register(MockBreaker, ()));
+    client.set_circuit_breaker(&breaker.address);
+    assert!(!client.is_paused());
+
+    let guardian = Address::generate(&env);
+    breaker.add_guardian(&guardian);
+    breaker.trip(&guardian, &Symbol::new(&env, "exploit"));
+    assert!(client.is_paused());
+    assert_eq!(
+        client.try_acquire_renewal_lock(&Address::generate(&env), &1350, &200),
+        Err(Ok(Error::Paused))
+    );
+}
+
+#[test]
+fn test_pause_all_trips_the_breaker() {
+    let (env, client, admin) = setup();
+    let reason = Symbol::new(&env, "exploit");
+    assert_eq!(
+        client.try_pause_all(&admin, &reason),
+        Err(Ok(Error::PeerNotSet))
+    );
+
+    let breaker = MockBreakerClient::new(&env, &env.register(MockBreaker, ()));
+    client.set_circuit_breaker(&breaker.address);
+    // Not yet a guardian of the breaker
+    assert_eq!(
+        client.try_pause_all(&admin, &reason),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(!client.is_paused());
+
+    breaker.add_guardian(&client.address);
+    let pauser = Address::generate(&env);
+    client.grant_role(&pauser, &PAUSER);
+    client.pause_all(&pauser, &reason);
+    assert!(breaker.is_paused(&Address::generate(&env)));
+    let info = client.get_pause_info().unwrap();
+    assert_eq!((info.by, info.incident), (pauser, Some(reason)));
+
+    // Lifting the local pause leaves renewals held by the breaker
+    client.set_paused(&admin, &false, &None);
+    assert!(client.is_paused());
+}
+
+// ── Protocol fee tests ───────────────────────────────────────────
+
+/// Stand-in for the utility token: merchants listed via `set_discount` get
+/// that discount.
+#[contract]
+struct MockUtilityToken;
+
+#[contractimpl]
+impl MockUtilityToken {
+    pub fn set_discount(env: Env, account: Address, discount_bps: u32) {
+        env.storage().instance().set(&account, &discount_bps);
+    }
+
+    pub fn fee_discount_bps(env: Env, account: Address) -> u32 {
+        env.storage().instance().get(&account).unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_protocol_fee_discounted_by_staking_tier() {
+    let (env, client, admin) = setup();
+    let staker = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    assert_eq!(client.quote_protocol_fee(&merchant, &10_000), (0, 0));
+
+    client.set_protocol_fee(&admin, &200);
+    assert_eq!(client.quote_protocol_fee(&merchant, &10_000), (200, 0));
+    // Quoting does not overflow however large the amount
+    assert_eq!(
+        client.quote_protocol_fee(&merchant, &i128::MAX),
+        (i128::MAX / 50, 0)
+    );
+
+    let token = MockUtilityTokenClient::new(&env, &env.register(MockUtilityToken, ()));
+    token.set_discount(&staker, &2_500);
+    client.set_utility_token(&token.address);
+    assert_eq!(client.quote_protocol_fee(&staker, &10_000), (150, 2_500));
+    assert_eq!(client.quote_protocol_fee(&merchant, &10_000), (200, 0));
+
+    // The fee is computed at charge time on a successful renewal
+    client.init_sub(
+        &Address::generate(&env),
+        &staker,
+        &500,
+        &86400,
+        &1000,
+        &1360,
+    );
+    client.approve_renewal(&1360, &1, &1000, &100);
+    acquire_lock(&env, &client, 1360, 200);
+    assert!(client.renew(&1360, &1, &500, &20260101));
+}
+
+#[test]
+fn test_protocol_fee_bounded() {
+    let (_env, client, admin) = setup();
+    assert_eq!(
+        client.try_set_protocol_fee(&admin, &10_001),
+        Err(Ok(Error::InvalidConfig))
+    );
+}
+
+#[test]
+fn test_renewal_splits_charge_between_payouts() {
+    let (env, client, admin) = setup();
+    let merchant = Address::generate(&env);
+    let platform = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let sub_id = 1743;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_payouts(
+        &merchant,
+        &sub_id,
+        &soroban_sdk::vec![
+            &env,
+            Payout {
+                recipient: platform.clone(),
+                weight: 1,
+            },
+            Payout {
+                recipient: creator.clone(),
+                weight: 2,
+            },
+        ],
+    );
+    assert_eq!(client.get_payouts(&sub_id).len(), 2);
+
+    // The split is taken net of the protocol fee; 490 doesn't divide by
+    // three, so the first recipient picks up the remainder.
+    client.set_protocol_fee(&admin, &200);
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &20260101));
+
+    let payouts: Vec<PayoutShare> = last_event(&env, "renewal_success")
+        .get(Symbol::new(&env, "payouts"))
+        .unwrap()
+        .into_val(&env);
+    assert_eq!(
+        payouts,
+        soroban_sdk::vec![
+            &env,
+            PayoutShare {
+                recipient: platform,
+                amount: 164,
+            },
+            PayoutShare {
+                recipient: creator,
+                amount: 326,
+            },
+        ]
+    );
+
+    // Clearing the payouts sends everything back to the merchant
+    client.set_payouts(&merchant, &sub_id, &Vec::new(&env));
+    assert!(client.get_payouts(&sub_id).is_empty());
+}
+
+#[test]
+fn test_payout_recipients_must_be_distinct() {
+    let (env, client, _admin) = setup();
+    let merchant = Address::generate(&env);
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &1744,
+    );
+    let payout = Payout {
+        recipient: Address::generate(&env),
+        weight: 1,
+    };
+    assert_eq!(
+        client.try_set_payouts(
+            &merchant,
+            &1744,
+            &soroban_sdk::vec![&env, payout.clone(), payout],
+        ),
+        Err(Ok(Error::InvalidPayouts))
+    );
+}
+
+#[test]
+fn test_merchant_key_acts_for_merchant_until_revoked() {
+    let (env, client, _admin) = setup();
+    let merchant = Address::generate(&env);
+    let billing = Address::generate(&env);
+    let sub_id = 1747;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    let payouts = soroban_sdk::vec![
+        &env,
+        Payout {
+            recipient: Address::generate(&env),
+            weight: 1,
+        },
+    ];
+    assert!(client.try_set_payouts(&billing, &sub_id, &payouts).is_err());
+
+    client.add_merchant_key(&merchant, &billing);
+    assert_eq!(
+        client.get_merchant_keys(&merchant),
+        soroban_sdk::vec![&env, billing.clone()]
+    );
+    client.set_payouts(&billing, &sub_id, &payouts);
+    assert_eq!(env.auths()[0].0, billing);
+    client.set_fallback_plan(&billing, &sub_id, &Some(200));
+    assert_eq!(client.get_fallback_plan(&sub_id), Some(200));
+
+    client.revoke_merchant_key(&merchant, &billing);
+    assert!(client.get_merchant_keys(&merchant).is_empty());
+    assert!(client
+        .try_set_payouts(&billing, &sub_id, &Vec::new(&env))
+        .is_err());
+}
+
+fn profile_bounds() -> ProfileBounds {
+    ProfileBounds {
+        max_retries: 5,
+        max_cooldown_ledgers: 100,
+        max_grace_ledgers: 1_000,
+        min_fee_bps: 50,
+        max_fee_bps: 500,
+        max_agents: 2,
+    }
+}
+
+#[test]
+fn test_merchant_profile_must_stay_in_bounds() {
+    let (env, client, admin) = setup();
+    let merchant = Address::generate(&env);
+    let mut profile = MerchantProfile {
+        max_retries: 2,
+        cooldown_ledgers: 10,
+        grace_ledgers: 0,
+        fee_bps: Some(100),
+        allowed_agents: Vec::new(&env),
+    };
+    assert!(client
+        .try_set_merchant_profile(&merchant, &profile)
+        .is_err());
+
+    client.set_profile_bounds(&admin, &profile_bounds());
+    client.set_merchant_profile(&merchant, &profile);
+    assert_eq!(
+        client.get_merchant_profile(&merchant),
+        Some(profile.clone())
+    );
+
+    profile.fee_bps = Some(20);
+    assert!(client
+        .try_set_merchant_profile(&merchant, &profile)
+        .is_err());
+    profile.fee_bps = None;
+    profile.max_retries = 6;
+    assert!(client
+        .try_set_merchant_profile(&merchant, &profile)
+        .is_err());
+
+    client.clear_merchant_profile(&merchant);
+    assert_eq!(client.get_merchant_profile(&merchant), None);
+}
+
+#[test]
+fn test_merchant_profile_applies_to_its_subs() {
+    let (env, client, admin) = setup();
+    let merchant = Address::generate(&env);
+    let agent = Address::generate(&env);
+    client.set_protocol_fee(&admin, &200);
+    client.set_profile_bounds(&admin, &profile_bounds());
+    client.set_merchant_profile(
+        &merchant,
+        &MerchantProfile {
+            max_retries: 0,
+            cooldown_ledgers: 5,
+            grace_ledgers: 30,
+            fee_bps: Some(100),
+            allowed_agents: soroban_sdk::vec![&env, agent.clone()],
+        },
+    );
+    assert_eq!(client.quote_protocol_fee(&merchant, &10_000), (100, 0));
+
+    let sub_id = 1370;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert!(client
+        .try_acquire_renewal_lock(&Address::generate(&env), &sub_id, &200)
+        .is_err());
+
+    // The keeper asks for 3 retries; the profile allows none, but its grace
+    // period keeps the subscription retrying for 30 ledgers
+    let mut cycle = 0;
+    for ledger in [0, 10, 20, 30] {
+        env.ledger().with_mut(|li| li.sequence_number = ledger);
+        cycle += 1;
+        client.approve_renewal(&sub_id, &cycle, &1000, &(ledger + 100));
+        client.acquire_renewal_lock(&agent, &sub_id, &200);
+        assert!(!renew_unpaid(
+            &env, &client, &sub_id, &cycle, &500, &20260101
+        ));
+    }
+    let data = client.get_sub(&sub_id);
+    assert_eq!(data.failure_count, 4);
+    assert_eq!(data.state, SubscriptionState::Failed);
+}
+
+#[test]
+fn test_default_grace_period_defers_failure() {
+    let (env, client, admin) = setup();
+    client.set_grace_period(&admin, &30);
+    let sub_id = 1756;
+    let merchant = Address::generate(&env);
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 0,
+            cooldown_ledgers: 0,
+        },
+    );
+
+    let mut cycle = 0;
+    for ledger in [0, 10, 20, 30] {
+        env.ledger().with_mut(|li| li.sequence_number = ledger);
+        cycle += 1;
+        client.approve_renewal(&sub_id, &cycle, &1000, &(ledger + 100));
+        acquire_lock(&env, &client, sub_id, 200);
+        assert!(!renew_unpaid(
+            &env, &client, &sub_id, &cycle, &500, &20260101
+        ));
+        if ledger == 0 {
+            let data = last_event(&env, "grace_period_started");
+            let ends_at: u32 = data
+                .get(Symbol::new(&env, "ends_at"))
+                .unwrap()
+                .into_val(&env);
+            assert_eq!(ends_at, 30);
+        }
+        if ledger == 20 {
+            assert_eq!(client.get_status(&sub_id), SubscriptionState::Retrying);
+            assert_eq!(client.get_grace_remaining(&sub_id), 10);
+        }
+    }
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Failed);
+    assert_eq!(client.get_grace_remaining(&sub_id), 0);
+}
+
+#[test]
+fn test_grace_restarts_after_recovery() {
+    let (env, client, admin) = setup();
+    let merchant = Address::generate(&env);
+    client.set_profile_bounds(&admin, &profile_bounds());
+    client.set_merchant_profile(
+        &merchant,
+        &MerchantProfile {
+            max_retries: 0,
+            cooldown_ledgers: 0,
+            grace_ledgers: 20,
+            fee_bps: None,
+            allowed_agents: Vec::new(&env),
+        },
+    );
+    let sub_id = 1371;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+
+    let renew = |ledger: u32, cycle: u64, succeed: bool| {
+        env.ledger().with_mut(|li| li.sequence_number = ledger);
+        client.approve_renewal(&sub_id, &cycle, &1000, &(ledger + 100));
+        acquire_lock(&env, &client, sub_id, 200);
+        if succeed {
+            client.renew(&sub_id, &cycle, &500, &(20260100 + cycle));
+        } else {
+            renew_unpaid(&env, &client, &sub_id, &cycle, &500, &(20260100 + cycle));
+        }
+    };
+    renew(0, 1, false);
+    renew(15, 2, true);
+    // A new run of failures gets a fresh grace period
+    renew(30, 3, false);
+    renew(45, 4, false);
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Retrying);
+    renew(50, 5, false);
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Failed);
+}
+
+// ── Ownership transfer tests ─────────────────────────────────────
+
+#[test]
+fn test_owner_can_transfer_sub() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    client.init_sub(&owner, &Address::generate(&env), &500, &86400, &1000, &1400);
+
+    client.transfer_sub(&1400, &owner, &buyer);
+    assert_eq!(client.get_sub(&1400).owner, buyer);
+}
+
+#[test]
+fn test_approved_operator_can_transfer_sub_once() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    client.init_sub(&owner, &Address::generate(&env), &500, &86400, &1000, &1401);
+
+    client.approve_sub_transfer(&1401, &Some(operator.clone()));
+    assert_eq!(
+        client.get_sub_transfer_operator(&1401),
+        Some(operator.clone())
+    );
+
+    client.transfer_sub(&1401, &operator, &buyer);
+    assert_eq!(client.get_sub(&1401).owner, buyer);
+    assert_eq!(client.get_sub_transfer_operator(&1401), None);
+    assert!(client
+        .try_transfer_sub(&1401, &operator, &Address::generate(&env))
+        .is_err());
+}
+
+#[test]
+fn test_stranger_cannot_transfer_sub() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    client.init_sub(&owner, &Address::generate(&env), &500, &86400, &1000, &1402);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_transfer_sub(&1402, &stranger, &stranger),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_cancelled_sub_cannot_be_transferred() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    client.init_sub(&owner, &Address::generate(&env), &500, &86400, &1000, &1403);
+    client.cancel_sub(&1403, &None);
+
+    assert_eq!(
+        client.try_transfer_sub(&1403, &owner, &Address::generate(&env)),
+        Err(Ok(Error::NotTransferable))
+    );
+}
+
+#[test]
+fn test_recovery_contract_moves_sub_and_drops_approval() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let recovery = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    client.init_sub(&owner, &Address::generate(&env), &500, &86400, &1000, &1404);
+    client.approve_sub_transfer(&1404, &Some(Address::generate(&env)));
+    client.set_recovery_contract(&recovery);
+
+    client.recover_sub(&1404, &owner, &new_owner);
+    assert_eq!(client.get_sub(&1404).owner, new_owner);
+    assert_eq!(client.get_sub_transfer_operator(&1404), None);
+}
+
+#[test]
+fn test_recover_sub_requires_recovery_contract() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    client.init_sub(&owner, &Address::generate(&env), &500, &86400, &1000, &1405);
+
+    assert_eq!(
+        client.try_recover_sub(&1405, &owner, &Address::generate(&env)),
+        Err(Ok(Error::PeerNotSet))
+    );
+}
+
+#[test]
+fn test_recover_sub_checks_current_owner() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    client.init_sub(&owner, &Address::generate(&env), &500, &86400, &1000, &1406);
+    client.set_recovery_contract(&Address::generate(&env));
+
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_recover_sub(&1406, &stranger, &stranger),
+        Err(Ok(Error::NotOwner))
+    );
+}
+
+// ── Payer tests ──────────────────────────────────────────────────
+
+#[test]
+fn test_payer_approves_and_is_charged_while_owner_cancels() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let parent = Address::generate(&env);
+    let sub_id = 1741;
+    client.init_sub(
+        &owner,
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert_eq!(client.get_payer(&sub_id), owner);
+
+    client.set_payer(&sub_id, &Some(parent.clone()));
+    let auths = env.auths();
+    assert!(auths.iter().any(|(signer, _)| *signer == owner));
+    assert!(auths.iter().any(|(signer, _)| *signer == parent));
+    assert_eq!(client.get_payer(&sub_id), parent);
+
+    client.approve_renewal(&sub_id, &1, &1000, &1000);
+    assert_eq!(env.auths()[0].0, parent);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &1));
+    assert_eq!(client.get_approval_utilization(&parent).len(), 1);
+    assert!(client.get_approval_utilization(&owner).is_empty());
+
+    client.cancel_sub(&sub_id, &None);
+    assert_eq!(env.auths()[0].0, owner);
+}
+
+#[test]
+fn test_transfer_drops_payer() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let sub_id = 1742;
+    client.init_sub(
+        &owner,
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_payer(&sub_id, &Some(Address::generate(&env)));
+
+    client.transfer_sub(&sub_id, &owner, &buyer);
+    assert_eq!(client.get_payer(&sub_id), buyer);
+}
+
+// ── Payment strategy tests ───────────────────────────────────────
+
+/// Payment strategy that pays the whole charge, or once set failing only
+/// what it was told to.
+#[contract]
+struct MockStrategy;
+
+#[contractimpl]
+impl MockStrategy {
+    pub fn set_failing(env: Env) {
+        env.storage().instance().set(&0u32, &0i128);
+    }
+
+    pub fn set_short(env: Env, paid: i128) {
+        env.storage().instance().set(&0u32, &paid);
+    }
+
+    pub fn settle(
+        env: Env,
+        renewal: Address,
+        sub_id: u64,
+        _cycle_id: u64,
+        _payer: Address,
+        _merchant: Address,
+        amount: i128,
+    ) -> i128 {
+        renewal.require_auth();
+        if let Some(paid) = env.storage().instance().get(&0u32) {
+            return paid;
+        }
+        env.storage().instance().set(&sub_id, &amount);
+        amount
+    }
+
+    pub fn settled(env: Env, sub_id: u64) -> Option<i128> {
+        env.storage().instance().get(&sub_id)
+    }
+}
+
+#[test]
+fn test_payment_strategy_settles_instead_of_pulling() {
+    let (env, client, _admin) = setup();
+    let strategy = MockStrategyClient::new(&env, &env.register(MockStrategy, ()));
+    let merchant = Address::generate(&env);
+    let sub_id = 1761;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert_eq!(
+        client.try_set_payment_strategy(
+            &Address::generate(&env),
+            &sub_id,
+            &Some(strategy.address.clone())
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_payment_strategy(&merchant, &sub_id, &Some(strategy.address.clone()));
+    assert_eq!(
+        client.get_payment_strategy(&sub_id),
+        Some(strategy.address.clone())
+    );
+
+    // The payer's token pull would fail, but the strategy settles instead
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(renew_unpaid(&env, &client, &sub_id, &1, &500, &1));
+    assert_eq!(strategy.settled(&sub_id), Some(500));
+
+    // A strategy paying nothing fails the renewal
+    strategy.set_failing();
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.approve_renewal(&sub_id, &2, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!client.renew(&sub_id, &2, &500, &2));
+    assert_eq!(client.get_sub(&sub_id).failure_count, 1);
+
+    client.set_payment_strategy(&merchant, &sub_id, &None);
+    assert_eq!(client.get_payment_strategy(&sub_id), None);
+}
+
+#[test]
+fn test_payment_strategy_settling_short_fails_the_renewal() {
+    let (env, client, _admin) = setup();
+    let strategy = MockStrategyClient::new(&env, &env.register(MockStrategy, ()));
+    let merchant = Address::generate(&env);
+    let sub_id = 17611;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_payment_strategy(&merchant, &sub_id, &Some(strategy.address.clone()));
+    strategy.set_short(&1);
+
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!client.renew(&sub_id, &1, &500, &1));
+    let data = client.get_sub(&sub_id);
+    assert_eq!(data.failure_count, 1);
+    assert_eq!(data.state, SubscriptionState::Retrying);
+    assert!(client.try_get_receipt(&sub_id, &1).is_err());
+}
+
+#[test]
+fn test_strategy_settlement_records_only_what_was_settled() {
+    let (env, client, admin) = setup();
+    let strategy = MockStrategyClient::new(&env, &env.register(MockStrategy, ()));
+    let loyalty = env.register(MockLoyalty, ());
+    client.set_loyalty_contract(&loyalty);
+    client.set_protocol_fee(&admin, &200);
+    let merchant = Address::generate(&env);
+    let sub_id = 17612;
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_payouts(
+        &merchant,
+        &sub_id,
+        &soroban_sdk::vec![
+            &env,
+            Payout {
+                recipient: Address::generate(&env),
+                weight: 1,
+            },
+        ],
+    );
+    client.set_payment_strategy(&merchant, &sub_id, &Some(strategy.address.clone()));
+
+    // The strategy paid the merchant in full: no fee, split or discount
+    // was taken out of it here
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &1));
+    let fee_charged = Symbol::new(&env, "protocol_fee_charged");
+    assert!(!env.events().all().iter().any(|(_, topics, _)| {
+        let topic: Symbol = topics.first().unwrap().into_val(&env);
+        topic == fee_charged
+    }));
+    let payouts: Vec<PayoutShare> = last_event(&env, "renewal_success")
+        .get(Symbol::new(&env, "payouts"))
+        .unwrap()
+        .into_val(&env);
+    assert_eq!(
+        payouts,
+        soroban_sdk::vec![
+            &env,
+            PayoutShare {
+                recipient: merchant.clone(),
+                amount: 500,
+            }
+        ]
+    );
+    let receipt = client.get_receipt(&sub_id, &1);
+    assert_eq!((receipt.amount, receipt.fee), (500, 0));
+    assert_eq!(MockLoyaltyClient::new(&env, &loyalty).credited(), None);
+}
+
+// ── Notification preference tests ────────────────────────────────
+
+#[test]
+fn test_owner_sets_notification_prefs_until_transfer() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let sub_id = 1746;
+    client.init_sub(
+        &owner,
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert_eq!(client.get_notification_prefs(&sub_id), None);
+
+    let prefs = NotificationPrefs {
+        remind_days: 3,
+        mode: NotificationMode::FailuresOnly,
+    };
+    client.set_notification_prefs(&sub_id, &Some(prefs.clone()));
+    assert_eq!(env.auths()[0].0, owner);
+    assert_eq!(client.get_notification_prefs(&sub_id), Some(prefs));
+
+    // Consent given by one owner doesn't carry over to the next
+    client.transfer_sub(&sub_id, &owner, &Address::generate(&env));
+    assert_eq!(client.get_notification_prefs(&sub_id), None);
+}
+
+// ── Metadata anchor tests ────────────────────────────────────────
+
+#[test]
+fn test_metadata_anchor_versions_need_both_parties() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &1800);
+    assert_eq!(client.get_metadata_anchor(&1800), None);
+
+    let terms_v1 = BytesN::from_array(&env, &[1; 32]);
+    let terms_v2 = BytesN::from_array(&env, &[2; 32]);
+    assert_eq!(client.anchor_metadata(&1800, &terms_v1), 1);
+    let auths = env.auths();
+    assert!(auths.iter().any(|(signer, _)| *signer == user));
+    assert!(auths.iter().any(|(signer, _)| *signer == merchant));
+
+    env.ledger().with_mut(|li| li.timestamp = 900);
+    assert_eq!(client.anchor_metadata(&1800, &terms_v2), 2);
+    assert_eq!(
+        client.get_metadata_anchor(&1800),
+        Some(MetadataAnchor {
+            hash: terms_v2,
+            version: 2,
+            anchored_at: 900,
+        })
+    );
+    assert_eq!(
+        client.get_metadata_anchor_at(&1800, &1).unwrap().hash,
+        terms_v1
+    );
+
+    env.set_auths(&[]);
+    assert!(client
+        .try_anchor_metadata(&1800, &BytesN::from_array(&env, &[3; 32]))
+        .is_err());
+}
+
+// ── Scheduler tests ──────────────────────────────────────────────
+
+/// Stand-in for the renewal scheduler: remembers each sub's due ledger.
+#[contract]
+struct MockScheduler;
+
+#[contractimpl]
+impl MockScheduler {
+    pub fn schedule(env: Env, sub_id: u64, due_ledger: u32) {
+        env.storage().instance().set(&sub_id, &due_ledger);
+    }
+
+    pub fn unschedule(env: Env, sub_id: u64) -> bool {
+        let queued = env.storage().instance().has(&sub_id);
+        env.storage().instance().remove(&sub_id);
+        queued
+    }
+
+    pub fn due(env: Env, sub_id: u64) -> Option<u32> {
+        env.storage().instance().get(&sub_id)
+    }
+}
+
+#[test]
+fn test_scheduler_follows_renewals() {
+    let (env, client, _admin) = setup();
+    let scheduler = MockSchedulerClient::new(&env, &env.register(MockScheduler, ()));
+    client.set_scheduler(&scheduler.address);
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+
+    let sub_id = 1500;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert_eq!(scheduler.due(&sub_id), Some(100 + 86400 / 5));
+
+    // A failed attempt is queued again once its cooldown has passed
+    client.approve_renewal(&sub_id, &1, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!renew_unpaid(&env, &client, &sub_id, &1, &500, &1));
+    assert_eq!(scheduler.due(&sub_id), Some(110));
+
+    env.ledger().with_mut(|li| li.sequence_number = 110);
+    client.approve_renewal(&sub_id, &2, &1000, &300);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &2, &500, &1));
+    assert_eq!(scheduler.due(&sub_id), Some(110 + 86400 / 5));
+
+    client.cancel_sub(&sub_id, &None);
+    assert_eq!(scheduler.due(&sub_id), None);
+}
+
+#[test]
+fn test_failed_sub_leaves_schedule() {
+    let (env, client, _admin) = setup();
+    let scheduler = MockSchedulerClient::new(&env, &env.register(MockScheduler, ()));
+    client.set_scheduler(&scheduler.address);
+
+    let sub_id = 1501;
+    let merchant = Address::generate(&env);
+    client.init_sub(
+        &Address::generate(&env),
+        &merchant,
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 0,
+            cooldown_ledgers: 10,
+        },
+    );
+    client.approve_renewal(&sub_id, &1, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!renew_unpaid(&env, &client, &sub_id, &1, &500, &1));
+    assert_eq!(client.get_sub(&sub_id).state, SubscriptionState::Failed);
+    assert_eq!(scheduler.due(&sub_id), None);
+}
+
+/// Stand-in for a scheduler that refuses every call.
+#[contract]
+struct BrokenScheduler;
+
+#[contractimpl]
+impl BrokenScheduler {
+    pub fn schedule(_env: Env, _sub_id: u64, _due_ledger: u32) {
+        panic!("scheduler misconfigured")
+    }
+
+    pub fn unschedule(_env: Env, _sub_id: u64) -> bool {
+        panic!("scheduler misconfigured")
+    }
+}
+
+#[test]
+fn test_broken_scheduler_does_not_block_renewals() {
+    let (env, client, _admin) = setup();
+    client.set_scheduler(&env.register(BrokenScheduler, ()));
+
+    let sub_id = 1502;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.approve_renewal(&sub_id, &1, &1000, &200);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &1, &500, &1));
+    client.cancel_sub(&sub_id, &None);
+    assert_eq!(client.get_sub(&sub_id).state, SubscriptionState::Cancelled);
+}
+
+// ── Clock helper tests ───────────────────────────────────────────
+
+#[test]
+fn test_clock_helpers_match_contract_windows() {
+    let (env, client, _admin) = setup();
+    let scheduler = MockSchedulerClient::new(&env, &env.register(MockScheduler, ()));
+    client.set_scheduler(&scheduler.address);
+    testutils::advance(&env, 100);
+    assert_eq!(env.ledger().timestamp(), 100 * LEDGER_SECONDS);
+
+    let sub_id = 1700;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert_eq!(
+        scheduler.due(&sub_id),
+        Some(testutils::renewal_due(100, 86400))
+    );
+
+    client.approve_renewal(&sub_id, &1, &1000, &1000);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!renew_unpaid(&env, &client, &sub_id, &1, &500, &1));
+    let opens = testutils::retry_opens(&client.get_sub(&sub_id), 10);
+
+    // One ledger short of the cooldown the retry is still refused
+    testutils::advance_to(&env, opens - 1);
+    client.approve_renewal(&sub_id, &2, &1000, &1000);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.try_renew(&sub_id, &2, &500, &1).is_err());
+    testutils::advance_to(&env, opens);
+    assert!(client.renew(&sub_id, &2, &500, &1));
+
+    testutils::advance_time(&env, 86400);
+    assert_eq!(scheduler.due(&sub_id), Some(env.ledger().sequence()));
+}
+
+#[test]
+fn test_ledger_time_conversions_follow_configured_rate() {
+    let (env, client, admin) = setup();
+    let scheduler = MockSchedulerClient::new(&env, &env.register(MockScheduler, ()));
+    client.set_scheduler(&scheduler.address);
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 1_000;
+        li.timestamp = 50_000;
+    });
+    assert_eq!(client.get_ledger_seconds(), LEDGER_SECONDS);
+    assert_eq!(client.ledgers_for(&3600), 720);
+
+    client.set_ledger_seconds(&admin, &6);
+    assert_eq!(client.ledgers_for(&3600), 600);
+    assert_eq!(client.ledger_at(&53_600), 1_600);
+    assert_eq!(client.timestamp_at(&1_600), 53_600);
+    // The past maps onto the present
+    assert_eq!(client.ledger_at(&10), 1_000);
+    assert_eq!(client.timestamp_at(&10), 50_000);
+    assert!(client.try_set_ledger_seconds(&admin, &0).is_err());
+
+    // Renewals are scheduled at the configured rate
+    let sub_id = 1735;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert_eq!(scheduler.due(&sub_id), Some(1_000 + 14_400));
+
+    // An approval expiring at a timestamp is usable until that ledger
+    let expires_at = client.ledger_at(&(50_000 + 60));
+    client.approve_renewal(&sub_id, &1, &1000, &expires_at);
+    testutils::advance_to(&env, expires_at + 1);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.try_renew(&sub_id, &1, &500, &1).is_err());
+}
+
+// ── Invariant tests ──────────────────────────────────────────────
+
+#[test]
+fn test_invariants_hold_through_lifecycle() {
+    let (env, client, _admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+
+    let sub_id = 300;
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 1,
+            cooldown_ledgers: 0,
+        },
+    );
+    assert!(client.check_invariants(&sub_id).is_empty());
+
+    // Retrying, then Failed once the retry limit is exceeded
+    for (approval_id, cycle_id) in [(1, 1), (2, 1)] {
+        client.approve_renewal(&sub_id, &approval_id, &1000, &100);
+        acquire_lock(&env, &client, sub_id, 200);
+        renew_unpaid(&env, &client, &sub_id, &approval_id, &500, &cycle_id);
+        assert!(client.check_invariants(&sub_id).is_empty());
+    }
+    assert_eq!(client.get_sub(&sub_id).state, SubscriptionState::Failed);
+
+    // Cancelling while a keeper holds the lock releases it
+    let sub_id = 301;
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    acquire_lock(&env, &client, sub_id, 200);
+    client.cancel_sub(&sub_id, &None);
+    assert!(client.get_renewal_lock(&sub_id).is_none());
+    assert!(client.check_invariants(&sub_id).is_empty());
+}
+
+#[test]
+fn test_invariants_report_broken_state() {
+    let (env, client, _admin) = setup();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let sub_id = 302;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    acquire_lock(&env, &client, sub_id, 200);
+
+    // Cancelled behind the contract's back, with a raised amount
+    env.as_contract(&client.address, || {
+        let mut data: SubscriptionData = env.storage().persistent().get(&sub_id).unwrap();
+        data.state = SubscriptionState::Cancelled;
+        data.amount = 5_000;
+        env.storage().persistent().set(&sub_id, &data);
+        let lc_key = LifecycleKey {
+            lifecycle_sub_id: sub_id,
+        };
+        let mut lifecycle: LifecycleTimestamps = env.storage().persistent().get(&lc_key).unwrap();
+        lifecycle.last_renewed_at = 10;
+        env.storage().persistent().set(&lc_key, &lifecycle);
+    });
+
+    assert_eq!(
+        client.check_invariants(&sub_id),
+        soroban_sdk::vec![
+            &env,
+            InvariantViolation::CancelledWithoutTimestamp,
+            InvariantViolation::LifecycleOutOfOrder,
+            InvariantViolation::LockOnTerminalSub,
+            InvariantViolation::IntegrityMismatch,
+        ]
+    );
+}
+
+// ── Address book tests ───────────────────────────────────────────
+
+/// Stand-in for the address book listing whatever was `put`.
+#[contract]
+struct MockBook;
+
+#[contractimpl]
+impl MockBook {
+    pub fn put(env: Env, component: Symbol, address: Address) {
+        env.storage().instance().set(&component, &address);
+    }
+
+    pub fn resolve(env: Env, component: Symbol) -> Option<Address> {
+        env.storage().instance().get(&component)
+    }
+}
+
+#[test]
+fn test_peers_resolve_through_address_book() {
+    let (env, client, _admin) = setup();
+    client.set_agent_registry(&env.register(MockAgents, ()));
+    let book = MockBookClient::new(&env, &env.register(MockBook, ()));
+    client.set_address_book(&book.address);
+    assert_eq!(client.get_address_book(), Some(book.address.clone()));
+    let agent = Address::generate(&env);
+
+    // The book has no registry yet, so the one set directly still applies
+    assert!(client
+        .try_acquire_renewal_lock(&agent, &1600, &200)
+        .is_err());
+
+    let registry = env.register(MockAgents, ());
+    MockAgentsClient::new(&env, &registry).grant(&agent);
+    book.put(&address_book::AGENTS, &registry);
+    client.acquire_renewal_lock(&agent, &1600, &200);
+}
+
+/// Stand-in for the subscription registry whose entries were all cancelled,
+/// so it flags failures but refuses to advance anything.
+#[contract]
+struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn own(env: Env, user: Address, entry: BytesN<32>) {
+        env.storage()
+            .instance()
+            .set(&user, &soroban_sdk::vec![&env, entry]);
+    }
+
+    pub fn get_user_subscriptions(env: Env, user: Address) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&user)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn mark_renewed(_env: Env, _subscription_id: BytesN<32>, _renewer: Address) -> u64 {
+        panic!("subscription is not active");
+    }
+
+    pub fn mark_at_risk(env: Env, subscription_id: BytesN<32>, _renewer: Address) {
+        env.storage().instance().set(&subscription_id, &true);
+    }
+
+    pub fn is_at_risk(env: Env, subscription_id: BytesN<32>) -> bool {
+        env.storage().instance().has(&subscription_id)
+    }
+
+    pub fn put(env: Env, subscription_id: BytesN<32>, metadata: RegistryMetadata) {
+        env.storage()
+            .instance()
+            .set(&(subscription_id, 0u32), &metadata);
+    }
+
+    pub fn get_subscription(env: Env, subscription_id: BytesN<32>) -> Option<RegistryMetadata> {
+        env.storage().instance().get(&(subscription_id, 0u32))
+    }
+}
+
+#[test]
+fn test_registry_entry_follows_renewals_best_effort() {
+    let (env, client, _admin) = setup();
+    let registry = MockRegistryClient::new(&env, &env.register(MockRegistry, ()));
+    let user = Address::generate(&env);
+    let entry = BytesN::from_array(&env, &[9; 32]);
+    let sub_id = 1610;
+    let merchant = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.set_retry_policy(
+        &merchant,
+        &sub_id,
+        &RetryPolicy {
+            max_retries: 3,
+            cooldown_ledgers: 0,
+        },
+    );
+    assert!(client
+        .try_link_registry_entry(&sub_id, &Some(entry.clone()))
+        .is_err());
+
+    client.set_subscription_registry(&registry.address);
+    assert!(client
+        .try_link_registry_entry(&sub_id, &Some(entry.clone()))
+        .is_err());
+    registry.own(&user, &entry);
+    client.link_registry_entry(&sub_id, &Some(entry.clone()));
+    assert_eq!(client.get_registry_entry(&sub_id), Some(entry.clone()));
+
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(!renew_unpaid(&env, &client, &sub_id, &1, &500, &20260101));
+    assert!(registry.is_at_risk(&entry));
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+
+    // The registry refusing to advance its entry does not undo the charge
+    client.approve_renewal(&sub_id, &2, &1000, &100);
+    acquire_lock(&env, &client, sub_id, 200);
+    assert!(client.renew(&sub_id, &2, &500, &20260101));
+
+    client.link_registry_entry(&sub_id, &None);
+    assert_eq!(client.get_registry_entry(&sub_id), None);
+}
+
+#[test]
+fn test_link_consistency_reports_drift() {
+    let (env, client, _admin) = setup();
+    let registry = MockRegistryClient::new(&env, &env.register(MockRegistry, ()));
+    client.set_subscription_registry(&registry.address);
+    let user = Address::generate(&env);
+    let entry = BytesN::from_array(&env, &[9; 32]);
+    let sub_id = 1760;
+    client.init_sub(
+        &user,
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    assert!(client.check_link_consistency(&sub_id).is_empty());
+
+    registry.own(&user, &entry);
+    client.link_registry_entry(&sub_id, &Some(entry.clone()));
+    assert_eq!(
+        client.check_link_consistency(&sub_id),
+        soroban_sdk::vec![&env, LinkDiscrepancy::EntryMissing]
+    );
+
+    let mut metadata = RegistryMetadata {
+        service_id: String::from_str(&env, "netflix"),
+        billing_interval: 86400,
+        expected_amount: 500,
+        next_renewal: 86400,
+        is_active: true,
+    };
+    registry.put(&entry, &metadata);
+    assert!(client.check_link_consistency(&sub_id).is_empty());
+
+    metadata.expected_amount = 600;
+    metadata.billing_interval = 7 * 86400;
+    registry.put(&entry, &metadata);
+    client.cancel_sub(&sub_id, &None);
+    assert_eq!(
+        client.check_link_consistency(&sub_id),
+        soroban_sdk::vec![
+            &env,
+            LinkDiscrepancy::AmountMismatch,
+            LinkDiscrepancy::IntervalMismatch,
+            LinkDiscrepancy::StatusMismatch,
+        ]
+    );
+}
+
+// ── Privacy mode tests ───────────────────────────────────────────
+
+#[test]
+fn test_privacy_mode_limits_detailed_views() {
+    let (env, client, _admin) = setup();
+    let agents = env.register(MockAgents, ());
+    client.set_agent_registry(&agents);
+    let auditor = Address::generate(&env);
+    MockAgentsClient::new(&env, &agents).grant(&auditor);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let sub_id = 1500;
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &sub_id);
+    client.approve_renewal(&sub_id, &1, &1000, &100);
+    // Everyone may read while privacy mode is off
+    assert!(client.can_view(&stranger, &sub_id));
+    assert_eq!(client.get_sub_as(&stranger, &sub_id).owner, user);
+
+    client.set_privacy_mode(&true);
+    assert!(client.is_privacy_mode());
+    assert!(client.try_get_sub(&sub_id).is_err());
+    assert!(client.try_get_lifecycle(&sub_id).is_err());
+    assert_eq!(client.get_status(&sub_id), SubscriptionState::Active);
+
+    for viewer in [&user, &merchant, &auditor] {
+        assert!(client.can_view(viewer, &sub_id));
+        assert_eq!(client.get_sub_as(viewer, &sub_id).merchant, merchant);
+    }
+    assert_eq!(
+        client
+            .get_approval(&auditor, &sub_id, &1)
+            .unwrap()
+            .max_spend,
+        1000
+    );
+    assert_eq!(client.get_approval(&user, &sub_id, &2), None);
+    assert!(!client.can_view(&stranger, &sub_id));
+    assert!(client.try_get_sub_as(&stranger, &sub_id).is_err());
+    assert!(client.try_get_lifecycle_as(&stranger, &sub_id).is_err());
+    assert!(client.try_get_approval(&stranger, &sub_id, &1).is_err());
+
+    client.set_privacy_mode(&false);
+    assert_eq!(client.get_sub(&sub_id).owner, user);
+}
+
+#[test]
+fn test_private_views_need_the_viewer_to_sign() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    let sub_id = 1501;
+    client.init_sub(
+        &user,
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    client.set_privacy_mode(&true);
+
+    env.set_auths(&[]);
+    assert!(client.try_get_lifecycle_as(&user, &sub_id).is_err());
+}
+
+// ── Event snapshot tests ─────────────────────────────────────────
+// Indexers decode these exact topics and fields. Rerun with
+// UPDATE_EVENT_SNAPSHOTS=1 only when a shape change is intended.
+
+fn recorder(
+    env: &Env,
+    client: &SubscriptionRenewalContractClient,
+) -> syncro_testutils::EventRecorder {
+    syncro_testutils::EventRecorder::new(env).label("renewal", &client.address)
+}
+
+#[test]
+fn test_events_renewal_success_flow() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let agent = Address::generate(&env);
+    let mut events = recorder(&env, &client)
+        .label("owner", &owner)
+        .label("merchant", &merchant)
+        .label("agent", &agent);
+
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &1);
+    events.record("init_sub");
+    client.approve_renewal(&1, &1, &1000, &100);
+    events.record("approve_renewal");
+    client.acquire_renewal_lock(&agent, &1, &200);
+    events.record("acquire_renewal_lock");
+    client.renew(&1, &1, &500, &1);
+    events.record("renew (success)");
+    client.cancel_sub(&1, &None);
+    events.record("cancel_sub");
+
+    syncro_testutils::assert_event_snapshot!("renewal_success_flow", events);
+}
+
+#[test]
+fn test_events_renewal_failure_flow() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let agent = Address::generate(&env);
+    let mut events = recorder(&env, &client)
+        .label("owner", &owner)
+        .label("merchant", &merchant)
+        .label("agent", &agent);
+
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &1);
+    client.set_retry_policy(
+        &merchant,
+        &1,
+        &RetryPolicy {
+            max_retries: 1,
+            cooldown_ledgers: 0,
+        },
+    );
+    client.approve_renewal(&1, &1, &1000, &100);
+    client.acquire_renewal_lock(&agent, &1, &200);
+    events.record("acquire_renewal_lock");
+    client.release_renewal_lock(&1);
+    events.record("release_renewal_lock");
+
+    client.acquire_renewal_lock(&agent, &1, &200);
+    renew_unpaid(&env, &client, &1, &1, &500, &1);
+    events.record("renew (retrying)");
+    client.approve_renewal(&1, &2, &1000, &100);
+    client.acquire_renewal_lock(&agent, &1, &200);
+    renew_unpaid(&env, &client, &1, &2, &500, &1);
+    events.record("renew (failed)");
+
+    syncro_testutils::assert_event_snapshot!("renewal_failure_flow", events);
+}
+
+// ── State injection tests ────────────────────────────────────────
+
+#[test]
+fn test_injected_state_reaches_edge_cases() {
+    let (env, client, _admin) = setup();
+    let owner = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.init_sub(&owner, &merchant, &500, &86400, &1000, &1);
+    testutils::jump_to(&env, 1_000, 5_000);
+
+    // A stale lock is taken over without waiting out its timeout
+    let stale = Address::generate(&env);
+    testutils::put_lock(&env, &client.address, 1, &stale, 0, 10);
+    assert_eq!(client.get_renewal_lock(&1).unwrap().holder, stale);
+    let agent = acquire_lock(&env, &client, 1, 200);
+    assert_eq!(client.get_renewal_lock(&1).unwrap().holder, agent);
+
+    // An approval that ran out before the renewal is refused
+    testutils::put_approval(
+        &env,
+        &client.address,
+        1,
+        7,
+        &RenewalApproval {
+            sub_id: 1,
+            max_spend: 1000,
+            expires_at: 999,
+            used: false,
+        },
+    );
+    assert_eq!(
+        client.try_renew(&1, &7, &500, &1),
+        Err(Ok(Error::ApprovalInvalid))
+    );
+
+    // Terms changed behind the contract's back need a matching hash
+    let mut data = client.get_sub(&1);
+    data.amount = 800;
+    data.integrity_hash = testutils::hash_terms(&env, &client.address, 1, &data);
+    testutils::put_sub(&env, &client.address, 1, &data);
+    assert_eq!(client.get_sub(&1).amount, 800);
+
+    data.state = SubscriptionState::Failed;
+    testutils::put_sub(&env, &client.address, 1, &data);
+    assert_eq!(
+        client.try_renew(&1, &7, &800, &2),
+        Err(Ok(Error::SubFailed))
+    );
+}
+
+// ── TTL maintenance tests ────────────────────────────────────────
+
+#[test]
+fn test_bump_all_extends_subscription_and_instance() {
+    use soroban_sdk::testutils::storage::{Instance as _, Persistent as _};
+    use syncro_common::ttl::BUMP_TO;
+
+    let (env, client, _admin) = setup();
+    let sub_id = 7u64;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+
+    // Subscriptions are stored under their bare id
+    let hint = soroban_sdk::vec![&env, sub_id.into_val(&env), 8u64.into_val(&env)];
+    assert_eq!(client.bump_all(&hint, &10), 1);
+    env.as_contract(&client.address, || {
+        assert_eq!(env.storage().persistent().get_ttl(&sub_id), BUMP_TO);
+        assert_eq!(env.storage().instance().get_ttl(), BUMP_TO);
+    });
+}
+
+#[test]
+fn test_bump_sub_extends_subscription_entries() {
+    use soroban_sdk::testutils::storage::Persistent as _;
+    use syncro_common::ttl::{BUMP_TO, DAY_IN_LEDGERS};
+
+    let (env, client, _admin) = setup();
+    let sub_id = 9u64;
+    client.init_sub(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &86400,
+        &1000,
+        &sub_id,
+    );
+    // Creating a subscription already keeps it alive
+    env.as_contract(&client.address, || {
+        assert_eq!(env.storage().persistent().get_ttl(&sub_id), BUMP_TO);
+    });
+
+    // Data, lifecycle, retry policy and version; no cycle charged yet
+    let ledgers = 200 * DAY_IN_LEDGERS;
+    assert_eq!(client.bump_sub(&sub_id, &ledgers), 4);
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert_eq!(storage.get_ttl(&sub_id), ledgers);
+        assert_eq!(
+            storage.get_ttl(&LifecycleKey {
+                lifecycle_sub_id: sub_id,
+            }),
+            ledgers
+        );
+    });
+
+    assert_eq!(
+        client.try_bump_sub(&sub_id, &0),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_bump_sub(&404, &ledgers),
+        Err(Ok(Error::SubNotFound))
+    );
+}
+
+// ── Delegation tests ─────────────────────────────────────────────
+
+#[test]
+fn test_delegated_approvals_stay_within_the_owner_budget() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let agent = Address::generate(&env);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &1);
+    client.init_sub(&user, &merchant, &500, &86400, &1000, &2);
+
+    // No budget from the owner, no delegated approvals
+    assert_eq!(
+        client.try_approve_renewal_as(&agent, &1, &1, &500, &100),
+        Err(Ok(Error::AgentNotAllowed))
+    );
+
+    let budget = DelegationBudget {
+        max_outstanding: 800,
+        max_approvals: 2,
+    };
+    client.set_delegation_budget(&user, &agent, &Some(budget.clone()));
+    client.approve_renewal_as(&agent, &1, &1, &500, &100);
+
+    // Outstanding spend and the approval count are both capped
+    assert_eq!(
+        client.try_approve_renewal_as(&agent, &2, &1, &500, &100),
+        Err(Ok(Error::InvalidAmount))
+    );
+    client.approve_renewal_as(&agent, &2, &1, &300, &100);
+    assert_eq!(
+        client.try_approve_renewal_as(&agent, &2, &2, &1, &100),
+        Err(Ok(Error::AgentRateLimited))
+    );
+    assert_eq!(
+        client.get_delegation(&user, &agent),
+        Some(Delegation {
+            budget: budget.clone(),
+            outstanding: 800,
+            approvals: 2,
+        })
+    );
+
+    // Using an approval frees its share of the budget
+    acquire_lock(&env, &client, 1, 200);
+    assert!(client.renew(&1, &1, &500, &1));
+    // Revoking frees it too, and only works on delegated approvals
+    client.revoke_delegated_approval(&2, &1);
+    assert_eq!(
+        client.try_revoke_delegated_approval(&2, &1),
+        Err(Ok(Error::ApprovalInvalid))
+    );
+    assert_eq!(
+        client.get_delegation(&user, &agent),
+        Some(Delegation {
+            budget,
+            outstanding: 0,
+            approvals: 0,
+        })
+    );
+
+    client.set_delegation_budget(&user, &agent, &None);
+    assert_eq!(client.get_delegation(&user, &agent), None);
+    assert_eq!(
+        client.try_approve_renewal_as(&agent, &2, &2, &100, &100),
+        Err(Ok(Error::AgentNotAllowed))
+    );
+}
+
+#[test]
+fn test_delegated_approvals_draw_on_the_payer_budget() {
+    let (env, client, _admin) = setup();
+    let user = Address::generate(&env);
+    let payer = Address::generate(&env);
+    let agent = Address::generate(&env);
+    client.init_sub(&user, &Address::generate(&env), &500, &86400, &1000, &1);
+    client.set_payer(&1, &Some(payer.clone()));
+    let budget = DelegationBudget {
+        max_outstanding: 800,
+        max_approvals: 2,
+    };
+
+    // The owner doesn't pay, so its budget doesn't cover the approval
+    client.set_delegation_budget(&user, &agent, &Some(budget.clone()));
+    assert_eq!(
+        client.try_approve_renewal_as(&agent, &1, &1, &500, &100),
+        Err(Ok(Error::AgentNotAllowed))
+    );
+
+    client.set_delegation_budget(&payer, &agent, &Some(budget));
+    client.approve_renewal_as(&agent, &1, &1, &500, &100);
+    assert_eq!(
+        client.get_delegation(&payer, &agent).unwrap().outstanding,
+        500
+    );
+    assert_eq!(client.get_delegation(&user, &agent).unwrap().outstanding, 0);
+
+    client.revoke_delegated_approval(&1, &1);
+    assert_eq!(
+        env.auths().first().map(|(address, _)| address.clone()),
+        Some(payer.clone())
+    );
+    assert_eq!(
+        client.get_delegation(&payer, &agent).unwrap().outstanding,
+        0
+    );
 }
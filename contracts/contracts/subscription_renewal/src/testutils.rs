@@ -0,0 +1,159 @@
+//! Clock and state helpers for tests written against this contract,
+//! behind the `testutils` feature.
+//!
+//! Billing frequencies are seconds, but the contract schedules renewals and
+//! enforces retry cooldowns in ledgers, converting at [`LEDGER_SECONDS`]
+//! unless the admin set another ledger time.
+//! These helpers move the ledger sequence and timestamp together at that
+//! rate and name the ledgers the contract compares against, so a test can
+//! step to the edge of a window without redoing the arithmetic.
+//!
+//! ```ignore
+//! // The payer has no allowance yet, so this renewal fails
+//! client.renew(&sub_id, &1, &500, &1);
+//! let data = client.get_sub(&sub_id);
+//! testutils::advance_to(&env, testutils::retry_opens(&data, 10));
+//! ```
+//!
+//! Edge cases that take many calls to reach, like a stale lock or a
+//! subscription one failure from `Failed`, can instead be written straight
+//! into the contract's storage with the `put_` helpers:
+//!
+//! ```ignore
+//! let mut data = client.get_sub(&sub_id);
+//! data.failure_count = 2;
+//! testutils::put_sub(&env, &client.address, sub_id, &data);
+//! testutils::put_lock(&env, &client.address, sub_id, &agent, 0, 10);
+//! ```
+use soroban_sdk::{testutils::Ledger, Address, BytesN, Env};
+
+pub use crate::LEDGER_SECONDS;
+use crate::{
+    ApprovalKey, RenewalApproval, RenewalLockData, RenewalLockKey, SubscriptionData,
+    SubscriptionRenewalContract,
+};
+
+/// Ledgers the contract counts for `seconds` of billing frequency at the
+/// default ledger time.
+pub fn ledgers_in(seconds: u64) -> u32 {
+    crate::ledgers_at(seconds, LEDGER_SECONDS)
+}
+
+/// Close `ledgers` ledgers, moving the clock [`LEDGER_SECONDS`] for each.
+pub fn advance(env: &Env, ledgers: u32) {
+    env.ledger().with_mut(|li| {
+        li.sequence_number += ledgers;
+        li.timestamp += ledgers as u64 * LEDGER_SECONDS;
+    });
+}
+
+/// Let `seconds` pass, closing as many ledgers as the contract counts for
+/// them.
+pub fn advance_time(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| {
+        li.sequence_number += ledgers_in(seconds);
+        li.timestamp += seconds;
+    });
+}
+
+/// Close ledgers until the sequence is `sequence`. Panics if it has
+/// already passed.
+pub fn advance_to(env: &Env, sequence: u32) {
+    let current = env.ledger().sequence();
+    assert!(
+        sequence >= current,
+        "ledger {sequence} is behind the current {current}"
+    );
+    advance(env, sequence - current);
+}
+
+/// Ledger a renewal at `renewed_at` schedules the next one for.
+pub fn renewal_due(renewed_at: u32, frequency: u64) -> u32 {
+    renewed_at.saturating_add(ledgers_in(frequency))
+}
+
+/// First ledger at which retrying `data` clears a cooldown of
+/// `cooldown_ledgers`. Without a failure on record a retry is never held
+/// back, so this is then the last attempt's ledger.
+pub fn retry_opens(data: &SubscriptionData, cooldown_ledgers: u32) -> u32 {
+    if data.failure_count == 0 {
+        return data.last_attempt_ledger;
+    }
+    data.last_attempt_ledger.saturating_add(cooldown_ledgers)
+}
+
+/// Set the ledger sequence and timestamp outright, backwards as well as
+/// forwards.
+pub fn jump_to(env: &Env, sequence: u32, timestamp: u64) {
+    env.ledger().with_mut(|li| {
+        li.sequence_number = sequence;
+        li.timestamp = timestamp;
+    });
+}
+
+/// Store `data` as `sub_id`'s subscription in the renewal contract at
+/// `contract`, replacing what is there. Only this entry is written: create
+/// the subscription through the contract first so its lifecycle and
+/// indexes exist. Changed terms need a matching `integrity_hash` from
+/// [`hash_terms`], or renewals fail with `IntegrityViolation`.
+pub fn put_sub(env: &Env, contract: &Address, sub_id: u64, data: &SubscriptionData) {
+    env.as_contract(contract, || env.storage().persistent().set(&sub_id, data));
+}
+
+/// The integrity hash the contract expects for the terms in `data`, with
+/// `sub_id`'s indexation if it has one.
+pub fn hash_terms(
+    env: &Env,
+    contract: &Address,
+    sub_id: u64,
+    data: &SubscriptionData,
+) -> BytesN<32> {
+    env.as_contract(contract, || {
+        SubscriptionRenewalContract::integrity_hash(
+            env,
+            &data.merchant,
+            data.amount,
+            data.frequency,
+            data.spending_cap,
+            SubscriptionRenewalContract::get_indexation(env.clone(), sub_id),
+        )
+    })
+}
+
+/// Give `holder` `sub_id`'s renewal lock as if it took it at `locked_at`
+/// for `lock_timeout` ledgers. A `locked_at` far enough back leaves a
+/// stale lock for the next agent to take over.
+pub fn put_lock(
+    env: &Env,
+    contract: &Address,
+    sub_id: u64,
+    holder: &Address,
+    locked_at: u32,
+    lock_timeout: u32,
+) {
+    let lock = RenewalLockData {
+        holder: holder.clone(),
+        locked_at,
+        lock_timeout,
+    };
+    let key = RenewalLockKey {
+        lock_sub_id: sub_id,
+    };
+    env.as_contract(contract, || env.storage().temporary().set(&key, &lock));
+}
+
+/// Store `approval` as `sub_id`'s approval `approval_id`, used or expired
+/// as it says.
+pub fn put_approval(
+    env: &Env,
+    contract: &Address,
+    sub_id: u64,
+    approval_id: u64,
+    approval: &RenewalApproval,
+) {
+    let key = ApprovalKey {
+        sub_id,
+        approval_id,
+    };
+    env.as_contract(contract, || env.storage().persistent().set(&key, approval));
+}
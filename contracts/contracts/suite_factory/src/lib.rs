@@ -0,0 +1,257 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype,
+    symbol_short, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Val, Vec,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 901,
+    NotInitialized = 902,
+    MissingWasm = 903,
+    SuiteExists = 904,
+    UnknownComponent = 905,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Wasm(Symbol),       // component -> WasmRelease
+    Suite(BytesN<32>),  // salt -> SuiteManifest
+}
+
+/// Uploaded code for one suite component.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WasmRelease {
+    pub wasm_hash: BytesN<32>,
+    pub version: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComponentDeployment {
+    pub component: Symbol,
+    pub address: Address,
+    pub version: u32,
+}
+
+/// Addresses of one fully wired suite deployment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuiteManifest {
+    pub admin: Address,
+    pub renewal: Address,
+    pub registry: Address,
+    pub logging: Address,
+    pub agent_registry: Address,
+    pub components: Vec<ComponentDeployment>,
+}
+
+#[contractevent]
+pub struct WasmReleased {
+    pub component: Symbol,
+    pub wasm_hash: BytesN<32>,
+    pub version: u32,
+}
+
+#[contractevent]
+pub struct SuiteDeployed {
+    pub salt: BytesN<32>,
+    pub admin: Address,
+    pub components: Vec<ComponentDeployment>,
+}
+
+#[contractclient(name = "RenewalClient")]
+pub trait RenewalInterface {
+    fn init(env: Env, admin: Address);
+    fn set_logging_contract(env: Env, address: Address);
+    fn set_agent_registry(env: Env, address: Address);
+    fn set_admin(env: Env, new_admin: Address);
+}
+
+#[contractclient(name = "AgentRegistryClient")]
+pub trait AgentRegistryInterface {
+    fn init(env: Env, admin: Address);
+}
+
+#[contract]
+pub struct SuiteFactoryContract;
+
+#[contractimpl]
+impl SuiteFactoryContract {
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(admin)
+    }
+
+    /// Component names accepted by `set_wasm`.
+    pub fn components(env: Env) -> Vec<Symbol> {
+        Vec::from_array(
+            &env,
+            [
+                symbol_short!("renewal"),
+                symbol_short!("registry"),
+                symbol_short!("logging"),
+                symbol_short!("agents"),
+            ],
+        )
+    }
+
+    /// Record the uploaded wasm hash and version for a component. Admin only.
+    pub fn set_wasm(
+        env: Env,
+        component: Symbol,
+        wasm_hash: BytesN<32>,
+        version: u32,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if !Self::components(env.clone()).contains(&component) {
+            return Err(Error::UnknownComponent);
+        }
+        env.storage().instance().set(
+            &DataKey::Wasm(component.clone()),
+            &WasmRelease {
+                wasm_hash: wasm_hash.clone(),
+                version,
+            },
+        );
+        WasmReleased {
+            component,
+            wasm_hash,
+            version,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_wasm(env: Env, component: Symbol) -> Option<WasmRelease> {
+        env.storage().instance().get(&DataKey::Wasm(component))
+    }
+
+    /// Deploy and wire a full suite in one transaction. The renewal contract
+    /// is initialized with the factory as admin so it can be pointed at the
+    /// logging contract, then handed over to `admin`. Admin only.
+    pub fn deploy_suite(env: Env, admin: Address, salt: BytesN<32>) -> Result<SuiteManifest, Error> {
+        Self::require_admin(&env)?;
+
+        let suite_key = DataKey::Suite(salt.clone());
+        if env.storage().persistent().has(&suite_key) {
+            return Err(Error::SuiteExists);
+        }
+
+        // Resolve every release up front so a missing hash deploys nothing
+        let renewal_release = Self::release(&env, symbol_short!("renewal"))?;
+        let registry_release = Self::release(&env, symbol_short!("registry"))?;
+        let logging_release = Self::release(&env, symbol_short!("logging"))?;
+        let agents_release = Self::release(&env, symbol_short!("agents"))?;
+
+        let logging = Self::deploy(&env, &salt, 0, &logging_release);
+        let registry = Self::deploy(&env, &salt, 1, &registry_release);
+
+        let agent_registry = Self::deploy(&env, &salt, 2, &agents_release);
+        AgentRegistryClient::new(&env, &agent_registry).init(&admin);
+
+        let renewal = Self::deploy(&env, &salt, 3, &renewal_release);
+        let renewal_client = RenewalClient::new(&env, &renewal);
+        renewal_client.init(&env.current_contract_address());
+        renewal_client.set_logging_contract(&logging);
+        renewal_client.set_agent_registry(&agent_registry);
+        renewal_client.set_admin(&admin);
+
+        let components = Vec::from_array(
+            &env,
+            [
+                ComponentDeployment {
+                    component: symbol_short!("renewal"),
+                    address: renewal.clone(),
+                    version: renewal_release.version,
+                },
+                ComponentDeployment {
+                    component: symbol_short!("registry"),
+                    address: registry.clone(),
+                    version: registry_release.version,
+                },
+                ComponentDeployment {
+                    component: symbol_short!("logging"),
+                    address: logging.clone(),
+                    version: logging_release.version,
+                },
+                ComponentDeployment {
+                    component: symbol_short!("agents"),
+                    address: agent_registry.clone(),
+                    version: agents_release.version,
+                },
+            ],
+        );
+
+        let manifest = SuiteManifest {
+            admin: admin.clone(),
+            renewal,
+            registry,
+            logging,
+            agent_registry,
+            components: components.clone(),
+        };
+        env.storage().persistent().set(&suite_key, &manifest);
+
+        SuiteDeployed {
+            salt,
+            admin,
+            components,
+        }
+        .publish(&env);
+        Ok(manifest)
+    }
+
+    pub fn get_suite(env: Env, salt: BytesN<32>) -> Option<SuiteManifest> {
+        env.storage().persistent().get(&DataKey::Suite(salt))
+    }
+
+    fn release(env: &Env, component: Symbol) -> Result<WasmRelease, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Wasm(component))
+            .ok_or(Error::MissingWasm)
+    }
+
+    /// Each component gets its own salt derived from the suite salt so the
+    /// four addresses are deterministic and distinct.
+    fn deploy(env: &Env, salt: &BytesN<32>, index: u32, release: &WasmRelease) -> Address {
+        let mut seed = Bytes::from_array(env, &salt.to_array());
+        seed.append(&index.to_xdr(env));
+        let component_salt: BytesN<32> = env.crypto().sha256(&seed).into();
+
+        env.deployer()
+            .with_current_contract(component_salt)
+            .deploy_v2(release.wasm_hash.clone(), ())
+    }
+}
+
+#[cfg(test)]
+mod test;
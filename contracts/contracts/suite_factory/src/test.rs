@@ -0,0 +1,61 @@
+use super::*;
+use soroban_sdk::{symbol_short, testutils::Address as _, Address, BytesN, Env};
+
+fn setup() -> (Env, SuiteFactoryContractClient<'static>) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(SuiteFactoryContract, ());
+    let client = SuiteFactoryContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.init(&admin);
+
+    (env, client)
+}
+
+#[test]
+fn test_set_and_get_wasm() {
+    let (env, client) = setup();
+
+    let hash = BytesN::from_array(&env, &[7; 32]);
+    client.set_wasm(&symbol_short!("renewal"), &hash, &3);
+
+    let release = client.get_wasm(&symbol_short!("renewal")).unwrap();
+    assert_eq!(release.wasm_hash, hash);
+    assert_eq!(release.version, 3);
+    assert!(client.get_wasm(&symbol_short!("logging")).is_none());
+}
+
+#[test]
+fn test_unknown_component_rejected() {
+    let (env, client) = setup();
+
+    let hash = BytesN::from_array(&env, &[7; 32]);
+    let result = client.try_set_wasm(&symbol_short!("oracle"), &hash, &1);
+    assert_eq!(result, Err(Ok(Error::UnknownComponent)));
+}
+
+#[test]
+fn test_deploy_requires_every_release() {
+    let (env, client) = setup();
+
+    let hash = BytesN::from_array(&env, &[7; 32]);
+    client.set_wasm(&symbol_short!("renewal"), &hash, &1);
+    client.set_wasm(&symbol_short!("registry"), &hash, &1);
+    client.set_wasm(&symbol_short!("logging"), &hash, &1);
+
+    let admin = Address::generate(&env);
+    let salt = BytesN::from_array(&env, &[1; 32]);
+    let result = client.try_deploy_suite(&admin, &salt);
+    assert_eq!(result, Err(Ok(Error::MissingWasm)));
+    assert!(client.get_suite(&salt).is_none());
+}
+
+#[test]
+fn test_cannot_init_twice() {
+    let (env, client) = setup();
+
+    let other = Address::generate(&env);
+    assert_eq!(client.try_init(&other), Err(Ok(Error::AlreadyInitialized)));
+}
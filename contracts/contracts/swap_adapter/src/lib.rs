@@ -0,0 +1,410 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, token,
+    Address, Env, Val, Vec,
+};
+
+const BPS: i128 = 10_000;
+/// Longest route accepted, in assets.
+pub const MAX_PATH_LEN: u32 = 4;
+/// How long an allowance granted to the router lives, in ledgers.
+const ALLOWANCE_LEDGERS: u32 = 1;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1701,
+    NotInitialized = 1702,
+    NotCharger = 1703,
+    InvalidRoute = 1704,
+    RouteNotFound = 1705,
+    NoPreference = 1706,
+    InvalidAmount = 1707,
+    SlippageExceeded = 1708,
+    InsufficientOutput = 1709,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Router,
+    Charger(Address),         // contract allowed to trigger payments -> true
+    Route(Address, Address),  // (asset in, asset out) -> Route
+    Preference(Address, u64), // (payer, sub_id) -> Preference
+}
+
+/// A swap route between two assets. `path` starts with the asset paid and
+/// ends with the asset the merchant receives.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Route {
+    pub path: Vec<Address>,
+    /// Most the input may exceed the quote by, in basis points.
+    pub max_slippage_bps: u32,
+}
+
+/// The asset a payer funds a subscription with, and the most they will
+/// spend on one renewal.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Preference {
+    pub asset: Address,
+    pub max_amount_in: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapResult {
+    pub asset_in: Address,
+    pub amount_in: i128,
+    pub amount_out: i128,
+}
+
+/// AMM router the adapter swaps through. Integrations with a specific AMM
+/// implement this interface. The router pulls the input from `from` through
+/// an allowance and sends exactly `amount_out` of the last asset to `to`.
+#[contractclient(name = "RouterClient")]
+pub trait SwapRouter {
+    fn get_amounts_in(env: Env, amount_out: i128, path: Vec<Address>) -> Vec<i128>;
+    fn swap_exact_out(
+        env: Env,
+        from: Address,
+        amount_out: i128,
+        amount_in_max: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128>;
+}
+
+#[contractevent]
+pub struct RouteSet {
+    pub asset_in: Address,
+    pub asset_out: Address,
+    pub hops: u32,
+    pub max_slippage_bps: u32,
+}
+
+#[contractevent]
+pub struct RouteRemoved {
+    pub asset_in: Address,
+    pub asset_out: Address,
+}
+
+#[contractevent]
+pub struct PreferenceSet {
+    pub payer: Address,
+    pub sub_id: u64,
+    pub asset: Address,
+    pub max_amount_in: i128,
+}
+
+#[contractevent]
+pub struct RenewalSwapped {
+    pub sub_id: u64,
+    pub payer: Address,
+    pub merchant: Address,
+    pub asset_in: Address,
+    pub amount_in: i128,
+    pub asset_out: Address,
+    pub amount_out: i128,
+}
+
+#[contract]
+pub struct SwapAdapterContract;
+
+#[contractimpl]
+impl SwapAdapterContract {
+    /// Initialize with an admin and the router swaps go through.
+    pub fn init(env: Env, admin: Address, router: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Router, &router);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Configuration (admin only) ────────────────────────────────
+
+    pub fn set_router(env: Env, router: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Router, &router);
+        Ok(())
+    }
+
+    /// Allow `charger` (the renewal contract or a keeper) to trigger
+    /// payments.
+    pub fn add_charger(env: Env, charger: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Charger(charger), &true);
+        Ok(())
+    }
+
+    pub fn remove_charger(env: Env, charger: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Charger(charger));
+        Ok(())
+    }
+
+    /// Set the route from the first asset of `path` to its last.
+    pub fn set_route(env: Env, path: Vec<Address>, max_slippage_bps: u32) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if path.len() < 2 || path.len() > MAX_PATH_LEN || max_slippage_bps as i128 > BPS {
+            return Err(Error::InvalidRoute);
+        }
+        let asset_in = path.first_unchecked();
+        let asset_out = path.last_unchecked();
+        if asset_in == asset_out {
+            return Err(Error::InvalidRoute);
+        }
+
+        let hops = path.len() - 1;
+        env.storage().persistent().set(
+            &DataKey::Route(asset_in.clone(), asset_out.clone()),
+            &Route {
+                path,
+                max_slippage_bps,
+            },
+        );
+        RouteSet {
+            asset_in,
+            asset_out,
+            hops,
+            max_slippage_bps,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn remove_route(env: Env, asset_in: Address, asset_out: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Route(asset_in.clone(), asset_out.clone()));
+        RouteRemoved {
+            asset_in,
+            asset_out,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_route(env: Env, asset_in: Address, asset_out: Address) -> Option<Route> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Route(asset_in, asset_out))
+    }
+
+    // ── Payer preferences ─────────────────────────────────────────
+
+    /// Pay renewals of `sub_id` in `asset`, spending at most
+    /// `max_amount_in` per renewal. The payer must give this contract an
+    /// allowance in `asset`.
+    pub fn set_preference(
+        env: Env,
+        payer: Address,
+        sub_id: u64,
+        asset: Address,
+        max_amount_in: i128,
+    ) -> Result<(), Error> {
+        payer.require_auth();
+        if max_amount_in <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().persistent().set(
+            &DataKey::Preference(payer.clone(), sub_id),
+            &Preference {
+                asset: asset.clone(),
+                max_amount_in,
+            },
+        );
+        PreferenceSet {
+            payer,
+            sub_id,
+            asset,
+            max_amount_in,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn clear_preference(env: Env, payer: Address, sub_id: u64) {
+        payer.require_auth();
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Preference(payer, sub_id));
+    }
+
+    pub fn get_preference(env: Env, payer: Address, sub_id: u64) -> Option<Preference> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Preference(payer, sub_id))
+    }
+
+    // ── Payment ───────────────────────────────────────────────────
+
+    /// Input needed right now to pay `amount_out` of `asset_out` from the
+    /// payer's preferred asset, before slippage.
+    pub fn quote(
+        env: Env,
+        payer: Address,
+        sub_id: u64,
+        asset_out: Address,
+        amount_out: i128,
+    ) -> Result<i128, Error> {
+        let preference = Self::load_preference(&env, &payer, sub_id)?;
+        if preference.asset == asset_out {
+            return Ok(amount_out);
+        }
+        let route = Self::load_route(&env, &preference.asset, &asset_out)?;
+        Ok(Self::router(&env)?
+            .get_amounts_in(&amount_out, &route.path)
+            .first_unchecked())
+    }
+
+    /// Pay the merchant `amount_out` of `asset_out` for a renewal of
+    /// `sub_id`, converting from the payer's preferred asset. Only chargers
+    /// may call this.
+    ///
+    /// The input is capped by both the route's slippage bound over the
+    /// current quote and the payer's per-renewal maximum; any unspent input
+    /// goes back to the payer.
+    pub fn pay(
+        env: Env,
+        charger: Address,
+        sub_id: u64,
+        payer: Address,
+        merchant: Address,
+        asset_out: Address,
+        amount_out: i128,
+    ) -> Result<SwapResult, Error> {
+        charger.require_auth();
+        if !env.storage().persistent().has(&DataKey::Charger(charger)) {
+            return Err(Error::NotCharger);
+        }
+        if amount_out <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let preference = Self::load_preference(&env, &payer, sub_id)?;
+        let this = env.current_contract_address();
+        let asset_in = token::TokenClient::new(&env, &preference.asset);
+
+        let amount_in = if preference.asset == asset_out {
+            if amount_out > preference.max_amount_in {
+                return Err(Error::SlippageExceeded);
+            }
+            asset_in.transfer_from(&this, &payer, &merchant, &amount_out);
+            amount_out
+        } else {
+            let route = Self::load_route(&env, &preference.asset, &asset_out)?;
+            let router = Self::router(&env)?;
+            let quote = router
+                .get_amounts_in(&amount_out, &route.path)
+                .first_unchecked();
+            let amount_in_max = (quote + quote * route.max_slippage_bps as i128 / BPS)
+                .min(preference.max_amount_in);
+            if quote > amount_in_max {
+                return Err(Error::SlippageExceeded);
+            }
+
+            let out = token::TokenClient::new(&env, &asset_out);
+            let before = out.balance(&merchant);
+            asset_in.transfer_from(&this, &payer, &this, &amount_in_max);
+            asset_in.approve(
+                &this,
+                &router.address,
+                &amount_in_max,
+                &(env.ledger().sequence() + ALLOWANCE_LEDGERS),
+            );
+            let spent = router
+                .swap_exact_out(
+                    &this,
+                    &amount_out,
+                    &amount_in_max,
+                    &route.path,
+                    &merchant,
+                    &env.ledger().timestamp(),
+                )
+                .first_unchecked();
+            if spent > amount_in_max || out.balance(&merchant) - before < amount_out {
+                return Err(Error::InsufficientOutput);
+            }
+
+            asset_in.approve(&this, &router.address, &0, &env.ledger().sequence());
+            if amount_in_max > spent {
+                asset_in.transfer(&this, &payer, &(amount_in_max - spent));
+            }
+            spent
+        };
+
+        RenewalSwapped {
+            sub_id,
+            payer,
+            merchant,
+            asset_in: preference.asset.clone(),
+            amount_in,
+            asset_out,
+            amount_out,
+        }
+        .publish(&env);
+        Ok(SwapResult {
+            asset_in: preference.asset,
+            amount_in,
+            amount_out,
+        })
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn router(env: &Env) -> Result<RouterClient<'_>, Error> {
+        let router: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Router)
+            .ok_or(Error::NotInitialized)?;
+        Ok(RouterClient::new(env, &router))
+    }
+
+    fn load_route(env: &Env, asset_in: &Address, asset_out: &Address) -> Result<Route, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Route(asset_in.clone(), asset_out.clone()))
+            .ok_or(Error::RouteNotFound)
+    }
+
+    fn load_preference(env: &Env, payer: &Address, sub_id: u64) -> Result<Preference, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Preference(payer.clone(), sub_id))
+            .ok_or(Error::NoPreference)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,214 @@
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, token::TokenClient, vec, Address, Env};
+use syncro_testutils::TestToken;
+
+/// Stand-in AMM router: every hop costs twice the output, and `set_short`
+/// makes it deliver less than promised.
+#[contract]
+struct MockRouter;
+
+#[contractimpl]
+impl MockRouter {
+    pub fn set_short(env: Env, short: i128) {
+        env.storage().instance().set(&0u32, &short);
+    }
+
+    pub fn get_amounts_in(env: Env, amount_out: i128, path: Vec<Address>) -> Vec<i128> {
+        let mut amounts = Vec::new(&env);
+        let mut amount = amount_out;
+        amounts.push_front(amount);
+        for _ in 1..path.len() {
+            amount *= 2;
+            amounts.push_front(amount);
+        }
+        amounts
+    }
+
+    pub fn swap_exact_out(
+        env: Env,
+        from: Address,
+        amount_out: i128,
+        amount_in_max: i128,
+        path: Vec<Address>,
+        to: Address,
+        _deadline: u64,
+    ) -> Vec<i128> {
+        let amounts = Self::get_amounts_in(env.clone(), amount_out, path.clone());
+        let amount_in = amounts.first_unchecked();
+        assert!(amount_in <= amount_in_max, "excessive input");
+
+        let this = env.current_contract_address();
+        TokenClient::new(&env, &path.first_unchecked())
+            .transfer_from(&this, &from, &this, &amount_in);
+        let short: i128 = env.storage().instance().get(&0u32).unwrap_or(0);
+        TokenClient::new(&env, &path.last_unchecked()).transfer(&this, &to, &(amount_out - short));
+        amounts
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: SwapAdapterContractClient<'static>,
+    router: MockRouterClient<'static>,
+    asset_in: TestToken,
+    asset_out: TestToken,
+    charger: Address,
+    payer: Address,
+    merchant: Address,
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let asset_in = TestToken::new(&env);
+    let asset_out = TestToken::new(&env);
+
+    let router = MockRouterClient::new(&env, &env.register(MockRouter, ()));
+    let contract_id = env.register(SwapAdapterContract, ());
+    let client = SwapAdapterContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env), &router.address);
+
+    let charger = Address::generate(&env);
+    client.add_charger(&charger);
+    client.set_route(
+        &vec![&env, asset_in.address.clone(), asset_out.address.clone()],
+        &100,
+    );
+
+    let payer = Address::generate(&env);
+    asset_in.mint(&payer, 10_000);
+    asset_out.mint(&router.address, 10_000);
+    asset_in.approve(&payer, &contract_id, &5_000, &1_000);
+
+    let merchant = Address::generate(&env);
+    Setup {
+        env,
+        client,
+        router,
+        asset_in,
+        asset_out,
+        charger,
+        payer,
+        merchant,
+    }
+}
+
+impl Setup {
+    fn prefer(&self, asset: &Address, max_amount_in: i128) {
+        self.client
+            .set_preference(&self.payer, &1, asset, &max_amount_in);
+    }
+
+    /// Pay `amount_out` to the merchant, unwrapping host-level failures.
+    fn pay(&self, amount_out: i128) -> Result<SwapResult, Error> {
+        match self.client.try_pay(
+            &self.charger,
+            &1,
+            &self.payer,
+            &self.merchant,
+            &self.asset_out.address,
+            &amount_out,
+        ) {
+            Ok(result) => Ok(result.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+}
+
+#[test]
+fn test_pay_swaps_into_merchant_asset() {
+    let s = setup();
+    s.prefer(&s.asset_in.address, 5_000);
+    assert_eq!(
+        s.client.quote(&s.payer, &1, &s.asset_out.address, &500),
+        1_000
+    );
+
+    let result = s.pay(500).unwrap();
+    assert_eq!(result.asset_in, s.asset_in.address);
+    assert_eq!(result.amount_in, 1_000);
+    assert_eq!(s.asset_out.balance(&s.merchant), 500);
+
+    // The slippage buffer comes back and nothing is left in the adapter
+    assert_eq!(s.asset_in.balance(&s.payer), 9_000);
+    assert_eq!(s.asset_in.balance(&s.client.address), 0);
+    assert_eq!(
+        s.asset_in.allowance(&s.client.address, &s.router.address),
+        0
+    );
+}
+
+#[test]
+fn test_same_asset_pays_directly() {
+    let s = setup();
+    let direct = s.asset_out.address.clone();
+    s.asset_out.mint(&s.payer, 1_000);
+    s.asset_out
+        .approve(&s.payer, &s.client.address, &1_000, &1_000);
+    s.client.set_preference(&s.payer, &1, &direct, &1_000);
+
+    let result = s.pay(700).unwrap();
+    assert_eq!(result.amount_in, 700);
+    assert_eq!(s.asset_out.balance(&s.merchant), 700);
+    assert_eq!(s.asset_out.balance(&s.payer), 300);
+}
+
+#[test]
+fn test_payer_maximum_bounds_input() {
+    let s = setup();
+    s.prefer(&s.asset_in.address, 999);
+    assert_eq!(s.pay(500), Err(Error::SlippageExceeded));
+    assert_eq!(s.asset_in.balance(&s.payer), 10_000);
+}
+
+#[test]
+fn test_short_delivery_reverts() {
+    let s = setup();
+    s.prefer(&s.asset_in.address, 5_000);
+    s.router.set_short(&1);
+    assert_eq!(s.pay(500), Err(Error::InsufficientOutput));
+    assert_eq!(s.asset_in.balance(&s.payer), 10_000);
+    assert_eq!(s.asset_out.balance(&s.merchant), 0);
+}
+
+#[test]
+fn test_pay_requires_charger_route_and_preference() {
+    let s = setup();
+    assert_eq!(s.pay(500), Err(Error::NoPreference));
+
+    s.prefer(&s.asset_in.address, 5_000);
+    assert_eq!(
+        s.client.try_pay(
+            &Address::generate(&s.env),
+            &1,
+            &s.payer,
+            &s.merchant,
+            &s.asset_out.address,
+            &500
+        ),
+        Err(Ok(Error::NotCharger))
+    );
+
+    s.client
+        .remove_route(&s.asset_in.address, &s.asset_out.address);
+    assert_eq!(s.pay(500), Err(Error::RouteNotFound));
+}
+
+#[test]
+fn test_invalid_routes_rejected() {
+    let s = setup();
+    let a = s.asset_in.address.clone();
+    assert_eq!(
+        s.client.try_set_route(&vec![&s.env, a.clone()], &100),
+        Err(Ok(Error::InvalidRoute))
+    );
+    assert_eq!(
+        s.client
+            .try_set_route(&vec![&s.env, a.clone(), a.clone()], &100),
+        Err(Ok(Error::InvalidRoute))
+    );
+    assert_eq!(
+        s.client
+            .try_set_route(&vec![&s.env, a, s.asset_out.address.clone()], &10_001),
+        Err(Ok(Error::InvalidRoute))
+    );
+}
@@ -0,0 +1,459 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, panic_with_error,
+    token::TokenInterface, Address, Env, MuxedAddress, String, Val, Vec,
+};
+use syncro_access::AccessError;
+
+const BPS: u32 = 10_000;
+/// Most tiers the staking schedule can have.
+pub const MAX_TIERS: u32 = 8;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 2201,
+    NotInitialized = 2202,
+    NoPendingAdmin = 2203,
+    Unauthorized = 2204,
+    InvalidAmount = 2205,
+    InsufficientBalance = 2206,
+    InsufficientAllowance = 2207,
+    InvalidExpiration = 2208,
+    InvalidTiers = 2209,
+    StakeLocked = 2210,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Metadata,
+    LockPeriod,
+    Tiers,
+    Balance(Address),            // account -> i128
+    Allowance(Address, Address), // (owner, spender) -> Allowance, temporary
+    Stake(Address),              // account -> StakeInfo
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMetadata {
+    pub decimals: u32,
+    pub name: String,
+    pub symbol: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Allowance {
+    amount: i128,
+    expiration_ledger: u32,
+}
+
+/// Staking at least `min_stake` takes `discount_bps` off protocol fees.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeTier {
+    pub min_stake: i128,
+    pub discount_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StakeInfo {
+    pub amount: i128,
+    /// Earliest time any of the stake can be withdrawn; pushed back by
+    /// every new stake.
+    pub unlock_at: u64,
+}
+
+// SEP-41 events, in the layout wallets and indexers expect.
+
+#[contractevent(data_format = "single-value")]
+pub struct Transfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "vec")]
+pub struct Approve {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub spender: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent(data_format = "single-value")]
+pub struct Mint {
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "single-value")]
+pub struct Burn {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct StakeTiersSet {
+    pub tier_count: u32,
+}
+
+#[contractevent]
+pub struct Staked {
+    pub account: Address,
+    pub amount: i128,
+    pub total: i128,
+    pub unlock_at: u64,
+}
+
+#[contractevent]
+pub struct Unstaked {
+    pub account: Address,
+    pub amount: i128,
+    pub total: i128,
+}
+
+/// The SYNCRO utility token: a SEP-41 token whose holders can stake for
+/// protocol-fee discounts on renewals.
+#[contract]
+pub struct SyncroToken;
+
+#[contractimpl]
+impl SyncroToken {
+    /// Initialize with an admin (the minter), token metadata and how long
+    /// staked tokens stay locked, in seconds.
+    pub fn init(
+        env: Env,
+        admin: Address,
+        decimals: u32,
+        name: String,
+        symbol: String,
+        lock_period: u64,
+    ) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        env.storage().instance().set(
+            &DataKey::Metadata,
+            &TokenMetadata {
+                decimals,
+                name,
+                symbol,
+            },
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::LockPeriod, &lock_period);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    pub fn mint(env: Env, to: Address, amount: i128) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::credit(&env, &to, amount);
+        Mint { to, amount }.publish(&env);
+        Ok(())
+    }
+
+    // ── Staking ───────────────────────────────────────────────────
+
+    /// Replace the staking tiers. Tiers must be in increasing `min_stake`
+    /// order. Admin only.
+    pub fn set_tiers(env: Env, tiers: Vec<StakeTier>) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        if tiers.len() > MAX_TIERS {
+            return Err(Error::InvalidTiers);
+        }
+        let mut previous = 0i128;
+        for tier in tiers.iter() {
+            if tier.min_stake <= previous || tier.discount_bps > BPS {
+                return Err(Error::InvalidTiers);
+            }
+            previous = tier.min_stake;
+        }
+        let tier_count = tiers.len();
+        env.storage().instance().set(&DataKey::Tiers, &tiers);
+        StakeTiersSet { tier_count }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_tiers(env: Env) -> Vec<StakeTier> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Tiers)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Move `amount` from the account's balance into its stake. The whole
+    /// stake is locked for the lock period from now.
+    pub fn stake(env: Env, account: Address, amount: i128) -> Result<StakeInfo, Error> {
+        account.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::debit(&env, &account, amount)?;
+        let lock_period: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LockPeriod)
+            .ok_or(Error::NotInitialized)?;
+
+        let mut stake = Self::get_stake(env.clone(), account.clone());
+        stake.amount += amount;
+        stake.unlock_at = env.ledger().timestamp() + lock_period;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Stake(account.clone()), &stake);
+
+        Staked {
+            account,
+            amount,
+            total: stake.amount,
+            unlock_at: stake.unlock_at,
+        }
+        .publish(&env);
+        Ok(stake)
+    }
+
+    /// Move `amount` of an unlocked stake back to the account's balance.
+    pub fn unstake(env: Env, account: Address, amount: i128) -> Result<StakeInfo, Error> {
+        account.require_auth();
+        let mut stake = Self::get_stake(env.clone(), account.clone());
+        if amount <= 0 || amount > stake.amount {
+            return Err(Error::InvalidAmount);
+        }
+        if env.ledger().timestamp() < stake.unlock_at {
+            return Err(Error::StakeLocked);
+        }
+
+        stake.amount -= amount;
+        let key = DataKey::Stake(account.clone());
+        if stake.amount == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &stake);
+        }
+        Self::credit(&env, &account, amount);
+
+        Unstaked {
+            account,
+            amount,
+            total: stake.amount,
+        }
+        .publish(&env);
+        Ok(stake)
+    }
+
+    pub fn get_stake(env: Env, account: Address) -> StakeInfo {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Stake(account))
+            .unwrap_or_default()
+    }
+
+    /// Staking tier of `account`: 0 without one, otherwise the 1-based
+    /// index of the highest tier its stake reaches.
+    pub fn tier(env: Env, account: Address) -> u32 {
+        let staked = Self::get_stake(env.clone(), account).amount;
+        Self::get_tiers(env)
+            .iter()
+            .take_while(|tier| tier.min_stake <= staked)
+            .count() as u32
+    }
+
+    /// Protocol-fee discount `account` is entitled to, in basis points.
+    pub fn fee_discount_bps(env: Env, account: Address) -> u32 {
+        match Self::tier(env.clone(), account) {
+            0 => 0,
+            tier => Self::get_tiers(env).get_unchecked(tier - 1).discount_bps,
+        }
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn credit(env: &Env, account: &Address, amount: i128) {
+        let key = DataKey::Balance(account.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(balance + amount));
+    }
+
+    fn debit(env: &Env, account: &Address, amount: i128) -> Result<(), Error> {
+        let key = DataKey::Balance(account.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if balance < amount {
+            return Err(Error::InsufficientBalance);
+        }
+        env.storage().persistent().set(&key, &(balance - amount));
+        Ok(())
+    }
+
+    fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+        let key = DataKey::Allowance(from.clone(), spender.clone());
+        let mut allowance = Self::load_allowance(env, from, spender);
+        if allowance.amount < amount {
+            panic_with_error!(env, Error::InsufficientAllowance);
+        }
+        allowance.amount -= amount;
+        env.storage().temporary().set(&key, &allowance);
+    }
+
+    fn load_allowance(env: &Env, from: &Address, spender: &Address) -> Allowance {
+        let allowance: Option<Allowance> = env
+            .storage()
+            .temporary()
+            .get(&DataKey::Allowance(from.clone(), spender.clone()));
+        match allowance {
+            Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+            _ => Allowance {
+                amount: 0,
+                expiration_ledger: 0,
+            },
+        }
+    }
+
+    fn move_balance(env: &Env, from: &Address, to: &Address, amount: i128) {
+        if amount < 0 {
+            panic_with_error!(env, Error::InvalidAmount);
+        }
+        if let Err(err) = Self::debit(env, from, amount) {
+            panic_with_error!(env, err);
+        }
+        Self::credit(env, to, amount);
+    }
+
+    fn metadata(env: &Env) -> TokenMetadata {
+        env.storage()
+            .instance()
+            .get(&DataKey::Metadata)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotInitialized))
+    }
+}
+
+#[contractimpl]
+impl TokenInterface for SyncroToken {
+    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        Self::load_allowance(&env, &from, &spender).amount
+    }
+
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        if amount < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        let ledger = env.ledger().sequence();
+        if amount > 0 && expiration_ledger < ledger {
+            panic_with_error!(&env, Error::InvalidExpiration);
+        }
+
+        let key = DataKey::Allowance(from.clone(), spender.clone());
+        env.storage().temporary().set(
+            &key,
+            &Allowance {
+                amount,
+                expiration_ledger,
+            },
+        );
+        if amount > 0 {
+            let live_for = expiration_ledger - ledger;
+            env.storage()
+                .temporary()
+                .extend_ttl(&key, live_for, live_for);
+        }
+        Approve {
+            from,
+            spender,
+            amount,
+            expiration_ledger,
+        }
+        .publish(&env);
+    }
+
+    fn balance(env: Env, id: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Balance(id))
+            .unwrap_or(0)
+    }
+
+    fn transfer(env: Env, from: Address, to: MuxedAddress, amount: i128) {
+        from.require_auth();
+        let to = to.address();
+        Self::move_balance(&env, &from, &to, amount);
+        Transfer { from, to, amount }.publish(&env);
+    }
+
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        Self::spend_allowance(&env, &from, &spender, amount);
+        Self::move_balance(&env, &from, &to, amount);
+        Transfer { from, to, amount }.publish(&env);
+    }
+
+    fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        if amount < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        if let Err(err) = Self::debit(&env, &from, amount) {
+            panic_with_error!(&env, err);
+        }
+        Burn { from, amount }.publish(&env);
+    }
+
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        if amount < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        Self::spend_allowance(&env, &from, &spender, amount);
+        if let Err(err) = Self::debit(&env, &from, amount) {
+            panic_with_error!(&env, err);
+        }
+        Burn { from, amount }.publish(&env);
+    }
+
+    fn decimals(env: Env) -> u32 {
+        Self::metadata(&env).decimals
+    }
+
+    fn name(env: Env) -> String {
+        Self::metadata(&env).name
+    }
+
+    fn symbol(env: Env) -> String {
+        Self::metadata(&env).symbol
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,129 @@
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::TokenClient,
+    vec, Address, Env, String,
+};
+
+const LOCK: u64 = 604_800;
+
+struct Setup {
+    env: Env,
+    client: SyncroTokenClient<'static>,
+    holder: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SyncroToken, ());
+    let client = SyncroTokenClient::new(&env, &contract_id);
+    client.init(
+        &Address::generate(&env),
+        &7,
+        &String::from_str(&env, "SYNCRO"),
+        &String::from_str(&env, "SYNC"),
+        &LOCK,
+    );
+    client.set_tiers(&vec![
+        &env,
+        StakeTier {
+            min_stake: 1_000,
+            discount_bps: 1_000,
+        },
+        StakeTier {
+            min_stake: 10_000,
+            discount_bps: 5_000,
+        },
+    ]);
+
+    let holder = Address::generate(&env);
+    client.mint(&holder, &20_000);
+    Setup {
+        env,
+        client,
+        holder,
+    }
+}
+
+#[test]
+fn test_sep41_transfers_and_allowances() {
+    let s = setup();
+    let token = TokenClient::new(&s.env, &s.client.address);
+    let other = Address::generate(&s.env);
+    let spender = Address::generate(&s.env);
+    assert_eq!(token.decimals(), 7);
+    assert_eq!(token.symbol(), String::from_str(&s.env, "SYNC"));
+
+    token.transfer(&s.holder, &other, &500);
+    assert_eq!(token.balance(&other), 500);
+
+    token.approve(&other, &spender, &300, &1_000);
+    token.transfer_from(&spender, &other, &spender, &200);
+    assert_eq!(token.allowance(&other, &spender), 100);
+    assert!(token
+        .try_transfer_from(&spender, &other, &spender, &101)
+        .is_err());
+
+    token.burn(&other, &300);
+    assert_eq!(token.balance(&other), 0);
+    assert!(token.try_transfer(&other, &s.holder, &1).is_err());
+}
+
+#[test]
+fn test_stake_tiers_grant_discounts() {
+    let s = setup();
+    assert_eq!(s.client.tier(&s.holder), 0);
+    assert_eq!(s.client.fee_discount_bps(&s.holder), 0);
+
+    s.client.stake(&s.holder, &1_000);
+    assert_eq!(s.client.tier(&s.holder), 1);
+    assert_eq!(s.client.fee_discount_bps(&s.holder), 1_000);
+
+    s.client.stake(&s.holder, &9_000);
+    assert_eq!(s.client.tier(&s.holder), 2);
+    assert_eq!(s.client.fee_discount_bps(&s.holder), 5_000);
+    assert_eq!(s.client.balance(&s.holder), 10_000);
+}
+
+#[test]
+fn test_stake_locked_until_period_ends() {
+    let s = setup();
+    s.env.ledger().with_mut(|li| li.timestamp = 1_000);
+    s.client.stake(&s.holder, &5_000);
+    assert_eq!(
+        s.client.try_unstake(&s.holder, &5_000),
+        Err(Ok(Error::StakeLocked))
+    );
+
+    s.env.ledger().with_mut(|li| li.timestamp = 1_000 + LOCK);
+    s.client.unstake(&s.holder, &2_000);
+    assert_eq!(s.client.get_stake(&s.holder).amount, 3_000);
+    assert_eq!(s.client.balance(&s.holder), 17_000);
+    assert_eq!(
+        s.client.try_unstake(&s.holder, &3_001),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_invalid_tiers_and_stakes_rejected() {
+    let s = setup();
+    let tier = |min_stake, discount_bps| StakeTier {
+        min_stake,
+        discount_bps,
+    };
+    assert_eq!(
+        s.client
+            .try_set_tiers(&vec![&s.env, tier(100, 500), tier(50, 900)]),
+        Err(Ok(Error::InvalidTiers))
+    );
+    assert_eq!(
+        s.client.try_set_tiers(&vec![&s.env, tier(100, 10_001)]),
+        Err(Ok(Error::InvalidTiers))
+    );
+    assert_eq!(
+        s.client.try_stake(&s.holder, &20_001),
+        Err(Ok(Error::InsufficientBalance))
+    );
+}
@@ -0,0 +1,313 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, token, Address, Env,
+    Symbol, Val, Vec,
+};
+
+const BPS: i128 = 10_000;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1901,
+    NotInitialized = 1902,
+    InvalidRate = 1903,
+    InvalidPeriod = 1904,
+    UnknownJurisdiction = 1905,
+    NotSettler = 1906,
+    InvalidAmount = 1907,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Token,
+    PeriodLength,
+    Settler(Address),        // contract allowed to settle renewals -> true
+    Jurisdiction(Symbol),    // jurisdiction code -> Jurisdiction
+    MerchantCode(Address),   // merchant -> jurisdiction code
+    Report(Address, u64),    // (merchant, period) -> WithholdingReport
+    LifetimeReport(Address), // merchant -> WithholdingReport
+}
+
+/// Withholding terms for one jurisdiction. `collector` receives the
+/// withheld part of every payment to merchants in the jurisdiction.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Jurisdiction {
+    pub rate_bps: u32,
+    pub collector: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WithholdingReport {
+    pub gross: i128,
+    pub withheld: i128,
+    pub net: i128,
+    pub payments: u32,
+}
+
+/// How one payment was split.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TaxSplit {
+    pub net: i128,
+    pub withheld: i128,
+}
+
+#[contractevent]
+pub struct JurisdictionSet {
+    pub code: Symbol,
+    pub rate_bps: u32,
+    pub collector: Address,
+}
+
+#[contractevent]
+pub struct MerchantJurisdictionSet {
+    pub merchant: Address,
+    pub code: Symbol,
+}
+
+#[contractevent]
+pub struct TaxWithheld {
+    pub sub_id: u64,
+    pub merchant: Address,
+    pub code: Symbol,
+    pub gross: i128,
+    pub withheld: i128,
+    pub net: i128,
+}
+
+#[contract]
+pub struct TaxContract;
+
+#[contractimpl]
+impl TaxContract {
+    /// Initialize with an admin, the payment token and the length of a
+    /// reporting period in seconds.
+    pub fn init(env: Env, admin: Address, token: Address, period_length: u64) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        if period_length == 0 {
+            return Err(Error::InvalidPeriod);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage()
+            .instance()
+            .set(&DataKey::PeriodLength, &period_length);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    // ── Configuration (admin only) ────────────────────────────────
+
+    /// Allow `settler` (the renewal contract) to settle payments.
+    pub fn add_settler(env: Env, settler: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Settler(settler), &true);
+        Ok(())
+    }
+
+    pub fn remove_settler(env: Env, settler: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Settler(settler));
+        Ok(())
+    }
+
+    /// Create or update a jurisdiction's rate and collector.
+    pub fn set_jurisdiction(
+        env: Env,
+        code: Symbol,
+        rate_bps: u32,
+        collector: Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if rate_bps as i128 > BPS {
+            return Err(Error::InvalidRate);
+        }
+        env.storage().persistent().set(
+            &DataKey::Jurisdiction(code.clone()),
+            &Jurisdiction {
+                rate_bps,
+                collector: collector.clone(),
+            },
+        );
+        JurisdictionSet {
+            code,
+            rate_bps,
+            collector,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Place a merchant in a jurisdiction. Merchants without one are paid
+    /// in full.
+    pub fn set_merchant_jurisdiction(
+        env: Env,
+        merchant: Address,
+        code: Symbol,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        if Self::get_jurisdiction(env.clone(), code.clone()).is_none() {
+            return Err(Error::UnknownJurisdiction);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::MerchantCode(merchant.clone()), &code);
+        MerchantJurisdictionSet { merchant, code }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_jurisdiction(env: Env, code: Symbol) -> Option<Jurisdiction> {
+        env.storage().persistent().get(&DataKey::Jurisdiction(code))
+    }
+
+    pub fn merchant_jurisdiction(env: Env, merchant: Address) -> Option<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::MerchantCode(merchant))
+    }
+
+    // ── Settlement ────────────────────────────────────────────────
+
+    /// Split `gross`, pulled from `payer` through an allowance, into the
+    /// merchant's net and the withheld part sent to the jurisdiction's
+    /// collector. Only settlers may call this.
+    pub fn settle(
+        env: Env,
+        settler: Address,
+        sub_id: u64,
+        payer: Address,
+        merchant: Address,
+        gross: i128,
+    ) -> Result<TaxSplit, Error> {
+        settler.require_auth();
+        if !env.storage().persistent().has(&DataKey::Settler(settler)) {
+            return Err(Error::NotSettler);
+        }
+        if gross <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let split = Self::preview(env.clone(), merchant.clone(), gross);
+        let token = Self::token(&env)?;
+        let this = env.current_contract_address();
+        token.transfer_from(&this, &payer, &merchant, &split.net);
+        let code = Self::merchant_jurisdiction(env.clone(), merchant.clone());
+        if let Some(code) = &code {
+            if split.withheld > 0 {
+                let jurisdiction = Self::get_jurisdiction(env.clone(), code.clone())
+                    .ok_or(Error::UnknownJurisdiction)?;
+                token.transfer_from(&this, &payer, &jurisdiction.collector, &split.withheld);
+            }
+        }
+
+        let period = Self::current_period(env.clone())?;
+        Self::add_to_report(&env, DataKey::Report(merchant.clone(), period), &split);
+        Self::add_to_report(&env, DataKey::LifetimeReport(merchant.clone()), &split);
+
+        TaxWithheld {
+            sub_id,
+            merchant,
+            code: code.unwrap_or_else(|| Symbol::new(&env, "none")),
+            gross,
+            withheld: split.withheld,
+            net: split.net,
+        }
+        .publish(&env);
+        Ok(split)
+    }
+
+    /// How `gross` paid to `merchant` would be split under current rates.
+    pub fn preview(env: Env, merchant: Address, gross: i128) -> TaxSplit {
+        let rate_bps = Self::merchant_jurisdiction(env.clone(), merchant)
+            .and_then(|code| Self::get_jurisdiction(env, code))
+            .map(|j| j.rate_bps)
+            .unwrap_or(0);
+        let withheld = gross * rate_bps as i128 / BPS;
+        TaxSplit {
+            net: gross - withheld,
+            withheld,
+        }
+    }
+
+    // ── Reports ───────────────────────────────────────────────────
+
+    /// The reporting period the current ledger time falls in.
+    pub fn current_period(env: Env) -> Result<u64, Error> {
+        let length: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PeriodLength)
+            .ok_or(Error::NotInitialized)?;
+        Ok(env.ledger().timestamp() / length)
+    }
+
+    /// Totals settled for `merchant` during `period`.
+    pub fn report(env: Env, merchant: Address, period: u64) -> WithholdingReport {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Report(merchant, period))
+            .unwrap_or_default()
+    }
+
+    /// Totals settled for `merchant` since the contract was deployed.
+    pub fn lifetime_report(env: Env, merchant: Address) -> WithholdingReport {
+        env.storage()
+            .persistent()
+            .get(&DataKey::LifetimeReport(merchant))
+            .unwrap_or_default()
+    }
+
+    // ── Internal ──────────────────────────────────────────────────
+
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn add_to_report(env: &Env, key: DataKey, split: &TaxSplit) {
+        let mut report: WithholdingReport =
+            env.storage().persistent().get(&key).unwrap_or_default();
+        report.gross += split.net + split.withheld;
+        report.withheld += split.withheld;
+        report.net += split.net;
+        report.payments += 1;
+        env.storage().persistent().set(&key, &report);
+    }
+
+    fn token(env: &Env) -> Result<token::TokenClient<'_>, Error> {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        Ok(token::TokenClient::new(env, &token))
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,147 @@
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, Symbol,
+};
+use syncro_testutils::TestToken;
+
+const MONTH: u64 = 2_592_000;
+
+struct Setup {
+    env: Env,
+    client: TaxContractClient<'static>,
+    token: TestToken,
+    settler: Address,
+    payer: Address,
+    merchant: Address,
+    collector: Address,
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    env.ledger().with_mut(|li| li.timestamp = MONTH * 10);
+
+    let token = TestToken::new(&env);
+    let contract_id = env.register(TaxContract, ());
+    let client = TaxContractClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env), &token.address, &MONTH);
+
+    let settler = Address::generate(&env);
+    client.add_settler(&settler);
+    let collector = Address::generate(&env);
+    client.set_jurisdiction(&Symbol::new(&env, "DE"), &1_900, &collector);
+    let merchant = Address::generate(&env);
+    client.set_merchant_jurisdiction(&merchant, &Symbol::new(&env, "DE"));
+
+    let payer = Address::generate(&env);
+    token.mint(&payer, 10_000);
+    token.approve(&payer, &contract_id, &10_000, &1_000);
+    Setup {
+        env,
+        client,
+        token,
+        settler,
+        payer,
+        merchant,
+        collector,
+    }
+}
+
+impl Setup {
+    fn settle(&self, merchant: &Address, gross: i128) -> TaxSplit {
+        self.client
+            .settle(&self.settler, &1, &self.payer, merchant, &gross)
+    }
+}
+
+#[test]
+fn test_settle_withholds_at_jurisdiction_rate() {
+    let s = setup();
+    let split = s.settle(&s.merchant, 1_000);
+    assert_eq!(
+        split,
+        TaxSplit {
+            net: 810,
+            withheld: 190
+        }
+    );
+    assert_eq!(s.token.balance(&s.merchant), 810);
+    assert_eq!(s.token.balance(&s.collector), 190);
+    assert_eq!(s.token.balance(&s.payer), 9_000);
+}
+
+#[test]
+fn test_merchant_without_jurisdiction_paid_in_full() {
+    let s = setup();
+    let exempt = Address::generate(&s.env);
+    assert_eq!(
+        s.settle(&exempt, 1_000),
+        TaxSplit {
+            net: 1_000,
+            withheld: 0
+        }
+    );
+    assert_eq!(s.token.balance(&exempt), 1_000);
+    assert_eq!(s.client.lifetime_report(&exempt).payments, 1);
+}
+
+#[test]
+fn test_reports_by_period() {
+    let s = setup();
+    s.settle(&s.merchant, 1_000);
+    s.settle(&s.merchant, 500);
+    syncro_testutils::advance_time(&s.env, MONTH);
+    s.settle(&s.merchant, 200);
+
+    let report = s.client.report(&s.merchant, &10);
+    assert_eq!(report.gross, 1_500);
+    assert_eq!(report.withheld, 285);
+    assert_eq!(report.net, 1_215);
+    assert_eq!(report.payments, 2);
+
+    assert_eq!(s.client.current_period(), 11);
+    assert_eq!(s.client.report(&s.merchant, &11).withheld, 38);
+    let lifetime = s.client.lifetime_report(&s.merchant);
+    assert_eq!(lifetime.gross, 1_700);
+    assert_eq!(lifetime.withheld, 323);
+    assert_eq!(lifetime.payments, 3);
+}
+
+#[test]
+fn test_rate_change_applies_to_later_payments() {
+    let s = setup();
+    s.client
+        .set_jurisdiction(&Symbol::new(&s.env, "DE"), &700, &s.collector);
+    assert_eq!(s.client.preview(&s.merchant, &1_000).withheld, 70);
+    assert_eq!(s.settle(&s.merchant, 1_000).withheld, 70);
+}
+
+#[test]
+fn test_configuration_errors() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_set_jurisdiction(&Symbol::new(&s.env, "XX"), &10_001, &s.collector),
+        Err(Ok(Error::InvalidRate))
+    );
+    assert_eq!(
+        s.client
+            .try_set_merchant_jurisdiction(&s.merchant, &Symbol::new(&s.env, "XX")),
+        Err(Ok(Error::UnknownJurisdiction))
+    );
+    assert_eq!(
+        s.client.try_settle(
+            &Address::generate(&s.env),
+            &1,
+            &s.payer,
+            &s.merchant,
+            &1_000
+        ),
+        Err(Ok(Error::NotSettler))
+    );
+    assert_eq!(
+        s.client
+            .try_settle(&s.settler, &1, &s.payer, &s.merchant, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
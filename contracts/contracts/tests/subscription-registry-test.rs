@@ -1,5 +1,13 @@
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String};
-use subscription_registry::{SubscriptionRegistry, SubscriptionRegistryClient};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env, String,
+};
+use subscription_registry::{
+    MerchantStats, PortfolioEntry, ServiceStats, SubscriptionMetadata, SubscriptionRegistry,
+    SubscriptionRegistryClient, CODE_VERSION, MAX_PORTFOLIO_IMPORT, REMINDER_WINDOW,
+};
+use syncro_common::ReasonCode;
 
 #[test]
 fn test_create_subscription() {
@@ -127,11 +135,15 @@ fn test_cancel_subscription() {
         &1735689600u64,
     );
 
-    client.cancel_subscription(&subscription_id, &user);
+    client.cancel_subscription(&subscription_id, &user, &Some(ReasonCode::SwitchingService));
 
     // Verify subscription is marked as inactive
     let metadata = client.get_subscription(&subscription_id).unwrap();
     assert!(!metadata.is_active);
+    assert_eq!(
+        client.get_cancel_reason(&subscription_id),
+        Some(ReasonCode::SwitchingService)
+    );
 }
 
 #[test]
@@ -201,8 +213,8 @@ fn test_cancel_already_cancelled_subscription() {
         &1735689600u64,
     );
 
-    client.cancel_subscription(&subscription_id, &user);
-    client.cancel_subscription(&subscription_id, &user);
+    client.cancel_subscription(&subscription_id, &user, &None);
+    client.cancel_subscription(&subscription_id, &user, &None);
 }
 
 #[test]
@@ -222,7 +234,7 @@ fn test_update_cancelled_subscription() {
         &1735689600u64,
     );
 
-    client.cancel_subscription(&subscription_id, &user);
+    client.cancel_subscription(&subscription_id, &user, &None);
     client.update_subscription(
         &subscription_id,
         &user,
@@ -317,3 +329,516 @@ fn test_subscription_id_uniqueness() {
     assert_ne!(sub2_id, sub3_id);
     assert_ne!(sub1_id, sub3_id);
 }
+
+/// Stand-in for the circuit breaker with every contract paused.
+#[contract]
+struct MockTrippedBreaker;
+
+#[contractimpl]
+impl MockTrippedBreaker {
+    pub fn is_paused(_env: Env, _contract: Address) -> bool {
+        true
+    }
+}
+
+#[test]
+#[should_panic(expected = "protocol is paused")]
+fn test_create_subscription_blocked_by_circuit_breaker() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+    client.set_circuit_breaker(&env.register(MockTrippedBreaker, ()));
+
+    client.create_subscription(
+        &Address::generate(&env),
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+}
+
+#[test]
+fn test_approved_operator_transfers_subscription() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let subscription_id = client.create_subscription(
+        &seller,
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+
+    client.approve_transfer(&subscription_id, &seller, &Some(operator.clone()));
+    client.transfer_subscription(&subscription_id, &operator, &buyer);
+
+    assert_eq!(client.get_user_subscriptions(&seller).len(), 0);
+    assert_eq!(
+        client.get_user_subscriptions(&buyer).get(0).unwrap(),
+        subscription_id
+    );
+    assert_eq!(client.get_transfer_approval(&subscription_id), None);
+}
+
+#[test]
+#[should_panic(expected = "transfer not approved")]
+fn test_transfer_requires_approval() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+
+    let seller = Address::generate(&env);
+    let subscription_id = client.create_subscription(
+        &seller,
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+
+    client.transfer_subscription(&subscription_id, &seller, &Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "not the subscription owner")]
+fn test_only_owner_can_approve_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+
+    let subscription_id = client.create_subscription(
+        &Address::generate(&env),
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+
+    let stranger = Address::generate(&env);
+    client.approve_transfer(&subscription_id, &stranger, &Some(stranger.clone()));
+}
+
+#[test]
+fn test_recovery_contract_moves_all_subscriptions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+    client.set_recovery_contract(&Address::generate(&env));
+
+    let lost = Address::generate(&env);
+    let recovered = Address::generate(&env);
+    let first = client.create_subscription(
+        &lost,
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+    let second = client.create_subscription(
+        &lost,
+        &String::from_str(&env, "spotify"),
+        &2592000,
+        &999,
+        &1735689600,
+    );
+    client.approve_transfer(&first, &lost, &Some(Address::generate(&env)));
+
+    assert_eq!(client.recover_subscriptions(&lost, &recovered), 2);
+    assert_eq!(client.get_user_subscriptions(&lost).len(), 0);
+    let subs = client.get_user_subscriptions(&recovered);
+    assert_eq!(subs.len(), 2);
+    assert!(subs.contains(&first) && subs.contains(&second));
+    assert_eq!(client.get_transfer_approval(&first), None);
+}
+
+#[test]
+#[should_panic(expected = "recovery contract not set")]
+fn test_recovery_requires_configured_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+
+    client.recover_subscriptions(&Address::generate(&env), &Address::generate(&env));
+}
+
+#[test]
+fn test_renewer_advances_next_renewal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+
+    let renewer = Address::generate(&env);
+    client.add_renewer(&renewer);
+    let subscription_id = client.create_subscription(
+        &Address::generate(&env),
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+
+    assert_eq!(
+        client.mark_renewed(&subscription_id, &renewer),
+        1735689600 + 2592000
+    );
+    assert_eq!(
+        client
+            .get_subscription(&subscription_id)
+            .unwrap()
+            .next_renewal,
+        1735689600 + 2592000
+    );
+}
+
+#[test]
+fn test_failed_renewal_flags_entry_until_renewed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+
+    let renewer = Address::generate(&env);
+    client.add_renewer(&renewer);
+    let subscription_id = client.create_subscription(
+        &Address::generate(&env),
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+    assert!(!client.is_at_risk(&subscription_id));
+
+    client.mark_at_risk(&subscription_id, &renewer);
+    assert!(client.is_at_risk(&subscription_id));
+    assert!(client
+        .try_mark_at_risk(&subscription_id, &Address::generate(&env))
+        .is_err());
+
+    client.mark_renewed(&subscription_id, &renewer);
+    assert!(!client.is_at_risk(&subscription_id));
+}
+
+#[test]
+#[should_panic(expected = "not a renewer")]
+fn test_mark_renewed_requires_renewer_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+
+    let subscription_id = client.create_subscription(
+        &Address::generate(&env),
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+    client.mark_renewed(&subscription_id, &Address::generate(&env));
+}
+
+#[test]
+fn test_init_records_code_version() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    client.init(&Address::generate(&env));
+    assert_eq!(client.get_code_version(), CODE_VERSION);
+}
+
+#[test]
+#[should_panic(expected = "not initialized")]
+fn test_upgrade_requires_admin() {
+    let env = Env::default();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    client.upgrade(&BytesN::from_array(&env, &[0; 32]));
+}
+
+#[test]
+fn test_merchant_views_track_linked_subscriptions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let mut ids = soroban_sdk::vec![&env];
+    for amount in [1599i128, 999, 799] {
+        let id = client.create_subscription(
+            &user,
+            &String::from_str(&env, "netflix"),
+            &2592000,
+            &amount,
+            &1735689600,
+        );
+        client.set_subscription_merchant(&id, &user, &merchant);
+        ids.push_back(id);
+    }
+    let first = ids.get(0).unwrap();
+    assert_eq!(
+        client.get_subscription_merchant(&first),
+        Some(merchant.clone())
+    );
+
+    client.update_subscription(&first, &user, &None, &None, &Some(1999), &None);
+    client.cancel_subscription(&ids.get(1).unwrap(), &user, &None);
+
+    assert_eq!(
+        client.get_merchant_stats(&merchant),
+        MerchantStats {
+            subscribers: 3,
+            active: 2,
+            expected_amount: 1999 + 799,
+        }
+    );
+    let page = client.get_merchant_subscribers(&merchant, &1, &5);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap(), ids.get(1).unwrap());
+    assert_eq!(client.get_merchant_subscribers(&merchant, &3, &5).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "merchant already set")]
+fn test_subscription_links_to_one_merchant() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let subscription_id = client.create_subscription(
+        &user,
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+    client.set_subscription_merchant(&subscription_id, &user, &Address::generate(&env));
+    client.set_subscription_merchant(&subscription_id, &user, &Address::generate(&env));
+}
+
+#[test]
+#[should_panic]
+fn test_merchant_subscribers_require_merchant_auth() {
+    let env = Env::default();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    client.get_merchant_subscribers(&Address::generate(&env), &0, &10);
+}
+
+#[test]
+fn test_service_stats_follow_subscription_changes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let netflix = String::from_str(&env, "netflix");
+    let hulu = String::from_str(&env, "hulu");
+    let monthly = client.create_subscription(&user, &netflix, &2592000, &1599, &1735689600);
+    let weekly = client.create_subscription(&user, &netflix, &604800, &300, &1735689600);
+    let moved = client.create_subscription(&user, &netflix, &2592000, &999, &1735689600);
+
+    // A weekly 300 counts as 300 * 30 / 7 a month
+    assert_eq!(
+        client.get_service_stats(&netflix),
+        ServiceStats {
+            active: 3,
+            cancelled: 0,
+            monthly_amount: 1599 + 1285 + 999,
+        }
+    );
+
+    client.update_subscription(&moved, &user, &Some(hulu.clone()), &None, &None, &None);
+    client.update_subscription(&monthly, &user, &None, &None, &Some(1999), &None);
+    client.cancel_subscription(&weekly, &user, &None);
+
+    assert_eq!(
+        client.get_service_stats(&netflix),
+        ServiceStats {
+            active: 1,
+            cancelled: 1,
+            monthly_amount: 1999,
+        }
+    );
+    assert_eq!(
+        client.get_service_stats(&hulu),
+        ServiceStats {
+            active: 1,
+            cancelled: 0,
+            monthly_amount: 999,
+        }
+    );
+}
+
+#[contract]
+struct MockDueTarget;
+
+#[contractimpl]
+impl MockDueTarget {
+    pub fn on_subscription_due(
+        env: Env,
+        _user: Address,
+        _subscription_id: BytesN<32>,
+        _next_renewal: u64,
+        _expected_amount: i128,
+    ) {
+        let calls: u32 = env.storage().instance().get(&0u32).unwrap_or(0);
+        env.storage().instance().set(&0u32, &(calls + 1));
+    }
+
+    pub fn calls(env: Env) -> u32 {
+        env.storage().instance().get(&0u32).unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_notify_due_calls_target_once_per_renewal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    let target = env.register(MockDueTarget, ());
+    let target_client = MockDueTargetClient::new(&env, &target);
+
+    let user = Address::generate(&env);
+    let next_renewal = 1735689600u64;
+    let due = client.create_subscription(
+        &user,
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &next_renewal,
+    );
+    client.create_subscription(
+        &user,
+        &String::from_str(&env, "spotify"),
+        &2592000,
+        &999,
+        &(next_renewal + 2592000),
+    );
+    client.set_notification_target(&user, &Some(target.clone()));
+    assert_eq!(client.get_notification_target(&user), Some(target));
+
+    env.ledger()
+        .with_mut(|li| li.timestamp = next_renewal - REMINDER_WINDOW - 1);
+    assert_eq!(client.notify_due(&user, &10), 0);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp = next_renewal - REMINDER_WINDOW);
+    assert_eq!(client.notify_due(&user, &10), 1);
+    assert_eq!(client.notify_due(&user, &10), 0);
+    assert_eq!(target_client.calls(), 1);
+
+    // The next renewal is reported again
+    client.update_subscription(&due, &user, &None, &None, &None, &Some(next_renewal + 60));
+    env.ledger()
+        .with_mut(|li| li.timestamp = next_renewal + 60 - REMINDER_WINDOW);
+    assert_eq!(client.notify_due(&user, &0), 0);
+    assert_eq!(client.notify_due(&user, &10), 1);
+    assert_eq!(target_client.calls(), 2);
+}
+
+#[test]
+#[should_panic(expected = "notification target not set")]
+fn test_notify_due_requires_target() {
+    let env = Env::default();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+    client.notify_due(&Address::generate(&env), &10);
+}
+
+#[test]
+fn test_portfolio_moves_between_deployments() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let old = SubscriptionRegistryClient::new(&env, &env.register(SubscriptionRegistry, ()));
+    let new = SubscriptionRegistryClient::new(&env, &env.register(SubscriptionRegistry, ()));
+
+    let user = Address::generate(&env);
+    let netflix = String::from_str(&env, "netflix");
+    old.create_subscription(&user, &netflix, &2592000, &1599, &1735689600);
+    let cancelled = old.create_subscription(&user, &netflix, &2592000, &999, &1735689600);
+    old.cancel_subscription(&cancelled, &user, &Some(ReasonCode::SwitchingService));
+
+    let portfolio = old.export_user_portfolio(&user);
+    assert_eq!(portfolio.len(), 2);
+    let ids = new.import_portfolio(&user, &portfolio);
+    assert_eq!(new.get_user_subscriptions(&user), ids);
+
+    // The terms come over under new ids, and the stats follow them
+    let imported = new.export_user_portfolio(&user);
+    for (before, after) in portfolio.iter().zip(imported.iter()) {
+        assert_eq!(before.metadata, after.metadata);
+    }
+    assert_eq!(
+        new.get_service_stats(&netflix),
+        old.get_service_stats(&netflix)
+    );
+}
+
+#[test]
+#[should_panic(expected = "expected_amount must be non-negative")]
+fn test_import_checks_entry_terms() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+
+    let entry = PortfolioEntry {
+        subscription_id: BytesN::from_array(&env, &[0; 32]),
+        metadata: SubscriptionMetadata {
+            service_id: String::from_str(&env, "netflix"),
+            billing_interval: 2592000,
+            expected_amount: 0,
+            next_renewal: 1735689600,
+            is_active: true,
+        },
+    };
+    client.import_portfolio(&Address::generate(&env), &vec![&env, entry]);
+}
+
+#[test]
+#[should_panic(expected = "too many portfolio entries")]
+fn test_import_is_capped() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SubscriptionRegistry, ());
+    let client = SubscriptionRegistryClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    client.create_subscription(
+        &user,
+        &String::from_str(&env, "netflix"),
+        &2592000,
+        &1599,
+        &1735689600,
+    );
+    let entry = client.export_user_portfolio(&user).get(0).unwrap();
+    let mut entries = vec![&env];
+    for _ in 0..=MAX_PORTFOLIO_IMPORT {
+        entries.push_back(entry.clone());
+    }
+    client.import_portfolio(&user, &entries);
+}
@@ -0,0 +1,262 @@
+#![no_std]
+//! Time-weighted average prices over the oracle adapter.
+//!
+//! Anyone may `sample` an asset; each sample reads the adapter's latest
+//! price and appends it to a fixed ring of observations carrying a running
+//! price-seconds total. A price holds from its sample until the next one, so
+//! the average over any window still covered by the ring is the difference
+//! of two running totals divided by the window. Fiat-denominated renewals
+//! should convert through `convert` rather than the spot price so a
+//! momentary spike does not reach the amount charged.
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, token,
+    Address, Env, Val, Vec,
+};
+use syncro_access::AccessError;
+use syncro_common::PriceData;
+
+/// Observations kept per asset; the oldest is overwritten past this.
+pub const MAX_OBSERVATIONS: u32 = 48;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 3001,
+    NotInitialized = 3002,
+    NoPendingAdmin = 3003,
+    Unauthorized = 3004,
+    PriceUnavailable = 3005,
+    StalePrice = 3006,
+    InvalidPrice = 3007,
+    SampleTooSoon = 3008,
+    InvalidWindow = 3009,
+    InsufficientHistory = 3010,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Config,
+    Cursor(Address),           // asset -> Cursor
+    Observation(Address, u32), // (asset, ring index) -> Observation
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TwapConfig {
+    pub oracle: Address,
+    /// Oldest oracle price, in seconds, a sample or average may rely on.
+    pub max_age: u64,
+    /// Shortest gap, in seconds, between two samples of one asset.
+    pub min_interval: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Observation {
+    pub timestamp: u64,
+    pub price: i128,
+    /// Sum of price × seconds from the asset's first sample to `timestamp`.
+    pub cumulative: i128,
+}
+
+/// Position of an asset's ring: the slot written next and how many slots
+/// are filled.
+#[contracttype]
+#[derive(Clone, Default)]
+struct Cursor {
+    next: u32,
+    count: u32,
+}
+
+/// The oracle adapter sampled for prices.
+#[contractclient(name = "OracleClient")]
+pub trait OracleInterface {
+    fn lastprice(env: Env, asset: Address) -> Option<PriceData>;
+}
+
+#[contractevent]
+pub struct PriceSampled {
+    pub asset: Address,
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+#[contract]
+pub struct TwapContract;
+
+#[contractimpl]
+impl TwapContract {
+    pub fn init(env: Env, admin: Address, config: TwapConfig) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        env.storage().instance().set(&DataKey::Config, &config);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    /// Replace the oracle or sampling limits. Admin only. Recorded
+    /// observations are kept.
+    pub fn set_config(env: Env, config: TwapConfig) -> Result<(), Error> {
+        syncro_access::require_admin(&env)?;
+        env.storage().instance().set(&DataKey::Config, &config);
+        Ok(())
+    }
+
+    pub fn get_config(env: Env) -> Result<TwapConfig, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Config)
+            .ok_or(Error::NotInitialized)
+    }
+
+    // ── Sampling ──────────────────────────────────────────────────
+
+    /// Record the oracle's current price for `asset`. Permissionless, so
+    /// keepers can sample on a schedule.
+    pub fn sample(env: Env, asset: Address) -> Result<Observation, Error> {
+        let config = Self::get_config(env.clone())?;
+        let now = env.ledger().timestamp();
+        let reported = OracleClient::new(&env, &config.oracle)
+            .lastprice(&asset)
+            .ok_or(Error::PriceUnavailable)?;
+        if reported.price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+        if now.saturating_sub(reported.timestamp) > config.max_age {
+            return Err(Error::StalePrice);
+        }
+
+        let mut cursor = Self::cursor(&env, &asset);
+        let cumulative = match Self::latest(env.clone(), asset.clone()) {
+            Some(last) => {
+                if now < last.timestamp + config.min_interval.max(1) {
+                    return Err(Error::SampleTooSoon);
+                }
+                Self::cumulative_at(&last, now)
+            }
+            None => 0,
+        };
+        let observation = Observation {
+            timestamp: now,
+            price: reported.price,
+            cumulative,
+        };
+        env.storage().persistent().set(
+            &DataKey::Observation(asset.clone(), cursor.next),
+            &observation,
+        );
+        cursor.next = (cursor.next + 1) % MAX_OBSERVATIONS;
+        cursor.count = (cursor.count + 1).min(MAX_OBSERVATIONS);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Cursor(asset.clone()), &cursor);
+
+        PriceSampled {
+            asset,
+            price: reported.price,
+            timestamp: now,
+        }
+        .publish(&env);
+        Ok(observation)
+    }
+
+    pub fn latest(env: Env, asset: Address) -> Option<Observation> {
+        let cursor = Self::cursor(&env, &asset);
+        if cursor.count == 0 {
+            return None;
+        }
+        Some(Self::observation(&env, &asset, 0, &cursor))
+    }
+
+    // ── Averages ──────────────────────────────────────────────────
+
+    /// Average price of `asset` over the last `window` seconds. Fails if
+    /// the ring no longer reaches back that far or sampling has lapsed for
+    /// longer than the configured `max_age`.
+    pub fn twap(env: Env, asset: Address, window: u64) -> Result<i128, Error> {
+        if window == 0 {
+            return Err(Error::InvalidWindow);
+        }
+        let config = Self::get_config(env.clone())?;
+        let cursor = Self::cursor(&env, &asset);
+        if cursor.count == 0 {
+            return Err(Error::InsufficientHistory);
+        }
+
+        let now = env.ledger().timestamp();
+        let latest = Self::observation(&env, &asset, 0, &cursor);
+        if now - latest.timestamp > config.max_age {
+            return Err(Error::StalePrice);
+        }
+        let start = now.checked_sub(window).ok_or(Error::InsufficientHistory)?;
+
+        let cumulative_now = Self::cumulative_at(&latest, now);
+        for back in 0..cursor.count {
+            let observation = Self::observation(&env, &asset, back, &cursor);
+            if observation.timestamp <= start {
+                let cumulative_start = Self::cumulative_at(&observation, start);
+                return Ok((cumulative_now - cumulative_start) / window as i128);
+            }
+        }
+        Err(Error::InsufficientHistory)
+    }
+
+    /// Amount of the `asset` token, in its smallest unit, worth
+    /// `fiat_amount` at the `window` average price. `fiat_amount` uses the
+    /// same decimals as the oracle's prices.
+    pub fn convert(
+        env: Env,
+        asset: Address,
+        fiat_amount: i128,
+        window: u64,
+    ) -> Result<i128, Error> {
+        let price = Self::twap(env.clone(), asset.clone(), window)?;
+        let decimals = token::TokenClient::new(&env, &asset).decimals();
+        Ok(fiat_amount * 10i128.pow(decimals) / price)
+    }
+
+    // ── Ring ──────────────────────────────────────────────────────
+
+    fn cursor(env: &Env, asset: &Address) -> Cursor {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Cursor(asset.clone()))
+            .unwrap_or_default()
+    }
+
+    /// The observation `back` samples before the newest.
+    fn observation(env: &Env, asset: &Address, back: u32, cursor: &Cursor) -> Observation {
+        let index = (cursor.next + MAX_OBSERVATIONS - 1 - back) % MAX_OBSERVATIONS;
+        env.storage()
+            .persistent()
+            .get(&DataKey::Observation(asset.clone(), index))
+            .expect("observation missing")
+    }
+
+    fn cumulative_at(observation: &Observation, timestamp: u64) -> i128 {
+        observation.cumulative + observation.price * (timestamp - observation.timestamp) as i128
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,149 @@
+use super::*;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+use syncro_testutils::{MockOracle, MockOracleClient, TestToken};
+
+const MAX_AGE: u64 = 600;
+
+struct Setup {
+    env: Env,
+    client: TwapContractClient<'static>,
+    oracle: MockOracleClient<'static>,
+    asset: Address,
+}
+
+fn setup() -> Setup {
+    let env = syncro_testutils::setup_env();
+    let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+    let client = TwapContractClient::new(&env, &env.register(TwapContract, ()));
+    client.init(
+        &Address::generate(&env),
+        &TwapConfig {
+            oracle: oracle.address.clone(),
+            max_age: MAX_AGE,
+            min_interval: 30,
+        },
+    );
+    let asset = TestToken::new(&env).address.clone();
+    Setup {
+        env,
+        client,
+        oracle,
+        asset,
+    }
+}
+
+impl Setup {
+    fn advance(&self, seconds: u64) {
+        syncro_testutils::advance_time(&self.env, seconds);
+    }
+
+    /// Report `price` now and sample it.
+    fn sample_at(&self, price: i128) {
+        self.oracle.set_price(&self.asset, &price);
+        self.client.sample(&self.asset);
+    }
+
+    fn twap(&self, window: u64) -> Result<i128, Error> {
+        match self.client.try_twap(&self.asset, &window) {
+            Ok(price) => Ok(price.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+
+    fn sample(&self) -> Result<Observation, Error> {
+        match self.client.try_sample(&self.asset) {
+            Ok(observation) => Ok(observation.unwrap()),
+            Err(err) => Err(err.unwrap()),
+        }
+    }
+}
+
+#[test]
+fn test_twap_weights_prices_by_time_held() {
+    let s = setup();
+    s.sample_at(100);
+    s.advance(60);
+    s.sample_at(200);
+    s.advance(60);
+
+    assert_eq!(s.twap(120), Ok(150));
+    assert_eq!(s.twap(60), Ok(200));
+    // The window may start between samples
+    assert_eq!(s.twap(90), Ok((100 * 30 + 200 * 60) / 90));
+}
+
+#[test]
+fn test_spike_barely_moves_twap() {
+    let s = setup();
+    s.sample_at(100);
+    for _ in 0..9 {
+        s.advance(60);
+        s.sample_at(100);
+    }
+    s.advance(50);
+    s.sample_at(1_000);
+    s.advance(10);
+
+    assert_eq!(s.twap(600), Ok((100 * 590 + 1_000 * 10) / 600));
+    assert_eq!(s.client.latest(&s.asset).unwrap().price, 1_000);
+}
+
+#[test]
+fn test_window_must_be_covered() {
+    let s = setup();
+    assert_eq!(s.twap(60), Err(Error::InsufficientHistory));
+    s.sample_at(100);
+    s.advance(60);
+
+    assert_eq!(s.twap(0), Err(Error::InvalidWindow));
+    assert_eq!(s.twap(61), Err(Error::InsufficientHistory));
+    assert_eq!(s.twap(60), Ok(100));
+
+    // Sampling that lapses past the age limit makes averages unavailable
+    s.advance(MAX_AGE);
+    assert_eq!(s.twap(60), Err(Error::StalePrice));
+}
+
+#[test]
+fn test_ring_drops_oldest_observations() {
+    let s = setup();
+    for price in 0..MAX_OBSERVATIONS as i128 + 2 {
+        s.sample_at(100 + price);
+        s.advance(60);
+    }
+    let span = (MAX_OBSERVATIONS as u64 - 1) * 60 + 60;
+    assert!(s.twap(span).is_ok());
+    assert_eq!(s.twap(span + 1), Err(Error::InsufficientHistory));
+}
+
+#[test]
+fn test_sample_rejects_bad_oracle_data() {
+    let s = setup();
+    assert_eq!(s.sample(), Err(Error::PriceUnavailable));
+
+    s.oracle.set_price(&s.asset, &0);
+    assert_eq!(s.sample(), Err(Error::InvalidPrice));
+
+    s.oracle.set_price(&s.asset, &100);
+    s.advance(MAX_AGE + 1);
+    assert_eq!(s.sample(), Err(Error::StalePrice));
+
+    s.sample_at(100);
+    s.advance(29);
+    assert_eq!(s.sample(), Err(Error::SampleTooSoon));
+    s.advance(1);
+    assert_eq!(s.sample().unwrap().cumulative, 100 * 30);
+}
+
+#[test]
+fn test_convert_uses_average_price() {
+    let s = setup();
+    // Prices and fiat amounts carry 7 decimals, like the asset
+    s.sample_at(2_000_000);
+    s.advance(60);
+    s.sample_at(6_000_000);
+    s.advance(60);
+
+    // $10 at an average of $0.40 is 25 tokens
+    assert_eq!(s.client.convert(&s.asset, &100_000_000, &120), 250_000_000);
+}
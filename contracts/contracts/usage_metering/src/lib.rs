@@ -0,0 +1,372 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, Env, Val, Vec,
+};
+use syncro_access::AccessError;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 501,
+    NotInitialized = 502,
+    Unauthorized = 503,
+    SubNotRegistered = 504,
+    SubAlreadyRegistered = 505,
+    RateTableMissing = 506,
+    InvalidRateTable = 507,
+    PeriodSettled = 508,
+    Overflow = 509,
+    NoPendingAdmin = 510,
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AlreadyInitialized => Error::AlreadyInitialized,
+            AccessError::NotInitialized => Error::NotInitialized,
+            AccessError::NoPendingAdmin => Error::NoPendingAdmin,
+            AccessError::MissingRole => Error::Unauthorized,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    RenewalContract,
+    RateTable(Address),      // merchant -> RateTable
+    Meter(Address, Address), // (merchant, meter) -> bool
+    SubMerchant(u64),        // sub_id -> merchant
+    Usage(u64, u64),         // (sub_id, period) -> units
+    Settled(u64, u64),       // (sub_id, period) -> settled amount
+}
+
+/// One band of a graduated rate table. Units above the previous band and up
+/// to (and including) `up_to` are billed at `unit_price`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateTier {
+    pub up_to: u64,
+    pub unit_price: i128,
+}
+
+/// Merchant pricing: a flat `base_amount` per period plus graduated tiers.
+/// Usage beyond the last tier is billed at the last tier's price.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateTable {
+    pub base_amount: i128,
+    pub tiers: Vec<RateTier>,
+}
+
+#[contractevent]
+pub struct RateTableUpdated {
+    pub merchant: Address,
+    pub tier_count: u32,
+}
+
+#[contractevent]
+pub struct MeterAuthorized {
+    pub merchant: Address,
+    pub meter: Address,
+}
+
+#[contractevent]
+pub struct MeterRevoked {
+    pub merchant: Address,
+    pub meter: Address,
+}
+
+#[contractevent]
+pub struct UsageReported {
+    pub sub_id: u64,
+    pub period: u64,
+    pub units: u64,
+    pub total_units: u64,
+}
+
+#[contractevent]
+pub struct PeriodSettled {
+    pub sub_id: u64,
+    pub period: u64,
+    pub units: u64,
+    pub amount: i128,
+}
+
+#[contract]
+pub struct UsageMeteringContract;
+
+#[contractimpl]
+impl UsageMeteringContract {
+    // ── Admin ─────────────────────────────────────────────────────
+
+    /// Initialize the contract with an admin and the renewal contract
+    /// allowed to settle periods.
+    pub fn init(env: Env, admin: Address, renewal_contract: Address) -> Result<(), Error> {
+        syncro_access::init_admin(&env, &admin)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::RenewalContract, &renewal_contract);
+        syncro_common::events::publish_schema(&env);
+        Ok(())
+    }
+
+    pub fn get_event_version(_env: Env) -> u32 {
+        syncro_common::events::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn bump_all(env: Env, keys_hint: Vec<Val>, limit: u32) -> u32 {
+        syncro_common::ttl::bump_all(&env, &keys_hint, limit)
+    }
+
+    fn require_admin(env: &Env) -> Result<Address, Error> {
+        Ok(syncro_access::require_admin(env)?)
+    }
+
+    /// Nominate a new admin, who takes over once they call `accept_admin`.
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        syncro_access::propose_admin(&env, &new_admin)?;
+        Ok(())
+    }
+
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        syncro_access::accept_admin(&env)?;
+        Ok(())
+    }
+
+    /// Point settlement at a different renewal contract. Admin only.
+    pub fn set_renewal_contract(env: Env, renewal_contract: Address) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::RenewalContract, &renewal_contract);
+        Ok(())
+    }
+
+    // ── Merchant configuration ────────────────────────────────────
+
+    /// Publish or replace the merchant's rate table.
+    pub fn set_rate_table(env: Env, merchant: Address, table: RateTable) -> Result<(), Error> {
+        merchant.require_auth();
+        Self::validate_table(&table)?;
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::RateTable(merchant.clone()), &table);
+
+        RateTableUpdated {
+            merchant,
+            tier_count: table.tiers.len(),
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_rate_table(env: Env, merchant: Address) -> Option<RateTable> {
+        env.storage().persistent().get(&DataKey::RateTable(merchant))
+    }
+
+    /// Allow `meter` to report usage for the merchant's subscriptions.
+    pub fn authorize_meter(env: Env, merchant: Address, meter: Address) {
+        merchant.require_auth();
+        env.storage()
+            .persistent()
+            .set(&DataKey::Meter(merchant.clone(), meter.clone()), &true);
+        MeterAuthorized { merchant, meter }.publish(&env);
+    }
+
+    pub fn revoke_meter(env: Env, merchant: Address, meter: Address) {
+        merchant.require_auth();
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Meter(merchant.clone(), meter.clone()));
+        MeterRevoked { merchant, meter }.publish(&env);
+    }
+
+    pub fn is_meter(env: Env, merchant: Address, meter: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Meter(merchant, meter))
+    }
+
+    /// Bind a renewal-contract subscription to the merchant's rate table.
+    pub fn register_sub(env: Env, merchant: Address, sub_id: u64) -> Result<(), Error> {
+        merchant.require_auth();
+        let key = DataKey::SubMerchant(sub_id);
+        if env.storage().persistent().has(&key) {
+            return Err(Error::SubAlreadyRegistered);
+        }
+        env.storage().persistent().set(&key, &merchant);
+        Ok(())
+    }
+
+    pub fn get_sub_merchant(env: Env, sub_id: u64) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::SubMerchant(sub_id))
+    }
+
+    // ── Usage reporting ───────────────────────────────────────────
+
+    /// Add `units` of usage to a subscription's period. The reporter must be
+    /// the merchant or one of its authorized meters.
+    pub fn report_usage(
+        env: Env,
+        reporter: Address,
+        sub_id: u64,
+        period: u64,
+        units: u64,
+    ) -> Result<u64, Error> {
+        reporter.require_auth();
+
+        let merchant: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SubMerchant(sub_id))
+            .ok_or(Error::SubNotRegistered)?;
+        if reporter != merchant
+            && !env
+                .storage()
+                .persistent()
+                .has(&DataKey::Meter(merchant, reporter))
+        {
+            return Err(Error::Unauthorized);
+        }
+
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Settled(sub_id, period))
+        {
+            return Err(Error::PeriodSettled);
+        }
+
+        let key = DataKey::Usage(sub_id, period);
+        let current: u64 = env.storage().persistent().get(&key).unwrap_or(0);
+        let total_units = current.checked_add(units).ok_or(Error::Overflow)?;
+        env.storage().persistent().set(&key, &total_units);
+
+        UsageReported {
+            sub_id,
+            period,
+            units,
+            total_units,
+        }
+        .publish(&env);
+        Ok(total_units)
+    }
+
+    pub fn get_usage(env: Env, sub_id: u64, period: u64) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Usage(sub_id, period))
+            .unwrap_or(0)
+    }
+
+    // ── Pricing / settlement ──────────────────────────────────────
+
+    /// Price the usage recorded so far for a period without settling it.
+    pub fn quote(env: Env, sub_id: u64, period: u64) -> Result<i128, Error> {
+        let table = Self::table_for_sub(&env, sub_id)?;
+        let units = Self::get_usage(env, sub_id, period);
+        Self::price(&table, units)
+    }
+
+    /// Close a period and return the amount to charge. Only the configured
+    /// renewal contract may settle. Settling is idempotent so that a renewal
+    /// retried for the same cycle charges the same amount.
+    pub fn settle(env: Env, sub_id: u64, period: u64) -> Result<i128, Error> {
+        let renewal: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::RenewalContract)
+            .ok_or(Error::NotInitialized)?;
+        renewal.require_auth();
+
+        let settled_key = DataKey::Settled(sub_id, period);
+        if let Some(amount) = env.storage().persistent().get::<_, i128>(&settled_key) {
+            return Ok(amount);
+        }
+
+        let table = Self::table_for_sub(&env, sub_id)?;
+        let units = Self::get_usage(env.clone(), sub_id, period);
+        let amount = Self::price(&table, units)?;
+        env.storage().persistent().set(&settled_key, &amount);
+
+        PeriodSettled {
+            sub_id,
+            period,
+            units,
+            amount,
+        }
+        .publish(&env);
+        Ok(amount)
+    }
+
+    pub fn get_settled(env: Env, sub_id: u64, period: u64) -> Option<i128> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Settled(sub_id, period))
+    }
+
+    fn table_for_sub(env: &Env, sub_id: u64) -> Result<RateTable, Error> {
+        let merchant: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SubMerchant(sub_id))
+            .ok_or(Error::SubNotRegistered)?;
+        env.storage()
+            .persistent()
+            .get(&DataKey::RateTable(merchant))
+            .ok_or(Error::RateTableMissing)
+    }
+
+    fn validate_table(table: &RateTable) -> Result<(), Error> {
+        if table.base_amount < 0 || table.tiers.is_empty() {
+            return Err(Error::InvalidRateTable);
+        }
+        let mut prev_up_to: Option<u64> = None;
+        for tier in table.tiers.iter() {
+            if tier.unit_price < 0 {
+                return Err(Error::InvalidRateTable);
+            }
+            if let Some(prev) = prev_up_to {
+                if tier.up_to <= prev {
+                    return Err(Error::InvalidRateTable);
+                }
+            }
+            prev_up_to = Some(tier.up_to);
+        }
+        Ok(())
+    }
+
+    fn price(table: &RateTable, units: u64) -> Result<i128, Error> {
+        let mut total = table.base_amount;
+        let mut billed: u64 = 0;
+
+        for tier in table.tiers.iter() {
+            if billed >= units {
+                break;
+            }
+            let band_end = if tier.up_to < units { tier.up_to } else { units };
+            let band_cost = ((band_end - billed) as i128)
+                .checked_mul(tier.unit_price)
+                .ok_or(Error::Overflow)?;
+            total = total.checked_add(band_cost).ok_or(Error::Overflow)?;
+            billed = band_end;
+        }
+
+        // Overflow usage is billed at the last tier's price
+        if billed < units {
+            let last = table.tiers.last().ok_or(Error::InvalidRateTable)?;
+            let overflow_cost = ((units - billed) as i128)
+                .checked_mul(last.unit_price)
+                .ok_or(Error::Overflow)?;
+            total = total.checked_add(overflow_cost).ok_or(Error::Overflow)?;
+        }
+
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,154 @@
+use super::*;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+/// Helper: registers the contract, initializes it, and publishes a
+/// two-tier rate table for a fresh merchant bound to `sub_id` 1.
+fn setup() -> (Env, UsageMeteringContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(UsageMeteringContract, ());
+    let client = UsageMeteringContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let renewal = Address::generate(&env);
+    client.init(&admin, &renewal);
+
+    let merchant = Address::generate(&env);
+    let table = RateTable {
+        base_amount: 100,
+        tiers: vec![
+            &env,
+            RateTier {
+                up_to: 1000,
+                unit_price: 2,
+            },
+            RateTier {
+                up_to: 5000,
+                unit_price: 1,
+            },
+        ],
+    };
+    client.set_rate_table(&merchant, &table);
+    client.register_sub(&merchant, &1);
+
+    (env, client, merchant)
+}
+
+#[test]
+fn test_graduated_pricing() {
+    let (_env, client, merchant) = setup();
+
+    client.report_usage(&merchant, &1, &202601, &1500);
+    // 100 base + 1000 * 2 + 500 * 1
+    assert_eq!(client.quote(&1, &202601), 2600);
+
+    // Usage past the last tier is billed at the last tier's price
+    client.report_usage(&merchant, &1, &202601, &4000);
+    assert_eq!(client.get_usage(&1, &202601), 5500);
+    assert_eq!(client.quote(&1, &202601), 100 + 2000 + 4000 + 500);
+}
+
+#[test]
+fn test_empty_period_charges_base_amount() {
+    let (_env, client, _merchant) = setup();
+    assert_eq!(client.quote(&1, &202601), 100);
+}
+
+#[test]
+fn test_authorized_meter_can_report() {
+    let (env, client, merchant) = setup();
+
+    let meter = Address::generate(&env);
+    client.authorize_meter(&merchant, &meter);
+    assert!(client.is_meter(&merchant, &meter));
+
+    assert_eq!(client.report_usage(&meter, &1, &202601, &10), 10);
+    assert_eq!(client.report_usage(&meter, &1, &202601, &5), 15);
+}
+
+#[test]
+fn test_unauthorized_reporter_rejected() {
+    let (env, client, merchant) = setup();
+
+    let meter = Address::generate(&env);
+    let result = client.try_report_usage(&meter, &1, &202601, &10);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    client.authorize_meter(&merchant, &meter);
+    client.revoke_meter(&merchant, &meter);
+    let result = client.try_report_usage(&meter, &1, &202601, &10);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_settle_freezes_period() {
+    let (_env, client, merchant) = setup();
+
+    client.report_usage(&merchant, &1, &202601, &10);
+    assert_eq!(client.settle(&1, &202601), 120);
+    assert_eq!(client.get_settled(&1, &202601), Some(120));
+
+    let result = client.try_report_usage(&merchant, &1, &202601, &10);
+    assert_eq!(result, Err(Ok(Error::PeriodSettled)));
+
+    // Next period is still open
+    client.report_usage(&merchant, &1, &202602, &10);
+}
+
+#[test]
+fn test_settle_is_idempotent() {
+    let (_env, client, merchant) = setup();
+
+    client.report_usage(&merchant, &1, &202601, &10);
+    let first = client.settle(&1, &202601);
+    let second = client.settle(&1, &202601);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_unregistered_sub_rejected() {
+    let (_env, client, merchant) = setup();
+
+    let result = client.try_report_usage(&merchant, &2, &202601, &10);
+    assert_eq!(result, Err(Ok(Error::SubNotRegistered)));
+    assert_eq!(client.try_quote(&2, &202601), Err(Ok(Error::SubNotRegistered)));
+}
+
+#[test]
+fn test_sub_cannot_be_registered_twice() {
+    let (env, client, _merchant) = setup();
+
+    let other = Address::generate(&env);
+    let result = client.try_register_sub(&other, &1);
+    assert_eq!(result, Err(Ok(Error::SubAlreadyRegistered)));
+}
+
+#[test]
+fn test_invalid_rate_table_rejected() {
+    let (env, client, merchant) = setup();
+
+    let unordered = RateTable {
+        base_amount: 0,
+        tiers: vec![
+            &env,
+            RateTier {
+                up_to: 100,
+                unit_price: 1,
+            },
+            RateTier {
+                up_to: 50,
+                unit_price: 1,
+            },
+        ],
+    };
+    let result = client.try_set_rate_table(&merchant, &unordered);
+    assert_eq!(result, Err(Ok(Error::InvalidRateTable)));
+
+    let empty = RateTable {
+        base_amount: 0,
+        tiers: vec![&env],
+    };
+    let result = client.try_set_rate_table(&merchant, &empty);
+    assert_eq!(result, Err(Ok(Error::InvalidRateTable)));
+}
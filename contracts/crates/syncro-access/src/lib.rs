@@ -0,0 +1,136 @@
+#![no_std]
+//! Admin and role management shared by the SYNCRO contracts.
+//!
+//! The admin lives in instance storage and is handed over either directly
+//! ([`set_admin`], for a deployer passing a fresh contract on) or in two
+//! steps ([`propose_admin`] then [`accept_admin`]) so a typo cannot lock the
+//! contract. Roles are per-account bitmaps in persistent storage; each
+//! contract defines what its bits mean.
+//!
+//! Keys are symbols and `("roles", account)` tuples, so they cannot collide
+//! with a contract's own `#[contracttype]` keys.
+
+use soroban_sdk::{symbol_short, Address, Env, Symbol};
+
+const ADMIN: Symbol = symbol_short!("admin");
+const PENDING_ADMIN: Symbol = symbol_short!("pend_adm");
+const ROLES: Symbol = symbol_short!("roles");
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AccessError {
+    AlreadyInitialized,
+    NotInitialized,
+    NoPendingAdmin,
+    MissingRole,
+}
+
+// ── Admin ─────────────────────────────────────────────────────────
+
+/// Set the first admin. Fails if the contract already has one.
+pub fn init_admin(env: &Env, admin: &Address) -> Result<(), AccessError> {
+    if has_admin(env) {
+        return Err(AccessError::AlreadyInitialized);
+    }
+    env.storage().instance().set(&ADMIN, admin);
+    Ok(())
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&ADMIN)
+}
+
+pub fn admin(env: &Env) -> Result<Address, AccessError> {
+    env.storage()
+        .instance()
+        .get(&ADMIN)
+        .ok_or(AccessError::NotInitialized)
+}
+
+/// Load the admin and require its authorization.
+pub fn require_admin(env: &Env) -> Result<Address, AccessError> {
+    let admin = admin(env)?;
+    admin.require_auth();
+    Ok(admin)
+}
+
+/// Replace the admin immediately. Requires the current admin's
+/// authorization and returns it.
+pub fn set_admin(env: &Env, new_admin: &Address) -> Result<Address, AccessError> {
+    let previous = require_admin(env)?;
+    env.storage().instance().set(&ADMIN, new_admin);
+    env.storage().instance().remove(&PENDING_ADMIN);
+    Ok(previous)
+}
+
+/// First step of a handover: the current admin nominates `new_admin`,
+/// replacing any earlier nomination.
+pub fn propose_admin(env: &Env, new_admin: &Address) -> Result<(), AccessError> {
+    require_admin(env)?;
+    env.storage().instance().set(&PENDING_ADMIN, new_admin);
+    Ok(())
+}
+
+/// Second step of a handover: the nominee takes over. Returns the previous
+/// and new admin.
+pub fn accept_admin(env: &Env) -> Result<(Address, Address), AccessError> {
+    let previous = admin(env)?;
+    let pending = pending_admin(env).ok_or(AccessError::NoPendingAdmin)?;
+    pending.require_auth();
+    env.storage().instance().set(&ADMIN, &pending);
+    env.storage().instance().remove(&PENDING_ADMIN);
+    Ok((previous, pending))
+}
+
+pub fn pending_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&PENDING_ADMIN)
+}
+
+// ── Roles ─────────────────────────────────────────────────────────
+
+/// Role bits held by `account`.
+pub fn roles(env: &Env, account: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&(ROLES, account.clone()))
+        .unwrap_or(0)
+}
+
+/// Whether `account` holds every bit of `role`.
+pub fn has_role(env: &Env, account: &Address, role: u32) -> bool {
+    roles(env, account) & role == role
+}
+
+/// Add `role` bits to `account`. Admin only. Returns the new bitmap.
+pub fn grant_role(env: &Env, account: &Address, role: u32) -> Result<u32, AccessError> {
+    require_admin(env)?;
+    let bits = roles(env, account) | role;
+    env.storage()
+        .persistent()
+        .set(&(ROLES, account.clone()), &bits);
+    Ok(bits)
+}
+
+/// Remove `role` bits from `account`. Admin only. Returns the new bitmap.
+pub fn revoke_role(env: &Env, account: &Address, role: u32) -> Result<u32, AccessError> {
+    require_admin(env)?;
+    let bits = roles(env, account) & !role;
+    let key = (ROLES, account.clone());
+    if bits == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &bits);
+    }
+    Ok(bits)
+}
+
+/// Require `account`'s authorization and every bit of `role`.
+pub fn require_role(env: &Env, account: &Address, role: u32) -> Result<(), AccessError> {
+    account.require_auth();
+    if !has_role(env, account, role) {
+        return Err(AccessError::MissingRole);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
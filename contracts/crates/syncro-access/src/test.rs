@@ -0,0 +1,105 @@
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, Address, Env};
+
+#[contract]
+struct Host;
+
+fn with_host(f: impl FnOnce(&Env)) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let host = env.register(Host, ());
+    env.as_contract(&host, || f(&env));
+}
+
+/// Like `with_host` but hands out the host so each step can run in its own
+/// frame, as separate contract calls would: an address can only authorize
+/// once per frame.
+fn host() -> (Env, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let host = env.register(Host, ());
+    (env, host)
+}
+
+const PAUSER: u32 = 1;
+const OPERATOR: u32 = 2;
+
+#[test]
+fn test_init_admin_once() {
+    with_host(|env| {
+        let owner = Address::generate(env);
+        assert_eq!(require_admin(env), Err(AccessError::NotInitialized));
+        assert_eq!(init_admin(env, &owner), Ok(()));
+        assert_eq!(
+            init_admin(env, &Address::generate(env)),
+            Err(AccessError::AlreadyInitialized)
+        );
+        assert_eq!(require_admin(env), Ok(owner));
+    });
+}
+
+#[test]
+fn test_two_step_transfer() {
+    with_host(|env| {
+        let owner = Address::generate(env);
+        let next = Address::generate(env);
+        init_admin(env, &owner).unwrap();
+        assert_eq!(accept_admin(env), Err(AccessError::NoPendingAdmin));
+
+        propose_admin(env, &next).unwrap();
+        // Nothing changes until the nominee accepts
+        assert_eq!(admin(env), Ok(owner.clone()));
+        assert_eq!(pending_admin(env), Some(next.clone()));
+
+        assert_eq!(accept_admin(env), Ok((owner, next.clone())));
+        assert_eq!(admin(env), Ok(next));
+        assert_eq!(pending_admin(env), None);
+    });
+}
+
+#[test]
+fn test_set_admin_clears_nomination() {
+    let (env, host) = host();
+    let owner = Address::generate(&env);
+    let next = Address::generate(&env);
+    env.as_contract(&host, || {
+        init_admin(&env, &owner).unwrap();
+        propose_admin(&env, &Address::generate(&env)).unwrap();
+    });
+
+    env.as_contract(&host, || {
+        assert_eq!(set_admin(&env, &next), Ok(owner));
+        assert_eq!(admin(&env), Ok(next));
+        assert_eq!(pending_admin(&env), None);
+    });
+}
+
+#[test]
+fn test_role_bitmaps() {
+    let (env, host) = host();
+    let account = Address::generate(&env);
+    env.as_contract(&host, || {
+        init_admin(&env, &Address::generate(&env)).unwrap();
+        assert_eq!(
+            require_role(&env, &account, PAUSER),
+            Err(AccessError::MissingRole)
+        );
+        assert_eq!(grant_role(&env, &account, PAUSER), Ok(PAUSER));
+    });
+
+    env.as_contract(&host, || {
+        assert_eq!(grant_role(&env, &account, OPERATOR), Ok(PAUSER | OPERATOR));
+        assert_eq!(require_role(&env, &account, PAUSER | OPERATOR), Ok(()));
+    });
+
+    env.as_contract(&host, || {
+        assert_eq!(revoke_role(&env, &account, PAUSER), Ok(OPERATOR));
+        assert!(!has_role(&env, &account, PAUSER));
+        assert!(has_role(&env, &account, OPERATOR));
+    });
+
+    env.as_contract(&host, || {
+        assert_eq!(revoke_role(&env, &account, OPERATOR), Ok(0));
+        assert_eq!(roles(&env, &account), 0);
+    });
+}
@@ -0,0 +1,77 @@
+//! An owner's subscription index, as the ordered `Vec` the renewal and
+//! registry contracts keep, or as a `Map` keyed by subscription id.
+
+use soroban_sdk::{contract, contractimpl, Address, Env, Map, Vec};
+
+#[contract]
+pub struct VecIndex;
+
+#[contractimpl]
+impl VecIndex {
+    pub fn add(env: Env, owner: Address, sub_id: u64) {
+        let mut subs: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&owner)
+            .unwrap_or(Vec::new(&env));
+        if !subs.contains(sub_id) {
+            subs.push_back(sub_id);
+            env.storage().persistent().set(&owner, &subs);
+        }
+    }
+
+    pub fn remove(env: Env, owner: Address, sub_id: u64) {
+        let mut subs: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&owner)
+            .unwrap_or(Vec::new(&env));
+        if let Some(index) = subs.first_index_of(sub_id) {
+            subs.remove(index);
+            env.storage().persistent().set(&owner, &subs);
+        }
+    }
+
+    pub fn contains(env: Env, owner: Address, sub_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .get::<_, Vec<u64>>(&owner)
+            .is_some_and(|subs| subs.contains(sub_id))
+    }
+}
+
+#[contract]
+pub struct MapIndex;
+
+#[contractimpl]
+impl MapIndex {
+    pub fn add(env: Env, owner: Address, sub_id: u64) {
+        let mut subs: Map<u64, ()> = env
+            .storage()
+            .persistent()
+            .get(&owner)
+            .unwrap_or(Map::new(&env));
+        if !subs.contains_key(sub_id) {
+            subs.set(sub_id, ());
+            env.storage().persistent().set(&owner, &subs);
+        }
+    }
+
+    pub fn remove(env: Env, owner: Address, sub_id: u64) {
+        let mut subs: Map<u64, ()> = env
+            .storage()
+            .persistent()
+            .get(&owner)
+            .unwrap_or(Map::new(&env));
+        if subs.remove(sub_id).is_some() {
+            env.storage().persistent().set(&owner, &subs);
+        }
+    }
+
+    pub fn contains(env: Env, owner: Address, sub_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .get::<_, Map<u64, ()>>(&owner)
+            .is_some_and(|subs| subs.contains_key(sub_id))
+    }
+}
@@ -0,0 +1,48 @@
+//! Storage layout benchmarks. Each module stores one hot structure of the
+//! SYNCRO contracts in alternative layouts, behind the same small contract
+//! interface; the tests run the same operation against each layout and
+//! compare the resources metered for it, so redesigns of persistent storage
+//! start from measurements.
+//!
+//! Run with `--nocapture` to see every measurement:
+//!
+//! ```text
+//! cargo test -p syncro-bench -- --nocapture
+//! ```
+
+mod index;
+mod log;
+mod subs;
+
+pub use index::{MapIndex, MapIndexClient, VecIndex, VecIndexClient};
+pub use log::{PagedLog, PagedLogClient};
+pub use subs::{MonolithicSubs, MonolithicSubsClient, SplitSubs, SplitSubsClient};
+
+use soroban_sdk::Env;
+
+/// Resources metered for the last top-level invocation in an `Env`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cost {
+    pub instructions: i64,
+    pub mem_bytes: i64,
+    /// Footprint reads, from disk and from memory.
+    pub read_entries: u32,
+    pub write_entries: u32,
+    pub write_bytes: u32,
+}
+
+impl Cost {
+    pub fn of_last_invocation(env: &Env) -> Self {
+        let used = env.cost_estimate().resources();
+        Self {
+            instructions: used.instructions,
+            mem_bytes: used.mem_bytes,
+            read_entries: used.disk_read_entries + used.memory_read_entries,
+            write_entries: used.write_entries,
+            write_bytes: used.write_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
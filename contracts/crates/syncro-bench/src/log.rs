@@ -0,0 +1,56 @@
+//! A subscription's log kept in pages of a fixed number of entries. The
+//! logging contract today keeps each subscription's log in one unbounded
+//! page, which a page size of 0 stands for.
+
+use soroban_sdk::{contract, contractimpl, contracttype, Env, String, Vec};
+
+/// Mirror of the logging contract's `LogEntry`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogEntry {
+    pub sub_id: u64,
+    pub event: u32,
+    pub timestamp: u64,
+    pub data: String,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum LogKey {
+    PageSize,
+    Count(u64),
+    Page(u64, u32),
+}
+
+#[contract]
+pub struct PagedLog;
+
+#[contractimpl]
+impl PagedLog {
+    pub fn __constructor(env: Env, page_size: u32) {
+        env.storage().instance().set(&LogKey::PageSize, &page_size);
+    }
+
+    pub fn append(env: Env, sub_id: u64, data: String) {
+        let page_size: u32 = env.storage().instance().get(&LogKey::PageSize).unwrap();
+        let storage = env.storage().persistent();
+        let count: u32 = storage.get(&LogKey::Count(sub_id)).unwrap_or(0);
+        let key = LogKey::Page(sub_id, count.checked_div(page_size).unwrap_or(0));
+        let mut page: Vec<LogEntry> = storage.get(&key).unwrap_or(Vec::new(&env));
+        page.push_back(LogEntry {
+            sub_id,
+            event: 0,
+            timestamp: env.ledger().timestamp(),
+            data,
+        });
+        storage.set(&key, &page);
+        storage.set(&LogKey::Count(sub_id), &(count + 1));
+    }
+
+    pub fn page(env: Env, sub_id: u64, index: u32) -> Vec<LogEntry> {
+        env.storage()
+            .persistent()
+            .get(&LogKey::Page(sub_id, index))
+            .unwrap_or(Vec::new(&env))
+    }
+}
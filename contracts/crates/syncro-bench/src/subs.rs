@@ -0,0 +1,120 @@
+//! `SubscriptionData` kept in one entry, as the renewal contract keeps it,
+//! or split into terms fixed at creation and status changed by each
+//! renewal.
+
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env};
+
+/// Every field of the renewal contract's `SubscriptionData`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    pub terms: Terms,
+    pub status: Status,
+}
+
+/// What the owner and merchant agreed; read by every renewal to check its
+/// integrity hash, written only when the terms change.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Terms {
+    pub owner: Address,
+    pub merchant: Address,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub integrity_hash: BytesN<32>,
+}
+
+/// What each renewal attempt updates.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Status {
+    pub state: u32,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+fn new_subscription(env: &Env, owner: Address, merchant: Address) -> Subscription {
+    Subscription {
+        terms: Terms {
+            owner,
+            merchant,
+            amount: 1599,
+            frequency: 2_592_000,
+            spending_cap: 20_000,
+            integrity_hash: BytesN::from_array(env, &[7; 32]),
+        },
+        status: Status {
+            state: 0,
+            failure_count: 0,
+            last_attempt_ledger: 0,
+        },
+    }
+}
+
+/// The whole subscription under its id.
+#[contract]
+pub struct MonolithicSubs;
+
+#[contractimpl]
+impl MonolithicSubs {
+    pub fn create(env: Env, sub_id: u64, owner: Address, merchant: Address) {
+        env.storage()
+            .persistent()
+            .set(&sub_id, &new_subscription(&env, owner, merchant));
+    }
+
+    /// What a failed renewal does: read everything, count the failure.
+    pub fn record_failure(env: Env, sub_id: u64) {
+        let mut sub: Subscription = env.storage().persistent().get(&sub_id).unwrap();
+        sub.status.failure_count += 1;
+        sub.status.last_attempt_ledger = env.ledger().sequence();
+        env.storage().persistent().set(&sub_id, &sub);
+    }
+
+    pub fn amount(env: Env, sub_id: u64) -> i128 {
+        let sub: Subscription = env.storage().persistent().get(&sub_id).unwrap();
+        sub.terms.amount
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum SplitKey {
+    Terms(u64),
+    Status(u64),
+}
+
+/// Terms and status under separate keys.
+#[contract]
+pub struct SplitSubs;
+
+#[contractimpl]
+impl SplitSubs {
+    pub fn create(env: Env, sub_id: u64, owner: Address, merchant: Address) {
+        let sub = new_subscription(&env, owner, merchant);
+        let storage = env.storage().persistent();
+        storage.set(&SplitKey::Terms(sub_id), &sub.terms);
+        storage.set(&SplitKey::Status(sub_id), &sub.status);
+    }
+
+    /// What a failed renewal does: read the terms to check them, count the
+    /// failure in the status.
+    pub fn record_failure(env: Env, sub_id: u64) {
+        let storage = env.storage().persistent();
+        let _terms: Terms = storage.get(&SplitKey::Terms(sub_id)).unwrap();
+        let mut status: Status = storage.get(&SplitKey::Status(sub_id)).unwrap();
+        status.failure_count += 1;
+        status.last_attempt_ledger = env.ledger().sequence();
+        storage.set(&SplitKey::Status(sub_id), &status);
+    }
+
+    pub fn amount(env: Env, sub_id: u64) -> i128 {
+        let terms: Terms = env
+            .storage()
+            .persistent()
+            .get(&SplitKey::Terms(sub_id))
+            .unwrap();
+        terms.amount
+    }
+}
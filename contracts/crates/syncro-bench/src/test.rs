@@ -0,0 +1,98 @@
+use soroban_sdk::{testutils::Address as _, Address, Env, String};
+
+use crate::*;
+
+/// Print and return the cost of the invocation `run` makes.
+fn measure(env: &Env, label: &str, run: impl FnOnce()) -> Cost {
+    run();
+    let cost = Cost::of_last_invocation(env);
+    println!("{label}: {cost:?}");
+    cost
+}
+
+#[test]
+fn test_split_subscription_writes_less_per_renewal() {
+    let env = Env::default();
+    let mono = MonolithicSubsClient::new(&env, &env.register(MonolithicSubs, ()));
+    let split = SplitSubsClient::new(&env, &env.register(SplitSubs, ()));
+    mono.create(&1, &Address::generate(&env), &Address::generate(&env));
+    split.create(&1, &Address::generate(&env), &Address::generate(&env));
+
+    // A renewal rewrites only the status, at the price of one more read
+    let mono_failure = measure(&env, "monolithic record_failure", || {
+        mono.record_failure(&1)
+    });
+    let split_failure = measure(&env, "split record_failure", || split.record_failure(&1));
+    assert!(split_failure.write_bytes * 2 < mono_failure.write_bytes);
+    assert_eq!(split_failure.read_entries, mono_failure.read_entries + 1);
+    assert_eq!(split_failure.write_entries, mono_failure.write_entries);
+
+    // Reading the terms alone costs no more than reading everything
+    let mono_read = measure(&env, "monolithic amount", || {
+        mono.amount(&1);
+    });
+    let split_read = measure(&env, "split amount", || {
+        split.amount(&1);
+    });
+    assert_eq!(split_read.read_entries, mono_read.read_entries);
+    assert!(split_read.instructions <= mono_read.instructions);
+}
+
+#[test]
+fn test_log_append_cost_grows_with_page_size() {
+    let env = Env::default();
+    let data = String::from_str(&env, "Renewal successful");
+
+    // Each append measured fills its page, the dearest append for the
+    // page size; 0 is one unbounded page, as the logging contract keeps
+    let costs: std::vec::Vec<Cost> = [8u32, 32, 128, 0]
+        .into_iter()
+        .map(|page_size| {
+            let log = PagedLogClient::new(&env, &env.register(PagedLog, (page_size,)));
+            for _ in 0..255 {
+                log.append(&1, &data);
+            }
+            measure(&env, &format!("append, page size {page_size}"), || {
+                log.append(&1, &data)
+            })
+        })
+        .collect();
+    for pair in costs.windows(2) {
+        assert!(pair[0].write_bytes < pair[1].write_bytes);
+        assert!(pair[0].instructions < pair[1].instructions);
+        assert_eq!(pair[0].write_entries, pair[1].write_entries);
+    }
+}
+
+#[test]
+fn test_vec_index_costs_no_more_than_map() {
+    let env = Env::default();
+    let vec = VecIndexClient::new(&env, &env.register(VecIndex, ()));
+    let map = MapIndexClient::new(&env, &env.register(MapIndex, ()));
+    let owner = Address::generate(&env);
+    let n = 100u64;
+    for id in 0..n {
+        vec.add(&owner, &id);
+        map.add(&owner, &id);
+    }
+
+    let vec_add = measure(&env, "vec add", || vec.add(&owner, &n));
+    let map_add = measure(&env, "map add", || map.add(&owner, &n));
+    let vec_remove = measure(&env, "vec remove", || vec.remove(&owner, &(n / 2)));
+    let map_remove = measure(&env, "map remove", || map.remove(&owner, &(n / 2)));
+    for (vec, map) in [(vec_add, map_add), (vec_remove, map_remove)] {
+        assert!(vec.write_bytes < map.write_bytes);
+        assert!(vec.mem_bytes < map.mem_bytes);
+        assert!(vec.instructions < map.instructions);
+    }
+
+    // A linear scan of an owner's ids is no slower than a map lookup at
+    // this size, to within 5%
+    let vec_contains = measure(&env, "vec contains", || {
+        vec.contains(&owner, &(n - 1));
+    });
+    let map_contains = measure(&env, "map contains", || {
+        map.contains(&owner, &(n - 1));
+    });
+    assert!(vec_contains.instructions * 100 <= map_contains.instructions * 105);
+}
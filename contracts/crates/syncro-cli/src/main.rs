@@ -0,0 +1,267 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use syncro_sdk::{clients, invoke::StellarCli, Contracts, Syncro};
+
+#[derive(Parser)]
+#[command(name = "syncro", about = "Operator tool for the SYNCRO contracts")]
+struct Cli {
+    /// Network name passed to the `stellar` CLI.
+    #[arg(long, env = "SYNCRO_NETWORK", default_value = "testnet", global = true)]
+    network: String,
+
+    /// Identity or secret used to sign.
+    #[arg(long, env = "SYNCRO_SOURCE", default_value = "default", global = true)]
+    source: String,
+
+    #[arg(long, env = "SYNCRO_RENEWAL_ID", global = true)]
+    renewal_id: Option<String>,
+
+    #[arg(long, env = "SYNCRO_AGENTS_ID", global = true)]
+    agents_id: Option<String>,
+
+    #[arg(long, env = "SYNCRO_REGISTRY_ID", global = true)]
+    registry_id: Option<String>,
+
+    #[arg(long, env = "SYNCRO_LOGGING_ID", global = true)]
+    logging_id: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Subscription renewal contract.
+    #[command(subcommand)]
+    Renewal(RenewalCmd),
+    /// Agent registry contract.
+    #[command(subcommand)]
+    Agents(AgentsCmd),
+    /// Subscription registry contract.
+    #[command(subcommand)]
+    Registry(RegistryCmd),
+    /// Logging contract.
+    #[command(subcommand)]
+    Logging(LoggingCmd),
+}
+
+#[derive(Subcommand)]
+enum RenewalCmd {
+    Init {
+        admin: String,
+    },
+    SetAdmin {
+        new_admin: String,
+    },
+    Pause {
+        /// Incident code recorded with the pause.
+        #[arg(long)]
+        reason: Option<u32>,
+    },
+    Unpause,
+    /// Pause renewals and trip the circuit breaker for every contract on it.
+    PauseAll {
+        /// Incident symbol recorded by the breaker.
+        reason: String,
+    },
+    /// Give an account role bits: 1 pauser, 2 config manager, 4 treasurer.
+    GrantRole {
+        account: String,
+        role: u32,
+    },
+    RevokeRole {
+        account: String,
+        role: u32,
+    },
+    /// List the accounts holding a role.
+    Roles,
+    Status,
+    /// Show who paused renewals, when and why.
+    PauseInfo,
+    /// Show the latest pause and unpause changes.
+    PauseHistory,
+    /// Serve subscription details only to owners, merchants and auditors.
+    Private,
+    /// Serve subscription details to anyone.
+    Public,
+    /// Set the token renewals are charged in.
+    SetToken {
+        address: String,
+    },
+    SetLogging {
+        address: String,
+    },
+    SetMetering {
+        address: String,
+    },
+    SetReceipts {
+        address: String,
+    },
+    /// Show the renewal lock held on a subscription.
+    Lock {
+        sub_id: u64,
+    },
+    /// Cancel every subscription an owner has, `batch` per transaction.
+    Offboard {
+        owner: String,
+        #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+        batch: u32,
+    },
+    /// Show the profile a merchant applies to its subscriptions.
+    Profile {
+        merchant: String,
+    },
+    /// Release a stuck renewal lock.
+    ReleaseLock {
+        sub_id: u64,
+    },
+    /// Show a subscription with its lifecycle timestamps and lock.
+    Inspect {
+        sub_id: u64,
+        /// Read as this viewer, as privacy mode requires.
+        #[arg(long = "as")]
+        viewer: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AgentsCmd {
+    Init {
+        admin: String,
+    },
+    Register {
+        agent: String,
+    },
+    /// Replace an agent's scopes, e.g. `renewals,approvals` or a bitmask.
+    Scopes {
+        agent: String,
+        scopes: String,
+    },
+    /// Point the registry at the renewal contract whose cached grants it
+    /// invalidates, or unset it when no address is given.
+    Renewal {
+        renewal: Option<String>,
+    },
+    /// Revoke an agent, noting why, e.g. `--reason agent_compromised`.
+    Revoke {
+        agent: String,
+        #[arg(long, default_value = "agent_retired")]
+        reason: String,
+    },
+    Check {
+        agent: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum RegistryCmd {
+    Get { subscription_id: String },
+    User { user: String },
+}
+
+#[derive(Subcommand)]
+enum LoggingCmd {
+    Logs {
+        sub_id: u64,
+        /// Read as this viewer, as privacy mode requires.
+        #[arg(long = "as")]
+        viewer: Option<String>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let invoker = StellarCli {
+        network: cli.network.clone(),
+        source_account: cli.source.clone(),
+    };
+    let syncro = Syncro::new(
+        &invoker,
+        Contracts {
+            renewal: cli.renewal_id.clone(),
+            registry: cli.registry_id.clone(),
+            agents: cli.agents_id.clone(),
+            logging: cli.logging_id.clone(),
+            ..Default::default()
+        },
+    );
+
+    let out = match &cli.command {
+        Command::Renewal(cmd) => {
+            let c = syncro.renewal()?;
+            match cmd {
+                RenewalCmd::Init { admin } => c.init(admin)?,
+                RenewalCmd::SetAdmin { new_admin } => c.set_admin(new_admin)?,
+                RenewalCmd::Pause { reason } => c.set_paused(&cli.source, true, *reason)?,
+                RenewalCmd::Unpause => c.set_paused(&cli.source, false, None)?,
+                RenewalCmd::PauseAll { reason } => c.pause_all(&cli.source, reason)?,
+                RenewalCmd::GrantRole { account, role } => c.grant_role(account, *role)?,
+                RenewalCmd::RevokeRole { account, role } => c.revoke_role(account, *role)?,
+                RenewalCmd::Roles => c.get_role_holders()?,
+                RenewalCmd::Status => c.is_paused()?,
+                RenewalCmd::PauseInfo => c.get_pause_info()?,
+                RenewalCmd::PauseHistory => c.get_pause_history()?,
+                RenewalCmd::Private => c.set_privacy_mode(true)?,
+                RenewalCmd::Public => c.set_privacy_mode(false)?,
+                RenewalCmd::SetToken { address } => c.set_token(address)?,
+                RenewalCmd::SetLogging { address } => c.set_logging_contract(address)?,
+                RenewalCmd::SetMetering { address } => c.set_metering_contract(address)?,
+                RenewalCmd::SetReceipts { address } => c.set_receipt_contract(address)?,
+                RenewalCmd::Lock { sub_id } => c.get_renewal_lock(*sub_id)?,
+                RenewalCmd::Offboard { owner, batch } => {
+                    let subs = c.get_owner_subs(owner)?;
+                    while c.cancel_all_for_owner(owner, *batch)? > 0 {}
+                    serde_json::json!({ "cancelled": subs })
+                }
+                RenewalCmd::Profile { merchant } => c.get_merchant_profile(merchant)?,
+                RenewalCmd::ReleaseLock { sub_id } => c.release_renewal_lock(*sub_id)?,
+                RenewalCmd::Inspect { sub_id, viewer } => serde_json::json!({
+                    "subscription": match viewer {
+                        Some(viewer) => c.get_sub_as(viewer, *sub_id)?,
+                        None => c.get_sub(*sub_id)?,
+                    },
+                    "lifecycle": match viewer {
+                        Some(viewer) => c.get_lifecycle_as(viewer, *sub_id)?,
+                        None => c.get_lifecycle(*sub_id)?,
+                    },
+                    "lock": c.get_renewal_lock(*sub_id)?,
+                    "metered": c.is_metered(*sub_id)?,
+                }),
+            }
+        }
+        Command::Agents(cmd) => {
+            let c = syncro.agents()?;
+            match cmd {
+                AgentsCmd::Init { admin } => c.init(admin)?,
+                AgentsCmd::Register { agent } => c.register(agent)?,
+                AgentsCmd::Scopes { agent, scopes } => {
+                    c.update_scopes(agent, clients::parse_scopes(scopes)?)?
+                }
+                AgentsCmd::Revoke { agent, reason } => {
+                    c.revoke_agent(agent, clients::parse_reason(reason)?)?
+                }
+                AgentsCmd::Renewal { renewal } => c.set_renewal_contract(renewal.as_deref())?,
+                AgentsCmd::Check { agent } => c.is_authorized(agent)?,
+            }
+        }
+        Command::Registry(cmd) => {
+            let c = syncro.registry()?;
+            match cmd {
+                RegistryCmd::Get { subscription_id } => c.get_subscription(subscription_id)?,
+                RegistryCmd::User { user } => c.get_user_subscriptions(user)?,
+            }
+        }
+        Command::Logging(LoggingCmd::Logs { sub_id, viewer }) => {
+            let c = syncro.logging()?;
+            match viewer {
+                Some(viewer) => c.get_logs_as(viewer, *sub_id)?,
+                None => c.get_logs(*sub_id)?,
+            }
+        }
+    };
+
+    if !out.is_null() {
+        println!("{}", serde_json::to_string_pretty(&out)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,393 @@
+#![no_std]
+//! Types and constants shared by the SYNCRO contracts.
+//!
+//! Anything that crosses a contract boundary (subscription states, agent
+//! scopes, log event kinds) lives here so every contract agrees on a single
+//! encoding.
+
+use soroban_sdk::{contracttype, Address, BytesN, String, Symbol, Vec};
+
+/// Represents the current state of a subscription
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SubscriptionState {
+    Active,
+    Retrying,
+    Failed,
+    Cancelled,
+    /// Left unrenewed past the delinquency horizon; final.
+    Expired,
+    /// Held by its owner; `resume_sub` returns it to its previous state.
+    Paused,
+}
+
+/// Permission bits granted to agents in the agent registry.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Scope {
+    Renewals = 1,
+    GiftCards = 2,
+    Approvals = 4,
+    /// Read subscription details while the renewal contract is in privacy
+    /// mode.
+    Audit = 8,
+}
+
+impl Scope {
+    /// Bitmask with every scope set.
+    pub const ALL: u32 = Scope::Renewals as u32
+        | Scope::GiftCards as u32
+        | Scope::Approvals as u32
+        | Scope::Audit as u32;
+
+    pub const fn bit(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Why something was rejected, failed or was revoked. Events, error paths
+/// and stored records quote these codes, so one number means the same
+/// thing wherever it appears. A published code never changes meaning.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ReasonCode {
+    ApprovalExpired = 1,
+    ApprovalUsed = 2,
+    AmountExceeded = 3,
+    ApprovalNotFound = 4,
+    /// The renewal charge did not go through; retries remain.
+    PaymentFailed = 10,
+    /// The renewal charge failed with no retries left.
+    RetriesExhausted = 11,
+    AgentCompromised = 20,
+    AgentMisbehaved = 21,
+    /// Taken out of service with nothing held against it.
+    AgentRetired = 22,
+    /// Cancellation survey answers, given by the owner when cancelling.
+    TooExpensive = 30,
+    NotUsed = 31,
+    SwitchingService = 32,
+    MissingFeatures = 33,
+    PoorService = 34,
+    OtherCancellation = 39,
+}
+
+/// Kinds of entries recorded by the logging contract. Discriminants are the
+/// numeric `event_type` codes other contracts pass when logging.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LogEvent {
+    Reminder,
+    Approval,
+    Renewal,
+    Failure,
+    Retry,
+    Cancellation,
+}
+
+/// Payload pushed to contracts registered with the callback registry.
+/// `kind` names the event (`renewed`, `renew_failed`, `cancelled`, ...);
+/// fields that do not apply to an event are zero.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Notification {
+    pub kind: Symbol,
+    pub sub_id: u64,
+    pub merchant: Address,
+    pub cycle_id: u64,
+    pub amount: i128,
+}
+
+/// Arguments of one renewal contract `renew` call inside a batch.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RenewOp {
+    pub approval_id: u64,
+    pub amount: i128,
+    pub cycle_id: u64,
+}
+
+/// One operation of a batch item, applied to the item's subscription.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BatchOp {
+    /// Acquire the renewal lock for this many ledgers.
+    AcquireLock(u32),
+    Renew(RenewOp),
+    RecordLog(LogEvent, String),
+    /// Advance the registry entry to its next renewal. Only valid after a
+    /// `Renew` in the same item, and skipped if that renewal failed.
+    MarkRegistryRenewed(BytesN<32>),
+}
+
+/// Operations on one subscription, applied all or nothing.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchItem {
+    pub sub_id: u64,
+    pub ops: Vec<BatchOp>,
+}
+
+/// What became of one batch item.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ItemOutcome {
+    /// Every operation applied; the item had no `Renew`.
+    Executed,
+    Renewed,
+    /// The renewal was attempted and failed; the failure is recorded.
+    RenewalFailed,
+    /// An operation errored and the whole item was rolled back.
+    Reverted,
+}
+
+/// Price of an asset as reported by the oracle adapter, scaled by the
+/// adapter's `decimals()`, with the time it was observed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Contract error code ranges. Each contract numbers its `#[contracterror]`
+/// variants inside its own range so an error code identifies its source:
+/// the renewal contract uses 100–199, the registry 200–299 and so on.
+/// Contracts that still panic instead of returning typed errors keep their
+/// range reserved.
+pub mod errors {
+    pub const RENEWAL_BASE: u32 = 100;
+    pub const REGISTRY_BASE: u32 = 200;
+    pub const AGENT_REGISTRY_BASE: u32 = 300;
+    pub const LOGGING_BASE: u32 = 400;
+    pub const USAGE_METERING_BASE: u32 = 500;
+    pub const RENEWAL_RECEIPTS_BASE: u32 = 600;
+    pub const KEEPER_BOUNTY_BASE: u32 = 700;
+    pub const MULTISIG_ADMIN_BASE: u32 = 800;
+    pub const SUITE_FACTORY_BASE: u32 = 900;
+    pub const SUBSCRIPTION_ACCOUNT_BASE: u32 = 1000;
+    pub const ARBITRATION_BASE: u32 = 1100;
+    pub const LOYALTY_BASE: u32 = 1200;
+    pub const BUNDLE_BASE: u32 = 1300;
+    pub const COST_SPLIT_BASE: u32 = 1400;
+    pub const CALLBACK_REGISTRY_BASE: u32 = 1500;
+    pub const INSURANCE_POOL_BASE: u32 = 1600;
+    pub const SWAP_ADAPTER_BASE: u32 = 1700;
+    pub const PREPAY_BASE: u32 = 1800;
+    pub const TAX_BASE: u32 = 1900;
+    pub const CIRCUIT_BREAKER_BASE: u32 = 2000;
+    pub const BRIDGE_INTAKE_BASE: u32 = 2100;
+    pub const SYNCRO_TOKEN_BASE: u32 = 2200;
+    pub const MARKETPLACE_BASE: u32 = 2300;
+    pub const STATUS_SNAPSHOT_BASE: u32 = 2400;
+    pub const SPONSORSHIP_BASE: u32 = 2500;
+    pub const GUARDIAN_RECOVERY_BASE: u32 = 2600;
+    pub const RENEWAL_SCHEDULER_BASE: u32 = 2700;
+    pub const BATCH_EXECUTOR_BASE: u32 = 2800;
+    pub const BATCH_RUNNER_BASE: u32 = 2900;
+    pub const TWAP_BASE: u32 = 3000;
+    pub const MULTICALL_BASE: u32 = 3200;
+    pub const ADDRESS_BOOK_BASE: u32 = 3300;
+
+    /// Width of every range.
+    pub const RANGE_SIZE: u32 = 100;
+
+    /// Every range's base with the contract it belongs to.
+    pub const SOURCES: &[(u32, &str)] = &[
+        (RENEWAL_BASE, "subscription_renewal"),
+        (REGISTRY_BASE, "subscription_registry"),
+        (AGENT_REGISTRY_BASE, "agent_registry"),
+        (LOGGING_BASE, "subscription_logging"),
+        (USAGE_METERING_BASE, "usage_metering"),
+        (RENEWAL_RECEIPTS_BASE, "renewal_receipts"),
+        (KEEPER_BOUNTY_BASE, "keeper_bounty"),
+        (MULTISIG_ADMIN_BASE, "multisig_admin"),
+        (SUITE_FACTORY_BASE, "suite_factory"),
+        (SUBSCRIPTION_ACCOUNT_BASE, "subscription_account"),
+        (ARBITRATION_BASE, "arbitration"),
+        (LOYALTY_BASE, "loyalty"),
+        (BUNDLE_BASE, "bundle"),
+        (COST_SPLIT_BASE, "cost_split"),
+        (CALLBACK_REGISTRY_BASE, "callback_registry"),
+        (INSURANCE_POOL_BASE, "insurance_pool"),
+        (SWAP_ADAPTER_BASE, "swap_adapter"),
+        (PREPAY_BASE, "prepay"),
+        (TAX_BASE, "tax"),
+        (CIRCUIT_BREAKER_BASE, "circuit_breaker"),
+        (BRIDGE_INTAKE_BASE, "bridge_intake"),
+        (SYNCRO_TOKEN_BASE, "syncro_token"),
+        (MARKETPLACE_BASE, "marketplace"),
+        (STATUS_SNAPSHOT_BASE, "status_snapshot"),
+        (SPONSORSHIP_BASE, "sponsorship"),
+        (GUARDIAN_RECOVERY_BASE, "guardian_recovery"),
+        (RENEWAL_SCHEDULER_BASE, "renewal_scheduler"),
+        (BATCH_EXECUTOR_BASE, "batch_executor"),
+        (BATCH_RUNNER_BASE, "batch_runner"),
+        (TWAP_BASE, "twap"),
+        (MULTICALL_BASE, "multicall"),
+        (ADDRESS_BOOK_BASE, "address_book"),
+    ];
+
+    /// Name of the contract whose range holds `code`.
+    pub fn source(code: u32) -> Option<&'static str> {
+        let base = code / RANGE_SIZE * RANGE_SIZE;
+        SOURCES
+            .iter()
+            .find(|(start, _)| *start == base)
+            .map(|(_, name)| *name)
+    }
+}
+
+/// Event schema versions.
+///
+/// Every contract publishes [`EventSchema`](events::EventSchema) when it is
+/// initialized and reports the version from `get_event_version()`, so an
+/// indexer can tell which decoders apply to a contract's events.
+pub mod events {
+    use soroban_sdk::{contractevent, Env};
+
+    /// Bumped whenever the topics or data layout of any emitted event changes.
+    pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+    /// Declares the schema the publishing contract's events follow from
+    /// here on.
+    #[contractevent]
+    pub struct EventSchema {
+        pub version: u32,
+    }
+
+    pub fn publish_schema(env: &Env) {
+        EventSchema {
+            version: EVENT_SCHEMA_VERSION,
+        }
+        .publish(env);
+    }
+}
+
+/// Archival TTL upkeep. The contracts do not extend TTLs as they write, so
+/// a keeper calls each contract's `bump_all` with the storage keys it wants
+/// kept alive. Keys are passed in their encoded form, as read off the
+/// ledger, because a contract cannot list its own entries.
+pub mod ttl {
+    use soroban_sdk::{Env, Val, Vec};
+
+    /// Ledgers per day at five seconds a ledger.
+    pub const DAY_IN_LEDGERS: u32 = 17_280;
+    /// Entries with fewer ledgers than this left are extended.
+    pub const BUMP_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+    /// TTL given to an extended entry.
+    pub const BUMP_TO: u32 = 120 * DAY_IN_LEDGERS;
+    /// Most hinted keys one call looks at.
+    pub const MAX_KEYS: u32 = 50;
+
+    /// Extend the contract's instance and code, then the persistent entries
+    /// under the first `limit` keys of `keys_hint`, skipping keys with no
+    /// entry. Returns how many entries were found.
+    pub fn bump_all(env: &Env, keys_hint: &Vec<Val>, limit: u32) -> u32 {
+        env.storage().instance().extend_ttl(BUMP_THRESHOLD, BUMP_TO);
+        let storage = env.storage().persistent();
+        let mut found = 0;
+        for key in keys_hint.iter().take(limit.min(MAX_KEYS) as usize) {
+            if storage.has(&key) {
+                storage.extend_ttl(&key, BUMP_THRESHOLD, BUMP_TO);
+                found += 1;
+            }
+        }
+        found
+    }
+}
+
+/// Who paused a contract, when and why.
+///
+/// A contract with its own pause switch calls [`record`](pause::record) on
+/// every change, so integrators can show the incident behind a pause and
+/// the recent on/off sequence instead of a bare flag.
+pub mod pause {
+    use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+    /// Changes kept by [`history`]; older ones are dropped.
+    pub const MAX_HISTORY: u32 = 20;
+
+    const HISTORY: Symbol = symbol_short!("pause_log");
+
+    /// One pause or unpause. `reason` is an operator-defined incident code;
+    /// `incident` is the reason a pause tripping the circuit breaker gave it.
+    #[contracttype]
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct PauseRecord {
+        pub paused: bool,
+        pub by: Address,
+        pub at: u64,
+        pub reason: Option<u32>,
+        pub incident: Option<Symbol>,
+    }
+
+    /// Append a change made by `by` at the current ledger time.
+    pub fn record(env: &Env, paused: bool, by: &Address, reason: Option<u32>) {
+        push(env, paused, by, reason, None);
+    }
+
+    /// Append a pause `by` made at the current ledger time that also
+    /// tripped the circuit breaker for `incident`.
+    pub fn record_trip(env: &Env, by: &Address, incident: &Symbol) {
+        push(env, true, by, None, Some(incident.clone()));
+    }
+
+    fn push(env: &Env, paused: bool, by: &Address, reason: Option<u32>, incident: Option<Symbol>) {
+        let mut history = history(env);
+        if history.len() == MAX_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(PauseRecord {
+            paused,
+            by: by.clone(),
+            at: env.ledger().timestamp(),
+            reason,
+            incident,
+        });
+        env.storage().persistent().set(&HISTORY, &history);
+    }
+
+    /// The change that paused the contract, while it is still in effect.
+    pub fn info(env: &Env) -> Option<PauseRecord> {
+        history(env).last().filter(|change| change.paused)
+    }
+
+    /// Recorded changes, oldest first.
+    pub fn history(env: &Env) -> Vec<PauseRecord> {
+        env.storage()
+            .persistent()
+            .get(&HISTORY)
+            .unwrap_or(Vec::new(env))
+    }
+}
+
+/// Looking peers up in the address book contract.
+///
+/// The book maps component names to the suite's current addresses. A
+/// contract pointed at a book asks it on each use instead of keeping its
+/// own copy, so re-wiring after an upgrade is one update in the book.
+pub mod address_book {
+    use soroban_sdk::{symbol_short, vec, Address, Env, IntoVal, Symbol};
+
+    pub const RENEWAL: Symbol = symbol_short!("renewal");
+    pub const REGISTRY: Symbol = symbol_short!("registry");
+    pub const LOGGING: Symbol = symbol_short!("logging");
+    pub const AGENTS: Symbol = symbol_short!("agents");
+    pub const TREASURY: Symbol = symbol_short!("treasury");
+    pub const ORACLE: Symbol = symbol_short!("oracle");
+
+    /// Current address `book` holds for `component`, if any.
+    pub fn resolve(env: &Env, book: &Address, component: &Symbol) -> Option<Address> {
+        env.invoke_contract(
+            book,
+            &symbol_short!("resolve"),
+            vec![env, component.into_val(env)],
+        )
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,120 @@
+use super::*;
+
+#[test]
+fn test_scope_bits_are_distinct() {
+    assert_eq!(Scope::Renewals.bit() & Scope::GiftCards.bit(), 0);
+    assert_eq!(Scope::Renewals.bit() & Scope::Approvals.bit(), 0);
+    assert_eq!(Scope::GiftCards.bit() & Scope::Approvals.bit(), 0);
+    assert_eq!(Scope::Audit.bit() & 0b111, 0);
+    assert_eq!(Scope::ALL, 0b1111);
+}
+
+#[test]
+fn test_reason_codes_are_stable() {
+    // Indexers and older events rely on these numbers
+    assert_eq!(ReasonCode::ApprovalExpired as u32, 1);
+    assert_eq!(ReasonCode::ApprovalNotFound as u32, 4);
+    assert_eq!(ReasonCode::PaymentFailed as u32, 10);
+    assert_eq!(ReasonCode::AgentCompromised as u32, 20);
+    assert_eq!(ReasonCode::TooExpensive as u32, 30);
+    assert_eq!(ReasonCode::OtherCancellation as u32, 39);
+}
+
+#[test]
+fn test_log_event_codes() {
+    // Contracts log with these numeric codes; reordering the enum breaks them
+    assert_eq!(LogEvent::Reminder as u32, 0);
+    assert_eq!(LogEvent::Approval as u32, 1);
+    assert_eq!(LogEvent::Renewal as u32, 2);
+    assert_eq!(LogEvent::Failure as u32, 3);
+    assert_eq!(LogEvent::Retry as u32, 4);
+    assert_eq!(LogEvent::Cancellation as u32, 5);
+}
+
+#[test]
+fn test_error_ranges_do_not_overlap() {
+    for (i, (base, _)) in errors::SOURCES.iter().enumerate() {
+        assert_eq!(base % errors::RANGE_SIZE, 0);
+        assert!(errors::SOURCES[i + 1..]
+            .iter()
+            .all(|(other, _)| other != base));
+    }
+}
+
+#[test]
+fn test_error_code_source() {
+    assert_eq!(
+        errors::source(errors::RENEWAL_BASE),
+        Some("subscription_renewal")
+    );
+    assert_eq!(errors::source(errors::TWAP_BASE + 10), Some("twap"));
+    assert_eq!(
+        errors::source(errors::LOGGING_BASE + 99),
+        Some("subscription_logging")
+    );
+    assert_eq!(errors::source(7), None);
+}
+
+#[soroban_sdk::contract]
+struct Host;
+
+#[test]
+fn test_bump_all_extends_hinted_entries() {
+    use soroban_sdk::{symbol_short, testutils::storage::Persistent, Env, IntoVal, Val, Vec};
+
+    let env = Env::default();
+    let host = env.register(Host, ());
+    env.as_contract(&host, || {
+        let stored = (symbol_short!("sub"), 1u64);
+        let other = (symbol_short!("sub"), 2u64);
+        env.storage().persistent().set(&stored, &true);
+        env.storage().persistent().set(&other, &true);
+        let before = env.storage().persistent().get_ttl(&stored);
+
+        let missing: Val = (symbol_short!("sub"), 3u64).into_val(&env);
+        let mut hint: Vec<Val> = Vec::new(&env);
+        hint.push_back(missing);
+        hint.push_back(stored.into_val(&env));
+        hint.push_back(other.into_val(&env));
+
+        // The limit counts hints, found or not
+        assert_eq!(ttl::bump_all(&env, &hint, 2), 1);
+        assert!(before < ttl::BUMP_THRESHOLD);
+        assert_eq!(env.storage().persistent().get_ttl(&stored), ttl::BUMP_TO);
+        assert_eq!(env.storage().persistent().get_ttl(&other), before);
+    });
+}
+
+#[test]
+fn test_pause_history_is_bounded() {
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, Env,
+    };
+
+    let env = Env::default();
+    let host = env.register(Host, ());
+    let admin = Address::generate(&env);
+    env.as_contract(&host, || {
+        assert_eq!(pause::info(&env), None);
+
+        env.ledger().with_mut(|li| li.timestamp = 500);
+        pause::record(&env, true, &admin, Some(7));
+        let paused = pause::info(&env).unwrap();
+        assert_eq!(
+            (paused.by, paused.at, paused.reason, paused.incident),
+            (admin.clone(), 500, Some(7), None)
+        );
+
+        pause::record(&env, false, &admin, None);
+        assert_eq!(pause::info(&env), None);
+
+        for _ in 1..pause::MAX_HISTORY {
+            pause::record(&env, true, &admin, None);
+        }
+        let history = pause::history(&env);
+        assert_eq!(history.len(), pause::MAX_HISTORY);
+        // The oldest change, the first pause, was dropped
+        assert!(!history.get(0).unwrap().paused);
+    });
+}
@@ -0,0 +1,613 @@
+//! Typed mirrors of every `#[contractevent]` emitted by the SYNCRO contracts.
+//!
+//! Events are published with the snake_case struct name as their only topic
+//! and the fields as a symbol-keyed map, so each definition below lists the
+//! topic and the field names exactly as the contract declares them.
+
+use serde::{Deserialize, Serialize};
+use stellar_xdr::curr::ScVal;
+
+use crate::{
+    scval::{as_map, field, FromScVal},
+    DecodeError,
+};
+
+macro_rules! syncro_events {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident => $topic:literal { $($field:ident: $ty:ty),* $(,)? }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+            pub struct $name {
+                $(pub $field: $ty,)*
+            }
+
+            impl FromScVal for $name {
+                fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+                    let map = as_map(value)?;
+                    Ok(Self {
+                        $($field: field(map, stringify!($field))?,)*
+                    })
+                }
+            }
+        )*
+
+        /// Any SYNCRO event, tagged with its struct name when serialized.
+        #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+        #[serde(tag = "event")]
+        pub enum SyncroEvent {
+            $($name($name),)*
+        }
+
+        impl SyncroEvent {
+            /// The topic symbol the event is published under.
+            pub fn topic(&self) -> &'static str {
+                match self {
+                    $(SyncroEvent::$name(_) => $topic,)*
+                }
+            }
+
+            /// Decode a payload given its topic. Unknown topics yield `None`.
+            pub fn from_topic(topic: &str, data: &ScVal) -> Result<Option<Self>, DecodeError> {
+                match topic {
+                    $($topic => Ok(Some(SyncroEvent::$name($name::from_sc_val(data)?))),)*
+                    _ => Ok(None),
+                }
+            }
+        }
+    };
+}
+
+/// Component entry inside a `SuiteDeployed` event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentDeployment {
+    pub component: String,
+    pub address: String,
+    pub version: u32,
+}
+
+impl FromScVal for ComponentDeployment {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+        let map = as_map(value)?;
+        Ok(Self {
+            component: field(map, "component")?,
+            address: field(map, "address")?,
+            version: field(map, "version")?,
+        })
+    }
+}
+
+/// Recipient's cut inside a `RenewalSuccess` event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutShare {
+    pub recipient: String,
+    pub amount: i128,
+}
+
+impl FromScVal for PayoutShare {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+        let map = as_map(value)?;
+        Ok(Self {
+            recipient: field(map, "recipient")?,
+            amount: field(map, "amount")?,
+        })
+    }
+}
+
+syncro_events! {
+    // ── Every contract ────────────────────────────────────────
+
+    /// Published on initialization; `version` is the event schema the
+    /// contract's events follow.
+    EventSchema => "event_schema" { version: u32 }
+
+    // ── Upgrades (renewal, registry, logging, agent registry) ────
+
+    ContractUpgraded => "contract_upgraded" { wasm_hash: String, from_version: u32 }
+    ContractMigrated => "contract_migrated" { from: u32, to: u32 }
+
+    // ── Subscription renewal ──────────────────────────────────
+
+    RenewalSuccess => "renewal_success" {
+        sub_id: u64,
+        owner: String,
+        payouts: Vec<PayoutShare>,
+    }
+    RenewalFailed => "renewal_failed" {
+        sub_id: u64,
+        failure_count: u32,
+        ledger: u32,
+        next_retry_ledger: Option<u32>,
+        retries_remaining: u32,
+    }
+    GracePeriodStarted => "grace_period_started" { sub_id: u64, ends_at: u32 }
+    StateTransition => "state_transition" { sub_id: u64, new_state: String }
+    PauseToggled => "pause_toggled" { paused: bool }
+    AdminTransferred => "admin_transferred" { previous_admin: String, new_admin: String }
+    RoleGranted => "role_granted" { account: String, role: u32, roles: u32 }
+    RoleRevoked => "role_revoked" { account: String, role: u32, roles: u32 }
+    ApprovalCreated => "approval_created" {
+        sub_id: u64,
+        approval_id: u64,
+        max_spend: i128,
+        expires_at: u32,
+    }
+    DelegationBudgetSet => "delegation_budget_set" {
+        owner: String,
+        agent: String,
+        max_outstanding: i128,
+        max_approvals: u32,
+    }
+    DelegationRevoked => "delegation_revoked" { owner: String, agent: String }
+    ApprovalDelegated => "approval_delegated" {
+        sub_id: u64,
+        approval_id: u64,
+        agent: String,
+        max_spend: i128,
+    }
+    /// `reason` is a shared reason code: 1=expired, 2=used,
+    /// 3=amount_exceeded, 4=not_found.
+    ApprovalRejected => "approval_rejected" { sub_id: u64, approval_id: u64, reason: u32 }
+    ApprovalConsumed => "approval_consumed" {
+        sub_id: u64,
+        approval_id: u64,
+        max_spend: i128,
+        spent: i128,
+    }
+    DuplicateRenewalRejected => "duplicate_renewal_rejected" { sub_id: u64, cycle_id: u64 }
+    SpendingCapExceeded => "spending_cap_exceeded" {
+        sub_id: u64,
+        cycle_id: u64,
+        amount: i128,
+        spent: i128,
+        spending_cap: i128,
+    }
+    MetadataAnchored => "metadata_anchored" { sub_id: u64, hash: String, version: u32 }
+    FrequencyChangeScheduled => "frequency_change_scheduled" {
+        sub_id: u64,
+        frequency: u64,
+        effective_at: u64,
+    }
+    FrequencyChangeCancelled => "frequency_change_cancelled" { sub_id: u64 }
+    FrequencyChanged => "frequency_changed" {
+        sub_id: u64,
+        previous_frequency: u64,
+        frequency: u64,
+    }
+    AmendmentApplied => "amendment_applied" {
+        sub_id: u64,
+        amount: i128,
+        frequency: u64,
+        spending_cap: i128,
+    }
+    DowngradedOnFailure => "downgraded_on_failure" {
+        sub_id: u64,
+        previous_amount: i128,
+        amount: i128,
+    }
+    RenewalPendingConfirmation => "renewal_pending_confirmation" {
+        sub_id: u64,
+        cycle_id: u64,
+        amount: i128,
+        expires_at: u32,
+    }
+    PendingRenewalConfirmed => "pending_renewal_confirmed" {
+        sub_id: u64,
+        cycle_id: u64,
+        amount: i128,
+    }
+    RenewalBatchProcessed => "renewal_batch_processed" {
+        requests: u32,
+        renewed: u32,
+        skipped: u32,
+    }
+    PartialPaymentReceived => "partial_payment_received" {
+        sub_id: u64,
+        cycle_id: u64,
+        funder: String,
+        amount: i128,
+        paid: i128,
+        required: i128,
+    }
+    PartialPaymentsRefunded => "partial_payments_refunded" {
+        sub_id: u64,
+        cycle_id: u64,
+        amount: i128,
+    }
+    AmountIndexed => "amount_indexed" {
+        sub_id: u64,
+        previous_amount: i128,
+        amount: i128,
+    }
+    IntegrityViolation => "integrity_violation" { sub_id: u64 }
+    RenewalLockAcquired => "renewal_lock_acquired" {
+        sub_id: u64,
+        locked_at: u32,
+        lock_timeout: u32,
+    }
+    RenewalLockReleased => "renewal_lock_released" { sub_id: u64, released_at: u32 }
+    /// `reason` is a shared reason code, 30-39 for cancellations.
+    SubscriptionCancelled => "subscription_cancelled" {
+        sub_id: u64,
+        merchant: String,
+        reason: Option<u32>,
+    }
+    SubscriptionRestored => "subscription_restored" { sub_id: u64, state: String }
+    SubscriptionExpired => "subscription_expired" { sub_id: u64, due_at: u64 }
+    SubscriptionMigrated => "subscription_migrated" { sub_id: u64, from: u32, to: u32 }
+    PayerSet => "payer_set" { sub_id: u64, payer: Option<String> }
+    PaymentStrategySet => "payment_strategy_set" { sub_id: u64, strategy: Option<String> }
+    NotificationPrefsSet => "notification_prefs_set" {
+        sub_id: u64,
+        remind_days: Option<u32>,
+        mode: Option<String>,
+    }
+    AgentCacheInvalidated => "agent_cache_invalidated" { agent: String }
+    PreferredAgentSet => "preferred_agent_set" { sub_id: u64, agent: Option<String> }
+    RenewalIntentRegistered => "renewal_intent_registered" {
+        sub_id: u64,
+        agent: String,
+        position: u32,
+    }
+    RenewalLockExpired => "renewal_lock_expired" {
+        sub_id: u64,
+        original_locked_at: u32,
+        expired_at: u32,
+    }
+    /// `event_kind`: 1=created, 2=activated, 3=renewed, 4=canceled.
+    LifecycleTimestampUpdated => "lifecycle_timestamp_updated" {
+        sub_id: u64,
+        event_kind: u32,
+        timestamp: u64,
+    }
+
+    ProtocolFeeCharged => "protocol_fee_charged" {
+        sub_id: u64,
+        cycle_id: u64,
+        merchant: String,
+        fee: i128,
+        discount_bps: u32,
+    }
+    TransferOperatorSet => "transfer_operator_set" { sub_id: u64, operator: Option<String> }
+    SubscriptionTransferred => "subscription_transferred" { sub_id: u64, from: String, to: String }
+    TemplateSet => "template_set" { template_id: u64, merchant: String, version: u32 }
+    MerchantProfileSet => "merchant_profile_set" { merchant: String, cleared: bool }
+    MerchantKeySet => "merchant_key_set" { merchant: String, key: String, revoked: bool }
+
+    // ── Subscription registry ─────────────────────────────────
+
+    SubscriptionCreatedEvent => "subscription_created_event" {
+        subscription_id: String,
+        user: String,
+        service_id: String,
+        billing_interval: u64,
+        expected_amount: i128,
+        next_renewal: u64,
+    }
+    SubscriptionUpdatedEvent => "subscription_updated_event" {
+        subscription_id: String,
+        user: String,
+        service_id: String,
+        billing_interval: u64,
+        expected_amount: i128,
+        next_renewal: u64,
+    }
+    /// `reason` is a shared reason code, 30-39 for cancellations.
+    SubscriptionCancelledEvent => "subscription_cancelled_event" {
+        subscription_id: String,
+        user: String,
+        service_id: String,
+        reason: Option<u32>,
+    }
+    SubscriptionTransferredEvent => "subscription_transferred_event" {
+        subscription_id: String,
+        from: String,
+        to: String,
+    }
+    SubscriptionRenewedEvent => "subscription_renewed_event" {
+        subscription_id: String,
+        next_renewal: u64,
+    }
+    SubscriptionAtRiskEvent => "subscription_at_risk_event" { subscription_id: String }
+    SubscriptionMerchantSetEvent => "subscription_merchant_set_event" {
+        subscription_id: String,
+        merchant: String,
+    }
+    SubscriptionDueNotifiedEvent => "subscription_due_notified_event" {
+        subscription_id: String,
+        user: String,
+        target: String,
+        next_renewal: u64,
+    }
+    PortfolioImportedEvent => "portfolio_imported_event" {
+        user: String,
+        subscription_ids: Vec<String>,
+    }
+
+    // ── Usage metering ────────────────────────────────────────
+
+    RateTableUpdated => "rate_table_updated" { merchant: String, tier_count: u32 }
+    MeterAuthorized => "meter_authorized" { merchant: String, meter: String }
+    MeterRevoked => "meter_revoked" { merchant: String, meter: String }
+    UsageReported => "usage_reported" { sub_id: u64, period: u64, units: u64, total_units: u64 }
+    PeriodSettled => "period_settled" { sub_id: u64, period: u64, units: u64, amount: i128 }
+
+    // ── Renewal receipts ──────────────────────────────────────
+
+    ReceiptMinted => "receipt_minted" { token_id: u64, owner: String, sub_id: u64, cycle_id: u64 }
+
+    // ── Keeper bounty ─────────────────────────────────────────
+
+    BountyFunded => "bounty_funded" { sub_id: u64, funder: String, amount: i128, balance: i128 }
+    BountyWithdrawn => "bounty_withdrawn" { sub_id: u64, amount: i128, balance: i128 }
+    CycleReserved => "cycle_reserved" { sub_id: u64, cycle_id: u64, keeper: String, expires_at: u32 }
+    BountyClaimed => "bounty_claimed" { sub_id: u64, cycle_id: u64, keeper: String, reward: i128 }
+
+    // ── Multisig admin ────────────────────────────────────────
+
+    ProposalCreated => "proposal_created" {
+        id: u64,
+        proposer: String,
+        target: String,
+        function: String,
+    }
+    ProposalApproved => "proposal_approved" { id: u64, signer: String, approvals: u32 }
+    ApprovalRevoked => "approval_revoked" { id: u64, signer: String, approvals: u32 }
+    ProposalExecuted => "proposal_executed" { id: u64, target: String, function: String }
+    SignersChanged => "signers_changed" { signer_count: u32, threshold: u32 }
+
+    // ── Subscription account ──────────────────────────────────
+
+    DeviceAdded => "device_added" {
+        public_key: String,
+        renewal_contract: String,
+        max_spend: i128,
+        expires_at: u32,
+    }
+    DeviceRemoved => "device_removed" { public_key: String }
+    OwnerRotated => "owner_rotated" { public_key: String }
+
+    // ── Arbitration ───────────────────────────────────────────
+
+    ArbitersChanged => "arbiters_changed" { arbiter_count: u32, quorum: u32 }
+    DisputeOpened => "dispute_opened" {
+        id: u64,
+        source: String,
+        sub_id: u64,
+        cycle_id: u64,
+        payer: String,
+        amount: i128,
+    }
+    EvidenceSubmitted => "evidence_submitted" { id: u64, party: String, evidence_hash: String }
+    /// `outcome`: 1=refund, 2=release.
+    RulingVoted => "ruling_voted" { id: u64, arbiter: String, outcome: u32 }
+    DisputeResolved => "dispute_resolved" { id: u64, outcome: u32 }
+    DisputeWithdrawn => "dispute_withdrawn" { id: u64 }
+
+    // ── Loyalty ───────────────────────────────────────────────
+
+    ProgramUpdated => "program_updated" { merchant: String, earn_bps: u32, max_discount_bps: u32 }
+    PointsEarned => "points_earned" {
+        user: String,
+        merchant: String,
+        sub_id: u64,
+        cycle_id: u64,
+        points: i128,
+    }
+    PointsRedeemed => "points_redeemed" { user: String, merchant: String, sub_id: u64, points: i128 }
+    DiscountApplied => "discount_applied" { sub_id: u64, cycle_id: u64, discount: i128 }
+
+    // ── Bundles ───────────────────────────────────────────────
+
+    BundleCreated => "bundle_created" {
+        bundle_id: u64,
+        owner: String,
+        price: i128,
+        component_count: u32,
+    }
+    BundleCharged => "bundle_charged" { bundle_id: u64, cycle: u64, paid: i128, refunded: i128 }
+    BundleChargeFailed => "bundle_charge_failed" { bundle_id: u64, cycle: u64 }
+    ComponentPaid => "component_paid" { bundle_id: u64, sub_id: u64, merchant: String, amount: i128 }
+    ComponentFailed => "component_failed" { bundle_id: u64, sub_id: u64, failures: u32 }
+    BundleCancelled => "bundle_cancelled" { bundle_id: u64 }
+
+    // ── Cost splitting ────────────────────────────────────────
+
+    PlanCreated => "plan_created" { plan_id: u64, organizer: String, merchant: String, price: i128 }
+    MemberJoined => "member_joined" { plan_id: u64, member: String, shares: u32 }
+    MemberLeft => "member_left" { plan_id: u64, member: String, refunded: i128 }
+    DepositChanged => "deposit_changed" { plan_id: u64, member: String, deposit: i128 }
+    PoolFunded => "pool_funded" { plan_id: u64, funder: String, amount: i128, pool: i128 }
+    MemberMissed => "member_missed" { plan_id: u64, member: String, cycle: u64, owed: i128 }
+    PlanCharged => "plan_charged" { plan_id: u64, cycle: u64, amount: i128, from_pool: i128 }
+    PlanChargeFailed => "plan_charge_failed" { plan_id: u64, cycle: u64, shortfall: i128 }
+    PlanCancelled => "plan_cancelled" { plan_id: u64 }
+
+    // ── Callback registry ─────────────────────────────────────
+
+    CallbackRegistered => "callback_registered" {
+        id: u64,
+        owner: String,
+        callback: String,
+        kind: String,
+    }
+    CallbackRemoved => "callback_removed" { id: u64 }
+    CallbackFailed => "callback_failed" { id: u64, failures: u32 }
+    CallbackDisabled => "callback_disabled" { id: u64 }
+    NotificationSent => "notification_sent" {
+        emitter: String,
+        kind: String,
+        sub_id: u64,
+        delivered: u32,
+    }
+
+    // ── Insurance pool ────────────────────────────────────────
+
+    StakeDeposited => "stake_deposited" { merchant: String, amount: i128, balance: i128 }
+    StakeWithdrawn => "stake_withdrawn" { merchant: String, amount: i128, balance: i128 }
+    ReserveFunded => "reserve_funded" { funder: String, amount: i128, reserve: i128 }
+    MerchantSuspended => "merchant_suspended" { merchant: String, suspended_at: u64 }
+    MerchantReinstated => "merchant_reinstated" { merchant: String }
+    /// `reason`: 1=suspended, 2=non_delivery.
+    ClaimSubmitted => "claim_submitted" {
+        id: u64,
+        sub_id: u64,
+        subscriber: String,
+        merchant: String,
+        reason: u32,
+        amount: i128,
+    }
+    ClaimVerified => "claim_verified" { id: u64, approved: bool, amount: i128 }
+    ClaimPaid => "claim_paid" { id: u64, from_stake: i128, from_reserve: i128 }
+
+    // ── Swap adapter ──────────────────────────────────────────
+
+    RouteSet => "route_set" { asset_in: String, asset_out: String, hops: u32, max_slippage_bps: u32 }
+    RouteRemoved => "route_removed" { asset_in: String, asset_out: String }
+    PreferenceSet => "preference_set" { payer: String, sub_id: u64, asset: String, max_amount_in: i128 }
+    RenewalSwapped => "renewal_swapped" {
+        sub_id: u64,
+        payer: String,
+        merchant: String,
+        asset_in: String,
+        amount_in: i128,
+        asset_out: String,
+        amount_out: i128,
+    }
+
+    // ── Prepay ────────────────────────────────────────────────
+
+    ScheduleSet => "schedule_set" { merchant: String, tier_count: u32 }
+    TermPrepaid => "term_prepaid" { sub_id: u64, owner: String, cycles: u32, per_cycle: i128, total: i128 }
+    CycleDrawn => "cycle_drawn" { sub_id: u64, cycle_id: u64, amount: i128, cycles_left: u32 }
+    PrepayCancelled => "prepay_cancelled" { sub_id: u64, refunded: i128 }
+
+    // ── Tax ───────────────────────────────────────────────────
+
+    JurisdictionSet => "jurisdiction_set" { code: String, rate_bps: u32, collector: String }
+    MerchantJurisdictionSet => "merchant_jurisdiction_set" { merchant: String, code: String }
+    TaxWithheld => "tax_withheld" {
+        sub_id: u64,
+        merchant: String,
+        code: String,
+        gross: i128,
+        withheld: i128,
+        net: i128,
+    }
+
+    // ── Circuit breaker ───────────────────────────────────────
+
+    BreakerTripped => "breaker_tripped" { guardian: String, reason: String }
+    BreakerReset => "breaker_reset" { admin: String }
+    ContractIsolated => "contract_isolated" { contract: String, guardian: String, reason: String }
+    ContractRestored => "contract_restored" { contract: String }
+
+    // ── Bridge intake ─────────────────────────────────────────
+
+    BridgeAllowed => "bridge_allowed" { bridge: String, source_chain: u32, verifier: String }
+    BridgeRemoved => "bridge_removed" { bridge: String, source_chain: u32 }
+    PaymentBridged => "payment_bridged" {
+        bridge: String,
+        source_chain: u32,
+        nonce: u64,
+        sub_id: u64,
+        cycle_id: u64,
+        amount: i128,
+    }
+
+    // ── Utility token ─────────────────────────────────────────
+    // Transfer, Approve, Mint and Burn follow the SEP-41 layout (addresses
+    // as topics) and are left to standard token indexers.
+
+    StakeTiersSet => "stake_tiers_set" { tier_count: u32 }
+    Staked => "staked" { account: String, amount: i128, total: i128, unlock_at: u64 }
+    Unstaked => "unstaked" { account: String, amount: i128, total: i128 }
+
+    // ── Marketplace ───────────────────────────────────────────
+
+    FeeSet => "fee_set" { fee_bps: u32, recipient: String }
+    SubscriptionListed => "subscription_listed" {
+        listing_id: u64,
+        sub_id: u64,
+        seller: String,
+        price: i128,
+    }
+    ListingCancelled => "listing_cancelled" { listing_id: u64, sub_id: u64 }
+    SubscriptionSold => "subscription_sold" {
+        listing_id: u64,
+        sub_id: u64,
+        seller: String,
+        buyer: String,
+        price: i128,
+        fee: i128,
+    }
+
+    // ── Status snapshot ───────────────────────────────────────
+
+    SnapshotCommitted => "snapshot_committed" {
+        epoch: u64,
+        root: String,
+        leaf_count: u32,
+        taken_at: u64,
+    }
+
+    // ── Sponsorship ───────────────────────────────────────────
+
+    SponsorshipFunded => "sponsorship_funded" { merchant: String, amount: i128, balance: i128 }
+    SponsorshipWithdrawn => "sponsorship_withdrawn" {
+        merchant: String,
+        amount: i128,
+        balance: i128,
+    }
+    ReimbursementSet => "reimbursement_set" { merchant: String, per_renewal: i128 }
+    RenewalSponsored => "renewal_sponsored" {
+        sub_id: u64,
+        cycle_id: u64,
+        merchant: String,
+        keeper: String,
+        reimbursed: i128,
+    }
+
+    // ── Guardian recovery ─────────────────────────────────────
+
+    GuardiansSet => "guardians_set" { owner: String, guardian_count: u32, threshold: u32 }
+    RecoveryInitiated => "recovery_initiated" { owner: String, new_owner: String, guardian: String }
+    RecoveryApproved => "recovery_approved" { owner: String, guardian: String, approvals: u32 }
+    RecoveryCancelled => "recovery_cancelled" { owner: String }
+    RecoveryExecuted => "recovery_executed" {
+        owner: String,
+        new_owner: String,
+        renewal_subs: u32,
+        registry_subs: u32,
+    }
+
+    // ── Renewal scheduler ─────────────────────────────────────
+
+    RenewalScheduled => "renewal_scheduled" { sub_id: u64, due_ledger: u32 }
+    RenewalUnscheduled => "renewal_unscheduled" { sub_id: u64 }
+    DueBatchPopped => "due_batch_popped" { keeper: String, count: u32 }
+
+    // ── Batch executor ────────────────────────────────────────
+
+    ItemReverted => "item_reverted" { keeper: String, sub_id: u64 }
+    BatchExecuted => "batch_executed" { keeper: String, items: u32, reverted: u32 }
+
+    // ── TWAP ──────────────────────────────────────────────────
+
+    PriceSampled => "price_sampled" { asset: String, price: i128, timestamp: u64 }
+
+    // ── Suite factory ─────────────────────────────────────────
+
+    WasmReleased => "wasm_released" { component: String, wasm_hash: String, version: u32 }
+    SuiteDeployed => "suite_deployed" {
+        salt: String,
+        admin: String,
+        components: Vec<ComponentDeployment>,
+    }
+
+    // ── Address book ──────────────────────────────────────────
+
+    AddressSet => "address_set" { component: String, address: String, version: u32 }
+}
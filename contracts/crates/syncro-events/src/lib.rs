@@ -0,0 +1,134 @@
+//! Decoding of SYNCRO contract events from ledger metadata.
+//!
+//! Indexers hand in a `ContractEvent` (or the base64 XDR of one, or a whole
+//! `TransactionMeta`) and get back typed, serde-serializable structs.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use stellar_xdr::curr::{
+    ContractEvent, ContractEventBody, ContractEventType, Limits, ReadXdr, ScVal, TransactionMeta,
+};
+
+pub mod events;
+pub mod scval;
+
+pub use events::*;
+
+/// Event schema version the decoders in this crate follow. Contracts
+/// announce theirs in [`EventSchema`] and from `get_event_version()`; a newer
+/// version than this needs a newer release of this crate.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum DecodeError {
+    Xdr(stellar_xdr::curr::Error),
+    MissingField(&'static str),
+    Unexpected {
+        expected: &'static str,
+        found: String,
+    },
+    Unsupported(&'static str),
+}
+
+impl DecodeError {
+    pub(crate) fn unexpected(expected: &'static str, found: &ScVal) -> Self {
+        DecodeError::Unexpected {
+            expected,
+            found: format!("{found:?}"),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Xdr(e) => write!(f, "invalid XDR: {e}"),
+            DecodeError::MissingField(name) => write!(f, "missing field `{name}`"),
+            DecodeError::Unexpected { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            DecodeError::Unsupported(what) => write!(f, "unsupported {what}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<stellar_xdr::curr::Error> for DecodeError {
+    fn from(e: stellar_xdr::curr::Error) -> Self {
+        DecodeError::Xdr(e)
+    }
+}
+
+/// A decoded event together with the contract that emitted it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedEvent {
+    pub contract_id: Option<String>,
+    #[serde(flatten)]
+    pub event: SyncroEvent,
+}
+
+/// Decode one contract event. Returns `None` for system/diagnostic events and
+/// for events whose topic is not a SYNCRO event.
+pub fn decode_event(event: &ContractEvent) -> Result<Option<DecodedEvent>, DecodeError> {
+    if event.type_ != ContractEventType::Contract {
+        return Ok(None);
+    }
+    let ContractEventBody::V0(body) = &event.body;
+    let Some(ScVal::Symbol(topic)) = body.topics.first() else {
+        return Ok(None);
+    };
+    let Some(decoded) = SyncroEvent::from_topic(&topic.0.to_utf8_string_lossy(), &body.data)?
+    else {
+        return Ok(None);
+    };
+    let contract_id = event
+        .contract_id
+        .as_ref()
+        .map(|id| stellar_strkey::Contract(id.0 .0).to_string());
+    Ok(Some(DecodedEvent {
+        contract_id,
+        event: decoded,
+    }))
+}
+
+/// Decode a base64-encoded `ContractEvent`, as returned by RPC `getEvents`.
+pub fn decode_event_base64(xdr: &str) -> Result<Option<DecodedEvent>, DecodeError> {
+    let event = ContractEvent::from_xdr_base64(xdr, Limits::none())?;
+    decode_event(&event)
+}
+
+/// Collect every SYNCRO event emitted by a transaction.
+pub fn decode_meta(meta: &TransactionMeta) -> Result<Vec<DecodedEvent>, DecodeError> {
+    let raw: Vec<&ContractEvent> = match meta {
+        TransactionMeta::V3(v3) => v3
+            .soroban_meta
+            .iter()
+            .flat_map(|m| m.events.iter())
+            .collect(),
+        TransactionMeta::V4(v4) => v4
+            .operations
+            .iter()
+            .flat_map(|op| op.events.iter())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let mut out = Vec::new();
+    for event in raw {
+        if let Some(decoded) = decode_event(event)? {
+            out.push(decoded);
+        }
+    }
+    Ok(out)
+}
+
+/// Decode a base64-encoded `TransactionMeta`, as found in `getTransaction`
+/// and `getLedgers` responses.
+pub fn decode_meta_base64(xdr: &str) -> Result<Vec<DecodedEvent>, DecodeError> {
+    let meta = TransactionMeta::from_xdr_base64(xdr, Limits::none())?;
+    decode_meta(&meta)
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,112 @@
+//! Conversions from XDR `ScVal`s to plain Rust values.
+
+use stellar_xdr::curr::{AccountId, ContractId, Hash, PublicKey, ScAddress, ScMap, ScVal, Uint256};
+
+use crate::DecodeError;
+
+pub trait FromScVal: Sized {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError>;
+}
+
+impl FromScVal for u32 {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+        match value {
+            ScVal::U32(v) => Ok(*v),
+            other => Err(DecodeError::unexpected("u32", other)),
+        }
+    }
+}
+
+impl FromScVal for u64 {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+        match value {
+            ScVal::U64(v) => Ok(*v),
+            other => Err(DecodeError::unexpected("u64", other)),
+        }
+    }
+}
+
+impl FromScVal for i128 {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+        match value {
+            ScVal::I128(parts) => Ok(((parts.hi as i128) << 64) | parts.lo as i128),
+            other => Err(DecodeError::unexpected("i128", other)),
+        }
+    }
+}
+
+impl FromScVal for bool {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+        match value {
+            ScVal::Bool(v) => Ok(*v),
+            other => Err(DecodeError::unexpected("bool", other)),
+        }
+    }
+}
+
+/// Addresses become strkeys, byte strings become lowercase hex, and unit
+/// enum variants (encoded as a one-symbol vector) become their name.
+impl FromScVal for String {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+        match value {
+            ScVal::Symbol(s) => Ok(s.0.to_utf8_string_lossy()),
+            ScVal::String(s) => Ok(s.0.to_utf8_string_lossy()),
+            ScVal::Address(addr) => address_to_strkey(addr),
+            ScVal::Bytes(b) => Ok(b.0.as_slice().iter().map(|b| format!("{b:02x}")).collect()),
+            ScVal::Vec(Some(items)) if items.0.len() == 1 => match &items.0[0] {
+                ScVal::Symbol(s) => Ok(s.0.to_utf8_string_lossy()),
+                other => Err(DecodeError::unexpected("enum variant", other)),
+            },
+            other => Err(DecodeError::unexpected("string-like value", other)),
+        }
+    }
+}
+
+impl<T: FromScVal> FromScVal for Vec<T> {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+        match value {
+            ScVal::Vec(Some(items)) => items.0.iter().map(T::from_sc_val).collect(),
+            ScVal::Vec(None) => Ok(Vec::new()),
+            other => Err(DecodeError::unexpected("vec", other)),
+        }
+    }
+}
+
+/// `None` is encoded as `Void`; anything else decodes as `Some`.
+impl<T: FromScVal> FromScVal for Option<T> {
+    fn from_sc_val(value: &ScVal) -> Result<Self, DecodeError> {
+        match value {
+            ScVal::Void => Ok(None),
+            other => T::from_sc_val(other).map(Some),
+        }
+    }
+}
+
+pub fn address_to_strkey(addr: &ScAddress) -> Result<String, DecodeError> {
+    match addr {
+        ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(bytes)))) => {
+            Ok(stellar_strkey::ed25519::PublicKey(*bytes).to_string())
+        }
+        ScAddress::Contract(ContractId(Hash(bytes))) => {
+            Ok(stellar_strkey::Contract(*bytes).to_string())
+        }
+        _ => Err(DecodeError::Unsupported("address kind")),
+    }
+}
+
+pub fn as_map(value: &ScVal) -> Result<&ScMap, DecodeError> {
+    match value {
+        ScVal::Map(Some(map)) => Ok(map),
+        other => Err(DecodeError::unexpected("map", other)),
+    }
+}
+
+/// Look up a symbol-keyed field of a contracttype struct or event payload.
+pub fn field<T: FromScVal>(map: &ScMap, name: &'static str) -> Result<T, DecodeError> {
+    let entry = map
+        .0
+        .iter()
+        .find(|e| matches!(&e.key, ScVal::Symbol(s) if s.0.as_slice() == name.as_bytes()))
+        .ok_or(DecodeError::MissingField(name))?;
+    T::from_sc_val(&entry.val)
+}
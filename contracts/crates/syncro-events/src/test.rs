@@ -0,0 +1,189 @@
+use stellar_xdr::curr::{
+    AccountId, ContractEvent, ContractEventBody, ContractEventType, ContractEventV0, ContractId,
+    ExtensionPoint, Hash, Int128Parts, Limits, PublicKey, ScAddress, ScMap, ScMapEntry, ScSymbol,
+    ScVal, ScVec, Uint256, WriteXdr,
+};
+
+use crate::{decode_event, decode_event_base64, DecodeError, SyncroEvent, EVENT_SCHEMA_VERSION};
+
+fn sym(s: &str) -> ScVal {
+    ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+}
+
+fn map(fields: Vec<(&str, ScVal)>) -> ScVal {
+    let entries: Vec<ScMapEntry> = fields
+        .into_iter()
+        .map(|(k, val)| ScMapEntry { key: sym(k), val })
+        .collect();
+    ScVal::Map(Some(ScMap(entries.try_into().unwrap())))
+}
+
+fn account(byte: u8) -> ScVal {
+    ScVal::Address(ScAddress::Account(AccountId(
+        PublicKey::PublicKeyTypeEd25519(Uint256([byte; 32])),
+    )))
+}
+
+fn event(topic: &str, data: ScVal) -> ContractEvent {
+    ContractEvent {
+        ext: ExtensionPoint::V0,
+        contract_id: Some(ContractId(Hash([7; 32]))),
+        type_: ContractEventType::Contract,
+        body: ContractEventBody::V0(ContractEventV0 {
+            topics: vec![sym(topic)].try_into().unwrap(),
+            data,
+        }),
+    }
+}
+
+#[test]
+fn test_decode_renewal_success() {
+    let owner = account(2);
+    let ev = event(
+        "renewal_success",
+        map(vec![
+            ("owner", owner.clone()),
+            (
+                "payouts",
+                ScVal::Vec(Some(ScVec(
+                    vec![map(vec![
+                        ("amount", ScVal::I128(Int128Parts { hi: 0, lo: 500 })),
+                        ("recipient", owner),
+                    ])]
+                    .try_into()
+                    .unwrap(),
+                ))),
+            ),
+            ("sub_id", ScVal::U64(42)),
+        ]),
+    );
+    let decoded = decode_event(&ev).unwrap().unwrap();
+
+    assert_eq!(
+        decoded.contract_id.as_deref(),
+        Some(stellar_strkey::Contract([7; 32]).to_string().as_str())
+    );
+    match decoded.event {
+        SyncroEvent::RenewalSuccess(e) => {
+            assert_eq!(e.sub_id, 42);
+            assert_eq!(
+                e.owner,
+                stellar_strkey::ed25519::PublicKey([2; 32]).to_string()
+            );
+            assert_eq!(e.payouts.len(), 1);
+            assert_eq!(e.payouts[0].recipient, e.owner);
+            assert_eq!(e.payouts[0].amount, 500);
+        }
+        other => panic!("unexpected {other:?}"),
+    }
+}
+
+#[test]
+fn test_decode_i128_and_enum_fields() {
+    let ev = event(
+        "bounty_funded",
+        map(vec![
+            (
+                "amount",
+                ScVal::I128(Int128Parts {
+                    hi: -1,
+                    lo: u64::MAX - 9,
+                }),
+            ),
+            ("balance", ScVal::I128(Int128Parts { hi: 1, lo: 0 })),
+            ("funder", account(3)),
+            ("sub_id", ScVal::U64(1)),
+        ]),
+    );
+    let SyncroEvent::BountyFunded(e) = decode_event(&ev).unwrap().unwrap().event else {
+        panic!("wrong variant");
+    };
+    assert_eq!(e.amount, -10);
+    assert_eq!(e.balance, 1i128 << 64);
+
+    let state = ScVal::Vec(Some(ScVec(vec![sym("Retrying")].try_into().unwrap())));
+    let ev = event(
+        "state_transition",
+        map(vec![("new_state", state), ("sub_id", ScVal::U64(5))]),
+    );
+    let SyncroEvent::StateTransition(e) = decode_event(&ev).unwrap().unwrap().event else {
+        panic!("wrong variant");
+    };
+    assert_eq!(e.new_state, "Retrying");
+}
+
+#[test]
+fn test_decode_optional_field() {
+    let ev = event(
+        "transfer_operator_set",
+        map(vec![("operator", ScVal::Void), ("sub_id", ScVal::U64(4))]),
+    );
+    let SyncroEvent::TransferOperatorSet(e) = decode_event(&ev).unwrap().unwrap().event else {
+        panic!("wrong variant");
+    };
+    assert_eq!(e.operator, None);
+
+    let ev = event(
+        "transfer_operator_set",
+        map(vec![("operator", account(2)), ("sub_id", ScVal::U64(4))]),
+    );
+    let SyncroEvent::TransferOperatorSet(e) = decode_event(&ev).unwrap().unwrap().event else {
+        panic!("wrong variant");
+    };
+    assert!(e.operator.unwrap().starts_with('G'));
+}
+
+#[test]
+fn test_decode_event_schema() {
+    let ev = event("event_schema", map(vec![("version", ScVal::U32(1))]));
+    match decode_event(&ev).unwrap().unwrap().event {
+        SyncroEvent::EventSchema(e) => assert_eq!(e.version, EVENT_SCHEMA_VERSION),
+        other => panic!("unexpected {other:?}"),
+    }
+}
+
+#[test]
+fn test_decode_contract_migrated() {
+    let ev = event(
+        "contract_migrated",
+        map(vec![("from", ScVal::U32(1)), ("to", ScVal::U32(3))]),
+    );
+    match decode_event(&ev).unwrap().unwrap().event {
+        SyncroEvent::ContractMigrated(e) => assert_eq!((e.from, e.to), (1, 3)),
+        other => panic!("unexpected {other:?}"),
+    }
+}
+
+#[test]
+fn test_unknown_topic_is_skipped() {
+    let ev = event("transfer", map(vec![]));
+    assert!(decode_event(&ev).unwrap().is_none());
+}
+
+#[test]
+fn test_missing_field_is_an_error() {
+    let ev = event("renewal_failed", map(vec![("sub_id", ScVal::U64(1))]));
+    assert!(matches!(
+        decode_event(&ev),
+        Err(DecodeError::MissingField("failure_count"))
+    ));
+}
+
+#[test]
+fn test_base64_roundtrip_and_serde() {
+    let ev = event(
+        "renewal_lock_acquired",
+        map(vec![
+            ("lock_timeout", ScVal::U32(20)),
+            ("locked_at", ScVal::U32(100)),
+            ("sub_id", ScVal::U64(9)),
+        ]),
+    );
+    let xdr = ev.to_xdr_base64(Limits::none()).unwrap();
+    let decoded = decode_event_base64(&xdr).unwrap().unwrap();
+    assert_eq!(decoded.event.topic(), "renewal_lock_acquired");
+
+    let json = serde_json::to_value(&decoded).unwrap();
+    assert_eq!(json["event"], "RenewalLockAcquired");
+    assert_eq!(json["locked_at"], 100);
+}
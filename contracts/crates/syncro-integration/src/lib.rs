@@ -0,0 +1,4 @@
+//! Cross-contract integration tests for the SYNCRO suite. The tests live in
+//! `tests/` and deploy every contract into a single `Env`; `tests/properties.rs`
+//! drives the renewal contract with random operation sequences and
+//! `tests/costs.rs` guards the resource cost of the hot paths.
@@ -0,0 +1,227 @@
+//! Resource-cost regression tests. Each hot path is invoked once in a fully
+//! wired suite and the resources metered for that invocation are compared
+//! against a ceiling. A failure here means a change made the path more
+//! expensive: either optimize it or raise the ceiling deliberately in the
+//! same change.
+
+use renewal_receipts::{RenewalReceiptContract, RenewalReceiptContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, String,
+};
+use subscription_logging::{
+    LogEvent, SubscriptionLoggingContract, SubscriptionLoggingContractClient,
+};
+use subscription_renewal::{SubscriptionRenewalContract, SubscriptionRenewalContractClient};
+use syncro_testutils::TestToken;
+
+const SUB_ID: u64 = 1;
+const AMOUNT: i128 = 1599;
+
+/// Upper bounds for a single top-level invocation.
+struct Ceiling {
+    instructions: i64,
+    mem_bytes: i64,
+    /// Footprint reads, from disk and from memory.
+    read_entries: u32,
+    write_entries: u32,
+    write_bytes: u32,
+}
+
+fn assert_within(env: &Env, path: &str, ceiling: Ceiling) {
+    let used = env.cost_estimate().resources();
+    let read_entries = used.disk_read_entries + used.memory_read_entries;
+    println!("{path}: {used:?}");
+
+    assert!(
+        used.instructions <= ceiling.instructions,
+        "{path}: {} instructions > {}",
+        used.instructions,
+        ceiling.instructions
+    );
+    assert!(
+        used.mem_bytes <= ceiling.mem_bytes,
+        "{path}: {} memory bytes > {}",
+        used.mem_bytes,
+        ceiling.mem_bytes
+    );
+    assert!(
+        read_entries <= ceiling.read_entries,
+        "{path}: {read_entries} read entries > {}",
+        ceiling.read_entries
+    );
+    assert!(
+        used.write_entries <= ceiling.write_entries,
+        "{path}: {} write entries > {}",
+        used.write_entries,
+        ceiling.write_entries
+    );
+    assert!(
+        used.write_bytes <= ceiling.write_bytes,
+        "{path}: {} write bytes > {}",
+        used.write_bytes,
+        ceiling.write_bytes
+    );
+}
+
+/// Renewal wired to a token, logging and receipts, with one funded
+/// subscription.
+fn deploy() -> (
+    Env,
+    SubscriptionRenewalContractClient<'static>,
+    SubscriptionLoggingContractClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_700_000_000);
+
+    let admin = Address::generate(&env);
+    let renewal_id = env.register(SubscriptionRenewalContract, ());
+    let logging_id = env.register(SubscriptionLoggingContract, ());
+    let receipts_id = env.register(RenewalReceiptContract, ());
+
+    let renewal = SubscriptionRenewalContractClient::new(&env, &renewal_id);
+    let logging = SubscriptionLoggingContractClient::new(&env, &logging_id);
+    renewal.init(&admin);
+    let token = TestToken::new(&env);
+    renewal.set_token(&token.address);
+    renewal.set_logging_contract(&logging_id);
+    renewal.set_receipt_contract(&receipts_id);
+    RenewalReceiptContractClient::new(&env, &receipts_id).init(&admin, &renewal_id);
+
+    let owner = Address::generate(&env);
+    token.mint(&owner, 20_000);
+    token.approve(&owner, &renewal_id, &20_000, &1_000);
+    renewal.init_sub(
+        &owner,
+        &Address::generate(&env),
+        &AMOUNT,
+        &2_592_000,
+        &20_000,
+        &SUB_ID,
+    );
+    (env, renewal, logging)
+}
+
+/// Renew the `period`th cycle once it is due, under its derived cycle id.
+fn renew(env: &Env, renewal: &SubscriptionRenewalContractClient, period: u64) {
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_700_000_000 + period * 2_592_000);
+    let cycle_id = renewal.get_current_cycle_id(&SUB_ID).unwrap();
+    renewal.approve_renewal(&SUB_ID, &cycle_id, &AMOUNT, &1_000);
+    renewal.acquire_renewal_lock(&Address::generate(env), &SUB_ID, &20);
+    assert!(renewal.renew(&SUB_ID, &cycle_id, &AMOUNT, &cycle_id));
+}
+
+#[test]
+fn test_renew_cost() {
+    let (env, renewal, _logging) = deploy();
+    renew(&env, &renewal, 1);
+    assert_within(
+        &env,
+        "renew",
+        Ceiling {
+            instructions: 6_000_000,
+            mem_bytes: 3_000_000,
+            // Includes the token balances and allowance the charge moves,
+            // the check for a pending frequency change, the spend in the
+            // spending cap's window, the payment strategy lookup and the
+            // check for an agent's delegation to release, and the billing
+            // anchor and trial the cycle id is derived from
+            read_entries: 36,
+            // Includes the owner's approval utilization, the receipt, the
+            // token balances and the spend window
+            write_entries: 19,
+            write_bytes: 6_000,
+        },
+    );
+}
+
+#[test]
+fn test_acquire_renewal_lock_cost() {
+    let (env, renewal, _logging) = deploy();
+    renewal.acquire_renewal_lock(&Address::generate(&env), &SUB_ID, &20);
+    assert_within(
+        &env,
+        "acquire_renewal_lock",
+        Ceiling {
+            instructions: 1_000_000,
+            mem_bytes: 500_000,
+            // Includes the preferred agent and the queue of renewal intents
+            read_entries: 6,
+            // The lock plus the agent's auth nonce
+            write_entries: 2,
+            write_bytes: 500,
+        },
+    );
+}
+
+#[test]
+fn test_record_log_cost() {
+    let (env, _renewal, logging) = deploy();
+    let data = String::from_str(&env, "Renewal successful");
+    logging.record_log(&2, &LogEvent::Renewal, &data);
+    assert_within(
+        &env,
+        "record_log",
+        Ceiling {
+            instructions: 1_000_000,
+            mem_bytes: 500_000,
+            read_entries: 4,
+            write_entries: 1,
+            write_bytes: 500,
+        },
+    );
+}
+
+/// The log of a subscription is a single vector entry, so appending rewrites
+/// the whole history. This pins the cost of a long-lived subscription's log
+/// until the log storage is redesigned.
+#[test]
+fn test_record_log_cost_with_long_history() {
+    let (env, _renewal, logging) = deploy();
+    let data = String::from_str(&env, "Renewal successful");
+    for _ in 0..100 {
+        logging.record_log(&2, &LogEvent::Renewal, &data);
+    }
+    logging.record_log(&2, &LogEvent::Renewal, &data);
+    assert_within(
+        &env,
+        "record_log (100 entries)",
+        Ceiling {
+            instructions: 10_000_000,
+            mem_bytes: 6_000_000,
+            read_entries: 4,
+            write_entries: 1,
+            write_bytes: 20_000,
+        },
+    );
+}
+
+/// Later cycles touch the same renewal entries as the first one; only the
+/// log grows.
+#[test]
+fn test_renew_cost_after_a_year() {
+    let (env, renewal, _logging) = deploy();
+    for period in 1..=12 {
+        renew(&env, &renewal, period);
+    }
+    assert_within(
+        &env,
+        "renew (cycle 12)",
+        Ceiling {
+            instructions: 6_000_000,
+            mem_bytes: 3_000_000,
+            // Includes the token balances and allowance the charge moves,
+            // the check for a pending frequency change, the spend in the
+            // spending cap's window, the payment strategy lookup and the
+            // check for an agent's delegation to release, and the billing
+            // anchor and trial the cycle id is derived from
+            read_entries: 36,
+            // Includes the owner's approval utilization, the receipt, the
+            // token balances and the spend window
+            write_entries: 19,
+            write_bytes: 9_000,
+        },
+    );
+}
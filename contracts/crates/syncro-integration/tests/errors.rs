@@ -0,0 +1,54 @@
+//! Error codes returned by the deployed contracts fall in the range
+//! `syncro_common::errors` assigns each of them.
+
+use soroban_sdk::Error;
+use syncro_common::errors;
+
+fn source(error: impl Into<Error>) -> Option<&'static str> {
+    errors::source(error.into().get_code())
+}
+
+#[test]
+fn test_error_codes_identify_their_contract() {
+    assert_eq!(
+        source(subscription_renewal::Error::DuplicateCycle),
+        Some("subscription_renewal")
+    );
+    assert_eq!(
+        source(agent_registry::Error::NoPendingAdmin),
+        Some("agent_registry")
+    );
+    assert_eq!(
+        source(subscription_logging::Error::Paused),
+        Some("subscription_logging")
+    );
+    assert_eq!(
+        source(renewal_receipts::Error::AlreadyInitialized),
+        Some("renewal_receipts")
+    );
+    assert_eq!(
+        source(marketplace::Error::SelfPurchase),
+        Some("marketplace")
+    );
+    assert_eq!(
+        source(guardian_recovery::Error::TooManySubscriptions),
+        Some("guardian_recovery")
+    );
+    assert_eq!(
+        source(renewal_scheduler::Error::InvalidLimit),
+        Some("renewal_scheduler")
+    );
+    assert_eq!(
+        source(batch_executor::Error::TooManyItems),
+        Some("batch_executor")
+    );
+    assert_eq!(
+        source(batch_runner::Error::RenewalRequired),
+        Some("batch_runner")
+    );
+    assert_eq!(source(multicall::Error::TooManySubs), Some("multicall"));
+    assert_eq!(
+        source(address_book::Error::Unauthorized),
+        Some("address_book")
+    );
+}
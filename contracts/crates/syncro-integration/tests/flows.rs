@@ -0,0 +1,678 @@
+use address_book::{AddressBookContract, AddressBookContractClient};
+use agent_registry::{AgentRegistry, AgentRegistryClient};
+use batch_executor::{BatchExecutorContract, BatchExecutorContractClient};
+use batch_runner::{BatchRunnerContract, BatchRunnerContractClient};
+use circuit_breaker::{CircuitBreakerContract, CircuitBreakerContractClient};
+use guardian_recovery::{GuardianRecoveryContract, GuardianRecoveryContractClient};
+use marketplace::{MarketplaceContract, MarketplaceContractClient};
+use multicall::{MulticallContract, MulticallContractClient};
+use renewal_receipts::{RenewalReceiptContract, RenewalReceiptContractClient};
+use renewal_scheduler::{QueueEntry, RenewalSchedulerContract, RenewalSchedulerContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, String, Symbol,
+};
+use subscription_logging::{
+    LogEvent, SubscriptionLoggingContract, SubscriptionLoggingContractClient,
+};
+use subscription_registry::{SubscriptionRegistry, SubscriptionRegistryClient};
+use subscription_renewal::{
+    testutils::{advance_to, renewal_due},
+    RetryPolicy, SubscriptionRenewalContract, SubscriptionRenewalContractClient, SubscriptionState,
+};
+use syncro_common::{
+    address_book::LOGGING, BatchItem, BatchOp, ItemOutcome, ReasonCode, RenewOp, Scope,
+};
+use syncro_testutils::TestToken;
+
+const SUB_ID: u64 = 1;
+const AMOUNT: i128 = 1599;
+const INTERVAL: u64 = 2_592_000;
+
+/// Every contract deployed into one Env and wired the way a production
+/// deployment is: renewal charges in a token, logs to the logging contract,
+/// gates locks on the agent registry and mints receipts.
+struct Suite {
+    env: Env,
+    renewal: SubscriptionRenewalContractClient<'static>,
+    registry: SubscriptionRegistryClient<'static>,
+    logging: SubscriptionLoggingContractClient<'static>,
+    agents: AgentRegistryClient<'static>,
+    receipts: RenewalReceiptContractClient<'static>,
+    token: TestToken,
+    agent: Address,
+    user: Address,
+    merchant: Address,
+}
+
+fn deploy() -> Suite {
+    let env = syncro_testutils::setup_env();
+
+    let admin = Address::generate(&env);
+    let renewal_id = env.register(SubscriptionRenewalContract, ());
+    let registry_id = env.register(SubscriptionRegistry, ());
+    let logging_id = env.register(SubscriptionLoggingContract, ());
+    let agents_id = env.register(AgentRegistry, ());
+    let receipts_id = env.register(RenewalReceiptContract, ());
+
+    let renewal = SubscriptionRenewalContractClient::new(&env, &renewal_id);
+    let registry = SubscriptionRegistryClient::new(&env, &registry_id);
+    let logging = SubscriptionLoggingContractClient::new(&env, &logging_id);
+    let agents = AgentRegistryClient::new(&env, &agents_id);
+    let receipts = RenewalReceiptContractClient::new(&env, &receipts_id);
+    let token = TestToken::new(&env);
+
+    renewal.init(&admin);
+    renewal.set_token(&token.address);
+    renewal.set_logging_contract(&logging_id);
+    renewal.set_agent_registry(&agents_id);
+    renewal.set_receipt_contract(&receipts_id);
+    // Failed cycles can be retried straight away
+    renewal.set_default_retry_policy(
+        &admin,
+        &RetryPolicy {
+            max_retries: 3,
+            cooldown_ledgers: 0,
+        },
+    );
+    receipts.init(&admin, &renewal_id);
+    agents.init(&admin);
+    agents.set_renewal_contract(&Some(renewal_id.clone()));
+
+    let agent = Address::generate(&env);
+    agents.register(&agent);
+    agents.update_scopes(&agent, &Scope::Renewals.bit());
+
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    Suite {
+        env,
+        renewal,
+        registry,
+        logging,
+        agents,
+        receipts,
+        token,
+        agent,
+        user,
+        merchant,
+    }
+}
+
+impl Suite {
+    /// Create the subscription in the registry, then mirror it into the
+    /// renewal contract, funding the user and letting the renewal contract
+    /// charge them. Returns the registry id.
+    fn subscribe(&self) -> BytesN<32> {
+        let registry_id = self.registry.create_subscription(
+            &self.user,
+            &String::from_str(&self.env, "netflix"),
+            &INTERVAL,
+            &AMOUNT,
+            &(self.env.ledger().timestamp() + INTERVAL),
+        );
+        self.renewal.init_sub(
+            &self.user,
+            &self.merchant,
+            &AMOUNT,
+            &INTERVAL,
+            &20_000,
+            &SUB_ID,
+        );
+        self.token.mint(&self.user, 20_000);
+        self.allow(&self.user, 20_000);
+        registry_id
+    }
+
+    /// Let the renewal contract pull up to `amount` from `owner`.
+    fn allow(&self, owner: &Address, amount: i128) {
+        let expiration = self.env.ledger().sequence() + 10_000;
+        self.token
+            .approve(owner, &self.renewal.address, &amount, &expiration);
+    }
+
+    /// The derived `cycle_id` of billing period `period`, which is also
+    /// the timestamp it starts at.
+    fn cycle_id(&self, period: u64) -> u64 {
+        self.renewal.compute_cycle_id(&SUB_ID, &period)
+    }
+
+    /// Move the clock to the start of billing period `period` unless it has
+    /// already begun.
+    fn reach_period(&self, period: u64) {
+        let start = self.cycle_id(period);
+        self.env
+            .ledger()
+            .with_mut(|li| li.timestamp = li.timestamp.max(start));
+    }
+
+    /// Renew billing period `period` once it has started, with the owner's
+    /// allowance covering the charge when it should succeed and withdrawn
+    /// when it should fail.
+    fn renew_cycle(&self, period: u64, succeed: bool) -> bool {
+        self.reach_period(period);
+        let owner = self.renewal.get_sub(&SUB_ID).owner;
+        self.allow(&owner, if succeed { AMOUNT } else { 0 });
+        self.renewal
+            .approve_renewal(&SUB_ID, &period, &AMOUNT, &1_000);
+        self.renewal.acquire_renewal_lock(&self.agent, &SUB_ID, &20);
+        self.renewal
+            .renew(&SUB_ID, &period, &AMOUNT, &self.cycle_id(period))
+    }
+
+    fn log_kinds(&self) -> Vec<LogEvent> {
+        self.logging
+            .get_logs(&SUB_ID)
+            .iter()
+            .map(|entry| entry.event)
+            .collect()
+    }
+}
+
+#[test]
+fn test_subscription_lifecycle_across_contracts() {
+    let suite = deploy();
+    let registry_id = suite.subscribe();
+    assert!(
+        suite
+            .registry
+            .get_subscription(&registry_id)
+            .unwrap()
+            .is_active
+    );
+
+    // Scoped agent renews the first cycle
+    assert!(suite.renew_cycle(1, true));
+    assert_eq!(
+        suite.renewal.get_sub(&SUB_ID).state,
+        SubscriptionState::Active
+    );
+    assert!(suite.renewal.get_renewal_lock(&SUB_ID).is_none());
+    assert!(suite
+        .receipts
+        .verify(&suite.user, &SUB_ID, &suite.cycle_id(1)));
+    assert_eq!(suite.token.balance(&suite.merchant), AMOUNT);
+
+    // Owner cancels in both contracts
+    suite.renewal.cancel_sub(&SUB_ID, &None);
+    suite
+        .registry
+        .cancel_subscription(&registry_id, &suite.user, &None);
+    assert_eq!(
+        suite.renewal.get_sub(&SUB_ID).state,
+        SubscriptionState::Cancelled
+    );
+    assert!(
+        !suite
+            .registry
+            .get_subscription(&registry_id)
+            .unwrap()
+            .is_active
+    );
+
+    // Every step reached the logging contract
+    assert_eq!(
+        suite.log_kinds(),
+        vec![LogEvent::Renewal, LogEvent::Renewal, LogEvent::Cancellation]
+    );
+}
+
+#[test]
+fn test_failed_renewal_is_logged_as_retry_without_receipt() {
+    let suite = deploy();
+    suite.subscribe();
+
+    assert!(!suite.renew_cycle(1, false));
+    assert_eq!(
+        suite.renewal.get_sub(&SUB_ID).state,
+        SubscriptionState::Retrying
+    );
+    assert_eq!(suite.log_kinds().last(), Some(&LogEvent::Retry));
+    assert_eq!(suite.receipts.balance(&suite.user), 0);
+
+    // The same cycle can be retried and then succeeds
+    assert!(suite.renew_cycle(1, true));
+    assert_eq!(suite.receipts.balance(&suite.user), 1);
+}
+
+#[test]
+fn test_renewal_outcomes_keep_registry_current() {
+    let suite = deploy();
+    let registry_id = suite.subscribe();
+    let next_renewal = suite
+        .registry
+        .get_subscription(&registry_id)
+        .unwrap()
+        .next_renewal;
+    suite.registry.init(&Address::generate(&suite.env));
+    suite.registry.add_renewer(&suite.renewal.address);
+    suite
+        .renewal
+        .set_subscription_registry(&suite.registry.address);
+    suite
+        .renewal
+        .link_registry_entry(&SUB_ID, &Some(registry_id.clone()));
+
+    assert!(!suite.renew_cycle(1, false));
+    assert!(suite.registry.is_at_risk(&registry_id));
+
+    assert!(suite.renew_cycle(1, true));
+    assert!(!suite.registry.is_at_risk(&registry_id));
+    assert_eq!(
+        suite
+            .registry
+            .get_subscription(&registry_id)
+            .unwrap()
+            .next_renewal,
+        next_renewal + INTERVAL
+    );
+}
+
+#[test]
+fn test_pause_all_halts_registry_creation() {
+    let suite = deploy();
+    let env = &suite.env;
+    let admin = suite.renewal.get_admin();
+    let breaker = CircuitBreakerContractClient::new(env, &env.register(CircuitBreakerContract, ()));
+    breaker.init(&admin);
+    breaker.add_guardian(&suite.renewal.address);
+    suite.registry.init(&admin);
+    suite.registry.set_circuit_breaker(&breaker.address);
+    suite.renewal.set_circuit_breaker(&breaker.address);
+
+    suite
+        .renewal
+        .pause_all(&admin, &Symbol::new(env, "incident"));
+    assert!(breaker.is_tripped());
+    assert!(suite.renewal.is_paused());
+    assert!(suite
+        .registry
+        .try_create_subscription(
+            &suite.user,
+            &String::from_str(env, "netflix"),
+            &INTERVAL,
+            &AMOUNT,
+            &(env.ledger().timestamp() + INTERVAL),
+        )
+        .is_err());
+
+    // The breaker's admin resumes the protocol; renewals stay paused
+    // until lifted here too
+    breaker.reset();
+    suite.subscribe();
+    assert!(suite.renewal.is_paused());
+    suite.renewal.set_paused(&admin, &false, &None);
+    assert!(!suite.renewal.is_paused());
+}
+
+#[test]
+fn test_revoked_agent_cannot_lock() {
+    let suite = deploy();
+    suite.subscribe();
+
+    suite
+        .agents
+        .revoke_agent(&suite.agent, &ReasonCode::AgentCompromised);
+    assert!(suite
+        .renewal
+        .try_acquire_renewal_lock(&suite.agent, &SUB_ID, &20)
+        .is_err());
+    assert_eq!(
+        suite.agents.get_revocation(&suite.agent),
+        Some(ReasonCode::AgentCompromised)
+    );
+}
+
+#[test]
+fn test_revocation_reaches_cached_grants() {
+    let suite = deploy();
+    suite.subscribe();
+    assert!(suite.renew_cycle(1, true));
+
+    // Still inside the window the agent's grant is cached for
+    suite
+        .agents
+        .revoke_agent(&suite.agent, &ReasonCode::AgentCompromised);
+    assert!(suite
+        .renewal
+        .try_acquire_renewal_lock(&suite.agent, &SUB_ID, &20)
+        .is_err());
+
+    // Narrowing the agent's scopes reaches the cache too
+    let keeper = Address::generate(&suite.env);
+    suite.agents.register(&keeper);
+    suite.agents.update_scopes(&keeper, &Scope::Renewals.bit());
+    suite.renewal.acquire_renewal_lock(&keeper, &SUB_ID, &20);
+    suite.renewal.release_renewal_lock(&SUB_ID);
+    suite.agents.update_scopes(&keeper, &Scope::Audit.bit());
+    assert!(suite
+        .renewal
+        .try_acquire_renewal_lock(&keeper, &SUB_ID, &20)
+        .is_err());
+}
+
+#[test]
+fn test_registered_agent_needs_renewals_scope() {
+    let suite = deploy();
+    suite.subscribe();
+
+    let gift_card_agent = Address::generate(&suite.env);
+    suite.agents.register(&gift_card_agent);
+    suite
+        .agents
+        .update_scopes(&gift_card_agent, &Scope::GiftCards.bit());
+    assert!(suite
+        .renewal
+        .try_acquire_renewal_lock(&gift_card_agent, &SUB_ID, &20)
+        .is_err());
+}
+
+#[test]
+fn test_cancelled_subscription_never_charges() {
+    let suite = deploy();
+    suite.subscribe();
+    suite.renewal.cancel_sub(&SUB_ID, &None);
+
+    suite.renewal.approve_renewal(&SUB_ID, &1, &AMOUNT, &1_000);
+    suite
+        .renewal
+        .acquire_renewal_lock(&suite.agent, &SUB_ID, &20);
+    assert!(suite.renewal.try_renew(&SUB_ID, &1, &AMOUNT, &1).is_err());
+    assert_eq!(suite.receipts.balance(&suite.user), 0);
+}
+
+#[test]
+fn test_subscription_resold_through_marketplace() {
+    let suite = deploy();
+    let registry_id = suite.subscribe();
+    let env = &suite.env;
+
+    let token = TestToken::new(env);
+    let market = MarketplaceContractClient::new(env, &env.register(MarketplaceContract, ()));
+    let treasury = Address::generate(env);
+    market.init(
+        &Address::generate(env),
+        &token.address,
+        &suite.renewal.address,
+        &suite.registry.address,
+        &500,
+        &treasury,
+    );
+
+    // The owner lets the marketplace move the subscription in both contracts
+    suite
+        .renewal
+        .approve_sub_transfer(&SUB_ID, &Some(market.address.clone()));
+    suite
+        .registry
+        .approve_transfer(&registry_id, &suite.user, &Some(market.address.clone()));
+    let listing_id = market.list(&suite.user, &SUB_ID, &10_000, &Some(registry_id.clone()));
+
+    let buyer = Address::generate(env);
+    token.mint(&buyer, 10_000);
+    assert_eq!(market.buy(&buyer, &listing_id), 500);
+
+    assert_eq!(token.balance(&suite.user), 9_500);
+    assert_eq!(token.balance(&treasury), 500);
+    assert_eq!(suite.renewal.get_sub(&SUB_ID).owner, buyer);
+    assert_eq!(suite.registry.get_user_subscriptions(&suite.user).len(), 0);
+    assert_eq!(
+        suite
+            .registry
+            .get_user_subscriptions(&buyer)
+            .get(0)
+            .unwrap(),
+        registry_id
+    );
+
+    // The new owner's renewals go through as before
+    suite.token.mint(&buyer, AMOUNT);
+    assert!(suite.renew_cycle(1, true));
+    assert!(suite.receipts.verify(&buyer, &SUB_ID, &suite.cycle_id(1)));
+}
+
+#[test]
+fn test_guardians_recover_lost_owner_subscriptions() {
+    let suite = deploy();
+    let registry_id = suite.subscribe();
+    let env = &suite.env;
+
+    let admin = Address::generate(env);
+    let recovery =
+        GuardianRecoveryContractClient::new(env, &env.register(GuardianRecoveryContract, ()));
+    recovery.init(
+        &admin,
+        &suite.renewal.address,
+        &suite.registry.address,
+        &86_400,
+    );
+    suite.renewal.set_recovery_contract(&recovery.address);
+    suite.registry.init(&admin);
+    suite.registry.set_recovery_contract(&recovery.address);
+
+    let guardians = [Address::generate(env), Address::generate(env)];
+    recovery.set_guardians(
+        &suite.user,
+        &soroban_sdk::vec![env, guardians[0].clone(), guardians[1].clone()],
+        &2,
+    );
+
+    // The user loses their key; both guardians agree on a new one
+    let new_key = Address::generate(env);
+    recovery.initiate_recovery(
+        &guardians[0],
+        &suite.user,
+        &new_key,
+        &soroban_sdk::vec![env, SUB_ID],
+    );
+    recovery.approve_recovery(&guardians[1], &suite.user);
+    syncro_testutils::advance_time(env, 86_400);
+    assert_eq!(recovery.execute_recovery(&suite.user), (1, 1));
+
+    assert_eq!(suite.renewal.get_sub(&SUB_ID).owner, new_key);
+    assert_eq!(suite.registry.get_user_subscriptions(&suite.user).len(), 0);
+    assert_eq!(
+        suite
+            .registry
+            .get_user_subscriptions(&new_key)
+            .get(0)
+            .unwrap(),
+        registry_id
+    );
+
+    // Renewals continue under the recovered owner, paid from its account
+    suite.token.mint(&new_key, AMOUNT);
+    assert!(suite.renew_cycle(1, true));
+    assert!(suite.receipts.verify(&new_key, &SUB_ID, &suite.cycle_id(1)));
+}
+
+#[test]
+fn test_keepers_take_due_renewals_from_scheduler() {
+    let suite = deploy();
+    let env = &suite.env;
+    let scheduler =
+        RenewalSchedulerContractClient::new(env, &env.register(RenewalSchedulerContract, ()));
+    scheduler.init(&Address::generate(env), &suite.renewal.address);
+    scheduler.add_keeper(&suite.agent);
+    suite.renewal.set_scheduler(&scheduler.address);
+
+    suite.subscribe();
+    let due = renewal_due(env.ledger().sequence(), INTERVAL);
+    assert_eq!(scheduler.get_due_ledger(&SUB_ID), Some(due));
+    assert_eq!(scheduler.pop_due(&suite.agent, &10).len(), 0);
+
+    advance_to(env, due);
+    let batch = scheduler.pop_due(&suite.agent, &10);
+    assert_eq!(
+        batch.get(0),
+        Some(QueueEntry {
+            due_ledger: due,
+            sub_id: SUB_ID
+        })
+    );
+    assert_eq!(scheduler.queue_len(), 0);
+
+    // Renewing puts the subscription back for its next cycle
+    suite.allow(&suite.user, AMOUNT);
+    suite
+        .renewal
+        .approve_renewal(&SUB_ID, &1, &AMOUNT, &(due + 100));
+    suite
+        .renewal
+        .acquire_renewal_lock(&suite.agent, &SUB_ID, &20);
+    assert!(suite
+        .renewal
+        .renew(&SUB_ID, &1, &AMOUNT, &suite.cycle_id(1)));
+    assert_eq!(
+        scheduler.get_due_ledger(&SUB_ID),
+        Some(renewal_due(due, INTERVAL))
+    );
+}
+
+#[test]
+fn test_keeper_batch_reverts_failed_items_only() {
+    let suite = deploy();
+    let registry_id = suite.subscribe();
+    let env = &suite.env;
+    suite.renewal.init_sub(
+        &suite.user,
+        &suite.merchant,
+        &AMOUNT,
+        &INTERVAL,
+        &20_000,
+        &2,
+    );
+
+    let admin = Address::generate(env);
+    let executor = BatchExecutorContractClient::new(env, &env.register(BatchExecutorContract, ()));
+    let runner = BatchRunnerContractClient::new(env, &env.register(BatchRunnerContract, ()));
+    executor.init(&admin, &runner.address);
+    runner.init(
+        &executor.address,
+        &suite.renewal.address,
+        &suite.logging.address,
+        &suite.registry.address,
+    );
+    suite.registry.init(&admin);
+    suite.registry.add_renewer(&runner.address);
+    let next_renewal = suite
+        .registry
+        .get_subscription(&registry_id)
+        .unwrap()
+        .next_renewal;
+    let second_logs = suite.logging.get_logs(&2).len();
+
+    // Only the first subscription has an approval, so the second item's
+    // renewal fails and takes its lock with it
+    suite.reach_period(1);
+    suite.renewal.approve_renewal(&SUB_ID, &1, &AMOUNT, &1_000);
+    let cycle_id = suite.cycle_id(1);
+    let renew = |approval_id| {
+        BatchOp::Renew(RenewOp {
+            approval_id,
+            amount: AMOUNT,
+            cycle_id,
+        })
+    };
+    let items = soroban_sdk::vec![
+        env,
+        BatchItem {
+            sub_id: SUB_ID,
+            ops: soroban_sdk::vec![
+                env,
+                BatchOp::AcquireLock(20),
+                renew(1),
+                BatchOp::RecordLog(LogEvent::Reminder, String::from_str(env, "batched")),
+                BatchOp::MarkRegistryRenewed(registry_id.clone()),
+            ],
+        },
+        BatchItem {
+            sub_id: 2,
+            ops: soroban_sdk::vec![env, BatchOp::AcquireLock(20), renew(2)],
+        },
+    ];
+    assert_eq!(
+        executor.execute(&suite.agent, &items),
+        soroban_sdk::vec![env, ItemOutcome::Renewed, ItemOutcome::Reverted]
+    );
+
+    assert!(suite
+        .receipts
+        .verify(&suite.user, &SUB_ID, &suite.cycle_id(1)));
+    assert_eq!(
+        suite.log_kinds(),
+        vec![LogEvent::Renewal, LogEvent::Renewal, LogEvent::Reminder]
+    );
+    assert_eq!(
+        suite
+            .registry
+            .get_subscription(&registry_id)
+            .unwrap()
+            .next_renewal,
+        next_renewal + INTERVAL
+    );
+    assert!(suite.renewal.get_renewal_lock(&2).is_none());
+    assert_eq!(suite.logging.get_logs(&2).len(), second_logs);
+}
+
+#[test]
+fn test_multicall_reads_a_subscription_in_one_call() {
+    let suite = deploy();
+    suite.subscribe();
+    assert!(!suite.renew_cycle(1, false));
+    suite.renewal.approve_renewal(&SUB_ID, &2, &AMOUNT, &1_000);
+    suite
+        .renewal
+        .acquire_renewal_lock(&suite.agent, &SUB_ID, &20);
+
+    let env = &suite.env;
+    let multicall = MulticallContractClient::new(env, &env.register(MulticallContract, ()));
+    multicall.init(
+        &Address::generate(env),
+        &suite.renewal.address,
+        &Some(suite.logging.address.clone()),
+    );
+
+    let views = multicall.multi_get(&soroban_sdk::vec![env, SUB_ID, 99], &1);
+    assert_eq!(views.len(), 1);
+    let view = views.get(0).unwrap();
+    let sub = suite.renewal.get_sub(&SUB_ID);
+    assert_eq!(view.sub.owner, sub.owner);
+    assert_eq!(view.sub.integrity_hash, sub.integrity_hash);
+    assert_eq!(view.sub.failure_count, 1);
+    assert_eq!(
+        view.lifecycle.created_at,
+        suite.renewal.get_lifecycle(&SUB_ID).created_at
+    );
+    assert_eq!(view.lock_holder, Some(suite.agent.clone()));
+    assert_eq!(view.lock_expires_at, Some(env.ledger().sequence() + 20));
+    assert_eq!(view.log_count, suite.logging.get_logs(&SUB_ID).len());
+    assert_eq!(view.recent_logs.len(), 1);
+    assert_eq!(view.recent_logs.get(0).unwrap().event, LogEvent::Retry);
+}
+
+#[test]
+fn test_address_book_rewires_logging_without_touching_renewal() {
+    let suite = deploy();
+    suite.subscribe();
+    let env = &suite.env;
+    let book = AddressBookContractClient::new(env, &env.register(AddressBookContract, ()));
+    book.init(&Address::generate(env));
+    book.set_address(&LOGGING, &suite.logging.address);
+    suite.renewal.set_address_book(&book.address);
+    assert!(suite.renew_cycle(1, true));
+    let logged = suite.logging.get_logs(&SUB_ID).len();
+
+    // Move logging to a fresh deployment through the book alone
+    let moved =
+        SubscriptionLoggingContractClient::new(env, &env.register(SubscriptionLoggingContract, ()));
+    assert_eq!(book.set_address(&LOGGING, &moved.address), 2);
+    assert!(suite.renew_cycle(2, true));
+
+    assert_eq!(suite.logging.get_logs(&SUB_ID).len(), logged);
+    assert_eq!(moved.get_logs(&SUB_ID).len(), 1);
+    assert_eq!(
+        book.get_address_at(&LOGGING, &1),
+        Some(suite.logging.address.clone())
+    );
+}
@@ -0,0 +1,332 @@
+//! Property-based tests for the renewal contract. Random sequences of
+//! approve / lock / renew / cancel / pause operations are replayed against a
+//! fresh deployment and the contract's invariants are checked after every
+//! step; the parameter fuzzers probe approval and lock edge cases.
+
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+use subscription_renewal::{
+    RetryPolicy, SubscriptionRenewalContract, SubscriptionRenewalContractClient, SubscriptionState,
+};
+use syncro_testutils::TestToken;
+
+const SUB_ID: u64 = 1;
+const AMOUNT: i128 = 500;
+const SPENDING_CAP: i128 = AMOUNT * 10;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Approve {
+        approval_id: u64,
+        max_spend: i128,
+        expires_in: u32,
+    },
+    Lock {
+        timeout: u32,
+    },
+    Release,
+    Renew {
+        approval_id: u64,
+        cycle_id: u64,
+        /// Whether the owner's allowance covers the charge.
+        funded: bool,
+    },
+    SetRetryPolicy {
+        max_retries: u32,
+        cooldown: u32,
+    },
+    Cancel,
+    Pause(bool),
+    Advance(u32),
+}
+
+/// Small parameter domains so that sequences hit the success paths often.
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => (
+            0u64..4,
+            prop_oneof![Just(AMOUNT - 1), Just(AMOUNT), Just(AMOUNT * 2)],
+            0u32..30,
+        )
+            .prop_map(|(approval_id, max_spend, expires_in)| Op::Approve {
+                approval_id,
+                max_spend,
+                expires_in,
+            }),
+        4 => (0u32..20).prop_map(|timeout| Op::Lock { timeout }),
+        1 => Just(Op::Release),
+        6 => (0u64..4, 0u64..6, any::<bool>()).prop_map(|(approval_id, cycle_id, funded)| {
+            Op::Renew {
+                approval_id,
+                cycle_id,
+                funded,
+            }
+        }),
+        1 => (0u32..4, 0u32..4)
+            .prop_map(|(max_retries, cooldown)| Op::SetRetryPolicy { max_retries, cooldown }),
+        1 => Just(Op::Cancel),
+        1 => any::<bool>().prop_map(Op::Pause),
+        3 => (1u32..10).prop_map(Op::Advance),
+    ]
+}
+
+struct Harness {
+    env: Env,
+    client: SubscriptionRenewalContractClient<'static>,
+    token: TestToken,
+    owner: Address,
+    merchant: Address,
+    agent: Address,
+    /// Cycles that were successfully charged.
+    charged: BTreeSet<u64>,
+    /// Approval ids consumed since they were last (re)issued.
+    consumed: BTreeSet<u64>,
+}
+
+/// Deploy the renewal contract with one active subscription, starting at
+/// ledger `start`. The owner can afford any charge and allows the contract
+/// to pull it.
+fn harness(start: u32) -> Harness {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.sequence_number = start);
+
+    let contract_id = env.register(SubscriptionRenewalContract, ());
+    let client = SubscriptionRenewalContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.init(&admin);
+    // The ops name cycles freely to probe the duplicate-cycle guard
+    client.set_derived_cycle_ids(&admin, &false);
+    let token = TestToken::new(&env);
+    client.set_token(&token.address);
+    let owner = Address::generate(&env);
+    token.mint(&owner, i128::MAX);
+    let merchant = Address::generate(&env);
+    client.init_sub(&owner, &merchant, &AMOUNT, &86_400, &SPENDING_CAP, &SUB_ID);
+
+    let agent = Address::generate(&env);
+    let harness = Harness {
+        env,
+        client,
+        token,
+        owner,
+        merchant,
+        agent,
+        charged: BTreeSet::new(),
+        consumed: BTreeSet::new(),
+    };
+    harness.allow(i128::MAX);
+    harness
+}
+
+impl Harness {
+    fn state(&self) -> SubscriptionState {
+        self.client.get_sub(&SUB_ID).state
+    }
+
+    fn set_ledger(&self, sequence: u32) {
+        self.env
+            .ledger()
+            .with_mut(|li| li.sequence_number = sequence);
+    }
+
+    /// Let the contract pull up to `amount` from the owner.
+    fn allow(&self, amount: i128) {
+        let expiration = self.env.ledger().sequence() + 1_000;
+        self.token
+            .approve(&self.owner, &self.client.address, &amount, &expiration);
+    }
+
+    fn set_retry_policy(&self, max_retries: u32, cooldown: u32) {
+        self.client.set_retry_policy(
+            &self.merchant,
+            &SUB_ID,
+            &RetryPolicy {
+                max_retries,
+                cooldown_ledgers: cooldown,
+            },
+        );
+    }
+
+    fn renew(&self, approval_id: u64, cycle_id: u64, funded: bool) -> bool {
+        self.allow(if funded { AMOUNT } else { 0 });
+        matches!(
+            self.client
+                .try_renew(&SUB_ID, &approval_id, &AMOUNT, &cycle_id),
+            Ok(Ok(true))
+        )
+    }
+
+    /// Apply one operation, ignoring rejected calls, and check the
+    /// invariants that must hold whatever the contract decided.
+    fn apply(&mut self, op: &Op) {
+        let before = self.state();
+        let paused = self.client.is_paused();
+
+        match *op {
+            Op::Approve {
+                approval_id,
+                max_spend,
+                expires_in,
+            } => {
+                let expires_at = self.env.ledger().sequence() + expires_in;
+                self.client
+                    .approve_renewal(&SUB_ID, &approval_id, &max_spend, &expires_at);
+                self.consumed.remove(&approval_id);
+            }
+            Op::Lock { timeout } => {
+                let locked = self
+                    .client
+                    .try_acquire_renewal_lock(&self.agent, &SUB_ID, &timeout)
+                    .is_ok();
+                assert!(!(locked && paused), "lock acquired while paused");
+            }
+            Op::Release => {
+                let _ = self.client.try_release_renewal_lock(&SUB_ID);
+            }
+            Op::Renew {
+                approval_id,
+                cycle_id,
+                funded,
+            } => {
+                self.allow(if funded { AMOUNT } else { 0 });
+                let result = self
+                    .client
+                    .try_renew(&SUB_ID, &approval_id, &AMOUNT, &cycle_id);
+                if let Ok(Ok(charged)) = result {
+                    assert!(!paused, "renewal ran while paused");
+                    assert!(
+                        !matches!(
+                            before,
+                            SubscriptionState::Cancelled | SubscriptionState::Failed
+                        ),
+                        "renewal ran on a {before:?} subscription"
+                    );
+                    assert!(
+                        self.consumed.insert(approval_id),
+                        "approval {approval_id} consumed twice"
+                    );
+                    assert!(
+                        self.client.get_renewal_lock(&SUB_ID).is_none(),
+                        "lock survived a renewal attempt"
+                    );
+                    if charged {
+                        assert!(
+                            self.charged.insert(cycle_id),
+                            "cycle {cycle_id} charged twice"
+                        );
+                        assert_eq!(self.state(), SubscriptionState::Active);
+                    }
+                }
+            }
+            Op::SetRetryPolicy {
+                max_retries,
+                cooldown,
+            } => self.set_retry_policy(max_retries, cooldown),
+            Op::Cancel => {
+                let _ = self.client.try_cancel_sub(&SUB_ID, &None);
+            }
+            Op::Pause(pause) => {
+                let admin = self.client.get_admin();
+                self.client.set_paused(&admin, &pause, &None)
+            }
+            Op::Advance(ledgers) => self
+                .env
+                .ledger()
+                .with_mut(|li| li.sequence_number += ledgers),
+        }
+
+        // Cancelled is terminal; Failed can only move on to Cancelled
+        let after = self.state();
+        match before {
+            SubscriptionState::Cancelled => assert_eq!(after, SubscriptionState::Cancelled),
+            SubscriptionState::Failed => assert!(matches!(
+                after,
+                SubscriptionState::Failed | SubscriptionState::Cancelled
+            )),
+            _ => {}
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_renewal_state_machine(ops in prop::collection::vec(op(), 1..40)) {
+        let mut h = harness(0);
+        for op in &ops {
+            h.apply(op);
+        }
+    }
+
+    #[test]
+    fn prop_lock_window(start in 0u32..1_000_000, timeout in any::<u32>(), elapsed in 0u32..2_000) {
+        let h = harness(start);
+        h.client.approve_renewal(&SUB_ID, &1, &AMOUNT, &u32::MAX);
+        h.client.acquire_renewal_lock(&h.agent, &SUB_ID, &timeout);
+
+        h.set_ledger(start + elapsed);
+        let renewed = h.renew(1, 1, true);
+        prop_assert_eq!(renewed, elapsed < timeout);
+
+        // An expired lock can always be taken over
+        if !renewed {
+            prop_assert!(h
+                .client
+                .try_acquire_renewal_lock(&h.agent, &SUB_ID, &timeout)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn prop_approval_bounds(
+        start in 0u32..1_000_000,
+        max_spend in any::<i128>(),
+        amount in any::<i128>(),
+        expires_at in any::<u32>(),
+    ) {
+        let h = harness(start);
+        h.client.approve_renewal(&SUB_ID, &1, &max_spend, &expires_at);
+        h.client.acquire_renewal_lock(&h.agent, &SUB_ID, &10);
+
+        let renewed = matches!(
+            h.client.try_renew(&SUB_ID, &1, &amount, &1),
+            Ok(Ok(true))
+        );
+        // A negative amount can't be transferred, so the renewal fails
+        prop_assert_eq!(
+            renewed,
+            0 <= amount && amount <= max_spend && amount <= SPENDING_CAP && start <= expires_at
+        );
+
+        // Whatever the outcome, the approval never pays for a second cycle
+        if renewed {
+            h.client.acquire_renewal_lock(&h.agent, &SUB_ID, &10);
+            prop_assert!(h
+                .client
+                .try_renew(&SUB_ID, &1, &amount, &2)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn prop_cooldown_window(start in 0u32..1_000_000, cooldown in any::<u32>(), elapsed in 0u32..2_000) {
+        let h = harness(start);
+        h.set_retry_policy(3, cooldown);
+        h.client.approve_renewal(&SUB_ID, &1, &AMOUNT, &u32::MAX);
+        h.client.approve_renewal(&SUB_ID, &2, &AMOUNT, &u32::MAX);
+        h.client.acquire_renewal_lock(&h.agent, &SUB_ID, &u32::MAX);
+        prop_assert!(!h.renew(1, 1, false));
+        prop_assert_eq!(h.state(), SubscriptionState::Retrying);
+
+        h.set_ledger(start + elapsed);
+        h.client.acquire_renewal_lock(&h.agent, &SUB_ID, &u32::MAX);
+        prop_assert_eq!(h.renew(2, 1, true), elapsed >= cooldown);
+    }
+}
@@ -0,0 +1,348 @@
+//! Access to the renewal contract: reads go through Stellar RPC and the
+//! `stellar` CLI in simulation mode, writes are submitted by the CLI, which
+//! takes care of footprints, fees and signing.
+
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+
+use crate::config::Config;
+
+/// Subscription fields the keeper schedules on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubView {
+    pub sub_id: u64,
+    pub state: String,
+    pub amount: i128,
+    pub frequency: u64,
+    pub failure_count: u32,
+    pub last_attempt_ledger: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LifecycleView {
+    pub created_at: u64,
+    pub last_renewed_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenewCall {
+    pub sub_id: u64,
+    pub approval_id: u64,
+    pub amount: i128,
+    pub cycle_id: u64,
+}
+
+/// Why a submission failed. Rejections come from the contract itself and
+/// will not succeed on retry; everything else is assumed transient.
+#[derive(Debug)]
+pub enum SubmitError {
+    Rejected(String),
+    Transient(anyhow::Error),
+}
+
+impl std::fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitError::Rejected(msg) => write!(f, "rejected by contract: {msg}"),
+            SubmitError::Transient(err) => write!(f, "transient failure: {err}"),
+        }
+    }
+}
+
+pub trait Chain {
+    fn latest_ledger(&self) -> Result<u32>;
+    fn get_sub(&self, sub_id: u64) -> Result<SubView>;
+    fn get_lifecycle(&self, sub_id: u64) -> Result<LifecycleView>;
+    /// Returns sub_ids created in `[from_ledger, latest]` and the next ledger
+    /// to resume from.
+    fn discover_subs(&self, from_ledger: u32) -> Result<(Vec<u64>, u32)>;
+    fn acquire_lock(&self, sub_id: u64, lock_timeout: u32) -> Result<(), SubmitError>;
+    fn renew(&self, call: &RenewCall) -> Result<bool, SubmitError>;
+    /// Extend `contract`'s instance and the entries under `keys`. Returns
+    /// how many of the keys had an entry.
+    fn bump_all(&self, contract: &str, keys: &[Value], limit: u32) -> Result<u32, SubmitError>;
+}
+
+pub struct CliChain {
+    config: Config,
+}
+
+impl CliChain {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    fn rpc(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: Value = ureq::post(&self.config.rpc_url)
+            .send_json(body)
+            .with_context(|| format!("calling {method}"))?
+            .into_json()?;
+        if let Some(err) = response.get("error") {
+            bail!("{method} failed: {err}");
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("{method} returned no result"))
+    }
+
+    /// Run `stellar contract invoke` on the renewal contract. With `send`
+    /// false the call is only simulated, which is how views are read.
+    fn invoke(
+        &self,
+        send: bool,
+        function: &str,
+        args: &[(&str, String)],
+    ) -> Result<String, SubmitError> {
+        self.invoke_on(&self.config.renewal_contract, send, function, args)
+    }
+
+    fn invoke_on(
+        &self,
+        contract: &str,
+        send: bool,
+        function: &str,
+        args: &[(&str, String)],
+    ) -> Result<String, SubmitError> {
+        let mut cmd = Command::new("stellar");
+        cmd.args(["contract", "invoke", "--id"])
+            .arg(contract)
+            .arg("--source-account")
+            .arg(&self.config.source_account)
+            .arg("--network")
+            .arg(&self.config.network)
+            .arg(if send { "--send=yes" } else { "--send=no" })
+            .arg("--")
+            .arg(function);
+        for (name, value) in args {
+            cmd.arg(format!("--{name}")).arg(value);
+        }
+
+        let output = cmd
+            .output()
+            .map_err(|e| SubmitError::Transient(anyhow!("spawning stellar CLI: {e}")))?;
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        Err(classify_failure(&stderr))
+    }
+
+    fn view(&self, function: &str, sub_id: u64) -> Result<Value> {
+        let out = self
+            .invoke(false, function, &[("sub_id", sub_id.to_string())])
+            .map_err(|e| anyhow!("{function}({sub_id}): {e}"))?;
+        serde_json::from_str(&out).with_context(|| format!("decoding {function} output"))
+    }
+}
+
+impl Chain for CliChain {
+    fn latest_ledger(&self) -> Result<u32> {
+        let result = self.rpc("getLatestLedger", json!({}))?;
+        json_u64(&result["sequence"]).map(|s| s as u32)
+    }
+
+    fn get_sub(&self, sub_id: u64) -> Result<SubView> {
+        let v = self.view("get_sub", sub_id)?;
+        Ok(SubView {
+            sub_id,
+            state: json_variant(&v["state"])?,
+            amount: json_i128(&v["amount"])?,
+            frequency: json_u64(&v["frequency"])?,
+            failure_count: json_u64(&v["failure_count"])? as u32,
+            last_attempt_ledger: json_u64(&v["last_attempt_ledger"])? as u32,
+        })
+    }
+
+    fn get_lifecycle(&self, sub_id: u64) -> Result<LifecycleView> {
+        let v = self.view("get_lifecycle", sub_id)?;
+        Ok(LifecycleView {
+            created_at: json_u64(&v["created_at"])?,
+            last_renewed_at: json_u64(&v["last_renewed_at"])?,
+        })
+    }
+
+    fn discover_subs(&self, from_ledger: u32) -> Result<(Vec<u64>, u32)> {
+        let result = self.rpc(
+            "getEvents",
+            json!({
+                "startLedger": from_ledger,
+                "filters": [{
+                    "type": "contract",
+                    "contractIds": [self.config.renewal_contract],
+                }],
+                "xdrFormat": "json",
+                "pagination": { "limit": 200 },
+            }),
+        )?;
+        let next = json_u64(&result["latestLedger"]).map(|l| l as u32 + 1)?;
+        let events = result["events"].as_array().cloned().unwrap_or_default();
+        Ok((created_sub_ids(&events), next))
+    }
+
+    fn acquire_lock(&self, sub_id: u64, lock_timeout: u32) -> Result<(), SubmitError> {
+        self.invoke(
+            true,
+            "acquire_renewal_lock",
+            &[
+                ("agent", self.config.source_account.clone()),
+                ("sub_id", sub_id.to_string()),
+                ("lock_timeout", lock_timeout.to_string()),
+            ],
+        )
+        .map(|_| ())
+    }
+
+    fn renew(&self, call: &RenewCall) -> Result<bool, SubmitError> {
+        let out = self.invoke(
+            true,
+            "renew",
+            &[
+                ("sub_id", call.sub_id.to_string()),
+                ("approval_id", call.approval_id.to_string()),
+                ("amount", call.amount.to_string()),
+                ("cycle_id", call.cycle_id.to_string()),
+            ],
+        )?;
+        Ok(out == "true")
+    }
+
+    fn bump_all(&self, contract: &str, keys: &[Value], limit: u32) -> Result<u32, SubmitError> {
+        let out = self.invoke_on(
+            contract,
+            true,
+            "bump_all",
+            &[
+                ("keys_hint", Value::from(keys.to_vec()).to_string()),
+                ("limit", limit.to_string()),
+            ],
+        )?;
+        out.parse()
+            .map_err(|_| SubmitError::Transient(anyhow!("unexpected bump_all output {out}")))
+    }
+}
+
+/// The renewal contract's entries for one subscription, as ScVal JSON: the
+/// subscription itself (stored under its bare id), its cycle guard, its
+/// lifecycle timestamps and its metered flag. Entries the contract never
+/// wrote are skipped by `bump_all`.
+pub fn renewal_keys(sub_id: u64) -> Vec<Value> {
+    let id = json!({ "u64": sub_id.to_string() });
+    let keyed = |field: &str| json!({ "map": [{ "key": { "symbol": field }, "val": id }] });
+    vec![
+        id.clone(),
+        keyed("sub_id"),
+        keyed("lifecycle_sub_id"),
+        keyed("metered_sub_id"),
+    ]
+}
+
+/// Contract panics and host errors surface in CLI stderr; anything else
+/// (network, RPC, sequence number races) is worth retrying.
+pub fn classify_failure(stderr: &str) -> SubmitError {
+    let permanent = ["HostError", "Error(Contract", "Error(WasmVm"];
+    if permanent.iter().any(|marker| stderr.contains(marker)) {
+        SubmitError::Rejected(stderr.lines().last().unwrap_or_default().to_string())
+    } else {
+        SubmitError::Transient(anyhow!(stderr.trim().to_string()))
+    }
+}
+
+/// Pick sub_ids out of `lifecycle_timestamp_updated` events with
+/// `event_kind == 1` (created).
+pub fn created_sub_ids(events: &[Value]) -> Vec<u64> {
+    let mut ids = Vec::new();
+    for event in events {
+        let is_lifecycle = event["topicJson"]
+            .as_array()
+            .and_then(|t| t.first())
+            .and_then(|t| t["symbol"].as_str())
+            == Some("lifecycle_timestamp_updated");
+        if !is_lifecycle {
+            continue;
+        }
+        let fields = map_fields(&event["valueJson"]);
+        let kind = fields.iter().find(|(k, _)| k == "event_kind");
+        let sub = fields.iter().find(|(k, _)| k == "sub_id");
+        if let (Some((_, kind)), Some((_, sub))) = (kind, sub) {
+            if json_u64(kind).ok() == Some(1) {
+                if let Ok(id) = json_u64(sub) {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+            }
+        }
+    }
+    ids
+}
+
+/// Flatten an ScVal map in JSON form (`{"map":[{"key":{"symbol":..},"val":..}]}`).
+fn map_fields(value: &Value) -> Vec<(String, Value)> {
+    value["map"]
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|e| {
+                    let key = e["key"]["symbol"].as_str()?.to_string();
+                    Some((key, e["val"].clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Integers come back as JSON numbers, strings, or typed ScVal wrappers
+/// depending on width and output mode.
+pub fn json_u64(value: &Value) -> Result<u64> {
+    if let Some(n) = value.as_u64() {
+        return Ok(n);
+    }
+    if let Some(s) = value.as_str() {
+        return s.parse().with_context(|| format!("not an integer: {s}"));
+    }
+    if let Some(obj) = value.as_object() {
+        for key in ["u64", "u32", "i64", "i32"] {
+            if let Some(inner) = obj.get(key) {
+                return json_u64(inner);
+            }
+        }
+    }
+    bail!("expected integer, got {value}")
+}
+
+pub fn json_i128(value: &Value) -> Result<i128> {
+    if let Some(n) = value.as_i64() {
+        return Ok(n as i128);
+    }
+    if let Some(s) = value.as_str() {
+        return s.parse().with_context(|| format!("not an integer: {s}"));
+    }
+    bail!("expected i128, got {value}")
+}
+
+/// Unit enum variants are rendered either as a bare string or a one-element
+/// array holding the variant name.
+pub fn json_variant(value: &Value) -> Result<String> {
+    if let Some(s) = value.as_str() {
+        return Ok(s.to_string());
+    }
+    if let Some(s) = value
+        .as_array()
+        .and_then(|a| a.first())
+        .and_then(|v| v.as_str())
+    {
+        return Ok(s.to_string());
+    }
+    bail!("expected enum variant, got {value}")
+}
@@ -0,0 +1,126 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Keeper configuration, loaded from a TOML file.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Config {
+    /// Stellar RPC endpoint used for ledger and event queries.
+    pub rpc_url: String,
+    /// Network name passed to the `stellar` CLI (e.g. "testnet").
+    pub network: String,
+    /// Identity or secret the `stellar` CLI signs with. It is also the agent
+    /// that holds renewal locks, so it needs the Renewals scope when the
+    /// renewal contract is linked to an agent registry.
+    pub source_account: String,
+    /// Renewal contract id (C...).
+    pub renewal_contract: String,
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval_secs: u64,
+    #[serde(default = "default_lock_timeout")]
+    pub lock_timeout_ledgers: u32,
+    /// Ledgers to wait after a failed attempt before retrying. The
+    /// contract enforces each subscription's own cooldown; this only saves
+    /// submissions it would reject.
+    #[serde(default = "default_cooldown")]
+    pub cooldown_ledgers: u32,
+    #[serde(default = "default_metrics_addr")]
+    pub metrics_addr: String,
+    /// First ledger to scan for newly created subscriptions. Discovery is
+    /// disabled when unset.
+    #[serde(default)]
+    pub discover_from_ledger: Option<u32>,
+    #[serde(default)]
+    pub backoff: BackoffConfig,
+    /// Archival TTL upkeep. Disabled when unset.
+    #[serde(default)]
+    pub ttl: Option<TtlConfig>,
+    /// Subscriptions watched regardless of discovery.
+    #[serde(default)]
+    pub subscriptions: Vec<WatchedSub>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub struct BackoffConfig {
+    pub initial_ms: u64,
+    pub max_ms: u64,
+    pub max_attempts: u32,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            initial_ms: 500,
+            max_ms: 30_000,
+            max_attempts: 5,
+        }
+    }
+}
+
+/// Periodic `bump_all` calls that keep contract state from being archived.
+/// Watched subscriptions' entries in the renewal contract are always
+/// included.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TtlConfig {
+    #[serde(default = "default_ttl_interval")]
+    pub every_ledgers: u32,
+    /// Keys per call; the contracts look at no more than 50.
+    #[serde(default = "default_ttl_limit")]
+    pub limit: u32,
+    #[serde(default)]
+    pub targets: Vec<TtlTarget>,
+}
+
+/// A contract to keep alive, with the storage keys to extend as ScVal JSON
+/// (e.g. `{ vec = [{ symbol = "Logs" }, { u64 = "1" }] }`). The instance is
+/// extended even with no keys.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TtlTarget {
+    pub contract: String,
+    #[serde(default)]
+    pub keys: Vec<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WatchedSub {
+    pub sub_id: u64,
+}
+
+fn default_poll_interval() -> u64 {
+    30
+}
+
+fn default_lock_timeout() -> u32 {
+    20
+}
+
+fn default_cooldown() -> u32 {
+    10
+}
+
+/// About a day of ledgers.
+fn default_ttl_interval() -> u32 {
+    17_280
+}
+
+fn default_ttl_limit() -> u32 {
+    50
+}
+
+fn default_metrics_addr() -> String {
+    "0.0.0.0:9100".to_string()
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("reading config {}", path.display()))?;
+        Self::parse(&raw)
+    }
+
+    pub fn parse(raw: &str) -> Result<Self> {
+        toml::from_str(raw).context("parsing keeper config")
+    }
+}
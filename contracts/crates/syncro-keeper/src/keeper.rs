@@ -0,0 +1,250 @@
+//! Scheduling loop: decide which subscriptions are due and drive them through
+//! lock acquisition and renewal, and periodically extend contract TTLs.
+
+use std::{thread, time::Duration};
+
+use log::{debug, info, warn};
+
+use crate::{
+    chain::{self, Chain, LifecycleView, RenewCall, SubView, SubmitError},
+    config::{BackoffConfig, Config},
+    metrics::Metrics,
+};
+
+/// Exponential backoff: `initial_ms * 2^attempt`, capped at `max_ms`.
+pub fn backoff_delay(cfg: &BackoffConfig, attempt: u32) -> Duration {
+    let factor = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    let ms = cfg.initial_ms.saturating_mul(factor).min(cfg.max_ms);
+    Duration::from_millis(ms)
+}
+
+/// Unix time at which the next charge is due. The first cycle is anchored at
+/// creation; later cycles at the last successful renewal.
+pub fn due_at(sub: &SubView, lifecycle: &LifecycleView) -> u64 {
+    let anchor = if lifecycle.last_renewed_at > 0 {
+        lifecycle.last_renewed_at
+    } else {
+        lifecycle.created_at
+    };
+    anchor.saturating_add(sub.frequency)
+}
+
+/// Billing period index since creation. Keepers that agree on the clock
+/// agree on the cycle, so duplicate charges are caught by the contract's
+/// cycle guard.
+pub fn cycle_index(sub: &SubView, lifecycle: &LifecycleView, now: u64) -> u64 {
+    if sub.frequency == 0 {
+        return 0;
+    }
+    now.saturating_sub(lifecycle.created_at) / sub.frequency
+}
+
+/// The cycle id of period `index`, as the contract's `compute_cycle_id`
+/// derives it: the timestamp the period starts at.
+pub fn cycle_id(sub: &SubView, lifecycle: &LifecycleView, index: u64) -> u64 {
+    lifecycle
+        .created_at
+        .saturating_add(index.saturating_mul(sub.frequency))
+}
+
+/// Whether a subscription should be attempted now.
+pub fn is_due(
+    sub: &SubView,
+    lifecycle: &LifecycleView,
+    now: u64,
+    ledger: u32,
+    cooldown: u32,
+) -> bool {
+    match sub.state.as_str() {
+        "Active" => now >= due_at(sub, lifecycle),
+        "Retrying" => ledger >= sub.last_attempt_ledger.saturating_add(cooldown),
+        _ => false,
+    }
+}
+
+pub struct Keeper<C: Chain> {
+    chain: C,
+    config: Config,
+    metrics: std::sync::Arc<Metrics>,
+    watched: Vec<u64>,
+    next_discovery_ledger: Option<u32>,
+    next_bump_ledger: u32,
+    sleep: fn(Duration),
+}
+
+impl<C: Chain> Keeper<C> {
+    pub fn new(chain: C, config: Config, metrics: std::sync::Arc<Metrics>) -> Self {
+        let watched = config.subscriptions.iter().map(|s| s.sub_id).collect();
+        let next_discovery_ledger = config.discover_from_ledger;
+        Self {
+            chain,
+            config,
+            metrics,
+            watched,
+            next_discovery_ledger,
+            next_bump_ledger: 0,
+            sleep: thread::sleep,
+        }
+    }
+
+    /// Replace the sleep used between retries (tests run without delays).
+    pub fn with_sleep(mut self, sleep: fn(Duration)) -> Self {
+        self.sleep = sleep;
+        self
+    }
+
+    pub fn watched(&self) -> &[u64] {
+        &self.watched
+    }
+
+    pub fn chain(&self) -> &C {
+        &self.chain
+    }
+
+    /// One polling pass. Returns how many renewals were submitted.
+    pub fn tick(&mut self, now: u64) -> anyhow::Result<u32> {
+        let ledger = self.chain.latest_ledger()?;
+        Metrics::set(&self.metrics.last_ledger, ledger as u64);
+        self.discover();
+        self.maintain_ttl(ledger);
+
+        let mut due = 0u64;
+        let mut submitted = 0;
+        for sub_id in self.watched.clone() {
+            let (sub, lifecycle) =
+                match (self.chain.get_sub(sub_id), self.chain.get_lifecycle(sub_id)) {
+                    (Ok(sub), Ok(lc)) => (sub, lc),
+                    (Err(e), _) | (_, Err(e)) => {
+                        warn!("sub {sub_id}: read failed: {e:#}");
+                        continue;
+                    }
+                };
+            if !is_due(&sub, &lifecycle, now, ledger, self.config.cooldown_ledgers) {
+                debug!("sub {sub_id}: not due");
+                continue;
+            }
+            due += 1;
+            if self.process(&sub, &lifecycle, now) {
+                submitted += 1;
+            }
+        }
+
+        Metrics::set(&self.metrics.due_subscriptions, due);
+        Metrics::inc(&self.metrics.ticks);
+        Ok(submitted)
+    }
+
+    fn discover(&mut self) {
+        let Some(from) = self.next_discovery_ledger else {
+            return;
+        };
+        match self.chain.discover_subs(from) {
+            Ok((ids, next)) => {
+                for id in ids {
+                    if !self.watched.contains(&id) {
+                        info!("discovered sub {id}");
+                        self.watched.push(id);
+                    }
+                }
+                self.next_discovery_ledger = Some(next);
+            }
+            Err(e) => warn!("discovery from ledger {from} failed: {e:#}"),
+        }
+    }
+
+    /// Call `bump_all` on the renewal contract, for the watched
+    /// subscriptions, and on every configured target, once per
+    /// `every_ledgers`.
+    fn maintain_ttl(&mut self, ledger: u32) {
+        let Some(ttl) = &self.config.ttl else {
+            return;
+        };
+        if ledger < self.next_bump_ledger {
+            return;
+        }
+
+        let renewal_keys = self
+            .watched
+            .iter()
+            .flat_map(|&sub_id| chain::renewal_keys(sub_id))
+            .collect();
+        let targets = std::iter::once((&self.config.renewal_contract, renewal_keys))
+            .chain(ttl.targets.iter().map(|t| (&t.contract, t.keys.clone())));
+        let limit = ttl.limit.max(1);
+        for (contract, keys) in targets {
+            // An empty hint still extends the instance
+            let mut batches: Vec<&[serde_json::Value]> = keys.chunks(limit as usize).collect();
+            if batches.is_empty() {
+                batches.push(&[]);
+            }
+            for batch in batches {
+                Metrics::inc(&self.metrics.ttl_bumps);
+                match self.submit(|| self.chain.bump_all(contract, batch, limit)) {
+                    Ok(found) => debug!("{contract}: extended {found} of {} entries", batch.len()),
+                    Err(e) => warn!("{contract}: bump_all not submitted: {e}"),
+                }
+            }
+        }
+        self.next_bump_ledger = ledger.saturating_add(ttl.every_ledgers);
+    }
+
+    /// Lock and renew one due subscription. By convention owners create one
+    /// approval per cycle with `approval_id` set to the period index.
+    fn process(&self, sub: &SubView, lifecycle: &LifecycleView, now: u64) -> bool {
+        let index = cycle_index(sub, lifecycle, now);
+        let cycle_id = cycle_id(sub, lifecycle, index);
+        let sub_id = sub.sub_id;
+
+        if let Err(e) = self.submit(|| {
+            self.chain
+                .acquire_lock(sub_id, self.config.lock_timeout_ledgers)
+        }) {
+            warn!("sub {sub_id}: could not acquire lock: {e}");
+            return false;
+        }
+
+        let call = RenewCall {
+            sub_id,
+            approval_id: index,
+            amount: sub.amount,
+            cycle_id,
+        };
+        Metrics::inc(&self.metrics.renewals_attempted);
+        match self.submit(|| self.chain.renew(&call)) {
+            Ok(true) => {
+                info!("sub {sub_id}: renewed cycle {cycle_id}");
+                Metrics::inc(&self.metrics.renewals_succeeded);
+            }
+            Ok(false) => {
+                info!("sub {sub_id}: renewal failed, contract scheduled a retry");
+                Metrics::inc(&self.metrics.renewals_failed);
+            }
+            Err(e) => warn!("sub {sub_id}: renew not submitted: {e}"),
+        }
+        true
+    }
+
+    /// Run `op`, retrying transient failures with exponential backoff.
+    fn submit<T>(&self, op: impl Fn() -> Result<T, SubmitError>) -> Result<T, SubmitError> {
+        let mut attempt = 0;
+        loop {
+            match op() {
+                Ok(v) => return Ok(v),
+                Err(SubmitError::Rejected(msg)) => {
+                    Metrics::inc(&self.metrics.submissions_rejected);
+                    return Err(SubmitError::Rejected(msg));
+                }
+                Err(SubmitError::Transient(e)) => {
+                    attempt += 1;
+                    if attempt >= self.config.backoff.max_attempts {
+                        return Err(SubmitError::Transient(e));
+                    }
+                    Metrics::inc(&self.metrics.submission_retries);
+                    let delay = backoff_delay(&self.config.backoff, attempt - 1);
+                    debug!("transient failure ({e:#}), retrying in {delay:?}");
+                    (self.sleep)(delay);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! Off-chain worker that renews due SYNCRO subscriptions.
+//!
+//! The renewal contract expects an external worker to acquire the renewal
+//! lock and call `renew` once a subscription's billing period has elapsed.
+//! This crate is that worker.
+
+pub mod chain;
+pub mod config;
+pub mod keeper;
+pub mod metrics;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,48 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use clap::Parser;
+use log::{error, info};
+use syncro_keeper::{chain::CliChain, config::Config, keeper::Keeper, metrics};
+
+#[derive(Parser)]
+#[command(name = "syncro-keeper", about = "Renews due SYNCRO subscriptions")]
+struct Args {
+    /// Path to the keeper TOML config.
+    #[arg(short, long, default_value = "keeper.toml")]
+    config: PathBuf,
+
+    /// Run a single polling pass and exit.
+    #[arg(long)]
+    once: bool,
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+    let config = Config::load(&args.config)?;
+
+    let metrics = Arc::new(metrics::Metrics::default());
+    metrics::serve(&config.metrics_addr, metrics.clone())?;
+    info!("metrics on http://{}/metrics", config.metrics_addr);
+
+    let interval = Duration::from_secs(config.poll_interval_secs);
+    let mut keeper = Keeper::new(CliChain::new(config.clone()), config, metrics);
+
+    loop {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        match keeper.tick(now) {
+            Ok(n) => info!("tick complete, {n} renewals submitted"),
+            Err(e) => error!("tick failed: {e:#}"),
+        }
+        if args.once {
+            return Ok(());
+        }
+        thread::sleep(interval);
+    }
+}
@@ -0,0 +1,125 @@
+//! Prometheus text-format metrics served over a minimal HTTP endpoint.
+
+use std::{
+    fmt::Write as _,
+    io::{Read, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use anyhow::Result;
+
+#[derive(Default)]
+pub struct Metrics {
+    pub ticks: AtomicU64,
+    pub due_subscriptions: AtomicU64,
+    pub renewals_attempted: AtomicU64,
+    pub renewals_succeeded: AtomicU64,
+    pub renewals_failed: AtomicU64,
+    pub submissions_rejected: AtomicU64,
+    pub submission_retries: AtomicU64,
+    pub ttl_bumps: AtomicU64,
+    pub last_ledger: AtomicU64,
+}
+
+impl Metrics {
+    pub fn inc(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set(gauge: &AtomicU64, value: u64) {
+        gauge.store(value, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let series: [(&str, &str, &str, &AtomicU64); 9] = [
+            (
+                "syncro_keeper_ticks_total",
+                "counter",
+                "Polling iterations completed",
+                &self.ticks,
+            ),
+            (
+                "syncro_keeper_due_subscriptions",
+                "gauge",
+                "Subscriptions due in the last tick",
+                &self.due_subscriptions,
+            ),
+            (
+                "syncro_keeper_renewals_attempted_total",
+                "counter",
+                "Renew transactions submitted",
+                &self.renewals_attempted,
+            ),
+            (
+                "syncro_keeper_renewals_succeeded_total",
+                "counter",
+                "Renewals that charged successfully",
+                &self.renewals_succeeded,
+            ),
+            (
+                "syncro_keeper_renewals_failed_total",
+                "counter",
+                "Renewals that returned false",
+                &self.renewals_failed,
+            ),
+            (
+                "syncro_keeper_submissions_rejected_total",
+                "counter",
+                "Submissions rejected by the contract",
+                &self.submissions_rejected,
+            ),
+            (
+                "syncro_keeper_submission_retries_total",
+                "counter",
+                "Transient submission failures retried",
+                &self.submission_retries,
+            ),
+            (
+                "syncro_keeper_ttl_bumps_total",
+                "counter",
+                "bump_all transactions submitted",
+                &self.ttl_bumps,
+            ),
+            (
+                "syncro_keeper_last_ledger",
+                "gauge",
+                "Latest ledger seen",
+                &self.last_ledger,
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, kind, help, value) in series {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            let _ = writeln!(out, "{name} {}", value.load(Ordering::Relaxed));
+        }
+        out
+    }
+}
+
+/// Serve `/metrics` on `addr` from a background thread.
+pub fn serve(addr: &str, metrics: Arc<Metrics>) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            // The request itself is irrelevant; drain what the client sent
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            let body = metrics.render();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    Ok(())
+}
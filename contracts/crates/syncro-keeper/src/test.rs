@@ -0,0 +1,356 @@
+use std::{cell::RefCell, collections::HashMap, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+use crate::{
+    chain::{self, Chain, LifecycleView, RenewCall, SubView, SubmitError},
+    config::{BackoffConfig, Config},
+    keeper::{self, Keeper},
+    metrics::Metrics,
+};
+
+const CONFIG: &str = r#"
+rpc_url = "http://localhost:8000/soroban/rpc"
+network = "local"
+source_account = "keeper"
+renewal_contract = "CRENEWAL"
+
+[[subscriptions]]
+sub_id = 1
+
+[[subscriptions]]
+sub_id = 2
+"#;
+
+fn sub(sub_id: u64, state: &str) -> SubView {
+    SubView {
+        sub_id,
+        state: state.to_string(),
+        amount: 500,
+        frequency: 100,
+        failure_count: 0,
+        last_attempt_ledger: 0,
+    }
+}
+
+#[derive(Default)]
+struct MockChain {
+    subs: HashMap<u64, (SubView, LifecycleView)>,
+    renew_results: RefCell<Vec<Result<bool, SubmitError>>>,
+    renewed: RefCell<Vec<RenewCall>>,
+    locks: RefCell<Vec<u64>>,
+    bumps: RefCell<Vec<(String, usize)>>,
+    discovered: Vec<u64>,
+}
+
+impl Chain for MockChain {
+    fn latest_ledger(&self) -> Result<u32> {
+        Ok(1000)
+    }
+
+    fn get_sub(&self, sub_id: u64) -> Result<SubView> {
+        self.subs
+            .get(&sub_id)
+            .map(|(s, _)| s.clone())
+            .ok_or_else(|| anyhow!("not found"))
+    }
+
+    fn get_lifecycle(&self, sub_id: u64) -> Result<LifecycleView> {
+        self.subs
+            .get(&sub_id)
+            .map(|(_, lc)| *lc)
+            .ok_or_else(|| anyhow!("not found"))
+    }
+
+    fn discover_subs(&self, _from_ledger: u32) -> Result<(Vec<u64>, u32)> {
+        Ok((self.discovered.clone(), 1001))
+    }
+
+    fn acquire_lock(&self, sub_id: u64, _lock_timeout: u32) -> Result<(), SubmitError> {
+        self.locks.borrow_mut().push(sub_id);
+        Ok(())
+    }
+
+    fn renew(&self, call: &RenewCall) -> Result<bool, SubmitError> {
+        self.renewed.borrow_mut().push(call.clone());
+        self.renew_results.borrow_mut().pop().unwrap_or(Ok(true))
+    }
+
+    fn bump_all(&self, contract: &str, keys: &[Value], _limit: u32) -> Result<u32, SubmitError> {
+        self.bumps
+            .borrow_mut()
+            .push((contract.to_string(), keys.len()));
+        Ok(keys.len() as u32)
+    }
+}
+
+fn keeper_with(chain: MockChain) -> (Keeper<MockChain>, Arc<Metrics>) {
+    let config = Config::parse(CONFIG).unwrap();
+    let metrics = Arc::new(Metrics::default());
+    let keeper = Keeper::new(chain, config, metrics.clone()).with_sleep(|_| {});
+    (keeper, metrics)
+}
+
+#[test]
+fn test_config_defaults() {
+    let config = Config::parse(CONFIG).unwrap();
+    assert_eq!(config.subscriptions.len(), 2);
+    assert_eq!(config.poll_interval_secs, 30);
+    assert_eq!(config.backoff, BackoffConfig::default());
+    assert!(config.discover_from_ledger.is_none());
+}
+
+#[test]
+fn test_backoff_delay_doubles_and_caps() {
+    let cfg = BackoffConfig {
+        initial_ms: 100,
+        max_ms: 1000,
+        max_attempts: 10,
+    };
+    assert_eq!(keeper::backoff_delay(&cfg, 0), Duration::from_millis(100));
+    assert_eq!(keeper::backoff_delay(&cfg, 2), Duration::from_millis(400));
+    assert_eq!(keeper::backoff_delay(&cfg, 4), Duration::from_millis(1000));
+    assert_eq!(keeper::backoff_delay(&cfg, 80), Duration::from_millis(1000));
+}
+
+#[test]
+fn test_due_and_cycle_math() {
+    let s = sub(1, "Active");
+    let fresh = LifecycleView {
+        created_at: 1000,
+        last_renewed_at: 0,
+    };
+    assert!(!keeper::is_due(&s, &fresh, 1099, 0, 10));
+    assert!(keeper::is_due(&s, &fresh, 1100, 0, 10));
+    assert_eq!(keeper::cycle_index(&s, &fresh, 1100), 1);
+    assert_eq!(keeper::cycle_id(&s, &fresh, 1), 1100);
+
+    let renewed = LifecycleView {
+        created_at: 1000,
+        last_renewed_at: 1100,
+    };
+    assert!(!keeper::is_due(&s, &renewed, 1150, 0, 10));
+    assert_eq!(keeper::cycle_index(&s, &renewed, 1250), 2);
+
+    assert!(!keeper::is_due(&sub(1, "Cancelled"), &fresh, 5000, 0, 10));
+}
+
+#[test]
+fn test_retrying_sub_waits_for_cooldown() {
+    let mut s = sub(1, "Retrying");
+    s.last_attempt_ledger = 995;
+    let lc = LifecycleView {
+        created_at: 1000,
+        last_renewed_at: 0,
+    };
+    assert!(!keeper::is_due(&s, &lc, 5000, 1000, 10));
+    assert!(keeper::is_due(&s, &lc, 5000, 1005, 10));
+}
+
+#[test]
+fn test_classify_failure() {
+    assert!(matches!(
+        chain::classify_failure("error: HostError: Error(Contract, #7)"),
+        SubmitError::Rejected(_)
+    ));
+    assert!(matches!(
+        chain::classify_failure("error: connection refused"),
+        SubmitError::Transient(_)
+    ));
+}
+
+#[test]
+fn test_created_sub_ids_from_events() {
+    let event = |kind: u64, sub_id: u64| {
+        json!({
+            "topicJson": [{ "symbol": "lifecycle_timestamp_updated" }],
+            "valueJson": { "map": [
+                { "key": { "symbol": "event_kind" }, "val": { "u32": kind } },
+                { "key": { "symbol": "sub_id" }, "val": { "u64": sub_id.to_string() } },
+                { "key": { "symbol": "timestamp" }, "val": { "u64": "0" } },
+            ]},
+        })
+    };
+    let events = vec![event(1, 7), event(2, 7), event(1, 9), event(1, 7)];
+    assert_eq!(chain::created_sub_ids(&events), vec![7, 9]);
+}
+
+#[test]
+fn test_tick_renews_only_due_subs() {
+    let mut chain = MockChain::default();
+    chain.subs.insert(
+        1,
+        (
+            sub(1, "Active"),
+            LifecycleView {
+                created_at: 1000,
+                last_renewed_at: 0,
+            },
+        ),
+    );
+    chain.subs.insert(
+        2,
+        (
+            sub(2, "Active"),
+            LifecycleView {
+                created_at: 1050,
+                last_renewed_at: 0,
+            },
+        ),
+    );
+    let (mut keeper, metrics) = keeper_with(chain);
+
+    assert_eq!(keeper.tick(1100).unwrap(), 1);
+    assert_eq!(
+        metrics
+            .due_subscriptions
+            .load(std::sync::atomic::Ordering::Relaxed),
+        1
+    );
+    assert_eq!(
+        metrics
+            .renewals_succeeded
+            .load(std::sync::atomic::Ordering::Relaxed),
+        1
+    );
+}
+
+#[test]
+fn test_transient_failures_are_retried() {
+    let mut chain = MockChain::default();
+    chain.subs.insert(
+        1,
+        (
+            sub(1, "Active"),
+            LifecycleView {
+                created_at: 1000,
+                last_renewed_at: 0,
+            },
+        ),
+    );
+    // Results are popped from the back: two transient failures, then success
+    chain.renew_results = RefCell::new(vec![
+        Ok(true),
+        Err(SubmitError::Transient(anyhow!("timeout"))),
+        Err(SubmitError::Transient(anyhow!("timeout"))),
+    ]);
+    let (mut keeper, metrics) = keeper_with(chain);
+
+    keeper.tick(1100).unwrap();
+    assert_eq!(
+        metrics
+            .submission_retries
+            .load(std::sync::atomic::Ordering::Relaxed),
+        2
+    );
+    assert_eq!(
+        metrics
+            .renewals_succeeded
+            .load(std::sync::atomic::Ordering::Relaxed),
+        1
+    );
+}
+
+#[test]
+fn test_rejections_are_not_retried() {
+    let mut chain = MockChain::default();
+    chain.subs.insert(
+        1,
+        (
+            sub(1, "Active"),
+            LifecycleView {
+                created_at: 1000,
+                last_renewed_at: 0,
+            },
+        ),
+    );
+    chain.renew_results = RefCell::new(vec![Err(SubmitError::Rejected("cooldown".into()))]);
+    let (mut keeper, metrics) = keeper_with(chain);
+
+    keeper.tick(1100).unwrap();
+    assert_eq!(
+        metrics
+            .submission_retries
+            .load(std::sync::atomic::Ordering::Relaxed),
+        0
+    );
+    assert_eq!(
+        metrics
+            .submissions_rejected
+            .load(std::sync::atomic::Ordering::Relaxed),
+        1
+    );
+}
+
+#[test]
+fn test_discovery_extends_watch_list() {
+    let chain = MockChain {
+        discovered: vec![2, 3],
+        ..Default::default()
+    };
+    let mut config = Config::parse(CONFIG).unwrap();
+    config.discover_from_ledger = Some(1);
+    let mut keeper = Keeper::new(chain, config, Arc::new(Metrics::default()));
+
+    keeper.tick(0).unwrap();
+    assert_eq!(keeper.watched(), &[1, 2, 3]);
+}
+
+#[test]
+fn test_renewal_keys_match_contract_layout() {
+    let keys = chain::renewal_keys(7);
+    assert_eq!(keys[0], json!({ "u64": "7" }));
+    assert_eq!(
+        keys[2],
+        json!({ "map": [{ "key": { "symbol": "lifecycle_sub_id" }, "val": { "u64": "7" } }] })
+    );
+}
+
+#[test]
+fn test_ttl_bumps_once_per_interval() {
+    let raw = format!(
+        "{CONFIG}\n{}",
+        r#"
+[ttl]
+every_ledgers = 100
+limit = 3
+
+[[ttl.targets]]
+contract = "CLOGGING"
+keys = [{ vec = [{ symbol = "Logs" }, { u64 = "1" }] }]
+
+[[ttl.targets]]
+contract = "CREGISTRY"
+"#
+    );
+    let config = Config::parse(&raw).unwrap();
+    let mut keeper = Keeper::new(MockChain::default(), config, Arc::new(Metrics::default()));
+
+    keeper.tick(0).unwrap();
+    let bumps = |k: &Keeper<MockChain>| k.chain().bumps.borrow().clone();
+    // Two watched subs with four keys each, in batches of three
+    assert_eq!(
+        bumps(&keeper),
+        [
+            ("CRENEWAL".to_string(), 3),
+            ("CRENEWAL".to_string(), 3),
+            ("CRENEWAL".to_string(), 2),
+            ("CLOGGING".to_string(), 1),
+            ("CREGISTRY".to_string(), 0),
+        ]
+    );
+
+    // Same ledger: not due again yet
+    keeper.tick(0).unwrap();
+    assert_eq!(bumps(&keeper).len(), 5);
+}
+
+#[test]
+fn test_metrics_render() {
+    let metrics = Metrics::default();
+    Metrics::inc(&metrics.ticks);
+    let text = metrics.render();
+    assert!(text.contains("# TYPE syncro_keeper_ticks_total counter"));
+    assert!(text.contains("syncro_keeper_ticks_total 1"));
+}
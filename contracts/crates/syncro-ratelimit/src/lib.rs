@@ -0,0 +1,195 @@
+#![no_std]
+//! Rate limiters over Soroban storage.
+//!
+//! A contract keeps one limiter per key (an agent, a subscription, ...) and
+//! calls [`check_and_consume`] before the action it wants to limit:
+//!
+//! ```ignore
+//! syncro_ratelimit::check_and_consume(&env, &agent, &config)
+//!     .map_err(|_| Error::RateLimited)?;
+//! ```
+//!
+//! State lives in temporary storage under a `("ratelimit", key)` tuple so it
+//! cannot collide with the contract's own keys. Entries are kept alive for
+//! one `period`; an entry that expires after that would have been back at
+//! full capacity anyway.
+
+use soroban_sdk::{contracttype, symbol_short, Env, IntoVal, Symbol, Val};
+
+/// Average ledger close time, used to turn a period into a TTL.
+const LEDGER_SECONDS: u64 = 5;
+const PREFIX: Symbol = symbol_short!("ratelimit");
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Strategy {
+    /// Up to `capacity` calls in a burst, refilled continuously at
+    /// `capacity` per `period`.
+    TokenBucket = 0,
+    /// Up to `capacity` calls per aligned window of `period` seconds.
+    FixedWindow = 1,
+}
+
+/// Limiter settings. A `period` of zero disables the limit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitConfig {
+    pub strategy: Strategy,
+    pub capacity: u32,
+    /// Length of the refill period or window, in seconds.
+    pub period: u64,
+}
+
+impl LimitConfig {
+    pub const fn token_bucket(capacity: u32, period: u64) -> Self {
+        LimitConfig {
+            strategy: Strategy::TokenBucket,
+            capacity,
+            period,
+        }
+    }
+
+    pub const fn fixed_window(capacity: u32, period: u64) -> Self {
+        LimitConfig {
+            strategy: Strategy::FixedWindow,
+            capacity,
+            period,
+        }
+    }
+
+    /// Whether the config can be stored: a disabled limit, or a nonzero
+    /// capacity.
+    pub fn is_valid(&self) -> bool {
+        self.period == 0 || self.capacity > 0
+    }
+}
+
+/// Stored limiter state. For a token bucket `level` is the fill level in
+/// units of `1 / period` calls and `updated_at` the last refill; for a
+/// fixed window they are the calls made and the window start.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct LimiterState {
+    level: u128,
+    updated_at: u64,
+}
+
+/// Returned when a call is over the limit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RateLimited {
+    /// Seconds until the next call would be allowed.
+    pub retry_after: u64,
+}
+
+/// Consume one call for `key`, or fail without consuming if the limit is
+/// reached.
+pub fn check_and_consume<K>(env: &Env, key: &K, config: &LimitConfig) -> Result<(), RateLimited>
+where
+    K: IntoVal<Env, Val> + Clone,
+{
+    if config.period == 0 {
+        return Ok(());
+    }
+    let now = env.ledger().timestamp();
+    let mut state = current(env, key, config, now);
+    let cost = unit(config);
+    if state.level < cost {
+        return Err(RateLimited {
+            retry_after: retry_after(config, &state, now),
+        });
+    }
+    state.level -= cost;
+
+    let storage_key = storage_key(env, key);
+    let ttl = ((config.period / LEDGER_SECONDS + 1).min(env.storage().max_ttl() as u64)) as u32;
+    env.storage().temporary().set(&storage_key, &state);
+    env.storage().temporary().extend_ttl(&storage_key, ttl, ttl);
+    Ok(())
+}
+
+/// Calls `key` could make right now.
+pub fn remaining<K>(env: &Env, key: &K, config: &LimitConfig) -> u32
+where
+    K: IntoVal<Env, Val> + Clone,
+{
+    if config.period == 0 {
+        return u32::MAX;
+    }
+    let state = current(env, key, config, env.ledger().timestamp());
+    (state.level / unit(config)) as u32
+}
+
+/// Forget `key`'s history, restoring its full capacity.
+pub fn reset<K>(env: &Env, key: &K)
+where
+    K: IntoVal<Env, Val> + Clone,
+{
+    env.storage().temporary().remove(&storage_key(env, key));
+}
+
+fn storage_key<K>(env: &Env, key: &K) -> (Symbol, Val)
+where
+    K: IntoVal<Env, Val>,
+{
+    (PREFIX, key.into_val(env))
+}
+
+/// Level that one call costs.
+fn unit(config: &LimitConfig) -> u128 {
+    match config.strategy {
+        Strategy::TokenBucket => config.period as u128,
+        Strategy::FixedWindow => 1,
+    }
+}
+
+/// State as of `now`: refilled for a token bucket, rolled over for a fixed
+/// window. Fixed-window levels count down from `capacity`.
+fn current<K>(env: &Env, key: &K, config: &LimitConfig, now: u64) -> LimiterState
+where
+    K: IntoVal<Env, Val> + Clone,
+{
+    let stored: Option<LimiterState> = env.storage().temporary().get(&storage_key(env, key));
+    match config.strategy {
+        Strategy::TokenBucket => {
+            let full = config.capacity as u128 * config.period as u128;
+            match stored {
+                Some(state) => {
+                    let elapsed = now.saturating_sub(state.updated_at) as u128;
+                    LimiterState {
+                        level: (state.level + elapsed * config.capacity as u128).min(full),
+                        updated_at: now,
+                    }
+                }
+                None => LimiterState {
+                    level: full,
+                    updated_at: now,
+                },
+            }
+        }
+        Strategy::FixedWindow => {
+            let window = now - now % config.period;
+            match stored {
+                Some(state) if state.updated_at == window => state,
+                _ => LimiterState {
+                    level: config.capacity as u128,
+                    updated_at: window,
+                },
+            }
+        }
+    }
+}
+
+fn retry_after(config: &LimitConfig, state: &LimiterState, now: u64) -> u64 {
+    match config.strategy {
+        Strategy::TokenBucket if config.capacity == 0 => u64::MAX,
+        Strategy::TokenBucket => {
+            let missing = config.period as u128 - state.level;
+            missing.div_ceil(config.capacity as u128) as u64
+        }
+        Strategy::FixedWindow => state.updated_at + config.period - now,
+    }
+}
+
+#[cfg(test)]
+mod test;
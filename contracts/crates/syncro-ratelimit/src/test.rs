@@ -0,0 +1,85 @@
+use super::*;
+use soroban_sdk::{contract, testutils::Ledger, Env};
+
+#[contract]
+struct Host;
+
+fn with_host(f: impl FnOnce(&Env)) {
+    let env = Env::default();
+    env.ledger().with_mut(|li| li.timestamp = 1_000_000);
+    let host = env.register(Host, ());
+    env.as_contract(&host, || f(&env));
+}
+
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+}
+
+#[test]
+fn test_token_bucket_bursts_then_refills() {
+    with_host(|env| {
+        let config = LimitConfig::token_bucket(3, 60);
+        for _ in 0..3 {
+            assert_eq!(check_and_consume(env, &1u64, &config), Ok(()));
+        }
+        assert_eq!(
+            check_and_consume(env, &1u64, &config),
+            Err(RateLimited { retry_after: 20 })
+        );
+
+        // One call refills every 20 seconds
+        advance(env, 19);
+        assert_eq!(remaining(env, &1u64, &config), 0);
+        advance(env, 1);
+        assert_eq!(remaining(env, &1u64, &config), 1);
+        assert_eq!(check_and_consume(env, &1u64, &config), Ok(()));
+
+        // Never refills past capacity
+        advance(env, 600);
+        assert_eq!(remaining(env, &1u64, &config), 3);
+    });
+}
+
+#[test]
+fn test_fixed_window_resets_on_boundary() {
+    with_host(|env| {
+        let config = LimitConfig::fixed_window(2, 100);
+        advance(env, 90);
+        assert_eq!(check_and_consume(env, &1u64, &config), Ok(()));
+        assert_eq!(check_and_consume(env, &1u64, &config), Ok(()));
+        assert_eq!(
+            check_and_consume(env, &1u64, &config),
+            Err(RateLimited { retry_after: 10 })
+        );
+
+        advance(env, 10);
+        assert_eq!(remaining(env, &1u64, &config), 2);
+        assert_eq!(check_and_consume(env, &1u64, &config), Ok(()));
+    });
+}
+
+#[test]
+fn test_keys_are_independent() {
+    with_host(|env| {
+        let config = LimitConfig::fixed_window(1, 100);
+        assert_eq!(check_and_consume(env, &1u64, &config), Ok(()));
+        assert!(check_and_consume(env, &1u64, &config).is_err());
+        assert_eq!(check_and_consume(env, &2u64, &config), Ok(()));
+
+        reset(env, &1u64);
+        assert_eq!(check_and_consume(env, &1u64, &config), Ok(()));
+    });
+}
+
+#[test]
+fn test_zero_period_disables_limit() {
+    with_host(|env| {
+        let config = LimitConfig::token_bucket(0, 0);
+        assert!(config.is_valid());
+        assert!(!LimitConfig::fixed_window(0, 60).is_valid());
+        for _ in 0..10 {
+            assert_eq!(check_and_consume(env, &1u64, &config), Ok(()));
+        }
+        assert_eq!(remaining(env, &1u64, &config), u32::MAX);
+    });
+}
@@ -0,0 +1,301 @@
+//! Deterministic replay of recorded contract invocations.
+//!
+//! A [`Recording`] names the contracts involved by kind and lists the calls
+//! made against them in order, with arguments as ScVal JSON (the form the
+//! `stellar` CLI and the keeper use). [`replay`] registers fresh instances
+//! in a new environment, makes each call with every auth mocked and renders
+//! what each call published and what the contracts hold afterwards.
+//! [`verify`] checks that against the recording's `expect`, so an incident
+//! pieced together from mainnet transactions keeps reproducing locally once
+//! its recording is checked in.
+//!
+//! Addresses in arguments are strkeys or names. A name that is not a
+//! contract's is given a generated account address in order of first use,
+//! and events and storage show both kinds by name.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    xdr::{ContractDataDurability, LedgerEntryData, ScAddress, ScVal},
+    Address, Env, Symbol, TryFromVal, Val,
+};
+use std::collections::BTreeMap;
+use syncro_testutils::EventRecorder;
+
+/// Contract kinds a recording can name.
+pub const KINDS: &[&str] = &[
+    "subscription_renewal",
+    "subscription_registry",
+    "subscription_logging",
+    "agent_registry",
+    "syncro_token",
+];
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Recording {
+    /// Ledger to start from; otherwise the testutils default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ledger: Option<LedgerStart>,
+    /// Contract name to kind, one of [`KINDS`].
+    pub contracts: BTreeMap<String, String>,
+    pub calls: Vec<Call>,
+    #[serde(default)]
+    pub expect: Outcome,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LedgerStart {
+    pub sequence: u32,
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Call {
+    /// Name of the contract called.
+    pub contract: String,
+    pub function: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<Value>,
+    /// Ledgers closed before the call.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub advance: u32,
+    /// The call failed when recorded, so it must fail on replay too.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub fails: bool,
+}
+
+/// What a replay produced: the events of each successful call under a
+/// `# <call> <contract>.<function>` heading, and every storage entry of the
+/// recorded contracts, sorted.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Outcome {
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default)]
+    pub storage: Vec<String>,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+impl Recording {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("invalid recording")
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = serde_json::to_string_pretty(self).expect("recording serializes");
+        json.push('\n');
+        json
+    }
+}
+
+/// Run `recording` against fresh contracts. Fails if a call's outcome
+/// differs from the recorded one or an argument cannot be decoded.
+pub fn replay(recording: &Recording) -> Result<Outcome> {
+    let env = syncro_testutils::setup_env();
+    if let Some(start) = &recording.ledger {
+        env.ledger().with_mut(|li| {
+            li.sequence_number = start.sequence;
+            li.timestamp = start.timestamp;
+        });
+    }
+
+    let mut contracts = BTreeMap::new();
+    for (name, kind) in &recording.contracts {
+        contracts.insert(name.clone(), register(&env, kind)?);
+    }
+    let mut names = contracts.clone();
+    for call in &recording.calls {
+        for arg in &call.args {
+            generate_accounts(&env, arg, &mut names);
+        }
+    }
+    let mut events = names
+        .iter()
+        .fold(EventRecorder::new(&env), |events, (name, address)| {
+            events.label(name, address)
+        });
+
+    for (index, call) in recording.calls.iter().enumerate() {
+        let step = format!("{} {}.{}", index + 1, call.contract, call.function);
+        let contract = contracts
+            .get(&call.contract)
+            .ok_or_else(|| anyhow!("call {step}: no contract named `{}`", call.contract))?;
+        let mut args = soroban_sdk::Vec::<Val>::new(&env);
+        for arg in &call.args {
+            args.push_back(decode(&env, arg, &names).with_context(|| format!("call {step}"))?);
+        }
+        if call.advance > 0 {
+            syncro_testutils::advance_ledgers(&env, call.advance);
+        }
+
+        let result = env.try_invoke_contract::<Val, soroban_sdk::Error>(
+            contract,
+            &Symbol::new(&env, &call.function),
+            args,
+        );
+        match (result, call.fails) {
+            (Ok(Ok(_)), false) => events.record(&step),
+            (Ok(Ok(_)), true) => bail!("call {step} succeeded but was recorded failing"),
+            (Ok(Err(err)), false) => bail!("call {step} returned an undecodable value: {err:?}"),
+            (Err(err), false) => bail!("call {step} failed: {err:?}"),
+            (_, true) => {}
+        }
+    }
+
+    Ok(Outcome {
+        events: events.render().lines().map(str::to_string).collect(),
+        storage: storage(&env, &contracts, &mut events),
+    })
+}
+
+/// Replay `recording` and check the result against its `expect`.
+pub fn verify(recording: &Recording) -> Result<()> {
+    let actual = replay(recording)?;
+    compare("events", &recording.expect.events, &actual.events)?;
+    compare("storage", &recording.expect.storage, &actual.storage)
+}
+
+fn compare(what: &str, expected: &[String], actual: &[String]) -> Result<()> {
+    let at = expected
+        .iter()
+        .zip(actual)
+        .position(|(e, a)| e != a)
+        .unwrap_or(expected.len().min(actual.len()));
+    if at == expected.len() && at == actual.len() {
+        return Ok(());
+    }
+    let line = |lines: &[String]| lines.get(at).cloned().unwrap_or_else(|| "<end>".into());
+    bail!(
+        "{what} differ at line {}\n  expected: {}\n  actual:   {}",
+        at + 1,
+        line(expected),
+        line(actual)
+    )
+}
+
+fn register(env: &Env, kind: &str) -> Result<Address> {
+    Ok(match kind {
+        "subscription_renewal" => {
+            env.register(subscription_renewal::SubscriptionRenewalContract, ())
+        }
+        "subscription_registry" => env.register(subscription_registry::SubscriptionRegistry, ()),
+        "subscription_logging" => {
+            env.register(subscription_logging::SubscriptionLoggingContract, ())
+        }
+        "agent_registry" => env.register(agent_registry::AgentRegistry, ()),
+        "syncro_token" => env.register(syncro_token::SyncroToken, ()),
+        other => bail!("unknown contract kind `{other}`; expected one of {KINDS:?}"),
+    })
+}
+
+/// Give every address name in `arg` not yet known an account address.
+fn generate_accounts(env: &Env, arg: &Value, names: &mut BTreeMap<String, Address>) {
+    match arg {
+        Value::Object(fields) => {
+            if let Some(Value::String(name)) = fields.get("address") {
+                if name.parse::<ScAddress>().is_err() && !names.contains_key(name) {
+                    names.insert(name.clone(), Address::generate(env));
+                }
+            }
+            fields
+                .values()
+                .for_each(|field| generate_accounts(env, field, names));
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| generate_accounts(env, item, names)),
+        _ => {}
+    }
+}
+
+fn decode(env: &Env, arg: &Value, names: &BTreeMap<String, Address>) -> Result<Val> {
+    let mut arg = arg.clone();
+    resolve_names(env, &mut arg, names);
+    let val: ScVal =
+        serde_json::from_value(arg.clone()).with_context(|| format!("invalid ScVal {arg}"))?;
+    Val::try_from_val(env, &val).map_err(|err| anyhow!("cannot convert {arg}: {err:?}"))
+}
+
+fn resolve_names(env: &Env, arg: &mut Value, names: &BTreeMap<String, Address>) {
+    match arg {
+        Value::Object(fields) => {
+            if let Some(Value::String(name)) = fields.get_mut("address") {
+                if let Some(address) = names.get(name.as_str()) {
+                    *name = sc_address(env, address).to_string();
+                }
+            }
+            fields
+                .values_mut()
+                .for_each(|field| resolve_names(env, field, names));
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| resolve_names(env, item, names)),
+        _ => {}
+    }
+}
+
+fn sc_address(env: &Env, address: &Address) -> ScAddress {
+    match ScVal::try_from_val(env, &address.to_val()) {
+        Ok(ScVal::Address(address)) => address,
+        _ => unreachable!("addresses convert to ScVal::Address"),
+    }
+}
+
+/// One line per storage entry of `contracts`, with each entry of a
+/// contract's instance storage listed on its own.
+fn storage(
+    env: &Env,
+    contracts: &BTreeMap<String, Address>,
+    events: &mut EventRecorder,
+) -> Vec<String> {
+    let owners: Vec<(ScAddress, &String)> = contracts
+        .iter()
+        .map(|(name, address)| (sc_address(env, address), name))
+        .collect();
+    let mut lines = Vec::new();
+    for (_, (entry, _)) in env.to_ledger_snapshot().ledger_entries {
+        let LedgerEntryData::ContractData(data) = entry.data else {
+            continue;
+        };
+        let Some((_, name)) = owners.iter().find(|(owner, _)| *owner == data.contract) else {
+            continue;
+        };
+        match (&data.key, &data.val) {
+            (ScVal::LedgerKeyContractInstance, ScVal::ContractInstance(instance)) => {
+                for item in instance.storage.iter().flat_map(|map| map.iter()) {
+                    let key = events.render_sc(&item.key);
+                    let val = events.render_sc(&item.val);
+                    lines.push(format!("{name} instance {key} = {val}"));
+                }
+            }
+            (key, val) => {
+                let durability = match data.durability {
+                    ContractDataDurability::Persistent => "persistent",
+                    ContractDataDurability::Temporary => "temporary",
+                };
+                let key = events.render_sc(key);
+                let val = events.render_sc(val);
+                lines.push(format!("{name} {durability} {key} = {val}"));
+            }
+        }
+    }
+    lines.sort();
+    lines
+}
+
+#[cfg(test)]
+mod test;
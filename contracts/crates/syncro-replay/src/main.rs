@@ -0,0 +1,40 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use std::path::PathBuf;
+use syncro_replay::Recording;
+
+#[derive(Parser)]
+#[command(
+    name = "syncro-replay",
+    about = "Replay recorded SYNCRO contract calls against fresh contracts"
+)]
+struct Cli {
+    /// Recording files to replay.
+    #[arg(required = true)]
+    recordings: Vec<PathBuf>,
+
+    /// Write each replay's events and storage into its recording instead
+    /// of checking them.
+    #[arg(long)]
+    update: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    for path in &cli.recordings {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let mut recording =
+            Recording::from_json(&json).with_context(|| path.display().to_string())?;
+        if cli.update {
+            recording.expect =
+                syncro_replay::replay(&recording).with_context(|| path.display().to_string())?;
+            std::fs::write(path, recording.to_json())?;
+            println!("updated {}", path.display());
+        } else {
+            syncro_replay::verify(&recording).with_context(|| path.display().to_string())?;
+            println!("ok {}", path.display());
+        }
+    }
+    Ok(())
+}
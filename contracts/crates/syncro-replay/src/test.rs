@@ -0,0 +1,94 @@
+use super::*;
+use std::path::Path;
+
+fn load(name: &str) -> Recording {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("recordings")
+        .join(name);
+    Recording::from_json(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn test_checked_in_recordings_reproduce() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("recordings");
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let recording = Recording::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        if let Err(err) = verify(&recording) {
+            panic!("{}: {err:#}", path.display());
+        }
+    }
+}
+
+#[test]
+fn test_replay_is_deterministic() {
+    let recording = load("renewal_retry.json");
+    assert_eq!(replay(&recording).unwrap(), replay(&recording).unwrap());
+}
+
+#[test]
+fn test_changed_storage_is_reported() {
+    let mut recording = load("renewal_retry.json");
+    let line = recording
+        .expect
+        .storage
+        .iter_mut()
+        .find(|line| line.starts_with("renewal persistent 1u64 ="))
+        .unwrap();
+    *line = line.replace("failure_count: 0u32", "failure_count: 1u32");
+
+    let err = verify(&recording).unwrap_err().to_string();
+    assert!(err.starts_with("storage differ"), "{err}");
+    assert!(err.contains("failure_count: 1u32"), "{err}");
+}
+
+#[test]
+fn test_missing_event_is_reported() {
+    let mut recording = load("renewal_retry.json");
+    recording.expect.events.pop();
+    let err = verify(&recording).unwrap_err().to_string();
+    assert!(err.starts_with("events differ"), "{err}");
+    assert!(err.contains("expected: <end>"), "{err}");
+}
+
+#[test]
+fn test_call_outcome_must_match_recording() {
+    let mut recording = load("renewal_retry.json");
+    let rejected = recording.calls.iter().position(|call| call.fails).unwrap();
+    recording.calls[rejected].fails = false;
+    let err = replay(&recording).unwrap_err().to_string();
+    assert!(
+        err.starts_with(&format!("call {} renewal.renew failed", rejected + 1)),
+        "{err}"
+    );
+
+    let mut recording = load("renewal_retry.json");
+    recording.calls[0].fails = true;
+    let err = replay(&recording).unwrap_err().to_string();
+    assert_eq!(
+        err,
+        "call 1 logging.init succeeded but was recorded failing"
+    );
+}
+
+#[test]
+fn test_invalid_recordings_are_rejected() {
+    let mut recording = load("renewal_retry.json");
+    recording
+        .contracts
+        .insert("oracle".into(), "price_oracle".into());
+    let err = replay(&recording).unwrap_err().to_string();
+    assert!(
+        err.starts_with("unknown contract kind `price_oracle`"),
+        "{err}"
+    );
+
+    let mut recording = load("renewal_retry.json");
+    recording.calls[0].contract = "logger".into();
+    let err = replay(&recording).unwrap_err().to_string();
+    assert_eq!(err, "call 1 logger.init: no contract named `logger`");
+
+    let mut recording = load("renewal_retry.json");
+    recording.calls[0].args = vec![serde_json::json!({ "u65": "1" })];
+    assert!(replay(&recording).is_err());
+}
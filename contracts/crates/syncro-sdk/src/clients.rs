@@ -0,0 +1,1607 @@
+//! Typed clients mirroring each contract's interface. Method names and
+//! argument names match the contract functions one-to-one so a call here maps
+//! directly onto `stellar contract invoke -- <fn> --<arg> <value>`.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+use crate::invoke::{Invoker, Mode};
+
+fn decode(function: &str, out: &str) -> Result<Value> {
+    if out.is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(out).with_context(|| format!("decoding {function} output"))
+}
+
+/// Binds an invoker to one deployed contract.
+pub struct Bound<'a> {
+    invoker: &'a dyn Invoker,
+    contract_id: &'a str,
+}
+
+impl<'a> Bound<'a> {
+    pub fn new(invoker: &'a dyn Invoker, contract_id: &'a str) -> Self {
+        Self {
+            invoker,
+            contract_id,
+        }
+    }
+
+    fn send(&self, function: &str, args: &[(&str, String)]) -> Result<Value> {
+        let out = self
+            .invoker
+            .invoke(self.contract_id, Mode::Send, function, args)?;
+        decode(function, &out)
+    }
+
+    fn view(&self, function: &str, args: &[(&str, String)]) -> Result<Value> {
+        let out = self
+            .invoker
+            .invoke(self.contract_id, Mode::Simulate, function, args)?;
+        decode(function, &out)
+    }
+
+    /// Schema version of the contract's events. Every SYNCRO contract has
+    /// this view, whichever client wraps it.
+    pub fn get_event_version(&self) -> Result<u32> {
+        let version = self.view("get_event_version", &[])?;
+        version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .with_context(|| format!("unexpected event version {version}"))
+    }
+}
+
+// ── Subscription renewal ──────────────────────────────────────
+
+pub struct RenewalClient<'a>(pub Bound<'a>);
+
+impl RenewalClient<'_> {
+    pub fn init(&self, admin: &str) -> Result<Value> {
+        self.0.send("init", &[("admin", admin.to_string())])
+    }
+
+    pub fn set_admin(&self, new_admin: &str) -> Result<Value> {
+        self.0
+            .send("set_admin", &[("new_admin", new_admin.to_string())])
+    }
+
+    /// Pause or unpause renewals as `caller`, the admin or a pauser,
+    /// optionally noting an incident `reason` code in the contract's pause
+    /// history.
+    pub fn set_paused(&self, caller: &str, paused: bool, reason: Option<u32>) -> Result<Value> {
+        let mut args = vec![
+            ("caller", caller.to_string()),
+            ("paused", paused.to_string()),
+        ];
+        if let Some(reason) = reason {
+            args.push(("reason", reason.to_string()));
+        }
+        self.0.send("set_paused", &args)
+    }
+
+    /// Pause renewals and trip the circuit breaker, halting the contracts
+    /// pointed at it, as `caller`, the admin or a pauser.
+    pub fn pause_all(&self, caller: &str, reason: &str) -> Result<Value> {
+        self.0.send(
+            "pause_all",
+            &[
+                ("caller", caller.to_string()),
+                ("reason", reason.to_string()),
+            ],
+        )
+    }
+
+    /// Give `account` the role bits in `role`: 1 pauser, 2 config manager,
+    /// 4 treasurer.
+    pub fn grant_role(&self, account: &str, role: u32) -> Result<Value> {
+        self.0.send(
+            "grant_role",
+            &[("account", account.to_string()), ("role", role.to_string())],
+        )
+    }
+
+    pub fn revoke_role(&self, account: &str, role: u32) -> Result<Value> {
+        self.0.send(
+            "revoke_role",
+            &[("account", account.to_string()), ("role", role.to_string())],
+        )
+    }
+
+    pub fn get_roles(&self, account: &str) -> Result<Value> {
+        self.0
+            .view("get_roles", &[("account", account.to_string())])
+    }
+
+    pub fn get_role_holders(&self) -> Result<Value> {
+        self.0.view("get_role_holders", &[])
+    }
+
+    /// Protocol fee in basis points of each renewal. `caller` is the admin
+    /// or a treasurer.
+    pub fn set_protocol_fee(&self, caller: &str, fee_bps: u32) -> Result<Value> {
+        self.0.send(
+            "set_protocol_fee",
+            &[
+                ("caller", caller.to_string()),
+                ("fee_bps", fee_bps.to_string()),
+            ],
+        )
+    }
+
+    pub fn is_paused(&self) -> Result<Value> {
+        self.0.view("is_paused", &[])
+    }
+
+    /// Step `sub_ids` written by older code up to the migrated storage
+    /// version, after `migrate`.
+    pub fn migrate_subs(&self, sub_ids: &[u64]) -> Result<Value> {
+        self.0.send(
+            "migrate_subs",
+            &[("sub_ids", serde_json::to_string(sub_ids)?)],
+        )
+    }
+
+    pub fn get_sub_version(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_sub_version", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Keep `sub_id`'s entries alive at least `ledgers` more ledgers.
+    pub fn bump_sub(&self, sub_id: u64, ledgers: u32) -> Result<Value> {
+        self.0.send(
+            "bump_sub",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("ledgers", ledgers.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_pause_info(&self) -> Result<Value> {
+        self.0.view("get_pause_info", &[])
+    }
+
+    pub fn get_pause_history(&self) -> Result<Value> {
+        self.0.view("get_pause_history", &[])
+    }
+
+    /// Set the token renewals are charged in. Admin only.
+    pub fn set_token(&self, address: &str) -> Result<Value> {
+        self.0
+            .send("set_token", &[("address", address.to_string())])
+    }
+
+    pub fn get_token(&self) -> Result<Value> {
+        self.0.view("get_token", &[])
+    }
+
+    pub fn set_logging_contract(&self, address: &str) -> Result<Value> {
+        self.0
+            .send("set_logging_contract", &[("address", address.to_string())])
+    }
+
+    pub fn set_metering_contract(&self, address: &str) -> Result<Value> {
+        self.0
+            .send("set_metering_contract", &[("address", address.to_string())])
+    }
+
+    pub fn set_receipt_contract(&self, address: &str) -> Result<Value> {
+        self.0
+            .send("set_receipt_contract", &[("address", address.to_string())])
+    }
+
+    pub fn set_agent_registry(&self, address: &str) -> Result<Value> {
+        self.0
+            .send("set_agent_registry", &[("address", address.to_string())])
+    }
+
+    pub fn set_address_book(&self, address: &str) -> Result<Value> {
+        self.0
+            .send("set_address_book", &[("address", address.to_string())])
+    }
+
+    pub fn set_subscription_registry(&self, address: &str) -> Result<Value> {
+        self.0.send(
+            "set_subscription_registry",
+            &[("address", address.to_string())],
+        )
+    }
+
+    /// Keep registry entry `entry` in step with `sub_id`'s renewals, or stop
+    /// with `None`. Signed by the subscription owner.
+    pub fn link_registry_entry(&self, sub_id: u64, entry: Option<&str>) -> Result<Value> {
+        let mut args = vec![("sub_id", sub_id.to_string())];
+        if let Some(entry) = entry {
+            args.push(("entry", entry.to_string()));
+        }
+        self.0.send("link_registry_entry", &args)
+    }
+
+    /// Limits for merchant profiles, as a `ProfileBounds` JSON object.
+    pub fn set_profile_bounds(&self, caller: &str, bounds: &Value) -> Result<Value> {
+        self.0.send(
+            "set_profile_bounds",
+            &[
+                ("caller", caller.to_string()),
+                ("bounds", bounds.to_string()),
+            ],
+        )
+    }
+
+    /// Settings for all of `merchant`'s subscriptions, as a
+    /// `MerchantProfile` JSON object. Signed by the merchant.
+    pub fn set_merchant_profile(&self, merchant: &str, profile: &Value) -> Result<Value> {
+        self.0.send(
+            "set_merchant_profile",
+            &[
+                ("merchant", merchant.to_string()),
+                ("profile", profile.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_merchant_profile(&self, merchant: &str) -> Result<Value> {
+        self.0.view(
+            "get_merchant_profile",
+            &[("merchant", merchant.to_string())],
+        )
+    }
+
+    /// Let `key` call `merchant`'s merchant-gated functions.
+    pub fn add_merchant_key(&self, merchant: &str, key: &str) -> Result<Value> {
+        self.0.send(
+            "add_merchant_key",
+            &[("merchant", merchant.to_string()), ("key", key.to_string())],
+        )
+    }
+
+    pub fn revoke_merchant_key(&self, merchant: &str, key: &str) -> Result<Value> {
+        self.0.send(
+            "revoke_merchant_key",
+            &[("merchant", merchant.to_string()), ("key", key.to_string())],
+        )
+    }
+
+    pub fn get_merchant_keys(&self, merchant: &str) -> Result<Value> {
+        self.0
+            .view("get_merchant_keys", &[("merchant", merchant.to_string())])
+    }
+
+    /// The cycle id of period `period_index` of `sub_id`.
+    pub fn compute_cycle_id(&self, sub_id: u64, period_index: u64) -> Result<Value> {
+        self.0.view(
+            "compute_cycle_id",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("period_index", period_index.to_string()),
+            ],
+        )
+    }
+
+    /// The cycle id of the period of `sub_id` underway now.
+    pub fn get_current_cycle_id(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_current_cycle_id", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Require `renew` to be given the current period's cycle id.
+    pub fn set_derived_cycle_ids(&self, caller: &str, enabled: bool) -> Result<Value> {
+        self.0.send(
+            "set_derived_cycle_ids",
+            &[
+                ("caller", caller.to_string()),
+                ("enabled", enabled.to_string()),
+            ],
+        )
+    }
+
+    /// Expected seconds between ledgers, used to convert time to ledgers.
+    pub fn set_ledger_seconds(&self, caller: &str, seconds: u64) -> Result<Value> {
+        self.0.send(
+            "set_ledger_seconds",
+            &[
+                ("caller", caller.to_string()),
+                ("seconds", seconds.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_ledger_seconds(&self) -> Result<Value> {
+        self.0.view("get_ledger_seconds", &[])
+    }
+
+    /// Ledger expected at `timestamp`, e.g. for an approval's `expires_at`.
+    pub fn ledger_at(&self, timestamp: u64) -> Result<Value> {
+        self.0
+            .view("ledger_at", &[("timestamp", timestamp.to_string())])
+    }
+
+    /// Timestamp `sequence` is expected to close at.
+    pub fn timestamp_at(&self, sequence: u32) -> Result<Value> {
+        self.0
+            .view("timestamp_at", &[("sequence", sequence.to_string())])
+    }
+
+    pub fn release_renewal_lock(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .send("release_renewal_lock", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn get_renewal_lock(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_renewal_lock", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn get_sub(&self, sub_id: u64) -> Result<Value> {
+        self.0.view("get_sub", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn get_lifecycle(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_lifecycle", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// The state `sub_id` was in at the end of `ledger`.
+    pub fn get_state_at(&self, sub_id: u64, ledger: u32) -> Result<Value> {
+        self.0.view(
+            "get_state_at",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("ledger", ledger.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_state_journal(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_state_journal", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn set_privacy_mode(&self, enabled: bool) -> Result<Value> {
+        self.0
+            .send("set_privacy_mode", &[("enabled", enabled.to_string())])
+    }
+
+    pub fn is_privacy_mode(&self) -> Result<Value> {
+        self.0.view("is_privacy_mode", &[])
+    }
+
+    /// Reason code of the latest failed renewal, or null after a success.
+    pub fn get_last_failure(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_last_failure", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Subscription state, the one detail readable in privacy mode.
+    pub fn get_status(&self, sub_id: u64) -> Result<Value> {
+        self.0.view("get_status", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// `get_sub` read as `viewer`, which must be the signing source while
+    /// privacy mode is on.
+    pub fn get_sub_as(&self, viewer: &str, sub_id: u64) -> Result<Value> {
+        self.0.view(
+            "get_sub_as",
+            &[
+                ("viewer", viewer.to_string()),
+                ("sub_id", sub_id.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_lifecycle_as(&self, viewer: &str, sub_id: u64) -> Result<Value> {
+        self.0.view(
+            "get_lifecycle_as",
+            &[
+                ("viewer", viewer.to_string()),
+                ("sub_id", sub_id.to_string()),
+            ],
+        )
+    }
+
+    /// Protocol rules the subscription currently breaks; an empty array
+    /// when healthy.
+    pub fn check_invariants(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("check_invariants", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Ways the linked registry entry disagrees with the subscription; an
+    /// empty array when they agree.
+    pub fn check_link_consistency(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("check_link_consistency", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn is_metered(&self, sub_id: u64) -> Result<Value> {
+        self.0.view("is_metered", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Park renewals over the spending cap for the owner to confirm.
+    pub fn set_soft_cap(&self, sub_id: u64, enabled: bool) -> Result<Value> {
+        self.0.send(
+            "set_soft_cap",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("enabled", enabled.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_pending_renewal(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_pending_renewal", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn confirm_pending_renewal(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .send("confirm_pending_renewal", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Pay `amount` towards a cycle on `funder`'s behalf. Signed by the
+    /// funder.
+    pub fn pay_partial(
+        &self,
+        sub_id: u64,
+        cycle_id: u64,
+        funder: &str,
+        amount: i128,
+    ) -> Result<Value> {
+        self.0.send(
+            "pay_partial",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("cycle_id", cycle_id.to_string()),
+                ("funder", funder.to_string()),
+                ("amount", amount.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_cycle_funding(&self, sub_id: u64, cycle_id: u64) -> Result<Value> {
+        self.0.view(
+            "get_cycle_funding",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("cycle_id", cycle_id.to_string()),
+            ],
+        )
+    }
+
+    pub fn refund_partial_payments(&self, sub_id: u64, cycle_id: u64) -> Result<Value> {
+        self.0.send(
+            "refund_partial_payments",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("cycle_id", cycle_id.to_string()),
+            ],
+        )
+    }
+
+    pub fn set_metered(&self, sub_id: u64, metered: bool) -> Result<Value> {
+        self.0.send(
+            "set_metered",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("metered", metered.to_string()),
+            ],
+        )
+    }
+
+    pub fn init_sub(&self, sub: &NewSubscription) -> Result<Value> {
+        self.0.send(
+            "init_sub",
+            &[
+                ("owner", sub.owner.clone()),
+                ("merchant", sub.merchant.clone()),
+                ("amount", sub.amount.to_string()),
+                ("frequency", sub.frequency.to_string()),
+                ("spending_cap", sub.spending_cap.to_string()),
+                ("sub_id", sub.sub_id.to_string()),
+            ],
+        )
+    }
+
+    /// `init_sub` with an indexation clause, as JSON
+    /// `{"rate_bps": ..., "interval": ...}`: the amount rises by `rate_bps`
+    /// every `interval` seconds, up to the spending cap.
+    pub fn init_indexed_sub(&self, sub: &NewSubscription, indexation: &Value) -> Result<Value> {
+        self.0.send(
+            "init_indexed_sub",
+            &[
+                ("owner", sub.owner.clone()),
+                ("merchant", sub.merchant.clone()),
+                ("amount", sub.amount.to_string()),
+                ("frequency", sub.frequency.to_string()),
+                ("spending_cap", sub.spending_cap.to_string()),
+                ("sub_id", sub.sub_id.to_string()),
+                ("indexation", indexation.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_indexation(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_indexation", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// The amount to pass `renew` for `sub_id` now, indexation included.
+    pub fn get_amount_due(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_amount_due", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Publish a template of `merchant`'s, with `terms` as JSON
+    /// `{"amount": ..., "frequency": ..., "spending_cap": ..., "trial": ...}`.
+    pub fn create_template(&self, merchant: &str, terms: &Value) -> Result<Value> {
+        self.0.send(
+            "create_template",
+            &[
+                ("merchant", merchant.to_string()),
+                ("terms", terms.to_string()),
+            ],
+        )
+    }
+
+    /// Publish `terms` as the next version of `template_id`.
+    pub fn update_template(&self, template_id: u64, terms: &Value) -> Result<Value> {
+        self.0.send(
+            "update_template",
+            &[
+                ("template_id", template_id.to_string()),
+                ("terms", terms.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_template(&self, template_id: u64) -> Result<Value> {
+        self.0
+            .view("get_template", &[("template_id", template_id.to_string())])
+    }
+
+    /// Create `sub_id` for `owner` on a template's latest terms. Signed by
+    /// the owner.
+    pub fn init_sub_from_template(
+        &self,
+        template_id: u64,
+        owner: &str,
+        sub_id: u64,
+    ) -> Result<Value> {
+        self.0.send(
+            "init_sub_from_template",
+            &[
+                ("template_id", template_id.to_string()),
+                ("owner", owner.to_string()),
+                ("sub_id", sub_id.to_string()),
+            ],
+        )
+    }
+
+    /// Cancel `sub_id`, optionally with a cancellation reason code.
+    pub fn cancel_sub(&self, sub_id: u64, reason: Option<u32>) -> Result<Value> {
+        let mut args = vec![("sub_id", sub_id.to_string())];
+        if let Some(reason) = reason {
+            args.push(("reason", reason.to_string()));
+        }
+        self.0.send("cancel_sub", &args)
+    }
+
+    pub fn get_cancel_reason(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_cancel_reason", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Hold `sub_id` so it is not renewed until resumed. Signed by the
+    /// owner.
+    pub fn pause_sub(&self, sub_id: u64) -> Result<Value> {
+        self.0.send("pause_sub", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn resume_sub(&self, sub_id: u64) -> Result<Value> {
+        self.0.send("resume_sub", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Undo a cancellation within the restore window. Signed by the owner.
+    pub fn restore_sub(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .send("restore_sub", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Ledgers after a cancellation during which it can be undone.
+    pub fn set_restore_window(&self, caller: &str, ledgers: u32) -> Result<Value> {
+        self.0.send(
+            "set_restore_window",
+            &[
+                ("caller", caller.to_string()),
+                ("ledgers", ledgers.to_string()),
+            ],
+        )
+    }
+
+    /// Ledgers a failing subscription keeps retrying past its last retry.
+    pub fn set_grace_period(&self, caller: &str, ledgers: u32) -> Result<Value> {
+        self.0.send(
+            "set_grace_period",
+            &[
+                ("caller", caller.to_string()),
+                ("ledgers", ledgers.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_grace_remaining(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_grace_remaining", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Seconds past its due date an unrenewed subscription is left before
+    /// it can be expired.
+    pub fn set_delinquency_horizon(&self, caller: &str, seconds: u64) -> Result<Value> {
+        self.0.send(
+            "set_delinquency_horizon",
+            &[
+                ("caller", caller.to_string()),
+                ("seconds", seconds.to_string()),
+            ],
+        )
+    }
+
+    /// Expire delinquent subscriptions among the next `limit` indexed.
+    pub fn sweep_expired(&self, limit: u32) -> Result<Value> {
+        self.0
+            .send("sweep_expired", &[("limit", limit.to_string())])
+    }
+
+    /// Anchor the hex `hash` of the terms agreed for `sub_id`. Signed by
+    /// both the owner and the merchant.
+    pub fn anchor_metadata(&self, sub_id: u64, hash: &str) -> Result<Value> {
+        self.0.send(
+            "anchor_metadata",
+            &[("sub_id", sub_id.to_string()), ("hash", hash.to_string())],
+        )
+    }
+
+    pub fn get_metadata_anchor(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_metadata_anchor", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Weighted recipients splitting each charge, as a JSON array of
+    /// `{"recipient": ..., "weight": ...}`; an empty array pays the
+    /// merchant everything. Signed by the merchant or one of its keys.
+    pub fn set_payouts(&self, caller: &str, sub_id: u64, payouts: &Value) -> Result<Value> {
+        self.0.send(
+            "set_payouts",
+            &[
+                ("caller", caller.to_string()),
+                ("sub_id", sub_id.to_string()),
+                ("payouts", payouts.to_string()),
+            ],
+        )
+    }
+
+    /// What the successful renewal of `sub_id` for `cycle_id` charged.
+    pub fn get_receipt(&self, sub_id: u64, cycle_id: u64) -> Result<Value> {
+        self.0.view(
+            "get_receipt",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("cycle_id", cycle_id.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_payouts(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_payouts", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Have `payer` fund `sub_id`, or go back to the owner paying with
+    /// `None`. Signed by the owner and the new payer.
+    pub fn set_payer(&self, sub_id: u64, payer: Option<&str>) -> Result<Value> {
+        let mut args = vec![("sub_id", sub_id.to_string())];
+        if let Some(payer) = payer {
+            args.push(("payer", payer.to_string()));
+        }
+        self.0.send("set_payer", &args)
+    }
+
+    pub fn get_payer(&self, sub_id: u64) -> Result<Value> {
+        self.0.view("get_payer", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Have the `strategy` contract settle `sub_id`'s charges, or go back
+    /// to the built-in payment with `None`. Signed by the owner and
+    /// `caller`, the merchant or one of its keys.
+    pub fn set_payment_strategy(
+        &self,
+        caller: &str,
+        sub_id: u64,
+        strategy: Option<&str>,
+    ) -> Result<Value> {
+        let mut args = vec![
+            ("caller", caller.to_string()),
+            ("sub_id", sub_id.to_string()),
+        ];
+        if let Some(strategy) = strategy {
+            args.push(("strategy", strategy.to_string()));
+        }
+        self.0.send("set_payment_strategy", &args)
+    }
+
+    pub fn get_payment_strategy(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_payment_strategy", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// The owner's notification consent for `sub_id`, as JSON
+    /// `{"remind_days": ..., "mode": ...}`, or none to withdraw it.
+    pub fn set_notification_prefs(&self, sub_id: u64, prefs: Option<&Value>) -> Result<Value> {
+        let mut args = vec![("sub_id", sub_id.to_string())];
+        if let Some(prefs) = prefs {
+            args.push(("prefs", prefs.to_string()));
+        }
+        self.0.send("set_notification_prefs", &args)
+    }
+
+    pub fn get_notification_prefs(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_notification_prefs", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Amount `sub_id` drops to instead of failing, or none with `None`.
+    /// Signed by both the owner and the merchant or one of its keys.
+    pub fn set_fallback_plan(
+        &self,
+        caller: &str,
+        sub_id: u64,
+        amount: Option<i128>,
+    ) -> Result<Value> {
+        let mut args = vec![
+            ("caller", caller.to_string()),
+            ("sub_id", sub_id.to_string()),
+        ];
+        if let Some(amount) = amount {
+            args.push(("amount", amount.to_string()));
+        }
+        self.0.send("set_fallback_plan", &args)
+    }
+
+    pub fn get_fallback_plan(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_fallback_plan", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Set the retry policy subscriptions created from now on start with.
+    /// Admin or config manager.
+    pub fn set_default_retry_policy(&self, caller: &str, policy: &Value) -> Result<Value> {
+        self.0.send(
+            "set_default_retry_policy",
+            &[
+                ("caller", caller.to_string()),
+                ("policy", policy.to_string()),
+            ],
+        )
+    }
+
+    /// Change how many failed renewals `sub_id` retries and how far apart.
+    /// Signed by the owner and `caller`, the merchant or one of its keys.
+    pub fn set_retry_policy(&self, caller: &str, sub_id: u64, policy: &Value) -> Result<Value> {
+        self.0.send(
+            "set_retry_policy",
+            &[
+                ("caller", caller.to_string()),
+                ("sub_id", sub_id.to_string()),
+                ("policy", policy.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_retry_policy(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_retry_policy", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// What `sub_id` was charged in its current spending window.
+    pub fn get_spend_window(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_spend_window", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Grow `sub_id`'s cooldown with each consecutive failure, or remove
+    /// the policy with `None`. Signed by the owner and `caller`, the
+    /// merchant or one of its keys.
+    pub fn set_backoff_policy(
+        &self,
+        caller: &str,
+        sub_id: u64,
+        policy: Option<&Value>,
+    ) -> Result<Value> {
+        let mut args = vec![
+            ("caller", caller.to_string()),
+            ("sub_id", sub_id.to_string()),
+        ];
+        if let Some(policy) = policy {
+            args.push(("policy", policy.to_string()));
+        }
+        self.0.send("set_backoff_policy", &args)
+    }
+
+    pub fn get_backoff_policy(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_backoff_policy", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn get_next_retry_ledger(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_next_retry_ledger", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Bill `sub_id` every `frequency` seconds from its next cycle
+    /// boundary. Signed by the owner and `caller`, the merchant or one of
+    /// its keys.
+    pub fn schedule_frequency_change(
+        &self,
+        caller: &str,
+        sub_id: u64,
+        frequency: u64,
+    ) -> Result<Value> {
+        self.0.send(
+            "schedule_frequency_change",
+            &[
+                ("caller", caller.to_string()),
+                ("sub_id", sub_id.to_string()),
+                ("frequency", frequency.to_string()),
+            ],
+        )
+    }
+
+    /// Replace `sub_id`'s amount, frequency and spending cap at once.
+    /// Signed by the owner and the merchant.
+    pub fn amend_sub(
+        &self,
+        sub_id: u64,
+        new_amount: i128,
+        new_frequency: u64,
+        new_cap: i128,
+    ) -> Result<Value> {
+        self.0.send(
+            "amend_sub",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("new_amount", new_amount.to_string()),
+                ("new_frequency", new_frequency.to_string()),
+                ("new_cap", new_cap.to_string()),
+            ],
+        )
+    }
+
+    pub fn cancel_frequency_change(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .send("cancel_frequency_change", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn get_frequency_change(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_frequency_change", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Cancel up to `limit` of `owner`'s subscriptions. Returns how many
+    /// are left to cancel.
+    pub fn cancel_all_for_owner(&self, owner: &str, limit: u32) -> Result<u32> {
+        let remaining = self.0.send(
+            "cancel_all_for_owner",
+            &[("owner", owner.to_string()), ("limit", limit.to_string())],
+        )?;
+        remaining
+            .as_u64()
+            .and_then(|n| u32::try_from(n).ok())
+            .with_context(|| format!("unexpected remaining count {remaining}"))
+    }
+
+    /// Approved versus charged spend for `owner`, by period.
+    pub fn get_approval_utilization(&self, owner: &str) -> Result<Value> {
+        self.0
+            .view("get_approval_utilization", &[("owner", owner.to_string())])
+    }
+
+    pub fn get_owner_subs(&self, owner: &str) -> Result<Value> {
+        self.0
+            .view("get_owner_subs", &[("owner", owner.to_string())])
+    }
+
+    pub fn approve_renewal(
+        &self,
+        sub_id: u64,
+        approval_id: u64,
+        max_spend: i128,
+        expires_at: u32,
+    ) -> Result<Value> {
+        self.0.send(
+            "approve_renewal",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("approval_id", approval_id.to_string()),
+                ("max_spend", max_spend.to_string()),
+                ("expires_at", expires_at.to_string()),
+            ],
+        )
+    }
+
+    /// Let `agent` create approvals for `owner` within `budget`, a
+    /// `DelegationBudget` JSON object, or stop it with `None`. Signed by
+    /// the owner.
+    pub fn set_delegation_budget(
+        &self,
+        owner: &str,
+        agent: &str,
+        budget: Option<&Value>,
+    ) -> Result<Value> {
+        let mut args = vec![("owner", owner.to_string()), ("agent", agent.to_string())];
+        if let Some(budget) = budget {
+            args.push(("budget", budget.to_string()));
+        }
+        self.0.send("set_delegation_budget", &args)
+    }
+
+    pub fn get_delegation(&self, owner: &str, agent: &str) -> Result<Value> {
+        self.0.view(
+            "get_delegation",
+            &[("owner", owner.to_string()), ("agent", agent.to_string())],
+        )
+    }
+
+    /// Create an approval signed by `agent` on the owner's behalf, counted
+    /// against the budget the owner gave it.
+    pub fn approve_renewal_as(
+        &self,
+        agent: &str,
+        sub_id: u64,
+        approval_id: u64,
+        max_spend: i128,
+        expires_at: u32,
+    ) -> Result<Value> {
+        self.0.send(
+            "approve_renewal_as",
+            &[
+                ("agent", agent.to_string()),
+                ("sub_id", sub_id.to_string()),
+                ("approval_id", approval_id.to_string()),
+                ("max_spend", max_spend.to_string()),
+                ("expires_at", expires_at.to_string()),
+            ],
+        )
+    }
+
+    pub fn revoke_delegated_approval(&self, sub_id: u64, approval_id: u64) -> Result<Value> {
+        self.0.send(
+            "revoke_delegated_approval",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("approval_id", approval_id.to_string()),
+            ],
+        )
+    }
+
+    /// Approve `operator` to transfer the subscription, or revoke with `None`.
+    pub fn approve_sub_transfer(&self, sub_id: u64, operator: Option<&str>) -> Result<Value> {
+        let mut args = vec![("sub_id", sub_id.to_string())];
+        if let Some(operator) = operator {
+            args.push(("operator", operator.to_string()));
+        }
+        self.0.send("approve_sub_transfer", &args)
+    }
+
+    pub fn transfer_sub(&self, sub_id: u64, caller: &str, new_owner: &str) -> Result<Value> {
+        self.0.send(
+            "transfer_sub",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("caller", caller.to_string()),
+                ("new_owner", new_owner.to_string()),
+            ],
+        )
+    }
+
+    pub fn acquire_renewal_lock(
+        &self,
+        agent: &str,
+        sub_id: u64,
+        lock_timeout: u32,
+    ) -> Result<Value> {
+        self.0.send(
+            "acquire_renewal_lock",
+            &[
+                ("agent", agent.to_string()),
+                ("sub_id", sub_id.to_string()),
+                ("lock_timeout", lock_timeout.to_string()),
+            ],
+        )
+    }
+
+    /// Drop `agent`'s cached registry grants, e.g. after a revocation.
+    pub fn invalidate_agent_cache(&self, agent: &str) -> Result<Value> {
+        self.0
+            .send("invalidate_agent_cache", &[("agent", agent.to_string())])
+    }
+
+    /// Pin `agent` to renew `sub_id`, or unpin with `None`. Signed by
+    /// `caller`, the owner or the merchant.
+    pub fn set_preferred_agent(
+        &self,
+        caller: &str,
+        sub_id: u64,
+        agent: Option<&str>,
+    ) -> Result<Value> {
+        let mut args = vec![
+            ("caller", caller.to_string()),
+            ("sub_id", sub_id.to_string()),
+        ];
+        if let Some(agent) = agent {
+            args.push(("agent", agent.to_string()));
+        }
+        self.0.send("set_preferred_agent", &args)
+    }
+
+    pub fn get_preferred_agent(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_preferred_agent", &[("sub_id", sub_id.to_string())])
+    }
+
+    /// Queue `agent` for `sub_id`'s lock instead of racing for it.
+    /// Signed by the agent.
+    pub fn register_renewal_intent(&self, agent: &str, sub_id: u64) -> Result<Value> {
+        self.0.send(
+            "register_renewal_intent",
+            &[("agent", agent.to_string()), ("sub_id", sub_id.to_string())],
+        )
+    }
+
+    pub fn get_renewal_intents(&self, sub_id: u64) -> Result<Value> {
+        self.0
+            .view("get_renewal_intents", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn renew(&self, call: &RenewArgs) -> Result<bool> {
+        let out = self.0.send(
+            "renew",
+            &[
+                ("sub_id", call.sub_id.to_string()),
+                ("approval_id", call.approval_id.to_string()),
+                ("amount", call.amount.to_string()),
+                ("cycle_id", call.cycle_id.to_string()),
+            ],
+        )?;
+        Ok(out == Value::Bool(true))
+    }
+
+    /// Renew each of `calls` in one transaction, skipping those whose
+    /// preconditions fail. Returns the contract's outcome per call.
+    pub fn renew_batch(&self, calls: &[RenewArgs]) -> Result<Value> {
+        let requests: Vec<Value> = calls
+            .iter()
+            .map(|call| {
+                serde_json::json!({
+                    "sub_id": call.sub_id.to_string(),
+                    "approval_id": call.approval_id.to_string(),
+                    "amount": call.amount.to_string(),
+                    "cycle_id": call.cycle_id.to_string(),
+                })
+            })
+            .collect();
+        self.0.send(
+            "renew_batch",
+            &[("requests", Value::Array(requests).to_string())],
+        )
+    }
+}
+
+// ── Agent registry ────────────────────────────────────────────
+
+pub struct AgentRegistryClient<'a>(pub Bound<'a>);
+
+impl AgentRegistryClient<'_> {
+    pub fn init(&self, admin: &str) -> Result<Value> {
+        self.0.send("init", &[("admin", admin.to_string())])
+    }
+
+    pub fn register(&self, agent: &str) -> Result<Value> {
+        self.0.send("register", &[("agent", agent.to_string())])
+    }
+
+    pub fn update_scopes(&self, agent: &str, scopes: u32) -> Result<Value> {
+        self.0.send(
+            "update_scopes",
+            &[("agent", agent.to_string()), ("scopes", scopes.to_string())],
+        )
+    }
+
+    /// Revoke `agent`, recording a reason code (see [`parse_reason`]).
+    pub fn revoke_agent(&self, agent: &str, reason: u32) -> Result<Value> {
+        self.0.send(
+            "revoke_agent",
+            &[("agent", agent.to_string()), ("reason", reason.to_string())],
+        )
+    }
+
+    /// Have revocations and scope changes drop the grants `renewal`
+    /// cached, or stop with `None`.
+    pub fn set_renewal_contract(&self, renewal: Option<&str>) -> Result<Value> {
+        let mut args = vec![];
+        if let Some(renewal) = renewal {
+            args.push(("renewal", renewal.to_string()));
+        }
+        self.0.send("set_renewal_contract", &args)
+    }
+
+    pub fn get_revocation(&self, agent: &str) -> Result<Value> {
+        self.0
+            .view("get_revocation", &[("agent", agent.to_string())])
+    }
+
+    pub fn is_authorized(&self, agent: &str) -> Result<Value> {
+        self.0
+            .view("is_authorized", &[("agent", agent.to_string())])
+    }
+}
+
+// ── Subscription registry ─────────────────────────────────────
+
+pub struct RegistryClient<'a>(pub Bound<'a>);
+
+impl RegistryClient<'_> {
+    pub fn create_subscription(
+        &self,
+        user: &str,
+        service_id: &str,
+        billing_interval: u64,
+        expected_amount: i128,
+        next_renewal: u64,
+    ) -> Result<Value> {
+        self.0.send(
+            "create_subscription",
+            &[
+                ("user", user.to_string()),
+                ("service_id", service_id.to_string()),
+                ("billing_interval", billing_interval.to_string()),
+                ("expected_amount", expected_amount.to_string()),
+                ("next_renewal", next_renewal.to_string()),
+            ],
+        )
+    }
+
+    /// Cancel `subscription_id`, optionally with a cancellation reason
+    /// code.
+    pub fn cancel_subscription(
+        &self,
+        subscription_id: &str,
+        user: &str,
+        reason: Option<u32>,
+    ) -> Result<Value> {
+        let mut args = vec![
+            ("subscription_id", subscription_id.to_string()),
+            ("user", user.to_string()),
+        ];
+        if let Some(reason) = reason {
+            args.push(("reason", reason.to_string()));
+        }
+        self.0.send("cancel_subscription", &args)
+    }
+
+    pub fn get_cancel_reason(&self, subscription_id: &str) -> Result<Value> {
+        self.0.view(
+            "get_cancel_reason",
+            &[("subscription_id", subscription_id.to_string())],
+        )
+    }
+
+    /// Approve `operator` to transfer one of `user`'s subscriptions, or
+    /// revoke with `None`.
+    pub fn approve_transfer(
+        &self,
+        subscription_id: &str,
+        user: &str,
+        operator: Option<&str>,
+    ) -> Result<Value> {
+        let mut args = vec![
+            ("subscription_id", subscription_id.to_string()),
+            ("user", user.to_string()),
+        ];
+        if let Some(operator) = operator {
+            args.push(("operator", operator.to_string()));
+        }
+        self.0.send("approve_transfer", &args)
+    }
+
+    pub fn transfer_subscription(
+        &self,
+        subscription_id: &str,
+        operator: &str,
+        to: &str,
+    ) -> Result<Value> {
+        self.0.send(
+            "transfer_subscription",
+            &[
+                ("subscription_id", subscription_id.to_string()),
+                ("operator", operator.to_string()),
+                ("to", to.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_subscription(&self, subscription_id: &str) -> Result<Value> {
+        self.0.view(
+            "get_subscription",
+            &[("subscription_id", subscription_id.to_string())],
+        )
+    }
+
+    pub fn get_user_subscriptions(&self, user: &str) -> Result<Value> {
+        self.0
+            .view("get_user_subscriptions", &[("user", user.to_string())])
+    }
+
+    /// Link one of `user`'s subscriptions to `merchant`; both sign.
+    pub fn set_subscription_merchant(
+        &self,
+        subscription_id: &str,
+        user: &str,
+        merchant: &str,
+    ) -> Result<Value> {
+        self.0.send(
+            "set_subscription_merchant",
+            &[
+                ("subscription_id", subscription_id.to_string()),
+                ("user", user.to_string()),
+                ("merchant", merchant.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_subscription_merchant(&self, subscription_id: &str) -> Result<Value> {
+        self.0.view(
+            "get_subscription_merchant",
+            &[("subscription_id", subscription_id.to_string())],
+        )
+    }
+
+    /// A page of the subscriptions linked to `merchant`, read as the
+    /// merchant.
+    pub fn get_merchant_subscribers(
+        &self,
+        merchant: &str,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Value> {
+        self.0.view(
+            "get_merchant_subscribers",
+            &[
+                ("merchant", merchant.to_string()),
+                ("offset", offset.to_string()),
+                ("limit", limit.to_string()),
+            ],
+        )
+    }
+
+    /// Have `notify_due` call the `target` contract when `user`'s
+    /// subscriptions come due, or stop with `None`.
+    pub fn set_notification_target(&self, user: &str, target: Option<&str>) -> Result<Value> {
+        let mut args = vec![("user", user.to_string())];
+        if let Some(target) = target {
+            args.push(("target", target.to_string()));
+        }
+        self.0.send("set_notification_target", &args)
+    }
+
+    /// Notify `user`'s target of up to `limit` subscriptions in their
+    /// reminder window. Anyone may submit this.
+    pub fn notify_due(&self, user: &str, limit: u32) -> Result<Value> {
+        self.0.send(
+            "notify_due",
+            &[("user", user.to_string()), ("limit", limit.to_string())],
+        )
+    }
+
+    /// Every subscription in `user`'s list with its terms, as a list of
+    /// `PortfolioEntry` JSON objects.
+    pub fn export_user_portfolio(&self, user: &str) -> Result<Value> {
+        self.0
+            .view("export_user_portfolio", &[("user", user.to_string())])
+    }
+
+    /// Recreate exported subscriptions in `user`'s list under new ids, from
+    /// a list of `PortfolioEntry` JSON objects. Signed by the user.
+    pub fn import_portfolio(&self, user: &str, entries: &Value) -> Result<Value> {
+        self.0.send(
+            "import_portfolio",
+            &[("user", user.to_string()), ("entries", entries.to_string())],
+        )
+    }
+
+    pub fn get_service_stats(&self, service_id: &str) -> Result<Value> {
+        self.0.view(
+            "get_service_stats",
+            &[("service_id", service_id.to_string())],
+        )
+    }
+
+    pub fn get_merchant_stats(&self, merchant: &str) -> Result<Value> {
+        self.0
+            .view("get_merchant_stats", &[("merchant", merchant.to_string())])
+    }
+
+    /// Whether the latest renewal reported for the entry failed.
+    pub fn is_at_risk(&self, subscription_id: &str) -> Result<Value> {
+        self.0.view(
+            "is_at_risk",
+            &[("subscription_id", subscription_id.to_string())],
+        )
+    }
+}
+
+// ── Logging ───────────────────────────────────────────────────
+
+pub struct LoggingClient<'a>(pub Bound<'a>);
+
+impl LoggingClient<'_> {
+    pub fn get_logs(&self, sub_id: u64) -> Result<Value> {
+        self.0.view("get_logs", &[("sub_id", sub_id.to_string())])
+    }
+
+    pub fn get_logs_as(&self, viewer: &str, sub_id: u64) -> Result<Value> {
+        self.0.view(
+            "get_logs_as",
+            &[
+                ("viewer", viewer.to_string()),
+                ("sub_id", sub_id.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_log_summary(&self, sub_id: u64, recent: u32) -> Result<Value> {
+        self.0.view(
+            "get_log_summary",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("recent", recent.to_string()),
+            ],
+        )
+    }
+}
+
+// ── Usage metering ────────────────────────────────────────────
+
+pub struct MeteringClient<'a>(pub Bound<'a>);
+
+impl MeteringClient<'_> {
+    pub fn register_sub(&self, merchant: &str, sub_id: u64) -> Result<Value> {
+        self.0.send(
+            "register_sub",
+            &[
+                ("merchant", merchant.to_string()),
+                ("sub_id", sub_id.to_string()),
+            ],
+        )
+    }
+
+    pub fn authorize_meter(&self, merchant: &str, meter: &str) -> Result<Value> {
+        self.0.send(
+            "authorize_meter",
+            &[
+                ("merchant", merchant.to_string()),
+                ("meter", meter.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_usage(&self, sub_id: u64, period: u64) -> Result<Value> {
+        self.0.view(
+            "get_usage",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("period", period.to_string()),
+            ],
+        )
+    }
+
+    pub fn quote(&self, sub_id: u64, period: u64) -> Result<Value> {
+        self.0.view(
+            "quote",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("period", period.to_string()),
+            ],
+        )
+    }
+}
+
+// ── Renewal receipts ──────────────────────────────────────────
+
+pub struct ReceiptsClient<'a>(pub Bound<'a>);
+
+impl ReceiptsClient<'_> {
+    pub fn receipt_for_cycle(&self, sub_id: u64, cycle_id: u64) -> Result<Value> {
+        self.0.view(
+            "receipt_for_cycle",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("cycle_id", cycle_id.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_receipt(&self, token_id: u64) -> Result<Value> {
+        self.0
+            .view("get_receipt", &[("token_id", token_id.to_string())])
+    }
+
+    pub fn has_paid_since(&self, owner: &str, merchant: &str, since: u64) -> Result<Value> {
+        self.0.view(
+            "has_paid_since",
+            &[
+                ("owner", owner.to_string()),
+                ("merchant", merchant.to_string()),
+                ("since", since.to_string()),
+            ],
+        )
+    }
+}
+
+// ── Keeper bounty ─────────────────────────────────────────────
+
+pub struct BountyClient<'a>(pub Bound<'a>);
+
+impl BountyClient<'_> {
+    pub fn fund(
+        &self,
+        funder: &str,
+        sub_id: u64,
+        reward_per_cycle: i128,
+        amount: i128,
+    ) -> Result<Value> {
+        self.0.send(
+            "fund",
+            &[
+                ("funder", funder.to_string()),
+                ("sub_id", sub_id.to_string()),
+                ("reward_per_cycle", reward_per_cycle.to_string()),
+                ("amount", amount.to_string()),
+            ],
+        )
+    }
+
+    pub fn withdraw(&self, sub_id: u64, amount: i128) -> Result<Value> {
+        self.0.send(
+            "withdraw",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("amount", amount.to_string()),
+            ],
+        )
+    }
+
+    pub fn get_bounty(&self, sub_id: u64) -> Result<Value> {
+        self.0.view("get_bounty", &[("sub_id", sub_id.to_string())])
+    }
+}
+
+// ── Multicall ─────────────────────────────────────────────────
+
+pub struct MulticallClient<'a>(pub Bound<'a>);
+
+impl MulticallClient<'_> {
+    pub fn get_view(&self, sub_id: u64, recent_logs: u32) -> Result<Value> {
+        self.0.view(
+            "get_view",
+            &[
+                ("sub_id", sub_id.to_string()),
+                ("recent_logs", recent_logs.to_string()),
+            ],
+        )
+    }
+
+    /// Everything shown for each of `sub_ids` in one simulated call.
+    pub fn multi_get(&self, sub_ids: &[u64], recent_logs: u32) -> Result<Value> {
+        self.0.view(
+            "multi_get",
+            &[
+                ("sub_ids", serde_json::to_string(sub_ids)?),
+                ("recent_logs", recent_logs.to_string()),
+            ],
+        )
+    }
+}
+
+// ── Address book ──────────────────────────────────────────────
+
+pub struct AddressBookClient<'a>(pub Bound<'a>);
+
+impl AddressBookClient<'_> {
+    /// Point `component` (e.g. `logging`) at `address`; returns the new
+    /// version.
+    pub fn set_address(&self, component: &str, address: &str) -> Result<Value> {
+        self.0.send(
+            "set_address",
+            &[
+                ("component", component.to_string()),
+                ("address", address.to_string()),
+            ],
+        )
+    }
+
+    pub fn resolve(&self, component: &str) -> Result<Value> {
+        self.0
+            .view("resolve", &[("component", component.to_string())])
+    }
+
+    pub fn get_entry(&self, component: &str) -> Result<Value> {
+        self.0
+            .view("get_entry", &[("component", component.to_string())])
+    }
+
+    pub fn components(&self) -> Result<Value> {
+        self.0.view("components", &[])
+    }
+}
+
+/// Arguments for `init_sub`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewSubscription {
+    pub owner: String,
+    pub merchant: String,
+    pub amount: i128,
+    pub frequency: u64,
+    pub spending_cap: i128,
+    pub sub_id: u64,
+}
+
+/// Arguments for `renew`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenewArgs {
+    pub sub_id: u64,
+    pub approval_id: u64,
+    pub amount: i128,
+    pub cycle_id: u64,
+}
+
+/// Parse a comma-separated list of scope names (or a raw bitmask) into the
+/// agent registry's scope bitmask.
+pub fn parse_scopes(input: &str) -> Result<u32> {
+    if let Ok(mask) = input.parse::<u32>() {
+        return Ok(mask);
+    }
+    let mut mask = 0;
+    for name in input.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        mask |= match name {
+            "renewals" => 1,
+            "gift_cards" => 2,
+            "approvals" => 4,
+            "audit" => 8,
+            "all" => 1 | 2 | 4 | 8,
+            other => bail!("unknown scope: {other}"),
+        };
+    }
+    Ok(mask)
+}
+
+/// Parse a reason name (e.g. `agent_retired`) or raw code into the shared
+/// reason code contracts expect.
+pub fn parse_reason(input: &str) -> Result<u32> {
+    if let Ok(code) = input.parse::<u32>() {
+        return Ok(code);
+    }
+    Ok(match input.trim() {
+        "approval_expired" => 1,
+        "approval_used" => 2,
+        "amount_exceeded" => 3,
+        "approval_not_found" => 4,
+        "payment_failed" => 10,
+        "retries_exhausted" => 11,
+        "agent_compromised" => 20,
+        "agent_misbehaved" => 21,
+        "agent_retired" => 22,
+        other => bail!("unknown reason: {other}"),
+    })
+}
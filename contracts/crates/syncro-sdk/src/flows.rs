@@ -0,0 +1,142 @@
+//! Multi-contract sequences a dapp backend usually needs. Each step is a
+//! separate transaction signed by the invoker's source account, so the
+//! source must be the subscriber for the owner-authorized steps.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::{
+    clients::{NewSubscription, RenewArgs},
+    Syncro,
+};
+
+/// Everything needed to open a subscription in the registry and the
+/// renewal contract at once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateSubscription {
+    pub subscription: NewSubscription,
+    pub service_id: String,
+    pub next_renewal: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreatedSubscription {
+    pub sub_id: u64,
+    /// Registry id (hex), present when a registry is configured.
+    pub registry_id: Option<String>,
+}
+
+/// Renewal parameters chosen by the caller for one charge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenewPolicy {
+    pub lock_timeout: u32,
+}
+
+impl Syncro<'_> {
+    /// Record the subscription in the registry (when configured) and
+    /// initialize it in the renewal contract.
+    pub fn create_subscription(&self, req: &CreateSubscription) -> Result<CreatedSubscription> {
+        let sub = &req.subscription;
+        let registry_id = match self.contracts().registry {
+            Some(_) => {
+                let id = self.registry()?.create_subscription(
+                    &sub.owner,
+                    &req.service_id,
+                    sub.frequency,
+                    sub.amount,
+                    req.next_renewal,
+                )?;
+                Some(
+                    id.as_str()
+                        .context("registry returned a non-string id")?
+                        .to_string(),
+                )
+            }
+            None => None,
+        };
+        self.renewal()?.init_sub(sub)?;
+        Ok(CreatedSubscription {
+            sub_id: sub.sub_id,
+            registry_id,
+        })
+    }
+
+    /// Approve one cycle's charge, take the renewal lock and renew. The
+    /// approval id doubles as the cycle id, matching the keeper's convention.
+    /// `agent` holds the lock and must be the signing source account.
+    pub fn approve_and_renew(
+        &self,
+        agent: &str,
+        sub_id: u64,
+        cycle_id: u64,
+        amount: i128,
+        expires_at: u32,
+        policy: RenewPolicy,
+    ) -> Result<bool> {
+        let renewal = self.renewal()?;
+        renewal.approve_renewal(sub_id, cycle_id, amount, expires_at)?;
+        renewal.acquire_renewal_lock(agent, sub_id, policy.lock_timeout)?;
+        renewal.renew(&RenewArgs {
+            sub_id,
+            approval_id: cycle_id,
+            amount,
+            cycle_id,
+        })
+    }
+
+    /// Cancel in the renewal contract and, when a registry id is given, mark
+    /// the registry entry inactive too, recording the cancellation `reason`
+    /// code in both. If the owner funded a keeper bounty for the
+    /// subscription, the unspent balance is refunded to them and returned.
+    pub fn cancel_subscription(
+        &self,
+        sub_id: u64,
+        owner: &str,
+        registry_id: Option<&str>,
+        reason: Option<u32>,
+    ) -> Result<i128> {
+        self.renewal()?.cancel_sub(sub_id, reason)?;
+        if let Some(id) = registry_id {
+            self.registry()?.cancel_subscription(id, owner, reason)?;
+        }
+        self.refund_bounty(sub_id, owner)
+    }
+
+    /// Let `operator` (e.g. the marketplace) transfer the subscription in
+    /// the renewal contract and, when a registry id is given, the registry.
+    /// `None` revokes the approvals.
+    pub fn approve_transfer_operator(
+        &self,
+        sub_id: u64,
+        owner: &str,
+        registry_id: Option<&str>,
+        operator: Option<&str>,
+    ) -> Result<()> {
+        self.renewal()?.approve_sub_transfer(sub_id, operator)?;
+        if let Some(id) = registry_id {
+            self.registry()?.approve_transfer(id, owner, operator)?;
+        }
+        Ok(())
+    }
+
+    /// Withdraw whatever `owner` has left in the subscription's bounty pool.
+    fn refund_bounty(&self, sub_id: u64, owner: &str) -> Result<i128> {
+        if self.contracts().bounty.is_none() {
+            return Ok(0);
+        }
+        let bounty = self.bounty()?;
+        let balance = match bounty.get_bounty(sub_id)? {
+            Value::Null => return Ok(0),
+            pool if pool["funder"].as_str() != Some(owner) => return Ok(0),
+            pool => pool["balance"]
+                .as_str()
+                .map(str::parse::<i128>)
+                .or_else(|| pool["balance"].as_i64().map(|b| Ok(b as i128)))
+                .context("bounty balance missing")??,
+        };
+        if balance > 0 {
+            bounty.withdraw(sub_id, balance)?;
+        }
+        Ok(balance)
+    }
+}
@@ -0,0 +1,69 @@
+//! Transport for contract calls. Everything goes through the `stellar` CLI,
+//! which resolves identities, builds footprints, simulates and signs.
+
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Result};
+
+/// Whether a call is only simulated (reads) or submitted on-chain (writes).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Simulate,
+    Send,
+}
+
+pub trait Invoker {
+    /// Invoke `function` on `contract_id` with named arguments and return the
+    /// CLI's stdout (the JSON-encoded return value).
+    fn invoke(
+        &self,
+        contract_id: &str,
+        mode: Mode,
+        function: &str,
+        args: &[(&str, String)],
+    ) -> Result<String>;
+}
+
+/// Invoker backed by the `stellar` binary on `PATH`.
+pub struct StellarCli {
+    pub network: String,
+    pub source_account: String,
+}
+
+impl Invoker for StellarCli {
+    fn invoke(
+        &self,
+        contract_id: &str,
+        mode: Mode,
+        function: &str,
+        args: &[(&str, String)],
+    ) -> Result<String> {
+        let mut cmd = Command::new("stellar");
+        cmd.args(["contract", "invoke", "--id"])
+            .arg(contract_id)
+            .arg("--source-account")
+            .arg(&self.source_account)
+            .arg("--network")
+            .arg(&self.network)
+            .arg(match mode {
+                Mode::Simulate => "--send=no",
+                Mode::Send => "--send=yes",
+            })
+            .arg("--")
+            .arg(function);
+        for (name, value) in args {
+            cmd.arg(format!("--{name}")).arg(value);
+        }
+
+        let output = cmd
+            .output()
+            .map_err(|e| anyhow!("spawning stellar CLI: {e}"))?;
+        if !output.status.success() {
+            bail!(
+                "{function} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
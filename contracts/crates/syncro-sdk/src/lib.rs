@@ -0,0 +1,94 @@
+//! Client SDK for the SYNCRO contracts.
+//!
+//! [`Syncro`] bundles the deployed contract ids of one suite with a transport
+//! and hands out typed clients for each contract. The [`flows`] module builds
+//! the common multi-call sequences (create, approve and renew, cancel) on
+//! top of them.
+
+use anyhow::{Context, Result};
+
+pub mod clients;
+pub mod flows;
+pub mod invoke;
+
+use clients::{
+    AddressBookClient, AgentRegistryClient, Bound, BountyClient, LoggingClient, MeteringClient,
+    MulticallClient, ReceiptsClient, RegistryClient, RenewalClient,
+};
+use invoke::Invoker;
+
+/// Contract ids of one deployed suite. Components that are not deployed stay
+/// `None`; asking for their client is an error.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Contracts {
+    pub renewal: Option<String>,
+    pub registry: Option<String>,
+    pub agents: Option<String>,
+    pub logging: Option<String>,
+    pub metering: Option<String>,
+    pub receipts: Option<String>,
+    pub bounty: Option<String>,
+    pub multicall: Option<String>,
+    pub address_book: Option<String>,
+}
+
+pub struct Syncro<'a> {
+    invoker: &'a dyn Invoker,
+    contracts: Contracts,
+}
+
+fn bound<'a>(invoker: &'a dyn Invoker, id: &'a Option<String>, name: &str) -> Result<Bound<'a>> {
+    let id = id
+        .as_deref()
+        .with_context(|| format!("{name} contract id not configured"))?;
+    Ok(Bound::new(invoker, id))
+}
+
+impl<'a> Syncro<'a> {
+    pub fn new(invoker: &'a dyn Invoker, contracts: Contracts) -> Self {
+        Self { invoker, contracts }
+    }
+
+    pub fn contracts(&self) -> &Contracts {
+        &self.contracts
+    }
+
+    pub fn renewal(&self) -> Result<RenewalClient<'_>> {
+        bound(self.invoker, &self.contracts.renewal, "renewal").map(RenewalClient)
+    }
+
+    pub fn registry(&self) -> Result<RegistryClient<'_>> {
+        bound(self.invoker, &self.contracts.registry, "registry").map(RegistryClient)
+    }
+
+    pub fn agents(&self) -> Result<AgentRegistryClient<'_>> {
+        bound(self.invoker, &self.contracts.agents, "agent registry").map(AgentRegistryClient)
+    }
+
+    pub fn logging(&self) -> Result<LoggingClient<'_>> {
+        bound(self.invoker, &self.contracts.logging, "logging").map(LoggingClient)
+    }
+
+    pub fn metering(&self) -> Result<MeteringClient<'_>> {
+        bound(self.invoker, &self.contracts.metering, "metering").map(MeteringClient)
+    }
+
+    pub fn receipts(&self) -> Result<ReceiptsClient<'_>> {
+        bound(self.invoker, &self.contracts.receipts, "receipts").map(ReceiptsClient)
+    }
+
+    pub fn bounty(&self) -> Result<BountyClient<'_>> {
+        bound(self.invoker, &self.contracts.bounty, "bounty").map(BountyClient)
+    }
+
+    pub fn multicall(&self) -> Result<MulticallClient<'_>> {
+        bound(self.invoker, &self.contracts.multicall, "multicall").map(MulticallClient)
+    }
+
+    pub fn address_book(&self) -> Result<AddressBookClient<'_>> {
+        bound(self.invoker, &self.contracts.address_book, "address book").map(AddressBookClient)
+    }
+}
+
+#[cfg(test)]
+mod test;
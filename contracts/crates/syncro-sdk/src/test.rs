@@ -0,0 +1,351 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use crate::{
+    clients::{self, AgentRegistryClient, Bound, NewSubscription, RenewalClient},
+    flows::{CreateSubscription, RenewPolicy},
+    invoke::{Invoker, Mode},
+    Contracts, Syncro,
+};
+
+type Call = (String, Mode, String, Vec<(String, String)>);
+
+#[derive(Default)]
+struct Recorder {
+    calls: RefCell<Vec<Call>>,
+    /// Canned stdout per function name; anything else returns nothing.
+    replies: HashMap<&'static str, &'static str>,
+}
+
+impl Recorder {
+    fn functions(&self) -> Vec<String> {
+        self.calls.borrow().iter().map(|c| c.2.clone()).collect()
+    }
+}
+
+impl Invoker for Recorder {
+    fn invoke(
+        &self,
+        contract_id: &str,
+        mode: Mode,
+        function: &str,
+        args: &[(&str, String)],
+    ) -> Result<String> {
+        self.calls.borrow_mut().push((
+            contract_id.to_string(),
+            mode,
+            function.to_string(),
+            args.iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        ));
+        Ok(self.replies.get(function).unwrap_or(&"").to_string())
+    }
+}
+
+#[test]
+fn test_event_version_is_read_from_any_contract() {
+    let rec = Recorder {
+        replies: HashMap::from([("get_event_version", "1")]),
+        ..Default::default()
+    };
+    let agents = AgentRegistryClient(Bound::new(&rec, "CAGENTS"));
+    assert_eq!(agents.0.get_event_version().unwrap(), 1);
+    assert_eq!(rec.calls.borrow()[0].1, Mode::Simulate);
+}
+
+#[test]
+fn test_writes_are_sent_with_named_args() {
+    let rec = Recorder::default();
+    let agents = AgentRegistryClient(Bound::new(&rec, "CAGENTS"));
+    assert_eq!(agents.update_scopes("GAGENT", 5).unwrap(), Value::Null);
+
+    let calls = rec.calls.borrow();
+    let (id, mode, function, args) = &calls[0];
+    assert_eq!(id, "CAGENTS");
+    assert_eq!(*mode, Mode::Send);
+    assert_eq!(function, "update_scopes");
+    assert_eq!(
+        args,
+        &vec![
+            ("agent".to_string(), "GAGENT".to_string()),
+            ("scopes".to_string(), "5".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_views_are_simulated_and_decoded() {
+    let rec = Recorder {
+        replies: HashMap::from([("get_renewal_lock", r#"{"locked_at":10,"lock_timeout":20}"#)]),
+        ..Default::default()
+    };
+    let renewal = RenewalClient(Bound::new(&rec, "CRENEWAL"));
+    let lock = renewal.get_renewal_lock(7).unwrap();
+
+    assert_eq!(lock, json!({ "locked_at": 10, "lock_timeout": 20 }));
+    assert_eq!(rec.calls.borrow()[0].1, Mode::Simulate);
+}
+
+#[test]
+fn test_multi_get_sends_ids_as_json() {
+    let rec = Recorder {
+        replies: HashMap::from([("multi_get", r#"[{"sub_id":3}]"#)]),
+        ..Default::default()
+    };
+    let syncro = Syncro::new(
+        &rec,
+        Contracts {
+            multicall: Some("CMULTICALL".into()),
+            ..Default::default()
+        },
+    );
+    let views = syncro.multicall().unwrap().multi_get(&[3, 4], 2).unwrap();
+    assert_eq!(views, json!([{ "sub_id": 3 }]));
+
+    let calls = rec.calls.borrow();
+    assert_eq!(calls[0].1, Mode::Simulate);
+    assert_eq!(
+        calls[0].3,
+        vec![
+            ("sub_ids".to_string(), "[3,4]".to_string()),
+            ("recent_logs".to_string(), "2".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_reason() {
+    assert_eq!(clients::parse_reason("approval_expired").unwrap(), 1);
+    assert_eq!(clients::parse_reason("agent_retired").unwrap(), 22);
+    assert_eq!(clients::parse_reason("21").unwrap(), 21);
+    assert!(clients::parse_reason("bored").is_err());
+}
+
+#[test]
+fn test_address_book_components_are_named() {
+    let rec = Recorder::default();
+    let syncro = Syncro::new(
+        &rec,
+        Contracts {
+            address_book: Some("CBOOK".into()),
+            ..Default::default()
+        },
+    );
+    let book = syncro.address_book().unwrap();
+    book.set_address("logging", "CLOGGING").unwrap();
+    book.resolve("logging").unwrap();
+
+    let calls = rec.calls.borrow();
+    assert_eq!(
+        (calls[0].1, calls[0].2.as_str()),
+        (Mode::Send, "set_address")
+    );
+    assert_eq!(
+        calls[0].3,
+        vec![
+            ("component".to_string(), "logging".to_string()),
+            ("address".to_string(), "CLOGGING".to_string()),
+        ]
+    );
+    assert_eq!(
+        (calls[1].1, calls[1].2.as_str()),
+        (Mode::Simulate, "resolve")
+    );
+}
+
+#[test]
+fn test_pause_maps_to_set_paused() {
+    let rec = Recorder::default();
+    let renewal = RenewalClient(Bound::new(&rec, "CRENEWAL"));
+    renewal.set_paused("GADMIN", true, None).unwrap();
+    renewal.set_paused("GPAUSER", true, Some(12)).unwrap();
+
+    let calls = rec.calls.borrow();
+    assert_eq!(calls[0].2, "set_paused");
+    assert_eq!(
+        calls[0].3,
+        vec![
+            ("caller".to_string(), "GADMIN".to_string()),
+            ("paused".to_string(), "true".to_string()),
+        ]
+    );
+    assert_eq!(
+        calls[1].3,
+        vec![
+            ("caller".to_string(), "GPAUSER".to_string()),
+            ("paused".to_string(), "true".to_string()),
+            ("reason".to_string(), "12".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_scopes() {
+    assert_eq!(clients::parse_scopes("renewals").unwrap(), 1);
+    assert_eq!(clients::parse_scopes("renewals, approvals").unwrap(), 5);
+    assert_eq!(clients::parse_scopes("audit").unwrap(), 8);
+    assert_eq!(clients::parse_scopes("all").unwrap(), 15);
+    assert_eq!(clients::parse_scopes("6").unwrap(), 6);
+    assert!(clients::parse_scopes("billing").is_err());
+}
+
+fn suite(rec: &Recorder, with_registry: bool) -> Syncro<'_> {
+    Syncro::new(
+        rec,
+        Contracts {
+            renewal: Some("CRENEWAL".into()),
+            registry: with_registry.then(|| "CREGISTRY".into()),
+            bounty: Some("CBOUNTY".into()),
+            ..Default::default()
+        },
+    )
+}
+
+fn new_sub() -> NewSubscription {
+    NewSubscription {
+        owner: "GOWNER".into(),
+        merchant: "GMERCHANT".into(),
+        amount: 1000,
+        frequency: 86_400,
+        spending_cap: 12_000,
+        sub_id: 9,
+    }
+}
+
+#[test]
+fn test_create_subscription_registers_then_inits() {
+    let rec = Recorder {
+        replies: HashMap::from([("create_subscription", r#""ab12""#)]),
+        ..Default::default()
+    };
+    let created = suite(&rec, true)
+        .create_subscription(&CreateSubscription {
+            subscription: new_sub(),
+            service_id: "music".into(),
+            next_renewal: 86_400,
+        })
+        .unwrap();
+
+    assert_eq!(created.sub_id, 9);
+    assert_eq!(created.registry_id.as_deref(), Some("ab12"));
+    assert_eq!(rec.functions(), vec!["create_subscription", "init_sub"]);
+    assert_eq!(rec.calls.borrow()[1].0, "CRENEWAL");
+}
+
+#[test]
+fn test_create_subscription_without_registry() {
+    let rec = Recorder::default();
+    let created = suite(&rec, false)
+        .create_subscription(&CreateSubscription {
+            subscription: new_sub(),
+            service_id: "music".into(),
+            next_renewal: 0,
+        })
+        .unwrap();
+    assert!(created.registry_id.is_none());
+    assert_eq!(rec.functions(), vec!["init_sub"]);
+}
+
+#[test]
+fn test_approve_and_renew_sequence() {
+    let rec = Recorder {
+        replies: HashMap::from([("renew", "true")]),
+        ..Default::default()
+    };
+    let policy = RenewPolicy { lock_timeout: 20 };
+    let renewed = suite(&rec, false)
+        .approve_and_renew("GOWNER", 9, 4, 1000, 5000, policy)
+        .unwrap();
+
+    assert!(renewed);
+    assert_eq!(
+        rec.functions(),
+        vec!["approve_renewal", "acquire_renewal_lock", "renew"]
+    );
+    let calls = rec.calls.borrow();
+    assert!(calls[2]
+        .3
+        .contains(&("approval_id".to_string(), "4".to_string())));
+}
+
+#[test]
+fn test_cancel_refunds_owner_bounty() {
+    let rec = Recorder {
+        replies: HashMap::from([(
+            "get_bounty",
+            r#"{"funder":"GOWNER","reward_per_cycle":"10","balance":"70"}"#,
+        )]),
+        ..Default::default()
+    };
+    let refunded = suite(&rec, true)
+        .cancel_subscription(9, "GOWNER", Some("ab12"), Some(30))
+        .unwrap();
+
+    assert_eq!(refunded, 70);
+    assert_eq!(
+        rec.functions(),
+        vec![
+            "cancel_sub",
+            "cancel_subscription",
+            "get_bounty",
+            "withdraw"
+        ]
+    ); // Both contracts record why
+    let calls = rec.calls.borrow();
+    assert!(calls[..2]
+        .iter()
+        .all(|(_, _, _, args)| args.contains(&("reason".to_string(), "30".to_string()))));
+}
+
+#[test]
+fn test_cancel_leaves_third_party_bounty() {
+    let rec = Recorder {
+        replies: HashMap::from([(
+            "get_bounty",
+            r#"{"funder":"GSPONSOR","reward_per_cycle":"10","balance":"70"}"#,
+        )]),
+        ..Default::default()
+    };
+    let refunded = suite(&rec, false)
+        .cancel_subscription(9, "GOWNER", None, None)
+        .unwrap();
+
+    assert_eq!(refunded, 0);
+    assert_eq!(rec.functions(), vec!["cancel_sub", "get_bounty"]);
+}
+
+#[test]
+fn test_approve_transfer_operator_in_both_contracts() {
+    let rec = Recorder::default();
+    suite(&rec, true)
+        .approve_transfer_operator(9, "GOWNER", Some("ab12"), Some("CMARKET"))
+        .unwrap();
+    assert_eq!(
+        rec.functions(),
+        vec!["approve_sub_transfer", "approve_transfer"]
+    );
+    assert_eq!(
+        rec.calls.borrow()[1].3.last(),
+        Some(&("operator".to_string(), "CMARKET".to_string()))
+    );
+
+    // Revoking leaves the optional argument out
+    let rec = Recorder::default();
+    suite(&rec, false)
+        .approve_transfer_operator(9, "GOWNER", None, None)
+        .unwrap();
+    assert_eq!(
+        rec.calls.borrow()[0].3,
+        vec![("sub_id".to_string(), "9".to_string())]
+    );
+}
+
+#[test]
+fn test_missing_contract_id_is_an_error() {
+    let rec = Recorder::default();
+    let syncro = Syncro::new(&rec, Contracts::default());
+    assert!(syncro.renewal().is_err());
+}
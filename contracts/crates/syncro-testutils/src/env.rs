@@ -0,0 +1,29 @@
+use soroban_sdk::{testutils::Ledger, Env};
+
+/// Ledger timestamp every [`setup_env`] environment starts at.
+pub const START: u64 = 1_700_000_000;
+
+/// Average ledger close time assumed by [`advance_ledgers`].
+pub const LEDGER_SECONDS: u64 = 5;
+
+/// A fresh environment with every auth mocked and the clock at [`START`].
+pub fn setup_env() -> Env {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = START);
+    env
+}
+
+/// Move the clock forward without closing ledgers.
+pub fn advance_time(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+}
+
+/// Close `ledgers` ledgers, moving the clock forward [`LEDGER_SECONDS`]
+/// for each.
+pub fn advance_ledgers(env: &Env, ledgers: u32) {
+    env.ledger().with_mut(|li| {
+        li.sequence_number += ledgers;
+        li.timestamp += ledgers as u64 * LEDGER_SECONDS;
+    });
+}
@@ -0,0 +1,165 @@
+//! Text snapshots of emitted events.
+//!
+//! An [`EventRecorder`] renders what contracts publish, one event per line,
+//! with addresses replaced by labels so snapshots do not depend on how test
+//! addresses are generated. Integers keep their type suffix (`7u64`), since
+//! widening a field breaks indexers as surely as renaming it.
+//! [`assert_event_snapshot!`](crate::assert_event_snapshot) compares the
+//! result with a file under the crate's `event_snapshots/` directory.
+
+extern crate std;
+
+use soroban_sdk::{
+    testutils::Events,
+    xdr::{Int128Parts, ScVal, UInt128Parts},
+    Address, Env, TryFromVal, Val,
+};
+use std::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Set to rewrite snapshots instead of comparing against them.
+pub const UPDATE_VAR: &str = "UPDATE_EVENT_SNAPSHOTS";
+
+pub struct EventRecorder {
+    env: Env,
+    labels: Vec<(ScVal, String)>,
+    unlabelled: usize,
+    lines: Vec<String>,
+}
+
+impl EventRecorder {
+    pub fn new(env: &Env) -> Self {
+        Self {
+            env: env.clone(),
+            labels: Vec::new(),
+            unlabelled: 0,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Render `address` as `name`. Unlabelled addresses are numbered in
+    /// the order they first appear.
+    pub fn label(mut self, name: &str, address: &Address) -> Self {
+        let val = ScVal::try_from_val(&self.env, &address.to_val()).unwrap();
+        self.labels.push((val, name.to_string()));
+        self
+    }
+
+    /// Append the events published by the last contract call under a
+    /// `# step` heading.
+    pub fn record(&mut self, step: &str) {
+        self.lines.push(format!("# {step}"));
+        for (contract, topics, data) in self.env.events().all().iter() {
+            let mut line = self.render_address(&contract);
+            line.push(':');
+            for topic in topics.iter() {
+                line.push(' ');
+                line.push_str(&self.render_val(topic));
+            }
+            line.push(' ');
+            line.push_str(&self.render_val(data));
+            self.lines.push(line);
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = self.lines.join("\n");
+        out.push('\n');
+        out
+    }
+
+    fn render_address(&mut self, address: &Address) -> String {
+        let val = ScVal::try_from_val(&self.env, &address.to_val()).unwrap();
+        self.render_sc(&val)
+    }
+
+    fn render_val(&mut self, val: Val) -> String {
+        let val = ScVal::try_from_val(&self.env, &val).unwrap();
+        self.render_sc(&val)
+    }
+
+    /// Render `val` as it would appear in an event, sharing this
+    /// recorder's labels.
+    pub fn render_sc(&mut self, val: &ScVal) -> String {
+        match val {
+            ScVal::Address(_) => match self.labels.iter().find(|(known, _)| known == val) {
+                Some((_, name)) => name.clone(),
+                None => {
+                    self.unlabelled += 1;
+                    let name = format!("address{}", self.unlabelled);
+                    self.labels.push((val.clone(), name.clone()));
+                    name
+                }
+            },
+            ScVal::Void => "()".to_string(),
+            ScVal::Bool(b) => b.to_string(),
+            ScVal::U32(n) => format!("{n}u32"),
+            ScVal::I32(n) => format!("{n}i32"),
+            ScVal::U64(n) => format!("{n}u64"),
+            ScVal::I64(n) => format!("{n}i64"),
+            ScVal::U128(UInt128Parts { hi, lo }) => {
+                format!("{}u128", (u128::from(*hi) << 64) | u128::from(*lo))
+            }
+            ScVal::I128(Int128Parts { hi, lo }) => {
+                format!("{}i128", (i128::from(*hi) << 64) | i128::from(*lo))
+            }
+            ScVal::Symbol(s) => s.to_utf8_string_lossy(),
+            ScVal::String(s) => format!("{:?}", s.to_utf8_string_lossy()),
+            ScVal::Bytes(b) => {
+                let hex: String = b.iter().map(|byte| format!("{byte:02x}")).collect();
+                format!("0x{hex}")
+            }
+            ScVal::Vec(Some(items)) => {
+                let items: Vec<String> = items.iter().map(|v| self.render_sc(v)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            ScVal::Map(Some(entries)) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|e| format!("{}: {}", self.render_sc(&e.key), self.render_sc(&e.val)))
+                    .collect();
+                format!("{{{}}}", entries.join(", "))
+            }
+            other => format!("{other:?}"),
+        }
+    }
+}
+
+/// Compare `actual` with the snapshot `name` of the crate at
+/// `manifest_dir`, or write it when [`UPDATE_VAR`] is set. Use through
+/// [`assert_event_snapshot!`](crate::assert_event_snapshot).
+pub fn assert_snapshot(manifest_dir: &str, name: &str, actual: &str) {
+    let path = std::path::Path::new(manifest_dir)
+        .join("event_snapshots")
+        .join(format!("{name}.txt"));
+    if std::env::var_os(UPDATE_VAR).is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let Ok(expected) = std::fs::read_to_string(&path) else {
+        panic!(
+            "no event snapshot at {}; rerun with {UPDATE_VAR}=1 to record it",
+            path.display()
+        );
+    };
+    if expected != actual {
+        panic!(
+            "events differ from {}; rerun with {UPDATE_VAR}=1 if the change is intended\n\
+             --- expected\n{expected}--- actual\n{actual}",
+            path.display()
+        );
+    }
+}
+
+/// Check an [`EventRecorder`] against `event_snapshots/<name>.txt` in the
+/// calling crate.
+#[macro_export]
+macro_rules! assert_event_snapshot {
+    ($name:expr, $recorder:expr) => {
+        $crate::assert_snapshot(env!("CARGO_MANIFEST_DIR"), $name, &$recorder.render())
+    };
+}
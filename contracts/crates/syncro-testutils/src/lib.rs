@@ -0,0 +1,30 @@
+#![no_std]
+//! Test fixtures shared by the SYNCRO contract test modules.
+//!
+//! Pull this in as a dev-dependency and start from [`setup_env`]:
+//!
+//! ```ignore
+//! let env = syncro_testutils::setup_env();
+//! let token = TestToken::new(&env);
+//! token.fund(&[&payer, &merchant], 10_000);
+//! syncro_testutils::advance_time(&env, 86_400);
+//! ```
+//!
+//! The mock contracts implement just the calls SYNCRO contracts make on
+//! their real counterparts, plus setters and getters for tests to steer and
+//! inspect them. Event snapshots are taken with [`EventRecorder`].
+
+mod env;
+mod events;
+mod logging;
+mod oracle;
+mod token;
+
+pub use env::{advance_ledgers, advance_time, setup_env, LEDGER_SECONDS, START};
+pub use events::{assert_snapshot, EventRecorder, UPDATE_VAR};
+pub use logging::{MockLogging, MockLoggingClient};
+pub use oracle::{MockOracle, MockOracleClient};
+pub use token::TestToken;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,23 @@
+use soroban_sdk::{contract, contractimpl, Env, String, Vec};
+use syncro_common::LogEvent;
+
+/// Stand-in for the logging contract: keeps the kind of every record per
+/// subscription and drops the data.
+#[contract]
+pub struct MockLogging;
+
+#[contractimpl]
+impl MockLogging {
+    pub fn record_log(env: Env, sub_id: u64, event: LogEvent, _data: String) {
+        let mut events = Self::logs(env.clone(), sub_id);
+        events.push_back(event);
+        env.storage().instance().set(&sub_id, &events);
+    }
+
+    pub fn logs(env: Env, sub_id: u64) -> Vec<LogEvent> {
+        env.storage()
+            .instance()
+            .get(&sub_id)
+            .unwrap_or(Vec::new(&env))
+    }
+}
@@ -0,0 +1,25 @@
+use soroban_sdk::{contract, contractimpl, Address, Env};
+use syncro_common::PriceData;
+
+/// Stand-in for the oracle adapter: reports whatever price it was last set.
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    /// Report `price` for `asset` as observed now.
+    pub fn set_price(env: Env, asset: Address, price: i128) {
+        let timestamp = env.ledger().timestamp();
+        Self::set_price_at(env, asset, price, timestamp);
+    }
+
+    pub fn set_price_at(env: Env, asset: Address, price: i128, timestamp: u64) {
+        env.storage()
+            .instance()
+            .set(&asset, &PriceData { price, timestamp });
+    }
+
+    pub fn lastprice(env: Env, asset: Address) -> Option<PriceData> {
+        env.storage().instance().get(&asset)
+    }
+}
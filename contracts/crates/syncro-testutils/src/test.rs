@@ -0,0 +1,97 @@
+use super::*;
+use soroban_sdk::{
+    contract, contractevent, contractimpl, testutils::Address as _, vec, Address, Env, String,
+};
+use syncro_common::LogEvent;
+
+#[test]
+fn test_token_funds_accounts() {
+    let env = setup_env();
+    let token = TestToken::new(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    token.fund(&[&a, &b], 500);
+    token.transfer(&a, &b, &200);
+    assert_eq!(token.balance(&a), 300);
+    assert_eq!(token.balance(&b), 700);
+}
+
+#[test]
+fn test_advance_ledgers_moves_clock() {
+    let env = setup_env();
+    let sequence = env.ledger().sequence();
+    advance_ledgers(&env, 10);
+    assert_eq!(env.ledger().sequence(), sequence + 10);
+    assert_eq!(env.ledger().timestamp(), START + 10 * LEDGER_SECONDS);
+
+    advance_time(&env, 1);
+    assert_eq!(env.ledger().sequence(), sequence + 10);
+    assert_eq!(env.ledger().timestamp(), START + 10 * LEDGER_SECONDS + 1);
+}
+
+#[test]
+fn test_mock_oracle_stamps_prices() {
+    let env = setup_env();
+    let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+    let asset = Address::generate(&env);
+    assert_eq!(oracle.lastprice(&asset), None);
+
+    oracle.set_price(&asset, &42);
+    let reported = oracle.lastprice(&asset).unwrap();
+    assert_eq!((reported.price, reported.timestamp), (42, START));
+}
+
+#[test]
+fn test_mock_logging_keeps_events_per_subscription() {
+    let env = setup_env();
+    let logging = MockLoggingClient::new(&env, &env.register(MockLogging, ()));
+    let data = String::from_str(&env, "");
+    logging.record_log(&1, &LogEvent::Renewal, &data);
+    logging.record_log(&1, &LogEvent::Retry, &data);
+    logging.record_log(&2, &LogEvent::Reminder, &data);
+    assert_eq!(
+        logging.logs(&1),
+        vec![&env, LogEvent::Renewal, LogEvent::Retry]
+    );
+    assert_eq!(logging.logs(&3).len(), 0);
+}
+
+#[contractevent]
+pub struct Sampled {
+    pub who: Address,
+    pub count: u32,
+    pub amount: i128,
+    pub note: String,
+}
+
+#[contract]
+struct Emitter;
+
+#[contractimpl]
+impl Emitter {
+    pub fn emit(env: Env, who: Address, amount: i128) {
+        Sampled {
+            who,
+            count: 1,
+            amount,
+            note: String::from_str(&env, "hi"),
+        }
+        .publish(&env);
+    }
+}
+
+#[test]
+fn test_event_recorder_matches_snapshot() {
+    let env = setup_env();
+    let emitter = EmitterClient::new(&env, &env.register(Emitter, ()));
+    let payer = Address::generate(&env);
+    let mut events = EventRecorder::new(&env)
+        .label("emitter", &emitter.address)
+        .label("payer", &payer);
+
+    emitter.emit(&payer, &-5);
+    events.record("labelled");
+    emitter.emit(&Address::generate(&env), &7);
+    events.record("unlabelled");
+    crate::assert_event_snapshot!("recorder", events);
+}
@@ -0,0 +1,47 @@
+use core::ops::Deref;
+
+use soroban_sdk::{
+    testutils::{Address as _, StellarAssetContract},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env,
+};
+
+/// A Stellar asset contract registered for a test: a SEP-41 token that
+/// mints on demand. Derefs to its [`TokenClient`], so `token.balance(..)`,
+/// `token.transfer(..)` and `token.address` work as on the client.
+pub struct TestToken {
+    /// The registration, for issuer flags.
+    pub sac: StellarAssetContract,
+    pub client: TokenClient<'static>,
+    pub admin: StellarAssetClient<'static>,
+}
+
+impl TestToken {
+    pub fn new(env: &Env) -> Self {
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(env));
+        Self {
+            client: TokenClient::new(env, &sac.address()),
+            admin: StellarAssetClient::new(env, &sac.address()),
+            sac,
+        }
+    }
+
+    pub fn mint(&self, to: &Address, amount: i128) {
+        self.admin.mint(to, &amount);
+    }
+
+    /// Mint `amount` to each of `accounts`.
+    pub fn fund(&self, accounts: &[&Address], amount: i128) {
+        for account in accounts {
+            self.mint(account, amount);
+        }
+    }
+}
+
+impl Deref for TestToken {
+    type Target = TokenClient<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
@@ -0,0 +1,95 @@
+#![no_std]
+//! Code upgrades for the core suite contracts.
+//!
+//! Each contract compiles in a `CODE_VERSION` and records it on `init`. An
+//! upgrade takes two admin calls, so a governance proposal (or the suite's
+//! admin) can step every component forward together:
+//!
+//! 1. [`upgrade`] swaps in the new code and keeps the contract's storage.
+//!    The new code only runs from the next invocation.
+//! 2. [`migrate`] on the new code, with the stored version as `from` and
+//!    the new `CODE_VERSION` as `to`, runs the contract's migration step for
+//!    each version in between and records `to`.
+//!
+//! `from` must equal the stored version, so a migration cannot run twice or
+//! skip a step. Contracts initialized before versioning have no stored
+//! version and count as version 1.
+
+use soroban_sdk::{contractevent, symbol_short, BytesN, Env, Symbol};
+
+const CODE_VERSION: Symbol = symbol_short!("code_ver");
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UpgradeError {
+    NotInitialized,
+    VersionMismatch,
+}
+
+/// Moves a contract's storage from one code version to the next. Entry
+/// `i` of the table handed to [`migrate`] upgrades version `i + 1` to
+/// `i + 2`.
+pub type Migration = fn(&Env);
+
+#[contractevent]
+pub struct ContractUpgraded {
+    pub wasm_hash: BytesN<32>,
+    pub from_version: u32,
+}
+
+#[contractevent]
+pub struct ContractMigrated {
+    pub from: u32,
+    pub to: u32,
+}
+
+/// Record the code version storage is laid out for. Called from `init`.
+pub fn init_version(env: &Env, version: u32) {
+    env.storage().instance().set(&CODE_VERSION, &version);
+}
+
+/// The code version storage was last migrated to.
+pub fn version(env: &Env) -> u32 {
+    env.storage().instance().get(&CODE_VERSION).unwrap_or(1)
+}
+
+/// Replace the running contract's code with the uploaded `wasm_hash`.
+/// Admin only.
+pub fn upgrade(env: &Env, wasm_hash: &BytesN<32>) -> Result<(), UpgradeError> {
+    syncro_access::require_admin(env).map_err(|_| UpgradeError::NotInitialized)?;
+    env.deployer()
+        .update_current_contract_wasm(wasm_hash.clone());
+    ContractUpgraded {
+        wasm_hash: wasm_hash.clone(),
+        from_version: version(env),
+    }
+    .publish(env);
+    Ok(())
+}
+
+/// Run `steps` from the stored version `from` up to `to`, which may not be
+/// past `code_version`, then record `to`. Admin only. Contracts announce
+/// their event schema again afterwards, as it may have changed with the
+/// code.
+pub fn migrate(
+    env: &Env,
+    from: u32,
+    to: u32,
+    code_version: u32,
+    steps: &[Migration],
+) -> Result<(), UpgradeError> {
+    syncro_access::require_admin(env).map_err(|_| UpgradeError::NotInitialized)?;
+    if from != version(env) || to <= from || to > code_version {
+        return Err(UpgradeError::VersionMismatch);
+    }
+    for v in from..to {
+        let step = steps.get((v - 1) as usize).expect("migration step missing");
+        step(env);
+    }
+    init_version(env, to);
+    ContractMigrated { from, to }.publish(env);
+    syncro_common::events::publish_schema(env);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
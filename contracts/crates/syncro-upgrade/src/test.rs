@@ -0,0 +1,97 @@
+use super::*;
+use soroban_sdk::{contract, symbol_short, testutils::Address as _, vec, Address, Env, Vec};
+
+#[contract]
+struct Host;
+
+const STEPS: Symbol = symbol_short!("steps");
+
+fn record(env: &Env, step: u32) {
+    let mut steps: Vec<u32> = env.storage().instance().get(&STEPS).unwrap_or(vec![env]);
+    steps.push_back(step);
+    env.storage().instance().set(&STEPS, &steps);
+}
+
+const MIGRATIONS: &[Migration] = &[|env| record(env, 2), |env| record(env, 3)];
+
+/// A host with an admin. Each `as_contract` is its own frame, as separate
+/// contract calls would be: the admin can only authorize once per frame.
+fn host() -> (Env, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let host = env.register(Host, ());
+    env.as_contract(&host, || {
+        syncro_access::init_admin(&env, &Address::generate(&env)).unwrap()
+    });
+    (env, host)
+}
+
+fn with_admin(f: impl FnOnce(&Env)) {
+    let (env, host) = host();
+    env.as_contract(&host, || f(&env));
+}
+
+#[test]
+fn test_unversioned_storage_counts_as_one() {
+    with_admin(|env| {
+        assert_eq!(version(env), 1);
+        init_version(env, 2);
+        assert_eq!(version(env), 2);
+    });
+}
+
+#[test]
+fn test_migrate_runs_each_step_in_order() {
+    with_admin(|env| {
+        init_version(env, 1);
+        migrate(env, 1, 3, 3, MIGRATIONS).unwrap();
+        assert_eq!(version(env), 3);
+        let steps: Vec<u32> = env.storage().instance().get(&STEPS).unwrap();
+        assert_eq!(steps, vec![env, 2, 3]);
+    });
+}
+
+#[test]
+fn test_migrate_resumes_from_stored_version() {
+    with_admin(|env| {
+        init_version(env, 2);
+        migrate(env, 2, 3, 3, MIGRATIONS).unwrap();
+        let steps: Vec<u32> = env.storage().instance().get(&STEPS).unwrap();
+        assert_eq!(steps, vec![env, 3]);
+    });
+}
+
+#[test]
+fn test_migrate_rejects_wrong_versions() {
+    let (env, host) = host();
+    env.as_contract(&host, || init_version(&env, 2));
+    let attempt = |from, to| env.as_contract(&host, || migrate(&env, from, to, 3, MIGRATIONS));
+
+    // `from` must be the stored version
+    assert_eq!(attempt(1, 3), Err(UpgradeError::VersionMismatch));
+    // Nothing to do, or a downgrade
+    assert_eq!(attempt(2, 2), Err(UpgradeError::VersionMismatch));
+    assert_eq!(attempt(2, 1), Err(UpgradeError::VersionMismatch));
+    // Beyond what this code knows
+    assert_eq!(attempt(2, 4), Err(UpgradeError::VersionMismatch));
+
+    env.as_contract(&host, || {
+        assert!(!env.storage().instance().has(&STEPS));
+        assert_eq!(version(&env), 2);
+    });
+}
+
+#[test]
+fn test_requires_an_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let host = env.register(Host, ());
+    env.as_contract(&host, || {
+        let hash = BytesN::from_array(&env, &[1; 32]);
+        assert_eq!(upgrade(&env, &hash), Err(UpgradeError::NotInitialized));
+        assert_eq!(
+            migrate(&env, 1, 2, 2, MIGRATIONS),
+            Err(UpgradeError::NotInitialized)
+        );
+    });
+}